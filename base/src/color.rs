@@ -29,6 +29,7 @@ impl ResolveColor<ColorU8> for () {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorU8 {
     r: u8,
     g: u8,
@@ -190,6 +191,37 @@ impl ColorU8 {
             + 0.7152 * (self.g as f32 / 255.0)
             + 0.0722 * (self.b as f32 / 255.0)
     }
+
+    /// Blend this color with `other`, `t` being the fraction of `other` (clamped to [0, 1]).
+    /// The RGB components are blended in linear RGB space, and the alpha component linearly.
+    pub fn blend(&self, other: ColorU8, t: f32) -> Self {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let [r1, g1, b1, a1] = self.rgba_f32();
+        let [r2, g2, b2, a2] = other.rgba_f32();
+        let mix = |c1: f32, c2: f32| {
+            let (l1, l2) = (srgb_to_linear(c1), srgb_to_linear(c2));
+            linear_to_srgb(l1 + (l2 - l1) * t)
+        };
+        ColorU8::from_rgba_f32(mix(r1, r2), mix(g1, g2), mix(b1, b2), a1 + (a2 - a1) * t)
+    }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    // rounding to the nearest u8 value once scaled
+    c + 0.5 / 255.0
 }
 
 const fn hex_to_u8(hex: u8) -> u8 {
@@ -341,6 +373,21 @@ impl FromStr for ColorU8 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn blend_linear_rgb() {
+        assert_eq!(RED.blend(BLUE, 0.0), RED);
+        assert_eq!(RED.blend(BLUE, 1.0), BLUE);
+        assert_eq!(RED.blend(BLUE, 2.0), BLUE);
+        assert_eq!(WHITE.blend(BLACK, -1.0), WHITE);
+
+        // mid-gray in linear space is lighter than the sRGB average
+        let gray = BLACK.blend(WHITE, 0.5);
+        assert_eq!(gray.rgb(), [188, 188, 188]);
+
+        let c = RED.with_alpha(0).blend(RED, 0.5);
+        assert_eq!(c.rgba(), [255, 0, 0, 127]);
+    }
+
     #[test]
     fn parse_html_hex() {
         // full and short hex
@@ -11,6 +11,7 @@ pub use tiny_skia_path::{Path, PathBuilder, PathSegment, PathVerb, Point, Transf
 
 /// A size in 2D space represented by width and height
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Size {
     w: f32,
     h: f32,
@@ -39,10 +40,29 @@ impl Size {
             h: self.h + dh,
         }
     }
+
+    /// The ratio of width over height
+    pub const fn aspect_ratio(&self) -> f32 {
+        self.w / self.h
+    }
+
+    /// The largest size with the same aspect ratio that fits within `max`
+    pub fn fit_within(&self, max: Size) -> Size {
+        let scale = (max.w / self.w).min(max.h / self.h);
+        Size {
+            w: self.w * scale,
+            h: self.h * scale,
+        }
+    }
 }
 
 /// A rectangle in 2D space represented by x, y, width and height
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "(f32, f32, f32, f32)", into = "(f32, f32, f32, f32)")
+)]
 pub struct Rect {
     x: FiniteF32,
     y: FiniteF32,
@@ -50,6 +70,28 @@ pub struct Rect {
     h: PositiveF32,
 }
 
+/// Build a rectangle from x, y, width and height,
+/// failing if a coordinate is not finite or if a dimension is negative
+impl TryFrom<(f32, f32, f32, f32)> for Rect {
+    type Error = &'static str;
+
+    fn try_from((x, y, w, h): (f32, f32, f32, f32)) -> Result<Self, Self::Error> {
+        Ok(Rect {
+            x: FiniteF32::new(x).ok_or("rectangle x is not finite")?,
+            y: FiniteF32::new(y).ok_or("rectangle y is not finite")?,
+            w: PositiveF32::new(w).ok_or("rectangle width is not positive")?,
+            h: PositiveF32::new(h).ok_or("rectangle height is not positive")?,
+        })
+    }
+}
+
+/// Convert a rectangle to a (x, y, width, height) tuple
+impl From<Rect> for (f32, f32, f32, f32) {
+    fn from(rect: Rect) -> Self {
+        (rect.x(), rect.y(), rect.width(), rect.height())
+    }
+}
+
 impl Rect {
     pub fn null() -> Self {
         Rect {
@@ -398,6 +440,20 @@ impl Rect {
             && point.y <= self.bottom()
     }
 
+    /// Test if the rectangle overlaps with another rectangle
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.left() <= other.right()
+            && other.left() <= self.right()
+            && self.top() <= other.bottom()
+            && other.top() <= self.bottom()
+    }
+
+    /// Bounding rectangle of a path
+    pub fn from_path_bounds(path: &Path) -> Rect {
+        let b = path.bounds();
+        Rect::from_trbl(b.top(), b.right(), b.bottom(), b.left())
+    }
+
     /// Build a path from the rectangle
     pub fn to_path(&self) -> Path {
         PathBuilder::from_rect(
@@ -405,10 +461,36 @@ impl Rect {
                 .unwrap(),
         )
     }
+
+    /// Build a path from the rectangle, with corners rounded by the given radius.
+    /// The radius is clamped to half the smallest side of the rectangle.
+    pub fn to_rounded_path(&self, radius: f32) -> Path {
+        let r = radius.min(self.width() / 2.0).min(self.height() / 2.0);
+        if r <= 0.0 {
+            return self.to_path();
+        }
+        // control point distance for a cubic approximation of a quarter circle
+        let k = r * 0.552_284_8;
+        let (l, t, rt, b) = (self.left(), self.top(), self.right(), self.bottom());
+
+        let mut pb = PathBuilder::new();
+        pb.move_to(l + r, t);
+        pb.line_to(rt - r, t);
+        pb.cubic_to(rt - r + k, t, rt, t + r - k, rt, t + r);
+        pb.line_to(rt, b - r);
+        pb.cubic_to(rt, b - r + k, rt - r + k, b, rt - r, b);
+        pb.line_to(l + r, b);
+        pb.cubic_to(l + r - k, b, l, b - r + k, l, b - r);
+        pb.line_to(l, t + r);
+        pb.cubic_to(l, t + r - k, l + r - k, t, l + r, t);
+        pb.close();
+        pb.finish().expect("Should be a valid path")
+    }
 }
 
 /// Padding within a graphical element
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Padding {
     /// Uniform padding in all directions
     Even(f32),
@@ -486,6 +568,23 @@ impl Padding {
             Padding::Custom { l, r, .. } => *l + *r,
         }
     }
+
+    /// The same padding with negative values clamped to zero
+    pub fn non_negative(self) -> Self {
+        match self {
+            Padding::Even(p) => Padding::Even(p.max(0.0)),
+            Padding::Center { v, h } => Padding::Center {
+                v: v.max(0.0),
+                h: h.max(0.0),
+            },
+            Padding::Custom { t, r, b, l } => Padding::Custom {
+                t: t.max(0.0),
+                r: r.max(0.0),
+                b: b.max(0.0),
+                l: l.max(0.0),
+            },
+        }
+    }
 }
 
 impl From<f32> for Padding {
@@ -604,3 +703,79 @@ impl From<(f32, f32, f32, f32)> for Margin {
         Margin::Custom { t, r, b, l }
     }
 }
+
+/// Serde support for [`Path`], to be used with `#[serde(with = "geom::serde_path")]`.
+///
+/// The path is represented as a sequence of segments, each being the verb followed by its points:
+/// `[["M", 0, 0], ["L", 10, 0], ["Q", 15, 5, 10, 10], ["C", 5, 15, 0, 15, 0, 10], ["Z"]]`.
+#[cfg(feature = "serde")]
+pub mod serde_path {
+    use serde::de::Error as _;
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::{Path, PathBuilder, PathSegment, Point};
+
+    /// Serialize a path as a sequence of segments
+    pub fn serialize<S: Serializer>(path: &Path, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(path.verbs().len()))?;
+        for seg in path.segments() {
+            let (verb, pts): (&str, &[Point]) = match &seg {
+                PathSegment::MoveTo(p) => ("M", std::slice::from_ref(p)),
+                PathSegment::LineTo(p) => ("L", std::slice::from_ref(p)),
+                PathSegment::QuadTo(p1, p) => ("Q", &[*p1, *p]),
+                PathSegment::CubicTo(p1, p2, p) => ("C", &[*p1, *p2, *p]),
+                PathSegment::Close => ("Z", &[]),
+            };
+            let mut elem = Vec::with_capacity(1 + 2 * pts.len());
+            elem.push(Elem::Verb(verb.to_string()));
+            for p in pts {
+                elem.push(Elem::Coord(p.x));
+                elem.push(Elem::Coord(p.y));
+            }
+            seq.serialize_element(&elem)?;
+        }
+        seq.end()
+    }
+
+    /// Deserialize a path from a sequence of segments
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Path, D::Error> {
+        let segs: Vec<Vec<Elem>> = Vec::deserialize(deserializer)?;
+        let mut pb = PathBuilder::new();
+        for seg in segs {
+            let (verb, coords) = match seg.split_first() {
+                Some((Elem::Verb(verb), coords)) => (verb.as_str(), coords),
+                _ => return Err(D::Error::custom("path segment must start with a verb")),
+            };
+            let coords = coords
+                .iter()
+                .map(|c| match c {
+                    Elem::Coord(c) => Ok(*c),
+                    Elem::Verb(v) => Err(D::Error::custom(format!("unexpected verb {v:?}"))),
+                })
+                .collect::<Result<Vec<f32>, _>>()?;
+            match (verb, coords.as_slice()) {
+                ("M", &[x, y]) => pb.move_to(x, y),
+                ("L", &[x, y]) => pb.line_to(x, y),
+                ("Q", &[x1, y1, x, y]) => pb.quad_to(x1, y1, x, y),
+                ("C", &[x1, y1, x2, y2, x, y]) => pb.cubic_to(x1, y1, x2, y2, x, y),
+                ("Z", &[]) => pb.close(),
+                _ => {
+                    return Err(D::Error::custom(format!(
+                        "invalid path segment {verb:?} with {} coordinates",
+                        coords.len()
+                    )));
+                }
+            }
+        }
+        pb.finish()
+            .ok_or_else(|| D::Error::custom("invalid or empty path"))
+    }
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    #[serde(untagged)]
+    enum Elem {
+        Verb(String),
+        Coord(f32),
+    }
+}
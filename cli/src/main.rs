@@ -0,0 +1,331 @@
+//! Command line tool to render `.plotive` files to PNG or SVG.
+//!
+//! ```text
+//! plotive figure.plotive --output figure.png --theme catppuccin-mocha
+//! ```
+//! The data of the figures must be declared in the file itself,
+//! either inline or with a reference to a CSV file (see `plotive::dsl::parse_document`).
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::{env, fmt, fs};
+
+use plotive::style::Builtin;
+use plotive::{Prepare, Style, dsl, geom, render};
+use plotive_pxl::SavePng;
+use plotive_svg::SaveSvg;
+
+const USAGE: &str = "\
+Usage: plotive <INPUT> --output <FILE> [OPTIONS]
+
+Render a .plotive file to PNG or SVG.
+
+Options:
+  -o, --output <FILE>    Output file, with .png or .svg extension (can be repeated)
+      --size <WxH>       Override the figure size, in figure units (e.g. 800x600)
+      --scale <SCALE>    Scale factor from figure units to pixels [default: 2 for PNG, 1 for SVG]
+      --size-mm <WxH>    Physical size of the output, in millimeters (e.g. 210x297 for A4).
+                         Overrides the scale. The figure is scaled to fit and centered.
+      --dpi <DPI>        Resolution of the physical size for PNG output [default: 300]
+      --theme <THEME>    Built-in style to render with [default: light]
+      --figure <INDEX>   Index of the figure to render, if the file has several [default: 0]
+  -h, --help             Print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Png,
+    Svg,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Args {
+    input: Option<PathBuf>,
+    outputs: Vec<PathBuf>,
+    size: Option<geom::Size>,
+    scale: Option<f32>,
+    size_mm: Option<geom::Size>,
+    dpi: Option<f32>,
+    theme: Option<Builtin>,
+    figure: usize,
+    help: bool,
+}
+
+#[derive(Debug)]
+enum Error {
+    Args(String),
+    Io(PathBuf, std::io::Error),
+    Pxl(plotive_pxl::Error),
+    Svg(plotive_svg::Error),
+    Drawing(plotive::drawing::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Args(msg) => write!(f, "{msg}\n\n{USAGE}"),
+            Error::Io(path, err) => write!(f, "Could not read {}: {err}", path.display()),
+            Error::Pxl(err) => write!(f, "Could not save PNG: {err}"),
+            Error::Svg(err) => write!(f, "Could not save SVG: {err}"),
+            Error::Drawing(err) => write!(f, "Could not prepare figure: {err}"),
+        }
+    }
+}
+
+fn parse_args<I>(args: I) -> Result<Args, Error>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_val) = match arg.split_once('=') {
+            Some((flag, val)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(val.to_string()))
+            }
+            _ => (arg.clone(), None),
+        };
+        let mut value = |flag: &str| {
+            inline_val
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| Error::Args(format!("Missing value for {flag}")))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => parsed.help = true,
+            "-o" | "--output" => parsed.outputs.push(PathBuf::from(value(&flag)?)),
+            "--size" => {
+                let val = value(&flag)?;
+                parsed.size = Some(
+                    parse_size(&val).ok_or_else(|| Error::Args(format!("Invalid size: {val}")))?,
+                );
+            }
+            "--scale" => {
+                let val = value(&flag)?;
+                let scale = val
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|s| *s > 0.0)
+                    .ok_or_else(|| Error::Args(format!("Invalid scale: {val}")))?;
+                parsed.scale = Some(scale);
+            }
+            "--size-mm" => {
+                let val = value(&flag)?;
+                parsed.size_mm = Some(
+                    parse_size(&val).ok_or_else(|| Error::Args(format!("Invalid size: {val}")))?,
+                );
+            }
+            "--dpi" => {
+                let val = value(&flag)?;
+                let dpi = val
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|d| *d > 0.0)
+                    .ok_or_else(|| Error::Args(format!("Invalid DPI: {val}")))?;
+                parsed.dpi = Some(dpi);
+            }
+            "--theme" => {
+                let val = value(&flag)?;
+                let theme = val.parse::<Builtin>().map_err(|_| {
+                    let names: Vec<&str> = Builtin::ALL.iter().map(|b| b.name()).collect();
+                    Error::Args(format!(
+                        "Unknown theme: {val} (expected one of {})",
+                        names.join(", ")
+                    ))
+                })?;
+                parsed.theme = Some(theme);
+            }
+            "--figure" => {
+                let val = value(&flag)?;
+                parsed.figure = val
+                    .parse()
+                    .map_err(|_| Error::Args(format!("Invalid figure index: {val}")))?;
+            }
+            _ if flag.starts_with('-') => {
+                return Err(Error::Args(format!("Unknown argument: {arg}")));
+            }
+            _ if parsed.input.is_none() => parsed.input = Some(PathBuf::from(arg)),
+            _ => return Err(Error::Args(format!("Unexpected argument: {arg}"))),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Parse a size such as "800x600"
+fn parse_size(s: &str) -> Option<geom::Size> {
+    let (w, h) = s.split_once(['x', 'X'])?;
+    let w: f32 = w.trim().parse().ok()?;
+    let h: f32 = h.trim().parse().ok()?;
+    (w > 0.0 && h > 0.0).then(|| geom::Size::new(w, h))
+}
+
+fn output_format(path: &Path) -> Result<Format, Error> {
+    let ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    match ext.as_deref() {
+        Some("png") => Ok(Format::Png),
+        Some("svg") => Ok(Format::Svg),
+        _ => Err(Error::Args(format!(
+            "Cannot deduce the output format of {} (expected .png or .svg)",
+            path.display()
+        ))),
+    }
+}
+
+fn run(args: Args) -> Result<ExitCode, Error> {
+    let Some(input) = args.input else {
+        return Err(Error::Args("Missing input file".to_string()));
+    };
+    if args.outputs.is_empty() {
+        return Err(Error::Args("Missing output file".to_string()));
+    }
+    let formats = args
+        .outputs
+        .iter()
+        .map(|path| output_format(path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let content = fs::read_to_string(&input).map_err(|err| Error::Io(input.clone(), err))?;
+    let doc = match dsl::parse_document_diag(&content, Some(&input)) {
+        Ok(doc) => doc,
+        Err(report) => {
+            eprintln!("{report:?}");
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+
+    let fig_count = doc.figures.len();
+    let Some(mut fig) = doc.figures.into_iter().nth(args.figure) else {
+        return Err(Error::Args(format!(
+            "No figure at index {} ({} has {fig_count} figure(s))",
+            args.figure,
+            input.display()
+        )));
+    };
+    if let Some(size) = args.size {
+        fig = fig.with_size(size);
+    }
+
+    let physical_size = args.size_mm.map(|size| {
+        render::PhysicalSize::new(size.width(), size.height(), args.dpi.unwrap_or(300.0))
+    });
+    let style: Style = args.theme.unwrap_or(Builtin::Light).into();
+    let fontdb = plotive::bundled_font_db();
+    let fig = fig
+        .prepare(&doc.data, Some(&fontdb))
+        .map_err(Error::Drawing)?;
+
+    for (path, format) in args.outputs.iter().zip(formats) {
+        match format {
+            Format::Png => fig
+                .save_png(
+                    path,
+                    &(),
+                    plotive_pxl::Params {
+                        style: style.clone(),
+                        scale: args.scale.unwrap_or(2.0),
+                        fontdb: Some(&fontdb),
+                        physical_size,
+                        ..Default::default()
+                    },
+                )
+                .map_err(Error::Pxl)?,
+            Format::Svg => fig
+                .save_svg(
+                    path,
+                    &(),
+                    plotive_svg::Params {
+                        style: style.clone(),
+                        scale: args.scale.unwrap_or(1.0),
+                        fontdb: Some(&fontdb),
+                        physical_size,
+                    },
+                )
+                .map_err(Error::Svg)?,
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if args.help {
+        println!("{USAGE}");
+        return ExitCode::SUCCESS;
+    }
+    match run(args) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, Error> {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parsed = args(&[
+            "fig.plotive",
+            "-o",
+            "fig.png",
+            "--output=fig.svg",
+            "--size",
+            "800x600",
+            "--scale",
+            "3",
+            "--theme",
+            "catppuccin-mocha",
+        ])
+        .unwrap();
+        assert_eq!(parsed.input, Some(PathBuf::from("fig.plotive")));
+        assert_eq!(
+            parsed.outputs,
+            vec![PathBuf::from("fig.png"), PathBuf::from("fig.svg")]
+        );
+        assert_eq!(parsed.size, Some(geom::Size::new(800.0, 600.0)));
+        assert_eq!(parsed.scale, Some(3.0));
+        assert_eq!(parsed.theme, Some(Builtin::CatppuccinMocha));
+        assert_eq!(parsed.figure, 0);
+        assert_eq!(parsed.size_mm, None);
+
+        let parsed = args(&["fig.plotive", "--size-mm", "210x297", "--dpi=150"]).unwrap();
+        assert_eq!(parsed.size_mm, Some(geom::Size::new(210.0, 297.0)));
+        assert_eq!(parsed.dpi, Some(150.0));
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(args(&["fig.plotive", "--size", "800"]).is_err());
+        assert!(args(&["fig.plotive", "--scale", "-1"]).is_err());
+        assert!(args(&["fig.plotive", "--dpi", "0"]).is_err());
+        assert!(args(&["fig.plotive", "--theme", "unknown"]).is_err());
+        assert!(args(&["fig.plotive", "--output"]).is_err());
+        assert!(args(&["fig.plotive", "other.plotive"]).is_err());
+        assert!(args(&["fig.plotive", "--unknown"]).is_err());
+    }
+
+    #[test]
+    fn test_output_format() {
+        assert_eq!(output_format(Path::new("a.png")).unwrap(), Format::Png);
+        assert_eq!(output_format(Path::new("a.SVG")).unwrap(), Format::Svg);
+        assert!(output_format(Path::new("a.pdf")).is_err());
+    }
+}
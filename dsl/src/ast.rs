@@ -1,5 +1,44 @@
+use std::path::PathBuf;
+
 use crate::lex::Span;
 
+/// A parsed document: its properties and the data it declares
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    pub data: Vec<Data>,
+    pub props: Vec<Prop>,
+}
+
+/// A data declaration
+#[derive(Debug, Clone, PartialEq)]
+pub enum Data {
+    /// An inline column: `data name = [...]`
+    Column { name: Ident, values: Array },
+    /// A CSV file: `data from "file.csv"`.
+    /// The path is resolved relative to the declaring file.
+    Csv { span: Span, path: PathBuf },
+}
+
+impl Data {
+    pub fn span(&self) -> Span {
+        match self {
+            Data::Column { name, values } => (name.span.0, values.span.1),
+            Data::Csv { span, .. } => *span,
+        }
+    }
+
+    /// Report the declaration at the given span
+    pub(crate) fn set_span(&mut self, span: Span) {
+        match self {
+            Data::Column { name, values } => {
+                name.span = span;
+                values.span = span;
+            }
+            Data::Csv { span: s, .. } => *s = span,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Ident {
     pub span: Span,
@@ -13,6 +52,14 @@ pub struct Prop {
 }
 
 impl Prop {
+    /// Report the whole property, including all nested values, at the given span
+    pub(crate) fn set_span_deep(&mut self, span: Span) {
+        self.name.span = span;
+        if let Some(value) = &mut self.value {
+            value.set_span_deep(span);
+        }
+    }
+
     pub fn span(&self) -> Span {
         let start_span = self.name.span;
         if let Some(value) = &self.value {
@@ -40,6 +87,41 @@ impl Value {
             Value::Struct(struct_) => struct_.span,
         }
     }
+
+    /// Report the value, including all nested values, at the given span
+    pub(crate) fn set_span_deep(&mut self, span: Span) {
+        match self {
+            Value::Scalar(scalar) => scalar.set_span_deep(span),
+            Value::Seq(seq) => seq.set_span_deep(span),
+            Value::Array(array) => array.span = span,
+            Value::Struct(struct_) => {
+                struct_.span = span;
+                if let Some(typ) = &mut struct_.typ {
+                    typ.span = span;
+                }
+                for prop in struct_.props.iter_mut() {
+                    prop.set_span_deep(span);
+                }
+            }
+        }
+    }
+
+    /// Returns the same value, reported at the given span
+    pub fn with_span(self, span: Span) -> Value {
+        match self {
+            Value::Scalar(scalar) => Value::Scalar(Scalar { span, ..scalar }),
+            Value::Seq(seq) => Value::Seq(Seq {
+                span,
+                scalars: seq
+                    .scalars
+                    .into_iter()
+                    .map(|scalar| Scalar { span, ..scalar })
+                    .collect(),
+            }),
+            Value::Array(array) => Value::Array(Array { span, ..array }),
+            Value::Struct(struct_) => Value::Struct(Struct { span, ..struct_ }),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -48,6 +130,16 @@ pub struct Scalar {
     pub kind: ScalarKind,
 }
 
+impl Scalar {
+    fn set_span_deep(&mut self, span: Span) {
+        self.span = span;
+        if let ScalarKind::Func(func) = &mut self.kind {
+            func.name.span = span;
+            func.args.set_span_deep(span);
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ScalarKind {
     Enum(String),
@@ -69,6 +161,15 @@ pub struct Seq {
     pub scalars: Vec<Scalar>,
 }
 
+impl Seq {
+    fn set_span_deep(&mut self, span: Span) {
+        self.span = span;
+        for scalar in self.scalars.iter_mut() {
+            scalar.set_span_deep(span);
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Array {
     pub span: Span,
@@ -16,6 +16,10 @@ pub trait DiagTrait: fmt::Debug + fmt::Display {
     fn help(&self) -> Option<String> {
         None
     }
+    /// The error in an included file that caused this one, if any
+    fn included(&self) -> Option<(Source, Box<dyn DiagTrait>)> {
+        None
+    }
 }
 
 impl DiagTrait for lex::Error {
@@ -28,6 +32,7 @@ impl DiagTrait for lex::Error {
             lex::Error::InvalidNumber(span, _) => *span,
             lex::Error::InvalidKebabIdent(span, _) => *span,
             lex::Error::InvalidPascalIdent(span, _) => *span,
+            lex::Error::InvalidDirective(span, _) => *span,
         }
     }
 
@@ -42,12 +47,33 @@ impl DiagTrait for parse::Error {
             parse::Error::Lex(err) => err.span(),
             parse::Error::UnexpectedEndOfInput(span) => *span,
             parse::Error::UnexpectedToken(tok, _) => tok.span,
+            parse::Error::UnknownVariable(ident) => ident.span,
+            parse::Error::InvalidExpr(span, _) => *span,
+            parse::Error::IncludeFailed { span, .. } => *span,
+            parse::Error::IncludeCycle(span, _) => *span,
+            parse::Error::MixedArray(span, _) => *span,
+            parse::Error::MisplacedData(span) => *span,
+            parse::Error::UnexpectedData(span) => *span,
+            parse::Error::Include { span, .. } => *span,
         }
     }
 
     fn message(&self) -> String {
         format!("{}", self)
     }
+
+    fn included(&self) -> Option<(Source, Box<dyn DiagTrait>)> {
+        match self {
+            parse::Error::Include { path, src, err, .. } => {
+                let source = Source {
+                    name: Some(path.display().to_string()),
+                    src: src.clone(),
+                };
+                Some((source, Box::new((**err).clone())))
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -97,11 +123,20 @@ impl miette::SourceCode for Source {
 pub struct Diagnostic {
     diag: Box<dyn DiagTrait>,
     source: Source,
+    /// Diagnostic of the error in an included file
+    included: Option<Box<Diagnostic>>,
 }
 
 impl<'a> Diagnostic {
     pub fn new(diag: Box<dyn DiagTrait>, source: Source) -> Self {
-        Self { diag, source }
+        let included = diag
+            .included()
+            .map(|(source, diag)| Box::new(Diagnostic::new(diag, source)));
+        Self {
+            diag,
+            source,
+            included,
+        }
     }
 }
 
@@ -145,4 +180,11 @@ impl miette::Diagnostic for Diagnostic {
     fn source_code(&self) -> Option<&dyn miette::SourceCode> {
         Some(&self.source as &dyn miette::SourceCode)
     }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn miette::Diagnostic> + 'a>> {
+        self.included.as_deref().map(|diag| {
+            Box::new(std::iter::once(diag as &dyn miette::Diagnostic))
+                as Box<dyn Iterator<Item = &'a dyn miette::Diagnostic>>
+        })
+    }
 }
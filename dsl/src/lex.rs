@@ -24,6 +24,7 @@ pub enum Error {
     InvalidNumber(Span, String),
     InvalidKebabIdent(Span, String),
     InvalidPascalIdent(Span, String),
+    InvalidDirective(Span, String),
 }
 
 impl fmt::Display for Error {
@@ -48,6 +49,7 @@ impl fmt::Display for Error {
                 write!(f, "Invalid kebab-case identifier {}", s)
             }
             Error::InvalidPascalIdent(_, s) => write!(f, "Invalid pascal-case identifier {}", s,),
+            Error::InvalidDirective(_, s) => write!(f, "Invalid directive @{}", s),
         }
     }
 }
@@ -66,6 +68,8 @@ pub struct Token {
 pub enum TokenKind {
     KebabCaseIdent(String),
     PascalCaseIdent(String),
+    /// A directive such as `@include`, without the `@`
+    Directive(String),
     OpenPar,
     ClosePar,
     OpenBracket,
@@ -74,6 +78,11 @@ pub enum TokenKind {
     CloseBrace,
     Colon,
     Comma,
+    Equal,
+    Plus,
+    Minus,
+    Star,
+    Slash,
     StrLit(String),
     IntLit(i64),
     FloatLit(f64),
@@ -171,10 +180,20 @@ where
             '}' => Ok(Some(TokenKind::CloseBrace)),
             ':' => Ok(Some(TokenKind::Colon)),
             ',' => Ok(Some(TokenKind::Comma)),
+            '=' => Ok(Some(TokenKind::Equal)),
+            '*' => Ok(Some(TokenKind::Star)),
             '"' => {
                 let buf = self.parse_string(start_pos)?;
                 Ok(Some(TokenKind::StrLit(buf)))
             }
+            '-' | '+' if !matches!(self.cursor.first(), Some('0'..='9')) => {
+                // a sign directly followed by a digit is part of a number literal
+                if c == '-' {
+                    Ok(Some(TokenKind::Minus))
+                } else {
+                    Ok(Some(TokenKind::Plus))
+                }
+            }
             '-' | '+' | '0'..='9' => {
                 let kind = self.parse_number(start_pos, c)?;
                 Ok(Some(kind))
@@ -187,8 +206,19 @@ where
                 let buf = self.parse_pascal_case_ident(start_pos, c)?;
                 Ok(Some(TokenKind::PascalCaseIdent(buf)))
             }
+            '@' => match self.cursor.next() {
+                Some(c @ 'a'..='z') => {
+                    let buf = self.parse_kebab_case_ident(start_pos, c)?;
+                    Ok(Some(TokenKind::Directive(buf)))
+                }
+                c => Err(Error::InvalidDirective(
+                    (start_pos, self.cursor.pos()),
+                    c.map(String::from).unwrap_or_default(),
+                )),
+            },
+            '/' if self.cursor.first() != Some('/') => Ok(Some(TokenKind::Slash)),
             '/' => {
-                self.expect_next('/')?;
+                self.cursor.next();
                 loop {
                     match self.cursor.next() {
                         None => break,
@@ -470,6 +500,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_operators() {
+        let toks = tokenize_str("a = (b + -2) * c - d / 4.0");
+        assert_eq!(
+            toks,
+            vec![
+                TokenKind::KebabCaseIdent("a".into()),
+                TokenKind::Space,
+                TokenKind::Equal,
+                TokenKind::Space,
+                TokenKind::OpenPar,
+                TokenKind::KebabCaseIdent("b".into()),
+                TokenKind::Space,
+                TokenKind::Plus,
+                TokenKind::Space,
+                TokenKind::IntLit(-2),
+                TokenKind::ClosePar,
+                TokenKind::Space,
+                TokenKind::Star,
+                TokenKind::Space,
+                TokenKind::KebabCaseIdent("c".into()),
+                TokenKind::Space,
+                TokenKind::Minus,
+                TokenKind::Space,
+                TokenKind::KebabCaseIdent("d".into()),
+                TokenKind::Space,
+                TokenKind::Slash,
+                TokenKind::Space,
+                TokenKind::FloatLit(4.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_directive() {
+        let toks = tokenize_str("@include \"common.plotive\"");
+        assert_eq!(
+            toks,
+            vec![
+                TokenKind::Directive("include".into()),
+                TokenKind::Space,
+                TokenKind::StrLit("common.plotive".into()),
+            ]
+        );
+    }
+
     #[test]
     fn test_comments_and_eol() {
         let toks = tokenize_str("// comment\nfoo\n//x\r\nbar");
@@ -514,14 +590,14 @@ mod fail_tests {
     }
 
     #[test]
-    fn test_malformed_comment() {
-        let toks = tokenize_str("foo: 1\n / bar");
+    fn test_unexpected_carriage_return() {
+        let toks = tokenize_str("foo: 1\r bar");
         assert!(toks.is_err());
         assert!(matches!(
             toks.unwrap_err(),
             Error::UnexpectedChar {
-                pos: 9,
-                expected: '/',
+                pos: 7,
+                expected: '\n',
                 found: ' '
             }
         ));
@@ -37,6 +37,40 @@
 //! }
 //! ```
 //!
+//! Values can be bound to variables with `let` and referenced by name
+//! in the rest of the property list where they are defined, including nested structs.
+//! Numeric values can be combined with the `+`, `-`, `*` and `/` operators and parentheses.
+//! Operators must be surrounded by spaces, as `-` is also valid within identifiers.
+//! ```dsl
+//! let axis = Ticks, Grid
+//! let gap = 10
+//! figure: {
+//!     space: gap * 2
+//!     plot: {
+//!         x-axis: "x", axis
+//!         y-axis: "y", axis
+//!     }
+//! }
+//! ```
+//! Variables and expressions are resolved during parsing.
+//!
+//! Other files can be included with the `@include` directive, e.g. to share variables
+//! or common definitions between documents:
+//! ```dsl
+//! @include "common/theme.plotive"
+//! ```
+//! The path is relative to the including file (see [`parse_with_path`]).
+//! The properties of the included file are inserted in place of the directive,
+//! and its top-level variables become visible in the including property list.
+//!
+//! Documents can also declare their data at the top level, either inline or from a CSV file:
+//! ```dsl
+//! data x = [0, 1, 2]
+//! data from "measurements.csv"
+//! ```
+//! Data declarations are returned by [`parse_document`] and rejected by [`parse_with_path`].
+//! All values of an inline array must have the same type.
+//!
 //! Plotive DSL documents are parsed into an abstract syntax tree (AST)
 //! defined in the [`ast`] module.
 //! The AST can then be parsed by applications.
@@ -51,4 +85,4 @@ mod parse;
 pub use diag::{DiagReport, DiagResult, DiagTrait, Diagnostic, Source};
 pub use input::Pos;
 pub use lex::Span;
-pub use parse::{Error, parse};
+pub use parse::{Error, parse, parse_document, parse_with_path};
@@ -1,4 +1,5 @@
-use std::fmt;
+use std::path::{Path, PathBuf};
+use std::{fmt, fs};
 
 use crate::ast;
 use crate::lex::{self, Span, Token, TokenKind};
@@ -8,6 +9,34 @@ pub enum Error {
     Lex(lex::Error),
     UnexpectedEndOfInput(Span),
     UnexpectedToken(Token, Option<String>),
+    UnknownVariable(ast::Ident),
+    InvalidExpr(Span, String),
+    /// An included file could not be read
+    IncludeFailed {
+        span: Span,
+        path: PathBuf,
+        reason: String,
+    },
+    /// A file includes itself, directly or through other files
+    IncludeCycle(Span, PathBuf),
+    /// An array mixes values of different types.
+    /// The span is the first value of unexpected type, and the string is the expected type.
+    MixedArray(Span, String),
+    /// A data declaration is not at the top-level of a document
+    MisplacedData(Span),
+    /// Data declarations are only supported by [`parse_document`]
+    UnexpectedData(Span),
+    /// An error occurred in an included file
+    Include {
+        /// Span of the include directive
+        span: Span,
+        /// Path of the included file
+        path: PathBuf,
+        /// Content of the included file
+        src: String,
+        /// The error, with a span in the included file
+        err: Box<Error>,
+    },
 }
 
 impl Error {}
@@ -32,24 +61,99 @@ impl fmt::Display for Error {
                 }
                 Ok(())
             }
+            Error::UnknownVariable(ident) => write!(f, "Unknown variable: {}", ident.name),
+            Error::InvalidExpr(_, reason) => write!(f, "Invalid expression: {}", reason),
+            Error::IncludeFailed { path, reason, .. } => {
+                write!(f, "Could not include {}: {}", path.display(), reason)
+            }
+            Error::IncludeCycle(_, path) => {
+                write!(f, "Include cycle: {} includes itself", path.display())
+            }
+            Error::MixedArray(_, expected) => {
+                write!(f, "Mixed types in array: expected {}", expected)
+            }
+            Error::MisplacedData(_) => {
+                write!(
+                    f,
+                    "Data can only be declared at the top-level of a document"
+                )
+            }
+            Error::UnexpectedData(_) => write!(f, "Data declarations are not supported here"),
+            Error::Include { path, err, .. } => {
+                write!(f, "In included file {}: {}", path.display(), err)
+            }
         }
     }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Parse the input into a list of properties.
+/// Included files are resolved relative to the current working directory of the process.
+/// Use [`parse_with_path`] to resolve them relative to the input file.
 pub fn parse<I>(input: I) -> Result<Vec<ast::Prop>>
+where
+    I: Iterator<Item = char> + Clone,
+{
+    parse_with_path(input, None)
+}
+
+/// Parse the input into a list of properties.
+/// `path` is the path of the input file, if any.
+/// Included files are resolved relative to its directory,
+/// or to the current working directory of the process if `path` is `None`.
+/// Data declarations are not supported, see [`parse_document`].
+pub fn parse_with_path<I>(input: I, path: Option<&Path>) -> Result<Vec<ast::Prop>>
+where
+    I: Iterator<Item = char> + Clone,
+{
+    let doc = parse_document(input, path)?;
+    if let Some(data) = doc.data.first() {
+        return Err(Error::UnexpectedData(data.span()));
+    }
+    Ok(doc.props)
+}
+
+/// Parse the input into a document, made of properties and data declarations.
+/// `path` is the path of the input file, if any.
+/// Included files and CSV files are resolved relative to its directory,
+/// or to the current working directory of the process if `path` is `None`.
+pub fn parse_document<I>(input: I, path: Option<&Path>) -> Result<ast::Document>
 where
     I: Iterator<Item = char> + Clone,
 {
     let tokens = lex::tokenize(input);
     let mut parser = Parser::new(tokens);
-    parser.parse_prop_list()
+    if let Some(path) = path {
+        parser.path = Some(path.to_path_buf());
+        if let Ok(path) = fs::canonicalize(path) {
+            parser.include_stack.push(path);
+        }
+    }
+    let props = parser.parse_prop_list()?;
+    parser.expect_end()?;
+    Ok(ast::Document {
+        data: parser.data,
+        props,
+    })
 }
 
+/// Variables bound in a property list
+type Scope = Vec<(String, ast::Value)>;
+
 pub struct Parser<T> {
     tokens: T,
     last_span: Span,
+    /// Variables bound with `let`, one scope per property list
+    scopes: Vec<Scope>,
+    /// Path of the parsed file, if any
+    path: Option<PathBuf>,
+    /// Canonical paths of the files being parsed, to detect include cycles
+    include_stack: Vec<PathBuf>,
+    /// Data declared at the top-level
+    data: Vec<ast::Data>,
+    /// Nesting level of structs
+    depth: usize,
 }
 
 impl<T> Parser<T> {
@@ -57,8 +161,36 @@ impl<T> Parser<T> {
         Self {
             tokens,
             last_span: Span::default(),
+            scopes: Vec::new(),
+            path: None,
+            include_stack: Vec::new(),
+            data: Vec::new(),
+            depth: 0,
         }
     }
+
+    /// Resolve a path relative to the directory of the parsed file
+    fn resolve_path(&self, rel_path: &str) -> PathBuf {
+        match self.path.as_deref().and_then(Path::parent) {
+            Some(dir) => dir.join(rel_path),
+            None => PathBuf::from(rel_path),
+        }
+    }
+
+    fn lookup_var(&self, ident: &ast::Ident) -> Result<ast::Value> {
+        let value = self
+            .scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(name, _)| *name == ident.name)
+            .map(|(_, value)| value.clone());
+        let Some(value) = value else {
+            return Err(Error::UnknownVariable(ident.clone()));
+        };
+        // the value is reported at the place it is referenced
+        Ok(value.with_span(ident.span))
+    }
 }
 
 impl<T> Parser<T>
@@ -66,9 +198,37 @@ where
     T: Iterator<Item = lex::Result<Token>> + Clone,
 {
     fn parse_prop_list(&mut self) -> Result<Vec<ast::Prop>> {
+        self.scopes.push(Vec::new());
+        let props = self.parse_prop_list_scoped();
+        self.scopes.pop();
+        props
+    }
+
+    fn parse_prop_list_scoped(&mut self) -> Result<Vec<ast::Prop>> {
         let mut props = Vec::new();
         loop {
             self.ignore_com_eol();
+            if self.is_keyword_decl("let") {
+                self.parse_let()?;
+                continue;
+            }
+            if self.is_keyword_decl("data") {
+                self.parse_data()?;
+                continue;
+            }
+            if let Some(Token {
+                kind: TokenKind::Directive(name),
+                span,
+            }) = self.first_token()?
+            {
+                if name != "include" {
+                    let tok = self.expect_next_token()?;
+                    return Err(Error::UnexpectedToken(tok, Some("@include".to_string())));
+                }
+                self.bump_token();
+                props.extend(self.parse_include(span)?);
+                continue;
+            }
             let Some(prop) = self.parse_prop()? else {
                 break;
             };
@@ -77,6 +237,200 @@ where
         Ok(props)
     }
 
+    /// Check whether the next tokens are `<keyword> <name>`, e.g. `let width`
+    fn is_keyword_decl(&self, keyword: &str) -> bool {
+        let mut tokens = self.tokens.clone().map_while(|t| t.ok());
+        matches!(
+            (tokens.next(), tokens.next(), tokens.next()),
+            (
+                Some(Token {
+                    kind: TokenKind::KebabCaseIdent(kw),
+                    ..
+                }),
+                Some(Token {
+                    kind: TokenKind::Space,
+                    ..
+                }),
+                Some(Token {
+                    kind: TokenKind::KebabCaseIdent(_),
+                    ..
+                }),
+            ) if kw == keyword
+        )
+    }
+
+    /// Parse the included file and return its properties.
+    /// Its top-level variables are added to the current scope.
+    /// Everything from the included file is reported at the include directive.
+    fn parse_include(&mut self, directive_span: Span) -> Result<Vec<ast::Prop>> {
+        self.ignore_opt_sp();
+        let (rel_path, span) = match self.expect_next_token()? {
+            Token {
+                kind: TokenKind::StrLit(path),
+                span,
+            } => (path, (directive_span.0, span.1)),
+            tok => return Err(Error::UnexpectedToken(tok, Some("file path".to_string()))),
+        };
+        self.ignore_opt_sp();
+
+        let path = self.resolve_path(&rel_path);
+        let read = fs::canonicalize(&path)
+            .and_then(|canonical| fs::read_to_string(&path).map(|src| (canonical, src)));
+        let (canonical, src) = read.map_err(|err| Error::IncludeFailed {
+            span,
+            path: path.clone(),
+            reason: err.to_string(),
+        })?;
+        if self.include_stack.contains(&canonical) {
+            return Err(Error::IncludeCycle(span, path));
+        }
+
+        let mut parser = Parser::new(lex::tokenize(src.chars()));
+        parser.path = Some(path.clone());
+        parser.include_stack = self.include_stack.clone();
+        parser.include_stack.push(canonical);
+        // the included file sees the variables defined before the include
+        parser.scopes = self.scopes.clone();
+        parser.depth = self.depth;
+
+        let included = parser.parse_included();
+        let (mut props, mut scope) = match included {
+            Ok(included) => included,
+            Err(err) => {
+                return Err(Error::Include {
+                    span,
+                    path,
+                    src,
+                    err: Box::new(err),
+                });
+            }
+        };
+
+        for prop in props.iter_mut() {
+            prop.set_span_deep(span);
+        }
+        for (_, value) in scope.iter_mut() {
+            value.set_span_deep(span);
+        }
+        for mut data in parser.data {
+            data.set_span(span);
+            self.data.push(data);
+        }
+        self.scopes
+            .last_mut()
+            .expect("a scope is pushed for each property list")
+            .extend(scope);
+        Ok(props)
+    }
+
+    /// Parse an included file, returning its properties and its top-level variables
+    fn parse_included(&mut self) -> Result<(Vec<ast::Prop>, Scope)> {
+        self.scopes.push(Vec::new());
+        let props = self.parse_prop_list_scoped()?;
+        self.expect_end()?;
+        let scope = self.scopes.pop().unwrap_or_default();
+        Ok((props, scope))
+    }
+
+    fn expect_end(&mut self) -> Result<()> {
+        match self.next_token()? {
+            Some(tok) => Err(Error::UnexpectedToken(tok, Some("property".to_string()))),
+            None => Ok(()),
+        }
+    }
+
+    /// Parse a data declaration, either an inline column or a CSV file
+    /// ```ebnf
+    /// data-decl = "data" , space , ( "from" , string-literal | kebab-case-ident , '=' , prop-value ) ;
+    /// ```
+    fn parse_data(&mut self) -> Result<()> {
+        // `data` keyword
+        let start_span = self.expect_next_token()?.span;
+        if self.depth > 0 {
+            return Err(Error::MisplacedData(start_span));
+        }
+        self.ignore_opt_sp();
+        let name = match self.expect_next_token()? {
+            Token {
+                kind: TokenKind::KebabCaseIdent(name),
+                span,
+            } => ast::Ident { span, name },
+            tok => return Err(Error::UnexpectedToken(tok, Some("column name".to_string()))),
+        };
+        self.ignore_opt_sp();
+
+        let data = match self.expect_next_token()? {
+            Token {
+                kind: TokenKind::StrLit(path),
+                span,
+            } if name.name == "from" => ast::Data::Csv {
+                span: (start_span.0, span.1),
+                path: self.resolve_path(&path),
+            },
+            Token {
+                kind: TokenKind::Equal,
+                ..
+            } => {
+                self.ignore_opt_sp();
+                match self.parse_prop_value()? {
+                    ast::Value::Array(values) => ast::Data::Column { name, values },
+                    value => {
+                        return Err(Error::InvalidExpr(
+                            value.span(),
+                            "expected an array of values".to_string(),
+                        ));
+                    }
+                }
+            }
+            tok => {
+                let expected = if name.name == "from" {
+                    "file path or ="
+                } else {
+                    "="
+                };
+                return Err(Error::UnexpectedToken(tok, Some(expected.to_string())));
+            }
+        };
+        self.ignore_opt_sp();
+        self.data.push(data);
+        Ok(())
+    }
+
+    fn parse_let(&mut self) -> Result<()> {
+        // `let` keyword
+        self.bump_token();
+        self.ignore_opt_sp();
+        let name = match self.expect_next_token()? {
+            Token {
+                kind: TokenKind::KebabCaseIdent(name),
+                span,
+            } => ast::Ident { span, name },
+            tok => {
+                return Err(Error::UnexpectedToken(
+                    tok,
+                    Some("variable name".to_string()),
+                ));
+            }
+        };
+        self.ignore_opt_sp();
+        match self.expect_next_token()? {
+            Token {
+                kind: TokenKind::Equal,
+                ..
+            } => (),
+            tok => return Err(Error::UnexpectedToken(tok, Some("=".to_string()))),
+        }
+        self.ignore_opt_sp();
+        let value = self.parse_prop_value()?;
+        self.ignore_opt_sp();
+
+        self.scopes
+            .last_mut()
+            .expect("a scope is pushed for each property list")
+            .push((name.name, value));
+        Ok(())
+    }
+
     fn parse_prop(&mut self) -> Result<Option<ast::Prop>> {
         self.ignore_opt_sp();
         let Some(tok) = self.first_token()? else {
@@ -127,31 +481,27 @@ where
                 };
                 self.parse_scalar_or_seq(scalar)
             }
-            TokenKind::IntLit(val) => {
-                let scalar = ast::Scalar {
-                    span: tok.span,
-                    kind: ast::ScalarKind::Int(val),
-                };
-                self.parse_scalar_or_seq(scalar)
-            }
-            TokenKind::FloatLit(val) => {
-                let scalar = ast::Scalar {
-                    span: tok.span,
-                    kind: ast::ScalarKind::Float(val),
-                };
-                self.parse_scalar_or_seq(scalar)
-            }
-            TokenKind::KebabCaseIdent(name) => {
-                let (end_span, func) = self.parse_func(ast::Ident {
-                    span: tok.span,
-                    name,
-                })?;
-                let scalar = ast::Scalar {
-                    span: (tok.span.0, end_span),
-                    kind: ast::ScalarKind::Func(func),
-                };
-                self.parse_scalar_or_seq(scalar)
-            }
+            TokenKind::IntLit(..)
+            | TokenKind::FloatLit(..)
+            | TokenKind::KebabCaseIdent(..)
+            | TokenKind::OpenPar
+            | TokenKind::Plus
+            | TokenKind::Minus => match self.parse_expr(tok)? {
+                ast::Value::Scalar(scalar) => self.parse_scalar_or_seq(scalar),
+                ast::Value::Seq(seq) if self.first_non_space_kind() == Some(TokenKind::Comma) => {
+                    // a sequence variable, followed by more scalars
+                    self.ignore_opt_sp();
+                    self.bump_token();
+                    let rest = self.parse_seq(seq.span.1, None)?;
+                    let mut scalars = seq.scalars;
+                    scalars.extend(rest.scalars);
+                    Ok(ast::Value::Seq(ast::Seq {
+                        span: (seq.span.0, rest.span.1),
+                        scalars,
+                    }))
+                }
+                value => Ok(value),
+            },
             TokenKind::PascalCaseIdent(name) => {
                 // both struct and enums can start with a pascal case identifier
                 Ok(self.parse_struct_or_enum_or_seq(ast::Ident {
@@ -208,44 +558,34 @@ where
                     res_scalars.push(scalar);
                     res_span.1 = span.1;
                 }
-                Token {
-                    kind: TokenKind::IntLit(val),
-                    span,
-                    ..
-                } => {
-                    self.bump_token();
-                    let scalar = ast::Scalar {
-                        span,
-                        kind: ast::ScalarKind::Int(val),
-                    };
-                    res_scalars.push(scalar);
-                    res_span.1 = span.1;
-                }
-                Token {
-                    kind: TokenKind::FloatLit(val),
-                    span,
+                tok @ Token {
+                    kind:
+                        TokenKind::IntLit(..)
+                        | TokenKind::FloatLit(..)
+                        | TokenKind::KebabCaseIdent(..)
+                        | TokenKind::OpenPar
+                        | TokenKind::Plus
+                        | TokenKind::Minus,
                     ..
                 } => {
                     self.bump_token();
-                    let scalar = ast::Scalar {
-                        span,
-                        kind: ast::ScalarKind::Float(val),
-                    };
-                    res_scalars.push(scalar);
-                    res_span.1 = span.1;
-                }
-                Token {
-                    kind: TokenKind::KebabCaseIdent(name),
-                    span,
-                } => {
-                    self.bump_token();
-                    let (end_span, func) = self.parse_func(ast::Ident { span: span, name })?;
-                    let scalar = ast::Scalar {
-                        span: (span.0, end_span),
-                        kind: ast::ScalarKind::Func(func),
-                    };
-                    res_span.1 = scalar.span.1;
-                    res_scalars.push(scalar);
+                    match self.parse_expr(tok)? {
+                        ast::Value::Scalar(scalar) => {
+                            res_span.1 = scalar.span.1;
+                            res_scalars.push(scalar);
+                        }
+                        // a sequence variable is spliced into the sequence
+                        ast::Value::Seq(seq) => {
+                            res_span.1 = seq.span.1;
+                            res_scalars.extend(seq.scalars);
+                        }
+                        value => {
+                            return Err(Error::InvalidExpr(
+                                value.span(),
+                                "expected a scalar value in sequence".to_string(),
+                            ));
+                        }
+                    }
                 }
                 Token {
                     kind: TokenKind::PascalCaseIdent(name),
@@ -281,6 +621,93 @@ where
         })
     }
 
+    /// Parse an expression, starting with the already consumed `tok`.
+    /// ```ebnf
+    /// expr    = term , { ( '+' | '-' ) , term } ;
+    /// term    = factor , { ( '*' | '/' ) , factor } ;
+    /// factor  = int-literal | float-literal | variable | func-value
+    ///         | ( '+' | '-' ) , factor | '(' , expr , ')' ;
+    /// ```
+    /// A variable can hold any value, but only numbers can be operands of arithmetic operators.
+    fn parse_expr(&mut self, tok: Token) -> Result<ast::Value> {
+        let mut lhs = self.parse_term(tok)?;
+        while let Some(op @ (TokenKind::Plus | TokenKind::Minus)) = self.first_non_space_kind() {
+            self.ignore_opt_sp();
+            self.bump_token();
+            self.ignore_opt_sp();
+            let tok = self.expect_next_token()?;
+            let rhs = self.parse_term(tok)?;
+            lhs = ast::Value::Scalar(eval_binary(op, lhs, rhs)?);
+        }
+        Ok(lhs)
+    }
+
+    fn parse_term(&mut self, tok: Token) -> Result<ast::Value> {
+        let mut lhs = self.parse_factor(tok)?;
+        while let Some(op @ (TokenKind::Star | TokenKind::Slash)) = self.first_non_space_kind() {
+            self.ignore_opt_sp();
+            self.bump_token();
+            self.ignore_opt_sp();
+            let tok = self.expect_next_token()?;
+            let rhs = self.parse_factor(tok)?;
+            lhs = ast::Value::Scalar(eval_binary(op, lhs, rhs)?);
+        }
+        Ok(lhs)
+    }
+
+    fn parse_factor(&mut self, tok: Token) -> Result<ast::Value> {
+        match tok.kind {
+            TokenKind::IntLit(val) => Ok(ast::Value::Scalar(ast::Scalar {
+                span: tok.span,
+                kind: ast::ScalarKind::Int(val),
+            })),
+            TokenKind::FloatLit(val) => Ok(ast::Value::Scalar(ast::Scalar {
+                span: tok.span,
+                kind: ast::ScalarKind::Float(val),
+            })),
+            TokenKind::KebabCaseIdent(name) => {
+                let ident = ast::Ident {
+                    span: tok.span,
+                    name,
+                };
+                if self.first_non_space_kind() == Some(TokenKind::OpenPar) {
+                    let (end_span, func) = self.parse_func(ident)?;
+                    Ok(ast::Value::Scalar(ast::Scalar {
+                        span: (tok.span.0, end_span),
+                        kind: ast::ScalarKind::Func(func),
+                    }))
+                } else {
+                    self.lookup_var(&ident)
+                }
+            }
+            TokenKind::OpenPar => {
+                self.ignore_opt_sp();
+                let first = self.expect_next_token()?;
+                let value = self.parse_expr(first)?;
+                self.ignore_opt_sp();
+                let close_span = self.expect_token(TokenKind::ClosePar)?;
+                let num = expect_num(value)?;
+                Ok(ast::Value::Scalar(
+                    num.to_scalar((tok.span.0, close_span.1)),
+                ))
+            }
+            TokenKind::Plus | TokenKind::Minus => {
+                self.ignore_opt_sp();
+                let first = self.expect_next_token()?;
+                let operand = self.parse_factor(first)?;
+                let span = (tok.span.0, operand.span().1);
+                let num = expect_num(operand)?;
+                let num = if tok.kind == TokenKind::Minus {
+                    num.neg(span)?
+                } else {
+                    num
+                };
+                Ok(ast::Value::Scalar(num.to_scalar(span)))
+            }
+            _ => Err(Error::UnexpectedToken(tok, Some("expression".to_string()))),
+        }
+    }
+
     fn parse_str_concatenation(
         &mut self,
         start_span: Span,
@@ -366,7 +793,10 @@ where
     }
 
     fn parse_struct(&mut self, start_span: Span, typ: Option<ast::Ident>) -> Result<ast::Struct> {
-        let props = self.parse_prop_list()?;
+        self.depth += 1;
+        let props = self.parse_prop_list();
+        self.depth -= 1;
+        let props = props?;
         match self.expect_next_token()? {
             Token {
                 span,
@@ -439,6 +869,10 @@ where
                     fvec.push(val);
                     return self.parse_float_sequence(fvec);
                 }
+                Token {
+                    kind: TokenKind::StrLit(..),
+                    span,
+                } => return Err(Error::MixedArray(span, "number".to_string())),
                 _ => (),
             }
         }
@@ -474,6 +908,10 @@ where
                     self.bump_token();
                     vec.push(val);
                 }
+                Token {
+                    kind: TokenKind::StrLit(..),
+                    span,
+                } => return Err(Error::MixedArray(span, "number".to_string())),
                 _ => (),
             }
         }
@@ -503,6 +941,10 @@ where
                     let (_, val) = self.parse_str_concatenation(span, val)?;
                     vec.push(val);
                 }
+                Token {
+                    kind: TokenKind::IntLit(..) | TokenKind::FloatLit(..),
+                    span,
+                } => return Err(Error::MixedArray(span, "string".to_string())),
                 _ => (),
             }
         }
@@ -561,6 +1003,15 @@ where
         self.tokens.clone().next().transpose()
     }
 
+    /// The kind of the first token that is not a space, without consuming anything
+    fn first_non_space_kind(&self) -> Option<TokenKind> {
+        self.tokens
+            .clone()
+            .map_while(|t| t.ok())
+            .map(|t| t.kind)
+            .find(|k| *k != TokenKind::Space)
+    }
+
     fn bump_token(&mut self) {
         self.next_token().unwrap();
     }
@@ -582,6 +1033,99 @@ where
     }
 }
 
+/// A numeric operand of an arithmetic expression
+#[derive(Debug, Clone, Copy)]
+enum Num {
+    Int(i64),
+    Float(f64),
+}
+
+impl Num {
+    fn as_f64(self) -> f64 {
+        match self {
+            Num::Int(val) => val as f64,
+            Num::Float(val) => val,
+        }
+    }
+
+    fn to_scalar(self, span: Span) -> ast::Scalar {
+        let kind = match self {
+            Num::Int(val) => ast::ScalarKind::Int(val),
+            Num::Float(val) => ast::ScalarKind::Float(val),
+        };
+        ast::Scalar { span, kind }
+    }
+
+    fn neg(self, span: Span) -> Result<Num> {
+        match self {
+            Num::Int(val) => val
+                .checked_neg()
+                .map(Num::Int)
+                .ok_or_else(|| overflow(span)),
+            Num::Float(val) => Ok(Num::Float(-val)),
+        }
+    }
+}
+
+fn overflow(span: Span) -> Error {
+    Error::InvalidExpr(span, "integer overflow".to_string())
+}
+
+fn expect_num(value: ast::Value) -> Result<Num> {
+    match value {
+        ast::Value::Scalar(ast::Scalar {
+            kind: ast::ScalarKind::Int(val),
+            ..
+        }) => Ok(Num::Int(val)),
+        ast::Value::Scalar(ast::Scalar {
+            kind: ast::ScalarKind::Float(val),
+            ..
+        }) => Ok(Num::Float(val)),
+        value => Err(Error::InvalidExpr(
+            value.span(),
+            "arithmetic operands must be numbers".to_string(),
+        )),
+    }
+}
+
+fn eval_binary(op: TokenKind, lhs: ast::Value, rhs: ast::Value) -> Result<ast::Scalar> {
+    let span = (lhs.span().0, rhs.span().1);
+    let rhs_span = rhs.span();
+    let lhs = expect_num(lhs)?;
+    let rhs = expect_num(rhs)?;
+
+    if op == TokenKind::Slash && rhs.as_f64() == 0.0 {
+        return Err(Error::InvalidExpr(rhs_span, "division by zero".to_string()));
+    }
+
+    let res = match (lhs, rhs) {
+        (Num::Int(a), Num::Int(b)) => {
+            let res = match op {
+                TokenKind::Plus => a.checked_add(b),
+                TokenKind::Minus => a.checked_sub(b),
+                TokenKind::Star => a.checked_mul(b),
+                // integer division only if exact
+                TokenKind::Slash if a % b == 0 => a.checked_div(b),
+                TokenKind::Slash => return Ok(Num::Float(a as f64 / b as f64).to_scalar(span)),
+                _ => unreachable!("not an arithmetic operator: {op:?}"),
+            };
+            Num::Int(res.ok_or_else(|| overflow(span))?)
+        }
+        (lhs, rhs) => {
+            let (a, b) = (lhs.as_f64(), rhs.as_f64());
+            let res = match op {
+                TokenKind::Plus => a + b,
+                TokenKind::Minus => a - b,
+                TokenKind::Star => a * b,
+                TokenKind::Slash => a / b,
+                _ => unreachable!("not an arithmetic operator: {op:?}"),
+            };
+            Num::Float(res)
+        }
+    };
+    Ok(res.to_scalar(span))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -915,6 +1459,179 @@ bar: 2 // another comment
         assert_eq!(props[0].name.name, "foo");
         assert_eq!(props[1].name.name, "bar");
     }
+
+    #[test]
+    fn test_let_binding() {
+        let dsl = r##"
+let accent = "#ff8800"
+foo: accent
+bar: accent, 2
+"##;
+        let props = parse(dsl.chars()).unwrap();
+        assert_eq!(props.len(), 2);
+        assert_eq!(
+            props[0].value,
+            Some(ast::Value::Scalar(ast::Scalar {
+                span: (29, 35),
+                kind: ast::ScalarKind::Str("#ff8800".into()),
+            }))
+        );
+        let Some(ast::Value::Seq(seq)) = &props[1].value else {
+            panic!("expected a sequence");
+        };
+        assert_eq!(seq.scalars[0].kind, ast::ScalarKind::Str("#ff8800".into()));
+        assert_eq!(seq.scalars[1].kind, ast::ScalarKind::Int(2));
+    }
+
+    #[test]
+    fn test_let_struct_and_seq() {
+        let dsl = r#"
+let axis = "x", Ticks
+let line = Line { width: 2 }
+foo: axis, Grid
+bar: line
+"#;
+        let props = parse(dsl.chars()).unwrap();
+        let Some(ast::Value::Seq(seq)) = &props[0].value else {
+            panic!("expected a sequence");
+        };
+        let kinds: Vec<_> = seq.scalars.iter().map(|s| s.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ast::ScalarKind::Str("x".into()),
+                ast::ScalarKind::Enum("Ticks".into()),
+                ast::ScalarKind::Enum("Grid".into()),
+            ]
+        );
+        let Some(ast::Value::Struct(line)) = &props[1].value else {
+            panic!("expected a struct");
+        };
+        assert_eq!(line.typ.as_ref().unwrap().name, "Line");
+        assert!(line.has_prop("width"));
+    }
+
+    #[test]
+    fn test_let_scopes() {
+        let dsl = r#"
+let base = 1
+foo: {
+    let base = 2
+    bar: base
+}
+baz: base
+"#;
+        let props = parse(dsl.chars()).unwrap();
+        let Some(ast::Value::Struct(foo)) = &props[0].value else {
+            panic!("expected a struct");
+        };
+        let Some(ast::Value::Scalar(bar)) = &foo.prop("bar").unwrap().value else {
+            panic!("expected a scalar");
+        };
+        assert_eq!(bar.kind, ast::ScalarKind::Int(2));
+        let Some(ast::Value::Scalar(baz)) = &props[1].value else {
+            panic!("expected a scalar");
+        };
+        assert_eq!(baz.kind, ast::ScalarKind::Int(1));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let dsl = r#"
+let base = 100
+a: base * 2
+b: (base + 20) / 4 - 2.5
+c: -base / 3
+d: base-2
+"#;
+        let props = parse(dsl.chars());
+        // `base-2` is a kebab-case identifier, not a subtraction
+        assert!(matches!(props, Err(Error::UnknownVariable(ident)) if ident.name == "base-2"));
+
+        let dsl = dsl.replace("d: base-2\n", "");
+        let props = parse(dsl.chars()).unwrap();
+        let kinds: Vec<_> = props
+            .iter()
+            .map(|p| match &p.value {
+                Some(ast::Value::Scalar(s)) => s.kind.clone(),
+                _ => panic!("expected a scalar"),
+            })
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ast::ScalarKind::Int(200),
+                ast::ScalarKind::Float(27.5),
+                ast::ScalarKind::Float(-100.0 / 3.0),
+            ]
+        );
+        let Some(ast::Value::Scalar(a)) = &props[0].value else {
+            unreachable!()
+        };
+        assert_eq!(a.span, (19, 27));
+    }
+
+    #[test]
+    fn test_expr_in_seq_and_func() {
+        let dsl = "let w = 3\nfoo: w * 2, fun(w + 1)";
+        let props = parse(dsl.chars()).unwrap();
+        let Some(ast::Value::Seq(seq)) = &props[0].value else {
+            panic!("expected a sequence");
+        };
+        assert_eq!(seq.scalars[0].kind, ast::ScalarKind::Int(6));
+        let ast::ScalarKind::Func(func) = &seq.scalars[1].kind else {
+            panic!("expected a function");
+        };
+        assert_eq!(func.args.scalars[0].kind, ast::ScalarKind::Int(4));
+    }
+
+    #[test]
+    fn test_data() {
+        let dsl = r#"
+let ys = [0.0, 1.5, 3.0]
+data x = [0, 1, 2]
+data y = ys
+data from "measurements.csv"
+foo: 1
+"#;
+        let doc = parse_document(dsl.chars(), Some(Path::new("dir/plot.plotive"))).unwrap();
+        assert_eq!(doc.props.len(), 1);
+        assert_eq!(
+            doc.data,
+            vec![
+                ast::Data::Column {
+                    name: ast::Ident {
+                        name: "x".into(),
+                        span: (31, 32),
+                    },
+                    values: ast::Array {
+                        span: (35, 44),
+                        kind: ast::ArrayKind::Int(vec![0, 1, 2]),
+                    },
+                },
+                ast::Data::Column {
+                    name: ast::Ident {
+                        name: "y".into(),
+                        span: (50, 51),
+                    },
+                    values: ast::Array {
+                        span: (54, 56),
+                        kind: ast::ArrayKind::Float(vec![0.0, 1.5, 3.0]),
+                    },
+                },
+                ast::Data::Csv {
+                    span: (57, 85),
+                    path: Path::new("dir").join("measurements.csv"),
+                },
+            ]
+        );
+
+        // data declarations are only supported with parse_document
+        assert!(matches!(
+            parse(dsl.chars()),
+            Err(Error::UnexpectedData((31, 44)))
+        ));
+    }
 }
 
 #[cfg(test)]
@@ -943,4 +1660,209 @@ mod fail_tests {
         assert!(matches!(err, Error::UnexpectedEndOfInput(_)));
         assert!(err.to_string().contains("Unexpected end of input"));
     }
+
+    #[test]
+    fn test_malformed_comment() {
+        let dsl = "foo: 1\n / bar";
+        let res = parse(dsl.chars());
+        assert!(matches!(
+            res,
+            Err(Error::UnexpectedToken(
+                Token {
+                    kind: TokenKind::Slash,
+                    span: (8, 9),
+                },
+                _
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_variable() {
+        let dsl = "let width = 2\nfoo: widht * 2";
+        let res = parse(dsl.chars());
+        let Err(Error::UnknownVariable(ident)) = res else {
+            panic!("expected unknown variable error");
+        };
+        assert_eq!(ident.name, "widht");
+        assert_eq!(ident.span, (19, 24));
+    }
+
+    #[test]
+    fn test_invalid_operand() {
+        let dsl = "let name = \"foo\"\nbar: name * 2";
+        let res = parse(dsl.chars());
+        assert!(matches!(res, Err(Error::InvalidExpr((22, 26), _))));
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        let dsl = "foo: 1 / (2 - 2)";
+        let res = parse(dsl.chars());
+        let Err(err) = res else {
+            panic!("expected an error");
+        };
+        assert!(matches!(err, Error::InvalidExpr((9, 16), _)));
+        assert!(err.to_string().contains("division by zero"));
+    }
+
+    #[test]
+    fn test_mixed_array() {
+        let res = parse(r#"foo: [1, 2.5, "3"]"#.chars());
+        assert!(matches!(res, Err(Error::MixedArray((14, 17), _))));
+        let res = parse(r#"foo: ["1", 2]"#.chars());
+        assert!(matches!(res, Err(Error::MixedArray((11, 12), _))));
+    }
+
+    #[test]
+    fn test_misplaced_data() {
+        let res = parse_document("foo: {\n    data x = [1]\n}".chars(), None);
+        assert!(matches!(res, Err(Error::MisplacedData((11, 15)))));
+    }
+
+    #[test]
+    fn test_data_not_array() {
+        let res = parse_document("data x = 1, 2".chars(), None);
+        assert!(matches!(res, Err(Error::InvalidExpr((9, 13), _))));
+    }
+}
+
+#[cfg(test)]
+mod include_tests {
+    use super::*;
+
+    /// Temporary directory, removed with its content when dropped
+    struct TempDir(PathBuf);
+
+    impl std::ops::Deref for TempDir {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Write the given files in a fresh temporary directory and return it
+    fn write_files(test: &str, files: &[(&str, &str)]) -> TempDir {
+        let dir = std::env::temp_dir().join(format!("plotive-dsl-{}-{}", test, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let dir = TempDir(dir);
+        for (name, content) in files {
+            let path = dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn parse_file(path: &Path) -> Result<Vec<ast::Prop>> {
+        let content = fs::read_to_string(path).unwrap();
+        parse_with_path(content.chars(), Some(path))
+    }
+
+    #[test]
+    fn test_include() {
+        let dir = write_files(
+            "include",
+            &[
+                (
+                    "main.plotive",
+                    "@include \"common/theme.plotive\"\nfoo: {\n    bar: width * 2\n}\n",
+                ),
+                (
+                    "common/theme.plotive",
+                    "@include \"base.plotive\"\nlet width = base + 1\ntheme: Dark\n",
+                ),
+                ("common/base.plotive", "let base = 2\n"),
+            ],
+        );
+        let props = parse_file(&dir.join("main.plotive")).unwrap();
+        assert_eq!(props.len(), 2);
+        assert_eq!(props[0].name.name, "theme");
+        // spliced props are reported at the include directive
+        assert_eq!(props[0].span(), (0, 31));
+
+        let Some(ast::Value::Struct(foo)) = &props[1].value else {
+            panic!("expected a struct");
+        };
+        let Some(ast::Value::Scalar(bar)) = &foo.prop("bar").unwrap().value else {
+            panic!("expected a scalar");
+        };
+        assert_eq!(bar.kind, ast::ScalarKind::Int(6));
+    }
+
+    #[test]
+    fn test_include_cycle() {
+        let dir = write_files(
+            "include-cycle",
+            &[
+                ("a.plotive", "@include \"b.plotive\"\n"),
+                ("b.plotive", "foo: 1\n@include \"a.plotive\"\n"),
+            ],
+        );
+        let res = parse_file(&dir.join("a.plotive"));
+        let Err(Error::Include { span, err, .. }) = res else {
+            panic!("expected an error in included file");
+        };
+        assert_eq!(span, (0, 20));
+        assert!(matches!(*err, Error::IncludeCycle((7, 27), _)));
+    }
+
+    #[test]
+    fn test_include_missing() {
+        let dir = write_files(
+            "include-missing",
+            &[("a.plotive", "foo: 1\n@include \"b.plotive\"")],
+        );
+        let res = parse_file(&dir.join("a.plotive"));
+        assert!(matches!(
+            res,
+            Err(Error::IncludeFailed { span: (7, 27), .. })
+        ));
+    }
+
+    #[test]
+    fn test_include_error() {
+        let dir = write_files(
+            "include-error",
+            &[
+                ("a.plotive", "@include \"b.plotive\"\n"),
+                ("b.plotive", "foo: 1\nbar: baz\n"),
+            ],
+        );
+        let res = parse_file(&dir.join("a.plotive"));
+        let Err(Error::Include {
+            span, path, err, ..
+        }) = res
+        else {
+            panic!("expected an error in included file");
+        };
+        assert_eq!(span, (0, 20));
+        assert_eq!(path, dir.join("b.plotive"));
+        let Error::UnknownVariable(ident) = *err else {
+            panic!("expected unknown variable");
+        };
+        assert_eq!(ident.span, (12, 15));
+    }
+
+    #[test]
+    fn test_unknown_directive() {
+        let res = parse("@import \"b.plotive\"".chars());
+        assert!(matches!(
+            res,
+            Err(Error::UnexpectedToken(
+                Token {
+                    kind: TokenKind::Directive(_),
+                    ..
+                },
+                _
+            ))
+        ));
+    }
 }
@@ -0,0 +1,53 @@
+use plotive::{data, des};
+
+mod common;
+
+fn main() {
+    let months: Vec<f64> = (0..24).map(|m| m as f64).collect();
+    let wave = |phase: f64, amp: f64| -> Vec<f64> {
+        months
+            .iter()
+            .map(|m| amp * (1.2 + (m / 4.0 + phase).sin()))
+            .collect()
+    };
+
+    let months = data::VecColumn::from(months.clone());
+    let rock = data::VecColumn::from(wave(0.0, 10.0));
+    let jazz = data::VecColumn::from(wave(1.5, 6.0));
+    let pop = data::VecColumn::from(wave(3.0, 14.0));
+    let folk = data::VecColumn::from(wave(4.5, 4.0));
+
+    let mut source = data::NamedColumns::new();
+    source.add_column("months", &months);
+    source.add_column("rock", &rock);
+    source.add_column("jazz", &jazz);
+    source.add_column("pop", &pop);
+    source.add_column("folk", &folk);
+
+    let series = ["rock", "jazz", "pop", "folk"]
+        .into_iter()
+        .map(|genre| {
+            des::series::AreaSeries::new(des::data_src_ref("months"), des::data_src_ref(genre))
+                .with_name(genre)
+        })
+        .collect();
+
+    let areas =
+        des::series::AreasGroup::new(series).with_baseline(des::series::AreasBaseline::Wiggle);
+
+    let x_axis = des::Axis::new()
+        .with_title("Month".into())
+        .with_ticks(Default::default());
+    let y_axis = des::Axis::new()
+        .with_title("Listeners".into())
+        .with_ticks(Default::default());
+
+    let plot = des::Plot::new(vec![areas.into()])
+        .with_x_axis(x_axis)
+        .with_y_axis(y_axis)
+        .with_legend(Default::default());
+
+    let fig = des::Figure::new(plot.into()).with_title("Streamgraph".into());
+
+    common::save_figure(&fig, &source, None, "areas");
+}
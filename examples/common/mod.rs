@@ -130,6 +130,7 @@ fn save_fig<D>(
                     style: args.style.as_ref().cloned().unwrap_or_default(),
                     scale: 2.0,
                     fontdb: Some(fontdb),
+                    ..Default::default()
                 },
             )
             .unwrap();
@@ -150,6 +151,7 @@ fn save_fig<D>(
                     style: args.style.as_ref().cloned().unwrap_or_default(),
                     scale: 1.0,
                     fontdb: Some(fontdb),
+                    ..Default::default()
                 },
             )
             .unwrap();
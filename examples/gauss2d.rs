@@ -0,0 +1,47 @@
+use plotive::{data, des};
+use rand_distr::{Distribution, Normal};
+
+mod common;
+
+fn main() {
+    const N_POP: usize = 20000;
+
+    let mut rng = common::predictable_rng(None);
+    let x_normal = Normal::new(0.0, 1.0).unwrap();
+    let y_normal = Normal::new(0.0, 0.5).unwrap();
+
+    let mut x = Vec::with_capacity(N_POP);
+    let mut y = Vec::with_capacity(N_POP);
+    for _ in 0..N_POP {
+        let xs: f64 = x_normal.sample(&mut rng);
+        let ys: f64 = y_normal.sample(&mut rng);
+        // correlate y with x
+        x.push(xs);
+        y.push(0.6 * xs + ys);
+    }
+
+    let x_axis = des::Axis::new()
+        .with_title("x".into())
+        .with_ticks(Default::default());
+    let y_axis = des::Axis::new()
+        .with_title("y".into())
+        .with_ticks(Default::default());
+
+    let series = des::Series::Hist2d(
+        des::series::Hist2d::new(des::data_src_ref("x"), des::data_src_ref("y"))
+            .with_bins(40, 30)
+            .with_norm(des::series::Hist2dNorm::Log),
+    );
+
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(x_axis)
+        .with_y_axis(y_axis);
+
+    let fig = des::Figure::new(plot.into()).with_title("Bivariate normal distribution".into());
+
+    let data_source = data::TableSource::new()
+        .with_f64_column("x".into(), x)
+        .with_f64_column("y".into(), y);
+
+    common::save_figure(&fig, &data_source, None, "gauss2d");
+}
@@ -0,0 +1,59 @@
+use std::time::Instant;
+
+use plotive::{Prepare, data, des, drawing, geom};
+
+/// Compares the preparation time of a 4x4 grid of subplots,
+/// serially and with the `parallel` feature.
+fn main() {
+    const ROWS: u32 = 4;
+    const COLS: u32 = 4;
+    const POINTS: usize = 200_000;
+    const RUNS: u32 = 5;
+
+    let x: Vec<f64> = (0..POINTS).map(|i| i as f64 / 1000.0).collect();
+    let mut data_source = data::TableSource::new().with_f64_column("x", x.clone());
+    let mut subplots = des::Subplots::new(ROWS, COLS).with_space(10.0);
+
+    for i in 0..ROWS * COLS {
+        let name = format!("y{i}");
+        let freq = (i + 1) as f64;
+        let y = x
+            .iter()
+            .map(|x| (x * freq).sin() * (-x / 100.0).exp())
+            .collect();
+        data_source = data_source.with_f64_column(&name, y);
+
+        let series = des::series::Line::new(des::data_src_ref("x"), des::data_src_ref(&name))
+            .with_name(format!("f = {freq}"))
+            .into();
+        let plot = des::Plot::new(vec![series])
+            .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+            .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+        subplots = subplots.with_plot((i / COLS, i % COLS), plot);
+    }
+
+    let fig = des::Figure::new(subplots.into()).with_size(geom::Size::new(1600.0, 1200.0));
+    let fontdb = plotive::bundled_font_db();
+    let layout = drawing::LayoutParams::default();
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        fig.prepare_with_layout(&data_source, Some(&fontdb), &layout)
+            .unwrap();
+    }
+    let serial = start.elapsed() / RUNS;
+
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        drawing::prepare_parallel(&fig, &data_source, Some(&fontdb), &layout).unwrap();
+    }
+    let parallel = start.elapsed() / RUNS;
+
+    println!("serial:   {serial:?}");
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    println!("parallel: {parallel:?} ({threads} threads)");
+    println!(
+        "speedup:  {:.2}x",
+        serial.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
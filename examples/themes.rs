@@ -0,0 +1,60 @@
+// The figure is prepared once, and drawn in light and dark themes.
+use std::f64::consts::PI;
+
+use plotive::{Prepare, Style, data, des};
+use plotive_pxl::SavePng;
+
+fn main() {
+    let sine = des::series::Line::new(des::data_src_ref("x"), des::data_src_ref("sin"))
+        .with_name("sin(x)")
+        .into();
+    let cosine = des::series::Line::new(des::data_src_ref("x"), des::data_src_ref("cos"))
+        .with_name("cos(x)")
+        .into();
+
+    let fig = des::Plot::new(vec![sine, cosine])
+        .with_x_axis(
+            des::Axis::new()
+                .with_title("x".into())
+                .with_ticks(
+                    des::axis::Ticks::new()
+                        .with_locator(des::axis::ticks::PiMultipleLocator::default().into()),
+                )
+                .with_grid(Default::default()),
+        )
+        .with_y_axis(
+            des::Axis::new()
+                .with_title("y".into())
+                .with_ticks(Default::default())
+                .with_grid(Default::default()),
+        )
+        .with_legend(des::plot::LegendPos::InTopRight.into())
+        .into_figure()
+        .with_title("Themes".into());
+
+    let x: Vec<f64> = (0..=360).map(|t| t as f64 * PI / 180.0).collect();
+    let sin = x.iter().map(|x| x.sin()).collect();
+    let cos = x.iter().map(|x| x.cos()).collect();
+
+    let data_source = data::TableSource::new()
+        .with_f64_column("x", x)
+        .with_f64_column("sin", sin)
+        .with_f64_column("cos", cos);
+
+    // the layout, texts and series paths are computed only once
+    let fig = fig.prepare(&data_source, None).unwrap();
+
+    // the colors are resolved at draw time, for each style
+    for (name, style) in [("light", Style::light()), ("dark", Style::dark())] {
+        fig.save_png(
+            format!("themes-{name}.png"),
+            &(),
+            plotive_pxl::Params {
+                style,
+                scale: 2.0,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    }
+}
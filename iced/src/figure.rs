@@ -1,4 +1,4 @@
-use iced::advanced::graphics::geometry;
+use iced::advanced::graphics::geometry::{self, frame};
 use iced::advanced::widget::tree;
 use iced::advanced::{Layout, Widget, layout, mouse, renderer, widget};
 use iced::{Element, Length, Rectangle, Size};
@@ -6,7 +6,7 @@ use plotive::render::Surface;
 use plotive::style::theme;
 use plotive::{drawing, geom, style};
 
-use crate::surface;
+use crate::{ToIced, surface};
 
 pub fn figure<'a, Message, Theme>(fig: &'a drawing::PreparedFigure) -> Figure<'a, Message, Theme>
 where
@@ -30,6 +30,76 @@ where
     on_mouse_wheel: Option<Box<dyn Fn(geom::Point, f32) -> Message + 'a>>,
     on_scale_change: Option<Box<dyn Fn(f32) -> Message + 'a>>,
     zoom_rect: Option<(geom::Point, geom::Point)>,
+    tooltip: Option<(geom::Point, String)>,
+    crosshair: Option<Crosshair>,
+    viewport: Option<geom::Rect>,
+}
+
+/// A crosshair drawn over a [`Figure`], with lines spanning its plot area
+#[derive(Debug, Clone)]
+pub struct Crosshair {
+    /// Position of the crosshair center, in figure coordinates
+    pub pos: geom::Point,
+    /// Area of the plot, in figure coordinates
+    pub plot_rect: geom::Rect,
+    /// Stroke of the crosshair lines
+    pub stroke: theme::Stroke,
+    /// Label of the X coordinate, shown below the plot area
+    pub x_label: String,
+    /// Label of the Y coordinate, shown left of the plot area
+    pub y_label: String,
+}
+
+impl Crosshair {
+    /// Crosshair centered on `pos`, spanning the area of the plot given by `hit`,
+    /// with the hit coordinates as labels
+    pub(crate) fn from_hit(
+        fig: &drawing::PreparedFigure,
+        pos: geom::Point,
+        hit: &drawing::PlotHit,
+        stroke: theme::Stroke,
+    ) -> Option<Self> {
+        let view = fig.plot_view(hit.idx)?;
+        Some(Crosshair {
+            pos,
+            plot_rect: view.rect(),
+            stroke,
+            x_label: hit.x_coords.to_string(),
+            y_label: hit.y_coords.to_string(),
+        })
+    }
+
+    /// Vertical and horizontal lines through the crosshair center, spanning the plot area
+    fn lines(&self) -> geom::Path {
+        let rect = &self.plot_rect;
+        let mut pb = geom::PathBuilder::new();
+        pb.move_to(self.pos.x, rect.top());
+        pb.line_to(self.pos.x, rect.bottom());
+        pb.move_to(rect.left(), self.pos.y);
+        pb.line_to(rect.right(), self.pos.y);
+        pb.finish().expect("Should be a valid path")
+    }
+
+    /// Top-left corners of the X and Y label boxes, in screen coordinates.
+    /// The X label is centered below the plot area, and the Y label left of it.
+    fn label_positions(
+        &self,
+        transform: &geom::Transform,
+        x_size: Size,
+        y_size: Size,
+    ) -> (iced::Point, iced::Point) {
+        let mut pos = self.pos;
+        transform.map_point(&mut pos);
+        let mut bottom_left = geom::Point {
+            x: self.plot_rect.left(),
+            y: self.plot_rect.bottom(),
+        };
+        transform.map_point(&mut bottom_left);
+
+        let x_pos = iced::Point::new(pos.x - x_size.width / 2.0, bottom_left.y);
+        let y_pos = iced::Point::new(bottom_left.x - y_size.width, pos.y - y_size.height / 2.0);
+        (x_pos, y_pos)
+    }
 }
 
 impl<'a, Theme> std::fmt::Debug for Figure<'a, Theme>
@@ -63,6 +133,9 @@ where
             on_mouse_wheel: None,
             on_scale_change: None,
             zoom_rect: None,
+            tooltip: None,
+            crosshair: None,
+            viewport: None,
         }
     }
 
@@ -141,6 +214,38 @@ where
         self.zoom_rect = Some((start, end));
         self
     }
+
+    /// Sets a tooltip of the [`Figure`], pointing at `pos` in figure coordinates.
+    #[must_use]
+    pub fn tooltip(mut self, pos: geom::Point, text: impl Into<String>) -> Self {
+        self.tooltip = Some((pos, text.into()));
+        self
+    }
+
+    /// Sets a crosshair of the [`Figure`].
+    #[must_use]
+    pub fn crosshair(mut self, crosshair: Crosshair) -> Self {
+        self.crosshair = Some(crosshair);
+        self
+    }
+
+    /// Sets the area of the [`Figure`] to display, in figure coordinates.
+    /// The area is magnified to fit the bounds of the widget, and the rest of the figure is clipped.
+    /// By default, the whole figure is displayed.
+    #[must_use]
+    pub fn viewport(mut self, area: geom::Rect) -> Self {
+        self.viewport = Some(area);
+        self
+    }
+
+    /// The transform from figure coordinates to the given widget bounds
+    fn transform(&self, bounds: Rectangle) -> geom::Transform {
+        match self.viewport {
+            Some(area) => fit_transform_to_bounds(area.size(), bounds)
+                .pre_concat(geom::Transform::from_translate(-area.x(), -area.y())),
+            None => fit_transform_to_bounds(self.fig.size(), bounds),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Default)]
@@ -152,23 +257,17 @@ struct State {
 
 fn fit_transform_to_bounds(size: geom::Size, bounds: Rectangle) -> geom::Transform {
     // scale up or down to fit the size into bounds, preserving aspect ratio and centering
-    let tx = bounds.x;
-    let ty = bounds.y;
-    let sx = bounds.width / size.width();
-    let sy = bounds.height / size.height();
-    let s = sx.min(sy);
-    let w = size.width() * s;
-    let h = size.height() * s;
-    let tx = tx + (bounds.width - w) / 2.0;
-    let ty = ty + (bounds.height - h) / 2.0;
+    let fit = size.fit_within(geom::Size::new(bounds.width, bounds.height));
+    let s = fit.width() / size.width();
+    let tx = bounds.x + (bounds.width - fit.width()) / 2.0;
+    let ty = bounds.y + (bounds.height - fit.height()) / 2.0;
     geom::Transform::from_translate(tx, ty).pre_concat(geom::Transform::from_scale(s, s))
 }
 
 fn fit_scale_to_bounds(size: geom::Size, bounds: Rectangle) -> f32 {
-    // scale up or down to fit the size into bounds, preserving aspect ratio and centering
-    let sx = bounds.width / size.width();
-    let sy = bounds.height / size.height();
-    sx.min(sy)
+    // scale up or down to fit the size into bounds, preserving aspect ratio
+    let fit = size.fit_within(geom::Size::new(bounds.width, bounds.height));
+    fit.width() / size.width()
 }
 
 impl<'a, Message, Theme, Renderer> Widget<Message, Theme, Renderer> for Figure<'a, Message, Theme>
@@ -246,7 +345,8 @@ where
             }
             iced::Event::Mouse(mouse_ev) => match mouse_ev {
                 mouse::Event::CursorMoved { position } => {
-                    let transform = fit_transform_to_bounds(self.fig.size(), layout.bounds())
+                    let transform = self
+                        .transform(layout.bounds())
                         .invert()
                         .expect("transform without skew should be invertible");
                     let mut point = geom::Point {
@@ -313,32 +413,166 @@ where
         let style = theme.style(&self.class);
 
         let bounds = layout.bounds();
-        let transform = fit_transform_to_bounds(self.fig.size(), bounds);
+        let transform = self.transform(bounds);
 
         let frame = renderer.new_frame(bounds);
         let mut surface = surface::IcedSurface::new(frame, bounds, transform);
 
-        self.fig.draw(&mut surface, &style);
+        // the iced surface never fails, so an error is a bug of the figure drawing
+        let res = self.fig.draw(&mut surface, &style);
+        debug_assert!(res.is_ok(), "Failed to draw the figure: {res:?}");
 
         if let Some((start, end)) = self.zoom_rect {
             let rect = geom::Rect::from_corners(start, end);
             let stroke = theme::Stroke::from(theme::Col::Foreground)
                 .with_pattern(style::Dash::default().into());
             let stroke = stroke.as_stroke(&style);
-            let _ = surface.draw_rect(&plotive::render::Rect {
+            let res = surface.draw_rect(&plotive::render::Rect {
                 rect,
                 stroke: Some(stroke),
                 fill: None,
                 transform: None,
             });
+            debug_assert!(res.is_ok(), "Failed to draw the zoom rectangle: {res:?}");
+        }
+
+        if let Some(crosshair) = &self.crosshair {
+            let path = crosshair.lines();
+            let res = surface.draw_path(&plotive::render::Path {
+                path: &path,
+                fill: None,
+                stroke: Some(crosshair.stroke.as_stroke(&style)),
+                transform: None,
+            });
+            debug_assert!(res.is_ok(), "Failed to draw the crosshair: {res:?}");
         }
 
-        for g in surface.into_geometries() {
-            renderer.draw_geometry(g);
+        let geometries = surface.into_geometries();
+        if self.viewport.is_some() {
+            // the magnified figure overflows the widget bounds
+            renderer.with_layer(bounds, |renderer| {
+                for g in geometries {
+                    renderer.draw_geometry(g);
+                }
+            });
+        } else {
+            for g in geometries {
+                renderer.draw_geometry(g);
+            }
+        }
+
+        if self.tooltip.is_some() || self.crosshair.is_some() {
+            let mut overlay = renderer.new_frame(bounds);
+            if let Some(crosshair) = &self.crosshair {
+                draw_crosshair_labels(&mut overlay, &style, &transform, crosshair);
+            }
+            if let Some((pos, text)) = &self.tooltip {
+                draw_tooltip(&mut overlay, &style, &transform, bounds, *pos, text);
+            }
+            renderer.draw_geometry(frame::Backend::into_geometry(overlay));
         }
     }
 }
 
+const OVERLAY_TEXT_SZ: f32 = 12.0;
+const OVERLAY_PADDING: f32 = 4.0;
+const TOOLTIP_OFFSET: f32 = 10.0;
+const TOOLTIP_MARKER_RADIUS: f32 = 4.0;
+
+/// Estimated size of a text box drawn with [`draw_text_box`].
+/// The text is laid out by iced at draw time, so its size is estimated here.
+fn text_box_size(text: &str) -> Size {
+    let lines = text.lines().count().max(1);
+    let chars = text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+    Size {
+        width: chars as f32 * OVERLAY_TEXT_SZ * 0.6 + 2.0 * OVERLAY_PADDING,
+        height: lines as f32 * OVERLAY_TEXT_SZ * 1.3 + 2.0 * OVERLAY_PADDING,
+    }
+}
+
+/// Draw a text in a box filled and stroked with the legend colors of the theme
+fn draw_text_box<Frame>(
+    frame: &mut Frame,
+    style: &plotive::Style,
+    top_left: iced::Point,
+    size: Size,
+    text: &str,
+) where
+    Frame: frame::Backend,
+{
+    let fg = style.theme().foreground().to_iced();
+    let bg = style.theme().legend_fill().to_iced();
+    let border = style.theme().legend_border().to_iced();
+
+    frame.fill_rectangle(top_left, size, bg);
+    frame.stroke_rectangle(
+        top_left,
+        size,
+        geometry::Stroke::default()
+            .with_color(border)
+            .with_width(1.0),
+    );
+    frame.fill_text(geometry::Text {
+        content: text.to_string(),
+        position: iced::Point::new(top_left.x + OVERLAY_PADDING, top_left.y + OVERLAY_PADDING),
+        color: fg,
+        size: OVERLAY_TEXT_SZ.into(),
+        ..Default::default()
+    });
+}
+
+/// Draw a tooltip box next to `pos` (in figure coordinates), with a marker on `pos`.
+/// The box is placed above right of `pos`, and flipped to remain within `bounds`.
+fn draw_tooltip<Frame>(
+    frame: &mut Frame,
+    style: &plotive::Style,
+    transform: &geom::Transform,
+    bounds: Rectangle,
+    pos: geom::Point,
+    text: &str,
+) where
+    Frame: frame::Backend,
+{
+    let fg = style.theme().foreground().to_iced();
+
+    let mut anchor = pos;
+    transform.map_point(&mut anchor);
+    let anchor = iced::Point::new(anchor.x, anchor.y);
+
+    let marker = geometry::Path::circle(anchor, TOOLTIP_MARKER_RADIUS);
+    frame.stroke(
+        &marker,
+        geometry::Stroke::default().with_color(fg).with_width(1.5),
+    );
+
+    let size = text_box_size(text);
+    let mut x = anchor.x + TOOLTIP_OFFSET;
+    if x + size.width > bounds.x + bounds.width {
+        x = anchor.x - TOOLTIP_OFFSET - size.width;
+    }
+    let mut y = anchor.y - TOOLTIP_OFFSET - size.height;
+    if y < bounds.y {
+        y = anchor.y + TOOLTIP_OFFSET;
+    }
+    draw_text_box(frame, style, iced::Point::new(x, y), size, text);
+}
+
+/// Draw the coordinates labels of a crosshair, in the margins of its plot area
+fn draw_crosshair_labels<Frame>(
+    frame: &mut Frame,
+    style: &plotive::Style,
+    transform: &geom::Transform,
+    crosshair: &Crosshair,
+) where
+    Frame: frame::Backend,
+{
+    let x_size = text_box_size(&crosshair.x_label);
+    let y_size = text_box_size(&crosshair.y_label);
+    let (x_pos, y_pos) = crosshair.label_positions(transform, x_size, y_size);
+    draw_text_box(frame, style, x_pos, x_size, &crosshair.x_label);
+    draw_text_box(frame, style, y_pos, y_size, &crosshair.y_label);
+}
+
 /// The theme catalog of a [`Figure`].
 pub trait Catalog: Sized {
     /// The item class of this [`Catalog`].
@@ -412,3 +646,76 @@ where
         Element::new(figure)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plotive::{Prepare, des};
+
+    use super::*;
+
+    fn crosshair() -> Crosshair {
+        Crosshair {
+            pos: geom::Point { x: 150.0, y: 80.0 },
+            plot_rect: geom::Rect::from_xywh(50.0, 20.0, 300.0, 200.0),
+            stroke: theme::Stroke::from(theme::Col::Foreground),
+            x_label: "1.5".to_string(),
+            y_label: "0.25".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_crosshair_from_hit() {
+        let line = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        );
+        let fig = des::Figure::new(des::Plot::new(vec![line.into()]).into())
+            .with_size(geom::Size::new(400.0, 300.0))
+            .prepare(&(), None)
+            .unwrap();
+        let stroke = theme::Stroke::from(theme::Col::Foreground);
+
+        let pos = geom::Point { x: 200.0, y: 150.0 };
+        let hit = fig.hit_test(pos).unwrap();
+        let crosshair = Crosshair::from_hit(&fig, pos, &hit, stroke).unwrap();
+        assert_eq!(crosshair.pos, pos);
+        assert_eq!(crosshair.plot_rect, fig.plot_view(hit.idx).unwrap().rect());
+        assert!(crosshair.plot_rect.contains_point(&pos));
+        assert_eq!(crosshair.x_label, hit.x_coords.to_string());
+        assert_eq!(crosshair.y_label, hit.y_coords.to_string());
+        assert!(!crosshair.x_label.is_empty());
+    }
+
+    #[test]
+    fn test_crosshair_lines() {
+        let crosshair = crosshair();
+        let bounds = crosshair.lines().bounds();
+        assert_eq!(bounds.left(), 50.0);
+        assert_eq!(bounds.right(), 350.0);
+        assert_eq!(bounds.top(), 20.0);
+        assert_eq!(bounds.bottom(), 220.0);
+
+        let points = crosshair.lines().points().to_vec();
+        assert_eq!(points.len(), 4);
+        assert!(points[..2].iter().all(|p| p.x == 150.0));
+        assert!(points[2..].iter().all(|p| p.y == 80.0));
+    }
+
+    #[test]
+    fn test_crosshair_label_positions() {
+        let crosshair = crosshair();
+        let x_size = Size::new(40.0, 20.0);
+        let y_size = Size::new(30.0, 20.0);
+
+        let transform = geom::Transform::identity();
+        let (x_pos, y_pos) = crosshair.label_positions(&transform, x_size, y_size);
+        assert_eq!(x_pos, iced::Point::new(130.0, 220.0));
+        assert_eq!(y_pos, iced::Point::new(20.0, 70.0));
+
+        // labels are placed in screen coordinates, and keep their size
+        let transform = geom::Transform::from_row(2.0, 0.0, 0.0, 2.0, 10.0, 5.0);
+        let (x_pos, y_pos) = crosshair.label_positions(&transform, x_size, y_size);
+        assert_eq!(x_pos, iced::Point::new(290.0, 445.0));
+        assert_eq!(y_pos, iced::Point::new(80.0, 155.0));
+    }
+}
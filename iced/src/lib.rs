@@ -2,7 +2,7 @@ pub mod figure;
 pub mod show;
 mod surface;
 
-pub use figure::{Figure, figure};
+pub use figure::{Crosshair, Figure, figure};
 pub use show::Show;
 
 pub trait ToIced {
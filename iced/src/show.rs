@@ -2,14 +2,16 @@
 
 use core::fmt;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use iced::widget::{button, column, mouse_area, row, space, text};
 use iced::{Alignment, Length, mouse};
 use iced_font_awesome::{fa_icon, fa_icon_solid};
 use plotive::drawing::zoom;
-use plotive::{Prepare, data, des, drawing, fontdb, geom};
+use plotive::style::theme;
+use plotive::{Prepare, data, des, drawing, fontdb, geom, style};
 
-use crate::figure::figure;
+use crate::figure::{Crosshair, figure};
 
 #[derive(Clone, Copy)]
 pub struct Commands(u16);
@@ -128,11 +130,50 @@ impl fmt::Debug for Commands {
     }
 }
 
+/// Formatter of the hover tooltip text, given the data point nearest to the cursor
+pub type TooltipFormatter = fn(&drawing::SeriesHit) -> String;
+
+/// Default tooltip formatter, showing the X and Y values on separate lines
+pub fn default_tooltip_formatter(hit: &drawing::SeriesHit) -> String {
+    format!("X = {}\nY = {}", hit.x.1, hit.y.1)
+}
+
+/// File format of the figure export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// PNG image
+    #[default]
+    Png,
+    /// SVG image
+    Svg,
+}
+
+/// Default stroke of the crosshair lines: a thin dashed line with the foreground color
+pub fn default_crosshair_stroke() -> theme::Stroke {
+    theme::Stroke::from(theme::Col::Foreground)
+        .with_width(0.5)
+        .with_pattern(style::Dash::default().into())
+}
+
 #[derive(Debug, Clone)]
 pub struct Params {
     pub style: Option<plotive::Style>,
     pub fontdb: Option<Arc<fontdb::Database>>,
     pub commands: Commands,
+    /// Show a tooltip with the values of the data point nearest to the cursor
+    pub tooltips: bool,
+    /// Formatter of the tooltip text. [`default_tooltip_formatter`] is used if `None`.
+    pub tooltip_formatter: Option<TooltipFormatter>,
+    /// Show a crosshair following the cursor over the plots
+    pub crosshair: bool,
+    /// Stroke of the crosshair lines. [`default_crosshair_stroke`] is used if `None`.
+    pub crosshair_stroke: Option<theme::Stroke>,
+    /// Format of the export with the `Ctrl+S` shortcut
+    pub export_format: ExportFormat,
+    /// Size of the exported figures, in pixels for PNG and in SVG user units.
+    /// The figure is scaled to fit within this size, preserving its aspect ratio.
+    /// If `None`, the figure is exported at its current scale in the window.
+    pub export_size: Option<geom::Size>,
 }
 
 impl Default for Params {
@@ -141,6 +182,12 @@ impl Default for Params {
             style: None,
             fontdb: None,
             commands: Commands::all(),
+            tooltips: false,
+            tooltip_formatter: None,
+            crosshair: false,
+            crosshair_stroke: None,
+            export_format: ExportFormat::default(),
+            export_size: None,
         }
     }
 }
@@ -152,6 +199,17 @@ pub trait Show {
     fn show<D>(self, data_source: Arc<D>, params: Params) -> iced::Result
     where
         D: data::Source + ?Sized + 'static;
+
+    /// Show the figure in a GUI window, reading the data once from a borrowed data source.
+    /// This function will block the calling thread until the window is closed.
+    ///
+    /// The window keeps no reference to the data source, so the figure is shown with [`FigureData::Detached`]:
+    /// zoom and pan magnify and move the figure as prepared, without re-ranging the axes,
+    /// and the data can't be exported to CSV.
+    /// Use [`show`](Self::show) with a shared data source for the full interactions.
+    fn show_borrowed<D>(self, data_source: &D, params: Params) -> iced::Result
+    where
+        D: data::Source + ?Sized;
 }
 
 impl Show for des::Figure {
@@ -161,12 +219,28 @@ impl Show for des::Figure {
     {
         let fontdb = params
             .fontdb
+            .clone()
             .unwrap_or_else(|| Arc::new(plotive::bundled_font_db()));
         let fig = self
             .prepare(&*data_source, Some(&*fontdb))
             .expect("Failed to prepare figure");
 
-        show_app(fig, data_source, fontdb, params.style)
+        show_app(fig, FigureData::Shared(data_source), fontdb, params)
+    }
+
+    fn show_borrowed<D>(self, data_source: &D, params: Params) -> iced::Result
+    where
+        D: data::Source + ?Sized,
+    {
+        let fontdb = params
+            .fontdb
+            .clone()
+            .unwrap_or_else(|| Arc::new(plotive::bundled_font_db()));
+        let fig = self
+            .prepare(data_source, Some(&*fontdb))
+            .expect("Failed to prepare figure");
+
+        show_app::<()>(fig, FigureData::Detached, fontdb, params)
     }
 }
 
@@ -177,30 +251,57 @@ impl Show for drawing::PreparedFigure {
     {
         let fontdb = params
             .fontdb
+            .clone()
+            .unwrap_or_else(|| Arc::new(plotive::bundled_font_db()));
+
+        show_app(self, FigureData::Shared(data_source), fontdb, params)
+    }
+
+    fn show_borrowed<D>(mut self, data_source: &D, params: Params) -> iced::Result
+    where
+        D: data::Source + ?Sized,
+    {
+        let fontdb = params
+            .fontdb
+            .clone()
             .unwrap_or_else(|| Arc::new(plotive::bundled_font_db()));
+        self.update_series_data(data_source)
+            .expect("Failed to update figure data");
 
-        show_app(self, data_source, fontdb, params.style)
+        show_app::<()>(self, FigureData::Detached, fontdb, params)
     }
 }
 
 fn show_app<D>(
     fig: drawing::PreparedFigure,
-    data_source: Arc<D>,
+    data: FigureData<D>,
     fontdb: Arc<fontdb::Database>,
-    style: Option<plotive::Style>,
+    params: Params,
 ) -> iced::Result
 where
     D: data::Source + ?Sized + 'static,
 {
+    let tooltips = params.tooltips.then(|| {
+        params
+            .tooltip_formatter
+            .unwrap_or(default_tooltip_formatter)
+    });
+    let crosshair = params.crosshair.then(|| {
+        params
+            .crosshair_stroke
+            .clone()
+            .unwrap_or_else(default_crosshair_stroke)
+    });
     iced::application(
         move || {
             let fig = fig.clone();
-            let data_source = data_source.clone();
             let fontdb = fontdb.clone();
-            let style = style.clone();
             let mut show = FigureShow::new(fontdb, Commands::all(), None);
-            show.set_figure(fig, data_source.clone());
-            show.set_style(style.clone());
+            show.set_figure_data(fig, data.clone());
+            show.set_style(params.style.clone());
+            show.set_tooltips(tooltips);
+            show.set_crosshair(crosshair.clone());
+            show.set_export(params.export_format, params.export_size);
             (show, iced::Task::none())
         },
         FigureShow::update,
@@ -211,6 +312,54 @@ where
     .run()
 }
 
+/// Maximum distance from the cursor to a data point to show its tooltip, in screen pixels
+const TOOLTIP_HIT_DIST: f32 = 12.0;
+/// Minimum width and height of a zoom rectangle, in screen pixels
+const ZOOM_MIN_SIZE: f32 = 4.0;
+/// Maximum delay between two clicks of a double-click
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
+/// Maximum distance between two clicks of a double-click, in screen pixels
+const DOUBLE_CLICK_DIST: f32 = 4.0;
+
+/// Whether a left press at `point` and time `now` completes a double-click with the `last` press.
+/// `max_dist` is in figure units.
+fn is_double_click(
+    last: Option<(Instant, geom::Point)>,
+    now: Instant,
+    point: geom::Point,
+    max_dist: f32,
+) -> bool {
+    last.is_some_and(|(time, pos)| {
+        now.duration_since(time) <= DOUBLE_CLICK_DELAY && pos.distance(point) <= max_dist
+    })
+}
+
+/// Rectangle of a box zoom dragged from `start` to `end`, or `None` if the drag is
+/// narrower than `min_size` in either direction, which is treated as a simple click.
+fn drag_zoom_rect(start: geom::Point, end: geom::Point, min_size: f32) -> Option<geom::Rect> {
+    let rect = geom::Rect::from_corners(start, end);
+    (rect.width() >= min_size && rect.height() >= min_size).then_some(rect)
+}
+
+/// Message of the export triggered by the `Ctrl+S` shortcut,
+/// or `None` if the export in `format` is not among the `commands`
+fn export_shortcut(format: ExportFormat, commands: Commands) -> Option<Message> {
+    match format {
+        ExportFormat::Png if commands.has_export_png() => Some(Message::ExportPng),
+        ExportFormat::Svg if commands.has_export_svg() => Some(Message::ExportSvg),
+        _ => None,
+    }
+}
+
+/// Scale of an exported figure, such that it fits within `size`,
+/// or the `current` scale of the figure in the window if `size` is `None`
+fn export_scale(fig: &drawing::PreparedFigure, size: Option<geom::Size>, current: f32) -> f32 {
+    size.map_or(current, |size| {
+        let fig_size = fig.size();
+        (size.width() / fig_size.width()).min(size.height() / fig_size.height())
+    })
+}
+
 /// Message type for the [`FigureShow`] controller.
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -245,6 +394,8 @@ enum Interaction {
         idx: des::PlotIdx,
         start: geom::Point,
         end: geom::Point,
+        /// Whether the zoom mode was enabled from the toolbar before dragging
+        enabled: bool,
     },
     PanEnabled,
     PanDragging {
@@ -253,11 +404,122 @@ enum Interaction {
     },
 }
 
+/// Access of a [`FigureShow`] to the data of its figure,
+/// which determines how the view interactions act on the figure.
+pub enum FigureData<D: ?Sized> {
+    /// The data source is shared with the view.
+    /// Zoom and pan re-range the axes of the plots and fetch the data again from the source,
+    /// and the data can be exported to CSV.
+    Shared(Arc<D>),
+    /// The figure is already prepared and the view has no access to the data.
+    /// Zoom and pan magnify and move the figure as it is prepared, without re-ranging the axes.
+    /// Tooltips, crosshair and image exports remain available, but not the CSV export.
+    Detached,
+}
+
+impl<D: ?Sized> Clone for FigureData<D> {
+    fn clone(&self) -> Self {
+        match self {
+            FigureData::Shared(data_source) => FigureData::Shared(data_source.clone()),
+            FigureData::Detached => FigureData::Detached,
+        }
+    }
+}
+
+impl<D: ?Sized> fmt::Debug for FigureData<D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FigureData::Shared(_) => f.write_str("Shared"),
+            FigureData::Detached => f.write_str("Detached"),
+        }
+    }
+}
+
 /// struct gathering data that is optional in FigureShow
 struct Fig<D: data::Source + ?Sized + 'static> {
     fig: drawing::PreparedFigure,
     home_view: zoom::FigureView,
-    data_source: Arc<D>,
+    data: FigureData<D>,
+    /// Magnified area of a detached figure, in figure coordinates
+    viewport: Option<geom::Rect>,
+}
+
+impl<D> Fig<D>
+where
+    D: data::Source + ?Sized + 'static,
+{
+    /// Area of the figure that is displayed, in figure coordinates
+    fn area(&self) -> geom::Rect {
+        self.viewport
+            .unwrap_or_else(|| geom::Rect::from_ps(geom::Point { x: 0.0, y: 0.0 }, self.fig.size()))
+    }
+
+    fn go_home(&mut self, fontdb: &fontdb::Database) {
+        match &self.data {
+            FigureData::Shared(data_source) => self
+                .fig
+                .apply_view(&self.home_view, &**data_source, Some(fontdb))
+                .expect("Failed to apply home view"),
+            FigureData::Detached => self.viewport = None,
+        }
+    }
+
+    /// Zoom the given plot to `rect`, in figure coordinates
+    fn zoom(&mut self, idx: des::PlotIdx, rect: geom::Rect, fontdb: &fontdb::Database) {
+        match &self.data {
+            FigureData::Shared(data_source) => {
+                let zoom = zoom::Zoom::new(rect);
+                self.fig
+                    .apply_zoom(idx, &zoom, &**data_source, Some(fontdb))
+                    .expect("Failed to apply zoom");
+            }
+            FigureData::Detached => self.viewport = Some(rect),
+        }
+    }
+
+    /// Zoom the given plot by `scale_factor` around `center`, in figure coordinates
+    fn zoom_about(
+        &mut self,
+        idx: des::PlotIdx,
+        center: geom::Point,
+        scale_factor: f32,
+        fontdb: &fontdb::Database,
+    ) {
+        match &self.data {
+            FigureData::Shared(_) => {
+                let view = self.fig.plot_view(idx).expect("Plot index invalid");
+                let rect = view.rect().scale_about(center, scale_factor);
+                self.zoom(idx, rect, fontdb);
+            }
+            FigureData::Detached => {
+                self.viewport = Some(self.area().scale_about(center, scale_factor));
+            }
+        }
+    }
+
+    /// Pan the given plot such that the content under `last` moves under `point`
+    fn pan(
+        &mut self,
+        idx: des::PlotIdx,
+        last: &mut geom::Point,
+        point: geom::Point,
+        fontdb: &fontdb::Database,
+    ) {
+        let delta_x = point.x - last.x;
+        let delta_y = point.y - last.y;
+        match &self.data {
+            FigureData::Shared(_) => {
+                *last = point;
+                let view = self.fig.plot_view(idx).expect("Plot index invalid");
+                let rect = view.rect().translate(-delta_x, -delta_y);
+                self.zoom(idx, rect, fontdb);
+            }
+            FigureData::Detached => {
+                // after the move, the content under the cursor is the one under `last`
+                self.viewport = Some(self.area().translate(-delta_x, -delta_y));
+            }
+        }
+    }
 }
 
 /// A figure show controller that manages a toolbar and a figure widget,
@@ -277,7 +539,14 @@ pub struct FigureShow<D: data::Source + ?Sized + 'static> {
     tb_status: Option<(String, String)>,
     interaction: Interaction,
     middle_but_drag: Option<(des::PlotIdx, geom::Point)>,
+    last_left_press: Option<(Instant, geom::Point)>,
     fig_scale: f32,
+    tooltips: Option<TooltipFormatter>,
+    tooltip: Option<(geom::Point, String)>,
+    crosshair_stroke: Option<theme::Stroke>,
+    crosshair: Option<Crosshair>,
+    export_format: ExportFormat,
+    export_size: Option<geom::Size>,
     #[cfg(feature = "clipboard")]
     clipboard: arboard::Clipboard,
     #[cfg(feature = "clipboard")]
@@ -304,7 +573,14 @@ where
             tb_status: None,
             interaction: Interaction::None,
             middle_but_drag: None,
+            last_left_press: None,
             fig_scale: 1.0,
+            tooltips: None,
+            tooltip: None,
+            crosshair_stroke: None,
+            crosshair: None,
+            export_format: ExportFormat::default(),
+            export_size: None,
             #[cfg(feature = "clipboard")]
             clipboard: arboard::Clipboard::new().unwrap(),
             #[cfg(feature = "clipboard")]
@@ -313,11 +589,18 @@ where
     }
 
     pub fn set_figure(&mut self, fig: drawing::PreparedFigure, data_source: Arc<D>) {
+        self.set_figure_data(fig, FigureData::Shared(data_source));
+    }
+
+    /// Set the figure along with its access to data, which determines the available interactions.
+    /// See [`FigureData`].
+    pub fn set_figure_data(&mut self, fig: drawing::PreparedFigure, data: FigureData<D>) {
         let home_view = fig.view();
         self.fig = Some(Fig {
             fig,
             home_view,
-            data_source,
+            data,
+            viewport: None,
         });
         self.at_home = true;
         self.interaction = Interaction::None;
@@ -333,12 +616,36 @@ where
         self.style = style;
     }
 
+    /// Enable hover tooltips with the given formatter, or disable them with `None`
+    pub fn set_tooltips(&mut self, formatter: Option<TooltipFormatter>) {
+        self.tooltips = formatter;
+        self.tooltip = None;
+    }
+
+    /// Set the format of the export with the `Ctrl+S` shortcut, and the size of the exported figures.
+    /// The figures are scaled to fit within `size`, or exported at their current scale
+    /// in the window if `size` is `None`.
+    pub fn set_export(&mut self, format: ExportFormat, size: Option<geom::Size>) {
+        self.export_format = format;
+        self.export_size = size;
+    }
+
+    /// Enable the crosshair with the given line stroke, or disable it with `None`
+    pub fn set_crosshair(&mut self, stroke: Option<theme::Stroke>) {
+        self.crosshair_stroke = stroke;
+        self.crosshair = None;
+    }
+
     pub fn figure(&self) -> Option<&drawing::PreparedFigure> {
         self.fig.as_ref().map(|f| &f.fig)
     }
 
+    /// The data source of the figure, if it is shared with the view
     pub fn data_source(&self) -> Option<&Arc<D>> {
-        self.fig.as_ref().map(|f| &f.data_source)
+        match self.fig.as_ref().map(|f| &f.data) {
+            Some(FigureData::Shared(data_source)) => Some(data_source),
+            _ => None,
+        }
     }
 
     pub fn style(&self) -> Option<&plotive::Style> {
@@ -358,11 +665,12 @@ where
             return iced::Task::none();
         };
 
+        // scale of the figure on screen, including the magnification of a detached figure
+        let view_scale = self.fig_scale * fig.fig.size().width() / fig.area().width();
+
         match msg {
             Message::GoHome => {
-                fig.fig
-                    .apply_view(&fig.home_view, &*fig.data_source, Some(&*self.fontdb))
-                    .expect("Failed to apply home view");
+                fig.go_home(&self.fontdb);
                 self.at_home = true;
                 self.interaction = Interaction::None;
             }
@@ -397,6 +705,23 @@ where
                     .map(|h| (format!("X = {}", h.x_coords), format!("Y = {}", h.y_coords)));
                 self.tb_status = status;
 
+                self.tooltip = match (self.tooltips, &self.interaction) {
+                    (Some(formatter), Interaction::None | Interaction::ZoomEnabled) => fig
+                        .fig
+                        .nearest_points(point, TOOLTIP_HIT_DIST / view_scale)
+                        .into_iter()
+                        .min_by(|a, b| a.dist.total_cmp(&b.dist))
+                        .map(|hit| (hit.pos, formatter(&hit))),
+                    _ => None,
+                };
+
+                self.crosshair = match (&self.crosshair_stroke, &hit) {
+                    (Some(stroke), Some(hit)) => {
+                        Crosshair::from_hit(&fig.fig, point, hit, stroke.clone())
+                    }
+                    _ => None,
+                };
+
                 match (&mut self.interaction, &hit) {
                     (Interaction::ZoomDragging { idx: plot, end, .. }, Some(hit)) => {
                         if *plot == hit.idx {
@@ -405,41 +730,58 @@ where
                     }
                     // match any hit because panning can go outside plot area
                     (Interaction::PanDragging { idx, last }, _) => {
-                        let delta_x = point.x - last.x;
-                        let delta_y = point.y - last.y;
-                        *last = point;
-                        let view = fig.fig.plot_view(*idx).expect("Plot index invalid");
-                        let rect = view.rect().translate(-delta_x, -delta_y);
-                        let zoom = zoom::Zoom::new(rect);
-                        fig.fig
-                            .apply_zoom(*idx, &zoom, &*fig.data_source, Some(&*self.fontdb))
-                            .expect("Failed to apply pan");
+                        fig.pan(*idx, last, point, &self.fontdb);
                         self.at_home = false;
                     }
                     _ => {}
                 }
 
                 if let Some((plot_idx, last)) = self.middle_but_drag.as_mut() {
-                    let delta_x = point.x - last.x;
-                    let delta_y = point.y - last.y;
-                    *last = point;
-                    let view = fig.fig.plot_view(*plot_idx).expect("Plot index invalid");
-                    let rect = view.rect().translate(-delta_x, -delta_y);
-                    let zoom = zoom::Zoom::new(rect);
-                    fig.fig
-                        .apply_zoom(*plot_idx, &zoom, &*fig.data_source, Some(&*self.fontdb))
-                        .expect("Failed to apply pan");
+                    fig.pan(*plot_idx, last, point, &self.fontdb);
                     self.at_home = false;
                 }
             }
             Message::FigureMousePress(point, mouse::Button::Left) => {
+                // a click on a legend entry hides or shows its series
+                if fig.fig.toggle_legend_entry_at(point) {
+                    self.last_left_press = None;
+                    return iced::Task::none();
+                }
+
                 let hit = fig.fig.hit_test_idx(point);
+
+                let now = Instant::now();
+                let double_click = is_double_click(
+                    self.last_left_press,
+                    now,
+                    point,
+                    DOUBLE_CLICK_DIST / view_scale,
+                );
+                self.last_left_press = Some((now, point));
+                if double_click && hit.is_some() {
+                    self.last_left_press = None;
+                    if !self.at_home {
+                        fig.go_home(&self.fontdb);
+                        self.at_home = true;
+                    }
+                    return iced::Task::none();
+                }
+
                 match (&self.interaction, hit) {
+                    (Interaction::None, Some(plot)) => {
+                        self.interaction = Interaction::ZoomDragging {
+                            idx: plot,
+                            start: point,
+                            end: point,
+                            enabled: false,
+                        };
+                    }
                     (Interaction::ZoomEnabled, Some(plot)) => {
                         self.interaction = Interaction::ZoomDragging {
                             idx: plot,
                             start: point,
                             end: point,
+                            enabled: true,
                         };
                     }
                     (Interaction::PanEnabled, Some(plot)) => {
@@ -452,19 +794,26 @@ where
                 }
             }
             Message::FigureMouseRelease(point, mouse::Button::Left) => match &self.interaction {
-                Interaction::ZoomDragging { idx, start, end } => {
+                Interaction::ZoomDragging {
+                    idx,
+                    start,
+                    end,
+                    enabled,
+                } => {
+                    let enabled = *enabled;
                     let hit = fig.fig.hit_test_idx(point);
-                    if let Some(hit_plot_idx) = hit {
-                        let rect = geom::Rect::from_corners(*start, *end);
+                    let rect = drag_zoom_rect(*start, *end, ZOOM_MIN_SIZE / view_scale);
+                    if let (Some(hit_plot_idx), Some(rect)) = (hit, rect) {
                         if *idx == hit_plot_idx {
-                            let zoom = zoom::Zoom::new(rect);
-                            fig.fig
-                                .apply_zoom(*idx, &zoom, &*fig.data_source, Some(&*self.fontdb))
-                                .expect("Failed to apply zoom");
+                            fig.zoom(*idx, rect, &self.fontdb);
                             self.at_home = false;
                         }
                     }
-                    self.interaction = Interaction::ZoomEnabled;
+                    self.interaction = if enabled {
+                        Interaction::ZoomEnabled
+                    } else {
+                        Interaction::None
+                    };
                 }
                 Interaction::PanDragging { .. } => {
                     self.interaction = Interaction::PanEnabled;
@@ -485,19 +834,16 @@ where
             Message::FigureMouseWheel(point, delta) => {
                 let hit = fig.fig.hit_test_idx(point);
                 if let Some(plot_idx) = hit {
-                    let view = fig.fig.plot_view(plot_idx).expect("Plot index invalid");
                     let scale_factor = (1.0 + delta * 0.1).max(0.1);
-                    let rect = view.rect().scale_about(point, scale_factor);
-                    let zoom = zoom::Zoom::new(rect);
-                    fig.fig
-                        .apply_zoom(plot_idx, &zoom, &*fig.data_source, Some(&*self.fontdb))
-                        .expect("Failed to apply zoom");
+                    fig.zoom_about(plot_idx, point, scale_factor, &self.fontdb);
                     self.at_home = false;
                 }
             }
             Message::Event(iced::event::Event::Mouse(ev)) => match ev {
                 iced::mouse::Event::CursorLeft => {
                     self.tb_status = None;
+                    self.tooltip = None;
+                    self.crosshair = None;
                 }
                 _ => {}
             },
@@ -510,6 +856,16 @@ where
                     } => {
                         self.interaction = Interaction::None;
                     }
+                    keyboard::Event::KeyPressed {
+                        key: keyboard::Key::Character(c),
+                        modifiers,
+                        ..
+                    } if modifiers.command() && c.as_str() == "s" => {
+                        return match export_shortcut(self.export_format, self.commands) {
+                            Some(msg) => self.update(msg),
+                            None => iced::Task::none(),
+                        };
+                    }
                     _ => {}
                 }
             }
@@ -529,7 +885,7 @@ where
                     } else {
                         plotive::Style::default()
                     };
-                    let scale = self.fig_scale;
+                    let scale = export_scale(&fig.fig, self.export_size, self.fig_scale);
                     fig.fig
                         .save_png(
                             path,
@@ -556,7 +912,7 @@ where
                     } else {
                         plotive::Style::default()
                     };
-                    let scale = self.fig_scale;
+                    let scale = export_scale(&fig.fig, self.export_size, self.fig_scale);
                     fig.fig
                         .save_svg(
                             path,
@@ -581,7 +937,7 @@ where
                 } else {
                     plotive::Style::default()
                 };
-                let scale = self.fig_scale;
+                let scale = export_scale(&fig.fig, self.export_size, self.fig_scale);
                 let pixmap = fig
                     .fig
                     .to_pixmap(
@@ -621,9 +977,9 @@ where
                     .add_filter("CSV File", &["csv"])
                     .set_file_name("figure_data.csv")
                     .save_file();
-                if let Some(path) = filename {
+                if let (Some(path), FigureData::Shared(data_source)) = (filename, &fig.data) {
                     let mut file = std::fs::File::create(path).unwrap();
-                    data::csv::export_data_source(&mut file, &*fig.data_source, Default::default())
+                    data::csv::export_data_source(&mut file, &**data_source, Default::default())
                         .expect("Failed to export figure data to CSV");
                 }
             }
@@ -648,6 +1004,7 @@ where
                 .into();
         };
 
+        let viewport = fig.viewport;
         let mut fig = figure(&fig.fig)
             .width(Length::Fill)
             .height(Length::Fill)
@@ -661,10 +1018,22 @@ where
             fig = fig.style(|_| style.clone());
         }
 
+        if let Some(area) = viewport {
+            fig = fig.viewport(area);
+        }
+
         if let Interaction::ZoomDragging { start, end, .. } = &self.interaction {
             fig = fig.zoom_rect(*start, *end);
         }
 
+        if let Some((pos, text)) = &self.tooltip {
+            fig = fig.tooltip(*pos, text.as_str());
+        }
+
+        if let Some(crosshair) = &self.crosshair {
+            fig = fig.crosshair(crosshair.clone());
+        }
+
         // Wrap with mouse_area to control cursor
         let interaction = match self.interaction {
             Interaction::PanEnabled if self.over_plot => iced::mouse::Interaction::Grabbing,
@@ -690,7 +1059,7 @@ where
         if self.commands.has_view() {
             let zooming = matches!(
                 self.interaction,
-                Interaction::ZoomEnabled | Interaction::ZoomDragging { .. }
+                Interaction::ZoomEnabled | Interaction::ZoomDragging { enabled: true, .. }
             );
             let panning = matches!(
                 self.interaction,
@@ -769,8 +1138,9 @@ where
 
         #[cfg(feature = "data-csv")]
         if self.commands.has_export_csv() {
+            let has_data = self.data_source().is_some();
             let convert_csv = button(fa_icon_solid("file-csv"))
-                .on_press_maybe(has_fig.then_some(Message::ExportCsv));
+                .on_press_maybe(has_data.then_some(Message::ExportCsv));
             toolbar = toolbar.push(convert_csv);
         }
 
@@ -783,3 +1153,156 @@ where
             .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prepared_figure() -> drawing::PreparedFigure {
+        let line = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0, 3.0, 4.0]),
+            des::data_inline(vec![0.0, 1.0, 4.0, 9.0, 16.0]),
+        );
+        let plot = des::Plot::new(vec![line.into()])
+            .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+            .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+        des::Figure::new(plot.into())
+            .with_size(geom::Size::new(400.0, 300.0))
+            .prepare(&(), None)
+            .unwrap()
+    }
+
+    /// Numeric X and Y coordinates of `point` in the plot under it
+    fn coords(fig: &drawing::PreparedFigure, point: geom::Point) -> (f64, f64) {
+        let hit = fig.hit_test(point).unwrap();
+        let x = hit.x_coords.get(0).unwrap().as_sample().as_num().unwrap();
+        let y = hit.y_coords.get(0).unwrap().as_sample().as_num().unwrap();
+        (x, y)
+    }
+
+    fn bounds(rect: geom::Rect) -> (f32, f32, f32, f32) {
+        (rect.x(), rect.y(), rect.width(), rect.height())
+    }
+
+    #[test]
+    fn test_export_shortcut() {
+        let msg = export_shortcut(ExportFormat::Png, Commands::all());
+        assert!(matches!(msg, Some(Message::ExportPng)));
+        let msg = export_shortcut(ExportFormat::Svg, Commands::all());
+        assert!(matches!(msg, Some(Message::ExportSvg)));
+
+        // the shortcut is disabled along with the export command
+        let commands = Commands::all().without_export_png();
+        assert!(export_shortcut(ExportFormat::Png, commands).is_none());
+        assert!(matches!(
+            export_shortcut(ExportFormat::Svg, commands),
+            Some(Message::ExportSvg)
+        ));
+        assert!(export_shortcut(ExportFormat::Svg, Commands::none()).is_none());
+    }
+
+    #[test]
+    fn test_export_size() {
+        use plotive_pxl::ToPixmap;
+
+        let fig = prepared_figure();
+        assert_eq!(export_scale(&fig, None, 1.5), 1.5);
+
+        // the figure fits within the export size, preserving its aspect ratio
+        let size = geom::Size::new(800.0, 600.0);
+        assert_eq!(export_scale(&fig, Some(size), 1.5), 2.0);
+        let size = geom::Size::new(1000.0, 450.0);
+        assert_eq!(export_scale(&fig, Some(size), 1.5), 1.5);
+
+        let scale = export_scale(&fig, Some(geom::Size::new(800.0, 800.0)), 1.0);
+        let pixmap = fig
+            .to_pixmap(
+                &(),
+                plotive_pxl::Params {
+                    scale,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+        assert_eq!((pixmap.width(), pixmap.height()), (800, 600));
+    }
+
+    #[test]
+    fn test_double_click() {
+        let t0 = Instant::now();
+        let p0 = geom::Point { x: 10.0, y: 10.0 };
+        let p1 = geom::Point { x: 12.0, y: 11.0 };
+        let soon = t0 + Duration::from_millis(100);
+        let late = t0 + DOUBLE_CLICK_DELAY + Duration::from_millis(1);
+
+        assert!(!is_double_click(None, soon, p1, 4.0));
+        assert!(is_double_click(Some((t0, p0)), soon, p1, 4.0));
+        assert!(!is_double_click(Some((t0, p0)), late, p1, 4.0));
+        assert!(!is_double_click(Some((t0, p0)), soon, p1, 2.0));
+    }
+
+    #[test]
+    fn test_drag_zoom_rect() {
+        let start = geom::Point { x: 50.0, y: 40.0 };
+
+        // a click, or a drag along a single direction, doesn't zoom
+        assert!(drag_zoom_rect(start, start, 4.0).is_none());
+        let end = geom::Point { x: 150.0, y: 42.0 };
+        assert!(drag_zoom_rect(start, end, 4.0).is_none());
+
+        // the rectangle doesn't depend on the drag direction
+        let end = geom::Point { x: 10.0, y: 100.0 };
+        let rect = drag_zoom_rect(start, end, 4.0).unwrap();
+        assert_eq!(bounds(rect), (10.0, 40.0, 40.0, 60.0));
+        assert_eq!(
+            drag_zoom_rect(end, start, 4.0).map(bounds),
+            Some(bounds(rect))
+        );
+    }
+
+    #[test]
+    fn test_box_zoom_and_reset() {
+        let fontdb = plotive::bundled_font_db();
+        let mut fig = prepared_figure();
+        let home_view = fig.view();
+
+        let idx = fig
+            .hit_test_idx(geom::Point { x: 200.0, y: 150.0 })
+            .unwrap();
+        let plot_rect = fig.plot_view(idx).unwrap().rect();
+        let start = geom::Point {
+            x: plot_rect.left() + plot_rect.width() * 0.25,
+            y: plot_rect.top() + plot_rect.height() * 0.25,
+        };
+        let end = geom::Point {
+            x: plot_rect.left() + plot_rect.width() * 0.75,
+            y: plot_rect.top() + plot_rect.height() * 0.5,
+        };
+        let start_coords = coords(&fig, start);
+        let end_coords = coords(&fig, end);
+
+        // the selected rectangle fills the plot area after the zoom
+        let rect = drag_zoom_rect(start, end, ZOOM_MIN_SIZE).unwrap();
+        fig.apply_zoom(idx, &zoom::Zoom::new(rect), &(), Some(&fontdb))
+            .unwrap();
+        let top_left = geom::Point {
+            x: plot_rect.left(),
+            y: plot_rect.top(),
+        };
+        let bottom_right = geom::Point {
+            x: plot_rect.right(),
+            y: plot_rect.bottom(),
+        };
+        let zoomed_tl = coords(&fig, top_left);
+        let zoomed_br = coords(&fig, bottom_right);
+        assert!((zoomed_tl.0 - start_coords.0).abs() < 1e-3);
+        assert!((zoomed_tl.1 - start_coords.1).abs() < 1e-3);
+        assert!((zoomed_br.0 - end_coords.0).abs() < 1e-3);
+        assert!((zoomed_br.1 - end_coords.1).abs() < 1e-3);
+
+        // going home restores the data-derived bounds
+        fig.apply_view(&home_view, &(), Some(&fontdb)).unwrap();
+        assert_eq!(coords(&fig, start), start_coords);
+        assert_eq!(coords(&fig, end), end_coords);
+    }
+}
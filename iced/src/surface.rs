@@ -44,22 +44,40 @@ impl<Frame> plotive::render::Surface for IcedSurface<Frame>
 where
     Frame: frame::Backend,
 {
-    fn prepare(&mut self, _size: geom::Size) {}
+    fn prepare(&mut self, _size: geom::Size) -> Result<(), render::Error> {
+        Ok(())
+    }
 
-    fn fill(&mut self, fill: render::Paint) {
-        let color = match fill {
-            render::Paint::Solid(c) => {
-                iced::Color::from_rgba8(c.red(), c.green(), c.blue(), c.alpha() as f32 / 255.0)
-            }
-        };
+    fn fill(&mut self, fill: render::Paint) -> Result<(), render::Error> {
+        let color = to_iced_paint_color(&fill);
         let bounds = self.clip_bounds();
         self.frames
             .last_mut()
             .unwrap()
             .fill_rectangle(bounds.position(), bounds.size(), color);
+        Ok(())
     }
 
-    fn draw_path(&mut self, path: &render::Path) {
+    fn fill_region(
+        &mut self,
+        fill: render::Paint,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error> {
+        let Some(region) = region else {
+            return self.fill(fill);
+        };
+        let color = to_iced_paint_color(&fill);
+        let rect = to_iced_rect(region, &self.transform);
+        if let Some(bounds) = self.clip_bounds().intersection(&rect) {
+            self.frames
+                .last_mut()
+                .unwrap()
+                .fill_rectangle(bounds.position(), bounds.size(), color);
+        }
+        Ok(())
+    }
+
+    fn draw_path(&mut self, path: &render::Path) -> Result<(), render::Error> {
         let transform = self.transform_item(path.transform);
         let iced_path = to_iced_path(&path.path, &transform);
 
@@ -76,26 +94,33 @@ where
                 .unwrap()
                 .stroke(&iced_path, iced_stroke);
         }
+        Ok(())
     }
 
     // The normal way to do clipping in iced would be to use draft, then paste into the previous frame.
     // However, because of https://github.com/iced-rs/iced/issues/3147 we use a workaround here:
     //   - Each clip push/pop creates a new frame with the correct clip bounds.
     //   - Each of those frames are returned as geometries and drawn in sequence
+    // Frames can only be clipped to a rectangle, so the clip path is approximated by its bounds.
 
-    fn push_clip(&mut self, clip: &render::Clip) {
+    fn push_clip(&mut self, clip: &render::Clip) -> Result<(), render::Error> {
         let transform = self.transform_item(clip.transform);
-        let iced_rect = to_iced_rect(&clip.rect, &transform);
+        let clip_rect = geom::Rect::from_path_bounds(clip.path);
+        let iced_rect = to_iced_rect(&clip_rect, &transform);
         let frame = self.frames.last_mut().unwrap().draft(iced_rect);
         self.frames.push(frame);
         self.clip_bounds.push(iced_rect);
+        Ok(())
     }
 
-    fn pop_clip(&mut self) {
-        let _ = self.clip_bounds.pop();
+    fn pop_clip(&mut self) -> Result<(), render::Error> {
+        self.clip_bounds
+            .pop()
+            .ok_or(render::Error::UnbalancedClipStack)?;
         let rect = self.clip_bounds();
         let frame = self.frames.last_mut().unwrap().draft(rect);
         self.frames.push(frame);
+        Ok(())
     }
 }
 
@@ -106,19 +131,31 @@ fn to_iced_color(color: plotive::ColorU8) -> iced::Color {
 }
 
 #[inline]
-fn to_iced_fill(paint: &render::Paint) -> geometry::Fill {
+fn to_iced_paint_color(paint: &render::Paint) -> iced::Color {
     match paint {
-        render::Paint::Solid(color) => to_iced_color(*color).into(),
+        render::Paint::Solid { color, .. } => iced::Color {
+            a: paint.opacity(),
+            ..to_iced_color(*color)
+        },
     }
 }
 
+#[inline]
+fn to_iced_fill(paint: &render::Paint) -> geometry::Fill {
+    to_iced_paint_color(paint).into()
+}
+
 #[inline]
 fn to_iced_stroke<'a>(
     stroke: &'a render::Stroke,
     pattern: &'a mut Vec<f32>,
     scale: f32,
 ) -> geometry::Stroke<'a> {
-    let style = to_iced_color(stroke.color).into();
+    let style = iced::Color {
+        a: stroke.opacity(),
+        ..to_iced_color(stroke.color)
+    }
+    .into();
     let width = stroke.width * scale;
     let line_dash = match &stroke.pattern {
         render::LinePattern::Solid => geometry::LineDash::default(),
@@ -0,0 +1,279 @@
+//! Headless export of time-evolving figures to image sequences and animations
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+
+use plotive::{Prepare, data, des, drawing};
+use tiny_skia::Pixmap;
+
+use crate::{Error, Params};
+
+/// Output of an animation export
+#[derive(Debug, Clone)]
+pub enum AnimationOutput {
+    /// Sequence of PNG files written in the given directory,
+    /// named `frame-0000.png`, `frame-0001.png`, etc.
+    /// The directory is created if it doesn't exist.
+    PngSequence(PathBuf),
+    /// Animated GIF file. Colors are quantized to a palette of 256 colors per frame.
+    Gif(PathBuf),
+    /// Animated PNG file
+    Apng(PathBuf),
+}
+
+/// Parameters of an animation export
+#[derive(Debug, Clone)]
+pub struct AnimationParams<'a> {
+    /// Parameters used to rasterize each frame
+    pub pxl: Params<'a>,
+    /// Number of frames per second of the animated formats.
+    /// It must be positive, and give a frame delay between 1 and 65535 milliseconds.
+    /// This parameter is ignored for PNG sequences.
+    pub frame_rate: f32,
+    /// Whether the axes bounds are kept at the ones of the first frame.
+    /// This avoids the jitter of the axes along the animation, but data of the
+    /// next frames that goes outside the bounds of the first frame is clipped.
+    /// Set explicit axis ranges in the figure design to control the bounds.
+    /// If `false` (the default), the figure is prepared again for each frame.
+    pub fixed_bounds: bool,
+    /// Whether the animated formats loop indefinitely, or play once.
+    pub repeat: bool,
+}
+
+impl Default for AnimationParams<'_> {
+    fn default() -> Self {
+        Self {
+            pxl: Params::default(),
+            frame_rate: 10.0,
+            fixed_bounds: false,
+            repeat: true,
+        }
+    }
+}
+
+/// Render a time-evolving figure to frames.
+///
+/// For each frame index in `0..frames`, `update` is called to update the data source,
+/// then the figure is drawn on a pixel surface and encoded to `output`.
+///
+/// Returns [`Error::NoFrames`] if `frames` is zero, and [`Error::InvalidFrameRate`]
+/// if the frame rate of an animated format is invalid (see [`AnimationParams::frame_rate`]).
+///
+/// # Example
+///
+/// ```rust
+/// use plotive::{data, des};
+/// use plotive_pxl::{AnimationOutput, AnimationParams, animate};
+///
+/// let series = des::series::Line::new(des::data_src_ref("x"), des::data_src_ref("y"));
+/// let fig = series.into_plot().into_figure();
+///
+/// let x: Vec<f64> = (0..=100).map(|i| i as f64 / 10.0).collect();
+/// let mut data_source = data::TableSource::new()
+///     .with_f64_column("x", x.clone())
+///     .with_f64_column("y", x.clone());
+///
+/// let path = std::env::temp_dir().join("wave.gif");
+/// let output = AnimationOutput::Gif(path.clone());
+/// let params = AnimationParams {
+///     fixed_bounds: true,
+///     ..Default::default()
+/// };
+/// animate(&fig, &mut data_source, 5, |i, src| {
+///     let phase = i as f64 * 0.5;
+///     let y = x.iter().map(|x| (x + phase).sin()).collect();
+///     *src = data::TableSource::new()
+///         .with_f64_column("x", x.clone())
+///         .with_f64_column("y", y);
+/// }, &output, params)
+/// .unwrap();
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+pub fn animate<D, F>(
+    fig: &des::Figure,
+    data_source: &mut D,
+    frames: usize,
+    mut update: F,
+    output: &AnimationOutput,
+    params: AnimationParams,
+) -> Result<(), Error>
+where
+    D: data::Source + ?Sized,
+    F: FnMut(usize, &mut D),
+{
+    // avoid loading the bundled fonts for each frame
+    let bundled_fontdb;
+    let fontdb = match params.pxl.fontdb {
+        Some(fontdb) => fontdb,
+        None => {
+            bundled_fontdb = plotive::bundled_font_db();
+            &bundled_fontdb
+        }
+    };
+
+    if frames == 0 {
+        return Err(Error::NoFrames);
+    }
+    let mut encoder = Encoder::new(output, frames, &params)?;
+    let mut prepared: Option<drawing::PreparedFigure> = None;
+
+    for i in 0..frames {
+        update(i, data_source);
+
+        let frame = match prepared.take() {
+            Some(mut frame) if params.fixed_bounds => {
+                frame.update_series_data(data_source)?;
+                frame
+            }
+            _ => fig.prepare(data_source, Some(fontdb))?,
+        };
+
+        encoder.write_frame(i, crate::draw_pixmap(&frame, &params.pxl)?)?;
+
+        prepared = Some(frame);
+    }
+
+    encoder.finish()
+}
+
+enum Encoder {
+    PngSequence(PathBuf),
+    Gif {
+        path: PathBuf,
+        delay: u16,
+        repeat: bool,
+        encoder: Option<gif::Encoder<BufWriter<File>>>,
+    },
+    Apng {
+        path: PathBuf,
+        frames: u32,
+        delay: u16,
+        repeat: bool,
+        writer: Option<png::Writer<BufWriter<File>>>,
+    },
+}
+
+impl Encoder {
+    fn new(
+        output: &AnimationOutput,
+        frames: usize,
+        params: &AnimationParams,
+    ) -> Result<Self, Error> {
+        // delay in milliseconds, checked for the animated formats
+        let delay = || {
+            let delay = (1000.0 / params.frame_rate).round();
+            if params.frame_rate > 0.0 && (1.0..=u16::MAX as f32).contains(&delay) {
+                Ok(delay as u16)
+            } else {
+                Err(Error::InvalidFrameRate(params.frame_rate))
+            }
+        };
+        match output {
+            AnimationOutput::PngSequence(dir) => {
+                std::fs::create_dir_all(dir)?;
+                Ok(Encoder::PngSequence(dir.clone()))
+            }
+            AnimationOutput::Gif(path) => Ok(Encoder::Gif {
+                path: path.clone(),
+                // GIF delays are in hundredths of a second
+                delay: delay()?.div_ceil(10),
+                repeat: params.repeat,
+                encoder: None,
+            }),
+            AnimationOutput::Apng(path) => Ok(Encoder::Apng {
+                path: path.clone(),
+                frames: frames as u32,
+                delay: delay()?,
+                repeat: params.repeat,
+                writer: None,
+            }),
+        }
+    }
+
+    /// Write a frame. The encoders of animated formats are created with the size of the first frame.
+    fn write_frame(&mut self, index: usize, pixmap: Pixmap) -> Result<(), Error> {
+        match self {
+            Encoder::PngSequence(dir) => {
+                pixmap.save_png(dir.join(format!("frame-{index:04}.png")))?;
+            }
+            Encoder::Gif {
+                path,
+                delay,
+                repeat,
+                encoder,
+            } => {
+                let (width, height) = gif_size(&pixmap)?;
+                let encoder = match encoder {
+                    Some(encoder) => encoder,
+                    None => {
+                        let file = BufWriter::new(File::create(&*path)?);
+                        let mut enc = gif::Encoder::new(file, width, height, &[])?;
+                        if *repeat {
+                            enc.set_repeat(gif::Repeat::Infinite)?;
+                        }
+                        encoder.insert(enc)
+                    }
+                };
+                let mut rgba = demultiplied_rgba(&pixmap);
+                let mut frame = gif::Frame::from_rgba_speed(width, height, &mut rgba, 10);
+                frame.delay = *delay;
+                encoder.write_frame(&frame)?;
+            }
+            Encoder::Apng {
+                path,
+                frames,
+                delay,
+                repeat,
+                writer,
+            } => {
+                let writer = match writer {
+                    Some(writer) => writer,
+                    None => {
+                        let file = BufWriter::new(File::create(&*path)?);
+                        let mut enc = png::Encoder::new(file, pixmap.width(), pixmap.height());
+                        enc.set_color(png::ColorType::Rgba);
+                        enc.set_depth(png::BitDepth::Eight);
+                        enc.set_animated(*frames, if *repeat { 0 } else { 1 })?;
+                        enc.set_frame_delay(*delay, 1000)?;
+                        writer.insert(enc.write_header()?)
+                    }
+                };
+                writer.write_image_data(&demultiplied_rgba(&pixmap))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), Error> {
+        match self {
+            Encoder::PngSequence(_) => Ok(()),
+            // the trailer is written when the encoder is dropped
+            Encoder::Gif { .. } => Ok(()),
+            Encoder::Apng { writer, .. } => {
+                if let Some(writer) = writer {
+                    writer.finish()?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn gif_size(pixmap: &Pixmap) -> Result<(u16, u16), Error> {
+    let width = u16::try_from(pixmap.width());
+    let height = u16::try_from(pixmap.height());
+    match (width, height) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(Error::InvalidSurfaceSize(pixmap.width(), pixmap.height())),
+    }
+}
+
+/// The pixels of the pixmap, as straight (non-premultiplied) RGBA bytes
+fn demultiplied_rgba(pixmap: &Pixmap) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(pixmap.data().len());
+    for px in pixmap.pixels() {
+        let c = px.demultiply();
+        rgba.extend_from_slice(&[c.red(), c.green(), c.blue(), c.alpha()]);
+    }
+    rgba
+}
@@ -0,0 +1,77 @@
+//! Simulation of color vision deficiencies on rendered pixels
+use tiny_skia::PremultipliedColorU8;
+
+/// Kind of color vision deficiency (CVD) to simulate
+///
+/// The simulation applies the full severity matrices of Machado, Oliveira and Fernandes (2009)
+/// to the linear RGB values of the pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cvd {
+    /// Absence of the red-sensitive (L) cones
+    Protanopia,
+    /// Absence of the green-sensitive (M) cones
+    Deuteranopia,
+    /// Absence of the blue-sensitive (S) cones
+    Tritanopia,
+}
+
+impl Cvd {
+    fn matrix(&self) -> [[f32; 3]; 3] {
+        match self {
+            Cvd::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998],
+            ],
+            Cvd::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881],
+            ],
+            Cvd::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900],
+            ],
+        }
+    }
+}
+
+/// Apply the CVD simulation to premultiplied pixels
+pub(crate) fn simulate(pixels: &mut [PremultipliedColorU8], cvd: Cvd) {
+    let m = cvd.matrix();
+    let to_linear: Vec<f32> = (0..=255u8)
+        .map(|c| srgb_to_linear(c as f32 / 255.0))
+        .collect();
+
+    for px in pixels.iter_mut() {
+        if px.alpha() == 0 {
+            continue;
+        }
+        let c = px.demultiply();
+        let rgb = [
+            to_linear[c.red() as usize],
+            to_linear[c.green() as usize],
+            to_linear[c.blue() as usize],
+        ];
+        let sim = m.map(|row| row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2]);
+        let [r, g, b] = sim.map(|l| (linear_to_srgb(l.clamp(0.0, 1.0)) * 255.0).round() as u8);
+        *px = tiny_skia::ColorU8::from_rgba(r, g, b, c.alpha()).premultiply();
+    }
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -4,11 +4,30 @@ use std::{fmt, io};
 use plotive::{ColorU8, Style, drawing, geom, render};
 use tiny_skia::{self, FillRule, Mask, Pixmap, PixmapMut};
 
+#[cfg(feature = "image-formats")]
+mod animate;
+mod cvd;
+
+#[cfg(feature = "image-formats")]
+pub use animate::{AnimationOutput, AnimationParams, animate};
+pub use cvd::Cvd;
+
 #[derive(Debug)]
 pub enum Error {
     Io(io::Error),
     Drawing(drawing::Error),
     InvalidSurfaceSize(u32, u32),
+    #[cfg(feature = "image-formats")]
+    Png(png::EncodingError),
+    #[cfg(feature = "image-formats")]
+    Gif(gif::EncodingError),
+    /// An animation was requested with zero frames
+    #[cfg(feature = "image-formats")]
+    NoFrames,
+    /// The frame rate of an animation is not positive, or gives a frame delay
+    /// that can't be encoded
+    #[cfg(feature = "image-formats")]
+    InvalidFrameRate(f32),
 }
 
 impl From<io::Error> for Error {
@@ -23,18 +42,65 @@ impl From<drawing::Error> for Error {
     }
 }
 
+impl From<render::Error> for Error {
+    fn from(err: render::Error) -> Self {
+        Error::Drawing(err.into())
+    }
+}
+
+#[cfg(feature = "image-formats")]
+impl From<png::EncodingError> for Error {
+    fn from(err: png::EncodingError) -> Self {
+        Error::Png(err)
+    }
+}
+
+#[cfg(feature = "image-formats")]
+impl From<gif::EncodingError> for Error {
+    fn from(err: gif::EncodingError) -> Self {
+        Error::Gif(err)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::Io(err) => write!(f, "IO error: {}", err),
             Error::Drawing(err) => write!(f, "Drawing error: {}", err),
             Error::InvalidSurfaceSize(w, h) => write!(f, "Invalid surface size: {}x{}", w, h),
+            #[cfg(feature = "image-formats")]
+            Error::Png(err) => write!(f, "PNG encoding error: {}", err),
+            #[cfg(feature = "image-formats")]
+            Error::Gif(err) => write!(f, "GIF encoding error: {}", err),
+            #[cfg(feature = "image-formats")]
+            Error::NoFrames => write!(f, "Animation has no frames"),
+            #[cfg(feature = "image-formats")]
+            Error::InvalidFrameRate(rate) => write!(f, "Invalid frame rate: {}", rate),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+/// Anti-aliasing mode of the pixel surfaces
+///
+/// Anti-aliasing with the high quality pipeline gives the smoothest result,
+/// but is also the slowest mode. Disabling anti-aliasing lets tiny-skia use its
+/// faster low precision pipeline, which is worth it for large batch exports or
+/// pixel-art rendering, at the cost of jagged curves and text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AntiAlias {
+    /// Anti-aliasing with the high quality pipeline
+    #[default]
+    HighQuality,
+    /// No anti-aliasing, with the fastest pipeline
+    None,
+    /// No anti-aliasing for paths made only of horizontal and vertical lines
+    /// (grids, ticks, axis lines...), which are then rendered crisp,
+    /// and high quality anti-aliasing for everything else (curves, markers, text...)
+    SharpLines,
+}
+
 /// Parameters needed for saving a figure as PNG
 #[derive(Debug, Clone)]
 pub struct Params<'a> {
@@ -45,6 +111,12 @@ pub struct Params<'a> {
     /// as the fonts have already been resolved.
     /// In such case, this parameter can be left to `None` (which is the default).
     pub fontdb: Option<&'a plotive::fontdb::Database>,
+    /// Anti-aliasing mode. See [`AntiAlias`] for the performance tradeoff.
+    pub anti_alias: AntiAlias,
+    /// Optional physical size of the image.
+    /// If set, the pixel size is computed from the physical size and its resolution,
+    /// and `scale` is ignored.
+    pub physical_size: Option<render::PhysicalSize>,
 }
 
 impl Default for Params<'_> {
@@ -53,6 +125,8 @@ impl Default for Params<'_> {
             style: Style::default(),
             scale: 1.0,
             fontdb: None,
+            anti_alias: AntiAlias::default(),
+            physical_size: None,
         }
     }
 }
@@ -109,16 +183,8 @@ impl SavePng for drawing::PreparedFigure {
         P: AsRef<Path>,
         D: plotive::data::Source + ?Sized,
     {
-        let size = self.size();
-        let witdth = (size.width() * params.scale) as u32;
-        let height = (size.height() * params.scale) as u32;
-
-        let mut surface =
-            PxlSurface::new(witdth, height).ok_or(Error::InvalidSurfaceSize(witdth, height))?;
-
-        self.draw(&mut surface, &params.style);
-
-        surface.save_png(path)?;
+        let pixmap = draw_pixmap(self, &params)?;
+        pixmap.save_png(path).map_err(io::Error::from)?;
         Ok(())
     }
 }
@@ -153,17 +219,53 @@ impl ToPixmap for drawing::PreparedFigure {
     where
         D: plotive::data::Source + ?Sized,
     {
-        let size = self.size();
-        let witdth = (size.width() * params.scale) as u32;
-        let height = (size.height() * params.scale) as u32;
+        draw_pixmap(self, &params)
+    }
+}
 
-        let mut surface =
-            PxlSurface::new(witdth, height).ok_or(Error::InvalidSurfaceSize(witdth, height))?;
+/// Draw a prepared figure on a new pixmap, sized according to the parameters
+fn draw_pixmap(fig: &drawing::PreparedFigure, params: &Params) -> Result<Pixmap, Error> {
+    let size = fig.size();
+    let (width, height) = match params.physical_size {
+        None => (
+            (size.width() * params.scale) as u32,
+            (size.height() * params.scale) as u32,
+        ),
+        Some(phys) => {
+            let scale = phys.fit_scale(size);
+            (
+                ((size.width() * scale).round() as u32).min(phys.pixel_width()),
+                ((size.height() * scale).round() as u32).min(phys.pixel_height()),
+            )
+        }
+    };
 
-        self.draw(&mut surface, &params.style);
+    let mut surface = PxlSurface::new(width, height)
+        .ok_or(Error::InvalidSurfaceSize(width, height))?
+        .with_anti_alias(params.anti_alias);
+    fig.draw(&mut surface, &params.style)?;
+    let pixmap = surface.into_pixmap();
 
-        Ok(surface.into_pixmap())
-    }
+    let Some(phys) = params.physical_size else {
+        return Ok(pixmap);
+    };
+    let (phys_width, phys_height) = (phys.pixel_width(), phys.pixel_height());
+    if (width, height) == (phys_width, phys_height) {
+        return Ok(pixmap);
+    }
+
+    // center the figure if its aspect ratio differs from the physical size
+    let mut page = Pixmap::new(phys_width, phys_height)
+        .ok_or(Error::InvalidSurfaceSize(phys_width, phys_height))?;
+    page.draw_pixmap(
+        ((phys_width - width) / 2) as i32,
+        ((phys_height - height) / 2) as i32,
+        pixmap.as_ref(),
+        &tiny_skia::PixmapPaint::default(),
+        tiny_skia::Transform::identity(),
+        None,
+    );
+    Ok(page)
 }
 
 #[derive(Debug, Clone)]
@@ -187,6 +289,19 @@ impl PxlSurface {
     pub fn into_pixmap(self) -> Pixmap {
         self.pixmap
     }
+
+    /// Set the anti-aliasing mode. Default is [`AntiAlias::HighQuality`]
+    pub fn with_anti_alias(mut self, anti_alias: AntiAlias) -> Self {
+        self.state.anti_alias = anti_alias;
+        self
+    }
+
+    /// Simulate how the drawn pixels are perceived with a color vision deficiency.
+    /// This is a post-process that must be called after drawing, and that alters the pixels.
+    /// It is useful to check the readability of a figure for colorblind readers.
+    pub fn simulate_cvd(&mut self, cvd: Cvd) {
+        cvd::simulate(self.pixmap.pixels_mut(), cvd);
+    }
 }
 
 pub struct PxlSurfaceRef<'a> {
@@ -210,6 +325,18 @@ impl<'a> PxlSurfaceRef<'a> {
         self.pixmap.as_ref().save_png(path)?;
         Ok(())
     }
+
+    /// Set the anti-aliasing mode. Default is [`AntiAlias::HighQuality`]
+    pub fn with_anti_alias(mut self, anti_alias: AntiAlias) -> Self {
+        self.state.anti_alias = anti_alias;
+        self
+    }
+
+    /// Simulate how the drawn pixels are perceived with a color vision deficiency.
+    /// See [`PxlSurface::simulate_cvd`].
+    pub fn simulate_cvd(&mut self, cvd: Cvd) {
+        cvd::simulate(self.pixmap.pixels_mut(), cvd);
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -218,6 +345,8 @@ struct State {
     height: u32,
     transform: geom::Transform,
     clip: Option<Mask>,
+    region: Option<(geom::Rect, Mask)>,
+    anti_alias: AntiAlias,
 }
 
 impl State {
@@ -227,32 +356,99 @@ impl State {
             height,
             transform: geom::Transform::identity(),
             clip: None,
+            region: None,
+            anti_alias: AntiAlias::default(),
+        }
+    }
+
+    /// A paint set up for the anti-aliasing mode, for the given path if any
+    fn paint(&self, path: Option<&geom::Path>) -> tiny_skia::Paint<'static> {
+        let anti_alias = match self.anti_alias {
+            AntiAlias::HighQuality => true,
+            AntiAlias::None => false,
+            AntiAlias::SharpLines => !path.is_some_and(is_axis_aligned),
+        };
+        tiny_skia::Paint {
+            anti_alias,
+            force_hq_pipeline: anti_alias,
+            ..Default::default()
         }
     }
 
-    fn prepare(&mut self, size: geom::Size) {
+    fn prepare_region(
+        &mut self,
+        size: geom::Size,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error> {
         let sx = self.width as f32 / size.width();
         let sy = self.height as f32 / size.height();
-        self.transform = geom::Transform::from_scale(sx, sy);
+        let transform = geom::Transform::from_scale(sx, sy);
+
+        // the region mask is reused across redraws of the same region
+        let prev_rect = self.region.as_ref().map(|(rect, _)| rect);
+        if transform != self.transform || prev_rect != region {
+            self.region = match region {
+                Some(rect) => {
+                    let mut mask = new_mask(self.width, self.height)?;
+                    mask.fill_path(&rect.to_path(), FillRule::Winding, false, transform);
+                    Some((*rect, mask))
+                }
+                None => None,
+            };
+        }
+        self.transform = transform;
+        Ok(())
     }
 
-    fn fill(&mut self, px: &mut PixmapMut<'_>, fill: render::Paint) {
-        match fill {
-            render::Paint::Solid(color) => {
-                let color = ts_color(color);
-                px.fill(color);
-            }
-        }
+    fn fill(
+        &mut self,
+        px: &mut PixmapMut<'_>,
+        fill: render::Paint,
+        rect: Option<&geom::Rect>,
+    ) -> Result<(), render::Error> {
+        let rect = rect.or(self.region.as_ref().map(|(rect, _)| rect));
+        let Some(rect) = rect else {
+            px.fill(ts_paint_color(&fill));
+            return Ok(());
+        };
+        // replace the region content rather than blending over it
+        let mut paint = tiny_skia::Paint {
+            blend_mode: tiny_skia::BlendMode::Source,
+            ..self.paint(None)
+        };
+        ts_fill(fill, &mut paint);
+        px.fill_path(
+            &rect.to_path(),
+            &paint,
+            FillRule::Winding,
+            self.transform,
+            self.region.as_ref().map(|(_, mask)| mask),
+        );
+        Ok(())
     }
 
-    fn draw_path(&mut self, px: &mut PixmapMut<'_>, path: &render::Path) {
+    fn mask(&self) -> Option<&Mask> {
+        self.clip
+            .as_ref()
+            .or(self.region.as_ref().map(|(_, mask)| mask))
+    }
+
+    fn draw_path(
+        &mut self,
+        px: &mut PixmapMut<'_>,
+        path: &render::Path,
+    ) -> Result<(), render::Error> {
         let transform = path
             .transform
             .map(|t| t.post_concat(self.transform))
             .unwrap_or(self.transform);
 
+        // axis alignment is only preserved by transforms without rotation or skew
+        let aligned = transform.kx == 0.0 && transform.ky == 0.0;
+        let paint = self.paint(Some(path.path).filter(|_| aligned));
+
         if let Some(fill) = path.fill {
-            let mut paint = tiny_skia::Paint::default();
+            let mut paint = paint.clone();
             ts_fill(fill, &mut paint);
 
             px.fill_path(
@@ -260,100 +456,327 @@ impl State {
                 &paint,
                 tiny_skia::FillRule::Winding,
                 transform,
-                self.clip.as_ref(),
+                self.mask(),
             );
         }
         if let Some(stroke) = path.stroke {
-            let mut paint = tiny_skia::Paint::default();
+            let mut paint = paint;
             let stroke = ts_stroke(stroke, &mut paint);
-            px.stroke_path(path.path, &paint, &stroke, transform, self.clip.as_ref());
+            px.stroke_path(path.path, &paint, &stroke, transform, self.mask());
+        }
+        Ok(())
+    }
+
+    fn draw_shadow(
+        &mut self,
+        px: &mut PixmapMut<'_>,
+        shadow: &render::Shadow,
+    ) -> Result<(), render::Error> {
+        let (dx, dy) = shadow.offset;
+        let transform = shadow
+            .transform
+            .copied()
+            .unwrap_or_default()
+            .post_translate(dx, dy)
+            .post_concat(self.transform);
+
+        let mut mask = new_mask(self.width, self.height)?;
+        mask.fill_path(shadow.path, FillRule::Winding, true, transform);
+        // blur radius is taken as twice the gaussian standard deviation
+        let sigma = shadow.blur / 2.0 * self.transform.sx;
+        blur_mask(&mut mask, sigma);
+        if let Some(clip) = self.mask() {
+            for (m, c) in mask.data_mut().iter_mut().zip(clip.data()) {
+                *m = ((*m as u32 * *c as u32 + 127) / 255) as u8;
+            }
         }
+
+        let mut paint = self.paint(None);
+        ts_fill(shadow.color.into(), &mut paint);
+        let rect = tiny_skia::Rect::from_xywh(0.0, 0.0, self.width as f32, self.height as f32)
+            .ok_or_else(|| render::Error::Backend("invalid surface rectangle".into()))?;
+        px.fill_rect(rect, &paint, tiny_skia::Transform::identity(), Some(&mask));
+        Ok(())
+    }
+
+    fn draw_image(
+        &mut self,
+        px: &mut PixmapMut<'_>,
+        image: &render::Image,
+    ) -> Result<(), render::Error> {
+        let pixmap = image_pixmap(image)?;
+        let rect = &image.rect;
+        let transform = geom::Transform::from_row(
+            rect.width() / image.width as f32,
+            0.0,
+            0.0,
+            rect.height() / image.height as f32,
+            rect.x(),
+            rect.y(),
+        );
+        let transform = image
+            .transform
+            .map(|t| transform.post_concat(*t))
+            .unwrap_or(transform)
+            .post_concat(self.transform);
+        let paint = tiny_skia::PixmapPaint {
+            opacity: image.opacity,
+            quality: tiny_skia::FilterQuality::Bilinear,
+            ..Default::default()
+        };
+        px.draw_pixmap(0, 0, pixmap.as_ref(), &paint, transform, self.mask());
+        Ok(())
     }
 
-    fn push_clip(&mut self, clip: &render::Clip) {
+    fn push_clip(&mut self, clip: &render::Clip) -> Result<(), render::Error> {
         if self.clip.is_some() {
-            unimplemented!("clip with more than 1 layer");
+            Err(render::Error::Unsupported("clip with more than 1 layer"))
         } else {
-            let mut mask = Mask::new(self.width, self.height).unwrap();
+            let mut mask = new_mask(self.width, self.height)?;
             let transform = clip
                 .transform
                 .map(|t| t.post_concat(self.transform))
                 .unwrap_or(self.transform);
-            let path = clip.rect.to_path();
-            mask.fill_path(&path, FillRule::Winding, true, transform);
+            mask.fill_path(clip.path, FillRule::Winding, true, transform);
+            if let Some((rect, _)) = &self.region {
+                mask.intersect_path(&rect.to_path(), FillRule::Winding, false, self.transform);
+            }
             self.clip = Some(mask);
+            Ok(())
         }
     }
 
-    fn pop_clip(&mut self) {
-        self.clip = None;
+    fn pop_clip(&mut self) -> Result<(), render::Error> {
+        self.clip
+            .take()
+            .map(|_| ())
+            .ok_or(render::Error::UnbalancedClipStack)
     }
 }
 
 impl render::Surface for PxlSurface {
-    fn prepare(&mut self, size: geom::Size) {
-        self.state.prepare(size)
+    fn prepare(&mut self, size: geom::Size) -> Result<(), render::Error> {
+        self.state.prepare_region(size, None)
+    }
+
+    fn prepare_region(
+        &mut self,
+        size: geom::Size,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error> {
+        self.state.prepare_region(size, region)
+    }
+
+    fn supports_partial(&self) -> bool {
+        true
     }
 
-    fn fill(&mut self, fill: render::Paint) {
+    fn fill(&mut self, fill: render::Paint) -> Result<(), render::Error> {
         let mut px = self.pixmap.as_mut();
-        self.state.fill(&mut px, fill)
+        self.state.fill(&mut px, fill, None)
     }
 
-    fn draw_path(&mut self, path: &render::Path) {
+    fn fill_region(
+        &mut self,
+        fill: render::Paint,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error> {
+        let mut px = self.pixmap.as_mut();
+        self.state.fill(&mut px, fill, region)
+    }
+
+    fn draw_path(&mut self, path: &render::Path) -> Result<(), render::Error> {
         let mut px = self.pixmap.as_mut();
         self.state.draw_path(&mut px, path)
     }
 
-    fn push_clip(&mut self, clip: &render::Clip) {
+    fn draw_shadow(&mut self, shadow: &render::Shadow) -> Result<(), render::Error> {
+        let mut px = self.pixmap.as_mut();
+        self.state.draw_shadow(&mut px, shadow)
+    }
+
+    fn draw_image(&mut self, image: &render::Image) -> Result<(), render::Error> {
+        let mut px = self.pixmap.as_mut();
+        self.state.draw_image(&mut px, image)
+    }
+
+    fn push_clip(&mut self, clip: &render::Clip) -> Result<(), render::Error> {
         self.state.push_clip(clip)
     }
 
-    fn pop_clip(&mut self) {
+    fn pop_clip(&mut self) -> Result<(), render::Error> {
         self.state.pop_clip()
     }
 }
 
 impl render::Surface for PxlSurfaceRef<'_> {
-    fn prepare(&mut self, size: geom::Size) {
-        self.state.prepare(size)
+    fn prepare(&mut self, size: geom::Size) -> Result<(), render::Error> {
+        self.state.prepare_region(size, None)
+    }
+
+    fn prepare_region(
+        &mut self,
+        size: geom::Size,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error> {
+        self.state.prepare_region(size, region)
+    }
+
+    fn supports_partial(&self) -> bool {
+        true
     }
 
-    fn fill(&mut self, fill: render::Paint) {
-        self.state.fill(&mut self.pixmap, fill)
+    fn fill(&mut self, fill: render::Paint) -> Result<(), render::Error> {
+        self.state.fill(&mut self.pixmap, fill, None)
     }
 
-    fn draw_path(&mut self, path: &render::Path) {
+    fn fill_region(
+        &mut self,
+        fill: render::Paint,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error> {
+        self.state.fill(&mut self.pixmap, fill, region)
+    }
+
+    fn draw_path(&mut self, path: &render::Path) -> Result<(), render::Error> {
         self.state.draw_path(&mut self.pixmap, path)
     }
 
-    fn push_clip(&mut self, clip: &render::Clip) {
+    fn draw_shadow(&mut self, shadow: &render::Shadow) -> Result<(), render::Error> {
+        self.state.draw_shadow(&mut self.pixmap, shadow)
+    }
+
+    fn draw_image(&mut self, image: &render::Image) -> Result<(), render::Error> {
+        self.state.draw_image(&mut self.pixmap, image)
+    }
+
+    fn push_clip(&mut self, clip: &render::Clip) -> Result<(), render::Error> {
         self.state.push_clip(clip)
     }
 
-    fn pop_clip(&mut self) {
+    fn pop_clip(&mut self) -> Result<(), render::Error> {
         self.state.pop_clip()
     }
 }
 
+/// Allocate a mask of the size of the surface
+fn new_mask(width: u32, height: u32) -> Result<Mask, render::Error> {
+    Mask::new(width, height).ok_or_else(|| {
+        render::Error::Backend(format!("failed to allocate a {}x{} mask", width, height))
+    })
+}
+
+/// Build a pixmap from the non-premultiplied RGBA data of the image
+fn image_pixmap(image: &render::Image) -> Result<Pixmap, render::Error> {
+    image.check()?;
+    let size = tiny_skia::IntSize::from_wh(image.width, image.height)
+        .expect("Image size should be checked");
+    let data = image
+        .rgba
+        .chunks_exact(4)
+        .flat_map(|px| {
+            let c = tiny_skia::ColorU8::from_rgba(px[0], px[1], px[2], px[3]).premultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect();
+    Ok(Pixmap::from_vec(data, size).expect("Image data should be checked"))
+}
+
+/// Whether the path is only made of horizontal and vertical lines
+fn is_axis_aligned(path: &geom::Path) -> bool {
+    let mut last = geom::Point::zero();
+    let mut start = last;
+    for seg in path.segments() {
+        match seg {
+            geom::PathSegment::MoveTo(p) => {
+                start = p;
+                last = p;
+            }
+            geom::PathSegment::LineTo(p) => {
+                if p.x != last.x && p.y != last.y {
+                    return false;
+                }
+                last = p;
+            }
+            geom::PathSegment::Close => {
+                if start.x != last.x && start.y != last.y {
+                    return false;
+                }
+                last = start;
+            }
+            geom::PathSegment::QuadTo(..) | geom::PathSegment::CubicTo(..) => return false,
+        }
+    }
+    true
+}
+
+/// Approximate a gaussian blur of the mask with three successive box blurs
+fn blur_mask(mask: &mut Mask, sigma: f32) {
+    // box width for 3 passes is sqrt(12 * sigma^2 / 3 + 1)
+    let radius = (((4.0 * sigma * sigma + 1.0).sqrt() - 1.0) / 2.0).round() as usize;
+    if radius == 0 {
+        return;
+    }
+    let width = mask.width() as usize;
+    let height = mask.height() as usize;
+    let data = mask.data_mut();
+    let mut tmp = vec![0u8; data.len()];
+    for _ in 0..3 {
+        box_blur_1d(data, &mut tmp, width, height, 1, width, radius);
+        box_blur_1d(&tmp, data, height, width, width, 1, radius);
+    }
+}
+
+/// Box blur along one direction.
+/// `len` values spaced by `step` make a line, and `lines` lines are spaced by `line_step`.
+/// Values outside of the line are taken as zero.
+fn box_blur_1d(
+    src: &[u8],
+    dst: &mut [u8],
+    len: usize,
+    lines: usize,
+    step: usize,
+    line_step: usize,
+    radius: usize,
+) {
+    let size = (2 * radius + 1) as u32;
+    for line in 0..lines {
+        let at = |i: usize| line * line_step + i * step;
+        let mut sum: u32 = (0..=radius.min(len - 1)).map(|i| src[at(i)] as u32).sum();
+        for i in 0..len {
+            dst[at(i)] = ((sum + size / 2) / size) as u8;
+            if i + radius + 1 < len {
+                sum += src[at(i + radius + 1)] as u32;
+            }
+            if i >= radius {
+                sum -= src[at(i - radius)] as u32;
+            }
+        }
+    }
+}
+
 fn ts_color(color: ColorU8) -> tiny_skia::Color {
     tiny_skia::Color::from_rgba8(color.red(), color.green(), color.blue(), color.alpha())
 }
 
-fn ts_fill(fill: render::Paint, paint: &mut tiny_skia::Paint) {
+/// Color of the paint, with its effective opacity
+fn ts_paint_color(fill: &render::Paint) -> tiny_skia::Color {
     match fill {
-        render::Paint::Solid(color) => {
-            let color = ts_color(color);
-            paint.set_color(color);
+        render::Paint::Solid { color, .. } => {
+            let mut color = ts_color(*color);
+            color.set_alpha(fill.opacity());
+            color
         }
     }
-    paint.force_hq_pipeline = true;
 }
 
-fn ts_stroke(stroke: render::Stroke, paint: &mut tiny_skia::Paint) -> tiny_skia::Stroke {
-    paint.force_hq_pipeline = true;
+fn ts_fill(fill: render::Paint, paint: &mut tiny_skia::Paint) {
+    paint.set_color(ts_paint_color(&fill));
+}
 
-    let color = ts_color(stroke.color);
+fn ts_stroke(stroke: render::Stroke, paint: &mut tiny_skia::Paint) -> tiny_skia::Stroke {
+    let mut color = ts_color(stroke.color);
+    color.set_alpha(stroke.opacity());
     paint.set_color(color);
 
     let mut ts = tiny_skia::Stroke {
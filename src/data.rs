@@ -15,6 +15,11 @@ pub mod csv;
 #[cfg(feature = "data-polars")]
 pub mod polars;
 
+#[cfg(feature = "utils")]
+mod group;
+#[cfg(feature = "utils")]
+pub use group::{Agg, GroupBy, GroupByError, group_by};
+
 #[cfg(feature = "time")]
 use crate::time::{DateTime, TimeDelta};
 
@@ -101,7 +106,7 @@ impl From<f64> for SampleRef<'_> {
         if val.is_finite() {
             SampleRef::Num(val)
         } else {
-            SampleRef::Num(val)
+            SampleRef::Null
         }
     }
 }
@@ -264,7 +269,7 @@ impl From<f64> for Sample {
         if val.is_finite() {
             Sample::Num(val)
         } else {
-            Sample::Num(val)
+            Sample::Null
         }
     }
 }
@@ -375,6 +380,15 @@ pub trait Column: std::fmt::Debug {
         }
     }
 
+    /// Get an iterator over the runs of contiguous non-null samples in the column.
+    /// Each run is yielded as the `(start, end)` indices of its samples, `end` being excluded.
+    /// Non-finite numbers are null samples (see [`SampleRef`]).
+    fn non_null_runs(&self) -> Box<dyn Iterator<Item = (usize, usize)> + '_> {
+        Box::new(NonNullRuns(
+            self.sample_iter().map(|s| !s.is_null()).enumerate(),
+        ))
+    }
+
     /// Get a copy of the column as a boxed trait object
     /// This should be implemented if a copy can be made in an efficient way.
     /// It is not mandatory that the same type is returned.
@@ -527,6 +541,29 @@ pub trait I64Column: std::fmt::Debug {
     }
 }
 
+/// Iterator over the runs of non-null samples, from an enumeration of whether samples are non-null.
+/// See [`Column::non_null_runs`].
+struct NonNullRuns<I>(std::iter::Enumerate<I>);
+
+impl<I> Iterator for NonNullRuns<I>
+where
+    I: Iterator<Item = bool>,
+{
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, _) = self.0.find(|(_, some)| *some)?;
+        let mut end = start + 1;
+        for (i, some) in self.0.by_ref() {
+            if !some {
+                break;
+            }
+            end = i + 1;
+        }
+        Some((start, end))
+    }
+}
+
 /// Trait for a column of string values
 pub trait StrColumn: std::fmt::Debug {
     /// Get the length of the column
@@ -744,6 +781,56 @@ impl Column for ICol<'_> {
     }
 }
 
+/// Column of the row indices `0..len`, without storage.
+/// This is the implicit X data of series given only Y data.
+#[derive(Debug, Clone, Copy)]
+pub struct IndexCol(pub usize);
+
+impl I64Column for IndexCol {
+    fn len(&self) -> usize {
+        self.0
+    }
+    fn len_some(&self) -> usize {
+        self.0
+    }
+    fn i64_iter(&self) -> Box<dyn Iterator<Item = Option<i64>> + '_> {
+        Box::new((0..self.0 as i64).map(Some))
+    }
+    fn minmax(&self) -> Option<(i64, i64)> {
+        (self.0 > 0).then(|| (0, self.0 as i64 - 1))
+    }
+}
+
+impl F64Column for IndexCol {
+    fn len(&self) -> usize {
+        self.0
+    }
+    fn len_some(&self) -> usize {
+        self.0
+    }
+    fn f64_iter(&self) -> Box<dyn Iterator<Item = Option<f64>> + '_> {
+        Box::new((0..self.0).map(|i| Some(i as f64)))
+    }
+    fn minmax(&self) -> Option<(f64, f64)> {
+        (self.0 > 0).then(|| (0.0, (self.0 - 1) as f64))
+    }
+}
+
+impl Column for IndexCol {
+    fn len(&self) -> usize {
+        self.0
+    }
+    fn len_some(&self) -> usize {
+        self.0
+    }
+    fn i64(&self) -> Option<&dyn I64Column> {
+        Some(self)
+    }
+    fn f64(&self) -> Option<&dyn F64Column> {
+        Some(self)
+    }
+}
+
 /// Column implementation for a slice of string-like values
 #[derive(Debug)]
 pub struct SCol<'a, T>(pub &'a [T]);
@@ -1324,6 +1411,94 @@ impl Column for Vec<Option<TimeDelta>> {
     }
 }
 
+/// Column of categories, encoded as integer codes into an ordered list of categories.
+///
+/// Each distinct category is stored once and the samples refer to it by its code.
+/// Categories are ordered by first appearance, which is also the order in which
+/// they are placed along a categorical axis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CategoricalColumn {
+    codes: Vec<u32>,
+    categories: Vec<String>,
+}
+
+impl CategoricalColumn {
+    /// Build the encoding of the given values
+    pub fn new(values: Vec<String>) -> Self {
+        let mut index: std::collections::HashMap<String, u32> = Default::default();
+        let mut categories = Vec::new();
+        let codes = values
+            .into_iter()
+            .map(|v| {
+                if let Some(code) = index.get(&v) {
+                    return *code;
+                }
+                let code = categories.len() as u32;
+                categories.push(v.clone());
+                index.insert(v, code);
+                code
+            })
+            .collect();
+        CategoricalColumn { codes, categories }
+    }
+
+    /// Get the code of each sample, indexing into [`Self::categories`]
+    pub fn codes(&self) -> &[u32] {
+        &self.codes
+    }
+
+    /// Get the distinct categories, in order of first appearance
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    /// Get the code of the given category, if present in the column
+    pub fn code_of(&self, category: &str) -> Option<u32> {
+        self.categories
+            .iter()
+            .position(|c| c == category)
+            .map(|i| i as u32)
+    }
+}
+
+impl From<Vec<String>> for CategoricalColumn {
+    fn from(values: Vec<String>) -> Self {
+        CategoricalColumn::new(values)
+    }
+}
+
+impl StrColumn for CategoricalColumn {
+    fn len(&self) -> usize {
+        self.codes.len()
+    }
+    fn len_some(&self) -> usize {
+        self.codes.len()
+    }
+    fn str_iter(&self) -> Box<dyn Iterator<Item = Option<&str>> + '_> {
+        Box::new(
+            self.codes
+                .iter()
+                .map(|c| Some(self.categories[*c as usize].as_str())),
+        )
+    }
+}
+
+impl Column for CategoricalColumn {
+    fn len(&self) -> usize {
+        self.codes.len()
+    }
+    fn len_some(&self) -> usize {
+        self.codes.len()
+    }
+    fn str(&self) -> Option<&dyn StrColumn> {
+        Some(self)
+    }
+    fn boxed_copy(&self) -> Box<dyn Column> {
+        Box::new(self.clone())
+    }
+}
+
 /// Simple collection of named columns, owning the data
 #[derive(Debug)]
 pub struct NamedOwnedColumns {
@@ -1422,11 +1597,176 @@ impl<'a> Source for NamedColumns<'a> {
     }
 }
 
+/// Filtered view over the rows of a data source, without copying the data.
+///
+/// The rows are selected by a predicate on the samples of a key column.
+/// All the columns of the view yield only the selected rows.
+#[derive(Debug)]
+pub struct Filtered<'a> {
+    names: Vec<&'a str>,
+    columns: Vec<FilteredColumn<'a>>,
+    len: usize,
+}
+
+impl<'a> Filtered<'a> {
+    /// Create a view of `source` selecting the rows for which `predicate`
+    /// returns true on the sample of the `key` column.
+    /// Returns None if `source` has no `key` column.
+    pub fn new<S, F>(source: &'a S, key: &str, predicate: F) -> Option<Self>
+    where
+        S: Source + ?Sized,
+        F: Fn(SampleRef<'_>) -> bool,
+    {
+        let mask: Arc<[bool]> = source.column(key)?.sample_iter().map(predicate).collect();
+        let len = mask.iter().filter(|keep| **keep).count();
+        let names = source.names();
+        let columns = names
+            .iter()
+            .map(|name| FilteredColumn {
+                col: source.column(name).unwrap(),
+                mask: mask.clone(),
+                len,
+            })
+            .collect();
+        Some(Filtered {
+            names,
+            columns,
+            len,
+        })
+    }
+
+    /// Get the number of selected rows
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether no row is selected
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Source for Filtered<'_> {
+    fn names(&self) -> Vec<&str> {
+        self.names.clone()
+    }
+
+    fn column(&self, name: &str) -> Option<&dyn Column> {
+        let idx = self.names.iter().position(|k| *k == name)?;
+        Some(&self.columns[idx])
+    }
+}
+
+/// Column of a [`Filtered`] view
+#[derive(Debug)]
+struct FilteredColumn<'a> {
+    col: &'a dyn Column,
+    mask: Arc<[bool]>,
+    len: usize,
+}
+
+impl FilteredColumn<'_> {
+    fn select<'s, T: 's>(
+        &'s self,
+        iter: Box<dyn Iterator<Item = T> + 's>,
+    ) -> Box<dyn Iterator<Item = T> + 's> {
+        Box::new(
+            iter.zip(self.mask.iter())
+                .filter_map(|(v, keep)| keep.then_some(v)),
+        )
+    }
+}
+
+impl Column for FilteredColumn<'_> {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn len_some(&self) -> usize {
+        self.sample_iter().filter(|s| !s.is_null()).count()
+    }
+
+    fn sample_iter(&self) -> Box<dyn Iterator<Item = SampleRef<'_>> + '_> {
+        self.select(self.col.sample_iter())
+    }
+
+    fn f64(&self) -> Option<&dyn F64Column> {
+        self.col.f64().map(|_| self as &dyn F64Column)
+    }
+
+    fn i64(&self) -> Option<&dyn I64Column> {
+        self.col.i64().map(|_| self as &dyn I64Column)
+    }
+
+    fn str(&self) -> Option<&dyn StrColumn> {
+        self.col.str().map(|_| self as &dyn StrColumn)
+    }
+
+    #[cfg(feature = "time")]
+    fn time(&self) -> Option<&dyn TimeColumn> {
+        self.col.time().map(|_| self as &dyn TimeColumn)
+    }
+
+    #[cfg(feature = "time")]
+    fn time_delta(&self) -> Option<&dyn TimeDeltaColumn> {
+        self.col.time_delta().map(|_| self as &dyn TimeDeltaColumn)
+    }
+}
+
+impl F64Column for FilteredColumn<'_> {
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn f64_iter(&self) -> Box<dyn Iterator<Item = Option<f64>> + '_> {
+        self.select(self.col.f64().unwrap().f64_iter())
+    }
+}
+
+impl I64Column for FilteredColumn<'_> {
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn i64_iter(&self) -> Box<dyn Iterator<Item = Option<i64>> + '_> {
+        self.select(self.col.i64().unwrap().i64_iter())
+    }
+}
+
+impl StrColumn for FilteredColumn<'_> {
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn str_iter(&self) -> Box<dyn Iterator<Item = Option<&str>> + '_> {
+        self.select(self.col.str().unwrap().str_iter())
+    }
+}
+
+#[cfg(feature = "time")]
+impl TimeColumn for FilteredColumn<'_> {
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn time_iter(&self) -> Box<dyn Iterator<Item = Option<DateTime>> + '_> {
+        self.select(self.col.time().unwrap().time_iter())
+    }
+}
+
+#[cfg(feature = "time")]
+impl TimeDeltaColumn for FilteredColumn<'_> {
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn time_delta_iter(&self) -> Box<dyn Iterator<Item = Option<TimeDelta>> + '_> {
+        self.select(self.col.time_delta().unwrap().time_delta_iter())
+    }
+}
+
 /// Column implementation backed by vectors, type known at runtime
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VecColumn {
-    /// f64 column
-    F64(Vec<f64>),
+    /// f64 column.
+    /// When serialized, NaN values are represented as null.
+    F64(#[cfg_attr(feature = "serde", serde(with = "serde_f64_nan"))] Vec<f64>),
     /// i64 column
     I64(Vec<Option<i64>>),
     /// string column
@@ -1439,6 +1779,26 @@ pub enum VecColumn {
     TimeDelta(Vec<Option<TimeDelta>>),
 }
 
+/// Serde support for f64 columns, where missing values (NaN) are represented as `None`
+#[cfg(feature = "serde")]
+mod serde_f64_nan {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(vals: &[f64], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            vals.iter()
+                .map(|v| if v.is_nan() { None } else { Some(*v) }),
+        )
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<f64>, D::Error> {
+        let vals: Vec<Option<f64>> = Vec::deserialize(deserializer)?;
+        Ok(vals.into_iter().map(|v| v.unwrap_or(f64::NAN)).collect())
+    }
+}
+
 impl From<Vec<f64>> for VecColumn {
     fn from(v: Vec<f64>) -> Self {
         VecColumn::F64(v)
@@ -1471,6 +1831,20 @@ impl From<Vec<String>> for VecColumn {
     }
 }
 
+impl VecColumn {
+    fn sample_at(&self, index: usize) -> SampleRef<'_> {
+        match self {
+            VecColumn::F64(v) => v.get(index).copied().into(),
+            VecColumn::I64(v) => v.get(index).copied().flatten().into(),
+            VecColumn::Str(v) => v.get(index).and_then(|s| s.as_deref()).into(),
+            #[cfg(feature = "time")]
+            VecColumn::Time(v) => v.get(index).copied().flatten().into(),
+            #[cfg(feature = "time")]
+            VecColumn::TimeDelta(v) => v.get(index).copied().flatten().into(),
+        }
+    }
+}
+
 impl Column for VecColumn {
     fn len(&self) -> usize {
         match self {
@@ -1646,10 +2020,46 @@ impl TableSource {
         self
     }
 
+    /// Add a f64 column computed row by row from the other columns, returning self for chaining.
+    ///
+    /// `f` returns `None` for a null value, so that nulls propagate with `?`
+    /// on the [`TableRow`] accessors.
+    pub fn with_derived<F>(mut self, name: &str, f: F) -> Self
+    where
+        F: Fn(TableRow<'_>) -> Option<f64>,
+    {
+        let col = (0..self.len)
+            .map(|index| {
+                f(TableRow {
+                    table: &self,
+                    index,
+                })
+                .unwrap_or(f64::NAN)
+            })
+            .collect();
+        self.add_column(name, VecColumn::F64(col));
+        self
+    }
+
+    /// Add a f64 column by mapping the values of the `src` column, returning self for chaining.
+    /// Null values, and values of a missing or non-numeric `src` column, remain null.
+    pub fn with_f64_map<F>(self, name: &str, src: &str, f: F) -> Self
+    where
+        F: Fn(f64) -> f64,
+    {
+        self.with_derived(name, |row| row.f64(src).map(&f))
+    }
+
     /// Get the number of rows in the table
     pub fn len(&self) -> usize {
         self.len
     }
+
+    /// Consume the table and return its named columns
+    #[cfg(feature = "dsl")]
+    pub(crate) fn into_columns(self) -> impl Iterator<Item = (String, VecColumn)> {
+        self.heads.into_iter().zip(self.columns)
+    }
 }
 
 impl Source for TableSource {
@@ -1669,6 +2079,53 @@ impl Source for TableSource {
     }
 }
 
+/// A row of a [`TableSource`], given to [`TableSource::with_derived`]
+#[derive(Clone, Copy)]
+pub struct TableRow<'a> {
+    table: &'a TableSource,
+    index: usize,
+}
+
+impl std::fmt::Debug for TableRow<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TableRow")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a> TableRow<'a> {
+    /// Get the index of the row in the table
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the row `delta` rows away from this one, if it is within the table.
+    /// Useful for differences and rolling windows.
+    pub fn offset(&self, delta: isize) -> Option<TableRow<'a>> {
+        let index = self.index.checked_add_signed(delta)?;
+        (index < self.table.len).then_some(TableRow {
+            table: self.table,
+            index,
+        })
+    }
+
+    /// Get the sample of the named column in this row.
+    /// Returns [`SampleRef::Null`] if there is no such column.
+    pub fn sample(&self, name: &str) -> SampleRef<'a> {
+        let Some(idx) = self.table.heads.iter().position(|k| k == name) else {
+            return SampleRef::Null;
+        };
+        self.table.columns[idx].sample_at(self.index)
+    }
+
+    /// Get the numeric value of the named column in this row,
+    /// or `None` if null or not numeric.
+    pub fn f64(&self, name: &str) -> Option<f64> {
+        self.sample(name).as_num()
+    }
+}
+
 /// Custom Debug implementation to pretty-print the table
 impl std::fmt::Debug for TableSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -1796,3 +2253,114 @@ impl std::fmt::Debug for TableSource {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_from_non_finite() {
+        assert_eq!(SampleRef::from(1.5), SampleRef::Num(1.5));
+        assert_eq!(SampleRef::from(f64::NAN), SampleRef::Null);
+        assert_eq!(SampleRef::from(f64::INFINITY), SampleRef::Null);
+        assert_eq!(SampleRef::from(f64::NEG_INFINITY), SampleRef::Null);
+
+        assert_eq!(Sample::from(1.5), Sample::Num(1.5));
+        assert_eq!(Sample::from(f64::NAN), Sample::Null);
+        assert_eq!(Sample::from(f64::INFINITY), Sample::Null);
+        assert_eq!(Sample::from(f64::NEG_INFINITY), Sample::Null);
+    }
+
+    #[test]
+    fn test_non_null_runs() {
+        let runs = |col: &dyn Column| col.non_null_runs().collect::<Vec<_>>();
+
+        let col = vec![1.0, 2.0, f64::NAN, 3.0, f64::NAN, f64::NAN, 4.0, 5.0, 6.0];
+        assert_eq!(runs(&col), vec![(0, 2), (3, 4), (6, 9)]);
+
+        let col = vec![f64::NAN, 1.0, f64::INFINITY];
+        assert_eq!(runs(&col), vec![(1, 2)]);
+
+        let col = vec![None, Some("a".to_string()), Some("b".to_string())];
+        assert_eq!(runs(&col), vec![(1, 3)]);
+
+        let col: Vec<f64> = vec![f64::NAN, f64::NAN];
+        assert!(runs(&col).is_empty());
+        let col: Vec<f64> = Vec::new();
+        assert!(runs(&col).is_empty());
+    }
+
+    #[test]
+    fn test_table_derived() {
+        let table = TableSource::new()
+            .with_f64_column("a", vec![1.0, 2.0, f64::NAN, 4.0])
+            .with_i64_column("b", vec![Some(2), Some(4), Some(1), None])
+            .with_derived("ratio", |row| Some(row.f64("a")? / row.f64("b")?))
+            .with_derived("sum2", |row| {
+                (0..2)
+                    .map(|d| row.offset(-d)?.f64("b"))
+                    .sum::<Option<f64>>()
+            })
+            .with_f64_map("twice", "a", |a| a * 2.0)
+            .with_f64_map("missing", "c", |c| c);
+
+        let vals = |name: &str| -> Vec<Option<f64>> {
+            let col = table.column(name).unwrap().f64().unwrap();
+            col.f64_iter().collect()
+        };
+        assert_eq!(vals("ratio"), vec![Some(0.5), Some(0.5), None, None]);
+        assert_eq!(vals("sum2"), vec![None, Some(6.0), Some(5.0), None]);
+        assert_eq!(vals("twice"), vec![Some(2.0), Some(4.0), None, Some(8.0)]);
+        assert_eq!(vals("missing"), vec![None; 4]);
+    }
+
+    #[test]
+    fn test_filtered() {
+        let table = TableSource::new()
+            .with_str_column(
+                "cat",
+                ["a", "b", "a", "c", "a"]
+                    .iter()
+                    .map(|s| Some(s.to_string()))
+                    .collect(),
+            )
+            .with_f64_column("x", vec![1.0, 2.0, f64::NAN, 4.0, 5.0])
+            .with_i64_column("n", vec![Some(1), Some(2), Some(3), None, None]);
+
+        let view = Filtered::new(&table, "cat", |s| s.as_cat() == Some("a")).unwrap();
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.names(), vec!["cat", "x", "n"]);
+
+        let x = view.column("x").unwrap();
+        assert_eq!(x.len(), 3);
+        assert_eq!(x.len_some(), 2);
+        let x: Vec<_> = x.f64().unwrap().f64_iter().collect();
+        assert_eq!(x, vec![Some(1.0), None, Some(5.0)]);
+
+        let n: Vec<_> = view.column("n").unwrap().as_i64_iter().unwrap().collect();
+        assert_eq!(n, vec![Some(1), Some(3), None]);
+        assert!(view.column("cat").unwrap().f64().is_none());
+
+        let copy = view.copy();
+        let cats: Vec<_> = copy.column("cat").unwrap().as_str_iter().unwrap().collect();
+        assert_eq!(cats, vec![Some("a"); 3]);
+
+        assert!(Filtered::new(&table, "missing", |_| true).is_none());
+    }
+
+    #[test]
+    fn test_categorical_column() {
+        let values = ["b", "a", "b", "c", "a"];
+        let col = CategoricalColumn::new(values.iter().map(|s| s.to_string()).collect());
+        assert_eq!(col.codes(), &[0, 1, 0, 2, 1]);
+        assert_eq!(col.categories(), &["b", "a", "c"]);
+        assert_eq!(col.code_of("c"), Some(2));
+        assert_eq!(col.code_of("d"), None);
+
+        let col: &dyn Column = &col;
+        assert_eq!(col.len(), 5);
+        assert_eq!(col.len_some(), 5);
+        let strs: Vec<_> = col.str().unwrap().str_iter().map(Option::unwrap).collect();
+        assert_eq!(strs, values);
+    }
+}
@@ -0,0 +1,348 @@
+//! Group-by aggregation of a data source into a summary table.
+use std::collections::HashMap;
+
+use super::{Column, SampleRef, Source, TableSource, VecColumn};
+
+/// Aggregation function applied to the values of each group
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Agg {
+    /// Sum of the values
+    Sum,
+    /// Arithmetic mean of the values
+    Mean,
+    /// Minimum value
+    Min,
+    /// Maximum value
+    Max,
+    /// Number of non-null values.
+    /// This is the only aggregation that accepts non-numeric value columns.
+    Count,
+    /// Median of the values
+    Median,
+}
+
+/// Group-by aggregation error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupByError {
+    /// A unknown column name was referenced
+    UnknownCol {
+        /// Column name
+        name: String,
+    },
+    /// The value column is not numeric and can't be aggregated
+    NotNumeric {
+        /// Column name
+        name: String,
+    },
+}
+
+impl std::fmt::Display for GroupByError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroupByError::UnknownCol { name } => write!(f, "Unknown column {name}"),
+            GroupByError::NotNumeric { name } => {
+                write!(f, "Column {name} is not numeric and can't be aggregated")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GroupByError {}
+
+/// Group the rows of a source by the values of a key column,
+/// and aggregate the values of another column for each group.
+///
+/// See [`group_by`] for a shortcut with the default options.
+#[derive(Debug, Clone)]
+pub struct GroupBy {
+    key_col: String,
+    value_col: String,
+    agg: Agg,
+    null_keys: bool,
+}
+
+impl GroupBy {
+    /// Create a new group-by aggregation of `value_col` grouped by `key_col`
+    pub fn new(key_col: &str, value_col: &str, agg: Agg) -> Self {
+        GroupBy {
+            key_col: key_col.to_string(),
+            value_col: value_col.to_string(),
+            agg,
+            null_keys: false,
+        }
+    }
+
+    /// Set whether the rows with a null key form their own group, and return self for chaining.
+    /// By default, these rows are dropped.
+    pub fn with_null_keys(self, null_keys: bool) -> Self {
+        GroupBy { null_keys, ..self }
+    }
+
+    /// Get the key column name
+    pub fn key_col(&self) -> &str {
+        &self.key_col
+    }
+
+    /// Get the value column name
+    pub fn value_col(&self) -> &str {
+        &self.value_col
+    }
+
+    /// Get the aggregation function
+    pub fn agg(&self) -> Agg {
+        self.agg
+    }
+
+    /// Get whether the rows with a null key form their own group
+    pub fn null_keys(&self) -> bool {
+        self.null_keys
+    }
+
+    /// Apply the aggregation to `source`.
+    ///
+    /// The returned table has one row per group, in order of first appearance of the keys,
+    /// with the keys in a column named after the key column,
+    /// and the aggregated values in a column named after the value column.
+    /// Groups without any non-null value have a null aggregated value, except for [`Agg::Count`].
+    pub fn apply<S>(&self, source: &S) -> Result<TableSource, GroupByError>
+    where
+        S: Source + ?Sized,
+    {
+        let key_col = column(source, &self.key_col)?;
+        let value_col = column(source, &self.value_col)?;
+
+        let values: Box<dyn Iterator<Item = Option<f64>>> = if self.agg == Agg::Count {
+            Box::new(
+                value_col
+                    .sample_iter()
+                    .map(|s| (!s.is_null()).then_some(0.0)),
+            )
+        } else {
+            value_col
+                .as_f64_iter()
+                .ok_or_else(|| GroupByError::NotNumeric {
+                    name: self.value_col.clone(),
+                })?
+        };
+
+        let mut index: HashMap<GroupKey, usize> = HashMap::new();
+        let mut keys: Vec<SampleRef> = Vec::new();
+        let mut groups: Vec<Vec<f64>> = Vec::new();
+        for (key, value) in key_col.sample_iter().zip(values) {
+            if key.is_null() && !self.null_keys {
+                continue;
+            }
+            let idx = *index.entry(GroupKey::new(key)).or_insert_with(|| {
+                keys.push(key);
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            if let Some(value) = value {
+                groups[idx].push(value);
+            }
+        }
+
+        let key_col = key_column(key_col, &keys);
+        let value_col = if self.agg == Agg::Count {
+            VecColumn::I64(groups.iter().map(|g| Some(g.len() as i64)).collect())
+        } else {
+            VecColumn::F64(
+                groups
+                    .into_iter()
+                    .map(|g| aggregate(self.agg, g).unwrap_or(f64::NAN))
+                    .collect(),
+            )
+        };
+        Ok(TableSource::new()
+            .with_column(&self.key_col, key_col)
+            .with_column(&self.value_col, value_col))
+    }
+}
+
+/// Group the rows of `source` by the values of `key_col`,
+/// and aggregate the values of `value_col` for each group with `agg`.
+/// The rows with a null key are dropped.
+///
+/// The returned table can be plotted directly, for example as a bar chart.
+/// See [`GroupBy`] for more options.
+pub fn group_by<S>(
+    source: &S,
+    key_col: &str,
+    value_col: &str,
+    agg: Agg,
+) -> Result<TableSource, GroupByError>
+where
+    S: Source + ?Sized,
+{
+    GroupBy::new(key_col, value_col, agg).apply(source)
+}
+
+fn column<'a, S>(source: &'a S, name: &str) -> Result<&'a dyn Column, GroupByError>
+where
+    S: Source + ?Sized,
+{
+    source.column(name).ok_or_else(|| GroupByError::UnknownCol {
+        name: name.to_string(),
+    })
+}
+
+/// Hashable version of a key sample
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum GroupKey<'a> {
+    Null,
+    Num(u64),
+    Cat(&'a str),
+    #[cfg(feature = "time")]
+    Time(u64),
+    #[cfg(feature = "time")]
+    TimeDelta(u64),
+}
+
+impl<'a> GroupKey<'a> {
+    fn new(sample: SampleRef<'a>) -> Self {
+        match sample {
+            SampleRef::Null => GroupKey::Null,
+            // normalize the zero sign
+            SampleRef::Num(v) => GroupKey::Num((v + 0.0).to_bits()),
+            SampleRef::Cat(v) => GroupKey::Cat(v),
+            #[cfg(feature = "time")]
+            SampleRef::Time(v) => GroupKey::Time(v.timestamp().to_bits()),
+            #[cfg(feature = "time")]
+            SampleRef::TimeDelta(v) => GroupKey::TimeDelta(v.seconds().to_bits()),
+        }
+    }
+}
+
+/// Build the key column of the aggregated table, with the same type as the source key column
+fn key_column(col: &dyn Column, keys: &[SampleRef]) -> VecColumn {
+    if col.i64().is_some() {
+        return VecColumn::I64(keys.iter().map(|k| k.as_num().map(|v| v as i64)).collect());
+    }
+    #[cfg(feature = "time")]
+    if col.time().is_some() {
+        return VecColumn::Time(keys.iter().map(SampleRef::as_time).collect());
+    } else if col.time_delta().is_some() {
+        return VecColumn::TimeDelta(keys.iter().map(SampleRef::as_time_delta).collect());
+    }
+    if col.str().is_some() {
+        VecColumn::Str(
+            keys.iter()
+                .map(|k| k.as_cat().map(str::to_string))
+                .collect(),
+        )
+    } else {
+        VecColumn::F64(
+            keys.iter()
+                .map(|k| k.as_num().unwrap_or(f64::NAN))
+                .collect(),
+        )
+    }
+}
+
+fn aggregate(agg: Agg, mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let res = match agg {
+        Agg::Sum => values.iter().sum(),
+        Agg::Mean => values.iter().sum::<f64>() / values.len() as f64,
+        Agg::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+        Agg::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        Agg::Count => values.len() as f64,
+        Agg::Median => {
+            values.sort_by(|a, b| a.total_cmp(b));
+            let mid = values.len() / 2;
+            if values.len().is_multiple_of(2) {
+                (values[mid - 1] + values[mid]) / 2.0
+            } else {
+                values[mid]
+            }
+        }
+    };
+    Some(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> TableSource {
+        let cat = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        TableSource::new()
+            .with_str_column(
+                "cat",
+                vec![cat("a"), cat("b"), cat("a"), cat(""), cat("b"), cat("a")],
+            )
+            .with_f64_column("val", vec![1.0, 2.0, 5.0, 10.0, f64::NAN, 3.0])
+            .with_i64_column("n", vec![Some(1), Some(2), Some(1), Some(2), None, None])
+    }
+
+    fn col<T>(table: &TableSource, name: &str, f: impl Fn(SampleRef) -> T) -> Vec<T> {
+        table.column(name).unwrap().sample_iter().map(f).collect()
+    }
+
+    #[test]
+    fn test_group_by_agg() {
+        let table = table();
+        let res = |agg| {
+            let res = group_by(&table, "cat", "val", agg).unwrap();
+            assert_eq!(
+                col(&res, "cat", |s| s.as_cat().unwrap().to_string()),
+                ["a", "b"]
+            );
+            col(&res, "val", |s| s.as_num())
+        };
+        assert_eq!(res(Agg::Sum), [Some(9.0), Some(2.0)]);
+        assert_eq!(res(Agg::Mean), [Some(3.0), Some(2.0)]);
+        assert_eq!(res(Agg::Min), [Some(1.0), Some(2.0)]);
+        assert_eq!(res(Agg::Max), [Some(5.0), Some(2.0)]);
+        assert_eq!(res(Agg::Count), [Some(3.0), Some(1.0)]);
+        assert_eq!(res(Agg::Median), [Some(3.0), Some(2.0)]);
+    }
+
+    #[test]
+    fn test_group_by_null_keys() {
+        let table = table();
+        let res = GroupBy::new("cat", "val", Agg::Sum)
+            .with_null_keys(true)
+            .apply(&table)
+            .unwrap();
+        assert_eq!(
+            col(&res, "cat", |s| s.as_cat().map(str::to_string)),
+            [Some("a".to_string()), Some("b".to_string()), None]
+        );
+        assert_eq!(
+            col(&res, "val", |s| s.as_num()),
+            [Some(9.0), Some(2.0), Some(10.0)]
+        );
+    }
+
+    #[test]
+    fn test_group_by_num_key() {
+        let table = table();
+        let res = group_by(&table, "n", "cat", Agg::Count).unwrap();
+        assert!(res.column("n").unwrap().i64().is_some());
+        assert_eq!(col(&res, "n", |s| s.as_num()), [Some(1.0), Some(2.0)]);
+        assert_eq!(col(&res, "cat", |s| s.as_num()), [Some(2.0), Some(1.0)]);
+
+        let res = group_by(&table, "n", "val", Agg::Median).unwrap();
+        assert_eq!(col(&res, "val", |s| s.as_num()), [Some(3.0), Some(6.0)]);
+    }
+
+    #[test]
+    fn test_group_by_errors() {
+        let table = table();
+        assert_eq!(
+            group_by(&table, "missing", "val", Agg::Sum).unwrap_err(),
+            GroupByError::UnknownCol {
+                name: "missing".to_string()
+            }
+        );
+        assert_eq!(
+            group_by(&table, "val", "cat", Agg::Mean).unwrap_err(),
+            GroupByError::NotNumeric {
+                name: "cat".to_string()
+            }
+        );
+    }
+}
@@ -2,6 +2,13 @@
  * # Declarative design module for plotive
  *
  * This module contains all data structures for the design of plotting figures.
+ *
+ * With the `serde` feature, all design types implement `Serialize` and `Deserialize`,
+ * so that figures can be exchanged with other programs, e.g. as JSON.
+ * The schema follows the Rust types: struct fields keep their names, and enum variants
+ * are externally tagged (e.g. `{"Line": {...}}`), except for [`DataCol`] where a
+ * string is a reference to a data source column and an object is inline data.
+ * Missing optional fields are deserialized as `None`.
  */
 pub mod annot;
 pub mod axis;
@@ -12,13 +19,35 @@ pub mod series;
 
 pub use annot::Annotation;
 pub use axis::Axis;
-pub use figure::{FigLegend, Figure};
+pub use figure::{FigLegend, Figure, Watermark};
 pub use legend::Legend;
-pub use plot::{Plot, PlotLegend, Subplots};
+pub use plot::{FreeformLayout, Plot, PlotLegend, Subplots, ZoomInset};
 pub use series::{DataCol, Series, data_inline, data_src_ref};
 
+/// Drawing order of the elements of the plot area.
+///
+/// Elements are drawn by increasing z-order, so that higher z-orders are drawn on top.
+/// Elements with the same z-order are drawn in this order: grids, series and annotations,
+/// each in the order they are defined.
+/// The constants define the default z-order of the different elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZOrder(pub i32);
+
+impl ZOrder {
+    /// Default z-order of grids
+    pub const GRID: ZOrder = ZOrder(-20);
+    /// Default z-order of annotation lines and spans
+    pub const BELOW_SERIES: ZOrder = ZOrder(-10);
+    /// Default z-order of series
+    pub const SERIES: ZOrder = ZOrder(0);
+    /// Default z-order of annotations other than lines
+    pub const ABOVE_SERIES: ZOrder = ZOrder(10);
+}
+
 /// Index of a plot in a subplot grid
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PlotIdx {
     /// Row index of the plot (0-based)
     pub row: u32,
@@ -36,6 +65,14 @@ impl PlotIdx {
         (self.row * cols + self.col) as usize
     }
 
+    pub(crate) fn from_index(index: usize, cols: u32) -> Self {
+        let index = index as u32;
+        PlotIdx {
+            row: index / cols,
+            col: index % cols,
+        }
+    }
+
     pub(crate) fn is_first(&self) -> bool {
         self.row == 0 && self.col == 0
     }
@@ -110,6 +147,7 @@ macro_rules! define_rich_text_structs {
 
         /// Rich text base properties with plotive theme colors
         #[derive(Debug, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $props_struct($crate::text::rich::TextProps<$crate::style::theme::Color>);
 
         impl $props_struct {
@@ -165,18 +203,25 @@ macro_rules! define_rich_text_structs {
                 self.0.outline()
             }
 
-            /// Check if strikeout is enabled
+            /// Check if underline is enabled
             pub fn underline(&self) -> bool {
                 self.0.underline()
             }
+
+            /// Check if strikeout is enabled
+            pub fn strikeout(&self) -> bool {
+                self.0.strikeout()
+            }
         }
 
         /// Rich text structure with plotive theme colors
         #[derive(Debug, Clone)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         pub struct $text_struct {
             text: String,
             props: $props_struct,
             spans: Vec<(usize, usize, $opt_props_struct)>,
+            math: Vec<$crate::text::rich::MathElem>,
         }
 
         impl From<String> for $text_struct {
@@ -185,6 +230,7 @@ macro_rules! define_rich_text_structs {
                     text,
                     props: $props_struct::default(),
                     spans: Vec::new(),
+                    math: Vec::new(),
                 }
             }
         }
@@ -195,6 +241,7 @@ macro_rules! define_rich_text_structs {
                     text: text.to_string(),
                     props: $props_struct::default(),
                     spans: Vec::new(),
+                    math: Vec::new(),
                 }
             }
         }
@@ -205,6 +252,7 @@ macro_rules! define_rich_text_structs {
                     text: text.text,
                     props: $props_struct::default(),
                     spans: text.prop_spans,
+                    math: text.math,
                 }
             }
         }
@@ -249,6 +297,9 @@ macro_rules! define_rich_text_structs {
                 for (start, end, props) in &self.spans {
                     builder.add_span(*start, *end, props.clone());
                 }
+                for elem in &self.math {
+                    builder.add_math(*elem);
+                }
                 builder.done(db)
             }
         }
@@ -1,16 +1,19 @@
 //! Annotations to place on the plot area.
-use crate::des::axis;
+use crate::des::{ZOrder, axis};
 use crate::style::{self, theme};
 use crate::text::Font;
 
 /// An arbitrary graphical annotation placed on the plot area.
 /// The placement is made according to the data coordinates.
-/// By default, lines are plotted under the series, and other annotations are plotted above the series.
-/// This can be changed using [`with_zpos()`](Annotation::with_zpos).
+/// By default, lines and spans are plotted under the series, and other annotations are plotted above the series.
+/// This can be changed using [`with_zpos()`](Annotation::with_zpos) or [`with_zorder()`](Annotation::with_zorder).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Annotation {
     /// A line plotted on the plot area.
     Line(Line),
+    /// A shaded region between two values of an axis.
+    Span(Span),
     /// An arrow plotted on the plot area.
     Arrow(Arrow),
     /// A marker plotted on the plot area.
@@ -25,6 +28,12 @@ impl From<Line> for Annotation {
     }
 }
 
+impl From<Span> for Annotation {
+    fn from(span: Span) -> Self {
+        Annotation::Span(span)
+    }
+}
+
 impl From<Arrow> for Annotation {
     fn from(arrow: Arrow) -> Self {
         Annotation::Arrow(arrow)
@@ -47,6 +56,7 @@ impl Annotation {
     pub(crate) fn pos_mut(&mut self) -> &mut Pos {
         match self {
             Annotation::Line(line) => &mut line.pos,
+            Annotation::Span(span) => &mut span.pos,
             Annotation::Arrow(arrow) => &mut arrow.pos,
             Annotation::Marker(marker) => &mut marker.pos,
             Annotation::Label(label) => &mut label.pos,
@@ -71,13 +81,20 @@ impl Annotation {
 
     /// Set the z-position of this annotation in relation to the series.
     pub fn with_zpos(mut self, zpos: ZPos) -> Self {
-        self.pos_mut().zpos = zpos;
+        self.pos_mut().zorder = zpos.into();
+        self
+    }
+
+    /// Set the z-order of this annotation, for finer control than [`with_zpos()`](Annotation::with_zpos).
+    pub fn with_zorder(mut self, zorder: ZOrder) -> Self {
+        self.pos_mut().zorder = zorder;
         self
     }
 }
 
 /// Positioning information for annotations placed on the plot area.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ZPos {
     /// Annotation displayed below the series
     BelowSeries,
@@ -85,17 +102,28 @@ pub enum ZPos {
     AboveSeries,
 }
 
+impl From<ZPos> for ZOrder {
+    fn from(zpos: ZPos) -> Self {
+        match zpos {
+            ZPos::BelowSeries => ZOrder::BELOW_SERIES,
+            ZPos::AboveSeries => ZOrder::ABOVE_SERIES,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct Pos {
     pub(crate) x: f64,
     pub(crate) y: f64,
     pub(crate) x_axis: axis::Ref,
     pub(crate) y_axis: axis::Ref,
-    pub(crate) zpos: ZPos,
+    pub(crate) zorder: ZOrder,
 }
 
 /// A line plotted on the plot area.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Line {
     pub(crate) direction: Direction,
     pub(crate) line: theme::Stroke,
@@ -104,6 +132,7 @@ pub struct Line {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) enum Direction {
     Horizontal,
     Vertical,
@@ -122,7 +151,7 @@ impl Line {
                 y: 0.0,
                 x_axis: Default::default(),
                 y_axis: Default::default(),
-                zpos: ZPos::BelowSeries,
+                zorder: ZOrder::BELOW_SERIES,
             },
         }
     }
@@ -137,7 +166,7 @@ impl Line {
                 y,
                 x_axis: Default::default(),
                 y_axis: Default::default(),
-                zpos: ZPos::BelowSeries,
+                zorder: ZOrder::BELOW_SERIES,
             },
         }
     }
@@ -154,7 +183,7 @@ impl Line {
                 y,
                 x_axis: Default::default(),
                 y_axis: Default::default(),
-                zpos: ZPos::BelowSeries,
+                zorder: ZOrder::BELOW_SERIES,
             },
         }
     }
@@ -169,7 +198,7 @@ impl Line {
                 y: y1,
                 x_axis: Default::default(),
                 y_axis: Default::default(),
-                zpos: ZPos::BelowSeries,
+                zorder: ZOrder::BELOW_SERIES,
             },
         }
     }
@@ -189,8 +218,72 @@ impl Line {
     }
 }
 
+/// A region of the plot area shaded between two values of an axis.
+/// A vertical span covers the whole height of the plot between two X values,
+/// and a horizontal span covers the whole width of the plot between two Y values.
+/// A `None` bound extends the span to the edge of the plot area.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    pub(crate) direction: SpanDirection,
+    pub(crate) start: Option<f64>,
+    pub(crate) end: Option<f64>,
+    pub(crate) fill: theme::Fill,
+
+    pub(crate) pos: Pos,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) enum SpanDirection {
+    Horizontal,
+    Vertical,
+}
+
+impl Span {
+    /// Shade a vertical region between the X values start and end
+    pub fn vertical(start: Option<f64>, end: Option<f64>) -> Self {
+        Self::new(SpanDirection::Vertical, start, end)
+    }
+
+    /// Shade a horizontal region between the Y values start and end
+    pub fn horizontal(start: Option<f64>, end: Option<f64>) -> Self {
+        Self::new(SpanDirection::Horizontal, start, end)
+    }
+
+    fn new(direction: SpanDirection, start: Option<f64>, end: Option<f64>) -> Self {
+        Span {
+            direction,
+            start,
+            end,
+            fill: theme::Fill::from(theme::Col::Foreground).with_opacity(0.15),
+            pos: Pos {
+                x: 0.0,
+                y: 0.0,
+                x_axis: Default::default(),
+                y_axis: Default::default(),
+                zorder: ZOrder::BELOW_SERIES,
+            },
+        }
+    }
+
+    /// Set the fill of the span.
+    /// By default, the foreground theme color is used with an opacity of 0.15.
+    pub fn with_fill(self, fill: theme::Fill) -> Self {
+        Self { fill, ..self }
+    }
+
+    /// Set whether the span is displayed below or above the series.
+    /// By default, the span is displayed below the series.
+    pub fn with_zpos(mut self, zpos: ZPos) -> Self {
+        self.pos.zorder = zpos.into();
+        self
+    }
+}
+
 /// An arrow plotted on the plot area
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Arrow {
     pub(crate) dx: f32,
     pub(crate) dy: f32,
@@ -214,7 +307,7 @@ impl Arrow {
                 y,
                 x_axis: Default::default(),
                 y_axis: Default::default(),
-                zpos: ZPos::AboveSeries,
+                zorder: ZOrder::ABOVE_SERIES,
             },
         }
     }
@@ -233,6 +326,7 @@ impl Arrow {
 
 /// An arbitrary marker to place on the plot area
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Marker {
     pub(crate) marker: theme::Marker,
     pub(crate) pos: Pos,
@@ -241,6 +335,7 @@ pub struct Marker {
 /// An anchor point for [`Label`].
 /// It defines which point of the label is positioned at the given data coordinates.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Anchor {
     /// Anchor at the center of the label
     Center,
@@ -265,6 +360,7 @@ pub enum Anchor {
 
 /// An arbitrary label to place on the plot area
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Label {
     pub(crate) text: String,
     pub(crate) font_size: f32,
@@ -272,6 +368,7 @@ pub struct Label {
     pub(crate) color: theme::Color,
     pub(crate) anchor: Anchor,
     pub(crate) frame: (Option<theme::Fill>, Option<theme::Stroke>),
+    pub(crate) shadow: Option<theme::Shadow>,
     pub(crate) angle: f32,
 
     pub(crate) pos: Pos,
@@ -287,13 +384,14 @@ impl Label {
             color: theme::Col::Foreground.into(),
             anchor: Anchor::default(),
             frame: (None, None),
+            shadow: None,
             angle: 0.0,
             pos: Pos {
                 x,
                 y,
                 x_axis: Default::default(),
                 y_axis: Default::default(),
-                zpos: ZPos::AboveSeries,
+                zorder: ZOrder::ABOVE_SERIES,
             },
         }
     }
@@ -329,6 +427,13 @@ impl Label {
         }
     }
 
+    /// Set the drop shadow of the label frame.
+    /// The shadow is only drawn when the label has a frame.
+    /// By default, there is no shadow.
+    pub fn with_shadow(self, shadow: Option<theme::Shadow>) -> Self {
+        Self { shadow, ..self }
+    }
+
     /// Set the rotation angle of the label in degrees in counter-clockwise direction.
     /// The label is rotated around its anchor point.
     /// By default, the angle is 0.0 (horizontal).
@@ -5,9 +5,11 @@
  * They are not tied to a specific orientation (X or Y), that is handled at the plot level.
  */
 
-pub use ticks::{Grid, MinorGrid, MinorTicks, Ticks, TicksFont};
+pub use ticks::{EmphasizedGrid, Grid, MinorGrid, MinorTicks, TickDirection, Ticks, TicksFont};
 
-use crate::style::defaults;
+use crate::des::ZOrder;
+use crate::style::{self, defaults};
+use crate::text;
 
 super::define_rich_text_structs!(Title, TitleProps, TitleOptProps);
 
@@ -19,6 +21,7 @@ impl Default for TitleProps {
 
 /// Side of the axis in the plot, applies to both X and Y axes.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Side {
     /// Axis is on the main side of the plot.
     /// That is bottom for X axis, left for Y axis
@@ -29,6 +32,21 @@ pub enum Side {
     Opposite,
 }
 
+/// Position of the axis title along the axis.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TitlePos {
+    /// Title is aligned with the start of the axis.
+    /// That is left for horizontal axes, bottom for vertical axes
+    Start,
+    /// Title is centered along the axis
+    #[default]
+    Center,
+    /// Title is aligned with the end of the axis.
+    /// That is right for horizontal axes, top for vertical axes
+    End,
+}
+
 /// A reference to another axis, either by id, index or by title.
 /// There is no need to specify the orientation (X or Y), as there they are always
 /// treated separately.
@@ -36,6 +54,7 @@ pub enum Side {
 ///     - sharing axes across different subplots of a figure
 ///     - attach series to a specific axis in the case of multiple X or Y axes
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Ref {
     /// Reference by index in the order declared in the plot,
     /// for the given orientation (X or Y), and starting at 0.
@@ -80,15 +99,24 @@ pub fn ref_id(id: impl Into<String>) -> Ref {
 
 /// Axis definition
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Axis {
     id: Option<String>,
     title: Option<Title>,
+    title_align: text::line::Align,
+    title_side: Option<Side>,
+    title_pos: TitlePos,
     side: Side,
     scale: Scale,
     ticks: Option<Ticks>,
     minor_ticks: Option<MinorTicks>,
     grid: Option<Grid>,
     minor_grid: Option<MinorGrid>,
+    emphasized_grids: Vec<EmphasizedGrid>,
+    grid_zorder: ZOrder,
+    grid_range: Option<(f64, f64)>,
+    axis_break: Option<(f64, f64)>,
+    spine_offset: f32,
 }
 
 impl Default for Axis {
@@ -96,16 +124,27 @@ impl Default for Axis {
     ///  - automatic linear scale
     ///  - main side (Bottom for X axis, Left for Y axis)
     ///  - no title, no ticks, no grid
+    ///  - title lines centered
+    ///  - title on the same side as the axis, centered along the axis
+    ///  - spine attached to the plot area
     fn default() -> Self {
         Axis {
             id: None,
             title: None,
+            title_align: text::line::Align::Center,
+            title_side: None,
+            title_pos: TitlePos::Center,
             side: Default::default(),
             scale: Default::default(),
             ticks: None,
             minor_ticks: None,
             grid: None,
             minor_grid: None,
+            emphasized_grids: Vec::new(),
+            grid_zorder: ZOrder::GRID,
+            grid_range: None,
+            axis_break: None,
+            spine_offset: 0.0,
         }
     }
 }
@@ -134,6 +173,32 @@ impl Axis {
         }
     }
 
+    /// Set the alignment of the title lines and return self for chaining.
+    /// Multi-line titles are always centered along the axis,
+    /// this sets how the lines are aligned with each other.
+    pub fn with_title_align(self, title_align: text::line::Align) -> Self {
+        Self {
+            title_align,
+            ..self
+        }
+    }
+
+    /// Set the side of the plot where the title is drawn and return self for chaining.
+    /// By default, the title is on the same side as the axis.
+    /// A title on the other side is drawn beyond the axes of that side,
+    /// and the space is reserved there.
+    pub fn with_title_side(self, title_side: Side) -> Self {
+        Self {
+            title_side: Some(title_side),
+            ..self
+        }
+    }
+
+    /// Set the position of the title along the axis and return self for chaining
+    pub fn with_title_pos(self, title_pos: TitlePos) -> Self {
+        Self { title_pos, ..self }
+    }
+
     /// Set this axis on the opposite side of the plot and return self for chaining
     pub fn with_opposite_side(self) -> Self {
         Self {
@@ -147,6 +212,56 @@ impl Axis {
         Self { scale, ..self }
     }
 
+    /// Remove the interval between `low` and `high` from the axis and return self for chaining.
+    /// The interval is collapsed to a seam, marked with zig-zag break marks on the axis line,
+    /// and no tick is placed inside of it.
+    /// This is useful to show outliers without squeezing the rest of the data.
+    /// The break is only supported on linear scales, and is ignored if the interval
+    /// is not strictly within the axis range.
+    pub fn with_break(self, low: f64, high: f64) -> Self {
+        Self {
+            axis_break: Some((low.min(high), low.max(high))),
+            ..self
+        }
+    }
+
+    /// Move the spine, the ticks and the labels of this axis away from the plot area
+    /// by `spine_offset`, and return self for chaining.
+    /// The ticks stay aligned with their data positions, and the offset is reserved in the layout.
+    /// With a [`Border::Box`](crate::des::plot::Border::Box), the box stays on the plot area,
+    /// so this is mostly useful with [`Border::Axis`](crate::des::plot::Border::Axis)
+    /// or hidden box sides (see [`Plot::with_spines`](crate::des::Plot::with_spines)).
+    /// Negative values are clamped to zero.
+    pub fn with_spine_offset(self, spine_offset: f32) -> Self {
+        Self {
+            spine_offset: spine_offset.max(0.0),
+            ..self
+        }
+    }
+
+    /// Copy of this axis sharing the scale of the figure axis `fig_ax_idx`, without title.
+    /// Used for the inner plots of subplots with figure-level shared axes.
+    pub(crate) fn to_outer_shared(&self, fig_ax_idx: usize) -> Self {
+        Self {
+            title: None,
+            scale: Scale::Shared(Ref::FigIdx(fig_ax_idx)),
+            ..self.clone()
+        }
+    }
+
+    /// Copy of this axis with the given scale, without title, break nor grid range.
+    /// Used for the axes of the zoomed insets.
+    pub(crate) fn to_zoomed(&self, scale: Scale) -> Self {
+        Self {
+            id: None,
+            title: None,
+            scale,
+            grid_range: None,
+            axis_break: None,
+            ..self.clone()
+        }
+    }
+
     /// Set the ticks of this axis and return self for chaining
     pub fn with_ticks(self, ticks: Ticks) -> Self {
         Self {
@@ -185,6 +300,40 @@ impl Axis {
         }
     }
 
+    /// Add grid lines at specific values, drawn with the given line style, and return self for chaining.
+    /// The lines are drawn with the major grid, on top of it, and replace the major grid lines
+    /// at the same values. They don't require the axis to have a grid nor ticks.
+    /// Values that fall outside of the axis range (or that the scale can't map,
+    /// such as negative values on a log scale) are not drawn.
+    pub fn with_emphasized_values(mut self, values: &[f64], line: style::theme::Stroke) -> Self {
+        self.emphasized_grids.push(EmphasizedGrid {
+            values: values.to_vec(),
+            line,
+        });
+        self
+    }
+
+    /// Set the z-order of the major and minor grids and return self for chaining.
+    /// By default, grids are drawn at [`ZOrder::GRID`], below the series.
+    pub fn with_grid_zorder(self, grid_zorder: ZOrder) -> Self {
+        Self {
+            grid_zorder,
+            ..self
+        }
+    }
+
+    /// Restrict the major and minor grid lines to the values between `low` and `high`,
+    /// in data space, and return self for chaining.
+    /// E.g. `with_grid_range(0.0, f64::INFINITY)` only draws grid lines above zero.
+    /// By default, grid lines are drawn over the whole axis range.
+    /// Emphasized values are not restricted.
+    pub fn with_grid_range(self, low: f64, high: f64) -> Self {
+        Self {
+            grid_range: Some((low.min(high), low.max(high))),
+            ..self
+        }
+    }
+
     /// Get the id of this axis, if any
     pub fn id(&self) -> Option<&str> {
         self.id.as_deref()
@@ -195,6 +344,22 @@ impl Axis {
         self.title.as_ref()
     }
 
+    /// Get the alignment of the title lines
+    pub fn title_align(&self) -> text::line::Align {
+        self.title_align
+    }
+
+    /// Get the side of the plot where the title is drawn.
+    /// This is the side of the axis, unless set with [Axis::with_title_side]
+    pub fn title_side(&self) -> Side {
+        self.title_side.unwrap_or(self.side)
+    }
+
+    /// Get the position of the title along the axis
+    pub fn title_pos(&self) -> TitlePos {
+        self.title_pos
+    }
+
     /// Get the side of this axis
     pub fn side(&self) -> Side {
         self.side
@@ -224,6 +389,31 @@ impl Axis {
         self.minor_grid.as_ref()
     }
 
+    /// Grid lines emphasized at specific values
+    pub fn emphasized_grids(&self) -> &[EmphasizedGrid] {
+        &self.emphasized_grids
+    }
+
+    /// Get the z-order of the grids
+    pub fn grid_zorder(&self) -> ZOrder {
+        self.grid_zorder
+    }
+
+    /// Get the range of values within which grid lines are drawn, if restricted
+    pub fn grid_range(&self) -> Option<(f64, f64)> {
+        self.grid_range
+    }
+
+    /// Get the interval removed from the axis, if any
+    pub fn axis_break(&self) -> Option<(f64, f64)> {
+        self.axis_break
+    }
+
+    /// Get the distance between the spine of this axis and the plot area
+    pub fn spine_offset(&self) -> f32 {
+        self.spine_offset
+    }
+
     /// Returns whether this axis will show ticks labels
     pub fn has_tick_labels(&self) -> bool {
         match &self.ticks {
@@ -241,6 +431,7 @@ impl Axis {
 /// Describe the bounds of an axis in data space
 /// None means automatic bounds depending on the data
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Range(pub Option<f64>, pub Option<f64>);
 
 impl From<(Option<f64>, Option<f64>)> for Range {
@@ -291,6 +482,7 @@ impl Range {
 
 /// Describe a logarithmic scale options
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LogScale {
     /// Logarithm base (typically 10.0)
     pub base: f64,
@@ -323,6 +515,7 @@ impl Default for LogScale {
 
 /// Describes the type of an axis scale
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Scale {
     /// Full auto scale, depending on the data and type of plot.
     /// Will typically translate to auto linear axis for numerical data
@@ -380,6 +573,7 @@ pub mod ticks {
 
     /// Describes how to locate the ticks of an axis
     #[derive(Debug, Default, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum Locator {
         /// Automatic tick placement, that depends on the type of axis (linear, logarithmic, categories),
         /// on the axis data range (bounds) and whether the ticks are major or minor
@@ -392,6 +586,11 @@ pub mod ticks {
         PiMultiple(PiMultipleLocator),
         /// Places ticks on a logarithmic scale, using the specified base and max number of bins
         Log(LogLocator),
+        /// Places minor ticks by subdividing the intervals between the major ticks.
+        /// Only valid for minor ticks. On a logarithmic scale, this is equivalent to [`Locator::Auto`].
+        Subdivide(Subdivisions),
+        /// Places ticks at explicit positions, optionally with explicit labels
+        Fixed(FixedLocator),
         #[cfg(feature = "time")]
         /// Places ticks on a time scale
         /// The series data must be DateTime, otherwise an error is returned.
@@ -405,6 +604,7 @@ pub mod ticks {
 
     /// A locator that places ticks automatically, using the specified number of bins and steps
     #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct MaxNLocator {
         /// Number of bins (that is number of ticks - 1)
         pub bins: u32,
@@ -431,6 +631,7 @@ pub mod ticks {
     /// A locator that places ticks at multiples of π
     /// The axis will be annotated with `× π`
     #[derive(Debug, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct PiMultipleLocator {
         /// Number of bins (that is number of ticks - 1)
         pub bins: u32,
@@ -450,6 +651,7 @@ pub mod ticks {
 
     /// A locator that places ticks on a logarithmic scale
     #[derive(Debug, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct LogLocator {
         /// Logarithm base
         pub base: f64,
@@ -467,9 +669,51 @@ pub mod ticks {
         }
     }
 
+    /// Number of subdivisions of the intervals between major ticks,
+    /// used to locate the minor ticks with [`Locator::Subdivide`].
+    /// `N(n)` places `n - 1` minor ticks between each pair of major ticks.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum Subdivisions {
+        /// 4 or 5 subdivisions, depending on the major interval:
+        /// 4 for multiples of 2, and 5 otherwise (e.g. multiples of 1, 2.5 or 5)
+        #[default]
+        Auto,
+        /// The specified number of subdivisions
+        N(u32),
+    }
+
+    impl From<Subdivisions> for Locator {
+        fn from(subdivisions: Subdivisions) -> Self {
+            Locator::Subdivide(subdivisions)
+        }
+    }
+
+    /// A locator that places ticks at explicit positions, bypassing the automatic placement.
+    /// The positions are in data space and are mapped through the axis scale.
+    /// Positions outside of the axis range are skipped.
+    /// Only valid for numeric axes.
+    #[derive(Debug, Clone, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct FixedLocator {
+        /// Positions of the ticks
+        pub positions: Vec<f64>,
+        /// Labels of the ticks, matching the positions by index.
+        /// The positions without a label (or all of them if `None`) are labeled
+        /// by the ticks formatter. Ignored for minor ticks.
+        pub labels: Option<Vec<String>>,
+    }
+
+    impl From<FixedLocator> for Locator {
+        fn from(locator: FixedLocator) -> Self {
+            Locator::Fixed(locator)
+        }
+    }
+
     #[cfg(feature = "time")]
     /// Describes how to locate the ticks of a DateTime axis
     #[derive(Debug, Default, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum DateTimeLocator {
         /// Automatic tick placement for DateTime axis using
         /// the axis bounds and heuristics to have a reasonable number of ticks
@@ -501,11 +745,15 @@ pub mod ticks {
     }
 
     #[cfg(feature = "time")]
-    /// Describes how to locate the ticks of a TimeDelta axis
+    /// Describes how to locate the ticks of a TimeDelta axis.
+    /// The ticks are multiples of the interval, hence symmetric around zero for negative deltas.
     #[derive(Debug, Default, Clone, Copy)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum TimeDeltaLocator {
         /// Automatic tick placement for TimeDelta axis using
-        /// the axis bounds and heuristics to have a reasonable number of ticks
+        /// the axis bounds and heuristics to have a reasonable number of ticks.
+        /// The interval is the smallest human interval (1s, 5s, 15s, 1min, 15min, 1h, 6h, 1 day, 1 week, ...)
+        /// giving at most 10 intervals over the axis.
         #[default]
         Auto,
         /// Place ticks every N days
@@ -530,6 +778,7 @@ pub mod ticks {
     #[allow(missing_copy_implementations)]
     /// Describes how to format the ticks labels
     #[derive(Debug, Default, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum Formatter {
         /// Automatic tick formatting.
         /// Depending on the scale and locator, the formatter will pick a suitable format.
@@ -557,6 +806,7 @@ pub mod ticks {
 
     /// A label formatter for DateTime ticks
     #[derive(Debug, Clone, Copy, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct PercentFormatter {
         /// Number of decimal places
         /// None means automatic
@@ -572,6 +822,7 @@ pub mod ticks {
     #[cfg(feature = "time")]
     /// A label formatter for DateTime ticks
     #[derive(Debug, Clone, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum DateTimeFormatter {
         /// Choose the format automatically according to time bounds
         #[default]
@@ -596,8 +847,11 @@ pub mod ticks {
     #[cfg(feature = "time")]
     /// A label formatter for TimeDelta ticks
     #[derive(Debug, Clone, Default)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub enum TimeDeltaFormatter {
-        /// Choose the format automatically based on data bounds
+        /// Choose the format automatically based on the interval between the ticks and the data bounds
+        /// (e.g. `%H:%M` for ticks every 15 minutes, `%D %H:%M` if the axis spans days).
+        /// This is also the format used by [`Formatter::Auto`] with a [`Locator::TimeDelta`].
         #[default]
         Auto,
         /// Format the ticks with a custom TimeDelta format (see [crate::time::TimeDelta::fmt_parse])
@@ -613,6 +867,7 @@ pub mod ticks {
 
     /// Describes the font of the ticks labels
     #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct TicksFont {
         /// The font of the ticks labels
         pub font: Font,
@@ -631,12 +886,13 @@ pub mod ticks {
 
     /// Describes the style of the major grid lines
     #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Grid(pub theme::Stroke);
 
     impl Default for Grid {
         fn default() -> Self {
             Grid(theme::Stroke {
-                width: 1.0,
+                width: defaults::GRID_LINE_WIDTH,
                 color: theme::Col::Grid.into(),
                 pattern: style::LinePattern::Solid,
                 opacity: None,
@@ -650,13 +906,43 @@ pub mod ticks {
         }
     }
 
+    /// Grid lines at specific values of an axis, with a distinct style.
+    /// E.g. a baseline at zero or a threshold.
+    /// See [`Axis::with_emphasized_values`](super::Axis::with_emphasized_values).
+    #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub struct EmphasizedGrid {
+        /// The values at which the lines are drawn, in data space
+        pub values: Vec<f64>,
+        /// The style of the lines
+        pub line: theme::Stroke,
+    }
+
+    /// Direction of the tick marks, relative to the plot area
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+    pub enum TickDirection {
+        /// The marks point into the plot area, and take no room outside of it
+        In,
+        /// The marks point away from the plot area
+        Out,
+        /// The marks cross the axis line
+        #[default]
+        Both,
+    }
+
     /// Describes the major ticks of an axis
     #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct Ticks {
         locator: Locator,
         formatter: Option<Formatter>,
         font: TicksFont,
         color: theme::Color,
+        offset_notation: bool,
+        direction: TickDirection,
+        length: Option<f32>,
+        count_hint: Option<(u32, u32)>,
     }
 
     impl Default for Ticks {
@@ -664,12 +950,18 @@ pub mod ticks {
         /// - automatic locator
         /// - labels with automatic formatter (unless the scale is shared)
         /// - default font and theme foreground color
+        /// - no offset notation
+        /// - marks crossing the axis line, with the length of the layout parameters
         fn default() -> Self {
             Ticks {
                 locator: Locator::default(),
                 formatter: Some(Formatter::default()),
                 font: TicksFont::default(),
                 color: theme::Col::Foreground.into(),
+                offset_notation: false,
+                direction: TickDirection::default(),
+                length: None,
+                count_hint: None,
             }
         }
     }
@@ -697,6 +989,73 @@ pub mod ticks {
         pub fn with_color(self, color: theme::Color) -> Self {
             Self { color, ..self }
         }
+        /// Returns a new ticks with offset notation enabled or disabled.
+        /// With offset notation, when the ticks share a common offset (e.g. `1500.1`, `1500.2`, ...)
+        /// or a common order of magnitude (e.g. `2e5`, `4e5`, ...), it is factored out of the labels
+        /// and shown once at the end of the axis (e.g. `+1500` or `×10⁵`).
+        /// Only applies to the automatic formatter on linear scales.
+        pub fn with_offset_notation(self, offset_notation: bool) -> Self {
+            Self {
+                offset_notation,
+                ..self
+            }
+        }
+        /// Returns a new ticks with the marks pointing in the specified direction.
+        /// Only the part of the marks outside of the plot area takes room in the layout.
+        pub fn with_direction(self, direction: TickDirection) -> Self {
+            Self { direction, ..self }
+        }
+        /// Returns a new ticks placed at the specified positions, in data space.
+        /// This is a shortcut for `with_locator(FixedLocator { positions, labels: None }.into())`.
+        /// The labels are formatted by the ticks formatter.
+        pub fn with_positions(self, positions: Vec<f64>) -> Self {
+            self.with_locator(
+                FixedLocator {
+                    positions,
+                    labels: None,
+                }
+                .into(),
+            )
+        }
+        /// Returns a new ticks placed at the specified positions, in data space, with explicit labels.
+        /// The labels match the positions by index, and the positions beyond the labels are
+        /// labeled by the ticks formatter.
+        /// The labels are hidden if the formatter is `None`.
+        pub fn with_labeled_positions(self, positions: Vec<f64>, labels: Vec<String>) -> Self {
+            self.with_locator(
+                FixedLocator {
+                    positions,
+                    labels: Some(labels),
+                }
+                .into(),
+            )
+        }
+        /// Returns a new ticks aiming for `count` ticks within the axis range.
+        /// See [`Ticks::with_count_range`].
+        pub fn with_target_count(self, count: u32) -> Self {
+            self.with_count_range(count, count)
+        }
+        /// Returns a new ticks aiming for a number of ticks between `min` and `max` within the axis range.
+        ///
+        /// This is a hint for the [`Locator::Auto`] and [`Locator::MaxN`] locators on linear scales,
+        /// which still place the ticks on round numbers, and pick the step that best approaches the count.
+        /// The count is therefore not guaranteed, but the density is kept when the axis range changes.
+        /// Other locators ignore it.
+        pub fn with_count_range(self, min: u32, max: u32) -> Self {
+            Self {
+                count_hint: Some((min.min(max), min.max(max))),
+                ..self
+            }
+        }
+        /// Returns a new ticks with marks of the specified length, in figure units.
+        /// With [`TickDirection::Both`], the marks extend by this length on each side of the axis line.
+        /// By default, the length is [`LayoutParams::tick_size`](crate::drawing::LayoutParams::tick_size).
+        pub fn with_length(self, length: f32) -> Self {
+            Self {
+                length: Some(length),
+                ..self
+            }
+        }
 
         /// Generates the ticks at the specified locations
         pub fn locator(&self) -> &Locator {
@@ -715,6 +1074,22 @@ pub mod ticks {
         pub fn color(&self) -> theme::Color {
             self.color
         }
+        /// Whether a common offset or order of magnitude is factored out of the labels
+        pub fn offset_notation(&self) -> bool {
+            self.offset_notation
+        }
+        /// Direction of the marks
+        pub fn direction(&self) -> TickDirection {
+            self.direction
+        }
+        /// Length of the marks, if set explicitly
+        pub fn length(&self) -> Option<f32> {
+            self.length
+        }
+        /// Range of the number of ticks the locator aims for, if any
+        pub fn count_hint(&self) -> Option<(u32, u32)> {
+            self.count_hint
+        }
     }
 
     impl From<Locator> for Ticks {
@@ -726,14 +1101,16 @@ pub mod ticks {
         }
     }
 
-    /// Describes the style of the minor grid lines
+    /// Describes the style of the minor grid lines.
+    /// The style is independent from the major [`Grid`]: by default minor grid lines are thinner and dashed.
     #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct MinorGrid(pub theme::Stroke);
 
     impl Default for MinorGrid {
         fn default() -> Self {
             MinorGrid(theme::Stroke {
-                width: 0.5,
+                width: defaults::MINOR_GRID_LINE_WIDTH,
                 color: theme::Col::Grid.into(),
                 pattern: style::LinePattern::Dash(Dash::default()),
                 opacity: None,
@@ -749,11 +1126,18 @@ pub mod ticks {
 
     /// Describes the minor ticks of an axis
     #[derive(Debug, Clone)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
     pub struct MinorTicks {
         /// Minor ticks locator
         locator: Locator,
         /// Ticks color
         color: theme::Color,
+        /// Ticks line width
+        width: f32,
+        /// Direction of the marks
+        direction: TickDirection,
+        /// Length of the marks
+        length: Option<f32>,
     }
 
     impl Default for MinorTicks {
@@ -761,6 +1145,9 @@ pub mod ticks {
             MinorTicks {
                 locator: Locator::default(),
                 color: theme::Col::Foreground.into(),
+                width: defaults::MINOR_TICK_LINE_WIDTH,
+                direction: TickDirection::default(),
+                length: None,
             }
         }
     }
@@ -784,10 +1171,35 @@ pub mod ticks {
         pub fn with_locator(self, locator: Locator) -> Self {
             Self { locator, ..self }
         }
+        /// Returns a new `MinorTicks` that subdivides the intervals between major ticks
+        /// and return self for chaining.
+        /// This is a shortcut for `with_locator(Locator::Subdivide(subdivisions))`
+        pub fn with_subdivisions(self, subdivisions: Subdivisions) -> Self {
+            self.with_locator(Locator::Subdivide(subdivisions))
+        }
         /// Returns a new `MinorTicks` with the specified color and return self for chaining
         pub fn with_color(self, color: theme::Color) -> Self {
             Self { color, ..self }
         }
+        /// Returns a new `MinorTicks` with the specified line width and return self for chaining
+        pub fn with_width(self, width: f32) -> Self {
+            Self { width, ..self }
+        }
+        /// Returns a new `MinorTicks` with the marks pointing in the specified direction
+        /// and return self for chaining.
+        /// The direction is independent from the one of the major ticks.
+        pub fn with_direction(self, direction: TickDirection) -> Self {
+            Self { direction, ..self }
+        }
+        /// Returns a new `MinorTicks` with marks of the specified length, in figure units,
+        /// and return self for chaining.
+        /// By default, the length is [`LayoutParams::minor_tick_size`](crate::drawing::LayoutParams::minor_tick_size).
+        pub fn with_length(self, length: f32) -> Self {
+            Self {
+                length: Some(length),
+                ..self
+            }
+        }
 
         /// Get the locator of these minor ticks
         pub fn locator(&self) -> &Locator {
@@ -797,5 +1209,17 @@ pub mod ticks {
         pub fn color(&self) -> theme::Color {
             self.color
         }
+        /// Get the line width of these minor ticks
+        pub fn width(&self) -> f32 {
+            self.width
+        }
+        /// Get the direction of the marks
+        pub fn direction(&self) -> TickDirection {
+            self.direction
+        }
+        /// Get the length of the marks, if set explicitly
+        pub fn length(&self) -> Option<f32> {
+            self.length
+        }
     }
 }
@@ -1,9 +1,11 @@
 //! Figure design structures
 use std::iter::FusedIterator;
 
-use crate::des::{Legend, Plot, PlotIdx, Subplots};
+use crate::des::plot::{PanelLabel, PanelLabelPos, PanelLabelStyle};
+use crate::des::{FreeformLayout, Legend, Plot, PlotIdx, PlotIdxIter, Subplots};
 use crate::geom;
 use crate::style::{defaults, theme};
+use crate::text::{self, Font};
 
 super::define_rich_text_structs!(Title, TitleProps, TitleOptProps);
 
@@ -13,8 +15,17 @@ impl Default for TitleProps {
     }
 }
 
+super::define_rich_text_structs!(Subtitle, SubtitleProps, SubtitleOptProps);
+
+impl Default for SubtitleProps {
+    fn default() -> Self {
+        SubtitleProps::new(defaults::SUBTITLE_FONT_SIZE)
+    }
+}
+
 /// Position of the legend relatively to the figure
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LegendPos {
     /// Position the legend at the top of the figure
     Top,
@@ -43,16 +54,167 @@ impl From<LegendPos> for FigLegend {
     }
 }
 
+/// Layer at which a watermark is drawn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WatermarkLayer {
+    /// Drawn over the figure background, behind everything else
+    #[default]
+    Behind,
+    /// Drawn in front of everything else
+    Front,
+}
+
+/// Raster image used as a watermark
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WatermarkImage {
+    /// RGBA pixels, 4 bytes per pixel in row-major order, with non-premultiplied alpha
+    pub rgba: Vec<u8>,
+    /// Width of the image in pixels
+    pub width: u32,
+    /// Height of the image in pixels
+    pub height: u32,
+    /// Size of the image on the figure, in figure units
+    pub size: geom::Size,
+}
+
+/// Content of a watermark
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WatermarkContent {
+    /// A text, drawn with the font, size and color of the watermark
+    Text(String),
+    /// A raster image
+    Image(WatermarkImage),
+}
+
+/// A semi-transparent text or image stamped at the center of the figure
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Watermark {
+    content: WatermarkContent,
+    font_size: f32,
+    font: Font,
+    color: theme::Color,
+    opacity: f32,
+    angle: f32,
+    layer: WatermarkLayer,
+}
+
+impl Watermark {
+    /// Create a text watermark, rotated by 30° and drawn behind the plots
+    pub fn text(text: impl Into<String>) -> Self {
+        Watermark {
+            content: WatermarkContent::Text(text.into()),
+            angle: 30.0,
+            ..Self::new_default()
+        }
+    }
+
+    /// Create an image watermark, drawn behind the plots
+    pub fn image(image: WatermarkImage) -> Self {
+        Watermark {
+            content: WatermarkContent::Image(image),
+            ..Self::new_default()
+        }
+    }
+
+    fn new_default() -> Self {
+        Watermark {
+            content: WatermarkContent::Text(String::new()),
+            font_size: defaults::WATERMARK_FONT_SIZE,
+            font: Font::default(),
+            color: theme::Col::Foreground.into(),
+            opacity: defaults::WATERMARK_OPACITY,
+            angle: 0.0,
+            layer: WatermarkLayer::default(),
+        }
+    }
+
+    /// Set the font size of a text watermark and return self for chaining
+    pub fn with_font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    /// Set the font of a text watermark and return self for chaining
+    pub fn with_font(self, font: Font) -> Self {
+        Self { font, ..self }
+    }
+
+    /// Set the color of a text watermark and return self for chaining.
+    /// By default, the foreground theme color is used.
+    pub fn with_color(self, color: theme::Color) -> Self {
+        Self { color, ..self }
+    }
+
+    /// Set the opacity (0.0 to 1.0) of the watermark and return self for chaining
+    pub fn with_opacity(self, opacity: f32) -> Self {
+        Self { opacity, ..self }
+    }
+
+    /// Set the rotation angle in degrees in counter-clockwise direction and return self for chaining.
+    /// The watermark is rotated around the figure center.
+    pub fn with_angle(self, angle: f32) -> Self {
+        Self { angle, ..self }
+    }
+
+    /// Set the layer at which the watermark is drawn and return self for chaining
+    pub fn with_layer(self, layer: WatermarkLayer) -> Self {
+        Self { layer, ..self }
+    }
+
+    /// Get the content of the watermark
+    pub fn content(&self) -> &WatermarkContent {
+        &self.content
+    }
+
+    /// Get the font size of a text watermark
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// Get the font of a text watermark
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    /// Get the color of a text watermark
+    pub fn color(&self) -> theme::Color {
+        self.color
+    }
+
+    /// Get the opacity of the watermark
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Get the rotation angle of the watermark in degrees
+    pub fn angle(&self) -> f32 {
+        self.angle
+    }
+
+    /// Get the layer at which the watermark is drawn
+    pub fn layer(&self) -> WatermarkLayer {
+        self.layer
+    }
+}
+
 /// Figure structure. This is the top-level structure representing a figure to be drawn.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Figure {
     plots: Plots,
 
     title: Option<Title>,
+    title_align: text::line::Align,
+    subtitle: Option<Subtitle>,
     size: geom::Size,
     legend: Option<FigLegend>,
+    plot_legends: bool,
     fill: Option<theme::Fill>,
     padding: geom::Padding,
+    watermark: Option<Watermark>,
 }
 
 impl Figure {
@@ -62,10 +224,14 @@ impl Figure {
             plots,
 
             title: None,
+            title_align: text::line::Align::Center,
+            subtitle: None,
             size: defaults::FIG_SIZE,
             legend: None,
+            plot_legends: true,
             fill: Some(theme::Col::Background.into()),
             padding: defaults::FIG_PADDING,
+            watermark: None,
         }
     }
 
@@ -77,6 +243,25 @@ impl Figure {
         }
     }
 
+    /// Set the alignment of the title and subtitle and return self for chaining.
+    /// The title is placed at the left edge, the center or the right edge of the figure content
+    /// (within the padding) depending on the alignment, and its lines are aligned accordingly.
+    /// By default, the title is centered.
+    pub fn with_title_align(self, title_align: text::line::Align) -> Self {
+        Figure {
+            title_align,
+            ..self
+        }
+    }
+
+    /// Set a subtitle, drawn below the title with the same alignment, and return self for chaining
+    pub fn with_subtitle(self, subtitle: Subtitle) -> Self {
+        Figure {
+            subtitle: Some(subtitle),
+            ..self
+        }
+    }
+
     /// Set the size and return self for chaining
     pub fn with_size(self, size: geom::Size) -> Self {
         Figure { size: size, ..self }
@@ -90,15 +275,36 @@ impl Figure {
         }
     }
 
+    /// Set whether the legends of individual plots are drawn and return self for chaining.
+    /// Set this to false to only show the figure legend.
+    pub fn with_plot_legends(self, plot_legends: bool) -> Self {
+        Figure {
+            plot_legends,
+            ..self
+        }
+    }
+
     /// Set the fill and return self for chaining
     /// Set this to None for a transparent background
     pub fn with_fill(self, fill: Option<theme::Fill>) -> Self {
         Figure { fill, ..self }
     }
 
-    /// Set the padding and return self for chaining
+    /// Set the padding between the figure edge and its content and return self for chaining.
+    /// Negative values are clamped to zero.
     pub fn with_padding(self, padding: geom::Padding) -> Self {
-        Figure { padding, ..self }
+        Figure {
+            padding: padding.non_negative(),
+            ..self
+        }
+    }
+
+    /// Set a watermark and return self for chaining
+    pub fn with_watermark(self, watermark: Watermark) -> Self {
+        Figure {
+            watermark: Some(watermark),
+            ..self
+        }
     }
 
     /// Get the size of the figure
@@ -111,6 +317,16 @@ impl Figure {
         self.title.as_ref()
     }
 
+    /// Get the alignment of the title and subtitle
+    pub fn title_align(&self) -> text::line::Align {
+        self.title_align
+    }
+
+    /// Get the subtitle of the figure
+    pub fn subtitle(&self) -> Option<&Subtitle> {
+        self.subtitle.as_ref()
+    }
+
     /// Get the plots of the figure
     pub fn plots(&self) -> &Plots {
         &self.plots
@@ -126,6 +342,11 @@ impl Figure {
         self.legend.as_ref()
     }
 
+    /// Whether the legends of individual plots are drawn
+    pub fn plot_legends(&self) -> bool {
+        self.plot_legends
+    }
+
     /// Get the fill of the figure
     pub fn fill(&self) -> Option<theme::Fill> {
         self.fill
@@ -135,20 +356,28 @@ impl Figure {
     pub fn padding(&self) -> &geom::Padding {
         &self.padding
     }
+
+    /// Get the watermark of the figure
+    pub fn watermark(&self) -> Option<&Watermark> {
+        self.watermark.as_ref()
+    }
 }
 
 /// Collection of plots for a figure
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Plots {
     /// Unique plot on the figure
-    Plot(Plot),
+    Plot(Box<Plot>),
     /// Subplots on the same figure
     Subplots(Subplots),
+    /// Plots at explicit positions on the figure
+    Freeform(FreeformLayout),
 }
 
 impl From<Plot> for Plots {
     fn from(plot: Plot) -> Self {
-        Plots::Plot(plot)
+        Plots::Plot(Box::new(plot))
     }
 }
 
@@ -158,28 +387,39 @@ impl From<Subplots> for Plots {
     }
 }
 
+impl From<FreeformLayout> for Plots {
+    fn from(layout: FreeformLayout) -> Self {
+        Plots::Freeform(layout)
+    }
+}
+
 impl Plots {
     /// The number of plots in this figure
     pub fn len(&self) -> usize {
         match self {
             Plots::Plot(..) => 1,
             Plots::Subplots(subplots) => subplots.len(),
+            Plots::Freeform(layout) => layout.len(),
         }
     }
 
-    /// The number of rows of plots in this figure
+    /// The number of rows of plots in this figure.
+    /// The plots of a freeform layout are on a single row.
     pub fn rows(&self) -> u32 {
         match self {
             Plots::Plot(..) => 1,
             Plots::Subplots(subplots) => subplots.rows(),
+            Plots::Freeform(..) => 1,
         }
     }
 
-    /// The number of columns of plots in this figure
+    /// The number of columns of plots in this figure.
+    /// This is the number of plots for a freeform layout.
     pub fn cols(&self) -> u32 {
         match self {
             Plots::Plot(..) => 1,
             Plots::Subplots(subplots) => subplots.cols(),
+            Plots::Freeform(layout) => layout.len() as u32,
         }
     }
 
@@ -190,6 +430,7 @@ impl Plots {
             Plots::Plot(plot) if idx.row == 0 && idx.col == 0 => Some(plot),
             Plots::Plot(..) => None,
             Plots::Subplots(subplots) => subplots.plot(idx),
+            Plots::Freeform(layout) => layout.plot(idx).map(|(_, plot)| plot),
         }
     }
 
@@ -200,7 +441,22 @@ impl Plots {
             Plots::Plot(plot) if idx.row == 0 && idx.col == 0 => Some(plot),
             Plots::Plot(..) => None,
             Plots::Subplots(subplots) => subplots.plot_mut(idx),
+            Plots::Freeform(layout) => layout.plot_mut(idx),
+        }
+    }
+
+    /// Label each plot at `pos` with a [`PanelLabel`] numbered by `style`, and return self for chaining.
+    /// The plots are numbered row by row, from top to bottom and left to right, skipping the empty cells.
+    /// The labels previously set on the plots are replaced.
+    pub fn with_auto_labels(mut self, style: PanelLabelStyle, pos: PanelLabelPos) -> Self {
+        let mut index = 0;
+        for idx in PlotIdxIter::new(self.rows(), self.cols()) {
+            if let Some(plot) = self.plot_mut(idx) {
+                plot.set_panel_label(Some(PanelLabel::new(style.label(index)).with_pos(pos)));
+                index += 1;
+            }
         }
+        self
     }
 
     /// Returns an iterator over the plots in this figure.
@@ -215,10 +471,42 @@ impl Plots {
     /// The space between plots in this figure (only for subplots)
     pub fn space(&self) -> f32 {
         match self {
-            Plots::Plot(..) => 0.0,
+            Plots::Plot(..) | Plots::Freeform(..) => 0.0,
             Plots::Subplots(subplots) => subplots.space(),
         }
     }
+
+    /// Whether the X scale of the main axes is shared across each column (only for subplots)
+    pub fn shared_x(&self) -> bool {
+        match self {
+            Plots::Plot(..) | Plots::Freeform(..) => false,
+            Plots::Subplots(subplots) => subplots.shared_x(),
+        }
+    }
+
+    /// Whether the Y scale of the main axes is shared across each row (only for subplots)
+    pub fn shared_y(&self) -> bool {
+        match self {
+            Plots::Plot(..) | Plots::Freeform(..) => false,
+            Plots::Subplots(subplots) => subplots.shared_y(),
+        }
+    }
+
+    /// The relative widths of the columns, if set (only for subplots)
+    pub fn col_ratios(&self) -> Option<&[f32]> {
+        match self {
+            Plots::Plot(..) | Plots::Freeform(..) => None,
+            Plots::Subplots(subplots) => subplots.col_ratios(),
+        }
+    }
+
+    /// The relative heights of the rows, if set (only for subplots)
+    pub fn row_ratios(&self) -> Option<&[f32]> {
+        match self {
+            Plots::Plot(..) | Plots::Freeform(..) => None,
+            Plots::Subplots(subplots) => subplots.row_ratios(),
+        }
+    }
 }
 
 /// An Iterator around a figure's plots, as returned by [`Plots::iter()`].
@@ -250,6 +538,11 @@ impl<'a> Iterator for PlotIter<'a> {
                     None
                 }
             }
+            Plots::Freeform(layout) => {
+                let plot = layout.plot(self.idx)?.1;
+                self.idx = self.idx.next(layout.len() as u32);
+                Some(Some(plot))
+            }
         }
     }
 }
@@ -4,11 +4,12 @@
 use std::num::NonZeroU32;
 
 use crate::geom::{Padding, Size};
-use crate::style::{defaults, theme};
+use crate::style::{defaults, series, theme};
 use crate::text;
 
 /// The font configuration for legend entries
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntryFont {
     /// The font size in figure units
     pub size: f32,
@@ -28,36 +29,122 @@ impl Default for EntryFont {
     }
 }
 
+/// The sample drawn next to the label of a manual legend entry
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SampleStyle {
+    /// A line segment, as for line series
+    Line(series::Stroke),
+    /// A marker, as for scatter series
+    Marker(series::Marker),
+    /// A filled rectangle with optional border, as for histograms and bars
+    Rect(series::Fill, Option<series::Stroke>),
+}
+
+/// A legend entry supplied by the user, independent of any series.
+/// Useful to explain e.g. a shaded region or a reference line.
+/// Automatic series colors resolve against the position of the entry in the legend.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LegendEntry {
+    label: String,
+    sample_style: SampleStyle,
+    font: Option<EntryFont>,
+}
+
+impl LegendEntry {
+    /// Create a new legend entry with the given label and sample style
+    pub fn new(label: impl Into<String>, sample_style: SampleStyle) -> Self {
+        Self {
+            label: label.into(),
+            sample_style,
+            font: None,
+        }
+    }
+
+    /// Get the label of the entry
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Get the sample style of the entry
+    pub fn sample_style(&self) -> &SampleStyle {
+        &self.sample_style
+    }
+
+    /// Get the font override of the entry
+    pub fn font(&self) -> Option<&EntryFont> {
+        self.font.as_ref()
+    }
+
+    /// Set a font specific to this entry and return self for chaining
+    pub fn with_font(self, font: EntryFont) -> Self {
+        Self {
+            font: Some(font),
+            ..self
+        }
+    }
+}
+
+/// Where manual entries are placed relative to the series entries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EntryOrder {
+    /// Manual entries come before the series entries
+    BeforeSeries,
+    /// Manual entries come after the series entries
+    #[default]
+    AfterSeries,
+}
+
 /// Legend configuration for a plot
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Legend<Pos> {
     pos: Pos,
     font: EntryFont,
     fill: Option<theme::Fill>,
     border: Option<theme::Stroke>,
+    radius: f32,
+    shadow: Option<theme::Shadow>,
     columns: Option<NonZeroU32>,
+    max_rows: Option<NonZeroU32>,
     padding: Padding,
     margin: f32,
     spacing: Size,
+    entries: Vec<LegendEntry>,
+    entry_order: EntryOrder,
+    label_overflow: text::rich::Overflow,
 }
 
 impl<Pos: Default> Default for Legend<Pos> {
     /// Create a default legend configuration
     /// - Fill color: theme::Col::LegendFill
     /// - Border: theme::Col::LegendBorder, 1.0
+    /// - Sharp corners
+    /// - No shadow
     /// - Font: default EntryFont
     /// - Default column layout (depdend on the position and number and width of entries)
+    /// - No maximum number of rows
     /// - Default padding and spacing
+    /// - No manual entries
+    /// - Labels wider than the available width are wrapped
     fn default() -> Self {
         Self {
             pos: Pos::default(),
             font: EntryFont::default(),
             fill: Some(theme::Col::LegendFill.into()),
             border: Some(theme::Col::LegendBorder.into()),
+            radius: 0.0,
+            shadow: None,
             columns: None,
+            max_rows: None,
             padding: defaults::LEGEND_PADDING.into(),
             margin: defaults::LEGEND_MARGIN,
             spacing: Size::new(defaults::LEGEND_H_SPACING, defaults::LEGEND_V_SPACING),
+            entries: Vec::new(),
+            entry_order: EntryOrder::default(),
+            label_overflow: text::rich::Overflow::Wrap,
         }
     }
 }
@@ -101,11 +188,26 @@ impl<Pos> Legend<Pos> {
         self.border.as_ref()
     }
 
+    /// Get the corner radius of the legend box
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    /// Get the drop shadow of the legend box
+    pub fn shadow(&self) -> Option<&theme::Shadow> {
+        self.shadow.as_ref()
+    }
+
     /// Get the number of columns for the legend entries
     pub fn columns(&self) -> Option<u32> {
         self.columns.map(|c| c.get())
     }
 
+    /// Get the maximum number of rows for the legend entries
+    pub fn max_rows(&self) -> Option<u32> {
+        self.max_rows.map(|r| r.get())
+    }
+
     /// Get the spacing between legend entries
     pub fn spacing(&self) -> Size {
         self.spacing
@@ -121,6 +223,21 @@ impl<Pos> Legend<Pos> {
         self.margin
     }
 
+    /// Get the manual entries of the legend
+    pub fn entries(&self) -> &[LegendEntry] {
+        &self.entries
+    }
+
+    /// Get the placement of manual entries relative to series entries
+    pub fn entry_order(&self) -> EntryOrder {
+        self.entry_order
+    }
+
+    /// Get how labels wider than the available width are handled
+    pub fn label_overflow(&self) -> text::rich::Overflow {
+        self.label_overflow
+    }
+
     /// Set the position of the legend and return self for chaining
     pub fn with_pos(self, pos: Pos) -> Self {
         Self { pos, ..self }
@@ -141,7 +258,21 @@ impl<Pos> Legend<Pos> {
         Self { border, ..self }
     }
 
-    /// Set the number of columns for the legend entries and return self for chaining
+    /// Set the corner radius of the legend box and return self for chaining.
+    /// Zero (the default) gives sharp corners.
+    pub fn with_radius(self, radius: f32) -> Self {
+        Self { radius, ..self }
+    }
+
+    /// Set the drop shadow of the legend box and return self for chaining.
+    /// By default, there is no shadow.
+    pub fn with_shadow(self, shadow: Option<theme::Shadow>) -> Self {
+        Self { shadow, ..self }
+    }
+
+    /// Set the number of columns for the legend entries and return self for chaining.
+    /// If the columns don't fit in the width available to the legend,
+    /// fewer columns are used, down to a single one.
     pub fn with_columns(self, columns: u32) -> Self {
         Self {
             columns: Some(NonZeroU32::new(columns).expect("columns > 0")),
@@ -149,6 +280,18 @@ impl<Pos> Legend<Pos> {
         }
     }
 
+    /// Set the maximum number of rows for the legend entries and return self for chaining.
+    /// Entries wrap into as many columns as needed.
+    /// The width available to the legend takes precedence: if these columns don't fit,
+    /// fewer columns are used and the legend exceeds the maximum number of rows.
+    /// Ignored if the number of columns is set explicitly.
+    pub fn with_max_rows(self, max_rows: u32) -> Self {
+        Self {
+            max_rows: Some(NonZeroU32::new(max_rows).expect("max_rows > 0")),
+            ..self
+        }
+    }
+
     /// Set the spacing between legend entries and return self for chaining
     pub fn with_spacing(self, spacing: Size) -> Self {
         Self { spacing, ..self }
@@ -163,4 +306,26 @@ impl<Pos> Legend<Pos> {
     pub fn with_margin(self, margin: f32) -> Self {
         Self { margin, ..self }
     }
+
+    /// Add a manual entry to the legend and return self for chaining
+    pub fn with_entry(mut self, entry: LegendEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    /// Set the placement of manual entries and return self for chaining
+    pub fn with_entry_order(self, entry_order: EntryOrder) -> Self {
+        Self {
+            entry_order,
+            ..self
+        }
+    }
+
+    /// Set how labels wider than the available width are handled and return self for chaining
+    pub fn with_label_overflow(self, label_overflow: text::rich::Overflow) -> Self {
+        Self {
+            label_overflow,
+            ..self
+        }
+    }
 }
@@ -1,10 +1,49 @@
 //! Plot design structures
 
+use crate::des::annot::Span;
 use crate::des::{Annotation, Axis, Legend, PlotIdx, Series};
 use crate::style::{defaults, theme};
+use crate::{geom, text};
+
+super::define_rich_text_structs!(Title, TitleProps, TitleOptProps);
+
+impl Default for TitleProps {
+    fn default() -> Self {
+        TitleProps::new(defaults::PLOT_TITLE_FONT_SIZE)
+    }
+}
+
+/// Box border style for the plot area
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BorderBox {
+    /// Line style for the border
+    pub line: theme::Stroke,
+    /// Radius of the rounded corners. Zero for sharp corners.
+    ///
+    /// The plot area fill follows the rounded corners, but the series are
+    /// clipped to the plot area rectangle unless [`Clip::RoundedRect`] is also set.
+    pub radius: f32,
+}
+
+impl Default for BorderBox {
+    fn default() -> Self {
+        BorderBox {
+            line: theme::Col::Foreground.into(),
+            radius: 0.0,
+        }
+    }
+}
+
+impl From<theme::Stroke> for BorderBox {
+    fn from(line: theme::Stroke) -> Self {
+        BorderBox { line, radius: 0.0 }
+    }
+}
 
 /// Arrow border style for the plot area
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AxisArrow {
     /// Line style for the border and arrow
     pub line: theme::Stroke,
@@ -30,9 +69,10 @@ impl Default for AxisArrow {
 
 /// Border style for the plot area
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Border {
     /// A box border around the plot area
-    Box(theme::Stroke),
+    Box(BorderBox),
     /// Border only on the axes sides
     Axis(theme::Stroke),
     /// Arrow border on the axes sides
@@ -43,7 +83,7 @@ impl Border {
     /// Get the line style for the border if applicable
     pub fn line(&self) -> &theme::Stroke {
         match self {
-            Border::Box(line) => line,
+            Border::Box(bb) => &bb.line,
             Border::Axis(line) => line,
             Border::AxisArrow(arrow) => &arrow.line,
         }
@@ -52,7 +92,19 @@ impl Border {
 
 impl Default for Border {
     fn default() -> Self {
-        Border::Box(theme::Col::Foreground.into())
+        Border::Box(BorderBox::default())
+    }
+}
+
+impl From<BorderBox> for Border {
+    fn from(bb: BorderBox) -> Self {
+        Border::Box(bb)
+    }
+}
+
+impl From<BorderBox> for Option<Border> {
+    fn from(bb: BorderBox) -> Self {
+        Some(Border::Box(bb))
     }
 }
 
@@ -68,9 +120,124 @@ impl From<AxisArrow> for Option<Border> {
     }
 }
 
+/// Visibility and style of one side of the plot border
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spine {
+    /// Whether the spine is drawn
+    pub visible: bool,
+    /// Color of the spine. If `None`, the color of the border line is used.
+    pub color: Option<theme::Color>,
+    /// Line width of the spine. If `None`, the width of the border line is used.
+    pub width: Option<f32>,
+}
+
+impl Spine {
+    /// A visible spine, styled as the border line
+    pub const VISIBLE: Spine = Spine {
+        visible: true,
+        color: None,
+        width: None,
+    };
+
+    /// A hidden spine
+    pub const HIDDEN: Spine = Spine {
+        visible: false,
+        color: None,
+        width: None,
+    };
+
+    /// Set the color of the spine and return self for chaining
+    pub fn with_color(self, color: theme::Color) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+
+    /// Set the line width of the spine and return self for chaining
+    pub fn with_width(self, width: f32) -> Self {
+        Self {
+            width: Some(width),
+            ..self
+        }
+    }
+
+    /// Apply the overrides of this spine to the border line
+    pub fn stroke(&self, line: &theme::Stroke) -> theme::Stroke {
+        theme::Stroke {
+            color: self.color.unwrap_or(line.color),
+            width: self.width.unwrap_or(line.width),
+            ..line.clone()
+        }
+    }
+}
+
+impl Default for Spine {
+    fn default() -> Self {
+        Spine::VISIBLE
+    }
+}
+
+/// Per side control of the plot border.
+///
+/// Each side of the [`Border`] can be hidden or styled independently.
+/// With [`Border::Box`], a box with customized sides is drawn as four separate lines,
+/// without rounded corners.
+/// With [`Border::Axis`] and [`Border::AxisArrow`], the spines apply to the axes of the corresponding sides.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpineConfig {
+    /// The spine at the top of the plot area
+    pub top: Spine,
+    /// The spine at the right of the plot area
+    pub right: Spine,
+    /// The spine at the bottom of the plot area
+    pub bottom: Spine,
+    /// The spine at the left of the plot area
+    pub left: Spine,
+}
+
+impl SpineConfig {
+    /// The "open" look, with only the left and bottom spines visible
+    pub fn open() -> Self {
+        SpineConfig {
+            top: Spine::HIDDEN,
+            right: Spine::HIDDEN,
+            ..Default::default()
+        }
+    }
+
+    /// Set the top spine and return self for chaining
+    pub fn with_top(self, top: Spine) -> Self {
+        Self { top, ..self }
+    }
+
+    /// Set the right spine and return self for chaining
+    pub fn with_right(self, right: Spine) -> Self {
+        Self { right, ..self }
+    }
+
+    /// Set the bottom spine and return self for chaining
+    pub fn with_bottom(self, bottom: Spine) -> Self {
+        Self { bottom, ..self }
+    }
+
+    /// Set the left spine and return self for chaining
+    pub fn with_left(self, left: Spine) -> Self {
+        Self { left, ..self }
+    }
+
+    /// Whether all the spines are visible and styled as the border line
+    pub fn is_default(&self) -> bool {
+        *self == SpineConfig::default()
+    }
+}
+
 /// Insets inside the plot area
 /// around the data.
 #[derive(Debug, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Insets {
     /// The insets depends on the style of series
     #[default]
@@ -79,8 +246,44 @@ pub enum Insets {
     Fixed(f32, f32),
 }
 
+/// Clipping of the series drawn in the plot area
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Clip {
+    /// Clip to the plot area rectangle
+    #[default]
+    Rect,
+    /// Clip to the plot area rectangle, with corners rounded by the given radius
+    RoundedRect(f32),
+    /// Clip to a custom path, in figure units relative to the top-left corner of the plot area
+    Custom(#[cfg_attr(feature = "serde", serde(with = "geom::serde_path"))] geom::Path),
+}
+
+/// Aspect ratio lock between X and Y data units.
+/// Only supported for plots with linear, non-shared axes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AspectMode {
+    /// One data unit along X has the same length than one data unit along Y
+    Equal,
+    /// One data unit along Y is the given number of times longer than one data unit along X.
+    /// The ratio must be finite and positive, otherwise the figure fails to prepare.
+    Ratio(f64),
+}
+
+impl AspectMode {
+    /// The length of a Y data unit relatively to the length of a X data unit
+    pub fn ratio(&self) -> f64 {
+        match self {
+            AspectMode::Equal => 1.0,
+            AspectMode::Ratio(ratio) => *ratio,
+        }
+    }
+}
+
 /// Position of the legend relatively to the plot
 #[derive(Debug, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LegendPos {
     /// Position the legend outside the plot area at the top
     OutTop,
@@ -140,8 +343,219 @@ impl From<LegendPos> for PlotLegend {
     }
 }
 
+/// A zoomed inset of a region of a plot.
+///
+/// The inset is a smaller plot, drawn over its parent plot, showing the series
+/// of the main axes of the parent within `x_range` and `y_range`.
+/// The zoomed region is outlined on the parent plot and connected to the inset by lines.
+/// Series referring to other axes of the parent are not shown in the inset.
+///
+/// The rectangle of the inset is normalized to the parent plot area:
+/// `(0, 0)` is the top-left corner and `(1, 1)` the bottom-right corner.
+/// The rectangle includes the axes of the inset.
+/// The axes of the inset are copies of the main axes of the parent, without title.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoomInset {
+    x_range: (f64, f64),
+    y_range: (f64, f64),
+    rect: geom::Rect,
+    line: theme::Stroke,
+    fill: Option<theme::Fill>,
+    connectors: bool,
+}
+
+impl ZoomInset {
+    /// Create a new zoomed inset of the region delimited by `x_range` and `y_range`,
+    /// placed in the normalized rectangle `rect` of the parent plot area
+    pub fn new(x_range: (f64, f64), y_range: (f64, f64), rect: geom::Rect) -> Self {
+        ZoomInset {
+            x_range,
+            y_range,
+            rect,
+            line: theme::Col::Foreground.into(),
+            fill: Some(theme::Col::Background.into()),
+            connectors: true,
+        }
+    }
+
+    /// Set the line style of the region outline and connectors and return self for chaining
+    pub fn with_line(self, line: theme::Stroke) -> Self {
+        Self { line, ..self }
+    }
+
+    /// Set the fill of the inset plot area and return self for chaining.
+    /// By default, the inset is filled with the background color to hide the parent plot.
+    pub fn with_fill(self, fill: Option<theme::Fill>) -> Self {
+        Self { fill, ..self }
+    }
+
+    /// Set whether the connector lines between the region and the inset are drawn,
+    /// and return self for chaining
+    pub fn with_connectors(self, connectors: bool) -> Self {
+        Self { connectors, ..self }
+    }
+
+    /// Get the X range of the zoomed region
+    pub fn x_range(&self) -> (f64, f64) {
+        self.x_range
+    }
+
+    /// Get the Y range of the zoomed region
+    pub fn y_range(&self) -> (f64, f64) {
+        self.y_range
+    }
+
+    /// Get the normalized rectangle of the inset in the parent plot area
+    pub fn rect(&self) -> &geom::Rect {
+        &self.rect
+    }
+
+    /// Get the line style of the region outline and connectors
+    pub fn line(&self) -> &theme::Stroke {
+        &self.line
+    }
+
+    /// Get the fill of the inset plot area
+    pub fn fill(&self) -> Option<&theme::Fill> {
+        self.fill.as_ref()
+    }
+
+    /// Get whether the connector lines are drawn
+    pub fn connectors(&self) -> bool {
+        self.connectors
+    }
+}
+
+/// Position of a panel label, relatively to the plot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PanelLabelPos {
+    /// At the top left corner of the plot cell, above the plot and its axes.
+    /// The space is reserved on the line of the plot title.
+    #[default]
+    OutTopLeft,
+    /// At the top right corner of the plot cell, above the plot and its axes.
+    /// The space is reserved on the line of the plot title.
+    OutTopRight,
+    /// Inside the plot area, at the top left corner
+    InTopLeft,
+    /// Inside the plot area, at the top right corner
+    InTopRight,
+    /// Inside the plot area, at the bottom left corner
+    InBottomLeft,
+    /// Inside the plot area, at the bottom right corner
+    InBottomRight,
+}
+
+/// Numbering style of automatic panel labels.
+/// See [`Plots::with_auto_labels`](crate::des::figure::Plots::with_auto_labels).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PanelLabelStyle {
+    /// Lowercase letters: a, b, c, ..., z, aa, ab, ...
+    #[default]
+    Lowercase,
+    /// Uppercase letters: A, B, C, ..., Z, AA, AB, ...
+    Uppercase,
+    /// Numbers starting at 1
+    Numbers,
+}
+
+impl PanelLabelStyle {
+    /// The label of the plot at `index`, starting at 0
+    pub fn label(&self, index: usize) -> String {
+        let first = match self {
+            PanelLabelStyle::Lowercase => b'a',
+            PanelLabelStyle::Uppercase => b'A',
+            PanelLabelStyle::Numbers => return (index + 1).to_string(),
+        };
+        // bijective base 26: z is followed by aa
+        let mut letters = Vec::new();
+        let mut n = index + 1;
+        while n > 0 {
+            n -= 1;
+            letters.push((first + (n % 26) as u8) as char);
+            n /= 26;
+        }
+        letters.iter().rev().collect()
+    }
+}
+
+/// A short label identifying a plot in a figure, e.g. `a` or `B`.
+/// By default, the label is bold, with the theme foreground color.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PanelLabel {
+    text: String,
+    pos: PanelLabelPos,
+    font: text::Font,
+    font_size: f32,
+    color: theme::Color,
+}
+
+impl PanelLabel {
+    /// Create a new panel label with the given text, at the top left corner of the plot cell
+    pub fn new(text: impl Into<String>) -> Self {
+        let font: text::Font = defaults::FONT_FAMILY.parse().unwrap();
+        PanelLabel {
+            text: text.into(),
+            pos: PanelLabelPos::default(),
+            font: font.with_weight(text::font::Weight::BOLD),
+            font_size: defaults::PANEL_LABEL_FONT_SIZE,
+            color: theme::Col::Foreground.into(),
+        }
+    }
+
+    /// Set the position of the label and return self for chaining
+    pub fn with_pos(self, pos: PanelLabelPos) -> Self {
+        Self { pos, ..self }
+    }
+
+    /// Set the font of the label and return self for chaining
+    pub fn with_font(self, font: text::Font) -> Self {
+        Self { font, ..self }
+    }
+
+    /// Set the font size of the label and return self for chaining
+    pub fn with_font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    /// Set the color of the label and return self for chaining
+    pub fn with_color(self, color: theme::Color) -> Self {
+        Self { color, ..self }
+    }
+
+    /// Get the text of the label
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the position of the label
+    pub fn pos(&self) -> PanelLabelPos {
+        self.pos
+    }
+
+    /// Get the font of the label
+    pub fn font(&self) -> &text::Font {
+        &self.font
+    }
+
+    /// Get the font size of the label
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// Get the color of the label
+    pub fn color(&self) -> theme::Color {
+        self.color
+    }
+}
+
 /// A plot, containing series, axes, title, legend, and styles
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Plot {
     series: Vec<Series>,
 
@@ -149,12 +563,20 @@ pub struct Plot {
     y_axes: Vec<Axis>,
     x_axis_set: bool,
     y_axis_set: bool,
-    title: Option<String>,
+    title: Option<Title>,
+    title_align: text::line::Align,
+    panel_label: Option<PanelLabel>,
     fill: Option<theme::Fill>,
     border: Option<Border>,
+    spines: SpineConfig,
     insets: Option<Insets>,
+    clip: Clip,
     legend: Option<PlotLegend>,
     annotations: Vec<Annotation>,
+    aspect: Option<AspectMode>,
+    padding: Option<geom::Padding>,
+    hidden_series_bounds: bool,
+    zoom_insets: Vec<ZoomInset>,
 }
 
 impl Plot {
@@ -167,11 +589,19 @@ impl Plot {
             x_axis_set: false,
             y_axis_set: false,
             title: None,
+            title_align: text::line::Align::Center,
+            panel_label: None,
             fill: None,
             border: Some(Border::default()),
+            spines: SpineConfig::default(),
             insets: Some(Insets::default()),
+            clip: Clip::default(),
             legend: None,
             annotations: vec![],
+            aspect: None,
+            padding: None,
+            hidden_series_bounds: true,
+            zoom_insets: vec![],
         }
     }
 
@@ -199,14 +629,36 @@ impl Plot {
         self
     }
 
-    /// Set the title of the plot and return self for chaining
-    pub fn with_title(self, title: String) -> Self {
+    /// Set the title of the plot and return self for chaining.
+    /// The title is drawn above the plot, beyond the axes on the top side,
+    /// and the space is reserved in the row of the plot.
+    pub fn with_title(self, title: Title) -> Self {
         Self {
             title: Some(title),
             ..self
         }
     }
 
+    /// Set the alignment of the title and return self for chaining.
+    /// The title is aligned with the left edge, the center or the right edge of the plot area
+    /// depending on the alignment. By default, the title is centered.
+    pub fn with_title_align(self, title_align: text::line::Align) -> Self {
+        Self {
+            title_align,
+            ..self
+        }
+    }
+
+    /// Set the panel label of the plot and return self for chaining.
+    /// See also [`Plots::with_auto_labels`](crate::des::figure::Plots::with_auto_labels)
+    /// to label all the plots of a figure.
+    pub fn with_panel_label(self, panel_label: PanelLabel) -> Self {
+        Self {
+            panel_label: Some(panel_label),
+            ..self
+        }
+    }
+
     /// Set the fill of the plot area and return self for chaining
     pub fn with_fill(self, fill: theme::Fill) -> Self {
         Self {
@@ -220,11 +672,31 @@ impl Plot {
         Self { border, ..self }
     }
 
+    /// Set the visibility and style of each side of the border and return self for chaining
+    pub fn with_spines(self, spines: SpineConfig) -> Self {
+        Self { spines, ..self }
+    }
+
     /// Set the insets of the plot area and return self for chaining
     pub fn with_insets(self, insets: Option<Insets>) -> Self {
         Self { insets, ..self }
     }
 
+    /// Set the padding around the plot and its axes, within the plot cell, and return self for chaining.
+    /// Negative values are clamped to zero.
+    /// If not set, the padding of the [`LayoutParams`](crate::drawing::LayoutParams) is used.
+    pub fn with_padding(self, padding: geom::Padding) -> Self {
+        Self {
+            padding: Some(padding.non_negative()),
+            ..self
+        }
+    }
+
+    /// Set the clipping of the series in the plot area and return self for chaining
+    pub fn with_clip(self, clip: Clip) -> Self {
+        Self { clip, ..self }
+    }
+
     /// Set the legend of the plot and return self for chaining
     pub fn with_legend(self, legend: PlotLegend) -> Self {
         Self {
@@ -233,12 +705,43 @@ impl Plot {
         }
     }
 
+    /// Lock the aspect ratio between X and Y data units and return self for chaining.
+    /// The plot area is shrunk within its cell and centered to honor the ratio.
+    pub fn with_aspect(self, aspect: AspectMode) -> Self {
+        Self {
+            aspect: Some(aspect),
+            ..self
+        }
+    }
+
+    /// Set whether hidden series contribute to the data bounds of the axes, and return self for chaining.
+    /// By default (`true`), the axes keep the same range when a series is hidden.
+    /// See [`Series::with_visible`].
+    pub fn with_hidden_series_bounds(self, hidden_series_bounds: bool) -> Self {
+        Self {
+            hidden_series_bounds,
+            ..self
+        }
+    }
+
     /// Add an arbitrary [`Annotation`] to the plot and return self for chaining
     pub fn with_annotation(mut self, annotation: Annotation) -> Self {
         self.annotations.push(annotation);
         self
     }
 
+    /// Add a shaded [`Span`] to the plot and return self for chaining.
+    /// This is a shortcut for adding the span as an [`Annotation`].
+    pub fn with_span(self, span: Span) -> Self {
+        self.with_annotation(span.into())
+    }
+
+    /// Add a zoomed inset of a region of the plot and return self for chaining
+    pub fn with_zoom_inset(mut self, inset: ZoomInset) -> Self {
+        self.zoom_insets.push(inset);
+        self
+    }
+
     /// Get the series of the plot
     pub fn series(&self) -> &[Series] {
         &self.series
@@ -255,8 +758,18 @@ impl Plot {
     }
 
     /// Get the title of the plot
-    pub fn title(&self) -> Option<&str> {
-        self.title.as_deref()
+    pub fn title(&self) -> Option<&Title> {
+        self.title.as_ref()
+    }
+
+    /// Get the alignment of the title
+    pub fn title_align(&self) -> text::line::Align {
+        self.title_align
+    }
+
+    /// Get the panel label of the plot
+    pub fn panel_label(&self) -> Option<&PanelLabel> {
+        self.panel_label.as_ref()
     }
 
     /// Get the fill of the plot area
@@ -269,11 +782,26 @@ impl Plot {
         self.border.as_ref()
     }
 
+    /// Get the visibility and style of each side of the border
+    pub fn spines(&self) -> &SpineConfig {
+        &self.spines
+    }
+
     /// Get the insets of the plot area
     pub fn insets(&self) -> Option<&Insets> {
         self.insets.as_ref()
     }
 
+    /// Get the padding around the plot and its axes, if set
+    pub fn padding(&self) -> Option<&geom::Padding> {
+        self.padding.as_ref()
+    }
+
+    /// Get the clipping of the series in the plot area
+    pub fn clip(&self) -> &Clip {
+        &self.clip
+    }
+
     /// Get the legend of the plot
     pub fn legend(&self) -> Option<&PlotLegend> {
         self.legend.as_ref()
@@ -284,6 +812,21 @@ impl Plot {
         &self.annotations
     }
 
+    /// Get the aspect lock of the plot
+    pub fn aspect(&self) -> Option<AspectMode> {
+        self.aspect
+    }
+
+    /// Get whether hidden series contribute to the data bounds of the axes
+    pub fn hidden_series_bounds(&self) -> bool {
+        self.hidden_series_bounds
+    }
+
+    /// Get the zoomed insets of the plot
+    pub fn zoom_insets(&self) -> &[ZoomInset] {
+        &self.zoom_insets
+    }
+
     /// Add a series to the plot
     pub fn push_series(&mut self, series: Series) {
         self.series.push(series);
@@ -294,6 +837,11 @@ impl Plot {
         self.annotations.push(annotation);
     }
 
+    /// Set or remove the panel label of the plot
+    pub fn set_panel_label(&mut self, panel_label: Option<PanelLabel>) {
+        self.panel_label = panel_label;
+    }
+
     /// Chaining helper to build a figure from this plot
     /// This is equivalent to `Figure::new(self.into())`
     ///
@@ -318,11 +866,16 @@ impl Plot {
 
 /// A collection of plots, arranged in a grid
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Subplots {
     rows: u32,
     cols: u32,
     plots: Vec<Option<Plot>>,
     space: f32,
+    col_ratios: Option<Vec<f32>>,
+    row_ratios: Option<Vec<f32>>,
+    shared_x: bool,
+    shared_y: bool,
 }
 
 impl Subplots {
@@ -333,6 +886,10 @@ impl Subplots {
             cols,
             plots: vec![None; (rows * cols) as usize],
             space: 0.0,
+            col_ratios: None,
+            row_ratios: None,
+            shared_x: false,
+            shared_y: false,
         }
     }
 
@@ -348,6 +905,44 @@ impl Subplots {
         Self { space, ..self }
     }
 
+    /// Set the relative widths of the columns and return self for chaining.
+    /// The space available to the plots is distributed proportionally to the ratios.
+    /// Panics if the number of ratios is not the number of columns or if a ratio is not positive.
+    pub fn with_col_ratios(self, ratios: &[f32]) -> Self {
+        assert_eq!(ratios.len(), self.cols as usize, "one ratio per column");
+        assert!(ratios.iter().all(|r| *r > 0.0), "ratios must be positive");
+        Self {
+            col_ratios: Some(ratios.to_vec()),
+            ..self
+        }
+    }
+
+    /// Set the relative heights of the rows and return self for chaining.
+    /// The space available to the plots is distributed proportionally to the ratios.
+    /// Panics if the number of ratios is not the number of rows or if a ratio is not positive.
+    pub fn with_row_ratios(self, ratios: &[f32]) -> Self {
+        assert_eq!(ratios.len(), self.rows as usize, "one ratio per row");
+        assert!(ratios.iter().all(|r| *r > 0.0), "ratios must be positive");
+        Self {
+            row_ratios: Some(ratios.to_vec()),
+            ..self
+        }
+    }
+
+    /// Share the X scale of the main axes across each column and return self for chaining.
+    /// Bounds are unified across the column, and only the bottom plot of the column
+    /// shows the tick labels and title of its X axis.
+    pub fn with_shared_x(self, shared_x: bool) -> Self {
+        Self { shared_x, ..self }
+    }
+
+    /// Share the Y scale of the main axes across each row and return self for chaining.
+    /// Bounds are unified across the row, and only the left plot of the row
+    /// shows the tick labels and title of its Y axis.
+    pub fn with_shared_y(self, shared_y: bool) -> Self {
+        Self { shared_y, ..self }
+    }
+
     /// Get a reference to a plot at the given row and column
     pub fn plot(&self, idx: impl Into<PlotIdx>) -> Option<&Plot> {
         let index = idx.into().index(self.cols);
@@ -380,9 +975,123 @@ impl Subplots {
         self.space
     }
 
+    /// Whether the X scale of the main axes is shared across each column
+    pub fn shared_x(&self) -> bool {
+        self.shared_x
+    }
+
+    /// Whether the Y scale of the main axes is shared across each row
+    pub fn shared_y(&self) -> bool {
+        self.shared_y
+    }
+
+    /// The relative widths of the columns, if set (equal widths otherwise)
+    pub fn col_ratios(&self) -> Option<&[f32]> {
+        self.col_ratios.as_deref()
+    }
+
+    /// The relative heights of the rows, if set (equal heights otherwise)
+    pub fn row_ratios(&self) -> Option<&[f32]> {
+        self.row_ratios.as_deref()
+    }
+
     /// Chaining helper to build a figure from these subplots
     /// This is equivalent to `Figure::new(self.into())`
     pub fn into_figure(self) -> super::Figure {
         super::Figure::new(self.into())
     }
 }
+
+/// A collection of plots placed at explicit positions in the figure,
+/// e.g. for an inset plot overlapping a main plot.
+///
+/// Each plot is given a rectangle normalized to the area available to the plots
+/// (the figure area without the padding, title and legend of the figure):
+/// `(0, 0)` is the top-left corner and `(1, 1)` the bottom-right corner.
+/// The rectangle includes the axes and the legend of the plot.
+///
+/// Plots can overlap, and are drawn in insertion order.
+/// They are indexed in insertion order as a single row of plots:
+/// the first plot is at `(0, 0)`, the second at `(0, 1)`, etc.
+/// Axes can't be shared across plots of a freeform layout.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FreeformLayout {
+    plots: Vec<(geom::Rect, Plot)>,
+}
+
+impl FreeformLayout {
+    /// Create a new empty freeform layout
+    pub fn new() -> Self {
+        FreeformLayout { plots: Vec::new() }
+    }
+
+    /// Add a plot in the normalized rectangle `rect` and return self for chaining
+    pub fn with_plot(mut self, rect: geom::Rect, plot: Plot) -> Self {
+        self.push_plot(rect, plot);
+        self
+    }
+
+    /// Add a plot in the normalized rectangle `rect`
+    pub fn push_plot(&mut self, rect: geom::Rect, plot: Plot) {
+        self.plots.push((rect, plot));
+    }
+
+    /// Get a reference to a plot and its normalized rectangle at the given index
+    pub fn plot(&self, idx: impl Into<PlotIdx>) -> Option<(&geom::Rect, &Plot)> {
+        let idx = idx.into();
+        if idx.row != 0 {
+            return None;
+        }
+        self.plots
+            .get(idx.col as usize)
+            .map(|(rect, plot)| (rect, plot))
+    }
+
+    /// Get a mutable reference to a plot at the given index
+    pub fn plot_mut(&mut self, idx: impl Into<PlotIdx>) -> Option<&mut Plot> {
+        let idx = idx.into();
+        if idx.row != 0 {
+            return None;
+        }
+        self.plots.get_mut(idx.col as usize).map(|(_, plot)| plot)
+    }
+
+    /// Returns an iterator over the plots and their normalized rectangle, in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&geom::Rect, &Plot)> + '_ {
+        self.plots.iter().map(|(rect, plot)| (rect, plot))
+    }
+
+    /// The number of plots in the layout
+    pub fn len(&self) -> usize {
+        self.plots.len()
+    }
+
+    /// Whether the layout has no plot
+    pub fn is_empty(&self) -> bool {
+        self.plots.is_empty()
+    }
+
+    /// Chaining helper to build a figure from this layout
+    /// This is equivalent to `Figure::new(self.into())`
+    pub fn into_figure(self) -> super::Figure {
+        super::Figure::new(self.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_panel_label_style() {
+        let lower = PanelLabelStyle::Lowercase;
+        assert_eq!(lower.label(0), "a");
+        assert_eq!(lower.label(25), "z");
+        assert_eq!(lower.label(26), "aa");
+        assert_eq!(lower.label(27), "ab");
+        assert_eq!(PanelLabelStyle::Uppercase.label(1), "B");
+        assert_eq!(PanelLabelStyle::Numbers.label(0), "1");
+        assert_eq!(PanelLabelStyle::Numbers.label(11), "12");
+    }
+}
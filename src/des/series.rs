@@ -1,13 +1,19 @@
 //! Data series definitions for plots.
 use crate::data;
-use crate::des::axis;
-use crate::style::{self, defaults};
+use crate::des::{ZOrder, axis};
+use crate::style::{self, defaults, theme};
+use crate::text::Font;
 
 /// A data column, either inline or a reference to a data source.
 ///
 /// Data columns can contain either inline data (vectors of values) or references
 /// to columns in a data source. This allows for flexible data handling in series.
+///
+/// When serialized, a reference is a plain string (e.g. `"time"`),
+/// and inline data is a typed column (e.g. `{"F64": [1.0, 2.0]}`).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(untagged))]
 pub enum DataCol {
     /// The data is provided inline, directly in the series
     Inline(data::VecColumn),
@@ -64,6 +70,7 @@ impl From<Vec<String>> for DataCol {
 /// This enum represents the different types of series that can be visualized.
 /// Each variant contains specific configuration and data for that series type.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Series {
     /// Plots data as a continuous line.
     Line(Line),
@@ -71,10 +78,14 @@ pub enum Series {
     Scatter(Scatter),
     /// Plots data in histograms.
     Histogram(Histogram),
+    /// Plots 2D data in a grid of bins colored by count.
+    Hist2d(Hist2d),
     /// Plots data as discrete bars.
     Bars(Bars),
     /// Plots data as a group of bars, that can be either stacked or aside
     BarsGroup(BarsGroup),
+    /// Plots data as a group of stacked areas
+    AreasGroup(AreasGroup),
 }
 
 impl Series {
@@ -84,11 +95,85 @@ impl Series {
             Series::Line(s) => (s.x_axis(), s.y_axis()),
             Series::Scatter(s) => (s.x_axis(), s.y_axis()),
             Series::Histogram(s) => (s.x_axis(), s.y_axis()),
+            Series::Hist2d(s) => (s.x_axis(), s.y_axis()),
             Series::Bars(s) => (s.x_axis(), s.y_axis()),
             Series::BarsGroup(s) => (s.x_axis(), s.y_axis()),
+            Series::AreasGroup(s) => (s.x_axis(), s.y_axis()),
+        }
+    }
+
+    /// Get the name of this series, as shown in the legend.
+    /// Groups of bars or areas have no name of their own, only their series.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Series::Line(s) => s.name(),
+            Series::Scatter(s) => s.name(),
+            Series::Histogram(s) => s.name(),
+            Series::Hist2d(s) => s.name(),
+            Series::Bars(s) => s.name(),
+            Series::BarsGroup(_) => None,
+            Series::AreasGroup(_) => None,
+        }
+    }
+
+    /// Get the z-order of this series
+    pub fn zorder(&self) -> ZOrder {
+        match self {
+            Series::Line(s) => s.zorder(),
+            Series::Scatter(s) => s.zorder(),
+            Series::Histogram(s) => s.zorder(),
+            Series::Hist2d(s) => s.zorder(),
+            Series::Bars(s) => s.zorder(),
+            Series::BarsGroup(s) => s.zorder(),
+            Series::AreasGroup(s) => s.zorder(),
+        }
+    }
+
+    /// Get whether this series is visible
+    pub fn visible(&self) -> bool {
+        match self {
+            Series::Line(s) => s.visible(),
+            Series::Scatter(s) => s.visible(),
+            Series::Histogram(s) => s.visible(),
+            Series::Hist2d(s) => s.visible(),
+            Series::Bars(s) => s.visible(),
+            Series::BarsGroup(s) => s.visible(),
+            Series::AreasGroup(s) => s.visible(),
+        }
+    }
+
+    /// Set whether this series is visible and return self for chaining.
+    /// A hidden series is not drawn, but keeps its legend entry, which is dimmed.
+    pub fn with_visible(self, visible: bool) -> Self {
+        match self {
+            Series::Line(s) => Series::Line(s.with_visible(visible)),
+            Series::Scatter(s) => Series::Scatter(s.with_visible(visible)),
+            Series::Histogram(s) => Series::Histogram(s.with_visible(visible)),
+            Series::Hist2d(s) => Series::Hist2d(s.with_visible(visible)),
+            Series::Bars(s) => Series::Bars(s.with_visible(visible)),
+            Series::BarsGroup(s) => Series::BarsGroup(s.with_visible(visible)),
+            Series::AreasGroup(s) => Series::AreasGroup(s.with_visible(visible)),
         }
     }
 
+    /// Copy of this series referring to the main axes of its plot.
+    /// Used for the zoomed insets, which only have main axes.
+    pub(crate) fn to_main_axes(&self) -> Self {
+        let mut series = self.clone();
+        let (x_axis, y_axis) = match &mut series {
+            Series::Line(s) => (&mut s.x_axis, &mut s.y_axis),
+            Series::Scatter(s) => (&mut s.x_axis, &mut s.y_axis),
+            Series::Histogram(s) => (&mut s.x_axis, &mut s.y_axis),
+            Series::Hist2d(s) => (&mut s.x_axis, &mut s.y_axis),
+            Series::Bars(s) => (&mut s.x_axis, &mut s.y_axis),
+            Series::BarsGroup(s) => (&mut s.x_axis, &mut s.y_axis),
+            Series::AreasGroup(s) => (&mut s.x_axis, &mut s.y_axis),
+        };
+        *x_axis = axis::Ref::default();
+        *y_axis = axis::Ref::default();
+        series
+    }
+
     /// Helper to build a plot from this series
     /// This can only be used if your plot contains a single series.
     /// This is equivalent to `Plot::new(vec![self])`
@@ -115,6 +200,12 @@ impl From<Histogram> for Series {
     }
 }
 
+impl From<Hist2d> for Series {
+    fn from(hist2d: Hist2d) -> Self {
+        Series::Hist2d(hist2d)
+    }
+}
+
 impl From<Bars> for Series {
     fn from(bars: Bars) -> Self {
         Series::Bars(bars)
@@ -127,8 +218,15 @@ impl From<BarsGroup> for Series {
     }
 }
 
+impl From<AreasGroup> for Series {
+    fn from(areas_group: AreasGroup) -> Self {
+        Series::AreasGroup(areas_group)
+    }
+}
+
 /// Interpolation methods for line series.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Interpolation {
     /// Straight line segments between points.
     #[default]
@@ -146,25 +244,65 @@ pub enum Interpolation {
     Spline,
 }
 
+/// Smoothing algorithms for line series.
+///
+/// Samples are assumed to be evenly spaced, and null values reset the smoothing window,
+/// unless the line connects null values (see [`Line::with_connect_nulls`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Smoothing {
+    /// Centered moving average over the given number of samples
+    MovingAverage(usize),
+    /// Exponentially weighted moving average, with the given smoothing factor between 0 and 1.
+    /// The higher the factor, the less smoothing.
+    Ewma(f64),
+    /// Savitzky-Golay filter: least-squares fit of a polynomial of the given order
+    /// over a centered window of samples.
+    /// Preserves peaks better than the moving average.
+    SavitzkyGolay {
+        /// Number of samples in the window
+        window: usize,
+        /// Order of the fitted polynomial
+        order: u32,
+    },
+}
+
 /// A line series structure.
 ///
 /// Plots data as a continuous line connecting points in order.
 /// This is one of the most common series types for visualizing trends and continuous data.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Line {
-    x_data: DataCol,
+    x_data: Option<DataCol>,
     y_data: DataCol,
 
     name: Option<String>,
     x_axis: axis::Ref,
     y_axis: axis::Ref,
+    zorder: ZOrder,
+    visible: bool,
     stroke: style::series::Stroke,
     interpolation: Interpolation,
+    connect_nulls: bool,
+    smoothing: Option<Smoothing>,
+    smoothing_stroke: Option<style::series::Stroke>,
+    smoothing_only: bool,
 }
 
 impl Line {
     /// Create a new line series with the given x and y data columns
     pub fn new(x_data: DataCol, y_data: DataCol) -> Self {
+        Self::new_opt_x(Some(x_data), y_data)
+    }
+
+    /// Create a new line series with only y data.
+    /// The x data is the row index of the y data, so the x axis runs from `0` to `len - 1`.
+    pub fn from_y(y_data: DataCol) -> Self {
+        Self::new_opt_x(None, y_data)
+    }
+
+    fn new_opt_x(x_data: Option<DataCol>, y_data: DataCol) -> Self {
         Line {
             x_data,
             y_data,
@@ -172,8 +310,14 @@ impl Line {
             name: None,
             x_axis: Default::default(),
             y_axis: Default::default(),
+            zorder: ZOrder::SERIES,
+            visible: true,
             stroke: style::series::Stroke::default().with_width(defaults::SERIES_LINE_WIDTH),
             interpolation: Interpolation::default(),
+            connect_nulls: false,
+            smoothing: None,
+            smoothing_stroke: None,
+            smoothing_only: false,
         }
     }
 
@@ -211,9 +355,51 @@ impl Line {
         self
     }
 
-    /// Get the x data column
-    pub fn x_data(&self) -> &DataCol {
-        &self.x_data
+    /// Set whether null data points are skipped, and return self for chaining.
+    /// If `true`, the line is drawn straight across null values, between the surrounding valid points.
+    /// By default (`false`), null values break the line.
+    pub fn with_connect_nulls(mut self, connect_nulls: bool) -> Self {
+        self.connect_nulls = connect_nulls;
+        self
+    }
+
+    /// Draw a smoothed version of the data over the raw line and return self for chaining.
+    /// The y data must be numeric.
+    pub fn with_smoothing(mut self, smoothing: Smoothing) -> Self {
+        self.smoothing = Some(smoothing);
+        self
+    }
+
+    /// Set the style of the smoothed line and return self for chaining.
+    /// By default, the smoothed line is drawn with the line style, twice as wide,
+    /// or as is if only the smoothed line is shown.
+    pub fn with_smoothing_line(mut self, line: style::series::Stroke) -> Self {
+        self.smoothing_stroke = Some(line);
+        self
+    }
+
+    /// Draw only the smoothed line, not the raw data, and return self for chaining
+    pub fn with_smoothing_only(mut self) -> Self {
+        self.smoothing_only = true;
+        self
+    }
+
+    /// Set the z-order of the series and return self for chaining.
+    /// By default, series are drawn at [`ZOrder::SERIES`], in the order of the plot.
+    /// Raise it to keep the line above the series that follow it in the plot.
+    pub fn with_zorder(self, zorder: ZOrder) -> Self {
+        Self { zorder, ..self }
+    }
+
+    /// Set whether the series is visible and return self for chaining.
+    /// A hidden series is not drawn, but keeps its legend entry, which is dimmed.
+    pub fn with_visible(self, visible: bool) -> Self {
+        Self { visible, ..self }
+    }
+    /// Get the x data column, if any.
+    /// Without x data, the row index of the y data is used.
+    pub fn x_data(&self) -> Option<&DataCol> {
+        self.x_data.as_ref()
     }
 
     /// Get the y data column
@@ -236,6 +422,16 @@ impl Line {
         &self.y_axis
     }
 
+    /// Get the z-order
+    pub fn zorder(&self) -> ZOrder {
+        self.zorder
+    }
+
+    /// Get whether the series is visible
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
     /// Get the stroke style
     pub fn stroke(&self) -> &style::series::Stroke {
         &self.stroke
@@ -267,6 +463,41 @@ impl Line {
     pub fn interpolation(&self) -> Interpolation {
         self.interpolation
     }
+
+    /// Get whether null data points are skipped instead of breaking the line
+    pub fn connect_nulls(&self) -> bool {
+        self.connect_nulls
+    }
+
+    /// Get the smoothing algorithm, if any
+    pub fn smoothing(&self) -> Option<&Smoothing> {
+        self.smoothing.as_ref()
+    }
+
+    /// Get the style of the smoothed line, if set
+    pub fn smoothing_line(&self) -> Option<&style::series::Stroke> {
+        self.smoothing_stroke.as_ref()
+    }
+
+    /// Get whether only the smoothed line is drawn
+    pub fn smoothing_only(&self) -> bool {
+        self.smoothing.is_some() && self.smoothing_only
+    }
+}
+
+/// Fitted model of a scatter series trend line.
+///
+/// The model is fitted with least squares on the numeric data points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fit {
+    /// Straight line: `y = a + b.x`
+    Linear,
+    /// Polynomial of the given degree: `y = a + b.x + c.x² + ...`
+    Polynomial(u32),
+    /// Exponential trend, linear in log space: `ln(y) = a + b.x`.
+    /// Data points with non-positive y are ignored.
+    LogLinear,
 }
 
 /// A scatter series structure.
@@ -274,19 +505,41 @@ impl Line {
 /// Plots data as individual scatter points without connecting them.
 /// Useful for visualizing correlations, distributions, and discrete data points.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Scatter {
-    x_data: DataCol,
+    x_data: Option<DataCol>,
     y_data: DataCol,
 
     name: Option<String>,
     x_axis: axis::Ref,
     y_axis: axis::Ref,
+    zorder: ZOrder,
+    visible: bool,
     marker: style::series::Marker,
+    size_data: Option<DataCol>,
+    size_range: (f32, f32),
+    color_data: Option<DataCol>,
+    colormap: style::series::ColorMap,
+    trendline: Option<Fit>,
+    trendline_stroke: style::series::Stroke,
+    alpha: f32,
+    legend_alpha: bool,
+    spread: Option<Spread>,
 }
 
 impl Scatter {
     /// Create a new scatter series with the given x and y data columns
     pub fn new(x_data: DataCol, y_data: DataCol) -> Self {
+        Self::new_opt_x(Some(x_data), y_data)
+    }
+
+    /// Create a new scatter series with only y data.
+    /// The x data is the row index of the y data, so the x axis runs from `0` to `len - 1`.
+    pub fn from_y(y_data: DataCol) -> Self {
+        Self::new_opt_x(None, y_data)
+    }
+
+    fn new_opt_x(x_data: Option<DataCol>, y_data: DataCol) -> Self {
         Scatter {
             x_data,
             y_data,
@@ -294,7 +547,19 @@ impl Scatter {
             name: None,
             x_axis: Default::default(),
             y_axis: Default::default(),
+            zorder: ZOrder::SERIES,
+            visible: true,
             marker: style::series::Marker::default(),
+            size_data: None,
+            size_range: defaults::MARKER_SIZE_RANGE,
+            color_data: None,
+            colormap: Default::default(),
+            trendline: None,
+            trendline_stroke: style::series::Stroke::default()
+                .with_width(defaults::SERIES_LINE_WIDTH),
+            alpha: 1.0,
+            legend_alpha: false,
+            spread: None,
         }
     }
 
@@ -326,9 +591,112 @@ impl Scatter {
         self
     }
 
-    /// Get the x data column
-    pub fn x_data(&self) -> &DataCol {
-        &self.x_data
+    /// Set a numeric data column that encodes the marker sizes, and return self for chaining.
+    ///
+    /// The column values are mapped to the marker areas, from the smallest to the largest
+    /// size of the size range. Markers with a null size have the size of the marker style.
+    pub fn with_size_column(mut self, data: DataCol) -> Self {
+        self.size_data = Some(data);
+        self
+    }
+
+    /// Set the range of marker sizes used with a size column, and return self for chaining
+    pub fn with_size_range(mut self, min: f32, max: f32) -> Self {
+        self.size_range = (min, max);
+        self
+    }
+
+    /// Set a numeric data column that encodes the marker fill colors, and return self for chaining.
+    ///
+    /// The column values are mapped to the color map set with [`with_colormap`](Self::with_colormap).
+    /// The colors replace the fill of the marker style, or its edge color if the marker has no fill.
+    /// Markers with a null color keep the marker style.
+    pub fn with_color_column(mut self, data: DataCol) -> Self {
+        self.color_data = Some(data);
+        self
+    }
+
+    /// Set the color map used with a color column, and return self for chaining
+    pub fn with_colormap(self, colormap: style::series::ColorMap) -> Self {
+        Self { colormap, ..self }
+    }
+
+    /// Draw a trend line fitted to the data across the x axis range, and return self for chaining.
+    /// The trend line is skipped if the data is not numeric or if the fit is degenerate,
+    /// for example with too few points or if all x values are equal.
+    pub fn with_trendline(mut self, fit: Fit) -> Self {
+        self.trendline = Some(fit);
+        self
+    }
+
+    /// Set the style of the trend line and return self for chaining
+    pub fn with_trendline_line(mut self, line: style::series::Stroke) -> Self {
+        self.trendline_stroke = line;
+        self
+    }
+
+    /// Set a global alpha (0.0 to 1.0) applied to every marker of the series, and return self for chaining.
+    ///
+    /// The alpha multiplies the opacity of the marker fill and edge, including the fills
+    /// encoded by a color column. With a low alpha, the density of overlapping markers
+    /// reads from the accumulated transparency.
+    /// The trend line is not affected.
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set whether the legend sample is also drawn with the alpha of the series, and return self for chaining.
+    /// By default, the legend sample is drawn at full opacity for clarity.
+    pub fn with_legend_alpha(mut self, legend_alpha: bool) -> Self {
+        self.legend_alpha = legend_alpha;
+        self
+    }
+
+    /// Spread the points randomly across the category axis, and return self for chaining.
+    ///
+    /// This makes strip plots, where the points of a category don't hide each other.
+    /// The `amount` is the width of the spread, relative to the width of a category
+    /// (the bin of a categorical axis, or one unit of a numeric axis), and is clamped to 0.0..=1.0.
+    /// The offsets are drawn from a generator initialized with `seed`, so that the output is reproducible.
+    /// See [`Spread::Jitter`].
+    pub fn with_jitter(self, amount: f32, seed: u64) -> Self {
+        Self {
+            spread: Some(Spread::Jitter {
+                amount: amount.clamp(0.0, 1.0),
+                seed,
+            }),
+            ..self
+        }
+    }
+
+    /// Pack the points across the category axis so that the markers don't overlap,
+    /// and return self for chaining.
+    ///
+    /// This makes swarm plots. See [`Spread::Swarm`].
+    pub fn with_swarm(self) -> Self {
+        Self {
+            spread: Some(Spread::Swarm),
+            ..self
+        }
+    }
+
+    /// Set the z-order of the series and return self for chaining.
+    /// By default, series are drawn at [`ZOrder::SERIES`], in the order of the plot.
+    /// Raise it to keep the markers above the lines or bars that follow them in the plot.
+    pub fn with_zorder(self, zorder: ZOrder) -> Self {
+        Self { zorder, ..self }
+    }
+
+    /// Set whether the series is visible and return self for chaining.
+    /// A hidden series is not drawn, but keeps its legend entry, which is dimmed.
+    pub fn with_visible(self, visible: bool) -> Self {
+        Self { visible, ..self }
+    }
+    /// Get the x data column, if any.
+    /// Without x data, the row index of the y data is used.
+    pub fn x_data(&self) -> Option<&DataCol> {
+        self.x_data.as_ref()
     }
 
     /// Get the y data column
@@ -351,10 +719,171 @@ impl Scatter {
         &self.y_axis
     }
 
+    /// Get the z-order
+    pub fn zorder(&self) -> ZOrder {
+        self.zorder
+    }
+
+    /// Get whether the series is visible
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
     /// Get the marker style
     pub fn marker(&self) -> &style::series::Marker {
         &self.marker
     }
+
+    /// Get the marker size data column, if any
+    pub fn size_data(&self) -> Option<&DataCol> {
+        self.size_data.as_ref()
+    }
+
+    /// Get the range of marker sizes used with a size column
+    pub fn size_range(&self) -> (f32, f32) {
+        self.size_range
+    }
+
+    /// Get the marker color data column, if any
+    pub fn color_data(&self) -> Option<&DataCol> {
+        self.color_data.as_ref()
+    }
+
+    /// Get the color map used with a color column
+    pub fn colormap(&self) -> &style::series::ColorMap {
+        &self.colormap
+    }
+
+    /// Get the trend line model, if any
+    pub fn trendline(&self) -> Option<&Fit> {
+        self.trendline.as_ref()
+    }
+
+    /// Get the style of the trend line
+    pub fn trendline_line(&self) -> &style::series::Stroke {
+        &self.trendline_stroke
+    }
+
+    /// Get the global alpha applied to the markers
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    /// Get whether the legend sample is drawn with the alpha of the series
+    pub fn legend_alpha(&self) -> bool {
+        self.legend_alpha
+    }
+
+    /// Get the spread of the points across the category axis, if any
+    pub fn spread(&self) -> Option<&Spread> {
+        self.spread.as_ref()
+    }
+}
+
+/// Spread of the points of a [`Scatter`] series across the category axis.
+///
+/// The category axis is the X axis, unless only the Y axis is categorical.
+/// The points are offset when drawn: the data itself and the hit-test samples are unchanged.
+/// The spread stays within the width of a category, which is the bin of a categorical axis,
+/// or one unit of a numeric axis (for example categories encoded as integers).
+/// The bounds of a numeric category axis expand to contain the spread.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Spread {
+    /// Random offsets, uniformly distributed over `amount` times the width of a category.
+    /// The offset of a point depends only on `seed` and on the index of the point in the data.
+    Jitter {
+        /// Width of the spread, relative to the width of a category
+        amount: f32,
+        /// Seed of the random offsets
+        seed: u64,
+    },
+    /// Offsets packing the markers side by side, without overlap.
+    /// The points of a category are placed in order of their value, each at the offset
+    /// the nearest to the center where it doesn't overlap the markers already placed.
+    /// Points that don't fit in the width of the category are clamped to its edges.
+    Swarm,
+}
+
+/// Maximum number of bins of a [`Histogram`] series computed from a [`BinSpec`]
+pub const MAX_HIST_BINS: u32 = 100_000;
+
+/// Strategy to compute the bins of a [`Histogram`] series.
+/// The number of bins computed from a count or a width can't exceed [`MAX_HIST_BINS`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinSpec {
+    /// A fixed number of bins of equal width, spanning the data range
+    Count(u32),
+    /// Bins of the given width, starting at the data minimum
+    Width(f64),
+    /// Explicit bin edges, which must be in increasing order.
+    /// `n` edges define `n - 1` bins. Data outside of the edges is not counted.
+    Edges(Vec<f64>),
+    /// Number of bins given by Sturges' rule: `ceil(log2(n)) + 1`.
+    /// Works well for roughly normal distributions.
+    Sturges,
+    /// Bin width given by the Freedman-Diaconis rule: `2 * IQR / cbrt(n)`.
+    /// Robust to outliers. Falls back to Sturges' rule if the inter-quartile range is zero,
+    /// or if it is so small compared to the data range that the bins would exceed [`MAX_HIST_BINS`].
+    FreedmanDiaconis,
+}
+
+impl Default for BinSpec {
+    fn default() -> Self {
+        BinSpec::Count(10)
+    }
+}
+
+impl From<u32> for BinSpec {
+    fn from(count: u32) -> Self {
+        BinSpec::Count(count)
+    }
+}
+
+impl From<Vec<f64>> for BinSpec {
+    fn from(edges: Vec<f64>) -> Self {
+        BinSpec::Edges(edges)
+    }
+}
+
+/// Cumulative mode of a [`Histogram`] series.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Cumulative {
+    /// Each bin shows its own value
+    #[default]
+    No,
+    /// Each bin shows the running sum of the bins up to itself
+    Forward,
+    /// Each bin shows the running sum of the bins from itself to the last one
+    Reverse,
+}
+
+impl From<bool> for Cumulative {
+    fn from(cumulative: bool) -> Self {
+        if cumulative {
+            Cumulative::Forward
+        } else {
+            Cumulative::No
+        }
+    }
+}
+
+/// Normalization of the bin values of a [`Histogram`] series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HistNorm {
+    /// Values are divided by the total count, such that the bins sum to 1.
+    /// A cumulative histogram then ends at 1.
+    Sum,
+    /// Values are divided by the greatest bin value, such that the highest bar is 1.
+    Max,
+    /// Probability density: values are divided by the total count and the bin width,
+    /// such that the histogram area sums to 1.
+    /// The cumulative sum of a density is a distribution function,
+    /// so a cumulative density histogram is the same as with [`HistNorm::Sum`].
+    Density,
 }
 
 /// A histogram series structure.
@@ -362,16 +891,20 @@ impl Scatter {
 /// Plots data by grouping values into bins and showing the frequency or density
 /// of values in each bin. Useful for visualizing distributions of continuous data.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Histogram {
     data: DataCol,
 
     name: Option<String>,
     x_axis: axis::Ref,
     y_axis: axis::Ref,
+    zorder: ZOrder,
+    visible: bool,
     fill: style::series::Fill,
     line: Option<style::series::Stroke>,
-    bins: u32,
-    density: bool,
+    bins: BinSpec,
+    cumulative: Cumulative,
+    norm: Option<HistNorm>,
 }
 
 impl Histogram {
@@ -383,10 +916,13 @@ impl Histogram {
             name: None,
             x_axis: Default::default(),
             y_axis: Default::default(),
+            zorder: ZOrder::SERIES,
+            visible: true,
             fill: style::series::Fill::default(),
             line: None,
-            bins: 10,
-            density: false,
+            bins: BinSpec::default(),
+            cumulative: Cumulative::No,
+            norm: None,
         }
     }
 
@@ -421,18 +957,54 @@ impl Histogram {
         self
     }
 
-    /// Set the number of bins and return self for chaining
-    pub fn with_bins(mut self, bins: u32) -> Self {
-        self.bins = bins;
+    /// Set the bin strategy and return self for chaining.
+    /// A plain number sets a fixed number of bins.
+    pub fn with_bins(mut self, bins: impl Into<BinSpec>) -> Self {
+        self.bins = bins.into();
         self
     }
 
-    /// Enable density mode (normalize by total count) and return self for chaining
+    /// Enable density mode and return self for chaining.
+    /// Bar heights are then normalized such that the histogram area sums to 1,
+    /// which is relevant for bins of unequal width.
+    /// This is equivalent to `with_normalize(HistNorm::Density)`.
     pub fn with_density(mut self) -> Self {
-        self.density = true;
+        self.norm = Some(HistNorm::Density);
+        self
+    }
+
+    /// Set the cumulative mode and return self for chaining.
+    /// `true` is a forward running sum.
+    ///
+    /// The running sum is computed before the normalization set with
+    /// [`with_normalize`](Self::with_normalize).
+    pub fn with_cumulative(mut self, cumulative: impl Into<Cumulative>) -> Self {
+        self.cumulative = cumulative.into();
         self
     }
 
+    /// Set the normalization of the bin values and return self for chaining.
+    ///
+    /// The normalization applies after the running sum of a cumulative histogram.
+    /// For example `with_cumulative(true).with_normalize(HistNorm::Sum)`
+    /// gives an empirical cumulative distribution function.
+    pub fn with_normalize(mut self, norm: HistNorm) -> Self {
+        self.norm = Some(norm);
+        self
+    }
+
+    /// Set the z-order of the series and return self for chaining.
+    /// By default, series are drawn at [`ZOrder::SERIES`], in the order of the plot.
+    /// Lower it to draw the bars of the histogram behind the other series.
+    pub fn with_zorder(self, zorder: ZOrder) -> Self {
+        Self { zorder, ..self }
+    }
+
+    /// Set whether the series is visible and return self for chaining.
+    /// A hidden series is not drawn, but keeps its legend entry, which is dimmed.
+    pub fn with_visible(self, visible: bool) -> Self {
+        Self { visible, ..self }
+    }
     /// Get the data column
     pub fn data(&self) -> &DataCol {
         &self.data
@@ -453,6 +1025,16 @@ impl Histogram {
         &self.y_axis
     }
 
+    /// Get the z-order
+    pub fn zorder(&self) -> ZOrder {
+        self.zorder
+    }
+
+    /// Get whether the series is visible
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
     /// Get the fill style
     pub fn fill(&self) -> &style::series::Fill {
         &self.fill
@@ -463,70 +1045,77 @@ impl Histogram {
         self.line.as_ref()
     }
 
-    /// Get the number of bins
-    pub fn bins(&self) -> u32 {
-        self.bins
+    /// Get the bin strategy
+    pub fn bins(&self) -> &BinSpec {
+        &self.bins
     }
 
     /// Get whether density mode is enabled
     pub fn density(&self) -> bool {
-        self.density
+        self.norm == Some(HistNorm::Density)
     }
-}
 
-/// Offset and width of the bar, in ratio of the category bin width.
-///
-/// The default is offset of 0.3, and width of 0.4, which has the effect of a bar centered in the bin
-/// (the bar starts at 30% of the bin and ends at 70% of the bin).
-///
-/// If multiple series are plotted, this offset and width should be adjusted, otherwise the bars will overlap.
-#[derive(Debug, Clone, Copy)]
-pub struct BarsPosition {
-    /// Offset from the start of the category bin (0.0 to 1.0).
-    pub offset: f32,
-    /// Width of the bar as a ratio of the bin width (0.0 to 1.0).
-    pub width: f32,
-}
+    /// Get the cumulative mode
+    pub fn cumulative(&self) -> Cumulative {
+        self.cumulative
+    }
 
-impl Default for BarsPosition {
-    fn default() -> Self {
-        BarsPosition {
-            offset: 0.3,
-            width: 0.4,
-        }
+    /// Get the normalization of the bin values, if any
+    pub fn normalize(&self) -> Option<HistNorm> {
+        self.norm
     }
 }
 
-/// A bars series structure.
+/// Normalization of the bin counts of a [`Hist2d`] series onto its color map.
+/// The most populated bin is always mapped to the end of the color map.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Hist2dNorm {
+    /// Counts are mapped linearly to the color map
+    #[default]
+    Linear,
+    /// Counts are mapped logarithmically to the color map,
+    /// which reveals the sparse bins of very dense data sets
+    Log,
+}
+
+/// A 2D histogram series structure.
 ///
-/// Plots data as discrete bars. One axis must contain categories, and the other must be numeric.
-/// Each category gets one bar whose height (or length for horizontal bars) represents the data value.
+/// Bins pairs of x and y values into a rectangular grid and colors each bin
+/// by its count, through a color map. Empty bins are not drawn.
+/// The bins along each axis are computed like those of a [`Histogram`], from a [`BinSpec`].
+/// Useful for visualizing the density of large scatter data sets.
 #[derive(Debug, Clone)]
-pub struct Bars {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hist2d {
     x_data: DataCol,
     y_data: DataCol,
 
     name: Option<String>,
     x_axis: axis::Ref,
     y_axis: axis::Ref,
-    fill: style::series::Fill,
-    line: Option<style::series::Stroke>,
-    position: BarsPosition,
+    zorder: ZOrder,
+    visible: bool,
+    colormap: style::series::ColorMap,
+    bins: (BinSpec, BinSpec),
+    norm: Hist2dNorm,
 }
 
-impl Bars {
-    /// Create a new bars series with the given x and y data columns
+impl Hist2d {
+    /// Create a new 2D histogram series with the given x and y data columns
     pub fn new(x_data: DataCol, y_data: DataCol) -> Self {
-        Bars {
+        Hist2d {
             x_data,
             y_data,
 
             name: None,
             x_axis: Default::default(),
             y_axis: Default::default(),
-            fill: style::series::Fill::default(),
-            line: None,
-            position: BarsPosition::default(),
+            zorder: ZOrder::SERIES,
+            visible: true,
+            colormap: Default::default(),
+            bins: (BinSpec::Count(10), BinSpec::Count(10)),
+            norm: Default::default(),
         }
     }
 
@@ -538,24 +1127,53 @@ impl Bars {
         }
     }
 
-    /// Set the fill style and return self for chaining
-    pub fn with_fill(self, fill: style::series::Fill) -> Self {
-        Self { fill, ..self }
+    /// Set a reference to the x axis and return self for chaining
+    pub fn with_x_axis(mut self, axis: axis::Ref) -> Self {
+        self.x_axis = axis;
+        self
     }
 
-    /// Set the line style for the bar outline and return self for chaining
-    pub fn with_line(self, line: style::series::Stroke) -> Self {
-        Self {
-            line: Some(line),
-            ..self
-        }
+    /// Set a reference to the y axis and return self for chaining
+    pub fn with_y_axis(mut self, axis: axis::Ref) -> Self {
+        self.y_axis = axis;
+        self
     }
 
-    /// Set the position (offset and width) and return self for chaining
-    pub fn with_position(self, position: BarsPosition) -> Self {
-        Self { position, ..self }
+    /// Set the color map and return self for chaining
+    pub fn with_colormap(self, colormap: style::series::ColorMap) -> Self {
+        Self { colormap, ..self }
+    }
+
+    /// Set the number of bins along x and y and return self for chaining
+    pub fn with_bins(self, nx: u32, ny: u32) -> Self {
+        self.with_bin_specs(BinSpec::Count(nx), BinSpec::Count(ny))
     }
 
+    /// Set the bin strategies along x and y and return self for chaining.
+    /// The total number of bins can't exceed [`MAX_HIST_BINS`].
+    pub fn with_bin_specs(mut self, x: BinSpec, y: BinSpec) -> Self {
+        self.bins = (x, y);
+        self
+    }
+
+    /// Set the count normalization mode and return self for chaining
+    pub fn with_norm(mut self, norm: Hist2dNorm) -> Self {
+        self.norm = norm;
+        self
+    }
+
+    /// Set the z-order of the series and return self for chaining.
+    /// By default, series are drawn at [`ZOrder::SERIES`], in the order of the plot.
+    /// Lower it, e.g. to [`ZOrder::BELOW_SERIES`], to draw the cells behind the other series.
+    pub fn with_zorder(self, zorder: ZOrder) -> Self {
+        Self { zorder, ..self }
+    }
+
+    /// Set whether the series is visible and return self for chaining.
+    /// A hidden series is not drawn, but keeps its legend entry, which is dimmed.
+    pub fn with_visible(self, visible: bool) -> Self {
+        Self { visible, ..self }
+    }
     /// Get the x data column
     pub fn x_data(&self) -> &DataCol {
         &self.x_data
@@ -581,20 +1199,301 @@ impl Bars {
         &self.y_axis
     }
 
-    /// Get the fill style
-    pub fn fill(&self) -> &style::series::Fill {
-        &self.fill
+    /// Get the z-order
+    pub fn zorder(&self) -> ZOrder {
+        self.zorder
     }
 
-    /// Get the line style, if any
-    pub fn line(&self) -> Option<&style::series::Stroke> {
-        self.line.as_ref()
+    /// Get whether the series is visible
+    pub fn visible(&self) -> bool {
+        self.visible
     }
 
-    /// Get the position configuration
+    /// Get the color map
+    pub fn colormap(&self) -> &style::series::ColorMap {
+        &self.colormap
+    }
+
+    /// Get the bin strategies along x and y
+    pub fn bins(&self) -> &(BinSpec, BinSpec) {
+        &self.bins
+    }
+
+    /// Get the count normalization mode
+    pub fn norm(&self) -> Hist2dNorm {
+        self.norm
+    }
+}
+
+/// Offset and width of the bar, in ratio of the category bin width.
+///
+/// The default is offset of 0.3, and width of 0.4, which has the effect of a bar centered in the bin
+/// (the bar starts at 30% of the bin and ends at 70% of the bin).
+///
+/// If multiple series are plotted, this offset and width should be adjusted, otherwise the bars will overlap.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BarsPosition {
+    /// Offset from the start of the category bin (0.0 to 1.0).
+    pub offset: f32,
+    /// Width of the bar as a ratio of the bin width (0.0 to 1.0).
+    pub width: f32,
+}
+
+impl Default for BarsPosition {
+    fn default() -> Self {
+        BarsPosition {
+            offset: 0.3,
+            width: 0.4,
+        }
+    }
+}
+
+impl BarsPosition {
+    /// Position of a bar centered in the category bin, with the given width ratio (0.0 to 1.0)
+    pub fn centered(width: f32) -> Self {
+        let width = width.clamp(0.0, 1.0);
+        BarsPosition {
+            offset: (1.0 - width) / 2.0,
+            width,
+        }
+    }
+}
+
+/// A bars series structure.
+///
+/// Plots data as discrete bars. One axis must contain categories, and the other must be numeric.
+/// Each category gets one bar whose height (or length for horizontal bars) represents the data value.
+///
+/// The orientation follows the data: bars are vertical if the x data is categorical,
+/// and horizontal if the y data is categorical. See [`Bars::with_orientation`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bars {
+    x_data: DataCol,
+    y_data: DataCol,
+
+    name: Option<String>,
+    x_axis: axis::Ref,
+    y_axis: axis::Ref,
+    zorder: ZOrder,
+    visible: bool,
+    fill: style::series::Fill,
+    line: Option<style::series::Stroke>,
+    position: BarsPosition,
+    corner_radius: f32,
+    orientation: Option<BarsOrientation>,
+    baseline: f64,
+    sign_colors: Option<(style::series::Color, style::series::Color)>,
+    sign_legend: BarsSignLegend,
+}
+
+impl Bars {
+    /// Create a new bars series with the given x and y data columns
+    pub fn new(x_data: DataCol, y_data: DataCol) -> Self {
+        Bars {
+            x_data,
+            y_data,
+
+            name: None,
+            x_axis: Default::default(),
+            y_axis: Default::default(),
+            zorder: ZOrder::SERIES,
+            visible: true,
+            fill: style::series::Fill::default(),
+            line: None,
+            position: BarsPosition::default(),
+            corner_radius: 0.0,
+            orientation: None,
+            baseline: 0.0,
+            sign_colors: None,
+            sign_legend: BarsSignLegend::default(),
+        }
+    }
+
+    /// Set the name and return self for chaining
+    pub fn with_name(self, name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+
+    /// Set the fill style and return self for chaining
+    pub fn with_fill(self, fill: style::series::Fill) -> Self {
+        Self { fill, ..self }
+    }
+
+    /// Set the line style for the bar outline and return self for chaining
+    pub fn with_line(self, line: style::series::Stroke) -> Self {
+        Self {
+            line: Some(line),
+            ..self
+        }
+    }
+
+    /// Set the position (offset and width) and return self for chaining
+    pub fn with_position(self, position: BarsPosition) -> Self {
+        Self { position, ..self }
+    }
+
+    /// Set the width of the bars as a ratio of the category bin (0.0 to 1.0),
+    /// with the bars centered in the bin, and return self for chaining.
+    /// See also [`Self::with_position`] to offset the bars.
+    pub fn with_bar_width(self, ratio: f32) -> Self {
+        self.with_position(BarsPosition::centered(ratio))
+    }
+
+    /// Set the gap between the bars of neighbouring categories as a ratio
+    /// of the category bin (0.0 to 1.0), with the bars centered in the bin,
+    /// and return self for chaining.
+    /// This is equivalent to a bar width of `1.0 - ratio`.
+    pub fn with_gap(self, ratio: f32) -> Self {
+        self.with_bar_width(1.0 - ratio.clamp(0.0, 1.0))
+    }
+
+    /// Set the radius of the rounded corners at the end of the bars, in figure units,
+    /// and return self for chaining.
+    /// Only the corners away from the baseline are rounded, that is the top corners
+    /// of vertical bars above the baseline and the bottom corners of those below.
+    /// The radius is clamped to half the bar width and to the bar length.
+    /// By default, the corners are square (radius of 0.0).
+    pub fn with_corner_radius(self, corner_radius: f32) -> Self {
+        Self {
+            corner_radius,
+            ..self
+        }
+    }
+
+    /// Set the orientation of the bars and return self for chaining.
+    /// Horizontal bars take their categories from the y data and their values from the x data.
+    /// By default, the orientation is inferred from which data is categorical.
+    /// If set, preparing the figure fails when the data does not match the orientation.
+    pub fn with_orientation(self, orientation: BarsOrientation) -> Self {
+        Self {
+            orientation: Some(orientation),
+            ..self
+        }
+    }
+
+    /// Set the value from which the bars grow, and return self for chaining.
+    /// Bars go up (or right) for values above the baseline and down (or left) for values below,
+    /// and the value axis always includes the baseline.
+    /// By default, the baseline is 0.0.
+    pub fn with_baseline(self, baseline: f64) -> Self {
+        Self { baseline, ..self }
+    }
+
+    /// Color the bars according to their side of the baseline, and return self for chaining.
+    /// Bars at or above the baseline are filled with `pos_color`, and bars below with `neg_color`.
+    /// The colors replace the color of the fill, whose opacity is kept.
+    /// See [`Self::with_sign_legend`] for the legend entry.
+    pub fn with_sign_colors(
+        self,
+        pos_color: style::series::Color,
+        neg_color: style::series::Color,
+    ) -> Self {
+        Self {
+            sign_colors: Some((pos_color, neg_color)),
+            ..self
+        }
+    }
+
+    /// Set how the legend entry shows the sign colors, and return self for chaining.
+    /// This has no effect without sign colors (see [`Self::with_sign_colors`]).
+    pub fn with_sign_legend(self, sign_legend: BarsSignLegend) -> Self {
+        Self {
+            sign_legend,
+            ..self
+        }
+    }
+
+    /// Set the z-order of the series and return self for chaining.
+    /// By default, series are drawn at [`ZOrder::SERIES`], in the order of the plot.
+    /// Lower it to draw the bars behind the lines or markers that annotate them.
+    pub fn with_zorder(self, zorder: ZOrder) -> Self {
+        Self { zorder, ..self }
+    }
+
+    /// Set whether the series is visible and return self for chaining.
+    /// A hidden series is not drawn, but keeps its legend entry, which is dimmed.
+    pub fn with_visible(self, visible: bool) -> Self {
+        Self { visible, ..self }
+    }
+    /// Get the x data column
+    pub fn x_data(&self) -> &DataCol {
+        &self.x_data
+    }
+
+    /// Get the y data column
+    pub fn y_data(&self) -> &DataCol {
+        &self.y_data
+    }
+
+    /// Get the name
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get a reference to the x axis
+    pub fn x_axis(&self) -> &axis::Ref {
+        &self.x_axis
+    }
+
+    /// Get a reference to the y axis
+    pub fn y_axis(&self) -> &axis::Ref {
+        &self.y_axis
+    }
+
+    /// Get the z-order
+    pub fn zorder(&self) -> ZOrder {
+        self.zorder
+    }
+
+    /// Get whether the series is visible
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Get the fill style
+    pub fn fill(&self) -> &style::series::Fill {
+        &self.fill
+    }
+
+    /// Get the line style, if any
+    pub fn line(&self) -> Option<&style::series::Stroke> {
+        self.line.as_ref()
+    }
+
+    /// Get the position configuration
     pub fn position(&self) -> &BarsPosition {
         &self.position
     }
+
+    /// Get the radius of the rounded corners
+    pub fn corner_radius(&self) -> f32 {
+        self.corner_radius
+    }
+
+    /// Get the orientation, if set explicitly
+    pub fn orientation(&self) -> Option<BarsOrientation> {
+        self.orientation
+    }
+
+    /// Get the baseline value
+    pub fn baseline(&self) -> f64 {
+        self.baseline
+    }
+
+    /// Get the colors of the bars above and below the baseline, if any
+    pub fn sign_colors(&self) -> Option<(style::series::Color, style::series::Color)> {
+        self.sign_colors
+    }
+
+    /// Get how the legend entry shows the sign colors
+    pub fn sign_legend(&self) -> BarsSignLegend {
+        self.sign_legend
+    }
 }
 
 /// A bar series within a bars group.
@@ -602,6 +1501,7 @@ impl Bars {
 /// Represents a single series of bars within a [`BarsGroup`].
 /// Each `BarSeries` contains data for one set of bars across all categories.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BarSeries {
     data: DataCol,
 
@@ -668,6 +1568,7 @@ impl BarSeries {
 ///
 /// Determines whether bars extend vertically (from the x-axis) or horizontally (from the y-axis).
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BarsOrientation {
     /// Bars extend vertically from the x-axis.
     #[default]
@@ -688,11 +1589,25 @@ impl BarsOrientation {
     }
 }
 
+/// Legend entry of bars colored by sign (see [`Bars::with_sign_colors`]).
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BarsSignLegend {
+    /// The entry shows both colors side by side
+    #[default]
+    Both,
+    /// The entry shows the color of the bars above the baseline
+    Positive,
+    /// The entry shows the color of the bars below the baseline
+    Negative,
+}
+
 /// Arrangement of multiple bar series within a group.
 ///
 /// Defines how multiple bar series are positioned relative to each other:
 /// either side-by-side or stacked on top of each other.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BarsArrangement {
     /// Bars are placed side-by-side within each category.
     Aside(BarsAsideArrangement),
@@ -704,6 +1619,7 @@ pub enum BarsArrangement {
 ///
 /// Specifies how bars are positioned when placed side-by-side within each category.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BarsAsideArrangement {
     /// Offset of the first bar within the bin (0.0 to 1.0).
     pub offset: f32,
@@ -727,6 +1643,7 @@ impl Default for BarsAsideArrangement {
 ///
 /// Specifies how bars are positioned when stacked on top of each other within each category.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BarsStackArrangement {
     /// Offset of the stacked bars within the bin (0.0 to 1.0).
     pub offset: f32,
@@ -749,20 +1666,134 @@ impl Default for BarsArrangement {
     }
 }
 
+/// Content of the labels of a [`BarsGroup`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BarLabelContent {
+    /// The value of the bar
+    #[default]
+    Value,
+    /// The share of the bar in the sum of all the bars of the group, in percent
+    PercentOfTotal,
+    /// The share of the bar in the sum of the bars of its category, in percent
+    PercentOfCategory,
+}
+
+/// Labels drawn at the center of each bar of a [`BarsGroup`].
+///
+/// Labels that don't fit within their bar are not drawn,
+/// nor are the labels of bars thinner than the [minimum size](Self::with_min_size).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BarLabels {
+    content: BarLabelContent,
+    font_size: f32,
+    font: Font,
+    color: theme::Color,
+    precision: Option<usize>,
+    min_size: f32,
+}
+
+impl Default for BarLabels {
+    fn default() -> Self {
+        BarLabels::new(BarLabelContent::default())
+    }
+}
+
+impl BarLabels {
+    /// Create new bar labels showing the given content
+    pub fn new(content: BarLabelContent) -> Self {
+        BarLabels {
+            content,
+            font_size: defaults::BAR_LABEL_FONT_SIZE,
+            font: Font::default(),
+            color: theme::Col::Foreground.into(),
+            precision: None,
+            min_size: 0.0,
+        }
+    }
+
+    /// Set the font size and return self for chaining
+    pub fn with_font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    /// Set the font and return self for chaining
+    pub fn with_font(self, font: Font) -> Self {
+        Self { font, ..self }
+    }
+
+    /// Set the color and return self for chaining.
+    /// By default, the foreground theme color is used.
+    pub fn with_color(self, color: theme::Color) -> Self {
+        Self { color, ..self }
+    }
+
+    /// Set the number of decimals and return self for chaining.
+    /// By default, all the values of the group are shown with the fewest decimals representing them,
+    /// up to 3 significant digits of the largest value, and percentages are rounded to the unit.
+    pub fn with_precision(self, precision: usize) -> Self {
+        Self {
+            precision: Some(precision),
+            ..self
+        }
+    }
+
+    /// Set the minimum size of a bar along the value axis to show its label,
+    /// in figure units, and return self for chaining.
+    pub fn with_min_size(self, min_size: f32) -> Self {
+        Self { min_size, ..self }
+    }
+
+    /// Get the content of the labels
+    pub fn content(&self) -> BarLabelContent {
+        self.content
+    }
+
+    /// Get the font size
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    /// Get the font
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    /// Get the color
+    pub fn color(&self) -> theme::Color {
+        self.color
+    }
+
+    /// Get the number of decimals, if set
+    pub fn precision(&self) -> Option<usize> {
+        self.precision
+    }
+
+    /// Get the minimum size of a bar to show its label
+    pub fn min_size(&self) -> f32 {
+        self.min_size
+    }
+}
+
 /// A group of bar series.
 ///
 /// Represents multiple bar series that share the same categories.
 /// The bars can be arranged either side-by-side or stacked, and can be oriented
 /// vertically or horizontally.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BarsGroup {
     categories: DataCol,
     series: Vec<BarSeries>,
 
     x_axis: axis::Ref,
     y_axis: axis::Ref,
+    zorder: ZOrder,
+    visible: bool,
     orientation: BarsOrientation,
     arrangement: BarsArrangement,
+    labels: Option<BarLabels>,
 }
 
 impl BarsGroup {
@@ -773,8 +1804,11 @@ impl BarsGroup {
             series,
             x_axis: Default::default(),
             y_axis: Default::default(),
+            zorder: ZOrder::SERIES,
+            visible: true,
             orientation: Default::default(),
             arrangement: Default::default(),
+            labels: None,
         }
     }
 
@@ -794,6 +1828,27 @@ impl BarsGroup {
         }
     }
 
+    /// Set the labels drawn on the bars and return self for chaining
+    pub fn with_labels(self, labels: BarLabels) -> Self {
+        Self {
+            labels: Some(labels),
+            ..self
+        }
+    }
+
+    /// Set the z-order of the series and return self for chaining.
+    /// By default, series are drawn at [`ZOrder::SERIES`], in the order of the plot.
+    /// All the bars of the group, and their labels, are drawn at this z-order.
+    pub fn with_zorder(self, zorder: ZOrder) -> Self {
+        Self { zorder, ..self }
+    }
+
+    /// Set whether the series is visible and return self for chaining.
+    /// A hidden series is not drawn, but keeps its legend entry, which is dimmed.
+    pub fn with_visible(self, visible: bool) -> Self {
+        Self { visible, ..self }
+    }
+
     /// Get the categories data column
     pub fn categories(&self) -> &DataCol {
         &self.categories
@@ -814,6 +1869,16 @@ impl BarsGroup {
         &self.y_axis
     }
 
+    /// Get the z-order
+    pub fn zorder(&self) -> ZOrder {
+        self.zorder
+    }
+
+    /// Get whether the series is visible
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
     /// Get the orientation
     pub fn orientation(&self) -> &BarsOrientation {
         &self.orientation
@@ -823,4 +1888,209 @@ impl BarsGroup {
     pub fn arrangement(&self) -> &BarsArrangement {
         &self.arrangement
     }
+
+    /// Get the labels drawn on the bars, if any
+    pub fn labels(&self) -> Option<&BarLabels> {
+        self.labels.as_ref()
+    }
+}
+
+/// An area series within an areas group.
+///
+/// Represents a single area of an [`AreasGroup`], stacked on top of the previous areas of the group.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AreaSeries {
+    x_data: DataCol,
+    y_data: DataCol,
+
+    name: Option<String>,
+    fill: style::series::Fill,
+    line: Option<style::series::Stroke>,
+}
+
+impl AreaSeries {
+    /// Create a new area series with the given x and y data columns
+    pub fn new(x_data: DataCol, y_data: DataCol) -> Self {
+        AreaSeries {
+            x_data,
+            y_data,
+
+            name: None,
+            fill: style::series::Fill::default(),
+            line: None,
+        }
+    }
+
+    /// Set the name and return self for chaining
+    pub fn with_name(self, name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+
+    /// Set the fill style and return self for chaining
+    pub fn with_fill(self, fill: style::series::Fill) -> Self {
+        Self { fill, ..self }
+    }
+
+    /// Set the line style for the area outline and return self for chaining
+    pub fn with_line(self, line: style::series::Stroke) -> Self {
+        Self {
+            line: Some(line),
+            ..self
+        }
+    }
+
+    /// Get the x data column
+    pub fn x_data(&self) -> &DataCol {
+        &self.x_data
+    }
+
+    /// Get the y data column
+    pub fn y_data(&self) -> &DataCol {
+        &self.y_data
+    }
+
+    /// Get the name
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Get the fill style
+    pub fn fill(&self) -> &style::series::Fill {
+        &self.fill
+    }
+
+    /// Get the line style, if any
+    pub fn line(&self) -> Option<&style::series::Stroke> {
+        self.line.as_ref()
+    }
+}
+
+/// Baseline of the bottom area of an [`AreasGroup`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AreasBaseline {
+    /// Areas are stacked from zero.
+    #[default]
+    Zero,
+    /// Areas are stacked symmetrically around zero (a.k.a. ThemeRiver).
+    Symmetric,
+    /// The baseline minimizes the weighted change of slope of the areas (a.k.a. streamgraph).
+    /// See Byron and Wattenberg, "Stacked Graphs – Geometry & Aesthetics".
+    Wiggle,
+}
+
+/// A group of stacked area series.
+///
+/// The areas are stacked cumulatively on top of each other, from the first series
+/// of the group to the last, over a shared x grid.
+/// By default, all series must have the same x data.
+/// With [`with_interpolate_x`](Self::with_interpolate_x), the y data of each series
+/// is instead linearly interpolated over the x data of the first series.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AreasGroup {
+    series: Vec<AreaSeries>,
+
+    x_axis: axis::Ref,
+    y_axis: axis::Ref,
+    zorder: ZOrder,
+    visible: bool,
+    baseline: AreasBaseline,
+    interpolate_x: bool,
+}
+
+impl AreasGroup {
+    /// Create a new areas group with the given area series
+    pub fn new(series: Vec<AreaSeries>) -> Self {
+        AreasGroup {
+            series,
+            x_axis: Default::default(),
+            y_axis: Default::default(),
+            zorder: ZOrder::SERIES,
+            visible: true,
+            baseline: Default::default(),
+            interpolate_x: false,
+        }
+    }
+
+    /// Set the baseline and return self for chaining
+    pub fn with_baseline(self, baseline: AreasBaseline) -> Self {
+        Self { baseline, ..self }
+    }
+
+    /// Set whether series with different x data are interpolated over the x data of the first series,
+    /// and return self for chaining.
+    /// Outside of the x range of a series, its y values are zero.
+    pub fn with_interpolate_x(self, interpolate_x: bool) -> Self {
+        Self {
+            interpolate_x,
+            ..self
+        }
+    }
+
+    /// Set a reference to the x axis and return self for chaining
+    /// Use this to associate the series with a specific x axis in the plot, when a plot has multiple x axes.
+    pub fn with_x_axis(mut self, axis: axis::Ref) -> Self {
+        self.x_axis = axis;
+        self
+    }
+
+    /// Set a reference to the y axis and return self for chaining
+    /// Use this to associate the series with a specific y axis in the plot, when a plot has multiple y axes.
+    pub fn with_y_axis(mut self, axis: axis::Ref) -> Self {
+        self.y_axis = axis;
+        self
+    }
+
+    /// Set the z-order of the series and return self for chaining.
+    /// By default, series are drawn at [`ZOrder::SERIES`], in the order of the plot.
+    /// All the areas of the group are drawn at this z-order, and keep their stacking order.
+    pub fn with_zorder(self, zorder: ZOrder) -> Self {
+        Self { zorder, ..self }
+    }
+
+    /// Set whether the series is visible and return self for chaining.
+    /// A hidden series is not drawn, but keeps its legend entry, which is dimmed.
+    pub fn with_visible(self, visible: bool) -> Self {
+        Self { visible, ..self }
+    }
+
+    /// Get the area series
+    pub fn series(&self) -> &[AreaSeries] {
+        &self.series
+    }
+
+    /// Get a reference to the x axis
+    pub fn x_axis(&self) -> &axis::Ref {
+        &self.x_axis
+    }
+
+    /// Get a reference to the y axis
+    pub fn y_axis(&self) -> &axis::Ref {
+        &self.y_axis
+    }
+
+    /// Get the z-order
+    pub fn zorder(&self) -> ZOrder {
+        self.zorder
+    }
+
+    /// Get whether the series is visible
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Get the baseline
+    pub fn baseline(&self) -> AreasBaseline {
+        self.baseline
+    }
+
+    /// Check whether series with different x data are interpolated
+    pub fn interpolate_x(&self) -> bool {
+        self.interpolate_x
+    }
 }
@@ -13,17 +13,22 @@ use crate::{Style, data, des, geom, render, text};
 mod annot;
 mod axis;
 mod figure;
+mod fit;
 mod hit_test;
+mod layout;
 mod legend;
 mod marker;
 mod plot;
 mod scale;
 mod series;
+mod smooth;
 mod ticks;
 pub mod zoom;
 
 pub use figure::PreparedFigure;
-pub use hit_test::PlotHit;
+pub use hit_test::{Pick, PlotHit, SeriesHit};
+pub use layout::LayoutParams;
+pub use series::SeriesGeometry;
 
 /// Errors that can occur during figure drawing
 #[derive(Debug)]
@@ -46,6 +51,11 @@ pub enum Error {
     InconsistentData(String),
     /// Font or text related error, e.g. missing glyphs or font not found
     FontOrText(text::Error),
+    /// The figure is too small to lay out its content,
+    /// e.g. the padding, titles, legends and axes leave no room for the plots
+    FigureTooSmall,
+    /// The rendering surface failed to draw the figure
+    Render(render::Error),
 }
 
 impl From<text::Error> for Error {
@@ -60,6 +70,12 @@ impl From<ttf_parser::FaceParsingError> for Error {
     }
 }
 
+impl From<render::Error> for Error {
+    fn from(err: render::Error) -> Self {
+        Error::Render(err)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -73,12 +89,23 @@ impl fmt::Display for Error {
             }
             Error::InconsistentData(reason) => write!(f, "Inconsistent data: {}", reason),
             Error::FontOrText(err) => err.fmt(f),
+            Error::FigureTooSmall => write!(f, "Figure is too small for its content"),
+            Error::Render(err) => err.fmt(f),
         }
     }
 }
 
 impl std::error::Error for Error {}
 
+/// Check that the rectangle has strictly more room than the given width and height
+fn check_room(rect: &geom::Rect, width: f32, height: f32) -> Result<(), Error> {
+    if rect.width() > width && rect.height() > height {
+        Ok(())
+    } else {
+        Err(Error::FigureTooSmall)
+    }
+}
+
 #[inline]
 fn fig_x_to_plot_x(plot_rect: &geom::Rect, fig_x: f32) -> f32 {
     fig_x - plot_rect.x()
@@ -96,6 +123,53 @@ fn plot_to_fig(plot_rect: &geom::Rect, plot_x: f32, plot_y: f32) -> (f32, f32) {
     (fig_x, fig_y)
 }
 
+/// Layout of a title hanging from its anchor, with lines aligned by `align`.
+/// See [`title_anchor_x`] for the matching anchor.
+fn title_layout(align: text::line::Align) -> text::rich::Layout {
+    let align = match align {
+        text::line::Align::Start | text::line::Align::Left => text::rich::Align::Left,
+        text::line::Align::Center => text::rich::Align::Center,
+        text::line::Align::End | text::line::Align::Right => text::rich::Align::Right,
+    };
+    text::rich::Layout::Horizontal(
+        align,
+        text::line::VerAlign::Hanging.into(),
+        Default::default(),
+    )
+}
+
+/// X coordinate of the anchor of a title aligned by `align` over `rect`:
+/// the left edge, the center or the right edge of `rect`.
+fn title_anchor_x(align: text::line::Align, rect: &geom::Rect) -> f32 {
+    match align {
+        text::line::Align::Start | text::line::Align::Left => rect.left(),
+        text::line::Align::Center => rect.center_x(),
+        text::line::Align::End | text::line::Align::Right => rect.right(),
+    }
+}
+
+/// Draw a rectangle with corners rounded by `radius`.
+/// A zero radius draws the sharp rectangle as is.
+fn draw_rounded_rect<S>(
+    surface: &mut S,
+    rect: &render::Rect,
+    radius: f32,
+) -> Result<(), render::Error>
+where
+    S: render::Surface,
+{
+    if radius <= 0.0 {
+        return surface.draw_rect(rect);
+    }
+    let path = rect.rect.to_rounded_path(radius);
+    surface.draw_path(&render::Path {
+        path: &path,
+        fill: rect.fill,
+        stroke: rect.stroke,
+        transform: rect.transform,
+    })
+}
+
 /// Extension trait to prepare a design figure for drawing
 pub trait Prepare {
     /// Prepare a figure for drawing.
@@ -111,6 +185,22 @@ pub trait Prepare {
         data_source: &D,
         fontdb: Option<&fontdb::Database>,
     ) -> Result<PreparedFigure, Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        self.prepare_with_layout(data_source, fontdb, &LayoutParams::default())
+    }
+
+    /// Prepare a figure for drawing, with custom layout parameters.
+    /// See [`Prepare::prepare`] for details.
+    ///
+    /// Panics: if `fontdb` is None and none of the bundled font features is enabled.
+    fn prepare_with_layout<D>(
+        &self,
+        data_source: &D,
+        fontdb: Option<&fontdb::Database>,
+        layout: &LayoutParams,
+    ) -> Result<PreparedFigure, Error>
     where
         D: data::Source + ?Sized;
 
@@ -128,31 +218,68 @@ pub trait Prepare {
         D: data::Source + ?Sized,
         S: render::Surface,
     {
-        self.prepare(data_source, fontdb)?.draw(surface, style);
+        self.prepare(data_source, fontdb)?.draw(surface, style)?;
         Ok(())
     }
 }
 
 impl Prepare for des::Figure {
-    fn prepare<D>(
+    fn prepare_with_layout<D>(
         &self,
         data_source: &D,
         fontdb: Option<&fontdb::Database>,
+        layout: &LayoutParams,
     ) -> Result<PreparedFigure, Error>
     where
         D: data::Source + ?Sized,
     {
-        with_ctx(data_source, fontdb, |ctx| ctx.setup_figure(self))
+        with_ctx(data_source, fontdb, layout, |ctx| ctx.setup_figure(self))
     }
 }
 
+/// Prepare a figure for drawing, with the series and legends of the plots prepared in parallel.
+///
+/// This is worth for figures with many subplots, where each plot is prepared on its own thread.
+/// The layout of the figure is done serially afterwards.
+/// The result is identical to [`Prepare::prepare_with_layout`]:
+/// the plots are stitched in their design order, regardless of the order in which threads complete.
+///
+/// Panics: if `fontdb` is None and none of the bundled font features is enabled.
+#[cfg(feature = "parallel")]
+pub fn prepare_parallel<D>(
+    fig: &des::Figure,
+    data_source: &D,
+    fontdb: Option<&fontdb::Database>,
+    layout: &LayoutParams,
+) -> Result<PreparedFigure, Error>
+where
+    D: data::Source + Sync,
+{
+    with_ctx(data_source, fontdb, layout, |ctx| {
+        let ctx = Ctx {
+            par_source: Some(data_source),
+            ..*ctx
+        };
+        ctx.setup_figure(fig)
+    })
+}
+
 #[derive(Debug)]
 struct Ctx<'a, D: ?Sized> {
     data_source: &'a D,
     fontdb: &'a fontdb::Database,
+    layout: &'a LayoutParams,
+    /// The data source, if it can be shared between threads to prepare the plots in parallel
+    #[cfg(feature = "parallel")]
+    par_source: Option<&'a (dyn data::Source + Sync)>,
 }
 
-fn with_ctx<D, F, R>(data_source: &D, fontdb: Option<&fontdb::Database>, f: F) -> R
+fn with_ctx<D, F, R>(
+    data_source: &D,
+    fontdb: Option<&fontdb::Database>,
+    layout: &LayoutParams,
+    f: F,
+) -> R
 where
     D: data::Source + ?Sized,
     F: FnOnce(&Ctx<'_, D>) -> R,
@@ -161,6 +288,9 @@ where
         let ctx = Ctx {
             data_source,
             fontdb,
+            layout,
+            #[cfg(feature = "parallel")]
+            par_source: None,
         };
         f(&ctx)
     } else {
@@ -176,6 +306,9 @@ where
             let ctx = Ctx {
                 data_source,
                 fontdb: &fontdb,
+                layout,
+                #[cfg(feature = "parallel")]
+                par_source: None,
             };
             f(&ctx)
         }
@@ -203,6 +336,10 @@ impl<'a, D: ?Sized> Ctx<'a, D> {
     fn fontdb(&self) -> &fontdb::Database {
         &self.fontdb
     }
+
+    fn layout(&self) -> &LayoutParams {
+        self.layout
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -273,6 +410,14 @@ impl Text {
         })
     }
 
+    fn with_opacity(mut self, opacity: f32) -> Self {
+        for span in self.spans.iter_mut() {
+            span.fill = span.fill.map(|f| f.with_opacity(opacity));
+            span.stroke = span.stroke.take().map(|s| s.with_opacity(opacity));
+        }
+        self
+    }
+
     fn width(&self) -> f32 {
         self.bbox.map_or(0.0, |r| r.width())
     }
@@ -291,7 +436,12 @@ impl Text {
         bbox
     }
 
-    fn draw<S>(&self, surface: &mut S, style: &Style, transform: Option<&geom::Transform>)
+    fn draw<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        transform: Option<&geom::Transform>,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
@@ -302,8 +452,9 @@ impl Text {
                 stroke: span.stroke.as_ref().map(|s| s.as_stroke(style)),
                 transform,
             };
-            surface.draw_path(&rpath);
+            surface.draw_path(&rpath)?;
         }
+        Ok(())
     }
 }
 
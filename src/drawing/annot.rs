@@ -1,7 +1,7 @@
 use std::f32;
 
 use super::Ctx;
-use crate::des::annot::{Anchor, Direction, Pos, ZPos};
+use crate::des::annot::{Anchor, Direction, Pos, SpanDirection};
 use crate::des::{self};
 use crate::drawing::axis::Axis;
 use crate::drawing::plot::{Axes, Orientation};
@@ -12,6 +12,7 @@ use crate::{Style, data, geom, render, text};
 #[derive(Debug, Clone)]
 pub(super) enum Annot {
     Line(des::annot::Line),
+    Span(des::annot::Span),
     Arrow(des::annot::Arrow),
     Marker(des::annot::Marker),
     Label(Label),
@@ -21,6 +22,7 @@ pub(super) enum Annot {
 pub(super) struct Label {
     text: Text,
     frame: (Option<theme::Fill>, Option<theme::Stroke>),
+    shadow: Option<theme::Shadow>,
     angle: f32,
     pos: Pos,
 }
@@ -32,6 +34,7 @@ where
     pub fn setup_annot(&self, annot: &des::Annotation, axes: &Axes) -> Result<Annot, super::Error> {
         let mut annot = match annot {
             des::Annotation::Line(line) => Annot::Line(line.clone()),
+            des::Annotation::Span(span) => Annot::Span(span.clone()),
             des::Annotation::Arrow(arrow) => Annot::Arrow(arrow.clone()),
             des::Annotation::Marker(marker) => Annot::Marker(marker.clone()),
             des::Annotation::Label(label) => {
@@ -59,6 +62,7 @@ where
                 Annot::Label(Label {
                     text,
                     frame: label.frame.clone(),
+                    shadow: label.shadow,
                     angle: label.angle,
                     pos: label.pos.clone(),
                 })
@@ -83,6 +87,7 @@ impl Annot {
     fn pos(&self) -> &Pos {
         match self {
             Annot::Line(line) => &line.pos,
+            Annot::Span(span) => &span.pos,
             Annot::Arrow(arrow) => &arrow.pos,
             Annot::Marker(marker) => &marker.pos,
             Annot::Label(label) => &label.pos,
@@ -92,14 +97,15 @@ impl Annot {
     fn pos_mut(&mut self) -> &mut Pos {
         match self {
             Annot::Line(line) => &mut line.pos,
+            Annot::Span(span) => &mut span.pos,
             Annot::Arrow(arrow) => &mut arrow.pos,
             Annot::Marker(marker) => &mut marker.pos,
             Annot::Label(label) => &mut label.pos,
         }
     }
 
-    pub fn zpos(&self) -> ZPos {
-        self.pos().zpos
+    pub fn zorder(&self) -> des::ZOrder {
+        self.pos().zorder
     }
 
     pub fn draw<S>(
@@ -108,7 +114,8 @@ impl Annot {
         style: &style::Style,
         axes: &Axes,
         plot_rect: &geom::Rect,
-    ) where
+    ) -> Result<(), render::Error>
+    where
         S: render::Surface,
     {
         let x_axis = axes
@@ -121,18 +128,22 @@ impl Annot {
             .unwrap();
         match self {
             Annot::Line(line) => {
-                self.draw_annot_line(surface, style, line, &x_axis, &y_axis, plot_rect);
+                self.draw_annot_line(surface, style, line, &x_axis, &y_axis, plot_rect)?;
+            }
+            Annot::Span(span) => {
+                self.draw_annot_span(surface, style, span, x_axis, y_axis, plot_rect)?;
             }
             Annot::Arrow(arrow) => {
-                self.draw_annot_arrow(surface, style, arrow, &x_axis, &y_axis, plot_rect);
+                self.draw_annot_arrow(surface, style, arrow, &x_axis, &y_axis, plot_rect)?;
             }
             Annot::Marker(marker) => {
-                self.draw_annot_marker(surface, style, marker, x_axis, y_axis, plot_rect);
+                self.draw_annot_marker(surface, style, marker, x_axis, y_axis, plot_rect)?;
             }
             Annot::Label(label) => {
-                self.draw_annot_label(surface, style, label, &x_axis, &y_axis, plot_rect);
+                self.draw_annot_label(surface, style, label, &x_axis, &y_axis, plot_rect)?;
             }
         }
+        Ok(())
     }
 
     fn draw_annot_line<S>(
@@ -143,7 +154,8 @@ impl Annot {
         x_axis: &Axis,
         y_axis: &Axis,
         plot_rect: &geom::Rect,
-    ) where
+    ) -> Result<(), render::Error>
+    where
         S: render::Surface,
     {
         let (x, y) = (line.pos.x, line.pos.y);
@@ -214,8 +226,60 @@ impl Annot {
                 stroke: Some(line.line.as_stroke(style)),
                 transform: None,
             };
-            surface.draw_path(&path);
+            surface.draw_path(&path)?;
+        }
+        Ok(())
+    }
+
+    fn draw_annot_span<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        span: &des::annot::Span,
+        x_axis: &Axis,
+        y_axis: &Axis,
+        plot_rect: &geom::Rect,
+    ) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        let (cm, extent) = match span.direction {
+            SpanDirection::Vertical => (x_axis.coord_map(), plot_rect.width()),
+            SpanDirection::Horizontal => (y_axis.coord_map(), plot_rect.height()),
+        };
+        // open-ended bounds extend to the plot edge
+        let start = span.start.map_or(0.0, |v| cm.map_coord_num(v));
+        let end = span.end.map_or(extent, |v| cm.map_coord_num(v));
+        // e.g. a negative bound on a logarithmic axis
+        if !start.is_finite() || !end.is_finite() {
+            return Ok(());
+        }
+        let (start, end) = (start.min(end).max(0.0), start.max(end).min(extent));
+        if start >= end {
+            return Ok(());
         }
+
+        let rect = match span.direction {
+            SpanDirection::Vertical => geom::Rect::from_trbl(
+                plot_rect.top(),
+                plot_rect.left() + end,
+                plot_rect.bottom(),
+                plot_rect.left() + start,
+            ),
+            SpanDirection::Horizontal => geom::Rect::from_trbl(
+                plot_rect.bottom() - end,
+                plot_rect.right(),
+                plot_rect.bottom() - start,
+                plot_rect.left(),
+            ),
+        };
+        let rect = render::Rect {
+            rect,
+            fill: Some(span.fill.as_paint(style)),
+            stroke: None,
+            transform: None,
+        };
+        surface.draw_rect(&rect)
     }
 
     fn draw_annot_arrow<S>(
@@ -226,7 +290,8 @@ impl Annot {
         x_axis: &Axis,
         y_axis: &Axis,
         plot_rect: &geom::Rect,
-    ) where
+    ) -> Result<(), render::Error>
+    where
         S: render::Surface,
     {
         let target_x = x_axis.coord_map().map_coord_num(arrow.pos.x);
@@ -251,7 +316,7 @@ impl Annot {
             stroke: Some(arrow.line.as_stroke(style)),
             transform: Some(&transform),
         };
-        surface.draw_path(&rpath);
+        surface.draw_path(&rpath)
     }
 
     fn draw_annot_marker<S>(
@@ -262,7 +327,8 @@ impl Annot {
         x_axis: &Axis,
         y_axis: &Axis,
         plot_rect: &geom::Rect,
-    ) where
+    ) -> Result<(), render::Error>
+    where
         S: render::Surface,
     {
         let x = x_axis.coord_map().map_coord_num(marker.pos.x);
@@ -277,7 +343,7 @@ impl Annot {
             stroke: marker.marker.stroke.as_ref().map(|l| l.as_stroke(style)),
             transform: Some(&transform),
         };
-        surface.draw_path(&rpath);
+        surface.draw_path(&rpath)
     }
 
     fn draw_annot_label<S>(
@@ -288,7 +354,8 @@ impl Annot {
         x_axis: &Axis,
         y_axis: &Axis,
         plot_rect: &geom::Rect,
-    ) where
+    ) -> Result<(), render::Error>
+    where
         S: render::Surface,
     {
         let x = x_axis.coord_map().map_coord_num(label.pos.x);
@@ -302,16 +369,20 @@ impl Annot {
             let bounds = label.text.bbox.expect("Text bbox should be computed");
             let rect =
                 geom::Rect::from_xywh(bounds.x(), bounds.y(), bounds.width(), bounds.height());
+            if let Some(shadow) = &label.shadow {
+                let path = rect.to_path();
+                surface.draw_shadow(&shadow.as_shadow(style, &path, Some(&transform)))?;
+            }
             let rrect = render::Rect {
                 rect,
                 fill: label.frame.0.as_ref().map(|f| f.as_paint(style)),
                 stroke: label.frame.1.as_ref().map(|l| l.as_stroke(style)),
                 transform: Some(&transform),
             };
-            surface.draw_rect(&rrect);
+            surface.draw_rect(&rrect)?;
         }
 
-        label.text.draw(surface, style, Some(&transform));
+        label.text.draw(surface, style, Some(&transform))
     }
 }
 
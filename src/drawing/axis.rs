@@ -1,6 +1,4 @@
-use std::cell::RefCell;
-use std::rc::Rc;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 mod bounds;
 mod side;
@@ -14,7 +12,7 @@ use crate::drawing::scale::{self, CoordMap};
 use crate::drawing::{Categories, Ctx, Error, Text, ticks};
 use crate::style::theme;
 use crate::text::{self, font};
-use crate::{Style, data, des, geom, missing_params, render};
+use crate::{Style, data, des, geom, render};
 
 #[derive(Debug, Clone)]
 pub struct Axis {
@@ -22,7 +20,7 @@ pub struct Axis {
     title_text: Option<String>,
     side: Side,
     draw_opts: DrawOpts,
-    scale: Rc<RefCell<AxisScale>>,
+    scale: Arc<RwLock<AxisScale>>,
 }
 
 impl Axis {
@@ -38,32 +36,55 @@ impl Axis {
         self.side
     }
 
-    pub fn scale(&self) -> &Rc<RefCell<AxisScale>> {
+    pub fn scale(&self) -> &Arc<RwLock<AxisScale>> {
         &self.scale
     }
 
     pub fn size_across(&self) -> f32 {
+        let offset = self.draw_opts.spine_offset;
         let mark_size = self.draw_opts.marks.as_ref().map_or(0.0, |m| m.size_out);
         let with_labels = self.draw_opts.ticks_labels;
-        let scale = self.scale.as_ref().borrow();
+        let lbl_margin = self.draw_opts.tick_label_margin;
+        let scale = self.scale.read().unwrap();
         let mut size = match &*scale {
             AxisScale::Num {
                 ticks: Some(ticks), ..
-            } => ticks.size_across(self.side, mark_size, with_labels),
+            } => {
+                ticks.size_across(self.side, mark_size, with_labels, lbl_margin)
+                    + self.vertical_annot_size(ticks)
+            }
             AxisScale::Cat {
                 ticks: Some(ticks), ..
-            } => ticks.size_across(self.side, mark_size, with_labels),
+            } => ticks.size_across(self.side, mark_size, with_labels, lbl_margin),
             _ => 0.0,
         };
-        if let Some(title) = self.draw_opts.title.as_ref() {
-            // vertical axis rotate the title, therefore we take the height in all cases.
-            size += title.height() + missing_params::AXIS_TITLE_MARGIN;
+        if self.draw_opts.title_side == self.side {
+            size += self.title_size_across();
         }
-        size
+        offset + size
+    }
+
+    /// The side of the plot where the title is drawn
+    pub fn title_side(&self) -> Side {
+        self.draw_opts.title_side
+    }
+
+    /// The size taken by the title across the axis, including its margin
+    pub fn title_size_across(&self) -> f32 {
+        // vertical axis rotate the title, therefore we take the height in all cases.
+        self.draw_opts
+            .title
+            .as_ref()
+            .map_or(0.0, |title| title.height() + self.draw_opts.title_margin)
+    }
+
+    /// The space to leave between this axis and the next one on the same side
+    pub fn spacing(&self) -> f32 {
+        self.draw_opts.spacing
     }
 
     pub fn coord_map(&self) -> Arc<dyn CoordMap> {
-        let scale = self.scale.as_ref().borrow();
+        let scale = self.scale.read().unwrap();
         match &*scale {
             AxisScale::Num { cm, .. } => Arc::clone(cm),
             AxisScale::Cat { bins, .. } => Arc::new(bins.clone()),
@@ -71,7 +92,7 @@ impl Axis {
     }
 
     pub fn format_sample(&self, sample: data::SampleRef) -> String {
-        let scale = self.scale.as_ref().borrow();
+        let scale = self.scale.read().unwrap();
         match &*scale {
             AxisScale::Num {
                 ticks: Some(ticks), ..
@@ -106,7 +127,7 @@ pub enum AxisScale {
         /// The coordinate mapper
         cm: Arc<dyn CoordMap>,
         /// The ticks and labels for the axis
-        ticks: Option<NumTicks>,
+        ticks: Option<Box<NumTicks>>,
         /// The minor ticks locations
         minor_ticks: Option<MinorTicks>,
     },
@@ -130,7 +151,7 @@ pub struct NumTicks {
 }
 
 impl NumTicks {
-    fn size_across(&self, side: Side, mark_size: f32, with_labels: bool) -> f32 {
+    fn size_across(&self, side: Side, mark_size: f32, with_labels: bool, lbl_margin: f32) -> f32 {
         // mark_size is only accounted for when there are labels
         // this allows to merge ticks of subplots with shared scales and zero inter-space
         if !with_labels {
@@ -140,7 +161,7 @@ impl NumTicks {
         let mut size = mark_size;
 
         if !self.ticks.is_empty() {
-            size += missing_params::TICK_LABEL_MARGIN;
+            size += lbl_margin;
         }
 
         match side {
@@ -181,6 +202,26 @@ struct TickMark {
     size_out: f32,
 }
 
+impl TickMark {
+    fn new(stroke: theme::Stroke, direction: des::axis::TickDirection, length: f32) -> Self {
+        let (size_in, size_out) = tick_mark_sizes(direction, length);
+        TickMark {
+            stroke,
+            size_in,
+            size_out,
+        }
+    }
+}
+
+/// Length of a tick mark inside and outside of the plot area
+fn tick_mark_sizes(direction: des::axis::TickDirection, length: f32) -> (f32, f32) {
+    match direction {
+        des::axis::TickDirection::In => (length, 0.0),
+        des::axis::TickDirection::Out => (0.0, length),
+        des::axis::TickDirection::Both => (length, length),
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct MinorTicks {
     des_ticks: des::axis::MinorTicks,
@@ -242,6 +283,17 @@ impl CoordMap for CategoryBins {
             .unwrap_or(data::SampleRef::Null)
     }
 
+    fn snap_coord(&self, pos: f32) -> data::SampleRef<'_> {
+        let Some(last) = self.categories.len().checked_sub(1) else {
+            return data::SampleRef::Null;
+        };
+        let cat_idx = ((pos - self.inset.0) / self.bin_size).floor().max(0.0) as usize;
+        self.categories
+            .get(cat_idx.min(last))
+            .map(data::SampleRef::Cat)
+            .unwrap_or(data::SampleRef::Null)
+    }
+
     fn axis_bounds(&self) -> BoundsRef<'_> {
         (&self.categories).into()
     }
@@ -263,7 +315,7 @@ pub struct CategoryTicks {
 }
 
 impl CategoryTicks {
-    fn size_across(&self, side: Side, mark_size: f32, with_labels: bool) -> f32 {
+    fn size_across(&self, side: Side, mark_size: f32, with_labels: bool, lbl_margin: f32) -> f32 {
         // Marks are separators rather than ticks, they don't shift the labels.
         // As such, they are only counted if labels are not there.
 
@@ -276,12 +328,12 @@ impl CategoryTicks {
         match side {
             Side::Bottom | Side::Top => {
                 if !self.lbls.is_empty() {
-                    size += missing_params::TICK_LABEL_MARGIN + self.font_size;
+                    size += lbl_margin + self.font_size;
                 }
             }
             Side::Left | Side::Right => {
                 if !self.lbls.is_empty() {
-                    size += missing_params::TICK_LABEL_MARGIN;
+                    size += lbl_margin;
                 }
                 let max_w = self
                     .lbls
@@ -302,12 +354,22 @@ impl CategoryTicks {
 #[derive(Debug, Clone)]
 struct DrawOpts {
     title: Option<Text>,
+    title_side: Side,
+    title_pos: des::axis::TitlePos,
     spine: Option<des::plot::Border>,
     marks: Option<TickMark>,
     minor_marks: Option<TickMark>,
     ticks_labels: bool,
     grid: Option<theme::Stroke>,
     minor_grid: Option<theme::Stroke>,
+    emphasized_grids: Vec<des::axis::EmphasizedGrid>,
+    grid_zorder: des::ZOrder,
+    grid_range: Option<(f64, f64)>,
+    title_margin: f32,
+    tick_label_margin: f32,
+    annot_margin: f32,
+    spacing: f32,
+    spine_offset: f32,
 }
 
 impl<D> Ctx<'_, D>
@@ -315,32 +377,50 @@ where
     D: data::Source + ?Sized,
 {
     /// Estimate the height taken by a horizontal axis.
-    /// It includes ticks marks, ticks labels and axis title.
+    /// It includes ticks marks, ticks labels and axis titles placed on this side.
     /// This is the height without any additional margin
     pub fn estimate_x_axes_height(&self, x_axes: &[des::Axis], side: des::axis::Side) -> f32 {
+        let layout = self.layout();
         let mut height = 0.0;
         for (idx, axis) in x_axes.iter().filter(|a| a.side() == side).enumerate() {
             if idx != 0 {
-                height += missing_params::AXIS_MARGIN + missing_params::AXIS_SPINE_WIDTH;
+                height += layout.axes_spacing();
             }
+            height += axis.spine_offset();
             if let Some(ticks) = axis.ticks() {
                 if axis.has_tick_labels() {
                     // ticks is only accounted for when there are labels
                     // this allows to merge ticks of subplots with shared scales and zero inter-space
+                    let length = ticks.length().unwrap_or(layout.tick_size);
+                    let (size_in, size_out) = tick_mark_sizes(ticks.direction(), length);
                     if idx != 0 {
-                        height += missing_params::TICK_SIZE;
+                        height += size_in;
                     }
-                    height += missing_params::TICK_SIZE;
-                    height += missing_params::TICK_LABEL_MARGIN + ticks.font().size;
+                    height += size_out;
+                    height += layout.tick_label_margin + ticks.font().size;
                 }
             }
-            if let Some(title) = axis.title() {
-                height += missing_params::AXIS_TITLE_MARGIN + title.props().font_size();
+            if axis.title_side() == side {
+                height += self.estimate_axis_title_height(axis);
             }
         }
+        // titles of the axes of the other side, moved to this side
+        for axis in x_axes
+            .iter()
+            .filter(|a| a.side() != side && a.title_side() == side)
+        {
+            height += self.estimate_axis_title_height(axis);
+        }
         height
     }
 
+    fn estimate_axis_title_height(&self, axis: &des::Axis) -> f32 {
+        axis.title().map_or(0.0, |title| {
+            let lines = title.text().lines().count().max(1);
+            self.layout().axis_title_margin + title.props().font_size() * lines as f32
+        })
+    }
+
     pub fn setup_axis(
         &self,
         des_axis: &des::Axis,
@@ -348,7 +428,7 @@ where
         side: Side,
         size_along: f32,
         insets: &geom::Padding,
-        shared_scale: Option<Rc<RefCell<AxisScale>>>,
+        shared_scale: Option<Arc<RwLock<AxisScale>>>,
         spine: Option<des::plot::Border>,
     ) -> Result<Axis, Error> {
         let id = des_axis.id().map(|s| s.to_string());
@@ -361,7 +441,10 @@ where
             scale
         } else {
             let insets = side.insets(insets);
-            Rc::new(RefCell::new(
+            if size_along <= insets.0 + insets.1 {
+                return Err(Error::FigureTooSmall);
+            }
+            Arc::new(RwLock::new(
                 self.setup_axis_scale(des_axis, bounds, side, size_along, insets)?,
             ))
         };
@@ -385,25 +468,45 @@ where
     ) -> Result<AxisScale, Error> {
         match bounds {
             Bounds::Num(nb) => {
-                let cm = scale::map_scale_coord_num(des_axis.scale(), size_along, &nb, insets);
+                let cm = scale::map_scale_coord_num(
+                    des_axis.scale(),
+                    size_along,
+                    &nb,
+                    insets,
+                    des_axis.axis_break(),
+                );
                 let nb = cm.axis_bounds().as_num().unwrap();
+                let axis_break = cm.num_break();
 
                 let ticks = des_axis
                     .ticks()
                     .map(|major_ticks| {
-                        self.setup_num_ticks(major_ticks, nb, des_axis.scale(), side, None)
+                        self.setup_num_ticks(
+                            major_ticks,
+                            nb,
+                            des_axis.scale(),
+                            axis_break,
+                            side,
+                            None,
+                        )
                     })
                     .transpose()?;
 
                 let minor_ticks = if let Some(mt) = des_axis.minor_ticks() {
-                    Some(self.setup_minor_ticks(mt, ticks.as_ref(), des_axis.scale(), nb)?)
+                    Some(self.setup_minor_ticks(
+                        mt,
+                        ticks.as_ref(),
+                        des_axis.scale(),
+                        nb,
+                        axis_break,
+                    )?)
                 } else {
                     None
                 };
 
                 Ok(AxisScale::Num {
                     cm,
-                    ticks,
+                    ticks: ticks.map(Box::new),
                     minor_ticks,
                     des_scale: des_axis.scale().clone(),
                 })
@@ -411,7 +514,8 @@ where
             #[cfg(feature = "time")]
             Bounds::Time(tb) => {
                 let nb: NumBounds = (*tb).into();
-                let cm = scale::map_scale_coord_num(des_axis.scale(), size_along, &nb, insets);
+                let cm =
+                    scale::map_scale_coord_num(des_axis.scale(), size_along, &nb, insets, None);
                 let nb = cm.axis_bounds().as_num().unwrap();
                 let tb: TimeBounds = nb.into();
 
@@ -430,16 +534,18 @@ where
 
                 Ok(AxisScale::Num {
                     cm,
-                    ticks,
+                    ticks: ticks.map(Box::new),
                     minor_ticks: None,
                     des_scale: des_axis.scale().clone(),
                 })
             }
             Bounds::Cat(cats) => {
                 let bins = CategoryBins::new(size_along, insets, cats.clone());
+                // labels along a horizontal axis must fit in their bin
+                let max_lbl_width = side.is_horizontal().then_some(bins.bin_size);
                 let ticks = des_axis
                     .ticks()
-                    .map(|t| self.setup_cat_ticks(t, cats, side))
+                    .map(|t| self.setup_cat_ticks(t, cats, side, max_lbl_width))
                     .transpose()?;
                 Ok(AxisScale::Cat { bins, ticks })
             }
@@ -451,6 +557,7 @@ where
         major_ticks: &des::axis::Ticks,
         nb: NumBounds,
         scale: &des::axis::Scale,
+        axis_break: Option<(f64, f64)>,
         side: Side,
         copy_from: Option<&NumTicks>,
     ) -> Result<NumTicks, Error> {
@@ -460,10 +567,16 @@ where
         let ticks_align = side.ticks_labels_align();
         let annot_align = side.annot_align();
 
-        let mut major_locs = ticks::locate_num(major_ticks.locator(), nb, scale)?;
+        let mut major_locs = ticks::locate_num(
+            major_ticks.locator(),
+            major_ticks.count_hint(),
+            nb,
+            scale,
+            axis_break,
+        )?;
         major_locs.retain(|l| nb.contains(*l));
 
-        let lbl_formatter = ticks::num_label_formatter(major_ticks, nb, scale);
+        let lbl_formatter = ticks::num_label_formatter(major_ticks, nb, scale, &major_locs);
         let mut ticks = Vec::new();
         for loc in major_locs.into_iter() {
             let text = lbl_formatter.format_label(loc.into());
@@ -505,8 +618,19 @@ where
         major_ticks: Option<&NumTicks>,
         scale: &des::axis::Scale,
         nb: NumBounds,
+        axis_break: Option<(f64, f64)>,
     ) -> Result<MinorTicks, Error> {
-        let mut locs = ticks::locate_minor(minor_ticks.locator(), nb, scale)?;
+        let auto_locator = des::axis::ticks::Locator::Auto;
+        let major_locator = major_ticks.map_or(&auto_locator, |t| t.des_ticks.locator());
+        let major_count_hint = major_ticks.and_then(|t| t.des_ticks.count_hint());
+        let mut locs = ticks::locate_minor(
+            minor_ticks.locator(),
+            major_locator,
+            major_count_hint,
+            nb,
+            scale,
+            axis_break,
+        )?;
         let major_locs = major_ticks.map(|t| t.ticks.as_slice()).unwrap_or(&[]);
 
         locs.retain(|l| {
@@ -580,6 +704,7 @@ where
         des: &des::axis::Ticks,
         cb: &Categories,
         side: Side,
+        max_lbl_width: Option<f32>,
     ) -> Result<CategoryTicks, Error> {
         let db: &font::Database = self.fontdb();
         let font = des.font();
@@ -595,15 +720,19 @@ where
                 font.font.clone(),
                 db,
             )?;
+            let lbl = match max_lbl_width {
+                Some(max_width) => lbl.ellipsize(max_width, db)?,
+                None => lbl,
+            };
             let lbl = Text::from_line_text(&lbl, db, des.color())?;
             lbls.push(lbl);
         }
 
-        let sep = Some(TickMark {
-            stroke: theme::Col::Foreground.into(),
-            size_in: missing_params::TICK_SIZE,
-            size_out: missing_params::TICK_SIZE,
-        });
+        let sep = Some(TickMark::new(
+            theme::Col::Foreground.into(),
+            des.direction(),
+            des.length().unwrap_or(self.layout().tick_size),
+        ));
 
         Ok(CategoryTicks {
             font_size: font.size,
@@ -619,36 +748,60 @@ where
         uses_shared: bool,
         spine: Option<des::plot::Border>,
     ) -> Result<DrawOpts, Error> {
+        let title_side = if des_axis.title_side() == side.to_des_side() {
+            side
+        } else {
+            side.opposite()
+        };
         let title = des_axis
             .title()
-            .map(|title| title.to_rich_text(side.title_layout(), &self.fontdb))
+            .map(|title| {
+                title.to_rich_text(
+                    title_side.title_layout(des_axis.title_align()),
+                    &self.fontdb,
+                )
+            })
             .transpose()?
             .map(|rich| Text::from_rich_text(&rich, &self.fontdb))
             .transpose()?;
 
+        let layout = self.layout();
         let ticks_labels = !uses_shared;
-        let marks = des_axis.ticks().map(|ticks| TickMark {
-            stroke: ticks.color().into(),
-            size_in: missing_params::TICK_SIZE,
-            size_out: missing_params::TICK_SIZE,
+        let marks = des_axis.ticks().map(|ticks| {
+            TickMark::new(
+                ticks.color().into(),
+                ticks.direction(),
+                ticks.length().unwrap_or(layout.tick_size),
+            )
         });
-        let minor_marks = des_axis.minor_ticks().map(|ticks| TickMark {
-            stroke: theme::Stroke::from(ticks.color())
-                .with_width(missing_params::MINOR_TICK_LINE_WIDTH),
-            size_in: missing_params::MINOR_TICK_SIZE,
-            size_out: missing_params::MINOR_TICK_SIZE,
+        let minor_marks = des_axis.minor_ticks().map(|ticks| {
+            TickMark::new(
+                theme::Stroke::from(ticks.color()).with_width(ticks.width()),
+                ticks.direction(),
+                ticks.length().unwrap_or(layout.minor_tick_size),
+            )
         });
         let grid = des_axis.grid().map(|grid| grid.0.clone());
         let minor_grid = des_axis.minor_grid().map(|grid| grid.0.clone());
 
         Ok(DrawOpts {
             title,
+            title_side,
+            title_pos: des_axis.title_pos(),
             spine,
             ticks_labels,
             marks,
             minor_marks,
             grid,
             minor_grid,
+            emphasized_grids: des_axis.emphasized_grids().to_vec(),
+            grid_zorder: des_axis.grid_zorder(),
+            grid_range: des_axis.grid_range(),
+            title_margin: layout.axis_title_margin,
+            tick_label_margin: layout.tick_label_margin,
+            annot_margin: layout.axis_annot_margin,
+            spacing: layout.axes_spacing(),
+            spine_offset: des_axis.spine_offset(),
         })
     }
 
@@ -658,7 +811,7 @@ where
         coord_map: Arc<dyn CoordMap>,
     ) -> Result<(), Error> {
         let scale = self.axis_rebuild_scale(axis, coord_map)?;
-        *axis.scale.as_ref().borrow_mut() = scale;
+        *axis.scale.write().unwrap() = scale;
         Ok(())
     }
 
@@ -667,7 +820,7 @@ where
         axis: &Axis,
         coord_map: Arc<dyn CoordMap>,
     ) -> Result<AxisScale, Error> {
-        let scale = axis.scale.as_ref().borrow();
+        let scale = axis.scale.read().unwrap();
         match &*scale {
             AxisScale::Num {
                 des_scale,
@@ -676,25 +829,39 @@ where
                 ..
             } => {
                 let bounds = coord_map.axis_bounds().as_num().unwrap();
+                let axis_break = coord_map.num_break();
                 let des_scale = adapt_des_scale(des_scale, &bounds);
                 let ticks = ticks
                     .as_ref()
                     .map(|t| {
-                        self.setup_num_ticks(&t.des_ticks, bounds, &des_scale, axis.side, Some(t))
+                        self.setup_num_ticks(
+                            &t.des_ticks,
+                            bounds,
+                            &des_scale,
+                            axis_break,
+                            axis.side,
+                            Some(t),
+                        )
                     })
                     .transpose()?;
 
                 let minor_ticks = minor_ticks
                     .as_ref()
                     .map(|mt| {
-                        self.setup_minor_ticks(&mt.des_ticks, ticks.as_ref(), &des_scale, bounds)
+                        self.setup_minor_ticks(
+                            &mt.des_ticks,
+                            ticks.as_ref(),
+                            &des_scale,
+                            bounds,
+                            axis_break,
+                        )
                     })
                     .transpose()?;
 
                 Ok(AxisScale::Num {
                     des_scale,
                     cm: coord_map,
-                    ticks,
+                    ticks: ticks.map(Box::new),
                     minor_ticks,
                 })
             }
@@ -730,16 +897,36 @@ fn tick_loc_is_close(a: f64, b: f64) -> bool {
 }
 
 impl Axis {
-    pub fn draw_minor_grids<S>(&self, surface: &mut S, style: &Style, plot_rect: &geom::Rect)
+    pub fn grid_zorder(&self) -> des::ZOrder {
+        self.draw_opts.grid_zorder
+    }
+
+    /// Whether a grid line at `loc` is within the grid range of the axis
+    fn in_grid_range(&self, loc: f64) -> bool {
+        match self.draw_opts.grid_range {
+            Some((low, high)) => {
+                (loc >= low || tick_loc_is_close(loc, low))
+                    && (loc <= high || tick_loc_is_close(loc, high))
+            }
+            None => true,
+        }
+    }
+
+    pub fn draw_minor_grids<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        plot_rect: &geom::Rect,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
-        let scale = self.scale.as_ref().borrow();
+        let scale = self.scale.read().unwrap();
         let AxisScale::Num {
             cm, minor_ticks, ..
         } = &*scale
         else {
-            return;
+            return Ok(());
         };
 
         if let Some(minor_ticks) = minor_ticks {
@@ -749,7 +936,12 @@ impl Axis {
                     2 * minor_ticks.locs.len(),
                 );
                 let stroke = Some(grid.as_stroke(style));
-                for t in minor_ticks.locs.iter().copied() {
+                for t in minor_ticks
+                    .locs
+                    .iter()
+                    .copied()
+                    .filter(|t| self.in_grid_range(*t))
+                {
                     let (p1, p2) = self.side.grid_line_points(t, &**cm, plot_rect);
                     pathb.move_to(p1.x, p1.y);
                     pathb.line_to(p2.x, p2.y);
@@ -760,27 +952,44 @@ impl Axis {
                         stroke,
                         transform: None,
                     };
-                    surface.draw_path(&rpath);
+                    surface.draw_path(&rpath)?;
                     pathb = path.clear();
                 }
             }
         }
+        Ok(())
     }
 
-    pub fn draw_major_grids<S>(&self, surface: &mut S, style: &Style, plot_rect: &geom::Rect)
+    pub fn draw_major_grids<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        plot_rect: &geom::Rect,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
-        let scale = self.scale.as_ref().borrow();
+        let scale = self.scale.read().unwrap();
         let AxisScale::Num { cm, ticks, .. } = &*scale else {
-            return;
+            return Ok(());
+        };
+        let emphasized = &self.draw_opts.emphasized_grids;
+        let is_emphasized = |loc: f64| {
+            emphasized
+                .iter()
+                .flat_map(|em| em.values.iter())
+                .any(|v| *v == loc || tick_loc_is_close(*v, loc))
         };
         if let Some(ticks) = ticks {
             if let Some(grid) = &self.draw_opts.grid {
                 let mut pathb =
                     geom::PathBuilder::with_capacity(2 * ticks.ticks.len(), 2 * ticks.ticks.len());
                 let stroke = Some(grid.as_stroke(style));
-                for t in ticks.ticks.iter() {
+                let ticks = ticks
+                    .ticks
+                    .iter()
+                    .filter(|t| self.in_grid_range(t.loc) && !is_emphasized(t.loc));
+                for t in ticks {
                     let (p1, p2) = self.side.grid_line_points(t.loc, &**cm, plot_rect);
                     pathb.move_to(p1.x, p1.y);
                     pathb.line_to(p2.x, p2.y);
@@ -791,23 +1000,64 @@ impl Axis {
                         stroke,
                         transform: None,
                     };
-                    surface.draw_path(&rpath);
+                    surface.draw_path(&rpath)?;
                     pathb = path.clear();
                 }
             }
         }
+
+        let extent = if self.side.is_horizontal() {
+            plot_rect.width()
+        } else {
+            plot_rect.height()
+        };
+        let mut pathb = geom::PathBuilder::new();
+        for em in emphasized {
+            let stroke = Some(em.line.as_stroke(style));
+            for v in em.values.iter().copied() {
+                // values outside the axis range, or that the scale can't map, are skipped
+                let pos = cm.map_coord_num(v);
+                if !pos.is_finite() || pos < -0.5 || pos > extent + 0.5 {
+                    continue;
+                }
+                let (p1, p2) = self.side.grid_line_points(v, &**cm, plot_rect);
+                pathb.move_to(p1.x, p1.y);
+                pathb.line_to(p2.x, p2.y);
+                let path = pathb.finish().expect("Should be a valid path");
+                let rpath = render::Path {
+                    path: &path,
+                    fill: None,
+                    stroke,
+                    transform: None,
+                };
+                surface.draw_path(&rpath)?;
+                pathb = path.clear();
+            }
+        }
+        Ok(())
     }
 
-    pub fn draw<S>(&self, surface: &mut S, style: &Style, plot_rect: &geom::Rect) -> f32
+    pub fn draw<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        plot_rect: &geom::Rect,
+    ) -> Result<f32, render::Error>
     where
         S: render::Surface,
     {
+        // the offset moves the whole axis across, the positions along the axis are unchanged
+        let offset = self.draw_opts.spine_offset;
+        let rect = self.side.shifted_rect(plot_rect, offset);
+        let plot_rect = &rect;
+
         if let Some(spine) = self.draw_opts.spine.as_ref() {
-            self.draw_spine(surface, style, plot_rect, spine);
+            self.draw_spine(surface, style, plot_rect, spine)?;
         }
+        self.draw_break_marks(surface, style, plot_rect)?;
 
         let mut shift_across = {
-            let scale = self.scale.as_ref().borrow();
+            let scale = self.scale.read().unwrap();
             match &*scale {
                 AxisScale::Num {
                     cm,
@@ -823,17 +1073,17 @@ impl Axis {
                             &**cm,
                             minor_ticks,
                             plot_rect,
-                        ));
+                        )?);
                     }
                     if let Some(ticks) = ticks {
                         shift = shift
-                            .max(self.draw_major_ticks(surface, style, &**cm, ticks, plot_rect));
+                            .max(self.draw_major_ticks(surface, style, &**cm, ticks, plot_rect)?);
                     }
                     shift
                 }
                 AxisScale::Cat { bins, ticks, .. } => {
                     if let Some(ticks) = ticks {
-                        self.draw_category_ticks(surface, style, bins, ticks, plot_rect)
+                        self.draw_category_ticks(surface, style, bins, ticks, plot_rect)?
                     } else {
                         0.0
                     }
@@ -841,14 +1091,44 @@ impl Axis {
             }
         };
 
+        if self.draw_opts.title_side == self.side {
+            shift_across = self.draw_title(surface, style, plot_rect, shift_across)?;
+        }
+        Ok(offset + shift_across)
+    }
+
+    /// Draw the title on its side of the plot, `shift_across` away from the plot area.
+    /// Returns the shift across after the title.
+    pub fn draw_title<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        plot_rect: &geom::Rect,
+        mut shift_across: f32,
+    ) -> Result<f32, render::Error>
+    where
+        S: render::Surface,
+    {
         if let Some(title) = self.draw_opts.title.as_ref() {
-            shift_across += missing_params::AXIS_TITLE_MARGIN;
-            let transform = self.side.title_transform(shift_across, plot_rect);
-            title.draw(surface, style, Some(&transform));
+            shift_across += self.draw_opts.title_margin;
+            // the lines are aligned with each other, and the whole block is placed along the axis
+            let anchor_x = title
+                .bbox
+                .map_or(0.0, |bbox| match self.draw_opts.title_pos {
+                    des::axis::TitlePos::Start => bbox.left(),
+                    des::axis::TitlePos::Center => bbox.center_x(),
+                    des::axis::TitlePos::End => bbox.right(),
+                });
+            let transform = self
+                .draw_opts
+                .title_side
+                .title_transform(shift_across, self.draw_opts.title_pos, plot_rect)
+                .pre_translate(-anchor_x, 0.0);
+            title.draw(surface, style, Some(&transform))?;
             // vertical titles are rotated, so it is always the height that is relevant here.
             shift_across += title.height();
         }
-        shift_across
+        Ok(shift_across)
     }
 
     fn draw_major_ticks<S>(
@@ -858,7 +1138,7 @@ impl Axis {
         cm: &dyn CoordMap,
         ticks: &NumTicks,
         plot_rect: &geom::Rect,
-    ) -> f32
+    ) -> Result<f32, render::Error>
     where
         S: render::Surface,
     {
@@ -867,14 +1147,14 @@ impl Axis {
         if let Some(mark) = self.draw_opts.marks.as_ref() {
             let transform = self.side.ticks_marks_transform(plot_rect);
             let ticks = ticks.ticks.iter().map(|t| cm.map_coord_num(t.loc));
-            shift_across += self.draw_ticks_marks(surface, style, ticks, mark, &transform);
+            shift_across += self.draw_ticks_marks(surface, style, ticks, mark, &transform)?;
         }
 
         if !self.draw_opts.ticks_labels {
-            return shift_across;
+            return Ok(shift_across);
         }
 
-        shift_across += missing_params::TICK_LABEL_MARGIN;
+        shift_across += self.draw_opts.tick_label_margin;
         let mut max_lbl_size: f32 = 0.0;
 
         for t in ticks.ticks.iter() {
@@ -885,16 +1165,30 @@ impl Axis {
             let transform = self
                 .side
                 .tick_label_transform(pos_along, shift_across, plot_rect);
-            t.lbl.draw(surface, style, Some(&transform));
+            t.lbl.draw(surface, style, Some(&transform))?;
         }
 
         shift_across += max_lbl_size;
 
         if let Some(annot) = ticks.annot.as_ref() {
-            let transform = self.side.annot_transform(shift_across, plot_rect);
-            annot.draw(surface, style, Some(&transform));
+            let transform =
+                self.side
+                    .annot_transform(shift_across, self.draw_opts.annot_margin, plot_rect);
+            annot.draw(surface, style, Some(&transform))?;
+        }
+        Ok(shift_across + self.vertical_annot_size(ticks))
+    }
+
+    /// Size across the axis taken by the annotation of vertical axes.
+    /// It is drawn beyond the labels, whereas for horizontal axes it is drawn
+    /// at the end of the axis, out of the way of the title.
+    fn vertical_annot_size(&self, ticks: &NumTicks) -> f32 {
+        match ticks.annot.as_ref() {
+            Some(annot) if self.draw_opts.ticks_labels && !self.side.is_horizontal() => {
+                self.draw_opts.annot_margin + annot.width()
+            }
+            _ => 0.0,
         }
-        shift_across
     }
 
     fn draw_spine<S>(
@@ -903,7 +1197,8 @@ impl Axis {
         style: &Style,
         plot_rect: &geom::Rect,
         spine: &des::plot::Border,
-    ) where
+    ) -> Result<(), render::Error>
+    where
         S: render::Surface,
     {
         let stroke = spine.line().as_stroke(style);
@@ -914,7 +1209,64 @@ impl Axis {
             stroke: Some(stroke),
             transform: None,
         };
-        surface.draw_path(&rpath);
+        surface.draw_path(&rpath)
+    }
+
+    /// Draw the zig-zag marks at the seam of an axis break, on a gap cut in the axis line
+    fn draw_break_marks<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        plot_rect: &geom::Rect,
+    ) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        const HALF_GAP: f32 = 3.0;
+        const HALF_LEN: f32 = 6.0;
+        const ZIG: f32 = 2.0;
+
+        let seam = {
+            let scale = self.scale.read().unwrap();
+            let AxisScale::Num { cm, .. } = &*scale else {
+                return Ok(());
+            };
+            let Some((low, _)) = cm.num_break() else {
+                return Ok(());
+            };
+            cm.map_coord_num(low)
+        };
+
+        let transform = self.side.ticks_marks_transform(plot_rect);
+        let gap = render::Rect {
+            rect: geom::Rect::from_xywh(seam - HALF_GAP, -HALF_LEN, 2.0 * HALF_GAP, 2.0 * HALF_LEN),
+            fill: Some(theme::Fill::from(theme::Col::Background).as_paint(style)),
+            stroke: None,
+            transform: Some(&transform),
+        };
+        surface.draw_rect(&gap)?;
+
+        let mut pb = geom::PathBuilder::new();
+        for x in [seam - HALF_GAP, seam + HALF_GAP] {
+            pb.move_to(x - ZIG, -HALF_LEN);
+            pb.line_to(x + ZIG, -HALF_LEN / 3.0);
+            pb.line_to(x - ZIG, HALF_LEN / 3.0);
+            pb.line_to(x + ZIG, HALF_LEN);
+        }
+        let path = pb.finish().expect("Should be a valid path");
+        let stroke = self
+            .draw_opts
+            .spine
+            .as_ref()
+            .map(|spine| spine.line().clone())
+            .unwrap_or_else(|| theme::Col::Foreground.into());
+        let rpath = render::Path {
+            path: &path,
+            fill: None,
+            stroke: Some(stroke.as_stroke(style)),
+            transform: Some(&transform),
+        };
+        surface.draw_path(&rpath)
     }
 
     fn draw_minor_ticks<S>(
@@ -924,12 +1276,12 @@ impl Axis {
         cm: &dyn CoordMap,
         minor_ticks: &MinorTicks,
         plot_rect: &geom::Rect,
-    ) -> f32
+    ) -> Result<f32, render::Error>
     where
         S: render::Surface,
     {
         let Some(mark) = self.draw_opts.minor_marks.as_ref() else {
-            return 0.0;
+            return Ok(0.0);
         };
         let transform = self.side.ticks_marks_transform(plot_rect);
         let ticks = minor_ticks
@@ -947,17 +1299,17 @@ impl Axis {
         bins: &CategoryBins,
         ticks: &CategoryTicks,
         plot_rect: &geom::Rect,
-    ) -> f32
+    ) -> Result<f32, render::Error>
     where
         S: render::Surface,
     {
         if let Some(sep) = ticks.sep.as_ref() {
             let locs = (0..bins.len() + 1).map(|i| bins.sep_location(i));
             let transform = self.side.ticks_marks_transform(plot_rect);
-            self.draw_ticks_marks(surface, style, locs, sep, &transform);
+            self.draw_ticks_marks(surface, style, locs, sep, &transform)?;
         }
         // tick marks are separators, so not counted in shift_across, because not supposed to overlap
-        let shift_across = missing_params::TICK_LABEL_MARGIN;
+        let shift_across = self.draw_opts.tick_label_margin;
 
         let mut max_lbl_size: f32 = 0.0;
 
@@ -969,10 +1321,10 @@ impl Axis {
             let transform = self
                 .side
                 .tick_label_transform(pos_along, shift_across, plot_rect);
-            lbl.draw(surface, style, Some(&transform));
+            lbl.draw(surface, style, Some(&transform))?;
         }
 
-        shift_across + max_lbl_size
+        Ok(shift_across + max_lbl_size)
     }
 
     // return shift across axis (distance to get away from axis to avoid collision)
@@ -983,7 +1335,7 @@ impl Axis {
         ticks: I,
         mark: &TickMark,
         transform: &geom::Transform,
-    ) -> f32
+    ) -> Result<f32, render::Error>
     where
         S: render::Surface,
         I: Iterator<Item = f32>,
@@ -1000,8 +1352,8 @@ impl Axis {
                 stroke: Some(mark.stroke.as_stroke(style)),
                 transform: Some(transform),
             };
-            surface.draw_path(&rpath);
+            surface.draw_path(&rpath)?;
         }
-        mark.size_out
+        Ok(mark.size_out)
     }
 }
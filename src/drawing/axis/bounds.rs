@@ -112,6 +112,25 @@ impl BoundsRef<'_> {
             _ => None,
         }
     }
+
+    /// Check whether `other` fits entirely within these bounds.
+    /// Time bounds are compared numerically, as the coordinate maps hold them as timestamps.
+    pub fn contains_bounds(&self, other: &BoundsRef) -> bool {
+        match (self, other) {
+            (BoundsRef::Num(a), BoundsRef::Num(b)) => a.contains(b.start()) && a.contains(b.end()),
+            #[cfg(feature = "time")]
+            (BoundsRef::Num(a), &BoundsRef::Time(b)) => {
+                let b: NumBounds = b.into();
+                a.contains(b.start()) && a.contains(b.end())
+            }
+            #[cfg(feature = "time")]
+            (BoundsRef::Time(a), BoundsRef::Time(b)) => {
+                a.contains(b.start()) && a.contains(b.end())
+            }
+            (BoundsRef::Cat(a), BoundsRef::Cat(b)) => b.iter().all(|c| a._contains(c)),
+            _ => false,
+        }
+    }
 }
 
 impl std::cmp::PartialEq<Bounds> for BoundsRef<'_> {
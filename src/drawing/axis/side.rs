@@ -1,6 +1,6 @@
 use crate::drawing::plot::Orientation;
 use crate::drawing::scale::CoordMap;
-use crate::{des, geom, missing_params, text};
+use crate::{des, geom, text};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Side {
@@ -35,6 +35,20 @@ impl Side {
         }
     }
 
+    /// The side across the plot area
+    pub fn opposite(&self) -> Self {
+        match self {
+            Side::Bottom => Side::Top,
+            Side::Top => Side::Bottom,
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self.direction(), Direction::Horizontal)
+    }
+
     fn direction(&self) -> Direction {
         match self {
             Side::Bottom | Side::Top => Direction::Horizontal,
@@ -42,51 +56,74 @@ impl Side {
         }
     }
 
-    /// Layout options for axis title
-    pub fn title_layout(&self) -> text::rich::Layout {
-        match self {
-            Side::Bottom => text::rich::Layout::Horizontal(
-                text::rich::Align::Center,
-                text::rich::VerAlign::Top,
-                Default::default(),
-            ),
-            Side::Top => text::rich::Layout::Horizontal(
-                text::rich::Align::Center,
-                text::rich::VerAlign::Bottom,
-                Default::default(),
-            ),
-            Side::Left => text::rich::Layout::Horizontal(
-                text::rich::Align::Center,
-                text::rich::VerAlign::Bottom,
-                Default::default(),
-            ),
-            Side::Right => text::rich::Layout::Horizontal(
-                text::rich::Align::Center,
-                text::rich::VerAlign::Top,
-                Default::default(),
-            ),
-        }
+    /// Layout options for axis title.
+    /// Multi-line titles grow away from the plot, with lines aligned according to `align`.
+    pub fn title_layout(&self, align: text::line::Align) -> text::rich::Layout {
+        let align = match align {
+            text::line::Align::Start => text::rich::Align::Start,
+            text::line::Align::Left => text::rich::Align::Left,
+            text::line::Align::Center => text::rich::Align::Center,
+            text::line::Align::End => text::rich::Align::End,
+            text::line::Align::Right => text::rich::Align::Right,
+        };
+        let ver_align = match self {
+            Side::Bottom | Side::Right => text::rich::VerAlign::Top,
+            Side::Top | Side::Left => text::rich::VerAlign::Bottom,
+        };
+        text::rich::Layout::Horizontal(align, ver_align, Default::default())
     }
 
-    pub fn title_transform(&self, shift_across: f32, rect: &geom::Rect) -> geom::Transform {
+    /// Return the transform to be applied to the axis title.
+    /// The origin is placed at `pos` along the axis, and `shift_across` away from the plot.
+    /// Vertical titles are rotated, such as their start is at the bottom.
+    pub fn title_transform(
+        &self,
+        shift_across: f32,
+        pos: des::axis::TitlePos,
+        rect: &geom::Rect,
+    ) -> geom::Transform {
+        let x = match pos {
+            des::axis::TitlePos::Start => rect.left(),
+            des::axis::TitlePos::Center => rect.center_x(),
+            des::axis::TitlePos::End => rect.right(),
+        };
+        let y = match pos {
+            des::axis::TitlePos::Start => rect.bottom(),
+            des::axis::TitlePos::Center => rect.center_y(),
+            des::axis::TitlePos::End => rect.top(),
+        };
         match self {
-            Side::Bottom => {
-                geom::Transform::from_translate(rect.center_x(), rect.bottom() + shift_across)
-            }
-            Side::Top => {
-                geom::Transform::from_translate(rect.center_x(), rect.top() - shift_across)
-            }
+            Side::Bottom => geom::Transform::from_translate(x, rect.bottom() + shift_across),
+            Side::Top => geom::Transform::from_translate(x, rect.top() - shift_across),
             Side::Left => {
-                geom::Transform::from_translate(rect.left() - shift_across, rect.center_y())
-                    .pre_rotate(-90.0)
+                geom::Transform::from_translate(rect.left() - shift_across, y).pre_rotate(-90.0)
             }
             Side::Right => {
-                geom::Transform::from_translate(rect.right() + shift_across, rect.center_y())
-                    .pre_rotate(-90.0)
+                geom::Transform::from_translate(rect.right() + shift_across, y).pre_rotate(-90.0)
             }
         }
     }
 
+    /// Move the edge of `rect` on this side away from the rectangle by `shift`
+    pub fn shifted_rect(&self, rect: &geom::Rect, shift: f32) -> geom::Rect {
+        match self {
+            Side::Top => rect.shifted_top_side(-shift),
+            Side::Right => rect.shifted_right_side(shift),
+            Side::Bottom => rect.shifted_bottom_side(shift),
+            Side::Left => rect.shifted_left_side(-shift),
+        }
+    }
+
+    /// The spine of this side in the plot spine configuration
+    pub fn spine<'a>(&self, spines: &'a des::plot::SpineConfig) -> &'a des::plot::Spine {
+        match self {
+            Side::Bottom => &spines.bottom,
+            Side::Top => &spines.top,
+            Side::Left => &spines.left,
+            Side::Right => &spines.right,
+        }
+    }
+
     pub fn spine_path(&self, rect: &geom::Rect, spine: &des::plot::Border) -> geom::Path {
         let overflow = match spine {
             des::plot::Border::Box(_) => 0.0,
@@ -213,8 +250,12 @@ impl Side {
         }
     }
 
-    pub fn annot_transform(&self, shift_across: f32, rect: &geom::Rect) -> geom::Transform {
-        let margin = missing_params::AXIS_ANNOT_MARGIN;
+    pub fn annot_transform(
+        &self,
+        shift_across: f32,
+        margin: f32,
+        rect: &geom::Rect,
+    ) -> geom::Transform {
         match self {
             Side::Bottom => {
                 geom::Transform::from_translate(rect.right(), rect.bottom() + shift_across + margin)
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use crate::drawing::legend::{self, LegendBuilder};
-use crate::drawing::{Ctx, Error, plot};
+use crate::drawing::{Ctx, Error, plot, series};
 use crate::style::theme;
-use crate::{Style, data, des, geom, missing_params, render, text};
+use crate::{Style, data, des, geom, render, text};
 
 /// A figure that has been prepared for drawing. See the [`Prepare`](crate::drawing::Prepare) trait.
 /// It contains all the necessary data and layout information.
@@ -10,13 +12,35 @@ use crate::{Style, data, des, geom, missing_params, render, text};
 /// Therefore, the fonts are no longer needed at draw time.
 ///
 /// The colors, strokes and fills will be resolved at draw time using the given theme.
+/// Nothing depends on the style during preparation, so the same prepared figure
+/// can be drawn several times with different styles (e.g. light and dark).
 #[derive(Debug)]
 pub struct PreparedFigure {
     pub(super) size: geom::Size,
     pub(super) fill: Option<theme::Fill>,
     pub(super) title: Option<(geom::Transform, super::Text)>,
+    pub(super) subtitle: Option<(geom::Transform, super::Text)>,
     pub(super) legend: Option<(geom::Point, legend::Legend)>,
     pub(super) plots: plot::Plots,
+    pub(super) watermark: Option<Watermark>,
+    pub(super) layout: super::LayoutParams,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Watermark {
+    layer: des::figure::WatermarkLayer,
+    transform: geom::Transform,
+    content: WatermarkContent,
+}
+
+#[derive(Debug, Clone)]
+enum WatermarkContent {
+    Text(super::Text),
+    Image {
+        image: des::figure::WatermarkImage,
+        rect: geom::Rect,
+        opacity: f32,
+    },
 }
 
 impl Clone for PreparedFigure {
@@ -25,8 +49,11 @@ impl Clone for PreparedFigure {
             size: self.size,
             fill: self.fill.clone(),
             title: self.title.clone(),
+            subtitle: self.subtitle.clone(),
             legend: self.legend.clone(),
             plots: self.plots.clone(),
+            watermark: self.watermark.clone(),
+            layout: self.layout,
         }
     }
 }
@@ -37,11 +64,91 @@ impl PreparedFigure {
         self.size
     }
 
+    /// The natural aspect ratio of the figure (width over height)
+    pub fn aspect_ratio(&self) -> f32 {
+        self.size.aspect_ratio()
+    }
+
+    /// The largest surface size that fits within `max` while preserving the aspect ratio
+    /// of the figure. Drawing the figure on a surface of this size doesn't distort it.
+    pub fn fit_size(&self, max: geom::Size) -> geom::Size {
+        self.size.fit_within(max)
+    }
+
     ///
     pub fn plot_indices(&self) -> impl Iterator<Item = des::PlotIdx> + '_ {
         self.plots.iter_indices()
     }
 
+    /// The geometry of the series `series_idx` of the plot `plot_idx`, in figure units.
+    /// The geometry is immutable, and reflects the last data update (see [`update_data`](Self::update_data)).
+    /// Returns `None` if there is no such series.
+    pub fn series_geometry(
+        &self,
+        plot_idx: impl Into<des::PlotIdx>,
+        series_idx: usize,
+    ) -> Option<series::SeriesGeometry<'_>> {
+        let plot = self.plots.plot(plot_idx.into())?;
+        plot.series().get(series_idx).map(|s| s.geometry())
+    }
+
+    /// Whether the series `series_idx` of the plot `plot_idx` is visible.
+    /// Returns `None` if there is no such series.
+    pub fn series_visible(
+        &self,
+        plot_idx: impl Into<des::PlotIdx>,
+        series_idx: usize,
+    ) -> Option<bool> {
+        let plot = self.plots.plot(plot_idx.into())?;
+        plot.series().get(series_idx).map(|s| s.visible())
+    }
+
+    /// Show or hide the series `series_idx` of the plot `plot_idx`,
+    /// and dim the legend entries of hidden series.
+    /// The axes are not updated and keep the bounds computed when the figure was prepared.
+    /// Returns `false` if there is no such series.
+    pub fn set_series_visible(
+        &mut self,
+        plot_idx: impl Into<des::PlotIdx>,
+        series_idx: usize,
+        visible: bool,
+    ) -> bool {
+        let Some(plot) = self.plots.plot_mut(plot_idx.into()) else {
+            return false;
+        };
+        if !plot.set_series_visible(series_idx, visible) {
+            return false;
+        }
+        if let Some((_, legend)) = self.legend.as_mut() {
+            let plots = &self.plots;
+            legend.update_dimmed(|plot_idx, series_idx| {
+                plots
+                    .plot(plot_idx)
+                    .and_then(|p| p.series().get(series_idx))
+                    .is_some_and(|s| s.visible())
+            });
+        }
+        true
+    }
+
+    /// Toggle the visibility of the series linked to the legend entry under `point`,
+    /// in figure coordinates. See [`legend_series_at`](Self::legend_series_at).
+    /// The series are all hidden if any of them is visible, and all shown otherwise.
+    /// Returns `false` if there is no entry linked to series under `point`.
+    pub fn toggle_legend_entry_at(&mut self, point: geom::Point) -> bool {
+        let Some(series) = self.legend_series_at(point) else {
+            return false;
+        };
+        let series = series.to_vec();
+        let visible = !series
+            .iter()
+            .any(|&(plot_idx, series_idx)| self.series_visible(plot_idx, series_idx) == Some(true));
+        for (plot_idx, series_idx) in series {
+            self.set_series_visible(plot_idx, series_idx, visible);
+        }
+        true
+    }
+
     pub(super) fn _title_area(&self) -> Option<geom::Rect> {
         self.title
             .as_ref()
@@ -59,6 +166,8 @@ impl PreparedFigure {
     /// efficient redraws in real-time applications.
     /// Note that axis bounds are not recomputed, only the series data is updated,
     /// within the same axes bounds.
+    /// Returns [`Error::InconsistentData`] if the X and Y columns of a series
+    /// no longer have the same length.
     pub fn update_series_data<D>(&mut self, data_source: &D) -> Result<(), Error>
     where
         D: data::Source + ?Sized,
@@ -66,6 +175,22 @@ impl PreparedFigure {
         self.plots.update_series_data(data_source)?;
         Ok(())
     }
+
+    /// Update the data for all series in the figure from the given data source,
+    /// without re-running the figure layout.
+    ///
+    /// Unlike [`update_series_data`](Self::update_series_data), the data bounds of each series
+    /// are recomputed from the new data.
+    /// Returns `true` if any series no longer fits within its axes bounds,
+    /// or if the data changed the text of its labels,
+    /// in which case the figure should be prepared again to relayout the axes and labels.
+    /// Otherwise, the series are re-mapped within the current axes and the figure can be redrawn as is.
+    pub fn update_data<D>(&mut self, data_source: &D) -> Result<bool, Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        self.plots.update_data(data_source)
+    }
 }
 
 impl<D> Ctx<'_, D>
@@ -73,29 +198,40 @@ where
     D: data::Source + ?Sized,
 {
     pub fn setup_figure(&self, fig: &des::Figure) -> Result<PreparedFigure, Error> {
-        let mut rect =
-            geom::Rect::from_ps(geom::Point { x: 0.0, y: 0.0 }, fig.size()).pad(fig.padding());
+        let fig_rect = geom::Rect::from_ps(geom::Point { x: 0.0, y: 0.0 }, fig.size());
+        super::check_room(&fig_rect, fig.padding().sum_hor(), fig.padding().sum_ver())?;
+        let mut rect = fig_rect.pad(fig.padding());
+
+        // the title and subtitle are stacked at the top of the figure
+        let layout = super::title_layout(fig.title_align());
+        let anchor_x = super::title_anchor_x(fig.title_align(), &rect);
+        let mut top = rect.top();
 
         let mut title = None;
         if let Some(fig_title) = fig.title() {
-            let layout = text::rich::Layout::Horizontal(
-                text::rich::Align::Center,
-                text::line::VerAlign::Hanging.into(),
-                Default::default(),
-            );
             let rich = fig_title.to_rich_text(layout, self.fontdb())?;
             let paths = super::Text::from_rich_text(&rich, self.fontdb())?;
+            let transform = geom::Transform::from_translate(anchor_x, top);
+            top += rich.visual_bbox().map_or(0.0, |bbox| bbox.height());
+            title = Some((transform, paths));
+        }
 
-            let anchor_x = rect.center_x();
-            let anchor_y = rect.top();
-            let transform = geom::Transform::from_translate(anchor_x, anchor_y);
-
-            rect = rect.shifted_top_side(
-                rich.visual_bbox().map_or(0.0, |bbox| bbox.height())
-                    + missing_params::FIG_TITLE_MARGIN,
-            );
+        let mut subtitle = None;
+        if let Some(fig_subtitle) = fig.subtitle() {
+            if title.is_some() {
+                top += self.layout().fig_subtitle_margin;
+            }
+            let rich = fig_subtitle.to_rich_text(layout, self.fontdb())?;
+            let paths = super::Text::from_rich_text(&rich, self.fontdb())?;
+            let transform = geom::Transform::from_translate(anchor_x, top);
+            top += rich.visual_bbox().map_or(0.0, |bbox| bbox.height());
+            subtitle = Some((transform, paths));
+        }
 
-            title = Some((transform, paths));
+        if title.is_some() || subtitle.is_some() {
+            let title_height = top - rect.top() + self.layout().fig_title_margin;
+            super::check_room(&rect, 0.0, title_height)?;
+            rect = rect.shifted_top_side(title_height);
         }
 
         let mut legend = None;
@@ -106,14 +242,79 @@ where
             }
         }
 
-        let plots = self.setup_plots(fig.plots(), &rect)?;
+        let plots = self.setup_plots(fig.plots(), &rect, fig.plot_legends())?;
+
+        let watermark = fig
+            .watermark()
+            .map(|wm| self.setup_watermark(wm, fig.size()))
+            .transpose()?;
 
         Ok(PreparedFigure {
             size: fig.size(),
             fill: fig.fill().clone(),
             title,
+            subtitle,
             legend,
             plots,
+            watermark,
+            layout: *self.layout(),
+        })
+    }
+
+    fn setup_watermark(
+        &self,
+        watermark: &des::figure::Watermark,
+        fig_size: geom::Size,
+    ) -> Result<Watermark, Error> {
+        // the content is centered on the origin, then rotated and moved to the figure center
+        let transform =
+            geom::Transform::from_translate(fig_size.width() / 2.0, fig_size.height() / 2.0)
+                .pre_rotate(-watermark.angle());
+
+        let content = match watermark.content() {
+            des::figure::WatermarkContent::Text(text) => {
+                let line_text = text::LineText::new(
+                    text.clone(),
+                    (text::line::Align::Center, text::line::VerAlign::Middle),
+                    watermark.font_size(),
+                    watermark.font().clone(),
+                    self.fontdb(),
+                )?;
+                let text =
+                    super::Text::from_line_text(&line_text, self.fontdb(), watermark.color())?
+                        .with_opacity(watermark.opacity());
+                WatermarkContent::Text(text)
+            }
+            des::figure::WatermarkContent::Image(image) => {
+                let size = image.size;
+                let rect = geom::Rect::from_xywh(
+                    -size.width() / 2.0,
+                    -size.height() / 2.0,
+                    size.width(),
+                    size.height(),
+                );
+                render::Image {
+                    rgba: &image.rgba,
+                    width: image.width,
+                    height: image.height,
+                    rect,
+                    opacity: watermark.opacity(),
+                    transform: None,
+                }
+                .check()
+                .map_err(|err| Error::InconsistentDesign(format!("Watermark: {}", err)))?;
+                WatermarkContent::Image {
+                    image: image.clone(),
+                    rect,
+                    opacity: watermark.opacity(),
+                }
+            }
+        };
+
+        Ok(Watermark {
+            layer: watermark.layer(),
+            transform,
+            content,
         })
     }
 
@@ -130,22 +331,46 @@ where
             self.fontdb(),
         );
 
-        let mut idx = 0;
-        for plot in fig.plots().iter().filter_map(|p| p) {
-            plot::for_each_series(plot, |s| {
+        builder.add_des_entries(legend, des::legend::EntryOrder::BeforeSeries)?;
+        // entries are merged by label, and indexed per plot as in plot legends,
+        // such that the same series identity gets the same automatic color in all plots
+        let mut labels = HashMap::new();
+        let cols = fig.plots().cols();
+        for (plt_idx, plot) in fig.plots().iter().enumerate() {
+            let Some(plot) = plot else { continue };
+            let plt_idx = des::PlotIdx::from_index(plt_idx, cols);
+            let mut idx = 0;
+            plot::for_each_series(plot, |series_idx, s| {
                 if let Some(entry) = s.legend_entry() {
-                    builder.add_entry(idx, entry)?;
+                    let entry_idx = match labels.get(entry.label) {
+                        Some(&entry_idx) => entry_idx,
+                        None => {
+                            let entry_idx = builder.len();
+                            labels.insert(entry.label.to_string(), entry_idx);
+                            builder.add_entry(idx, entry)?;
+                            entry_idx
+                        }
+                    };
+                    let visible = plot.series()[series_idx].visible();
+                    builder.link_series(entry_idx, (plt_idx, series_idx), visible);
                     idx += 1;
                 }
                 Ok(())
             })?;
         }
 
+        builder.add_des_entries(legend, des::legend::EntryOrder::AfterSeries)?;
+
         let Some(leg) = builder.layout() else {
             return Ok(None);
         };
 
         let sz = leg.size();
+        if legend.pos().prefers_vertical() {
+            super::check_room(rect, sz.width() + legend.margin(), 0.0)?;
+        } else {
+            super::check_room(rect, 0.0, sz.height() + legend.margin())?;
+        }
         let top_left = match legend.pos() {
             des::figure::LegendPos::Top => {
                 let tl = geom::Point {
@@ -185,24 +410,844 @@ where
 impl PreparedFigure {
     /// Draw the figure on the given rendering surface, using the given theme
     /// The surface content will be replaced by the figure drawing.
-    pub fn draw<S>(&self, surface: &mut S, style: &Style)
+    /// The style is only used for this drawing, the figure doesn't need to be prepared again
+    /// to be drawn with another style.
+    ///
+    /// Returns the first error reported by the surface, if any.
+    pub fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
-        surface.prepare(self.size);
+        surface.prepare(self.size)?;
+        self.draw_content(surface, style, None)
+    }
 
+    /// Redraw only the given region of the figure, in figure units.
+    /// The rest of the surface content is left untouched, and series entirely
+    /// outside of the region are skipped.
+    ///
+    /// If the surface doesn't support partial redraw, the whole figure is drawn.
+    pub fn draw_region<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        region: &geom::Rect,
+    ) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        if !surface.supports_partial() {
+            return self.draw(surface, style);
+        }
+        surface.prepare_region(self.size, Some(region))?;
+        self.draw_content(surface, style, Some(region))
+    }
+
+    fn draw_content<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
         if let Some(fill) = &self.fill {
-            surface.fill(fill.as_paint(style));
+            surface.fill_region(fill.as_paint(style), region)?;
+        }
+
+        let watermark = self.watermark.as_ref();
+        if let Some(wm) = watermark.filter(|wm| wm.layer == des::figure::WatermarkLayer::Behind) {
+            wm.draw(surface, style)?;
         }
 
         if let Some((transform, title)) = &self.title {
-            title.draw(surface, style, Some(transform));
+            title.draw(surface, style, Some(transform))?;
+        }
+        if let Some((transform, subtitle)) = &self.subtitle {
+            subtitle.draw(surface, style, Some(transform))?;
         }
 
         if let Some((pos, legend)) = &self.legend {
-            legend.draw(surface, style, pos);
+            legend.draw(surface, style, pos)?;
+        }
+
+        self.plots.draw(surface, style, region)?;
+
+        if let Some(wm) = watermark.filter(|wm| wm.layer == des::figure::WatermarkLayer::Front) {
+            wm.draw(surface, style)?;
         }
+        Ok(())
+    }
+}
+
+impl Watermark {
+    fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        match &self.content {
+            WatermarkContent::Text(text) => text.draw(surface, style, Some(&self.transform)),
+            WatermarkContent::Image {
+                image,
+                rect,
+                opacity,
+            } => {
+                // the image data is checked when the figure is prepared,
+                // surfaces that can't draw images skip the watermark
+                let res = surface.draw_image(&render::Image {
+                    rgba: &image.rgba,
+                    width: image.width,
+                    height: image.height,
+                    rect: *rect,
+                    opacity: *opacity,
+                    transform: Some(&self.transform),
+                });
+                match res {
+                    Err(render::Error::Unsupported(_)) => Ok(()),
+                    res => res,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::{self, FCol, NamedColumns};
+    use crate::drawing::{Error, LayoutParams, Prepare};
+    use crate::style::defaults;
+    use crate::{ColorU8, Style, des, geom, render};
+
+    /// A surface whose backend fails on the n-th path, counted from 1
+    struct FailingSurface {
+        fail_at: usize,
+        paths: usize,
+    }
+
+    impl render::Surface for FailingSurface {
+        fn prepare(&mut self, _size: geom::Size) -> Result<(), render::Error> {
+            Ok(())
+        }
+        fn fill(&mut self, _fill: render::Paint) -> Result<(), render::Error> {
+            Ok(())
+        }
+        fn draw_path(&mut self, _path: &render::Path) -> Result<(), render::Error> {
+            self.paths += 1;
+            if self.paths == self.fail_at {
+                Err(render::Error::Backend(format!("path {}", self.paths)))
+            } else {
+                Ok(())
+            }
+        }
+        fn push_clip(&mut self, _clip: &render::Clip) -> Result<(), render::Error> {
+            Ok(())
+        }
+        fn pop_clip(&mut self) -> Result<(), render::Error> {
+            Ok(())
+        }
+    }
+
+    /// A surface supporting partial redraw, recording the prepared region
+    /// and the stroke colors of the drawn paths
+    struct RecordingSurface {
+        partial: bool,
+        region: Option<geom::Rect>,
+        strokes: Vec<ColorU8>,
+    }
+
+    impl RecordingSurface {
+        fn new(partial: bool) -> Self {
+            RecordingSurface {
+                partial,
+                region: None,
+                strokes: Vec::new(),
+            }
+        }
+    }
+
+    impl render::Surface for RecordingSurface {
+        fn prepare(&mut self, _size: geom::Size) -> Result<(), render::Error> {
+            self.region = None;
+            Ok(())
+        }
+        fn prepare_region(
+            &mut self,
+            _size: geom::Size,
+            region: Option<&geom::Rect>,
+        ) -> Result<(), render::Error> {
+            self.region = region.copied();
+            Ok(())
+        }
+        fn supports_partial(&self) -> bool {
+            self.partial
+        }
+        fn fill(&mut self, _fill: render::Paint) -> Result<(), render::Error> {
+            Ok(())
+        }
+        fn draw_path(&mut self, path: &render::Path) -> Result<(), render::Error> {
+            if let Some(stroke) = &path.stroke {
+                self.strokes.push(stroke.color);
+            }
+            Ok(())
+        }
+        fn push_clip(&mut self, _clip: &render::Clip) -> Result<(), render::Error> {
+            Ok(())
+        }
+        fn pop_clip(&mut self) -> Result<(), render::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_prepared_figure_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<super::PreparedFigure>();
+    }
+
+    #[test]
+    fn test_fit_size() {
+        let fig = des::Figure::new(des::Plot::new(vec![]).into())
+            .with_size(geom::Size::new(400.0, 300.0));
+        let fig = fig.prepare(&(), None).unwrap();
+        assert_eq!(fig.aspect_ratio(), 4.0 / 3.0);
+
+        let fit = fig.fit_size(geom::Size::new(1000.0, 600.0));
+        assert_eq!(fit, geom::Size::new(800.0, 600.0));
+        let fit = fig.fit_size(geom::Size::new(200.0, 600.0));
+        assert_eq!(fit, geom::Size::new(200.0, 150.0));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_prepare_parallel() {
+        let x: Vec<f64> = (0..50).map(|i| i as f64).collect();
+        let mut src = data::TableSource::new().with_f64_column("x", x.clone());
+        let mut subplots = des::Subplots::new(4, 4);
+        for i in 0..16 {
+            let y = x.iter().map(|x| (x * (i + 1) as f64).sin()).collect();
+            let name = format!("y{i}");
+            src = src.with_f64_column(&name, y);
+            let series = (0..3)
+                .map(|s| {
+                    des::series::Line::new(des::data_src_ref("x"), des::data_src_ref(&name))
+                        .with_name(format!("s{s}"))
+                        .into()
+                })
+                .collect();
+            let plot = des::Plot::new(series).with_legend(Default::default());
+            subplots = subplots.with_plot((i / 4, i % 4), plot);
+        }
+        let fig = des::Figure::new(subplots.into());
+
+        let layout = LayoutParams::default();
+        let serial = fig.prepare_with_layout(&src, None, &layout).unwrap();
+        let parallel = crate::drawing::prepare_parallel(&fig, &src, None, &layout).unwrap();
+        assert_eq!(format!("{serial:?}"), format!("{parallel:?}"));
+    }
+
+    #[test]
+    fn test_update_data_bounds_change() {
+        let x = FCol(&[0.0, 1.0, 2.0, 3.0]);
+        let y = FCol(&[0.0, 1.0, 4.0, 9.0]);
+        let y_in = FCol(&[9.0, 4.0, 1.0, 0.0]);
+        let y_out = FCol(&[0.0, 1.0, 4.0, 16.0]);
+
+        let mut src = NamedColumns::new();
+        src.add_column("x", &x);
+        src.add_column("y", &y);
+
+        let line = des::series::Line::new(des::data_src_ref("x"), des::data_src_ref("y"));
+        let fig = des::Figure::new(des::Plot::new(vec![line.into()]).into());
+        let mut prepared = fig.prepare(&src, None).unwrap();
+
+        src.add_column("y", &y_in);
+        assert!(!prepared.update_data(&src).unwrap());
+
+        src.add_column("y", &y_out);
+        assert!(prepared.update_data(&src).unwrap());
+    }
+
+    #[test]
+    fn test_update_data_length_mismatch() {
+        let x = FCol(&[0.0, 1.0, 2.0, 3.0]);
+        let y = FCol(&[0.0, 1.0, 4.0, 9.0]);
+        let y_short = FCol(&[0.0, 1.0, 4.0]);
+
+        let mut src = NamedColumns::new();
+        src.add_column("x", &x);
+        src.add_column("y", &y);
+
+        let line = des::series::Line::new(des::data_src_ref("x"), des::data_src_ref("y"))
+            .with_name("squares");
+        let fig = des::Figure::new(des::Plot::new(vec![line.into()]).into());
+        let mut prepared = fig.prepare(&src, None).unwrap();
+
+        src.add_column("y", &y_short);
+        let err = prepared.update_series_data(&src).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Inconsistent data: Series 'squares': X and Y data must be the same length (got 4 and 3)"
+        );
+        assert!(matches!(
+            prepared.update_data(&src),
+            Err(Error::InconsistentData(_))
+        ));
+    }
+
+    #[test]
+    fn test_update_data_bar_labels() {
+        let cats = data::VecColumn::from(vec!["a".to_string(), "b".to_string()]);
+        let a = FCol(&[40.0, 60.0]);
+        let b = FCol(&[60.0, 40.0]);
+        let b_same = FCol(&[60.0, 40.0]);
+        let b_changed = FCol(&[50.0, 40.0]);
+
+        let mut src = NamedColumns::new();
+        src.add_column("cats", &cats);
+        src.add_column("a", &a);
+        src.add_column("b", &b);
+
+        let group = des::series::BarsGroup::new(
+            des::data_src_ref("cats"),
+            vec![
+                des::series::BarSeries::new(des::data_src_ref("a")),
+                des::series::BarSeries::new(des::data_src_ref("b")),
+            ],
+        )
+        .with_arrangement(des::series::BarsArrangement::Stack(Default::default()))
+        .with_labels(des::series::BarLabels::new(
+            des::series::BarLabelContent::PercentOfCategory,
+        ));
+        let fig = des::Figure::new(des::Plot::new(vec![group.into()]).into());
+        let mut prepared = fig.prepare(&src, None).unwrap();
+
+        src.add_column("b", &b_same);
+        assert!(!prepared.update_data(&src).unwrap());
+
+        // same bounds, but the labels text changed
+        src.add_column("b", &b_changed);
+        assert!(prepared.update_data(&src).unwrap());
+    }
+
+    #[test]
+    fn test_draw_backend_error() {
+        let line = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        );
+        let plot = des::Plot::new(vec![line.into()])
+            .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+            .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+        let fig = des::Figure::new(plot.into());
+        let prepared = fig.prepare(&(), None).unwrap();
+        let style = Style::default();
+
+        let mut surface = FailingSurface {
+            fail_at: usize::MAX,
+            paths: 0,
+        };
+        prepared.draw(&mut surface, &style).unwrap();
+        let num_paths = surface.paths;
+        assert!(num_paths > 3);
+
+        // drawing stops at the first failing path and returns its error
+        let mut surface = FailingSurface {
+            fail_at: 3,
+            paths: 0,
+        };
+        let res = prepared.draw(&mut surface, &style);
+        assert_eq!(res, Err(render::Error::Backend("path 3".to_string())));
+        assert_eq!(surface.paths, 3);
+
+        let mut surface = FailingSurface {
+            fail_at: num_paths,
+            paths: 0,
+        };
+        let res = prepared.draw(&mut surface, &style);
+        assert!(matches!(res, Err(render::Error::Backend(..))));
+    }
+
+    #[test]
+    fn test_draw_region() {
+        const LEFT: ColorU8 = ColorU8::from_rgb(255, 0, 0);
+        const RIGHT: ColorU8 = ColorU8::from_rgb(0, 0, 255);
+
+        let line = |color: ColorU8| {
+            des::series::Line::new(
+                des::data_inline(vec![0.0, 1.0, 2.0]),
+                des::data_inline(vec![0.0, 1.0, 0.0]),
+            )
+            .with_line(color.into())
+        };
+        let subplots = des::Subplots::new(1, 2)
+            .with_plot((0, 0), des::Plot::new(vec![line(LEFT).into()]))
+            .with_plot((0, 1), des::Plot::new(vec![line(RIGHT).into()]));
+        let fig = des::Figure::new(subplots.into()).with_size(geom::Size::new(400.0, 300.0));
+        let prepared = fig.prepare(&(), None).unwrap();
+        let style = Style::default();
+        let count = |surface: &RecordingSurface, color| {
+            surface.strokes.iter().filter(|c| **c == color).count()
+        };
+
+        let mut full = RecordingSurface::new(true);
+        prepared.draw(&mut full, &style).unwrap();
+        assert_eq!(full.region, None);
+        assert_eq!(count(&full, LEFT), 1);
+        assert_eq!(count(&full, RIGHT), 1);
+
+        // only the series of the left plot intersects the region
+        let region = geom::Rect::from_xywh(0.0, 0.0, 150.0, 300.0);
+        let mut partial = RecordingSurface::new(true);
+        prepared.draw_region(&mut partial, &style, &region).unwrap();
+        assert_eq!(partial.region, Some(region));
+        assert_eq!(count(&partial, LEFT), 1);
+        assert_eq!(count(&partial, RIGHT), 0);
+        assert!(partial.strokes.len() < full.strokes.len());
+
+        // surfaces without partial redraw get the whole figure
+        let mut fallback = RecordingSurface::new(false);
+        prepared
+            .draw_region(&mut fallback, &style, &region)
+            .unwrap();
+        assert_eq!(fallback.region, None);
+        assert_eq!(fallback.strokes, full.strokes);
+    }
+
+    #[test]
+    fn test_nearest_points() {
+        let line = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        );
+        let fig = des::Figure::new(des::Plot::new(vec![line.into()]).into());
+        let prepared = fig.prepare(&(), None).unwrap();
+
+        let rect = prepared.plot_view(des::PlotIdx::new(0, 0)).unwrap().rect();
+        let center = geom::Point {
+            x: rect.center_x(),
+            y: rect.center_y(),
+        };
+        let hits = prepared.nearest_points(center, rect.height());
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].series_idx, 0);
+        assert!((hits[0].x.0.as_num().unwrap() - 1.0).abs() < 1e-3);
+        assert!((hits[0].y.0.as_num().unwrap() - 1.0).abs() < 1e-3);
+
+        assert!(prepared.nearest_points(center, 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_pick() {
+        let below = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        )
+        .with_name("below");
+        let above = des::series::Scatter::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        )
+        .with_name("above");
+        let fig = des::Figure::new(des::Plot::new(vec![below.into(), above.into()]).into());
+        let prepared = fig.prepare(&(), None).unwrap();
+
+        let rect = prepared.plot_view(des::PlotIdx::new(0, 0)).unwrap().rect();
+        let center = geom::Point {
+            x: rect.center_x(),
+            y: rect.center_y(),
+        };
+        // both series have a point at the same distance, the topmost one is picked
+        let pick = prepared.pick(center, rect.height()).unwrap();
+        assert_eq!(pick.series_idx, 1);
+        assert_eq!(pick.series_name.as_deref(), Some("above"));
+        assert_eq!(pick.data_idx, 1);
+        assert!((pick.x.0.as_num().unwrap() - 1.0).abs() < 1e-3);
+        assert!((pick.y.0.as_num().unwrap() - 1.0).abs() < 1e-3);
+        assert!(!pick.x.1.is_empty());
+
+        assert!(prepared.pick(center, 1.0).is_none());
+    }
+
+    #[test]
+    fn test_pick_miss() {
+        let line = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        );
+        let fig = des::Figure::new(des::Plot::new(vec![line.into()]).into());
+        let prepared = fig.prepare(&(), None).unwrap();
+
+        // outside of the plot area
+        let rect = prepared.plot_view(des::PlotIdx::new(0, 0)).unwrap().rect();
+        let outside = geom::Point {
+            x: rect.left() - 1.0,
+            y: rect.top() - 1.0,
+        };
+        assert!(prepared.pick(outside, f32::INFINITY).is_none());
+        assert!(prepared.nearest_points(outside, f32::INFINITY).is_empty());
+
+        // in the plot area, but too far from the data points
+        let point = geom::Point {
+            x: rect.center_x(),
+            y: rect.bottom() - 1.0,
+        };
+        let max_dist = rect.height() / 4.0;
+        assert!(prepared.pick(point, max_dist).is_none());
+        assert!(prepared.nearest_points(point, max_dist).is_empty());
+    }
+
+    #[test]
+    fn test_pick_overlap() {
+        let scatter = |name: &str| {
+            des::series::Scatter::new(
+                des::data_inline(vec![0.0, 1.0, 2.0]),
+                des::data_inline(vec![0.0, 1.0, 0.0]),
+            )
+            .with_name(name)
+        };
+        let plot = des::Plot::new(vec![
+            scatter("raised")
+                .with_zorder(des::ZOrder::ABOVE_SERIES)
+                .into(),
+            scatter("last").into(),
+        ]);
+        let layout = des::FreeformLayout::new()
+            .with_plot(geom::Rect::from_xywh(0.0, 0.0, 1.0, 1.0), plot.clone())
+            .with_plot(geom::Rect::from_xywh(0.5, 0.5, 0.5, 0.5), plot);
+        let fig = des::Figure::new(layout.into());
+        let prepared = fig.prepare(&(), None).unwrap();
+
+        let inset = des::PlotIdx::new(0, 1);
+        let rect = prepared.plot_view(inset).unwrap().rect();
+        let center = geom::Point {
+            x: rect.center_x(),
+            y: rect.center_y(),
+        };
+
+        // both series have a point at the center of both plots:
+        // the topmost plot is searched, and the series drawn above the other is picked
+        let pick = prepared.pick(center, rect.height()).unwrap();
+        assert_eq!(pick.plot_idx, inset);
+        assert_eq!(pick.series_name.as_deref(), Some("raised"));
+        assert_eq!(pick.data_idx, 1);
+
+        let hits = prepared.nearest_points(center, rect.height());
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|h| h.plot_idx == inset));
+        assert_eq!(hits[0].dist, pick.dist);
+        assert_eq!(hits[1].dist, pick.dist);
+    }
+
+    #[test]
+    fn test_pick_hidden() {
+        let line = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        )
+        .with_visible(false);
+        let fig = des::Figure::new(des::Plot::new(vec![line.into()]).into());
+        let mut prepared = fig.prepare(&(), None).unwrap();
+
+        let idx = des::PlotIdx::new(0, 0);
+        let rect = prepared.plot_view(idx).unwrap().rect();
+        let center = geom::Point {
+            x: rect.center_x(),
+            y: rect.center_y(),
+        };
+        assert!(prepared.pick(center, rect.height()).is_none());
+        assert!(prepared.nearest_points(center, rect.height()).is_empty());
+
+        assert!(prepared.set_series_visible(idx, 0, true));
+        assert!(prepared.pick(center, rect.height()).is_some());
+        assert_eq!(prepared.nearest_points(center, rect.height()).len(), 1);
+    }
+
+    #[test]
+    fn test_series_visible() {
+        let below = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        )
+        .with_name("below");
+        let above = des::series::Scatter::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        )
+        .with_name("above")
+        .with_visible(false);
+        let plot = des::Plot::new(vec![below.into(), above.into()])
+            .with_legend(des::plot::LegendPos::InTopLeft.into());
+        let fig = des::Figure::new(plot.into());
+        let mut prepared = fig.prepare(&(), None).unwrap();
+
+        let idx = des::PlotIdx::new(0, 0);
+        assert_eq!(prepared.series_visible(idx, 0), Some(true));
+        assert_eq!(prepared.series_visible(idx, 1), Some(false));
+        assert_eq!(prepared.series_visible(idx, 2), None);
+
+        // the hidden series is not picked
+        let rect = prepared.plot_view(idx).unwrap().rect();
+        let center = geom::Point {
+            x: rect.center_x(),
+            y: rect.center_y(),
+        };
+        assert_eq!(prepared.pick(center, rect.height()).unwrap().series_idx, 0);
+
+        assert!(prepared.set_series_visible(idx, 1, true));
+        assert_eq!(prepared.pick(center, rect.height()).unwrap().series_idx, 1);
+        assert!(!prepared.set_series_visible(idx, 2, true));
+    }
+
+    #[test]
+    fn test_toggle_legend_entry() {
+        let line = |name: &str| {
+            des::series::Line::new(
+                des::data_inline(vec![0.0, 1.0, 2.0]),
+                des::data_inline(vec![0.0, 1.0, 0.0]),
+            )
+            .with_name(name)
+            .into()
+        };
+        let subplots = des::Subplots::new(1, 2)
+            .with_plot((0, 0), des::Plot::new(vec![line("a"), line("b")]))
+            .with_plot((0, 1), des::Plot::new(vec![line("a")]));
+        let fig = des::Figure::new(subplots.into()).with_legend(des::figure::LegendPos::Top.into());
+        let mut prepared = fig.prepare(&(), None).unwrap();
+
+        // the first entry of the figure legend is linked to the series "a" of both plots
+        let (top_left, _) = prepared.legend.as_ref().unwrap();
+        let point = geom::Point {
+            x: top_left.x + defaults::LEGEND_PADDING + 2.0,
+            y: top_left.y + defaults::LEGEND_PADDING + 2.0,
+        };
+        let a0 = (des::PlotIdx::new(0, 0), 0);
+        let a1 = (des::PlotIdx::new(0, 1), 0);
+        assert_eq!(prepared.legend_series_at(point), Some(&[a0, a1][..]));
+
+        assert!(prepared.toggle_legend_entry_at(point));
+        assert_eq!(prepared.series_visible(a0.0, a0.1), Some(false));
+        assert_eq!(prepared.series_visible(a1.0, a1.1), Some(false));
+        assert_eq!(prepared.series_visible((0, 0), 1), Some(true));
+
+        // with some series visible, the entry hides them all
+        prepared.set_series_visible(a1.0, a1.1, true);
+        assert!(prepared.toggle_legend_entry_at(point));
+        assert_eq!(prepared.series_visible(a1.0, a1.1), Some(false));
+
+        assert!(prepared.toggle_legend_entry_at(point));
+        assert_eq!(prepared.series_visible(a0.0, a0.1), Some(true));
+        assert_eq!(prepared.series_visible(a1.0, a1.1), Some(true));
+
+        assert!(!prepared.toggle_legend_entry_at(geom::Point { x: -1.0, y: -1.0 }));
+    }
+
+    #[test]
+    fn test_freeform_hit_test() {
+        let plot = |name: &str| {
+            let line = des::series::Line::new(
+                des::data_inline(vec![0.0, 1.0, 2.0]),
+                des::data_inline(vec![0.0, 1.0, 0.0]),
+            )
+            .with_name(name);
+            des::Plot::new(vec![line.into()]).with_legend(des::plot::LegendPos::InTopLeft.into())
+        };
+        let layout = des::FreeformLayout::new()
+            .with_plot(geom::Rect::from_xywh(0.0, 0.0, 1.0, 1.0), plot("main"))
+            .with_plot(geom::Rect::from_xywh(0.5, 0.5, 0.5, 0.5), plot("inset"));
+        let fig = des::Figure::new(layout.into());
+        let mut prepared = fig.prepare(&(), None).unwrap();
+
+        let main = des::PlotIdx::new(0, 0);
+        let inset = des::PlotIdx::new(0, 1);
+        assert_eq!(prepared.plot_indices().collect::<Vec<_>>(), [main, inset]);
+
+        // the inset is drawn above the main plot
+        let inset_rect = prepared.plot_view(inset).unwrap().rect();
+        let center = geom::Point {
+            x: inset_rect.center_x(),
+            y: inset_rect.center_y(),
+        };
+        assert_eq!(prepared.hit_test_idx(center), Some(inset));
+        let main_rect = prepared.plot_view(main).unwrap().rect();
+        let corner = geom::Point {
+            x: main_rect.left() + 1.0,
+            y: main_rect.bottom() - 1.0,
+        };
+        assert_eq!(prepared.hit_test_idx(corner), Some(main));
+
+        // the legend of the inset is linked to its own series
+        let (top_left, _) = prepared.plots.plot(inset).unwrap().legend().unwrap();
+        let point = geom::Point {
+            x: top_left.x + defaults::LEGEND_PADDING + 2.0,
+            y: top_left.y + defaults::LEGEND_PADDING + 2.0,
+        };
+        assert!(prepared.toggle_legend_entry_at(point));
+        assert_eq!(prepared.series_visible(inset, 0), Some(false));
+        assert_eq!(prepared.series_visible(main, 0), Some(true));
+    }
+
+    #[test]
+    fn test_series_geometry() {
+        let x = FCol(&[0.0, 1.0, 2.0]);
+        let y = FCol(&[0.0, 1.0, 0.0]);
+        let y_flat = FCol(&[0.5, 0.5, 0.5]);
+
+        let mut src = NamedColumns::new();
+        src.add_column("x", &x);
+        src.add_column("y", &y);
+
+        let line = des::series::Line::new(des::data_src_ref("x"), des::data_src_ref("y"));
+        let bars = des::series::Bars::new(
+            des::data_inline(vec!["a".to_string(), "b".to_string()]),
+            des::data_inline(vec![1.0, 2.0]),
+        );
+        let subplots = des::Subplots::new(1, 2)
+            .with_plot((0, 0), des::Plot::new(vec![line.into()]))
+            .with_plot((0, 1), des::Plot::new(vec![bars.into()]));
+        let fig = des::Figure::new(subplots.into());
+        let mut prepared = fig.prepare(&src, None).unwrap();
+
+        let rect = prepared.plot_view(des::PlotIdx::new(0, 0)).unwrap().rect();
+        let geom = prepared.series_geometry((0, 0), 0).unwrap();
+        assert_eq!(geom.paths.len(), 1);
+        let points = geom.points.unwrap();
+        assert_eq!(points.len(), 3);
+        assert!(points.iter().all(|p| rect.contains_point(p)));
+        assert!(points[1].y < points[0].y);
+        let bounds = geom.paths[0].bounds();
+        assert!((bounds.left() - points[0].x).abs() < 1e-3);
+        assert!((bounds.right() - points[2].x).abs() < 1e-3);
+
+        // the geometry follows the data updates
+        src.add_column("y", &y_flat);
+        assert!(!prepared.update_data(&src).unwrap());
+        let points = prepared.series_geometry((0, 0), 0).unwrap().points.unwrap();
+        assert!((points[0].y - points[1].y).abs() < 1e-3);
+
+        let geom = prepared.series_geometry((0, 1), 0).unwrap();
+        assert_eq!(geom.paths.len(), 1);
+        assert!(geom.points.is_none());
+
+        assert!(prepared.series_geometry((0, 0), 1).is_none());
+    }
+
+    #[test]
+    fn test_data_at_pixel() {
+        let line = des::series::Line::new(
+            des::data_inline(vec![1.0, 10.0, 100.0]),
+            des::data_inline(vec![1.0, 10.0, 100.0]),
+        );
+        let plot = des::Plot::new(vec![line.into()])
+            .with_y_axis(des::Axis::new().with_scale(des::axis::Scale::Log(Default::default())));
+        let fig = des::Figure::new(plot.into());
+        let prepared = fig.prepare(&(), None).unwrap();
+
+        let rect = prepared.plot_view(des::PlotIdx::new(0, 0)).unwrap().rect();
+        // data points are mapped back to their values, through the linear x axis and log y axis
+        let center = geom::Point {
+            x: rect.center_x(),
+            y: rect.center_y(),
+        };
+        let hits = prepared.nearest_points(center, rect.width());
+        let (x, y) = prepared.data_at_pixel(hits[0].pos).unwrap();
+        assert!((x.as_num().unwrap() - 10.0).abs() < 1e-3);
+        assert!((y.as_num().unwrap() - 10.0).abs() < 1e-3);
+
+        assert!(
+            prepared
+                .data_at_pixel(geom::Point { x: -1.0, y: -1.0 })
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_data_at_pixel_categories() {
+        let bars = des::series::Bars::new(
+            des::data_inline(vec!["a".to_string(), "b".to_string(), "c".to_string()]),
+            des::data_inline(vec![1.0, 2.0, 3.0]),
+        );
+        let fig = des::Figure::new(des::Plot::new(vec![bars.into()]).into());
+        let prepared = fig.prepare(&(), None).unwrap();
+
+        let rect = prepared.plot_view(des::PlotIdx::new(0, 0)).unwrap().rect();
+        let y = rect.center_y();
+        let at = |x: f32| {
+            let (x, _) = prepared.data_at_pixel(geom::Point { x, y }).unwrap();
+            x.as_cat().map(str::to_string)
+        };
+        // the insets at both ends snap to the first and last categories
+        assert_eq!(at(rect.x() + 0.5).as_deref(), Some("a"));
+        assert_eq!(at(rect.center_x()).as_deref(), Some("b"));
+        assert_eq!(at(rect.right() - 0.5).as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_figure_too_small() {
+        let line = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        );
+        let plot = des::Plot::new(vec![line.into()])
+            .with_x_axis(des::Axis::new().with_title("x".into()))
+            .with_y_axis(des::Axis::new().with_title("y".into()));
+        let fig = des::Figure::new(plot.into())
+            .with_title("Title".into())
+            .with_size(geom::Size::new(10.0, 10.0));
+        assert!(matches!(
+            fig.prepare(&(), None),
+            Err(crate::drawing::Error::FigureTooSmall)
+        ));
+    }
+
+    #[test]
+    fn test_layout_params() {
+        let line = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![0.0, 1.0, 0.0]),
+        );
+        let plot = des::Plot::new(vec![line.into()])
+            .with_x_axis(
+                des::Axis::new()
+                    .with_title("x".into())
+                    .with_ticks(Default::default()),
+            )
+            .with_y_axis(
+                des::Axis::new()
+                    .with_title("y".into())
+                    .with_ticks(Default::default()),
+            );
+        let fig = des::Figure::new(plot.into());
+        let idx = des::PlotIdx::new(0, 0);
+
+        let default = fig.prepare(&(), None).unwrap();
+        let default_rect = default.plot_view(idx).unwrap().rect();
+
+        let layout = LayoutParams {
+            axis_title_margin: 20.0,
+            tick_size: 10.0,
+            ..Default::default()
+        };
+        let custom = fig.prepare_with_layout(&(), None, &layout).unwrap();
+        let custom_rect = custom.plot_view(idx).unwrap().rect();
+
+        // both axes grow by (20 - 8) + (10 - 4) = 18
+        assert!((default_rect.height() - custom_rect.height() - 18.0).abs() < 1e-3);
+        assert!((default_rect.width() - custom_rect.width() - 18.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_non_finite_data_skipped() {
+        let line = des::series::Line::new(
+            des::data_inline(vec![0.0, 1.0, 2.0, 3.0, 4.0]),
+            des::data_inline(vec![0.0, f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 4.0]),
+        );
+        let fig = des::Figure::new(des::Plot::new(vec![line.into()]).into());
+        let prepared = fig.prepare(&(), None).unwrap();
 
-        self.plots.draw(surface, style);
+        let plot = prepared.plots.plots()[0].as_ref().unwrap();
+        let points = plot.series()[0].points().unwrap();
+        assert_eq!(points.len(), 2);
+        assert!(points.iter().all(|p| p.x.is_finite() && p.y.is_finite()));
     }
 }
@@ -0,0 +1,130 @@
+//! Least-squares fitting helpers
+
+use crate::des;
+
+/// Fit a polynomial of the given degree to the data points with least squares.
+/// Returns the coefficients in increasing degree order,
+/// or `None` if the system is degenerate (e.g. too few distinct x values).
+pub fn polyfit(xs: &[f64], ys: &[f64], degree: usize) -> Option<Vec<f64>> {
+    debug_assert_eq!(xs.len(), ys.len());
+    let n = degree + 1;
+    if xs.len() < n {
+        return None;
+    }
+
+    // normal equations: (Xt.X).c = Xt.y, stored as an augmented matrix
+    let mut powers = vec![0.0; 2 * n - 1];
+    let mut rhs = vec![0.0; n];
+    for (&x, &y) in xs.iter().zip(ys.iter()) {
+        let mut p = 1.0;
+        for (k, pow) in powers.iter_mut().enumerate() {
+            *pow += p;
+            if k < n {
+                rhs[k] += p * y;
+            }
+            p *= x;
+        }
+    }
+    let mut mat: Vec<Vec<f64>> = (0..n)
+        .map(|r| {
+            let mut row = powers[r..r + n].to_vec();
+            row.push(rhs[r]);
+            row
+        })
+        .collect();
+
+    // Gaussian elimination with partial pivoting
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&a, &b| mat[a][col].abs().total_cmp(&mat[b][col].abs()))?;
+        if mat[pivot][col].abs() < 1e-12 * mat[pivot].iter().map(|v| v.abs()).fold(1.0, f64::max) {
+            return None;
+        }
+        mat.swap(col, pivot);
+        let (top, bottom) = mat.split_at_mut(col + 1);
+        let pivot_row = &top[col];
+        for row in bottom.iter_mut() {
+            let f = row[col] / pivot_row[col];
+            for (v, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *v -= f * p;
+            }
+        }
+    }
+
+    let mut coefs = vec![0.0; n];
+    for r in (0..n).rev() {
+        let sum: f64 = (r + 1..n).map(|c| mat[r][c] * coefs[c]).sum();
+        coefs[r] = (mat[r][n] - sum) / mat[r][r];
+    }
+    coefs.iter().all(|c| c.is_finite()).then_some(coefs)
+}
+
+/// Evaluate a polynomial given its coefficients in increasing degree order
+pub fn polyval(coefs: &[f64], x: f64) -> f64 {
+    coefs.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+/// A fitted model, ready to be evaluated
+#[derive(Debug, Clone)]
+pub struct Model {
+    fit: des::series::Fit,
+    coefs: Vec<f64>,
+}
+
+impl Model {
+    /// Fit the model to the data points.
+    /// Returns `None` if the fit is degenerate.
+    pub fn fit(fit: des::series::Fit, xs: &[f64], ys: &[f64]) -> Option<Self> {
+        let coefs = match fit {
+            des::series::Fit::Linear => polyfit(xs, ys, 1)?,
+            des::series::Fit::Polynomial(degree) => polyfit(xs, ys, degree as usize)?,
+            des::series::Fit::LogLinear => {
+                let (xs, ys): (Vec<f64>, Vec<f64>) = xs
+                    .iter()
+                    .zip(ys.iter())
+                    .filter(|(_, y)| **y > 0.0)
+                    .map(|(x, y)| (*x, y.ln()))
+                    .unzip();
+                polyfit(&xs, &ys, 1)?
+            }
+        };
+        Some(Model { fit, coefs })
+    }
+
+    /// Evaluate the model at `x`
+    pub fn eval(&self, x: f64) -> f64 {
+        match self.fit {
+            des::series::Fit::LogLinear => polyval(&self.coefs, x).exp(),
+            _ => polyval(&self.coefs, x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_polyfit() {
+        let xs = [0.0, 1.0, 2.0, 3.0, 4.0];
+        let ys: Vec<f64> = xs.iter().map(|x| 1.0 - 2.0 * x + 0.5 * x * x).collect();
+
+        let coefs = polyfit(&xs, &ys, 2).unwrap();
+        assert!((coefs[0] - 1.0).abs() < 1e-9);
+        assert!((coefs[1] + 2.0).abs() < 1e-9);
+        assert!((coefs[2] - 0.5).abs() < 1e-9);
+        assert!((polyval(&coefs, 5.0) - 3.5).abs() < 1e-9);
+
+        // not enough points
+        assert!(polyfit(&xs[..2], &ys[..2], 2).is_none());
+        // all x equal
+        assert!(polyfit(&[1.0, 1.0, 1.0], &[0.0, 1.0, 2.0], 1).is_none());
+    }
+
+    #[test]
+    fn test_model_log_linear() {
+        let xs = [0.0, 1.0, 2.0, 3.0];
+        let ys: Vec<f64> = xs.iter().map(|x: &f64| 2.0 * (0.5 * x).exp()).collect();
+        let model = Model::fit(des::series::Fit::LogLinear, &xs, &ys).unwrap();
+        assert!((model.eval(4.0) - 2.0 * 2.0f64.exp()).abs() < 1e-9);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::des::PlotIdx;
+use crate::drawing::plot::{Orientation, Plot};
 use crate::{data, geom};
 
 #[derive(Debug, Clone)]
@@ -85,39 +86,232 @@ pub struct PlotHit {
     pub y_coords: PlotCoords,
 }
 
+/// Data point of a series nearest to a hit test point.
+/// See [`PreparedFigure::nearest_points`](super::PreparedFigure::nearest_points).
+#[derive(Debug, Clone)]
+pub struct SeriesHit {
+    /// Index of the plot containing the series
+    pub plot_idx: PlotIdx,
+    /// Index of the series in the plot
+    pub series_idx: usize,
+    /// Position of the data point in figure coordinates
+    pub pos: geom::Point,
+    /// Distance from the hit test point to the data point, in figure units
+    pub dist: f32,
+    /// X coordinate of the data point, with its formatted text
+    pub x: (data::Sample, String),
+    /// Y coordinate of the data point, with its formatted text
+    pub y: (data::Sample, String),
+}
+
+/// Data point nearest to a picked point of a figure.
+/// See [`PreparedFigure::pick`](super::PreparedFigure::pick).
+///
+/// It holds the values of the data point and their text formatted by the axes,
+/// so that a tooltip can be rendered without querying the data source.
+#[derive(Debug, Clone)]
+pub struct Pick {
+    /// Index of the plot containing the series
+    pub plot_idx: PlotIdx,
+    /// Index of the series in the plot
+    pub series_idx: usize,
+    /// Name of the series, if any
+    pub series_name: Option<String>,
+    /// Index of the data point in the data columns of the series
+    pub data_idx: usize,
+    /// Position of the data point in figure coordinates
+    pub pos: geom::Point,
+    /// Distance from the picked point to the data point, in figure units
+    pub dist: f32,
+    /// X value of the data point, with its formatted text
+    pub x: (data::Sample, String),
+    /// Y value of the data point, with its formatted text
+    pub y: (data::Sample, String),
+}
+
 impl super::PreparedFigure {
+    /// The plots under `point`, topmost first.
+    /// Plots only overlap in freeform layouts, where they are drawn in order.
+    fn plots_at(&self, point: geom::Point) -> impl Iterator<Item = &Plot> {
+        self.plots
+            .plots()
+            .iter()
+            .rev()
+            .filter_map(Option::as_ref)
+            .filter(move |p| p.rect().contains_point(&point))
+    }
+
+    fn plot_at(&self, point: geom::Point) -> Option<&Plot> {
+        self.plots_at(point).next()
+    }
+
+    /// Pick the data point nearest to `point`, in figure coordinates,
+    /// among the series of the plot under `point`.
+    /// Only data points within `max_dist` of `point` (in figure units) are considered,
+    /// and only series made of individual data points (lines and scatters).
+    /// If several data points are at the same distance, the one of the topmost series is picked.
+    /// Hidden series are ignored.
+    pub fn pick(&self, point: geom::Point, max_dist: f32) -> Option<Pick> {
+        let p = self.plot_at(point)?;
+        let axes = p.axes()?;
+
+        let series = p.series();
+        // series are drawn by z-order, then in order of definition
+        let above = |a: usize, b: usize| (series[a].zorder(), a) > (series[b].zorder(), b);
+        let (series_idx, pt_idx, dist) =
+            series_nearest_points(p, point, max_dist).reduce(|nearest, candidate| {
+                let better = candidate.2 < nearest.2
+                    || (candidate.2 == nearest.2 && above(candidate.0, nearest.0));
+                if better { candidate } else { nearest }
+            })?;
+
+        let series = &series[series_idx];
+        let sample = series.point_samples()?.get(pt_idx)?;
+        let (x_ref, y_ref) = series.axes();
+        let x_axis = axes.or_find(Orientation::X, x_ref).ok()??;
+        let y_axis = axes.or_find(Orientation::Y, y_ref).ok()??;
+        Some(Pick {
+            plot_idx: p.idx(),
+            series_idx,
+            series_name: series.name().map(str::to_string),
+            data_idx: sample.index,
+            pos: series.points()?[pt_idx],
+            dist,
+            x: (sample.x.clone(), x_axis.format_sample(sample.x.as_ref())),
+            y: (sample.y.clone(), y_axis.format_sample(sample.y.as_ref())),
+        })
+    }
+
+    /// Find the nearest data point of each series of the plot under `point`.
+    /// Only data points within `max_dist` of `point` (in figure units) are returned,
+    /// and only series made of individual data points (lines and scatters) are considered.
+    /// The coordinates of the data points are mapped back through the axes of each series.
+    /// Hidden series are ignored.
+    pub fn nearest_points(&self, point: geom::Point, max_dist: f32) -> Vec<SeriesHit> {
+        let mut hits = Vec::new();
+        let Some(p) = self.plot_at(point) else {
+            return hits;
+        };
+        let Some(axes) = p.axes() else {
+            return hits;
+        };
+        let rect = p.rect();
+
+        for (series_idx, pt_idx, dist) in series_nearest_points(p, point, max_dist) {
+            let series = &p.series()[series_idx];
+            let Some(&pos) = series.points().and_then(|points| points.get(pt_idx)) else {
+                continue;
+            };
+
+            let (x_ref, y_ref) = series.axes();
+            let (Ok(Some(x_axis)), Ok(Some(y_axis))) = (
+                axes.or_find(Orientation::X, x_ref),
+                axes.or_find(Orientation::Y, y_ref),
+            ) else {
+                continue;
+            };
+            let x_cm = x_axis.coord_map();
+            let y_cm = y_axis.coord_map();
+            let x = x_cm.unmap_coord(pos.x - rect.x());
+            let y = y_cm.unmap_coord(rect.bottom() - pos.y);
+            hits.push(SeriesHit {
+                plot_idx: p.idx(),
+                series_idx,
+                pos,
+                dist,
+                x: (x.into(), x_axis.format_sample(x)),
+                y: (y.into(), y_axis.format_sample(y)),
+            });
+        }
+        hits
+    }
+
     /// Perform a hit test on the figure for the given point in figure coordinates.
     pub fn hit_test(&self, point: geom::Point) -> Option<PlotHit> {
-        for p in self.plots.plots().iter().filter_map(Option::as_ref) {
+        for p in self.plots_at(point) {
             let rect = p.rect();
-            if rect.contains_point(&point) {
-                let point = geom::Point {
-                    x: point.x - rect.x(),
-                    y: rect.bottom() - point.y,
-                };
-                if let Some(axes) = p.axes() {
-                    let x_coords = axes_coords(&axes.x(), point.x);
-                    let y_coords = axes_coords(&axes.y(), point.y);
-                    return Some(PlotHit {
-                        idx: p.idx(),
-                        x_coords,
-                        y_coords,
-                    });
-                }
+            let point = geom::Point {
+                x: point.x - rect.x(),
+                y: rect.bottom() - point.y,
+            };
+            if let Some(axes) = p.axes() {
+                let x_coords = axes_coords(&axes.x(), point.x);
+                let y_coords = axes_coords(&axes.y(), point.y);
+                return Some(PlotHit {
+                    idx: p.idx(),
+                    x_coords,
+                    y_coords,
+                });
             }
         }
         None
     }
 
-    /// Perform a hit test on the figure for the given point in figure coordinates.
-    /// Only checks if a plot is hit, and returns its index.
-    pub fn hit_test_idx(&self, point: geom::Point) -> Option<PlotIdx> {
+    /// Map a point in figure coordinates to data coordinates,
+    /// using the main X and Y axes of the plot under the point.
+    /// Figure coordinates are pixels when the figure is rendered at scale 1.
+    ///
+    /// Numerical axes (linear, logarithmic and time axes) return numbers.
+    /// Categorical axes snap to the nearest category, including in the insets of the axis.
+    /// Returns `None` if the point is not in a plot, or if the plot has no axes.
+    pub fn data_at_pixel(&self, point: geom::Point) -> Option<(data::Sample, data::Sample)> {
+        let p = self.plot_at(point)?;
+        let axes = p.axes()?;
+        let rect = p.rect();
+        let x_cm = axes.x().first()?.coord_map();
+        let y_cm = axes.y().first()?.coord_map();
+        let x = x_cm.snap_coord(point.x - rect.x());
+        let y = y_cm.snap_coord(rect.bottom() - point.y);
+        Some((x.into(), y.into()))
+    }
+
+    /// Get the series linked to the legend entry under `point`, in figure coordinates.
+    /// Both the plot legends and the figure legend are tested.
+    /// Each series is given by the index of its plot and its index in the plot.
+    /// A figure legend entry is linked to all the series sharing its label,
+    /// and the entries of a group of bars or areas are linked to the whole group.
+    /// Returns `None` if there is no entry linked to series under `point`.
+    pub fn legend_series_at(&self, point: geom::Point) -> Option<&[(PlotIdx, usize)]> {
         self.plots
             .plots()
             .iter()
+            .rev()
             .filter_map(Option::as_ref)
-            .find_map(|p| p.rect().contains_point(&point).then_some(p.idx()))
+            .filter_map(Plot::legend)
+            .chain(self.legend.as_ref())
+            .find_map(|(top_left, legend)| legend.entry_series_at(top_left, &point))
     }
+
+    /// Perform a hit test on the figure for the given point in figure coordinates.
+    /// Only checks if a plot is hit, and returns its index.
+    pub fn hit_test_idx(&self, point: geom::Point) -> Option<PlotIdx> {
+        self.plot_at(point).map(Plot::idx)
+    }
+}
+
+/// The data point nearest to `point` of each visible series of `plot`, within `max_dist` of `point`,
+/// as the series index, the index of the point in the series points and the distance.
+/// Only series made of individual data points (lines and scatters) are considered.
+/// If several points of a series are at the same distance, the first one is returned.
+fn series_nearest_points(
+    plot: &Plot,
+    point: geom::Point,
+    max_dist: f32,
+) -> impl Iterator<Item = (usize, usize, f32)> + '_ {
+    plot.series()
+        .iter()
+        .enumerate()
+        .filter(|(_, series)| series.visible())
+        .filter_map(move |(series_idx, series)| {
+            let (pt_idx, dist) = series
+                .points()?
+                .iter()
+                .map(|pt| pt.distance(point))
+                .enumerate()
+                .filter(|(_, dist)| *dist <= max_dist)
+                .min_by(|a, b| a.1.total_cmp(&b.1))?;
+            Some((series_idx, pt_idx, dist))
+        })
 }
 
 fn axes_coords(axes: &[super::axis::Axis], pos: f32) -> PlotCoords {
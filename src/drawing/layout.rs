@@ -0,0 +1,63 @@
+//! Layout parameters of the drawing
+
+use crate::geom;
+
+/// Spacing parameters used to lay out the figure elements.
+///
+/// All values are in figure units.
+/// The default values are suitable for most figures, but can be tweaked
+/// for dense or sparse figures. See [`Prepare::prepare_with_layout`](super::Prepare::prepare_with_layout).
+#[derive(Debug, Clone, Copy)]
+pub struct LayoutParams {
+    /// Space between the figure title and the rest of the figure
+    pub fig_title_margin: f32,
+    /// Space between the figure title and subtitle
+    pub fig_subtitle_margin: f32,
+    /// Space between the title of a plot and the plot with its axes
+    pub plot_title_margin: f32,
+    /// Space between a panel label and the edges of the plot area, or the plot title
+    pub panel_label_margin: f32,
+    /// Default padding around each plot and its axes, within the plot cell.
+    /// Can be overridden per plot with [`des::Plot::with_padding`](crate::des::Plot::with_padding)
+    pub plot_padding: geom::Padding,
+    /// Space between two axes on the same side of a plot
+    pub axis_margin: f32,
+    /// Space between the tick labels and the axis title
+    pub axis_title_margin: f32,
+    /// Space between the tick labels and the axis annotation (e.g. a multiplication factor)
+    pub axis_annot_margin: f32,
+    /// Width accounted for the spine of an axis, when several axes are on the same side
+    pub axis_spine_width: f32,
+    /// Length of the major ticks marks
+    pub tick_size: f32,
+    /// Space between the ticks marks and the ticks labels
+    pub tick_label_margin: f32,
+    /// Length of the minor ticks marks
+    pub minor_tick_size: f32,
+}
+
+impl Default for LayoutParams {
+    fn default() -> Self {
+        LayoutParams {
+            fig_title_margin: 12.0,
+            fig_subtitle_margin: 4.0,
+            plot_title_margin: 8.0,
+            panel_label_margin: 6.0,
+            plot_padding: geom::Padding::Even(0.0),
+            axis_margin: 10.0,
+            axis_title_margin: 8.0,
+            axis_annot_margin: 4.0,
+            axis_spine_width: 1.0,
+            tick_size: 4.0,
+            tick_label_margin: 4.0,
+            minor_tick_size: 2.0,
+        }
+    }
+}
+
+impl LayoutParams {
+    /// Space taken between two consecutive axes on the same side of a plot
+    pub(super) fn axes_spacing(&self) -> f32 {
+        self.axis_margin + self.axis_spine_width
+    }
+}
@@ -9,13 +9,26 @@ pub enum Shape {
     Line(style::series::Stroke),
     Marker(style::series::Marker),
     Rect(style::series::Fill, Option<style::series::Stroke>),
+    /// A rectangle filled with two fills, on its left and right halves
+    SplitRect(
+        style::series::Fill,
+        style::series::Fill,
+        Option<style::series::Stroke>,
+    ),
 }
 
 #[derive(Debug, Clone, Copy)]
 pub enum ShapeRef<'a> {
     Line(&'a style::series::Stroke),
     Marker(&'a style::series::Marker),
-    Rect(&'a style::series::Fill, Option<&'a style::series::Stroke>),
+    /// A marker whose fill and edge opacity are multiplied by the given alpha
+    FadedMarker(&'a style::series::Marker, f32),
+    Rect(style::series::Fill, Option<&'a style::series::Stroke>),
+    SplitRect(
+        style::series::Fill,
+        style::series::Fill,
+        Option<&'a style::series::Stroke>,
+    ),
 }
 
 impl ShapeRef<'_> {
@@ -23,7 +36,27 @@ impl ShapeRef<'_> {
         match self {
             &ShapeRef::Line(line) => Shape::Line(line.clone()),
             &ShapeRef::Marker(marker) => Shape::Marker(marker.clone()),
-            &ShapeRef::Rect(fill, line) => Shape::Rect(fill.clone(), line.cloned()),
+            &ShapeRef::FadedMarker(marker, alpha) => Shape::Marker(marker.clone().faded(alpha)),
+            &ShapeRef::Rect(fill, line) => Shape::Rect(fill, line.cloned()),
+            &ShapeRef::SplitRect(left, right, line) => Shape::SplitRect(left, right, line.cloned()),
+        }
+    }
+}
+
+impl Shape {
+    /// Multiply the opacity of the shape by `alpha`
+    fn faded(&self, alpha: f32) -> Shape {
+        match self {
+            Shape::Line(line) => Shape::Line(line.clone().faded(alpha)),
+            Shape::Marker(marker) => Shape::Marker(marker.clone().faded(alpha)),
+            Shape::Rect(fill, line) => {
+                Shape::Rect(fill.faded(alpha), line.clone().map(|l| l.faded(alpha)))
+            }
+            Shape::SplitRect(left, right, line) => Shape::SplitRect(
+                left.faded(alpha),
+                right.faded(alpha),
+                line.clone().map(|l| l.faded(alpha)),
+            ),
         }
     }
 }
@@ -44,6 +77,10 @@ struct LegendEntry {
     text: Text,
     x: f32,
     y: f32,
+    /// The series toggled by this entry, as (plot index, series index in the plot)
+    series: Vec<(des::PlotIdx, usize)>,
+    /// Whether all the series of this entry are hidden
+    dimmed: bool,
 }
 
 impl LegendEntry {
@@ -61,9 +98,13 @@ pub struct LegendBuilder<'a> {
     font: des::legend::EntryFont,
     fill: Option<theme::Fill>,
     border: Option<theme::Stroke>,
+    radius: f32,
+    shadow: Option<theme::Shadow>,
     columns: Option<u32>,
+    max_rows: Option<u32>,
     spacing: Size,
     padding: Padding,
+    label_overflow: text::rich::Overflow,
 
     avail_width: f32,
     fontdb: &'a fontdb::Database,
@@ -74,6 +115,8 @@ pub struct LegendBuilder<'a> {
 pub struct Legend {
     fill: Option<theme::Fill>,
     border: Option<theme::Stroke>,
+    radius: f32,
+    shadow: Option<theme::Shadow>,
     entries: Vec<LegendEntry>,
 
     size: geom::Size,
@@ -87,16 +130,21 @@ impl<'a> LegendBuilder<'a> {
         fontdb: &'a fontdb::Database,
     ) -> LegendBuilder<'a> {
         let mut columns = legend.columns();
-        if columns.is_none() && prefers_vertical {
+        let max_rows = legend.max_rows();
+        if columns.is_none() && max_rows.is_none() && prefers_vertical {
             columns.replace(1);
         }
         LegendBuilder {
             font: legend.font().clone(),
             fill: legend.fill().cloned(),
             border: legend.border().cloned(),
+            radius: legend.radius(),
+            shadow: legend.shadow().cloned(),
             columns,
+            max_rows,
             spacing: legend.spacing(),
             padding: legend.padding(),
+            label_overflow: legend.label_overflow(),
 
             avail_width: avail_width,
             fontdb,
@@ -118,50 +166,143 @@ impl<'a> LegendBuilder<'a> {
             font.font.clone(),
             &self.fontdb,
         )?;
-        let text = Text::from_line_text(&text, &self.fontdb, font.color)?;
+        let max_width = self.max_label_width();
+        let text = if text.width() <= max_width {
+            Text::from_line_text(&text, &self.fontdb, font.color)?
+        } else {
+            self.overflowing_label(entry.label, font, max_width)?
+        };
         self.entries.push(LegendEntry {
             index,
             shape,
             text,
             x: f32::NAN,
             y: f32::NAN,
+            series: Vec::new(),
+            dimmed: false,
         });
         Ok(())
     }
 
+    /// Number of entries added so far
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Link the entry at position `entry` to a series of the figure.
+    /// The entry is dimmed if all its linked series are hidden.
+    pub fn link_series(&mut self, entry: usize, series: (des::PlotIdx, usize), visible: bool) {
+        let e = &mut self.entries[entry];
+        e.dimmed = !visible && (e.series.is_empty() || e.dimmed);
+        e.series.push(series);
+    }
+
+    /// Width available to a label when entries are laid out in a single column
+    fn max_label_width(&self) -> f32 {
+        self.avail_width
+            - self.padding.sum_hor()
+            - defaults::LEGEND_SHAPE_SIZE.width()
+            - defaults::LEGEND_SHAPE_SPACING
+    }
+
+    /// Build a label wrapped or truncated to `max_width`
+    fn overflowing_label(
+        &self,
+        label: &str,
+        font: &des::legend::EntryFont,
+        max_width: f32,
+    ) -> Result<Text, drawing::Error> {
+        match self.label_overflow {
+            text::rich::Overflow::Wrap => {
+                let props = text::rich::TextProps::new(font.size)
+                    .with_font(font.font.clone())
+                    .with_fill(Some(font.color));
+                let layout = text::rich::Layout::Horizontal(
+                    text::rich::Align::Start,
+                    text::rich::VerAlign::Center,
+                    Default::default(),
+                );
+                let text = text::RichTextBuilder::new(label.to_string(), props)
+                    .with_layout(layout)
+                    .with_max_width(max_width, text::rich::Overflow::Wrap)
+                    .done(self.fontdb)?;
+                Text::from_rich_text(&text, self.fontdb)
+            }
+            text::rich::Overflow::Ellipsis => {
+                let align = (text::line::Align::Start, text::line::VerAlign::Middle);
+                let text = LineText::new(
+                    label.to_string(),
+                    align,
+                    font.size,
+                    font.font.clone(),
+                    self.fontdb,
+                )?
+                .ellipsize(max_width, self.fontdb)?;
+                Text::from_line_text(&text, self.fontdb, font.color)
+            }
+        }
+    }
+
+    /// Add the manual entries of `legend` if they are placed according to `order`.
+    /// Must be called once for each order, before and after series entries.
+    pub fn add_des_entries<Pos>(
+        &mut self,
+        legend: &des::Legend<Pos>,
+        order: des::legend::EntryOrder,
+    ) -> Result<(), drawing::Error> {
+        if legend.entry_order() != order {
+            return Ok(());
+        }
+        for e in legend.entries() {
+            let shape = match e.sample_style() {
+                des::legend::SampleStyle::Line(line) => ShapeRef::Line(line),
+                des::legend::SampleStyle::Marker(marker) => ShapeRef::Marker(marker),
+                des::legend::SampleStyle::Rect(fill, line) => ShapeRef::Rect(*fill, line.as_ref()),
+            };
+            let index = self.entries.len();
+            self.add_entry(
+                index,
+                Entry {
+                    label: e.label(),
+                    font: e.font(),
+                    shape,
+                },
+            )?;
+        }
+        Ok(())
+    }
+
     pub fn layout(mut self) -> Option<Legend> {
         if self.entries.is_empty() {
             return None;
         }
         let row_height = self.max_entry_height();
-        let column_width = self.max_entry_width();
-        let columns = self
-            .columns
-            .unwrap_or_else(|| self.calc_columns(column_width))
-            .max(1);
-        let mut col = 0;
+        let columns = self.calc_columns();
+        let col_widths = self.column_widths(columns);
+
         let mut x = self.padding.left();
         let mut y = self.padding.top();
         let mut w: f32 = 0.0;
         let mut h: f32 = 0.0;
-        for e in &mut self.entries {
+        for (i, e) in self.entries.iter_mut().enumerate() {
+            let col = i % columns;
             e.x = x;
             e.y = y;
-            w = w.max(x + column_width);
+            w = w.max(x + col_widths[col]);
             h = h.max(y + row_height);
             if col == columns - 1 {
-                col = 0;
                 x = self.padding.left();
                 y += row_height + self.spacing.height();
             } else {
-                col += 1;
-                x += column_width + self.spacing.width();
+                x += col_widths[col] + self.spacing.width();
             }
         }
         let sz = geom::Size::new(w + self.padding.right(), h + self.padding.bottom());
         Some(Legend {
             fill: self.fill,
             border: self.border,
+            radius: self.radius,
+            shadow: self.shadow,
             entries: self.entries,
             size: sz,
         })
@@ -175,23 +316,37 @@ impl<'a> LegendBuilder<'a> {
         height
     }
 
-    fn max_entry_width(&self) -> f32 {
-        let mut width = f32::NAN;
-        for e in &self.entries {
-            width = width.max(e.width());
+    /// Width of each column when entries are laid out row by row in `columns` columns
+    fn column_widths(&self, columns: usize) -> Vec<f32> {
+        let mut widths = vec![0.0f32; columns];
+        for (i, e) in self.entries.iter().enumerate() {
+            let col = i % columns;
+            widths[col] = widths[col].max(e.width());
         }
-        width
+        widths
+    }
+
+    fn content_width(&self, columns: usize) -> f32 {
+        let widths = self.column_widths(columns);
+        widths.iter().sum::<f32>() + (columns - 1) as f32 * self.spacing.width()
     }
 
-    fn calc_columns(&self, column_width: f32) -> u32 {
+    /// Compute the number of columns, starting from the requested layout
+    /// and reducing it until the legend fits in the available width.
+    /// The available width has precedence over both the requested columns and maximum rows.
+    fn calc_columns(&self) -> usize {
+        let n = self.entries.len();
+        let columns = match (self.columns, self.max_rows) {
+            (Some(columns), _) => columns as usize,
+            (None, Some(max_rows)) => n.div_ceil(max_rows as usize),
+            (None, None) => n,
+        };
+        let mut columns = columns.clamp(1, n);
         let avail_width = self.avail_width - self.padding.sum_hor();
-        let mut cols = 1;
-        let mut width = column_width;
-        while (width + column_width + self.spacing.width()) < avail_width {
-            width += column_width + self.spacing.width();
-            cols += 1;
+        while columns > 1 && self.content_width(columns) > avail_width {
+            columns -= 1;
         }
-        cols
+        columns
     }
 }
 
@@ -200,28 +355,84 @@ impl Legend {
         self.size
     }
 
-    pub fn draw<S>(&self, surface: &mut S, style: &Style, top_left: &geom::Point)
+    /// Get the series linked to the entry at `point`, if any.
+    /// `top_left` is the position of the legend.
+    pub fn entry_series_at(
+        &self,
+        top_left: &geom::Point,
+        point: &geom::Point,
+    ) -> Option<&[(des::PlotIdx, usize)]> {
+        self.entries
+            .iter()
+            .find(|e| {
+                geom::Rect::from_xywh(top_left.x + e.x, top_left.y + e.y, e.width(), e.height())
+                    .contains_point(point)
+            })
+            .map(|e| e.series.as_slice())
+            .filter(|series| !series.is_empty())
+    }
+
+    /// Set the plot of all the series linked to the entries.
+    /// Only relevant to plot legends, whose entries are linked to the series of a single plot.
+    pub fn set_plot_idx(&mut self, plot_idx: des::PlotIdx) {
+        for e in self.entries.iter_mut() {
+            for (idx, _) in e.series.iter_mut() {
+                *idx = plot_idx;
+            }
+        }
+    }
+
+    /// Update the dimming of the entries after the visibility of series has changed
+    pub fn update_dimmed<F>(&mut self, visible: F)
+    where
+        F: Fn(des::PlotIdx, usize) -> bool,
+    {
+        for e in self.entries.iter_mut().filter(|e| !e.series.is_empty()) {
+            e.dimmed = !e.series.iter().any(|&(plt, idx)| visible(plt, idx));
+        }
+    }
+
+    pub fn draw<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        top_left: &geom::Point,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
         let rect = geom::Rect::from_ps(*top_left, self.size);
+        if let Some(shadow) = &self.shadow {
+            let path = rect.to_rounded_path(self.radius);
+            surface.draw_shadow(&shadow.as_shadow(style, &path, None))?;
+        }
         if self.fill.is_some() || self.border.is_some() {
-            surface.draw_rect(&render::Rect {
-                rect,
-                fill: self.fill.map(|f| f.as_paint(style)),
-                stroke: self.border.as_ref().map(|l| l.as_stroke(style)),
-                transform: None,
-            });
+            drawing::draw_rounded_rect(
+                surface,
+                &render::Rect {
+                    rect,
+                    fill: self.fill.map(|f| f.as_paint(style)),
+                    stroke: self.border.as_ref().map(|l| l.as_stroke(style)),
+                    transform: None,
+                },
+                self.radius,
+            )?;
         }
 
         for entry in &self.entries {
-            entry.draw(surface, style, &rect);
+            entry.draw(surface, style, &rect)?;
         }
+        Ok(())
     }
 }
 
 impl LegendEntry {
-    fn draw<S>(&self, surface: &mut S, style: &Style, rect: &geom::Rect)
+    fn draw<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        rect: &geom::Rect,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
@@ -243,7 +454,15 @@ impl LegendEntry {
 
         let rc = (style, self.index);
 
-        match &self.shape {
+        let faded;
+        let shape = if self.dimmed {
+            faded = self.shape.faded(defaults::LEGEND_DIMMED_OPACITY);
+            &faded
+        } else {
+            &self.shape
+        };
+
+        match shape {
             Shape::Line(line) => {
                 let mut path = geom::PathBuilder::new();
                 path.move_to(shape_rect.left(), shape_rect.center_y());
@@ -256,7 +475,7 @@ impl LegendEntry {
                     stroke: Some(line.as_stroke(&rc)),
                     transform: None,
                 };
-                surface.draw_path(&line);
+                surface.draw_path(&line)?;
             }
             Shape::Marker(marker) => {
                 let path = crate::drawing::marker::marker_path(&marker);
@@ -269,7 +488,7 @@ impl LegendEntry {
                     stroke: marker.stroke.as_ref().map(|s| s.as_stroke(&rc)),
                     transform: Some(&transform),
                 };
-                surface.draw_path(&path);
+                surface.draw_path(&path)?;
             }
             Shape::Rect(fill, line) => {
                 let r = geom::Rect::from_ps(
@@ -285,7 +504,35 @@ impl LegendEntry {
                     stroke: line.as_ref().map(|l| l.as_stroke(&rc)),
                     transform: None,
                 };
-                surface.draw_rect(&rr);
+                surface.draw_rect(&rr)?;
+            }
+            Shape::SplitRect(left, right, line) => {
+                let r = geom::Rect::from_ps(
+                    geom::Point {
+                        x: rect.left(),
+                        y: rect.center_y() - shape_sz.height() / 2.0,
+                    },
+                    shape_sz,
+                );
+                let half = r.width() / 2.0;
+                for (x, fill) in [(r.left(), left), (r.left() + half, right)] {
+                    let rr = render::Rect {
+                        rect: geom::Rect::from_xywh(x, r.top(), half, r.height()),
+                        fill: Some(fill.as_paint(&rc)),
+                        stroke: None,
+                        transform: None,
+                    };
+                    surface.draw_rect(&rr)?;
+                }
+                if let Some(line) = line {
+                    let rr = render::Rect {
+                        rect: r,
+                        fill: None,
+                        stroke: Some(line.as_stroke(&rc)),
+                        transform: None,
+                    };
+                    surface.draw_rect(&rr)?;
+                }
             }
         };
 
@@ -293,6 +540,68 @@ impl LegendEntry {
             rect.left() + shape_sz.width() + defaults::LEGEND_SHAPE_SPACING,
             rect.center_y(),
         );
-        self.text.draw(surface, style, Some(&transform));
+        if self.dimmed {
+            let text = self
+                .text
+                .clone()
+                .with_opacity(defaults::LEGEND_DIMMED_OPACITY);
+            text.draw(surface, style, Some(&transform))?;
+        } else {
+            self.text.draw(surface, style, Some(&transform))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lay out `n` entries labeled with `label` in a legend of the given width
+    fn layout(legend: &des::PlotLegend, n: usize, label: &str, avail_width: f32) -> Legend {
+        let fontdb = crate::bundled_font_db();
+        let line = style::series::Stroke::default();
+        let mut builder = LegendBuilder::from_des(legend, false, avail_width, &fontdb);
+        for index in 0..n {
+            let entry = Entry {
+                label,
+                font: None,
+                shape: ShapeRef::Line(&line),
+            };
+            builder.add_entry(index, entry).unwrap();
+        }
+        builder.layout().unwrap()
+    }
+
+    fn rows(legend: &Legend) -> usize {
+        let mut ys: Vec<f32> = legend.entries.iter().map(|e| e.y).collect();
+        ys.dedup();
+        ys.len()
+    }
+
+    #[test]
+    fn test_max_rows_narrowed() {
+        let legend = des::PlotLegend::new(des::plot::LegendPos::OutBottom).with_max_rows(2);
+
+        // enough width: 6 entries in 3 columns of 2 rows
+        let wide = layout(&legend, 6, "a long series name", 2000.0);
+        assert_eq!(rows(&wide), 2);
+
+        // the available width has precedence over the maximum rows
+        let narrow = layout(&legend, 6, "a long series name", 300.0);
+        assert!(rows(&narrow) > 2);
+        assert!(narrow.size().width() <= 300.0);
+    }
+
+    #[test]
+    fn test_columns_narrowed() {
+        let legend = des::PlotLegend::new(des::plot::LegendPos::OutBottom).with_columns(3);
+
+        let wide = layout(&legend, 6, "a long series name", 2000.0);
+        assert_eq!(rows(&wide), 2);
+
+        let narrow = layout(&legend, 6, "a long series name", 300.0);
+        assert!(rows(&narrow) > 2);
+        assert!(narrow.size().width() <= 300.0);
     }
 }
@@ -1,16 +1,16 @@
-use std::cell::RefCell;
+use std::borrow::Cow;
 use std::f32;
-use std::rc::Rc;
+use std::sync::{Arc, RwLock};
 
-use crate::des::{PlotIdx, annot};
+use crate::des::PlotIdx;
 use crate::drawing::annot::Annot;
-use crate::drawing::axis::{Axis, AxisScale, Bounds, Side};
+use crate::drawing::axis::{Axis, AxisScale, Bounds, NumBounds, Side};
 use crate::drawing::legend::{Legend, LegendBuilder};
-use crate::drawing::scale::CoordMapXy;
+use crate::drawing::scale::{self, CoordMapXy};
 use crate::drawing::series::{self, Series, SeriesExt};
 use crate::drawing::{Ctx, Error};
 use crate::style::{defaults, theme};
-use crate::{Style, data, des, geom, missing_params, render};
+use crate::{Style, data, des, geom, render, text};
 
 #[derive(Debug, Clone)]
 pub(super) struct Plots {
@@ -55,11 +55,16 @@ pub(super) struct Plot {
     // None when there is no series (empty plot)
     axes: Option<Axes>,
 
+    title: Option<(geom::Transform, super::Text)>,
+    panel_label: Option<(geom::Transform, super::Text)>,
     fill: Option<theme::Fill>,
     border: Option<des::plot::Border>,
+    spines: des::plot::SpineConfig,
+    clip: des::plot::Clip,
     series: Vec<Series>,
     legend: Option<(geom::Point, Legend)>,
     annots: Vec<Annot>,
+    zoom_insets: Vec<ZoomInset>,
 }
 
 impl Plot {
@@ -78,6 +83,52 @@ impl Plot {
     pub(super) fn axes_mut(&mut self) -> Option<&mut Axes> {
         self.axes.as_mut()
     }
+
+    pub(super) fn series(&self) -> &[Series] {
+        &self.series
+    }
+
+    pub(super) fn legend(&self) -> Option<&(geom::Point, Legend)> {
+        self.legend.as_ref()
+    }
+
+    fn set_idx(&mut self, idx: PlotIdx) {
+        self.idx = idx;
+        if let Some((_, legend)) = self.legend.as_mut() {
+            legend.set_plot_idx(idx);
+        }
+    }
+
+    /// Show or hide a series, and update the dimming of the plot legend.
+    /// Returns false if there is no such series.
+    pub(super) fn set_series_visible(&mut self, series_idx: usize, visible: bool) -> bool {
+        let Some(series) = self.series.get_mut(series_idx) else {
+            return false;
+        };
+        series.set_visible(visible);
+        if let Some((_, legend)) = self.legend.as_mut() {
+            let series = &self.series;
+            legend.update_dimmed(|_, idx| series[idx].visible());
+        }
+        for inset in self.zoom_insets.iter_mut() {
+            if inset.shown[series_idx] {
+                inset.plot.set_series_visible(series_idx, visible);
+            }
+        }
+        true
+    }
+}
+
+/// A zoomed inset of a region of a plot
+#[derive(Debug, Clone)]
+struct ZoomInset {
+    /// The zoomed region, in figure units
+    region: geom::Rect,
+    plot: Plot,
+    line: theme::Stroke,
+    connectors: bool,
+    /// Whether each series of the parent plot is shown in the inset
+    shown: Vec<bool>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -157,6 +208,50 @@ struct PlotData {
     series: Vec<Series>,
     legend: Option<Legend>,
     insets: geom::Padding,
+    title: Option<PlotTitle>,
+    panel_label: Option<PanelLabel>,
+}
+
+impl PlotData {
+    /// Height taken by the title and the panel label above the plot and its axes on `side`
+    fn title_height(&self, side: des::axis::Side) -> f32 {
+        if side != des::axis::Side::Opposite {
+            return 0.0;
+        }
+        let title = self.title.as_ref().map_or(0.0, |t| t.height);
+        let label = match self.panel_label.as_ref() {
+            Some(label) if label.is_out() => label.height,
+            _ => 0.0,
+        };
+        title.max(label)
+    }
+}
+
+/// Title of a plot, laid out during setup phase
+#[derive(Debug, Clone)]
+struct PlotTitle {
+    text: super::Text,
+    /// Height of the title, including the margin with the plot
+    height: f32,
+}
+
+/// Panel label of a plot, laid out during setup phase
+#[derive(Debug, Clone)]
+struct PanelLabel {
+    text: super::Text,
+    pos: des::plot::PanelLabelPos,
+    width: f32,
+    /// Height of the label, including the margin with the plot if it is out of the plot area
+    height: f32,
+}
+
+impl PanelLabel {
+    fn is_out(&self) -> bool {
+        matches!(
+            self.pos,
+            des::plot::PanelLabelPos::OutTopLeft | des::plot::PanelLabelPos::OutTopRight
+        )
+    }
 }
 
 trait IrPlotExt {
@@ -180,7 +275,9 @@ impl IrPlotExt for des::Plot {
 }
 
 trait IrPlotsExt {
+    fn rows(&self) -> u32;
     fn cols(&self) -> u32;
+    fn or_shared(&self, or: Orientation) -> bool;
     fn plot(&self, idx: PlotIdx) -> Option<&des::Plot>;
     fn plots(&self) -> impl Iterator<Item = Option<&des::Plot>> + '_;
 
@@ -230,12 +327,45 @@ trait IrPlotsExt {
         }
         None
     }
+
+    /// If the orientation is shared at figure level, get the figure index of the axis
+    /// the axis `ax_idx` of plot `plt_idx` shares its scale with.
+    /// Only the main axes (index 0) are shared, with the one of the bottom plot
+    /// of the column for X, and of the left plot of the row for Y.
+    fn or_outer_shared(&self, or: Orientation, plt_idx: usize, ax_idx: usize) -> Option<usize> {
+        if ax_idx != 0 || !self.or_shared(or) {
+            return None;
+        }
+        let cols = self.cols();
+        let row = plt_idx as u32 / cols;
+        let col = plt_idx as u32 % cols;
+        let outer = match or {
+            Orientation::X => (0..self.rows())
+                .rev()
+                .find_map(|r| self.idx_plt((r, col)).map(|(idx, _)| idx)),
+            Orientation::Y => (0..cols).find_map(|c| self.idx_plt((row, c)).map(|(idx, _)| idx)),
+        }?;
+        if outer == plt_idx {
+            return None;
+        }
+        self.or_find_axis(or, &des::axis::Ref::Idx(0), outer)
+            .map(|(fig_ax_idx, _)| fig_ax_idx)
+    }
 }
 
 impl IrPlotsExt for des::figure::Plots {
+    fn rows(&self) -> u32 {
+        self.rows()
+    }
     fn cols(&self) -> u32 {
         self.cols()
     }
+    fn or_shared(&self, or: Orientation) -> bool {
+        match or {
+            Orientation::X => self.shared_x(),
+            Orientation::Y => self.shared_y(),
+        }
+    }
     fn plot(&self, idx: PlotIdx) -> Option<&des::Plot> {
         self.plot(idx)
     }
@@ -259,13 +389,18 @@ impl PlotAxes {
                 if a.side().to_des_side() != side {
                     continue;
                 }
+                if cnt > 0 {
+                    sz += a.spacing();
+                }
                 sz += a.size_across();
                 cnt += 1;
             }
         }
-        if cnt > 1 {
-            sz += (cnt as f32 - 1.0)
-                * (missing_params::AXIS_MARGIN + missing_params::AXIS_SPINE_WIDTH);
+        // titles of the axes of the other side, moved to this side
+        for a in self.0.iter().flatten() {
+            if a.side().to_des_side() != side && a.title_side().to_des_side() == side {
+                sz += a.title_size_across();
+            }
         }
         sz
     }
@@ -277,17 +412,23 @@ where
 {
     /// Setup a collection of plots, given an design representation of the plots
     /// and a bounding rectangle.
+    /// If `with_legends` is false, the legends of the plots are not setup.
     pub fn setup_plots(
         &self,
         des_plots: &des::figure::Plots,
         rect: &geom::Rect,
+        with_legends: bool,
     ) -> Result<Plots, Error> {
+        if let des::figure::Plots::Freeform(layout) = des_plots {
+            return self.setup_freeform_plots(layout, rect, with_legends);
+        }
+
         // We build all needed characteristics by the plots one after another.
         // Each characteristic (axes, interspace etc.) is in vector, indexed in the
         // same order than the plots
 
         // PlotData contains all data that is not impacted by the size of axes
-        let plot_data = self.setup_plot_data(des_plots, rect)?;
+        let plot_data = self.setup_plot_data(des_plots, rect, with_legends)?;
 
         // Estimate the space taken by all horizontal axes
         // Can be slightly wrong if font metrics height isn't exactly font size.
@@ -301,12 +442,17 @@ where
             + des_plots.space() * (des_plots.rows() - 1) as f32;
 
         // Now we can determine length of vertical axes and set them all up
-        let subplot_rect_height = (rect.height() - hor_space_height) / des_plots.rows() as f32;
+        super::check_room(rect, 0.0, hor_space_height)?;
+        let subplot_rect_heights = distribute_size(
+            rect.height() - hor_space_height,
+            des_plots.rows(),
+            des_plots.row_ratios(),
+        );
         let y_axes = self.setup_orientation_axes(
             Orientation::Y,
             des_plots,
             &plot_data,
-            subplot_rect_height,
+            &subplot_rect_heights,
         )?;
 
         // Now we calculate the interspace between vertical axes
@@ -318,9 +464,18 @@ where
             + des_plots.space() * (des_plots.cols() - 1) as f32;
 
         // Now we can determine width of horizontal axes and set them all up
-        let subplot_rect_width = (rect.width() - vert_space_width) / des_plots.cols() as f32;
-        let x_axes =
-            self.setup_orientation_axes(Orientation::X, des_plots, &plot_data, subplot_rect_width)?;
+        super::check_room(rect, vert_space_width, 0.0)?;
+        let subplot_rect_widths = distribute_size(
+            rect.width() - vert_space_width,
+            des_plots.cols(),
+            des_plots.col_ratios(),
+        );
+        let x_axes = self.setup_orientation_axes(
+            Orientation::X,
+            des_plots,
+            &plot_data,
+            &subplot_rect_widths,
+        )?;
 
         // bottom heights were estimated, we can now calculate them accurately and rebuild the y-axes
         let bottom_heights =
@@ -330,12 +485,17 @@ where
         let hor_space_height = bottom_heights.iter().sum::<f32>()
             + top_heights.iter().sum::<f32>()
             + des_plots.space() * (des_plots.rows() - 1) as f32;
-        let subplot_rect_height = (rect.height() - hor_space_height) / des_plots.rows() as f32;
+        super::check_room(rect, 0.0, hor_space_height)?;
+        let subplot_rect_heights = distribute_size(
+            rect.height() - hor_space_height,
+            des_plots.rows(),
+            des_plots.row_ratios(),
+        );
         let y_axes = self.setup_orientation_axes(
             Orientation::Y,
             des_plots,
             &plot_data,
-            subplot_rect_height,
+            &subplot_rect_heights,
         )?;
 
         // Everything is now ready to setup all plots
@@ -346,11 +506,13 @@ where
 
         let mut y = rect.y();
         for row in 0..des_plots.rows() {
+            let subplot_rect_height = subplot_rect_heights[row as usize];
             let height =
                 subplot_rect_height + top_heights[row as usize] + bottom_heights[row as usize];
             let mut x = rect.x();
 
             for col in 0..des_plots.cols() {
+                let subplot_rect_width = subplot_rect_widths[col as usize];
                 let width =
                     subplot_rect_width + left_widths[col as usize] + right_widths[col as usize];
 
@@ -367,19 +529,22 @@ where
                         subplot_rect_height,
                     );
 
-                    let PlotData { series, legend, .. } = data.unwrap();
+                    let data = data.unwrap();
+                    let data_title_height = data.title_height(des::axis::Side::Opposite);
+                    let PlotData {
+                        series,
+                        legend,
+                        title,
+                        panel_label,
+                        ..
+                    } = data;
 
-                    let legend = legend.map(|leg| {
-                        let top_left = legend_top_left(
-                            des_plot.legend().unwrap(),
-                            leg.size(),
-                            &plot_rect,
-                            &outer_rect,
-                        );
-                        (top_left, leg)
-                    });
+                    // the title and the panel label hang from the top of the row,
+                    // and the rest of the plot is below them
+                    let cell_rect = outer_rect;
+                    let outer_rect = outer_rect.shifted_top_side(data_title_height);
 
-                    let axes = {
+                    let mut axes = {
                         let x_ax = x_axes.unwrap();
                         let y_ax = y_axes.unwrap();
                         let x: Vec<Axis> = x_ax.0.into_iter().filter_map(|a| a).collect();
@@ -396,6 +561,23 @@ where
                         }
                     };
 
+                    let plot_rect = match (des_plot.aspect(), axes.as_mut()) {
+                        (Some(aspect), Some(axes)) => {
+                            self.lock_plot_aspect(aspect, axes, &plot_rect)?
+                        }
+                        _ => plot_rect,
+                    };
+
+                    let legend = legend.map(|leg| {
+                        let top_left = legend_top_left(
+                            des_plot.legend().unwrap(),
+                            leg.size(),
+                            &plot_rect,
+                            &outer_rect,
+                        );
+                        (top_left, leg)
+                    });
+
                     let annots = if let Some(axes) = axes.as_ref() {
                         des_plot
                             .annotations()
@@ -406,16 +588,61 @@ where
                         Vec::new()
                     };
 
+                    let zoom_insets = if let Some(axes) = axes.as_ref() {
+                        des_plot
+                            .zoom_insets()
+                            .iter()
+                            .map(|zi| {
+                                self.setup_zoom_inset(zi, des_plot, axes, &plot_rect, (row, col))
+                            })
+                            .collect::<Result<_, Error>>()?
+                    } else {
+                        Vec::new()
+                    };
+
+                    let panel_label = panel_label.map(|label| {
+                        let (x, y) = self.panel_label_anchor(&label, &cell_rect, &plot_rect);
+                        (label, geom::Transform::from_translate(x, y))
+                    });
+
+                    let title = title.map(|title| {
+                        let mut x = super::title_anchor_x(des_plot.title_align(), &plot_rect);
+                        // the title is moved aside of an out label on the same side
+                        let margin = self.layout().panel_label_margin;
+                        match (des_plot.title_align(), panel_label.as_ref()) {
+                            (
+                                text::line::Align::Start | text::line::Align::Left,
+                                Some((label, _)),
+                            ) if label.pos == des::plot::PanelLabelPos::OutTopLeft => {
+                                x = x.max(cell_rect.left() + label.width + margin);
+                            }
+                            (
+                                text::line::Align::End | text::line::Align::Right,
+                                Some((label, _)),
+                            ) if label.pos == des::plot::PanelLabelPos::OutTopRight => {
+                                x = x.min(cell_rect.right() - label.width - margin);
+                            }
+                            _ => (),
+                        }
+                        (geom::Transform::from_translate(x, y), title.text)
+                    });
+                    let panel_label = panel_label.map(|(label, transform)| (transform, label.text));
+
                     let plt_idx = row * des_plots.cols() + col;
                     let plot = Plot {
                         idx: (row, col).into(),
                         rect: plot_rect,
+                        title,
+                        panel_label,
                         fill: des_plot.fill().cloned(),
                         border: des_plot.border().cloned(),
+                        spines: *des_plot.spines(),
+                        clip: des_plot.clip().clone(),
                         axes,
                         series,
                         legend,
                         annots,
+                        zoom_insets,
                     };
                     plots[plt_idx as usize] = Some(plot);
                 }
@@ -435,38 +662,324 @@ where
         Ok(plots)
     }
 
+    /// Setup the plots of a freeform layout.
+    /// Each plot is setup on its own, as a single plot figure, in its rectangle mapped to `rect`.
+    fn setup_freeform_plots(
+        &self,
+        layout: &des::FreeformLayout,
+        rect: &geom::Rect,
+        with_legends: bool,
+    ) -> Result<Plots, Error> {
+        let mut plots = Vec::with_capacity(layout.len());
+        for (col, (norm_rect, des_plot)) in layout.iter().enumerate() {
+            let plot_rect = geom::Rect::from_xywh(
+                rect.x() + norm_rect.x() * rect.width(),
+                rect.y() + norm_rect.y() * rect.height(),
+                norm_rect.width() * rect.width(),
+                norm_rect.height() * rect.height(),
+            );
+            let des_plots = des::figure::Plots::Plot(Box::new(des_plot.clone()));
+            let single = self.setup_plots(&des_plots, &plot_rect, with_legends)?;
+            let mut plot = single.plots.into_iter().next().flatten();
+            if let Some(plot) = plot.as_mut() {
+                plot.set_idx(PlotIdx::new(0, col as u32));
+            }
+            plots.push(plot);
+        }
+        Ok(Plots {
+            size: (1, layout.len() as u32),
+            plots,
+        })
+    }
+
+    /// Setup a zoomed inset of the plot `des_plot`, whose main axes are already setup in `axes`.
+    /// The inset is setup as a single plot figure, in its rectangle mapped to `plot_rect`,
+    /// with the series of the main axes of the parent.
+    fn setup_zoom_inset(
+        &self,
+        inset: &des::ZoomInset,
+        des_plot: &des::Plot,
+        axes: &Axes,
+        plot_rect: &geom::Rect,
+        idx: impl Into<PlotIdx>,
+    ) -> Result<ZoomInset, Error> {
+        let (x_min, x_max) = inset.x_range();
+        let (y_min, y_max) = inset.y_range();
+        let (x_min, x_max) = (x_min.min(x_max), x_min.max(x_max));
+        let (y_min, y_max) = (y_min.min(y_max), y_min.max(y_max));
+
+        let x_scale = zoomed_scale(&axes.x[0], (x_min, x_max))?;
+        let y_scale = zoomed_scale(&axes.y[0], (y_min, y_max))?;
+
+        // Series of other axes are kept hidden, so that series indices and colors are preserved
+        let mut shown = Vec::with_capacity(des_plot.series().len());
+        let mut series = Vec::with_capacity(des_plot.series().len());
+        for s in des_plot.series() {
+            let (x_ax_ref, y_ax_ref) = s.axes();
+            let on_main = axes.or_find_idx(Orientation::X, x_ax_ref)? == Some(0)
+                && axes.or_find_idx(Orientation::Y, y_ax_ref)? == Some(0);
+            series.push(s.to_main_axes().with_visible(on_main && s.visible()));
+            shown.push(on_main);
+        }
+
+        let mut inset_plot = des::Plot::new(series)
+            .with_x_axis(des_plot.x_axes()[0].to_zoomed(x_scale))
+            .with_y_axis(des_plot.y_axes()[0].to_zoomed(y_scale))
+            .with_insets(None)
+            .with_padding(geom::Padding::Even(0.0))
+            .with_hidden_series_bounds(false);
+        if let Some(fill) = inset.fill() {
+            inset_plot = inset_plot.with_fill(*fill);
+        }
+
+        let norm_rect = inset.rect();
+        let rect = geom::Rect::from_xywh(
+            plot_rect.x() + norm_rect.x() * plot_rect.width(),
+            plot_rect.y() + norm_rect.y() * plot_rect.height(),
+            norm_rect.width() * plot_rect.width(),
+            norm_rect.height() * plot_rect.height(),
+        );
+        let single = self.setup_plots(
+            &des::figure::Plots::Plot(Box::new(inset_plot)),
+            &rect,
+            false,
+        )?;
+        let Some(mut plot) = single.plots.into_iter().next().flatten() else {
+            unreachable!("single plot figure without plot");
+        };
+        plot.set_idx(idx.into());
+
+        let x_cm = axes.x[0].coord_map();
+        let y_cm = axes.y[0].coord_map();
+        let region = geom::Rect::from_trbl(
+            plot_rect.bottom() - y_cm.map_coord_num(y_max),
+            plot_rect.left() + x_cm.map_coord_num(x_max),
+            plot_rect.bottom() - y_cm.map_coord_num(y_min),
+            plot_rect.left() + x_cm.map_coord_num(x_min),
+        );
+
+        Ok(ZoomInset {
+            region,
+            plot,
+            line: inset.line().clone(),
+            connectors: inset.connectors(),
+            shown,
+        })
+    }
+
+    /// Shrink `plot_rect` along one dimension and center it to honor the aspect lock.
+    /// The coordinate maps of the axes along the shrunk dimension are updated accordingly.
+    fn lock_plot_aspect(
+        &self,
+        aspect: des::plot::AspectMode,
+        axes: &mut Axes,
+        plot_rect: &geom::Rect,
+    ) -> Result<geom::Rect, Error> {
+        let ratio = aspect.ratio();
+        if !ratio.is_finite() || ratio <= 0.0 {
+            return Err(Error::InconsistentDesign(format!(
+                "Aspect ratio must be finite and positive (got {ratio})"
+            )));
+        }
+        for ax in axes.x.iter().chain(axes.y.iter()) {
+            lin_unshared_bounds(ax)?;
+        }
+        let x_bounds = lin_unshared_bounds(&axes.x[0])?;
+        let y_bounds = lin_unshared_bounds(&axes.y[0])?;
+        let sz = scale::aspect_locked_size(x_bounds, y_bounds, plot_rect.size(), ratio);
+
+        if sz.width() < plot_rect.width() {
+            for ax in axes.x.iter_mut() {
+                let cm = scale::lin_coord_map(sz.width(), lin_unshared_bounds(ax)?);
+                self.axis_set_coord_map(ax, cm)?;
+            }
+        }
+        if sz.height() < plot_rect.height() {
+            for ax in axes.y.iter_mut() {
+                let cm = scale::lin_coord_map(sz.height(), lin_unshared_bounds(ax)?);
+                self.axis_set_coord_map(ax, cm)?;
+            }
+        }
+
+        Ok(geom::Rect::from_xywh(
+            plot_rect.center_x() - sz.width() / 2.0,
+            plot_rect.center_y() - sz.height() / 2.0,
+            sz.width(),
+            sz.height(),
+        ))
+    }
+
     fn setup_plot_data(
         &self,
         des_plots: &des::figure::Plots,
         rect: &geom::Rect,
+        with_legends: bool,
     ) -> Result<Vec<Option<PlotData>>, Error> {
+        let cols = des_plots.cols();
+        let avail_widths = distribute_size(
+            rect.width() - des_plots.space() * (cols - 1) as f32,
+            cols,
+            des_plots.col_ratios(),
+        );
+
+        #[cfg(feature = "parallel")]
+        if let Some(data_source) = self.par_source {
+            use rayon::prelude::*;
+
+            let ctx = Ctx {
+                data_source,
+                fontdb: self.fontdb,
+                layout: self.layout,
+                par_source: None,
+            };
+            let des_plots: Vec<_> = des_plots.iter().enumerate().collect();
+            // collecting an indexed parallel iterator preserves the order of the plots
+            return des_plots
+                .into_par_iter()
+                .map(|(idx, des_plot)| {
+                    des_plot
+                        .map(|p| {
+                            ctx.setup_one_plot_data(
+                                PlotIdx::from_index(idx, cols),
+                                p,
+                                avail_widths[idx % cols as usize],
+                                with_legends,
+                            )
+                        })
+                        .transpose()
+                })
+                .collect();
+        }
+
         let mut plot_data = vec![None; des_plots.len()];
         for (idx, des_plot) in des_plots.iter().enumerate() {
             let Some(des_plot) = des_plot else { continue };
-            let series = self.setup_plot_series(des_plot)?;
-            let cols = des_plots.cols() as f32;
-            let avail_width = (rect.width() - des_plots.space() * (cols - 1.0)) / cols;
-            let legend = self.setup_plot_legend(des_plot, avail_width)?;
-            let insets = plot_insets(des_plot);
-            plot_data[idx] = Some(PlotData {
-                series,
-                legend,
-                insets,
-            });
+            let avail_width = avail_widths[idx % cols as usize];
+            let plt_idx = PlotIdx::from_index(idx, cols);
+            plot_data[idx] =
+                Some(self.setup_one_plot_data(plt_idx, des_plot, avail_width, with_legends)?);
         }
         Ok(plot_data)
     }
 
+    fn setup_one_plot_data(
+        &self,
+        plt_idx: PlotIdx,
+        des_plot: &des::Plot,
+        avail_width: f32,
+        with_legends: bool,
+    ) -> Result<PlotData, Error> {
+        let series = self.setup_plot_series(des_plot)?;
+        let legend = if with_legends {
+            self.setup_plot_legend(plt_idx, des_plot, avail_width)?
+        } else {
+            None
+        };
+        let insets = plot_insets(des_plot, &series);
+        let title = des_plot
+            .title()
+            .map(|title| self.setup_plot_title(title, des_plot.title_align()))
+            .transpose()?;
+        let panel_label = des_plot
+            .panel_label()
+            .map(|label| self.setup_panel_label(label))
+            .transpose()?;
+        Ok(PlotData {
+            series,
+            legend,
+            insets,
+            title,
+            panel_label,
+        })
+    }
+
+    fn setup_plot_title(
+        &self,
+        title: &des::plot::Title,
+        align: text::line::Align,
+    ) -> Result<PlotTitle, Error> {
+        let rich = title.to_rich_text(super::title_layout(align), self.fontdb())?;
+        let text = super::Text::from_rich_text(&rich, self.fontdb())?;
+        let height =
+            rich.visual_bbox().map_or(0.0, |bbox| bbox.height()) + self.layout().plot_title_margin;
+        Ok(PlotTitle { text, height })
+    }
+
+    fn setup_panel_label(&self, label: &des::plot::PanelLabel) -> Result<PanelLabel, Error> {
+        use des::plot::PanelLabelPos;
+
+        let align = match label.pos() {
+            PanelLabelPos::OutTopLeft | PanelLabelPos::InTopLeft => {
+                (text::line::Align::Left, text::line::VerAlign::Hanging)
+            }
+            PanelLabelPos::OutTopRight | PanelLabelPos::InTopRight => {
+                (text::line::Align::Right, text::line::VerAlign::Hanging)
+            }
+            PanelLabelPos::InBottomLeft => (text::line::Align::Left, text::line::VerAlign::Bottom),
+            PanelLabelPos::InBottomRight => {
+                (text::line::Align::Right, text::line::VerAlign::Bottom)
+            }
+        };
+        let line = text::LineText::new(
+            label.text().to_string(),
+            align,
+            label.font_size(),
+            label.font().clone(),
+            self.fontdb(),
+        )?;
+        let text = super::Text::from_line_text(&line, self.fontdb(), label.color())?;
+
+        let mut label = PanelLabel {
+            text,
+            pos: label.pos(),
+            width: line.width(),
+            height: 0.0,
+        };
+        if label.is_out() {
+            // extent of the label below its hanging anchor
+            label.height = line.bbox().map_or(0.0, |bbox| bbox.bottom().max(0.0))
+                + self.layout().plot_title_margin;
+        }
+        Ok(label)
+    }
+
+    /// Anchor of the panel label, in the plot cell or in the plot area
+    fn panel_label_anchor(
+        &self,
+        label: &PanelLabel,
+        cell_rect: &geom::Rect,
+        plot_rect: &geom::Rect,
+    ) -> (f32, f32) {
+        use des::plot::PanelLabelPos;
+
+        let margin = self.layout().panel_label_margin;
+        match label.pos {
+            PanelLabelPos::OutTopLeft => (cell_rect.left(), cell_rect.top()),
+            PanelLabelPos::OutTopRight => (cell_rect.right(), cell_rect.top()),
+            PanelLabelPos::InTopLeft => (plot_rect.left() + margin, plot_rect.top() + margin),
+            PanelLabelPos::InTopRight => (plot_rect.right() - margin, plot_rect.top() + margin),
+            PanelLabelPos::InBottomLeft => (plot_rect.left() + margin, plot_rect.bottom() - margin),
+            PanelLabelPos::InBottomRight => {
+                (plot_rect.right() - margin, plot_rect.bottom() - margin)
+            }
+        }
+    }
+
+    fn plot_padding<'p>(&'p self, plot: &'p des::Plot) -> &'p geom::Padding {
+        plot.padding().unwrap_or(&self.layout().plot_padding)
+    }
+
     fn setup_plot_series(&self, plot: &des::Plot) -> Result<Vec<Series>, Error> {
         plot.series()
             .iter()
             .enumerate()
-            .map(|(index, s)| Series::prepare(index, s, self.data_source()))
+            .map(|(index, s)| Series::prepare(index, s, self.data_source(), self.fontdb()))
             .collect()
     }
 
     fn setup_plot_legend(
         &self,
+        plt_idx: PlotIdx,
         des_plot: &des::Plot,
         avail_width: f32,
     ) -> Result<Option<Legend>, Error> {
@@ -481,14 +994,19 @@ where
             self.fontdb(),
         );
 
+        builder.add_des_entries(des_leg, des::legend::EntryOrder::BeforeSeries)?;
         let mut idx = 0;
-        for_each_series(des_plot, |s| {
+        for_each_series(des_plot, |series_idx, s| {
             if let Some(entry) = s.legend_entry() {
+                let entry_idx = builder.len();
                 builder.add_entry(idx, entry)?;
+                let visible = des_plot.series()[series_idx].visible();
+                builder.link_series(entry_idx, (plt_idx, series_idx), visible);
                 idx += 1;
             }
             Ok(())
         })?;
+        builder.add_des_entries(des_leg, des::legend::EntryOrder::AfterSeries)?;
 
         Ok(builder.layout())
     }
@@ -506,8 +1024,18 @@ where
                 if let Some((plt_idx, des_plot)) = des_plots.idx_plt((row, col)) {
                     let data = datas[plt_idx].as_ref().unwrap();
 
-                    let mut height = x_plot_padding(side);
-                    height += self.estimate_x_axes_height(des_plot.x_axes(), side);
+                    let x_axes = match des_plots.or_outer_shared(Orientation::X, plt_idx, 0) {
+                        Some(fig_ax_idx) => {
+                            let mut x_axes = des_plot.x_axes().to_vec();
+                            x_axes[0] = x_axes[0].to_outer_shared(fig_ax_idx);
+                            Cow::Owned(x_axes)
+                        }
+                        None => Cow::Borrowed(des_plot.x_axes()),
+                    };
+
+                    let mut height = x_plot_padding(self.plot_padding(des_plot), side);
+                    height += self.estimate_x_axes_height(&x_axes, side);
+                    height += data.title_height(side);
                     if let (Some(des_leg), Some(leg)) = (des_plot.legend(), data.legend.as_ref()) {
                         if x_side_matches_out_legend_pos(side, des_leg.pos()) {
                             height += leg.size().height() + des_leg.margin();
@@ -540,8 +1068,9 @@ where
                 let data = datas[index].as_ref().unwrap();
                 let x_axes = x_axes[index].as_ref().unwrap();
 
-                let mut height = x_plot_padding(side);
+                let mut height = x_plot_padding(self.plot_padding(des_plot), side);
                 height += x_axes.size_across(side);
+                height += data.title_height(side);
 
                 if let (Some(des_leg), Some(leg)) = (des_plot.legend(), data.legend.as_ref()) {
                     if x_side_matches_out_legend_pos(side, des_leg.pos()) {
@@ -551,8 +1080,8 @@ where
 
                 max_height = max_height.max(height);
             }
-            debug_assert!(max_height.is_finite());
-            heights.push(max_height);
+            // a row without any plot takes no room
+            heights.push(max_height.max(0.0));
         }
         heights
     }
@@ -573,7 +1102,7 @@ where
                     let data = datas[index].as_ref().unwrap();
                     let y_axis = y_axes[index].as_ref().unwrap();
 
-                    let mut width = y_plot_padding(side);
+                    let mut width = y_plot_padding(self.plot_padding(des_plot), side);
                     width += y_axis.size_across(side);
 
                     if let (Some(des_leg), Some(leg)) = (des_plot.legend(), data.legend.as_ref()) {
@@ -585,8 +1114,8 @@ where
                     max_width = max_width.max(width);
                 }
             }
-            debug_assert!(max_width.is_finite());
-            widths.push(max_width);
+            // a column without any plot takes no room
+            widths.push(max_width.max(0.0));
         }
         widths
     }
@@ -596,15 +1125,21 @@ where
         or: Orientation,
         des_plots: &des::figure::Plots,
         datas: &[Option<PlotData>],
-        size_along: f32,
+        sizes_along: &[f32],
     ) -> Result<Vec<Option<PlotAxes>>, Error> {
         let mut plot_axes = vec![None; des_plots.len()];
 
+        // size along the axes of a plot, according its row or column
+        let cols = des_plots.cols() as usize;
+        let plot_size_along = |plt_idx: usize| match or {
+            Orientation::X => sizes_along[plt_idx % cols],
+            Orientation::Y => sizes_along[plt_idx / cols],
+        };
+
         // collecting all axes that own their scale.
 
         // ax_infos is Some only for the axis owning their scale
-        let mut ax_infos: Vec<Option<(Bounds, Rc<RefCell<AxisScale>>)>> =
-            vec![None; des_plots.or_axes_len(or)];
+        let mut ax_infos: Vec<Option<ScaleInfo>> = vec![None; des_plots.or_axes_len(or)];
 
         // index of the first axis of a plot, at figure level
         let mut fig_ax_idx0 = 0;
@@ -620,7 +1155,9 @@ where
             let mut opposite_off_plot = false;
 
             for (ax_idx, des_ax) in des_axes.iter().enumerate() {
-                if des_ax.scale().is_shared() {
+                if des_ax.scale().is_shared()
+                    || des_plots.or_outer_shared(or, plt_idx, ax_idx).is_some()
+                {
                     continue;
                 }
 
@@ -630,7 +1167,8 @@ where
                 // If Series::x/y_axis returns None, it refers implicitly to ax_idx == 0.
 
                 // We also have to collect data bounds of series that refer to a shared axis
-                // referring explicitly to `des_ax`. This is done in the inner loop with `des_ax2`.
+                // referring explicitly to `des_ax`, or implicitly through the figure-level
+                // shared axes. This is done in the inner loop with `des_ax2`.
 
                 let matcher = series::AxisMatcher {
                     plt_idx,
@@ -643,21 +1181,39 @@ where
                 for (plt_idx2, des_plot2) in des_plots.iter().enumerate() {
                     let Some(des_plot2) = des_plot2 else { continue };
                     let data = datas[plt_idx2].as_ref().unwrap();
-                    let series = &data.series;
-                    bounds = Series::unite_bounds(or, series, bounds, &matcher, plt_idx2)?;
+                    let hidden_bounds = des_plot2.hidden_series_bounds();
+                    let series: Vec<&Series> = data
+                        .series
+                        .iter()
+                        .filter(|s| hidden_bounds || s.visible())
+                        .collect();
+                    let series = series.iter().copied();
+                    bounds = Series::unite_bounds(or, series.clone(), bounds, &matcher, plt_idx2)?;
 
                     for (ax_idx2, des_ax2) in des_plot2.or_axes(or).iter().enumerate() {
-                        if let des::axis::Scale::Shared(ax_ref2) = des_ax2.scale() {
-                            if matcher.matches_ref(ax_ref2, plt_idx2)? {
-                                let matcher = series::AxisMatcher {
-                                    plt_idx: plt_idx2,
-                                    ax_idx: ax_idx2,
-                                    id: des_ax2.id(),
-                                    title: des_ax2.title().map(|t| t.text()),
-                                };
-                                bounds =
-                                    Series::unite_bounds(or, series, bounds, &matcher, plt_idx2)?;
+                        let matches = match des_ax2.scale() {
+                            des::axis::Scale::Shared(ax_ref2) => {
+                                matcher.matches_ref(ax_ref2, plt_idx2)?
+                            }
+                            _ => {
+                                des_plots.or_outer_shared(or, plt_idx2, ax_idx2)
+                                    == Some(fig_ax_idx0 + ax_idx)
                             }
+                        };
+                        if matches {
+                            let matcher = series::AxisMatcher {
+                                plt_idx: plt_idx2,
+                                ax_idx: ax_idx2,
+                                id: des_ax2.id(),
+                                title: des_ax2.title().map(|t| t.text()),
+                            };
+                            bounds = Series::unite_bounds(
+                                or,
+                                series.clone(),
+                                bounds,
+                                &matcher,
+                                plt_idx2,
+                            )?;
                         }
                     }
                 }
@@ -674,23 +1230,26 @@ where
                 // spine is drawn by axis:
                 //  - when it is off plot area
                 //  - when it is in plot area, but not a boxed plot
+                let side = Side::from_or_des_side(or, des_ax.side());
                 let spine = match (off_plot_area, des_plot.border()) {
                     (true, _) => des_plot.border().cloned(),
                     (false, Some(des::plot::Border::Box(_))) => None,
                     (false, Some(border)) => Some(border.clone()),
                     (false, None) => None,
-                };
+                }
+                .and_then(|border| styled_spine(border, side.spine(des_plot.spines())));
 
+                let size_along = plot_size_along(plt_idx);
                 let ax = self.setup_axis(
                     des_ax,
                     &bounds,
-                    Side::from_or_des_side(or, des_ax.side()),
+                    side,
                     size_along,
                     &datas[plt_idx].as_ref().unwrap().insets,
                     None,
                     spine,
                 )?;
-                ax_infos[fig_ax_idx0 + ax_idx] = Some((bounds, ax.scale().clone()));
+                ax_infos[fig_ax_idx0 + ax_idx] = Some((bounds, ax.scale().clone(), size_along));
                 axes[ax_idx] = Some(ax);
             }
 
@@ -713,9 +1272,15 @@ where
             let mut opposite_off_plot = false;
 
             for (ax_idx, des_ax) in des_axes.iter().enumerate() {
-                let des::axis::Scale::Shared(ax_ref) = des_ax.scale() else {
-                    continue;
+                let outer_shared = des_plots
+                    .or_outer_shared(or, plt_idx, ax_idx)
+                    .map(|fig_ax_idx| des_ax.to_outer_shared(fig_ax_idx));
+                let des_ax = match (des_ax.scale(), outer_shared.as_ref()) {
+                    (des::axis::Scale::Shared(..), _) => des_ax,
+                    (_, Some(outer_shared)) => outer_shared,
+                    _ => continue,
                 };
+                let ax_ref = des_ax.scale().shared_ref().unwrap();
                 let (fig_ax_idx, _) = des_plots
                     .or_find_axis(or, ax_ref, plt_idx)
                     .ok_or_else(|| Error::UnknownAxisRef(ax_ref.clone()))?;
@@ -724,6 +1289,14 @@ where
                     .as_ref()
                     .ok_or_else(|| Error::IllegalAxisRef(ax_ref.clone()))?;
 
+                // the coordinate mapping is shared, so the plot areas must have the same size
+                let size_along = plot_size_along(plt_idx);
+                if size_along != info.2 {
+                    return Err(Error::InconsistentDesign(format!(
+                        "Axis shared with {ax_ref:?} is in a plot of a different size"
+                    )));
+                }
+
                 let off_plot = match des_ax.side() {
                     des::axis::Side::Main => &mut main_off_plot,
                     des::axis::Side::Opposite => &mut opposite_off_plot,
@@ -734,17 +1307,19 @@ where
                 // spine is drawn by axis:
                 //  - when it is off plot area
                 //  - when it is in plot area, but not a boxed plot
+                let side = Side::from_or_des_side(or, des_ax.side());
                 let spine = match (off_plot_area, des_plot.border()) {
                     (true, _) => des_plot.border().cloned(),
                     (false, Some(des::plot::Border::Box(_))) => None,
                     (false, Some(border)) => Some(border.clone()),
                     (false, None) => None,
-                };
+                }
+                .and_then(|border| styled_spine(border, side.spine(des_plot.spines())));
 
                 let axis = self.setup_axis(
                     des_ax,
                     &info.0,
-                    Side::from_or_des_side(or, des_ax.side()),
+                    side,
                     size_along,
                     &datas[plt_idx].as_ref().unwrap().insets,
                     Some(info.1.clone()),
@@ -757,19 +1332,96 @@ where
     }
 }
 
+/// Apply the style of a spine to the border drawn by an axis.
+/// Returns `None` if the spine is hidden.
+fn styled_spine(border: des::plot::Border, spine: &des::plot::Spine) -> Option<des::plot::Border> {
+    if !spine.visible {
+        return None;
+    }
+    let border = match border {
+        des::plot::Border::Box(bb) => des::plot::Border::Box(des::plot::BorderBox {
+            line: spine.stroke(&bb.line),
+            ..bb
+        }),
+        des::plot::Border::Axis(line) => des::plot::Border::Axis(spine.stroke(&line)),
+        des::plot::Border::AxisArrow(arrow) => des::plot::Border::AxisArrow(des::plot::AxisArrow {
+            line: spine.stroke(&arrow.line),
+            ..arrow
+        }),
+    };
+    Some(border)
+}
+
+/// The axis bounds of a linear axis that doesn't share its scale with other axes
+fn lin_unshared_bounds(axis: &Axis) -> Result<NumBounds, Error> {
+    if Arc::strong_count(axis.scale()) > 1 {
+        return Err(Error::InconsistentDesign(
+            "Aspect lock is not supported with shared axes".into(),
+        ));
+    }
+    let scale = axis.scale().read().unwrap();
+    match &*scale {
+        AxisScale::Num {
+            des_scale: des::axis::Scale::Auto | des::axis::Scale::Linear(..),
+            cm,
+            ..
+        } => Ok(cm.axis_bounds().as_num().unwrap()),
+        _ => Err(Error::InconsistentDesign(
+            "Aspect lock is only supported with linear axes".into(),
+        )),
+    }
+}
+
+/// Scale of the axis of a zoomed inset, covering `range` with the same kind of scale than `axis`
+fn zoomed_scale(axis: &Axis, range: (f64, f64)) -> Result<des::axis::Scale, Error> {
+    let scale = axis.scale().read().unwrap();
+    match &*scale {
+        AxisScale::Num {
+            des_scale: des::axis::Scale::Log(log),
+            ..
+        } => Ok(des::axis::LogScale::new(log.base, range.into()).into()),
+        AxisScale::Num { .. } => Ok(des::axis::Scale::Linear(range.into())),
+        AxisScale::Cat { .. } => Err(Error::InconsistentDesign(
+            "Zoomed insets are only supported with numerical axes".into(),
+        )),
+    }
+}
+
+/// Data bounds, scale and size along the axis of an axis owning its scale
+type ScaleInfo = (Bounds, Arc<RwLock<AxisScale>>, f32);
+
+/// Distribute `total` size into `n` parts, proportionally to `ratios` if any, equally otherwise
+fn distribute_size(total: f32, n: u32, ratios: Option<&[f32]>) -> Vec<f32> {
+    match ratios {
+        Some(ratios) => {
+            let sum: f32 = ratios.iter().sum();
+            ratios.iter().map(|r| total * r / sum).collect()
+        }
+        None => vec![total / n as f32; n as usize],
+    }
+}
+
+/// Call `f` for each series of `plot` that can have a legend entry,
+/// with the index of the plot series it belongs to
 pub fn for_each_series<F>(plot: &des::Plot, mut f: F) -> Result<(), Error>
 where
-    F: FnMut(&dyn SeriesExt) -> Result<(), Error>,
+    F: FnMut(usize, &dyn SeriesExt) -> Result<(), Error>,
 {
-    for s in plot.series() {
+    for (idx, s) in plot.series().iter().enumerate() {
         match &s {
-            des::Series::Line(line) => f(line)?,
-            des::Series::Scatter(scatter) => f(scatter)?,
-            des::Series::Histogram(hist) => f(hist)?,
-            des::Series::Bars(bars) => f(bars)?,
+            des::Series::Line(line) => f(idx, line)?,
+            des::Series::Scatter(scatter) => f(idx, scatter)?,
+            des::Series::Histogram(hist) => f(idx, hist)?,
+            des::Series::Hist2d(hist) => f(idx, hist)?,
+            des::Series::Bars(bars) => f(idx, bars)?,
             des::Series::BarsGroup(bars_group) => {
                 for bs in bars_group.series() {
-                    f(bs)?
+                    f(idx, bs)?
+                }
+            }
+            des::Series::AreasGroup(areas_group) => {
+                for s in areas_group.series() {
+                    f(idx, s)?
                 }
             }
         }
@@ -777,42 +1429,33 @@ where
     Ok(())
 }
 
-fn plot_insets(plot: &des::Plot) -> geom::Padding {
+fn plot_insets(plot: &des::Plot, series: &[Series]) -> geom::Padding {
     match plot.insets() {
         Some(&des::plot::Insets::Fixed(x, y)) => geom::Padding::Center { v: y, h: x },
-        Some(des::plot::Insets::Auto) => auto_insets(plot),
+        Some(des::plot::Insets::Auto) => auto_insets(series),
         None => geom::Padding::Even(0.0),
     }
 }
 
-fn auto_insets(plot: &des::Plot) -> geom::Padding {
-    for s in plot.series() {
-        match s {
-            des::Series::Histogram(..) => return defaults::PLOT_VER_BARS_AUTO_INSETS,
-            des::Series::Bars(..) => return defaults::PLOT_VER_BARS_AUTO_INSETS,
-            des::Series::BarsGroup(bg) if bg.orientation().is_vertical() => {
-                return defaults::PLOT_VER_BARS_AUTO_INSETS;
-            }
-            des::Series::BarsGroup(bg) if bg.orientation().is_horizontal() => {
-                return defaults::PLOT_HOR_BARS_AUTO_INSETS;
-            }
-            _ => (),
-        }
+fn auto_insets(series: &[Series]) -> geom::Padding {
+    match series.iter().find_map(|s| s.bars_orientation()) {
+        Some(des::series::BarsOrientation::Vertical) => defaults::PLOT_VER_BARS_AUTO_INSETS,
+        Some(des::series::BarsOrientation::Horizontal) => defaults::PLOT_HOR_BARS_AUTO_INSETS,
+        None => defaults::PLOT_XY_AUTO_INSETS,
     }
-    defaults::PLOT_XY_AUTO_INSETS
 }
 
-fn x_plot_padding(side: des::axis::Side) -> f32 {
+fn x_plot_padding(padding: &geom::Padding, side: des::axis::Side) -> f32 {
     match side {
-        des::axis::Side::Main => missing_params::PLOT_PADDING.bottom(),
-        des::axis::Side::Opposite => missing_params::PLOT_PADDING.top(),
+        des::axis::Side::Main => padding.bottom(),
+        des::axis::Side::Opposite => padding.top(),
     }
 }
 
-fn y_plot_padding(side: des::axis::Side) -> f32 {
+fn y_plot_padding(padding: &geom::Padding, side: des::axis::Side) -> f32 {
     match side {
-        des::axis::Side::Main => missing_params::PLOT_PADDING.left(),
-        des::axis::Side::Opposite => missing_params::PLOT_PADDING.right(),
+        des::axis::Side::Main => padding.left(),
+        des::axis::Side::Opposite => padding.right(),
     }
 }
 
@@ -845,26 +1488,78 @@ impl Plots {
         Ok(())
     }
 
-    pub fn draw<S>(&self, surface: &mut S, style: &Style)
+    /// Recompute the series bounds from `data_source` and re-map the series data.
+    /// Returns `true` if the bounds of any series overflow its axes bounds,
+    /// or if the labels of any series are outdated.
+    pub fn update_data<D>(&mut self, data_source: &D) -> Result<bool, Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        let mut overflow = false;
+        for plot in self.plots.iter_mut() {
+            if let Some(plot) = plot.as_mut() {
+                overflow |= plot.update_data(data_source)?;
+            }
+        }
+        Ok(overflow)
+    }
+
+    /// Draw the plots on the surface.
+    /// If `region` is given, series entirely outside of it are skipped.
+    pub fn draw<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
         self.plots
             .iter()
             .filter_map(Option::as_ref)
-            .for_each(|p| p.draw(surface, style));
+            .try_for_each(|p| p.draw(surface, style, region))
     }
 }
 
 impl Plot {
     fn update_series_data<D>(&mut self, data_source: &D) -> Result<(), Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        self.map_series_data(data_source)?;
+        for inset in self.zoom_insets.iter_mut() {
+            inset.plot.update_series_data(data_source)?;
+        }
+        Ok(())
+    }
+
+    fn update_data<D>(&mut self, data_source: &D) -> Result<bool, Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        for series in self.series.iter_mut() {
+            series.update_bounds(data_source)?;
+        }
+        // the series of zoomed insets overflow their axes by design
+        for inset in self.zoom_insets.iter_mut() {
+            inset.plot.update_data(data_source)?;
+        }
+        self.map_series_data(data_source)
+    }
+
+    /// Map the series data to the plot area.
+    /// Returns `true` if the bounds of any series overflow its axes bounds,
+    /// or if the labels of any series are outdated.
+    fn map_series_data<D>(&mut self, data_source: &D) -> Result<bool, Error>
     where
         D: data::Source + ?Sized,
     {
         let Some(axes) = &self.axes else {
-            return Ok(());
+            return Ok(false);
         };
 
+        let mut overflow = false;
         for series in self.series.iter_mut() {
             let (x_ax_ref, y_ax_ref) = series.axes();
             let x = axes.or_find(Orientation::X, x_ax_ref)?;
@@ -879,50 +1574,79 @@ impl Plot {
                 y: &*y_cm,
             };
 
+            overflow |= !series.fits_in(&cm) || series.labels_outdated();
             series.update_data(data_source, &self.rect, &cm)?;
         }
-        Ok(())
+        Ok(overflow)
     }
 
-    fn draw<S>(&self, surface: &mut S, style: &Style)
+    fn draw<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
-        self.draw_background(surface, style);
+        self.draw_background(surface, style)?;
+        if let Some((transform, title)) = &self.title {
+            title.draw(surface, style, Some(transform))?;
+        }
         let Some(axes) = &self.axes else {
-            self.draw_border_box(surface, style);
-            return;
+            self.draw_border_box(surface, style)?;
+            return self.draw_panel_label(surface, style);
         };
 
-        axes.draw_grids(surface, style, &self.rect);
-
-        self.draw_annotations(surface, style, axes, annot::ZPos::BelowSeries);
-        self.draw_series(surface, style);
-        self.draw_annotations(surface, style, axes, annot::ZPos::AboveSeries);
+        self.draw_layers(surface, style, axes, region)?;
 
-        axes.draw(surface, style, &self.rect);
-        self.draw_border_box(surface, style);
+        axes.draw(surface, style, &self.rect)?;
+        self.draw_border_box(surface, style)?;
 
         if let Some((top_left, leg)) = self.legend.as_ref() {
-            leg.draw(surface, style, top_left);
+            leg.draw(surface, style, top_left)?;
+        }
+
+        for inset in self.zoom_insets.iter() {
+            inset.draw(surface, style, region)?;
         }
+        self.draw_panel_label(surface, style)
     }
 
-    fn draw_background<S>(&self, surface: &mut S, style: &Style)
+    fn draw_panel_label<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        if let Some((transform, label)) = &self.panel_label {
+            label.draw(surface, style, Some(transform))?;
+        }
+        Ok(())
+    }
+
+    fn draw_background<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
         if let Some(fill) = &self.fill {
-            surface.draw_rect(&render::Rect {
-                rect: self.rect,
-                fill: Some(fill.as_paint(style)),
-                stroke: None,
-                transform: None,
-            });
+            let radius = match self.border.as_ref() {
+                Some(des::plot::Border::Box(bb)) if self.spines.is_default() => bb.radius,
+                _ => 0.0,
+            };
+            super::draw_rounded_rect(
+                surface,
+                &render::Rect {
+                    rect: self.rect,
+                    fill: Some(fill.as_paint(style)),
+                    stroke: None,
+                    transform: None,
+                },
+                radius,
+            )?;
         }
+        Ok(())
     }
 
-    fn draw_border_box<S>(&self, surface: &mut S, style: &Style)
+    fn draw_border_box<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
@@ -930,76 +1654,249 @@ impl Plot {
         // otherwise, axes draw the border as spines
         let rect = self.rect;
         match self.border.as_ref() {
-            Some(des::plot::Border::Box(stroke)) => {
-                surface.draw_rect(&render::Rect {
-                    rect,
-                    fill: None,
-                    stroke: Some(stroke.as_stroke(style)),
-                    transform: None,
-                });
+            Some(border @ des::plot::Border::Box(bb)) if !self.spines.is_default() => {
+                // customized sides are drawn separately, without rounded corners
+                for side in [Side::Top, Side::Right, Side::Bottom, Side::Left] {
+                    let spine = side.spine(&self.spines);
+                    if !spine.visible {
+                        continue;
+                    }
+                    let path = side.spine_path(&rect, border);
+                    let line = spine.stroke(&bb.line);
+                    let rpath = render::Path {
+                        path: &path,
+                        fill: None,
+                        stroke: Some(line.as_stroke(style)),
+                        transform: None,
+                    };
+                    surface.draw_path(&rpath)?;
+                }
+            }
+            Some(des::plot::Border::Box(bb)) => {
+                super::draw_rounded_rect(
+                    surface,
+                    &render::Rect {
+                        rect,
+                        fill: None,
+                        stroke: Some(bb.line.as_stroke(style)),
+                        transform: None,
+                    },
+                    bb.radius,
+                )?;
             }
             _ => (),
         }
+        Ok(())
+    }
+
+    /// Draw the grids, series and annotations by increasing z-order
+    fn draw_layers<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        axes: &Axes,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        let mut layers = Vec::new();
+        for axis in axes.x.iter().chain(axes.y.iter()) {
+            layers.push((axis.grid_zorder(), Layer::MinorGrid(axis)));
+        }
+        for axis in axes.x.iter().chain(axes.y.iter()) {
+            layers.push((axis.grid_zorder(), Layer::MajorGrid(axis)));
+        }
+        for series in self.series.iter().filter(|s| s.visible()) {
+            layers.push((series.zorder(), Layer::Series(series)));
+        }
+        for annot in self.annots.iter() {
+            layers.push((annot.zorder(), Layer::Annot(annot)));
+        }
+        // stable sort: same z-order keeps the order above
+        layers.sort_by_key(|(zorder, _)| *zorder);
+
+        let mut i = 0;
+        while i < layers.len() {
+            match layers[i].1 {
+                Layer::MinorGrid(axis) => axis.draw_minor_grids(surface, style, &self.rect)?,
+                Layer::MajorGrid(axis) => axis.draw_major_grids(surface, style, &self.rect)?,
+                Layer::Annot(annot) => annot.draw(surface, style, axes, &self.rect)?,
+                Layer::Series(_) => {
+                    // successive series are drawn within the same clip
+                    let end = layers[i..]
+                        .iter()
+                        .position(|(_, l)| !matches!(l, Layer::Series(_)))
+                        .map_or(layers.len(), |n| i + n);
+                    let series = layers[i..end].iter().filter_map(|(_, l)| match l {
+                        Layer::Series(series) => Some(*series),
+                        _ => None,
+                    });
+                    self.draw_series(surface, style, series, region)?;
+                    i = end;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        Ok(())
     }
 
-    fn draw_series<S>(&self, surface: &mut S, style: &Style)
+    fn draw_series<'a, S, I>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        series: I,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
+        I: Iterator<Item = &'a Series>,
     {
         let rect = self.rect;
-        let series = &self.series;
 
+        let (path, transform) = match &self.clip {
+            des::plot::Clip::Rect => (rect.to_path(), None),
+            des::plot::Clip::RoundedRect(radius) => (rect.to_rounded_path(*radius), None),
+            des::plot::Clip::Custom(path) => (
+                path.clone(),
+                Some(geom::Transform::from_translate(rect.x(), rect.y())),
+            ),
+        };
         let clip = render::Clip {
-            rect: &rect,
-            transform: None,
+            path: &path,
+            transform: transform.as_ref(),
+        };
+        // a surface unable to clip still draws the series, unclipped
+        let clipped = match surface.push_clip(&clip) {
+            Ok(()) => true,
+            Err(render::Error::Unsupported(_)) => false,
+            Err(err) => return Err(err),
         };
-        surface.push_clip(&clip);
 
-        for series in series.iter() {
-            series.draw(surface, style);
+        let res = series
+            .filter(|series| {
+                region
+                    .is_none_or(|region| series.bbox().is_some_and(|bbox| bbox.intersects(region)))
+            })
+            .try_for_each(|series| series.draw(surface, style));
+        // the clip is popped even if drawing failed, to leave the surface balanced
+        if clipped {
+            surface.pop_clip()?;
         }
-        surface.pop_clip();
+        res
     }
+}
 
-    fn draw_annotations<S>(&self, surface: &mut S, style: &Style, axes: &Axes, zpos: annot::ZPos)
+impl ZoomInset {
+    fn draw<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
-        for annot in self.annots.iter() {
-            if annot.zpos() == zpos {
-                annot.draw(surface, style, axes, &self.rect);
+        let stroke = self.line.as_stroke(style);
+        surface.draw_rect(&render::Rect {
+            rect: self.region,
+            fill: None,
+            stroke: Some(stroke),
+            transform: None,
+        })?;
+
+        if self.connectors {
+            let mut path = geom::PathBuilder::new();
+            for (p1, p2) in zoom_connectors(&self.region, &self.plot.rect) {
+                path.move_to(p1.x, p1.y);
+                path.line_to(p2.x, p2.y);
+            }
+            if let Some(path) = path.finish() {
+                surface.draw_path(&render::Path {
+                    path: &path,
+                    fill: None,
+                    stroke: Some(stroke),
+                    transform: None,
+                })?;
             }
         }
+
+        self.plot.draw(surface, style, region)
     }
 }
 
-impl Axes {
-    fn draw_grids<S>(&self, surface: &mut S, style: &Style, rect: &geom::Rect)
-    where
-        S: render::Surface,
-    {
-        for axis in self.x.iter() {
-            axis.draw_minor_grids(surface, style, rect);
-        }
-        for axis in self.y.iter() {
-            axis.draw_minor_grids(surface, style, rect);
-        }
-        for axis in self.x.iter() {
-            axis.draw_major_grids(surface, style, rect);
-        }
-        for axis in self.y.iter() {
-            axis.draw_major_grids(surface, style, rect);
-        }
+/// The connector lines between a zoomed region and its inset.
+/// The corners facing each other are connected, such that the lines
+/// don't cross the region nor the inset. No line is returned if they overlap.
+fn zoom_connectors(region: &geom::Rect, inset: &geom::Rect) -> Vec<(geom::Point, geom::Point)> {
+    let dx = if inset.left() > region.right() {
+        1
+    } else if inset.right() < region.left() {
+        -1
+    } else {
+        0
+    };
+    let dy = if inset.top() > region.bottom() {
+        1
+    } else if inset.bottom() < region.top() {
+        -1
+    } else {
+        0
+    };
+    match (dx, dy) {
+        (0, 0) => vec![],
+        (1, 0) => vec![
+            (region.top_right(), inset.top_left()),
+            (region.bottom_right(), inset.bottom_left()),
+        ],
+        (-1, 0) => vec![
+            (region.top_left(), inset.top_right()),
+            (region.bottom_left(), inset.bottom_right()),
+        ],
+        (0, 1) => vec![
+            (region.bottom_left(), inset.top_left()),
+            (region.bottom_right(), inset.top_right()),
+        ],
+        (0, -1) => vec![
+            (region.top_left(), inset.bottom_left()),
+            (region.top_right(), inset.bottom_right()),
+        ],
+        // diagonal placement: the corners on the outline of both rectangles
+        (dx, dy) if dx == dy => vec![
+            (region.top_right(), inset.top_right()),
+            (region.bottom_left(), inset.bottom_left()),
+        ],
+        _ => vec![
+            (region.top_left(), inset.top_left()),
+            (region.bottom_right(), inset.bottom_right()),
+        ],
     }
+}
+
+/// An element of the plot area, drawn according to its z-order
+#[derive(Debug, Clone, Copy)]
+enum Layer<'a> {
+    MinorGrid(&'a Axis),
+    MajorGrid(&'a Axis),
+    Series(&'a Series),
+    Annot(&'a Annot),
+}
 
-    fn draw<S>(&self, surface: &mut S, style: &Style, plot_rect: &geom::Rect)
+impl Axes {
+    fn draw<S>(
+        &self,
+        surface: &mut S,
+        style: &Style,
+        plot_rect: &geom::Rect,
+    ) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
-        self.draw_side(surface, style, &self.x, Side::Top, plot_rect);
-        self.draw_side(surface, style, &self.y, Side::Right, plot_rect);
-        self.draw_side(surface, style, &self.x, Side::Bottom, plot_rect);
-        self.draw_side(surface, style, &self.y, Side::Left, plot_rect);
+        self.draw_side(surface, style, &self.x, Side::Top, plot_rect)?;
+        self.draw_side(surface, style, &self.y, Side::Right, plot_rect)?;
+        self.draw_side(surface, style, &self.x, Side::Bottom, plot_rect)?;
+        self.draw_side(surface, style, &self.y, Side::Left, plot_rect)
     }
 
     fn draw_side<S>(
@@ -1009,23 +1906,31 @@ impl Axes {
         axes: &[Axis],
         side: Side,
         plot_rect: &geom::Rect,
-    ) where
+    ) -> Result<(), render::Error>
+    where
         S: render::Surface,
     {
         let mut rect = *plot_rect;
+        let mut shift_across = 0.0;
+        let mut cnt = 0;
         for axis in axes.iter() {
             if axis.side() == side {
-                let shift = axis.draw(surface, style, &rect)
-                    + missing_params::AXIS_MARGIN
-                    + missing_params::AXIS_SPINE_WIDTH;
-                rect = match side {
-                    Side::Top => rect.shifted_top_side(-shift),
-                    Side::Right => rect.shifted_right_side(shift),
-                    Side::Bottom => rect.shifted_bottom_side(shift),
-                    Side::Left => rect.shifted_left_side(-shift),
-                };
+                if cnt > 0 {
+                    shift_across += axis.spacing();
+                }
+                cnt += 1;
+                let size = axis.draw(surface, style, &rect)?;
+                shift_across += size;
+                rect = side.shifted_rect(&rect, size + axis.spacing());
             }
         }
+        // titles of the axes of the other side, moved to this side
+        for axis in axes.iter() {
+            if axis.side() != side && axis.title_side() == side {
+                shift_across = axis.draw_title(surface, style, plot_rect, shift_across)?;
+            }
+        }
+        Ok(())
     }
 }
 
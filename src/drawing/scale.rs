@@ -1,13 +1,13 @@
 use std::sync::Arc;
 
 use crate::drawing::axis;
-use crate::{data, des};
+use crate::{data, des, geom};
 
 /// Maps coordinates from data space to surface space.
 /// The surface space starts at zero for lowest displayed data and goes up for higher data.
 /// Typically, only one of the two map_coord_num or map_coord_cat should be implemented,
 /// depending on whether the scale is numerical or categorical.
-pub trait CoordMap: std::fmt::Debug {
+pub trait CoordMap: std::fmt::Debug + Send + Sync {
     fn axis_bounds(&self) -> axis::BoundsRef<'_>;
 
     fn map_coord(&self, sample: data::SampleRef) -> Option<f32> {
@@ -35,8 +35,24 @@ pub trait CoordMap: std::fmt::Debug {
         unimplemented!("Only for categorical scales");
     }
 
+    /// Inverse of [`map_coord_num`](Self::map_coord_num)
+    fn unmap_coord_num(&self, _pos: f32) -> f64 {
+        unimplemented!("Only for numerical scales");
+    }
+
     fn unmap_coord(&self, pos: f32) -> data::SampleRef<'_>;
 
+    /// Like [`unmap_coord`](Self::unmap_coord), but categorical scales snap
+    /// to the nearest category instead of returning null outside of the bins.
+    fn snap_coord(&self, pos: f32) -> data::SampleRef<'_> {
+        self.unmap_coord(pos)
+    }
+
+    /// Interval of data removed from a numerical scale, collapsed at a single position.
+    fn num_break(&self) -> Option<(f64, f64)> {
+        None
+    }
+
     fn create_view(&self, start: f32, end: f32) -> Arc<dyn CoordMap>;
 }
 
@@ -59,14 +75,15 @@ pub fn map_scale_coord_num(
     plot_size: f32,
     axis_bounds: &axis::NumBounds,
     insets: (f32, f32),
+    axis_break: Option<(f64, f64)>,
 ) -> Arc<dyn CoordMap> {
     match scale {
         des::axis::Scale::Auto | des::axis::Scale::Linear(des::axis::Range(None, None)) => {
-            Arc::new(LinCoordMap::new(plot_size, insets, *axis_bounds))
+            lin_or_broken_coord_map(plot_size, insets, *axis_bounds, axis_break)
         }
         des::axis::Scale::Linear(range) => {
             let (adj_nb, adj_insets) = adjusted_nb_insets(*range, axis_bounds, insets);
-            Arc::new(LinCoordMap::new(plot_size, adj_insets, adj_nb))
+            lin_or_broken_coord_map(plot_size, adj_insets, adj_nb, axis_break)
         }
         des::axis::Scale::Log(des::axis::LogScale { base, range }) => {
             let (adj_nb, adj_insets) = adjusted_nb_insets(*range, axis_bounds, insets);
@@ -76,6 +93,36 @@ pub fn map_scale_coord_num(
     }
 }
 
+/// Create a linear coordinate map spanning exactly `axis_bounds` over `plot_size`
+pub fn lin_coord_map(plot_size: f32, axis_bounds: axis::NumBounds) -> Arc<dyn CoordMap> {
+    Arc::new(LinCoordMap {
+        plot_size,
+        ab: axis_bounds,
+    })
+}
+
+/// Compute the size of a plot area displaying the `x` and `y` bounds, such that
+/// one data unit along Y is `aspect` times longer than one data unit along X.
+/// The returned size fits in `size` and is shrunk along one dimension only.
+pub fn aspect_locked_size(
+    x: axis::NumBounds,
+    y: axis::NumBounds,
+    size: geom::Size,
+    aspect: f64,
+) -> geom::Size {
+    // the shared units-per-pixel, expressed in X data units
+    let x_upp = x.span() / size.width() as f64;
+    let y_upp = y.span() / size.height() as f64 * aspect;
+    if x_upp > y_upp {
+        // X is the constraining dimension, Y is shrunk
+        let height = y.span() * aspect / x_upp;
+        geom::Size::new(size.width(), height as f32)
+    } else {
+        let width = x.span() / y_upp;
+        geom::Size::new(width as f32, size.height())
+    }
+}
+
 fn adjusted_nb_insets(
     range: des::axis::Range,
     nb: &axis::NumBounds,
@@ -121,10 +168,13 @@ impl CoordMap for LinCoordMap {
         ratio as f32 * self.plot_size
     }
 
-    fn unmap_coord(&self, pos: f32) -> data::SampleRef<'_> {
+    fn unmap_coord_num(&self, pos: f32) -> f64 {
         let ratio = pos as f64 / self.plot_size as f64;
-        let value = self.ab.start() + ratio * self.ab.span();
-        data::SampleRef::Num(value)
+        self.ab.start() + ratio * self.ab.span()
+    }
+
+    fn unmap_coord(&self, pos: f32) -> data::SampleRef<'_> {
+        data::SampleRef::Num(self.unmap_coord_num(pos))
     }
 
     fn axis_bounds(&self) -> axis::BoundsRef<'_> {
@@ -145,6 +195,113 @@ impl CoordMap for LinCoordMap {
     }
 }
 
+fn lin_or_broken_coord_map(
+    plot_size: f32,
+    insets: (f32, f32),
+    ab: axis::NumBounds,
+    axis_break: Option<(f64, f64)>,
+) -> Arc<dyn CoordMap> {
+    match axis_break {
+        Some((low, high)) if ab.start() < low && low < high && high < ab.end() => {
+            Arc::new(BrokenCoordMap::new(plot_size, insets, ab, (low, high)))
+        }
+        _ => Arc::new(LinCoordMap::new(plot_size, insets, ab)),
+    }
+}
+
+/// Linear coordinate map from which an interval of data is removed.
+/// The data is compressed by removing the interval, and the compressed data
+/// is mapped linearly. The whole interval maps to the seam position.
+#[derive(Debug, Clone, Copy)]
+struct BrokenCoordMap {
+    // linear map of the compressed data
+    lin: LinCoordMap,
+    low: f64,
+    high: f64,
+    // real data bounds, including the removed interval
+    ab: axis::NumBounds,
+}
+
+impl BrokenCoordMap {
+    fn new(plot_size: f32, insets: (f32, f32), ab: axis::NumBounds, brk: (f64, f64)) -> Self {
+        let (low, high) = brk;
+        let compressed = axis::NumBounds::from((ab.start(), ab.end() - (high - low)));
+        Self::from_lin(LinCoordMap::new(plot_size, insets, compressed), low, high)
+    }
+
+    fn from_lin(lin: LinCoordMap, low: f64, high: f64) -> Self {
+        let mut cm = BrokenCoordMap {
+            lin,
+            low,
+            high,
+            ab: lin.ab,
+        };
+        cm.ab = (cm.expand(lin.ab.start()), cm.expand(lin.ab.end())).into();
+        cm
+    }
+
+    fn compress(&self, x: f64) -> f64 {
+        if x <= self.low {
+            x
+        } else if x >= self.high {
+            x - (self.high - self.low)
+        } else {
+            self.low
+        }
+    }
+
+    fn expand(&self, x: f64) -> f64 {
+        if x <= self.low {
+            x
+        } else {
+            x + (self.high - self.low)
+        }
+    }
+}
+
+impl CoordMap for BrokenCoordMap {
+    fn map_coord_num(&self, x: f64) -> f32 {
+        self.lin.map_coord_num(self.compress(x))
+    }
+
+    fn unmap_coord_num(&self, pos: f32) -> f64 {
+        self.expand(self.lin.unmap_coord_num(pos))
+    }
+
+    fn unmap_coord(&self, pos: f32) -> data::SampleRef<'_> {
+        data::SampleRef::Num(self.unmap_coord_num(pos))
+    }
+
+    fn axis_bounds(&self) -> axis::BoundsRef<'_> {
+        self.ab.into()
+    }
+
+    fn num_break(&self) -> Option<(f64, f64)> {
+        Some((self.low, self.high))
+    }
+
+    fn create_view(&self, start: f32, end: f32) -> Arc<dyn CoordMap> {
+        let lin = LinCoordMap {
+            plot_size: self.lin.plot_size,
+            ab: (
+                self.lin.unmap_coord_num(start),
+                self.lin.unmap_coord_num(end),
+            )
+                .into(),
+        };
+        if lin.ab.start() < self.low && self.low < lin.ab.end() {
+            Arc::new(BrokenCoordMap::from_lin(lin, self.low, self.high))
+        } else {
+            // the seam is out of the view
+            let ab = (self.expand(lin.ab.start()), self.expand(lin.ab.end())).into();
+            Arc::new(LinCoordMap {
+                plot_size: lin.plot_size,
+                ab,
+            })
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct LogCoordMap {
     base: f64,
@@ -187,13 +344,16 @@ impl CoordMap for LogCoordMap {
         ratio as f32 * self.plot_size
     }
 
-    fn unmap_coord(&self, pos: f32) -> data::SampleRef<'_> {
+    fn unmap_coord_num(&self, pos: f32) -> f64 {
         let start = self.ab.start().log(self.base);
         let end = self.ab.end().log(self.base);
         let ratio = pos as f64 / self.plot_size as f64;
         let log_value = start + ratio * (end - start);
-        let value = self.base.powf(log_value);
-        data::SampleRef::Num(value)
+        self.base.powf(log_value)
+    }
+
+    fn unmap_coord(&self, pos: f32) -> data::SampleRef<'_> {
+        data::SampleRef::Num(self.unmap_coord_num(pos))
     }
 
     fn axis_bounds(&self) -> axis::BoundsRef<'_> {
@@ -224,7 +384,7 @@ mod tests {
     fn test_map_scale_coord_linear_auto() {
         let linear_auto = des::axis::Scale::Linear(des::axis::Range::AUTO);
 
-        let map = map_scale_coord_num(&linear_auto, 100.0, &(0.0, 10.0).into(), (0.0, 0.0));
+        let map = map_scale_coord_num(&linear_auto, 100.0, &(0.0, 10.0).into(), (0.0, 0.0), None);
         assert_near!(rel, map.map_coord_num(0.0), 0.0);
         assert_near!(rel, map.map_coord_num(5.0), 50.0);
         assert_near!(rel, map.map_coord_num(10.0), 100.0);
@@ -234,7 +394,7 @@ mod tests {
             axis::BoundsRef::Num((0.0, 10.0).into())
         );
 
-        let map = map_scale_coord_num(&linear_auto, 110.0, &(0.0, 10.0).into(), (10.0, 0.0));
+        let map = map_scale_coord_num(&linear_auto, 110.0, &(0.0, 10.0).into(), (10.0, 0.0), None);
         assert_near!(rel, map.map_coord_num(0.0), 10.0);
         assert_near!(rel, map.map_coord_num(5.0), 60.0);
         assert_near!(rel, map.map_coord_num(10.0), 110.0);
@@ -244,7 +404,7 @@ mod tests {
             axis::BoundsRef::Num((-1.0, 10.0).into())
         );
 
-        let map = map_scale_coord_num(&linear_auto, 120.0, &(0.0, 10.0).into(), (10.0, 10.0));
+        let map = map_scale_coord_num(&linear_auto, 120.0, &(0.0, 10.0).into(), (10.0, 10.0), None);
         assert_near!(rel, map.map_coord_num(0.0), 10.0);
         assert_near!(rel, map.map_coord_num(5.0), 60.0);
         assert_near!(rel, map.map_coord_num(10.0), 110.0);
@@ -255,6 +415,102 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_map_scale_coord_broken() {
+        let linear_auto = des::axis::Scale::Linear(des::axis::Range::AUTO);
+        let axis_bounds = (0.0, 110.0).into();
+
+        // 10..100 is removed, leaving 20 data units over 100px
+        let map = map_scale_coord_num(
+            &linear_auto,
+            100.0,
+            &axis_bounds,
+            (0.0, 0.0),
+            Some((10.0, 100.0)),
+        );
+        assert_eq!(map.num_break(), Some((10.0, 100.0)));
+        assert_near!(rel, map.map_coord_num(0.0), 0.0);
+        assert_near!(rel, map.map_coord_num(5.0), 25.0);
+        assert_near!(rel, map.map_coord_num(10.0), 50.0);
+        assert_near!(rel, map.map_coord_num(50.0), 50.0);
+        assert_near!(rel, map.map_coord_num(100.0), 50.0);
+        assert_near!(rel, map.map_coord_num(105.0), 75.0);
+        assert_near!(rel, map.map_coord_num(110.0), 100.0);
+        assert_near!(rel, map.unmap_coord_num(75.0), 105.0);
+        assert_near!(rel, map.unmap_coord_num(25.0), 5.0);
+        assert_near!(
+            rel,
+            map.axis_bounds(),
+            axis::BoundsRef::Num((0.0, 110.0).into())
+        );
+
+        // insets extend the bounds on both sides of the break
+        let map = map_scale_coord_num(
+            &linear_auto,
+            120.0,
+            &axis_bounds,
+            (10.0, 10.0),
+            Some((10.0, 100.0)),
+        );
+        assert_near!(rel, map.map_coord_num(0.0), 10.0);
+        assert_near!(rel, map.map_coord_num(110.0), 110.0);
+        assert_near!(
+            rel,
+            map.axis_bounds(),
+            axis::BoundsRef::Num((-2.0, 112.0).into())
+        );
+
+        // break outside of the bounds is ignored
+        let map = map_scale_coord_num(
+            &linear_auto,
+            100.0,
+            &axis_bounds,
+            (0.0, 0.0),
+            Some((100.0, 200.0)),
+        );
+        assert!(map.num_break().is_none());
+
+        // views keep the break only if the seam is visible
+        let map = map_scale_coord_num(
+            &linear_auto,
+            100.0,
+            &axis_bounds,
+            (0.0, 0.0),
+            Some((10.0, 100.0)),
+        );
+        let view = map.create_view(25.0, 75.0);
+        assert_eq!(view.num_break(), Some((10.0, 100.0)));
+        assert_near!(rel, view.map_coord_num(5.0), 0.0);
+        assert_near!(rel, view.map_coord_num(105.0), 100.0);
+        let view = map.create_view(60.0, 100.0);
+        assert!(view.num_break().is_none());
+        assert_near!(
+            rel,
+            view.axis_bounds(),
+            axis::BoundsRef::Num((102.0, 110.0).into())
+        );
+    }
+
+    #[test]
+    fn test_aspect_locked_size() {
+        let size = geom::Size::new(200.0, 100.0);
+
+        // X is 10 units over 200px, Y must be 20px per unit
+        let sz = aspect_locked_size((0.0, 10.0).into(), (0.0, 2.0).into(), size, 1.0);
+        assert_near!(rel, sz.width(), 200.0);
+        assert_near!(rel, sz.height(), 40.0);
+
+        // Y is 10 units over 100px, X must be 10px per unit
+        let sz = aspect_locked_size((0.0, 5.0).into(), (0.0, 10.0).into(), size, 1.0);
+        assert_near!(rel, sz.width(), 50.0);
+        assert_near!(rel, sz.height(), 100.0);
+
+        // Y unit is twice as long as X unit
+        let sz = aspect_locked_size((0.0, 10.0).into(), (0.0, 2.0).into(), size, 2.0);
+        assert_near!(rel, sz.width(), 200.0);
+        assert_near!(rel, sz.height(), 80.0);
+    }
+
     #[test]
     fn test_map_scale_coord_log_auto() {
         let log_auto = des::axis::Scale::Log(des::axis::LogScale {
@@ -263,7 +519,7 @@ mod tests {
         });
         let axis_bounds = (1e-5, 1e5).into();
 
-        let map = map_scale_coord_num(&log_auto, 100.0, &axis_bounds, (0.0, 0.0));
+        let map = map_scale_coord_num(&log_auto, 100.0, &axis_bounds, (0.0, 0.0), None);
         assert_near!(rel, map.map_coord_num(1e-5), 0.0);
         assert_near!(rel, map.map_coord_num(1.0), 50.0);
         assert_near!(rel, map.map_coord_num(1e5), 100.0);
@@ -273,7 +529,7 @@ mod tests {
             axis::BoundsRef::Num((1e-5, 1e5).into())
         );
 
-        let map = map_scale_coord_num(&log_auto, 110.0, &axis_bounds, (10.0, 0.0));
+        let map = map_scale_coord_num(&log_auto, 110.0, &axis_bounds, (10.0, 0.0), None);
         assert_near!(rel, map.map_coord_num(1e-5), 10.0);
         assert_near!(rel, map.map_coord_num(1.0), 60.0);
         assert_near!(rel, map.map_coord_num(1e5), 110.0);
@@ -283,7 +539,7 @@ mod tests {
             axis::BoundsRef::Num((1e-6, 1e5).into())
         );
 
-        let map = map_scale_coord_num(&log_auto, 120.0, &axis_bounds, (10.0, 10.0));
+        let map = map_scale_coord_num(&log_auto, 120.0, &axis_bounds, (10.0, 10.0), None);
         assert_near!(rel, map.map_coord_num(1e-5), 10.0);
         assert_near!(rel, map.map_coord_num(1.0), 60.0);
         assert_near!(rel, map.map_coord_num(1e5), 110.0);
@@ -3,9 +3,11 @@ use scale::{CoordMap, CoordMapXy};
 
 use crate::drawing::plot::Orientation;
 use crate::drawing::{
-    Categories, ColumnExt, Error, F64ColumnExt, axis, legend, marker, plot_to_fig, scale,
+    Categories, ColumnExt, Error, F64ColumnExt, Text, axis, fit, legend, marker, plot_to_fig,
+    scale, smooth, ticks,
 };
-use crate::{Style, data, des, geom, render, style};
+use crate::text::{self, fontdb};
+use crate::{ColorU8, Style, data, des, geom, render, style};
 
 /// trait implemented by series, or any other item that
 /// has to populate the legend
@@ -15,10 +17,14 @@ pub trait SeriesExt {
 
 impl SeriesExt for des::series::Line {
     fn legend_entry(&self) -> Option<legend::Entry<'_>> {
+        let stroke = match self.smoothing_line() {
+            Some(stroke) if self.smoothing_only() => stroke,
+            _ => self.stroke(),
+        };
         self.name().map(|n| legend::Entry {
             label: n.as_ref(),
             font: None,
-            shape: legend::ShapeRef::Line(self.stroke()),
+            shape: legend::ShapeRef::Line(stroke),
         })
     }
 }
@@ -28,7 +34,11 @@ impl SeriesExt for des::series::Scatter {
         self.name().map(|n| legend::Entry {
             label: n.as_ref(),
             font: None,
-            shape: legend::ShapeRef::Marker(self.marker()),
+            shape: if self.legend_alpha() {
+                legend::ShapeRef::FadedMarker(self.marker(), self.alpha())
+            } else {
+                legend::ShapeRef::Marker(self.marker())
+            },
         })
     }
 }
@@ -38,17 +48,51 @@ impl SeriesExt for des::series::Histogram {
         self.name().map(|n| legend::Entry {
             label: n.as_ref(),
             font: None,
-            shape: legend::ShapeRef::Rect(&self.fill(), self.line()),
+            shape: legend::ShapeRef::Rect(*self.fill(), self.line()),
         })
     }
 }
 
+impl SeriesExt for des::series::Hist2d {
+    fn legend_entry(&self) -> Option<legend::Entry<'_>> {
+        // colors of a 2D histogram are not represented in the legend
+        None
+    }
+}
+
 impl SeriesExt for des::series::Bars {
+    fn legend_entry(&self) -> Option<legend::Entry<'_>> {
+        let fill = *self.fill();
+        let shape = match self.sign_colors() {
+            None => legend::ShapeRef::Rect(fill, self.line()),
+            Some((pos, neg)) => match self.sign_legend() {
+                des::series::BarsSignLegend::Both => legend::ShapeRef::SplitRect(
+                    fill_with_color(fill, pos),
+                    fill_with_color(fill, neg),
+                    self.line(),
+                ),
+                des::series::BarsSignLegend::Positive => {
+                    legend::ShapeRef::Rect(fill_with_color(fill, pos), self.line())
+                }
+                des::series::BarsSignLegend::Negative => {
+                    legend::ShapeRef::Rect(fill_with_color(fill, neg), self.line())
+                }
+            },
+        };
+        self.name().map(|n| legend::Entry {
+            label: n.as_ref(),
+            font: None,
+            shape,
+        })
+    }
+}
+
+impl SeriesExt for des::series::AreaSeries {
     fn legend_entry(&self) -> Option<legend::Entry<'_>> {
         self.name().map(|n| legend::Entry {
             label: n.as_ref(),
             font: None,
-            shape: legend::ShapeRef::Rect(self.fill(), self.line()),
+            shape: legend::ShapeRef::Rect(*self.fill(), self.line()),
         })
     }
 }
@@ -58,11 +102,18 @@ impl SeriesExt for des::series::BarSeries {
         self.name().map(|n| legend::Entry {
             label: n.as_ref(),
             font: None,
-            shape: legend::ShapeRef::Rect(&self.fill(), self.line()),
+            shape: legend::ShapeRef::Rect(*self.fill(), self.line()),
         })
     }
 }
 
+/// The fill with its color replaced by `color`, keeping its opacity
+fn fill_with_color(fill: style::series::Fill, color: style::series::Color) -> style::series::Fill {
+    match fill {
+        style::Fill::Solid { opacity, .. } => style::Fill::Solid { color, opacity },
+    }
+}
+
 fn get_column<'a, D>(
     col: &'a des::series::DataCol,
     data_source: &'a D,
@@ -78,21 +129,57 @@ where
     }
 }
 
+/// A column of a data source, or the implicit row index column
+enum ColumnRef<'a> {
+    Data(&'a dyn data::Column),
+    Index(data::IndexCol),
+}
+
+impl<'a> std::ops::Deref for ColumnRef<'a> {
+    type Target = dyn data::Column + 'a;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            ColumnRef::Data(col) => *col,
+            ColumnRef::Index(col) => col,
+        }
+    }
+}
+
+/// Get the X and Y columns of a series.
+/// Without X data, the X column is the row index of the Y column.
+fn get_xy_columns<'a, D>(
+    x_data: Option<&'a des::series::DataCol>,
+    y_data: &'a des::series::DataCol,
+    data_source: &'a D,
+) -> Result<(ColumnRef<'a>, &'a dyn data::Column), Error>
+where
+    D: data::Source + ?Sized,
+{
+    let y_col = get_column(y_data, data_source)?;
+    let x_col = match x_data {
+        Some(x_data) => ColumnRef::Data(get_column(x_data, data_source)?),
+        None => ColumnRef::Index(data::IndexCol(y_col.len())),
+    };
+    Ok((x_col, y_col))
+}
+
 fn calc_xy_bounds<D>(
     data_source: &D,
-    x_data: &des::series::DataCol,
+    x_data: Option<&des::series::DataCol>,
     y_data: &des::series::DataCol,
 ) -> Result<(axis::Bounds, axis::Bounds), Error>
 where
     D: data::Source + ?Sized,
 {
-    let x_col = get_column(x_data, data_source)?;
-    let y_col = get_column(y_data, data_source)?;
+    let (x_col, y_col) = get_xy_columns(x_data, y_data, data_source)?;
 
     if x_col.len() != y_col.len() {
-        return Err(Error::InconsistentData(
-            "X and Y data must be the same length".to_string(),
-        ));
+        return Err(Error::InconsistentData(format!(
+            "X and Y data must be the same length (got {} and {})",
+            x_col.len(),
+            y_col.len()
+        )));
     }
 
     let x_bounds = x_col.bounds().ok_or(Error::UnboundedAxis)?;
@@ -101,6 +188,130 @@ where
     Ok((x_bounds, y_bounds))
 }
 
+/// Map a data point to figure coordinates.
+/// Null samples and non-finite mapped coordinates (e.g. zero on a log scale) give `None`,
+/// so that the point is skipped like missing data.
+fn map_fig_point(
+    rect: &geom::Rect,
+    cm: &CoordMapXy,
+    x: data::SampleRef,
+    y: data::SampleRef,
+) -> Option<(f32, f32)> {
+    let (x, y) = cm.map_coord((x, y))?;
+    let (x, y) = plot_to_fig(rect, x, y);
+    (x.is_finite() && y.is_finite()).then_some((x, y))
+}
+
+/// Data of a point mapped to figure coordinates
+#[derive(Debug, Clone)]
+pub struct PointSample {
+    /// Index of the point in the data columns
+    pub index: usize,
+    pub x: data::Sample,
+    pub y: data::Sample,
+}
+
+impl PointSample {
+    fn new(index: usize, x: data::SampleRef, y: data::SampleRef) -> Self {
+        PointSample {
+            index,
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+}
+
+/// Geometry of a prepared series, in figure units.
+/// See [`PreparedFigure::series_geometry`](super::PreparedFigure::series_geometry).
+///
+/// The geometry is the result of the coordinate mapping through the axes of the series,
+/// as computed during preparation or the last data update.
+/// It is the geometry drawn by the series, without strokes width.
+#[derive(Debug, Clone)]
+pub struct SeriesGeometry<'a> {
+    /// The paths drawn for the series: the line and its smoothing, the trendline of a scatter,
+    /// the outline of histograms and bars, or one path per series of a bars or areas group.
+    /// Scatter markers are not included (see `points`), nor the cells of 2D histograms.
+    pub paths: Vec<&'a geom::Path>,
+    /// The data points of lines and scatters. `None` for the other series.
+    pub points: Option<&'a [geom::Point]>,
+}
+
+/// Map the non-null data points to figure coordinates
+fn map_points(
+    rect: &geom::Rect,
+    x_col: &dyn data::Column,
+    y_col: &dyn data::Column,
+    cm: &CoordMapXy,
+) -> (Vec<geom::Point>, Vec<PointSample>) {
+    let mut points = Vec::with_capacity(x_col.len());
+    let mut samples = Vec::with_capacity(x_col.len());
+
+    for (i, (x, y)) in x_col.sample_iter().zip(y_col.sample_iter()).enumerate() {
+        let Some((fx, fy)) = map_fig_point(rect, cm, x, y) else {
+            continue;
+        };
+        points.push(geom::Point { x: fx, y: fy });
+        samples.push(PointSample::new(i, x, y));
+    }
+    (points, samples)
+}
+
+/// Build the path of the smoothed data.
+/// Each run of non-null data points is smoothed independently,
+/// unless null values are skipped with `connect_nulls`.
+fn make_path_smoothed(
+    smoothing: &des::series::Smoothing,
+    connect_nulls: bool,
+    rect: &geom::Rect,
+    x_col: &dyn data::Column,
+    y_col: &dyn data::F64Column,
+    cm: &CoordMapXy,
+) -> Option<geom::Path> {
+    let mut pb = geom::PathBuilder::new();
+    let mut run_x: Vec<f32> = Vec::new();
+    let mut run_y: Vec<f64> = Vec::new();
+
+    let flush = |pb: &mut geom::PathBuilder, run_x: &mut Vec<f32>, run_y: &mut Vec<f64>| {
+        let smoothed = smooth::smooth(smoothing, run_y);
+        for (i, (x, y)) in run_x.iter().zip(smoothed).enumerate() {
+            let (x, y) = plot_to_fig(rect, *x, cm.y.map_coord_num(y));
+            if i == 0 {
+                pb.move_to(x, y);
+            } else {
+                pb.line_to(x, y);
+            }
+        }
+        run_x.clear();
+        run_y.clear();
+    };
+
+    for (x, y) in x_col.sample_iter().zip(y_col.f64_iter()) {
+        match (cm.x.map_coord(x), y) {
+            (Some(x), Some(y)) if y.is_finite() => {
+                run_x.push(x);
+                run_y.push(y);
+            }
+            _ if connect_nulls => (),
+            _ => flush(&mut pb, &mut run_x, &mut run_y),
+        }
+    }
+    flush(&mut pb, &mut run_x, &mut run_y);
+
+    pb.finish()
+}
+
+fn stroked_bbox(path: &geom::Path, stroke: Option<&style::series::Stroke>) -> geom::Rect {
+    let bbox = geom::Rect::from_path_bounds(path);
+    let hw = stroke.map_or(0.0, |s| s.width / 2.0);
+    geom::Rect::from_trbl(
+        bbox.top() - hw,
+        bbox.right() + hw,
+        bbox.bottom() + hw,
+        bbox.left() - hw,
+    )
+}
+
 #[derive(Debug, Clone)]
 pub(super) struct AxisMatcher<'a> {
     pub(super) plt_idx: usize,
@@ -126,8 +337,11 @@ impl<'a> AxisMatcher<'a> {
 #[derive(Debug, Clone)]
 pub struct Series {
     plot: SeriesPlot,
+    name: Option<String>,
     x_axis: des::axis::Ref,
     y_axis: des::axis::Ref,
+    zorder: des::ZOrder,
+    visible: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -135,12 +349,19 @@ enum SeriesPlot {
     Line(Line),
     Scatter(Scatter),
     Histogram(Histogram),
+    Hist2d(Hist2d),
     Bars(Bars),
     BarsGroup(BarsGroup),
+    AreasGroup(AreasGroup),
 }
 
 impl Series {
-    pub fn prepare<D>(index: usize, series: &des::Series, data_source: &D) -> Result<Self, Error>
+    pub fn prepare<D>(
+        index: usize,
+        series: &des::Series,
+        data_source: &D,
+        fontdb: &fontdb::Database,
+    ) -> Result<Self, Error>
     where
         D: data::Source + ?Sized,
     {
@@ -152,9 +373,13 @@ impl Series {
             des::Series::Histogram(des) => {
                 SeriesPlot::Histogram(Histogram::prepare(index, des, data_source)?)
             }
+            des::Series::Hist2d(des) => SeriesPlot::Hist2d(Hist2d::prepare(des, data_source)?),
             des::Series::Bars(des) => SeriesPlot::Bars(Bars::prepare(index, des, data_source)?),
             des::Series::BarsGroup(des) => {
-                SeriesPlot::BarsGroup(BarsGroup::prepare(index, des, data_source)?)
+                SeriesPlot::BarsGroup(BarsGroup::prepare(index, des, data_source, fontdb)?)
+            }
+            des::Series::AreasGroup(des) => {
+                SeriesPlot::AreasGroup(AreasGroup::prepare(index, des, data_source)?)
             }
         };
 
@@ -162,11 +387,30 @@ impl Series {
 
         Ok(Series {
             plot,
+            name: series.name().map(str::to_string),
             x_axis: x_axis.clone(),
             y_axis: y_axis.clone(),
+            zorder: series.zorder(),
+            visible: series.visible(),
         })
     }
 
+    pub fn zorder(&self) -> des::ZOrder {
+        self.zorder
+    }
+
+    pub fn visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
     pub fn axes(&self) -> (&des::axis::Ref, &des::axis::Ref) {
         (&self.x_axis, &self.y_axis)
     }
@@ -213,8 +457,10 @@ impl Series {
                 (scatter.ab.0.as_bound_ref(), scatter.ab.1.as_bound_ref())
             }
             SeriesPlot::Histogram(hist) => (hist.ab.0.into(), hist.ab.1.into()),
+            SeriesPlot::Hist2d(hist) => (hist.ab.0.into(), hist.ab.1.into()),
             SeriesPlot::Bars(bars) => bars.bounds(),
             SeriesPlot::BarsGroup(bg) => (bg.bounds.0.as_bound_ref(), bg.bounds.1.as_bound_ref()),
+            SeriesPlot::AreasGroup(ag) => (ag.bounds.0.as_bound_ref(), ag.bounds.1.as_bound_ref()),
         }
     }
 
@@ -223,8 +469,10 @@ impl Series {
             SeriesPlot::Line(line) => &line.axes.0,
             SeriesPlot::Scatter(scatter) => &scatter.axes.0,
             SeriesPlot::Histogram(hist) => &hist.axes.0,
+            SeriesPlot::Hist2d(hist) => &hist.axes.0,
             SeriesPlot::Bars(bars) => &bars.axes.0,
             SeriesPlot::BarsGroup(bg) => &bg.axes.0,
+            SeriesPlot::AreasGroup(ag) => &ag.axes.0,
         }
     }
 
@@ -233,8 +481,196 @@ impl Series {
             SeriesPlot::Line(line) => &line.axes.1,
             SeriesPlot::Scatter(scatter) => &scatter.axes.1,
             SeriesPlot::Histogram(hist) => &hist.axes.1,
+            SeriesPlot::Hist2d(hist) => &hist.axes.1,
             SeriesPlot::Bars(bars) => &bars.axes.1,
             SeriesPlot::BarsGroup(bg) => &bg.axes.1,
+            SeriesPlot::AreasGroup(ag) => &ag.axes.1,
+        }
+    }
+
+    /// Recompute the data bounds of the series from the data source.
+    /// This must be called before `update_data` if the data may have changed.
+    pub fn update_bounds<D>(&mut self, data_source: &D) -> Result<(), Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        self.check_xy_lengths(data_source)?;
+        match &mut self.plot {
+            SeriesPlot::Line(line) => {
+                line.ab = calc_xy_bounds(data_source, line.cols.0.as_ref(), &line.cols.1)?;
+            }
+            SeriesPlot::Scatter(sc) => {
+                sc.ab = calc_xy_bounds(data_source, sc.cols.0.as_ref(), &sc.cols.1)?;
+            }
+            SeriesPlot::Histogram(hist) => {
+                let (bins, ab) =
+                    calc_hist_bins(data_source, &hist.data, &hist.bin_spec, hist.mode)?;
+                hist.bins = bins;
+                hist.ab = ab;
+            }
+            SeriesPlot::Hist2d(hist) => {
+                let (bins, ab) = calc_hist2d_bins(data_source, &hist.cols, &hist.bin_specs)?;
+                hist.bins = bins;
+                hist.ab = ab;
+            }
+            SeriesPlot::Bars(bars) => {
+                bars.bounds =
+                    calc_bars_bounds(data_source, &bars.cols, bars.orientation, bars.baseline)?;
+            }
+            SeriesPlot::BarsGroup(bg) => {
+                bg.bounds = calc_bars_group_bounds(
+                    data_source,
+                    &bg.categories,
+                    &bg.series,
+                    &bg.orientation,
+                    &bg.arrangement,
+                )?;
+                bg.update_labels(data_source)?;
+            }
+            SeriesPlot::AreasGroup(ag) => ag.update_bounds(data_source)?,
+        }
+        Ok(())
+    }
+
+    /// Check whether the data bounds of the series fit within the axes bounds of `cm`
+    pub fn fits_in(&self, cm: &CoordMapXy) -> bool {
+        let (x, y) = self.bounds();
+        cm.x.axis_bounds().contains_bounds(&x) && cm.y.axis_bounds().contains_bounds(&y)
+    }
+
+    /// The orientation of the bars of histograms, bars and bars groups.
+    /// Returns `None` for the other series.
+    pub fn bars_orientation(&self) -> Option<des::series::BarsOrientation> {
+        match &self.plot {
+            SeriesPlot::Histogram(..) => Some(des::series::BarsOrientation::Vertical),
+            SeriesPlot::Bars(bars) => Some(bars.bounds.orientation()),
+            SeriesPlot::BarsGroup(bg) => Some(bg.orientation),
+            _ => None,
+        }
+    }
+
+    /// Check whether the labels of the series no longer match the data,
+    /// in which case the series must be prepared again to lay out the new labels.
+    pub fn labels_outdated(&self) -> bool {
+        match &self.plot {
+            SeriesPlot::BarsGroup(bg) => bg.labels.as_ref().is_some_and(|l| l.outdated),
+            _ => false,
+        }
+    }
+
+    /// The data points of the series, mapped to figure coordinates.
+    /// Returns `None` for series that are not made of individual data points, such as bars and histograms.
+    pub fn points(&self) -> Option<&[geom::Point]> {
+        match &self.plot {
+            SeriesPlot::Line(line) => Some(&line.points),
+            SeriesPlot::Scatter(sc) => Some(&sc.points),
+            _ => None,
+        }
+    }
+
+    /// The data of each point returned by [`points`](Self::points), in the same order
+    pub fn point_samples(&self) -> Option<&[PointSample]> {
+        match &self.plot {
+            SeriesPlot::Line(line) => Some(&line.samples),
+            SeriesPlot::Scatter(sc) => Some(&sc.samples),
+            _ => None,
+        }
+    }
+
+    /// The geometry of the series, in figure units
+    pub fn geometry(&self) -> SeriesGeometry<'_> {
+        let paths = match &self.plot {
+            SeriesPlot::Line(line) => {
+                let raw = line
+                    .path
+                    .as_ref()
+                    .filter(|_| line.smoothing.as_ref().is_none_or(|s| s.show_raw));
+                let smoothed = line.smoothing.as_ref().and_then(|s| s.path.as_ref());
+                raw.into_iter().chain(smoothed).collect()
+            }
+            SeriesPlot::Scatter(sc) => sc
+                .trendline
+                .as_ref()
+                .and_then(|t| t.path.as_ref())
+                .into_iter()
+                .collect(),
+            SeriesPlot::Histogram(hist) => hist.path.iter().collect(),
+            SeriesPlot::Hist2d(_) => Vec::new(),
+            SeriesPlot::Bars(bars) => bars.path.iter().chain(bars.neg_path.iter()).collect(),
+            SeriesPlot::BarsGroup(bg) => bg.series_paths.iter().collect(),
+            SeriesPlot::AreasGroup(ag) => ag.series_paths.iter().flatten().collect(),
+        };
+        SeriesGeometry {
+            paths,
+            points: self.points(),
+        }
+    }
+
+    /// Bounding box of the series geometry, including strokes, in figure units.
+    /// Returns `None` if the series has nothing to draw.
+    pub fn bbox(&self) -> Option<geom::Rect> {
+        match &self.plot {
+            SeriesPlot::Line(line) => {
+                let raw = line
+                    .path
+                    .as_ref()
+                    .map(|p| stroked_bbox(p, Some(&line.stroke)));
+                let smoothed = line
+                    .smoothing
+                    .as_ref()
+                    .and_then(|s| s.path.as_ref().map(|p| stroked_bbox(p, Some(&s.stroke))));
+                match (raw, smoothed) {
+                    (Some(a), Some(b)) => Some(geom::Rect::unite(&a, &b)),
+                    (a, b) => a.or(b),
+                }
+            }
+            SeriesPlot::Scatter(sc) => {
+                let stroke = sc.marker.stroke.as_ref();
+                let marker = stroked_bbox(&sc.path, stroke);
+                let trendline = sc
+                    .trendline
+                    .as_ref()
+                    .and_then(|t| t.path.as_ref().map(|p| stroked_bbox(p, Some(&t.stroke))));
+                sc.points
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| {
+                        match sc.marks.get(i).and_then(|m| m.path.as_ref()) {
+                            Some(path) => stroked_bbox(path, stroke),
+                            None => marker,
+                        }
+                        .translate(p.x, p.y)
+                    })
+                    .chain(trendline)
+                    .reduce(|a, b| geom::Rect::unite(&a, &b))
+            }
+            SeriesPlot::Histogram(hist) => hist
+                .path
+                .as_ref()
+                .map(|p| stroked_bbox(p, hist.line.as_ref())),
+            SeriesPlot::Hist2d(hist) => hist
+                .cells
+                .iter()
+                .map(|(r, _)| *r)
+                .reduce(|a, b| geom::Rect::unite(&a, &b)),
+            SeriesPlot::Bars(bars) => bars
+                .path
+                .iter()
+                .chain(bars.neg_path.iter())
+                .map(|p| stroked_bbox(p, bars.line.as_ref()))
+                .reduce(|a, b| geom::Rect::unite(&a, &b)),
+            SeriesPlot::BarsGroup(bg) => bg
+                .series
+                .iter()
+                .zip(bg.series_paths.iter())
+                .map(|(s, p)| stroked_bbox(p, s.line()))
+                .reduce(|a, b| geom::Rect::unite(&a, &b)),
+            SeriesPlot::AreasGroup(ag) => ag
+                .series
+                .iter()
+                .zip(ag.series_paths.iter())
+                .filter_map(|(s, p)| p.as_ref().map(|p| stroked_bbox(p, s.line())))
+                .reduce(|a, b| geom::Rect::unite(&a, &b)),
         }
     }
 
@@ -247,6 +683,7 @@ impl Series {
     where
         D: data::Source + ?Sized,
     {
+        self.check_xy_lengths(data_source)?;
         match &mut self.plot {
             SeriesPlot::Line(xy) => {
                 xy.update_data(data_source, rect, cm);
@@ -255,17 +692,46 @@ impl Series {
             SeriesPlot::Histogram(hist) => {
                 hist.update_data(rect, cm);
             }
+            SeriesPlot::Hist2d(hist) => hist.update_data(rect, cm),
             SeriesPlot::Bars(bars) => {
                 bars.update_data(data_source, rect, cm);
             }
             SeriesPlot::BarsGroup(bg) => bg.update_data(data_source, rect, cm),
+            SeriesPlot::AreasGroup(ag) => ag.update_data(rect, cm),
         }
         Ok(())
     }
+
+    /// Check that the X and Y columns of the series have the same length.
+    /// The data may have changed since the setup phase, and mapping columns of different
+    /// lengths would silently misalign the points.
+    fn check_xy_lengths<D>(&self, data_source: &D) -> Result<(), Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        let (index, x_data, y_data) = match &self.plot {
+            SeriesPlot::Line(xy) => (xy.index, xy.cols.0.as_ref(), &xy.cols.1),
+            SeriesPlot::Scatter(sc) => (sc.index, sc.cols.0.as_ref(), &sc.cols.1),
+            SeriesPlot::Bars(bars) => (bars.index, Some(&bars.cols.0), &bars.cols.1),
+            _ => return Ok(()),
+        };
+        let (x_col, y_col) = get_xy_columns(x_data, y_data, data_source)?;
+        let (x_len, y_len) = (x_col.len(), y_col.len());
+        if x_len == y_len {
+            return Ok(());
+        }
+        let series = match &self.name {
+            Some(name) => format!("Series '{name}'"),
+            None => format!("Series #{index}"),
+        };
+        Err(Error::InconsistentData(format!(
+            "{series}: X and Y data must be the same length (got {x_len} and {y_len})"
+        )))
+    }
 }
 
 impl Series {
-    pub fn draw<S>(&self, surface: &mut S, style: &Style)
+    pub fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
@@ -273,8 +739,10 @@ impl Series {
             SeriesPlot::Line(xy) => xy.draw(surface, style),
             SeriesPlot::Scatter(sc) => sc.draw(surface, style),
             SeriesPlot::Histogram(hist) => hist.draw(surface, style),
+            SeriesPlot::Hist2d(hist) => hist.draw(surface),
             SeriesPlot::Bars(bars) => bars.draw(surface, style),
             SeriesPlot::BarsGroup(bg) => bg.draw(surface, style),
+            SeriesPlot::AreasGroup(ag) => ag.draw(surface, style),
         }
     }
 }
@@ -282,12 +750,24 @@ impl Series {
 #[derive(Debug, Clone)]
 struct Line {
     index: usize,
-    cols: (des::DataCol, des::DataCol),
+    cols: (Option<des::DataCol>, des::DataCol),
     ab: (axis::Bounds, axis::Bounds),
     axes: (des::axis::Ref, des::axis::Ref),
     path: Option<geom::Path>,
+    points: Vec<geom::Point>,
+    samples: Vec<PointSample>,
     stroke: style::series::Stroke,
     interpolation: des::series::Interpolation,
+    connect_nulls: bool,
+    smoothing: Option<LineSmoothing>,
+}
+
+#[derive(Debug, Clone)]
+struct LineSmoothing {
+    smoothing: des::series::Smoothing,
+    stroke: style::series::Stroke,
+    show_raw: bool,
+    path: Option<geom::Path>,
 }
 
 impl Line {
@@ -295,16 +775,46 @@ impl Line {
     where
         D: data::Source + ?Sized,
     {
-        let cols = (des.x_data().clone(), des.y_data().clone());
-        let (x_bounds, y_bounds) = calc_xy_bounds(data_source, &cols.0, &cols.1)?;
+        let cols = (des.x_data().cloned(), des.y_data().clone());
+        let (x_bounds, y_bounds) = calc_xy_bounds(data_source, cols.0.as_ref(), &cols.1)?;
+
+        let smoothing = match des.smoothing() {
+            Some(smoothing) => {
+                if get_column(&cols.1, data_source)?.f64().is_none() {
+                    return Err(Error::InconsistentData(
+                        "Smoothed line data must be numeric".into(),
+                    ));
+                }
+                let stroke = match des.smoothing_line() {
+                    Some(stroke) => stroke.clone(),
+                    None if des.smoothing_only() => des.stroke().clone(),
+                    None => {
+                        let width = des.stroke().width * 2.0;
+                        des.stroke().clone().with_width(width)
+                    }
+                };
+                Some(LineSmoothing {
+                    smoothing: *smoothing,
+                    stroke,
+                    show_raw: !des.smoothing_only(),
+                    path: None,
+                })
+            }
+            None => None,
+        };
+
         Ok(Line {
             index,
             cols,
             ab: (x_bounds, y_bounds),
             axes: (des.x_axis().clone(), des.y_axis().clone()),
             path: None,
+            points: Vec::new(),
+            samples: Vec::new(),
             stroke: des.stroke().clone(),
             interpolation: des.interpolation(),
+            connect_nulls: des.connect_nulls(),
+            smoothing,
         })
     }
 
@@ -313,8 +823,9 @@ impl Line {
         D: data::Source + ?Sized,
     {
         // unwraping here as data is checked during setup phase
-        let x_col = get_column(&self.cols.0, data_source).unwrap();
-        let y_col = get_column(&self.cols.1, data_source).unwrap();
+        let (x_col, y_col) =
+            get_xy_columns(self.cols.0.as_ref(), &self.cols.1, data_source).unwrap();
+        let x_col = &*x_col;
 
         debug_assert!(x_col.len() == y_col.len());
 
@@ -334,7 +845,20 @@ impl Line {
             }
         };
 
-        self.path = Some(path);
+        self.path = path;
+        (self.points, self.samples) = map_points(rect, x_col, y_col, cm);
+
+        if let Some(smoothing) = self.smoothing.as_mut() {
+            let y_col = y_col.f64().expect("Checked during setup");
+            smoothing.path = make_path_smoothed(
+                &smoothing.smoothing,
+                self.connect_nulls,
+                rect,
+                x_col,
+                y_col,
+                cm,
+            );
+        }
     }
 
     fn make_path_linear(
@@ -343,17 +867,14 @@ impl Line {
         x: &dyn data::Column,
         y: &dyn data::Column,
         cm: &CoordMapXy,
-    ) -> geom::Path {
+    ) -> Option<geom::Path> {
         let mut in_a_line = false;
         let mut pb = geom::PathBuilder::with_capacity(x.len() + 1, x.len());
         for (x, y) in x.sample_iter().zip(y.sample_iter()) {
-            if x.is_null() || y.is_null() {
-                in_a_line = false;
+            let Some((x, y)) = map_fig_point(rect, cm, x, y) else {
+                in_a_line &= self.connect_nulls;
                 continue;
-            }
-            let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
-            let x = rect.left() + x;
-            let y = rect.bottom() - y;
+            };
             // if x_col.len() == 1024 {
             //     println!("  adding point {} {}", x, y);
             // }
@@ -364,7 +885,7 @@ impl Line {
                 in_a_line = true;
             }
         }
-        pb.finish().expect("Should be a valid path")
+        pb.finish()
     }
 
     fn make_path_step_early(
@@ -373,18 +894,18 @@ impl Line {
         x: &dyn data::Column,
         y: &dyn data::Column,
         cm: &CoordMapXy,
-    ) -> geom::Path {
+    ) -> Option<geom::Path> {
         let mut pb = geom::PathBuilder::new();
 
         let mut prev_x: Option<f32> = None;
 
         for (x, y) in x.sample_iter().zip(y.sample_iter()) {
-            if x.is_null() || y.is_null() {
-                prev_x = None;
+            let Some((x, y)) = map_fig_point(rect, cm, x, y) else {
+                if !self.connect_nulls {
+                    prev_x = None;
+                }
                 continue;
-            }
-            let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
-            let (x, y) = plot_to_fig(rect, x, y);
+            };
 
             if let Some(px) = prev_x {
                 pb.line_to(px, y);
@@ -395,7 +916,7 @@ impl Line {
             prev_x = Some(x);
         }
 
-        pb.finish().expect("Should be a valid path")
+        pb.finish()
     }
 
     fn make_path_step_late(
@@ -404,18 +925,18 @@ impl Line {
         x: &dyn data::Column,
         y: &dyn data::Column,
         cm: &CoordMapXy,
-    ) -> geom::Path {
+    ) -> Option<geom::Path> {
         let mut pb = geom::PathBuilder::new();
 
         let mut prev_y: Option<f32> = None;
 
         for (x, y) in x.sample_iter().zip(y.sample_iter()) {
-            if x.is_null() || y.is_null() {
-                prev_y = None;
+            let Some((x, y)) = map_fig_point(rect, cm, x, y) else {
+                if !self.connect_nulls {
+                    prev_y = None;
+                }
                 continue;
-            }
-            let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
-            let (x, y) = plot_to_fig(rect, x, y);
+            };
 
             if let Some(py) = prev_y {
                 pb.line_to(x, py);
@@ -426,7 +947,7 @@ impl Line {
             prev_y = Some(y);
         }
 
-        pb.finish().expect("Should be a valid path")
+        pb.finish()
     }
 
     fn make_path_step_middle(
@@ -435,20 +956,20 @@ impl Line {
         x: &dyn data::Column,
         y: &dyn data::Column,
         cm: &CoordMapXy,
-    ) -> geom::Path {
+    ) -> Option<geom::Path> {
         let mut pb = geom::PathBuilder::new();
 
         let mut prev_x: Option<f32> = None;
         let mut prev_y: Option<f32> = None;
 
         for (x, y) in x.sample_iter().zip(y.sample_iter()) {
-            if x.is_null() || y.is_null() {
-                prev_x = None;
-                prev_y = None;
+            let Some((x, y)) = map_fig_point(rect, cm, x, y) else {
+                if !self.connect_nulls {
+                    prev_x = None;
+                    prev_y = None;
+                }
                 continue;
-            }
-            let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
-            let (x, y) = plot_to_fig(rect, x, y);
+            };
 
             if let (Some(px), Some(py)) = (prev_x, prev_y) {
                 let mx = (px + x) / 2.0;
@@ -462,7 +983,7 @@ impl Line {
             prev_y = Some(y);
         }
 
-        pb.finish().expect("Should be a valid path")
+        pb.finish()
     }
 
     fn make_path_cubic_spline(
@@ -471,7 +992,7 @@ impl Line {
         x: &dyn data::Column,
         y: &dyn data::Column,
         cm: &CoordMapXy,
-    ) -> geom::Path {
+    ) -> Option<geom::Path> {
         const NAN: (f32, f32) = (f32::NAN, f32::NAN);
         let mut buf: [(f32, f32); 4] = [NAN, NAN, NAN, NAN];
         let mut buf_idx = 0;
@@ -503,16 +1024,17 @@ impl Line {
         }
 
         for (x, y) in x.sample_iter().zip(y.sample_iter()) {
-            if x.is_null() || y.is_null() {
+            let Some((x, y)) = map_fig_point(rect, cm, x, y) else {
+                if self.connect_nulls {
+                    continue;
+                }
                 if buf_idx == 3 {
                     // we draw the last segment if any
                     add_point(&mut pb, &[buf[0], buf[1], buf[2], buf[2]]);
                 }
                 buf_idx = 0;
                 continue;
-            }
-            let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
-            let (x, y) = plot_to_fig(rect, x, y);
+            };
 
             // first point, or after a gap
             if buf_idx == 0 {
@@ -542,108 +1064,721 @@ impl Line {
             add_point(&mut pb, &[buf[0], buf[1], buf[2], buf[2]]);
         }
 
-        pb.finish().expect("Should be a valid path")
+        pb.finish()
     }
 
-    fn draw<S>(&self, surface: &mut S, style: &Style)
+    fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
         let rc = (style, self.index);
 
-        let path = render::Path {
-            path: self.path.as_ref().unwrap(),
-            fill: None,
-            stroke: Some(self.stroke.as_stroke(&rc)),
-            transform: None,
-        };
-        surface.draw_path(&path);
+        if let Some(path) = self.path.as_ref()
+            && self.smoothing.as_ref().is_none_or(|s| s.show_raw)
+        {
+            let path = render::Path {
+                path,
+                fill: None,
+                stroke: Some(self.stroke.as_stroke(&rc)),
+                transform: None,
+            };
+            surface.draw_path(&path)?;
+        }
+
+        if let Some(LineSmoothing {
+            stroke,
+            path: Some(path),
+            ..
+        }) = &self.smoothing
+        {
+            let path = render::Path {
+                path,
+                fill: None,
+                stroke: Some(stroke.as_stroke(&rc)),
+                transform: None,
+            };
+            surface.draw_path(&path)?;
+        }
+        Ok(())
     }
 }
 
 #[derive(Debug, Clone)]
 struct Scatter {
     index: usize,
-    cols: (des::DataCol, des::DataCol),
+    cols: (Option<des::DataCol>, des::DataCol),
     ab: (axis::Bounds, axis::Bounds),
     axes: (des::axis::Ref, des::axis::Ref),
     path: geom::Path,
     points: Vec<geom::Point>,
+    samples: Vec<PointSample>,
     marker: style::series::Marker,
+    size_col: Option<(des::DataCol, (f32, f32))>,
+    color_col: Option<(des::DataCol, style::series::ColorMap)>,
+    /// Per point marker path and fill, set if the markers are encoded by size or color columns
+    marks: Vec<PointMark>,
+    trendline: Option<Trendline>,
+    spread: Option<ScatterSpread>,
 }
 
-impl Scatter {
-    fn prepare<D>(index: usize, des: &des::series::Scatter, data_source: &D) -> Result<Self, Error>
-    where
-        D: data::Source + ?Sized,
-    {
-        let cols = (des.x_data().clone(), des.y_data().clone());
-        let (x_bounds, y_bounds) = calc_xy_bounds(data_source, &cols.0, &cols.1)?;
-        let path = marker::marker_path(des.marker());
-        Ok(Scatter {
-            index,
-            cols,
-            ab: (x_bounds, y_bounds),
-            axes: (des.x_axis().clone(), des.y_axis().clone()),
-            path,
-            points: Vec::new(),
-            marker: des.marker().clone(),
-        })
+/// Spread of the scatter points across the category axis
+#[derive(Debug, Clone, Copy)]
+struct ScatterSpread {
+    spread: des::series::Spread,
+    /// The category axis, across which the points are offset
+    cat_axis: Orientation,
+}
+
+#[derive(Debug, Clone)]
+struct PointMark {
+    path: Option<geom::Path>,
+    fill: Option<style::series::Fill>,
+    stroke: Option<style::series::Stroke>,
+}
+
+/// Width of the spread relative to the width of a category
+fn spread_width(spread: &des::series::Spread) -> f32 {
+    match spread {
+        des::series::Spread::Jitter { amount, .. } => *amount,
+        des::series::Spread::Swarm => 1.0,
     }
+}
 
-    fn update_data<D>(&mut self, data_source: &D, rect: &geom::Rect, cm: &CoordMapXy)
-    where
-        D: data::Source + ?Sized,
-    {
-        let x_col = get_column(&self.cols.0, data_source).unwrap();
-        let y_col = get_column(&self.cols.1, data_source).unwrap();
-        debug_assert!(x_col.len() == y_col.len());
+/// Width in figure units of the category of a sample:
+/// the bin of a categorical axis, or one unit around the value on a numeric axis.
+/// The width is zero (no spread) if the unit around the value can't be mapped,
+/// e.g. when it extends below zero on a log axis.
+fn category_width(cm: &dyn CoordMap, sample: &data::Sample) -> f32 {
+    let width = match (cm.axis_bounds(), sample) {
+        (axis::BoundsRef::Cat(_), _) => cm.cat_bin_size(),
+        (axis::BoundsRef::Num(_), data::Sample::Num(v)) => {
+            (cm.map_coord_num(v + 0.5) - cm.map_coord_num(v - 0.5)).abs()
+        }
+        _ => 0.0,
+    };
+    if width.is_finite() { width } else { 0.0 }
+}
 
-        let mut points = Vec::with_capacity(x_col.len());
+/// Deterministic pseudo-random number in 0.0..1.0 for the point at `index`.
+/// This is the SplitMix64 generator, indexed by the point so that the offset of a point
+/// doesn't depend on the other points.
+fn jitter_unit(seed: u64, index: usize) -> f32 {
+    let mut z = seed.wrapping_add(
+        (index as u64)
+            .wrapping_add(1)
+            .wrapping_mul(0x9E3779B97F4A7C15),
+    );
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^= z >> 31;
+    // 24 bits fit exactly in the f32 mantissa
+    (z >> 40) as f32 / (1u32 << 24) as f32
+}
 
-        for (x, y) in x_col.sample_iter().zip(y_col.sample_iter()) {
-            if x.is_null() || y.is_null() {
-                continue;
-            }
-            let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
-            let x = rect.left() + x;
-            let y = rect.bottom() - y;
-            points.push(geom::Point { x, y });
+/// Compute the offsets packing markers without overlap across the category axis.
+/// Points are grouped by their `centers` on the category axis and placed in order of their `values`,
+/// each at the offset the nearest to the center that doesn't overlap the points already placed.
+/// Offsets are clamped to half of the category `widths`.
+fn swarm_offsets(centers: &[f32], values: &[f32], diameters: &[f32], widths: &[f32]) -> Vec<f32> {
+    let mut order: Vec<usize> = (0..centers.len()).collect();
+    order.sort_by(|&a, &b| {
+        centers[a]
+            .total_cmp(&centers[b])
+            .then(values[a].total_cmp(&values[b]))
+    });
+
+    let mut offsets = vec![0.0; centers.len()];
+    // points further than the largest diameter along the values can't overlap
+    let max_diameter = diameters.iter().copied().fold(0.0, f32::max);
+    // indices of the points placed in the current category,
+    // within the largest diameter of the current value
+    let mut placed: Vec<usize> = Vec::new();
+    let mut candidates: Vec<f32> = Vec::new();
+
+    for (n, &i) in order.iter().enumerate() {
+        if n > 0 && centers[order[n - 1]] != centers[i] {
+            placed.clear();
+        }
+        // values are increasing within a category
+        placed.retain(|&j| values[i] - values[j] <= max_diameter);
+        let near: Vec<(f32, f32)> = placed
+            .iter()
+            .filter_map(|&j| {
+                let dist = 0.5 * (diameters[i] + diameters[j]);
+                let dv = values[i] - values[j];
+                (dv.abs() < dist).then(|| (offsets[j], (dist * dist - dv * dv).sqrt()))
+            })
+            .collect();
+
+        candidates.clear();
+        candidates.push(0.0);
+        for &(off, across) in near.iter() {
+            candidates.push(off - across);
+            candidates.push(off + across);
         }
-        self.points = points;
+        candidates.sort_by(|a, b| a.abs().total_cmp(&b.abs()));
+
+        // small tolerance for the candidates tangent to a neighbor
+        let fits = |c: f32| {
+            near.iter()
+                .all(|&(off, across)| (c - off).abs() >= across - 1e-3)
+        };
+        let offset = candidates.iter().copied().find(|&c| fits(c)).unwrap_or(0.0);
+        let half_width = 0.5 * widths[i];
+        offsets[i] = offset.clamp(-half_width, half_width);
+        placed.push(i);
     }
+    offsets
+}
 
-    fn draw<S>(&self, surface: &mut S, style: &Style)
-    where
-        S: render::Surface,
-    {
-        let rc = (style, self.index);
+/// Check that an encoding column is numeric and of the same length as the x data
+fn check_encoding_column<D>(
+    data_source: &D,
+    col: &des::DataCol,
+    len: usize,
+    what: &str,
+) -> Result<(), Error>
+where
+    D: data::Source + ?Sized,
+{
+    let col = get_column(col, data_source)?;
+    if col.f64().is_none() {
+        return Err(Error::InconsistentData(format!(
+            "{what} data must be numeric"
+        )));
+    }
+    if col.len() != len {
+        return Err(Error::InconsistentData(format!(
+            "{what} data must be the same length as X and Y data"
+        )));
+    }
+    Ok(())
+}
 
-        for p in &self.points {
-            let transform = geom::Transform::from_translate(p.x, p.y);
-            let path = render::Path {
-                path: &self.path,
-                fill: self.marker.fill.as_ref().map(|f| f.as_paint(&rc)),
-                stroke: self.marker.stroke.as_ref().map(|l| l.as_stroke(&rc)),
-                transform: Some(&transform),
-            };
-            surface.draw_path(&path);
-        }
+/// Iterate over an optional encoding column, with its value bounds
+fn encoding_iter<'a, D>(
+    data_source: &'a D,
+    col: Option<&'a des::DataCol>,
+) -> (
+    Box<dyn Iterator<Item = Option<f64>> + 'a>,
+    Option<axis::NumBounds>,
+)
+where
+    D: data::Source + ?Sized,
+{
+    // unwraping here as data is checked during setup phase
+    match col.map(|c| get_column(c, data_source).unwrap().f64().unwrap()) {
+        Some(col) => (col.f64_iter(), col.bounds()),
+        None => (Box::new(std::iter::repeat(None)), None),
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-struct HistBin {
-    /// Start and end of this bin
-    range: (f64, f64),
-    /// Either count or density
-    value: f64,
+/// Ratio of `val` within `bounds`, 0.5 if `bounds` is a single value
+fn bounds_ratio(val: f64, bounds: &axis::NumBounds) -> f64 {
+    if bounds.span() > 0.0 {
+        (val - bounds.start()) / bounds.span()
+    } else {
+        0.5
+    }
 }
 
 #[derive(Debug, Clone)]
-struct Histogram {
-    index: usize,
-    ab: (axis::NumBounds, axis::NumBounds),
+struct Trendline {
+    fit: des::series::Fit,
+    stroke: style::series::Stroke,
+    path: Option<geom::Path>,
+}
+
+impl Trendline {
+    /// Number of segments used to draw curved models
+    const SEGMENTS: usize = 100;
+
+    fn update_data(
+        &mut self,
+        rect: &geom::Rect,
+        x_col: &dyn data::Column,
+        y_col: &dyn data::Column,
+        cm: &CoordMapXy,
+    ) {
+        self.path = None;
+        let (Some(x_col), Some(y_col)) = (x_col.f64(), y_col.f64()) else {
+            return;
+        };
+        let (xs, ys): (Vec<f64>, Vec<f64>) = x_col
+            .f64_iter()
+            .zip(y_col.f64_iter())
+            .filter_map(|(x, y)| Some((x?, y?)))
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .unzip();
+        let Some(model) = fit::Model::fit(self.fit, &xs, &ys) else {
+            return;
+        };
+
+        // keep the curve in a reasonable range around the plot, as it is clipped anyway
+        let y_min = rect.top() - rect.height() * 10.0;
+        let y_max = rect.bottom() + rect.height() * 10.0;
+
+        let mut pb = geom::PathBuilder::new();
+        let mut in_a_line = false;
+        for i in 0..=Self::SEGMENTS {
+            let px = rect.width() * i as f32 / Self::SEGMENTS as f32;
+            let Some(x) = cm.x.unmap_coord(px).as_num() else {
+                continue;
+            };
+            let y = model.eval(x);
+            if !y.is_finite() {
+                in_a_line = false;
+                continue;
+            }
+            // e.g. a fitted value below zero on a log axis
+            let (fx, fy) = plot_to_fig(rect, px, cm.y.map_coord_num(y));
+            if !fy.is_finite() {
+                in_a_line = false;
+                continue;
+            }
+            let fy = fy.clamp(y_min, y_max);
+            if in_a_line {
+                pb.line_to(fx, fy);
+            } else {
+                pb.move_to(fx, fy);
+                in_a_line = true;
+            }
+        }
+        self.path = pb.finish();
+    }
+}
+
+impl Scatter {
+    fn prepare<D>(index: usize, des: &des::series::Scatter, data_source: &D) -> Result<Self, Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        let cols = (des.x_data().cloned(), des.y_data().clone());
+        let (mut x_bounds, mut y_bounds) = calc_xy_bounds(data_source, cols.0.as_ref(), &cols.1)?;
+        let spread = des.spread().map(|spread| {
+            let cat_axis = match (&x_bounds, &y_bounds) {
+                (axis::Bounds::Cat(_), _) => Orientation::X,
+                (_, axis::Bounds::Cat(_)) => Orientation::Y,
+                _ => Orientation::X,
+            };
+            let cat_bounds = match cat_axis {
+                Orientation::X => &mut x_bounds,
+                Orientation::Y => &mut y_bounds,
+            };
+            if let axis::Bounds::Num(nb) = cat_bounds {
+                let half_width = 0.5 * spread_width(spread) as f64;
+                *nb = (nb.start() - half_width, nb.end() + half_width).into();
+            }
+            ScatterSpread {
+                spread: *spread,
+                cat_axis,
+            }
+        });
+        let path = marker::marker_path(des.marker());
+        let len = get_column(&cols.1, data_source)?.len();
+        if let Some(col) = des.size_data() {
+            check_encoding_column(data_source, col, len, "Marker size")?;
+        }
+        if let Some(col) = des.color_data() {
+            check_encoding_column(data_source, col, len, "Marker color")?;
+        }
+        let trendline = des.trendline().map(|fit| Trendline {
+            fit: *fit,
+            stroke: des.trendline_line().clone(),
+            path: None,
+        });
+        Ok(Scatter {
+            index,
+            cols,
+            ab: (x_bounds, y_bounds),
+            axes: (des.x_axis().clone(), des.y_axis().clone()),
+            path,
+            points: Vec::new(),
+            samples: Vec::new(),
+            marker: des.marker().clone().faded(des.alpha()),
+            size_col: des.size_data().map(|c| (c.clone(), des.size_range())),
+            color_col: des
+                .color_data()
+                .map(|c| (c.clone(), des.colormap().clone())),
+            marks: Vec::new(),
+            trendline,
+            spread,
+        })
+    }
+
+    fn update_data<D>(&mut self, data_source: &D, rect: &geom::Rect, cm: &CoordMapXy)
+    where
+        D: data::Source + ?Sized,
+    {
+        let (x_col, y_col) =
+            get_xy_columns(self.cols.0.as_ref(), &self.cols.1, data_source).unwrap();
+        let x_col = &*x_col;
+        debug_assert!(x_col.len() == y_col.len());
+
+        if self.size_col.is_none() && self.color_col.is_none() {
+            (self.points, self.samples) = map_points(rect, x_col, y_col, cm);
+        } else {
+            let (points, samples, marks) = self.map_marks(data_source, rect, x_col, y_col, cm);
+            self.points = points;
+            self.samples = samples;
+            self.marks = marks;
+        }
+        if let Some(trendline) = self.trendline.as_mut() {
+            trendline.update_data(rect, x_col, y_col, cm);
+        }
+        if let Some(spread) = self.spread {
+            self.apply_spread(spread, cm);
+        }
+    }
+
+    /// Offset the mapped points across the category axis
+    fn apply_spread(&mut self, spread: ScatterSpread, cm: &CoordMapXy) {
+        let (cat_cm, cat_of): (_, fn(&PointSample) -> &data::Sample) = match spread.cat_axis {
+            Orientation::X => (cm.x, |s| &s.x),
+            Orientation::Y => (cm.y, |s| &s.y),
+        };
+        // width of the category of each point, in figure units
+        let widths: Vec<f32> = self
+            .samples
+            .iter()
+            .map(|s| category_width(cat_cm, cat_of(s)))
+            .collect();
+
+        let offsets = match spread.spread {
+            des::series::Spread::Jitter { amount, seed } => self
+                .samples
+                .iter()
+                .zip(widths.iter())
+                .map(|(s, w)| (jitter_unit(seed, s.index) - 0.5) * amount * w)
+                .collect(),
+            des::series::Spread::Swarm => {
+                let diameters: Vec<f32> = (0..self.points.len())
+                    .map(|i| match self.marks.get(i).and_then(|m| m.path.as_ref()) {
+                        Some(path) => path.bounds().width(),
+                        None => self.marker.size.0,
+                    })
+                    .collect();
+                let (centers, values): (Vec<f32>, Vec<f32>) = match spread.cat_axis {
+                    Orientation::X => self.points.iter().map(|p| (p.x, p.y)).unzip(),
+                    Orientation::Y => self.points.iter().map(|p| (p.y, p.x)).unzip(),
+                };
+                swarm_offsets(&centers, &values, &diameters, &widths)
+            }
+        };
+
+        for (p, off) in self.points.iter_mut().zip(offsets) {
+            match spread.cat_axis {
+                Orientation::X => p.x += off,
+                Orientation::Y => p.y += off,
+            }
+        }
+    }
+
+    /// Map the data points along with their size and color encodings
+    fn map_marks<D>(
+        &self,
+        data_source: &D,
+        rect: &geom::Rect,
+        x_col: &dyn data::Column,
+        y_col: &dyn data::Column,
+        cm: &CoordMapXy,
+    ) -> (Vec<geom::Point>, Vec<PointSample>, Vec<PointMark>)
+    where
+        D: data::Source + ?Sized,
+    {
+        let (sizes, size_bounds) =
+            encoding_iter(data_source, self.size_col.as_ref().map(|(c, _)| c));
+        let (colors, color_bounds) =
+            encoding_iter(data_source, self.color_col.as_ref().map(|(c, _)| c));
+
+        let mut points = Vec::with_capacity(x_col.len());
+        let mut samples = Vec::with_capacity(x_col.len());
+        let mut marks = Vec::with_capacity(x_col.len());
+
+        let xy = x_col.sample_iter().zip(y_col.sample_iter());
+        for (i, ((x, y), (size, color))) in xy.zip(sizes.zip(colors)).enumerate() {
+            let Some((fx, fy)) = map_fig_point(rect, cm, x, y) else {
+                continue;
+            };
+            points.push(geom::Point { x: fx, y: fy });
+            samples.push(PointSample::new(i, x, y));
+
+            let path = match (size, &self.size_col, &size_bounds) {
+                (Some(size), Some((_, (min, max))), Some(bounds)) if size.is_finite() => {
+                    // the value maps to the marker area
+                    let ratio = bounds_ratio(size, bounds) as f32;
+                    let size = (min * min + ratio * (max * max - min * min)).sqrt();
+                    let marker = style::series::Marker {
+                        size: size.into(),
+                        ..self.marker.clone()
+                    };
+                    Some(marker::marker_path(&marker))
+                }
+                _ => None,
+            };
+
+            let (fill, stroke) = match (color, &self.color_col, &color_bounds) {
+                (Some(color), Some((_, colormap)), Some(bounds)) if color.is_finite() => {
+                    let color = colormap.sample(bounds_ratio(color, bounds)).into();
+                    match (&self.marker.fill, &self.marker.stroke) {
+                        (Some(style::series::Fill::Solid { opacity, .. }), _) => (
+                            Some(style::series::Fill::Solid {
+                                color,
+                                opacity: *opacity,
+                            }),
+                            None,
+                        ),
+                        // hollow markers stay hollow, and their edge takes the color
+                        (None, Some(stroke)) => (
+                            None,
+                            Some(style::series::Stroke {
+                                color,
+                                ..stroke.clone()
+                            }),
+                        ),
+                        (None, None) => (None, None),
+                    }
+                }
+                _ => (None, None),
+            };
+
+            marks.push(PointMark { path, fill, stroke });
+        }
+        (points, samples, marks)
+    }
+
+    fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        let rc = (style, self.index);
+
+        if let Some(Trendline {
+            stroke,
+            path: Some(path),
+            ..
+        }) = &self.trendline
+        {
+            let path = render::Path {
+                path,
+                fill: None,
+                stroke: Some(stroke.as_stroke(&rc)),
+                transform: None,
+            };
+            surface.draw_path(&path)?;
+        }
+
+        for (i, p) in self.points.iter().enumerate() {
+            let mark = self.marks.get(i);
+            let transform = geom::Transform::from_translate(p.x, p.y);
+            let path = render::Path {
+                path: mark.and_then(|m| m.path.as_ref()).unwrap_or(&self.path),
+                fill: mark
+                    .and_then(|m| m.fill.as_ref())
+                    .or(self.marker.fill.as_ref())
+                    .map(|f| f.as_paint(&rc)),
+                stroke: mark
+                    .and_then(|m| m.stroke.as_ref())
+                    .or(self.marker.stroke.as_ref())
+                    .map(|l| l.as_stroke(&rc)),
+                transform: Some(&transform),
+            };
+            surface.draw_path(&path)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct HistBin {
+    /// Start and end of this bin
+    range: (f64, f64),
+    /// Count, possibly cumulated and normalized
+    value: f64,
+}
+
+/// Compute the bin edges of a histogram, given the finite data samples, sorted in increasing order
+fn calc_hist_edges(spec: &des::series::BinSpec, sorted: &[f64]) -> Result<Vec<f64>, Error> {
+    use des::series::BinSpec;
+
+    let (mut start, mut end) = match (sorted.first(), sorted.last()) {
+        (Some(&start), Some(&end)) => (start, end),
+        _ => return Err(Error::UnboundedAxis),
+    };
+    if start == end {
+        start -= 0.5;
+        end += 0.5;
+    }
+    let span = end - start;
+    let n = sorted.len() as f64;
+
+    let sturges_count = || (n.log2().ceil() as u32 + 1).max(1);
+    let even_edges = |count: u32| -> Vec<f64> {
+        let count = count.max(1);
+        (0..=count)
+            .map(|i| start + span * i as f64 / count as f64)
+            .collect()
+    };
+    // number of bins of the given width, or None if it exceeds the maximum
+    let width_count = |width: f64| -> Option<usize> {
+        let count = (span / width).ceil().max(1.0);
+        (count <= des::series::MAX_HIST_BINS as f64).then_some(count as usize)
+    };
+    let width_edges = |width: f64, count: usize| -> Vec<f64> {
+        (0..=count).map(|i| start + width * i as f64).collect()
+    };
+    let too_many_bins = || {
+        Error::InconsistentDesign(format!(
+            "Histogram can't have more than {} bins",
+            des::series::MAX_HIST_BINS
+        ))
+    };
+
+    let edges = match spec {
+        BinSpec::Count(count) => {
+            if *count > des::series::MAX_HIST_BINS {
+                return Err(too_many_bins());
+            }
+            even_edges(*count)
+        }
+        BinSpec::Width(width) => {
+            if !width.is_finite() || *width <= 0.0 {
+                return Err(Error::InconsistentDesign(
+                    "Histogram bin width must be positive".into(),
+                ));
+            }
+            let count = width_count(*width).ok_or_else(too_many_bins)?;
+            width_edges(*width, count)
+        }
+        BinSpec::Edges(edges) => {
+            if edges.len() < 2
+                || edges
+                    .windows(2)
+                    .any(|w| w[0].partial_cmp(&w[1]) != Some(std::cmp::Ordering::Less))
+            {
+                return Err(Error::InconsistentDesign(
+                    "Histogram bin edges must be at least 2 values in increasing order".into(),
+                ));
+            }
+            edges.clone()
+        }
+        BinSpec::Sturges => even_edges(sturges_count()),
+        BinSpec::FreedmanDiaconis => {
+            let quantile = |q: f64| sorted[((n - 1.0) * q).round() as usize];
+            let iqr = quantile(0.75) - quantile(0.25);
+            let width = 2.0 * iqr / n.cbrt();
+            match width_count(width).filter(|_| iqr > 0.0) {
+                Some(count) => width_edges(width, count),
+                None => even_edges(sturges_count()),
+            }
+        }
+    };
+    Ok(edges)
+}
+
+/// Index of the bin of `x` given the bin `edges`, or `None` if `x` is outside of the edges.
+/// The last bin includes its end edge.
+fn hist_bin_index(edges: &[f64], x: f64) -> Option<usize> {
+    let bins = edges.len() - 1;
+    (x >= edges[0] && x <= edges[bins])
+        .then(|| edges.partition_point(|&e| e <= x).clamp(1, bins) - 1)
+}
+
+/// Cumulative and normalization modes of a histogram
+type HistMode = (des::series::Cumulative, Option<des::series::HistNorm>);
+
+fn calc_hist_bins<D>(
+    data_source: &D,
+    data: &des::DataCol,
+    spec: &des::series::BinSpec,
+    mode: HistMode,
+) -> Result<(Vec<HistBin>, (axis::NumBounds, axis::NumBounds)), Error>
+where
+    D: data::Source + ?Sized,
+{
+    let col = get_column(data, data_source)?;
+    let col = col.f64().ok_or(Error::InconsistentData(
+        "Histogram data must be numeric".into(),
+    ))?;
+
+    let mut samples: Vec<f64> = col.f64_iter().flatten().filter(|x| x.is_finite()).collect();
+    samples.sort_by(f64::total_cmp);
+
+    let edges = calc_hist_edges(spec, &samples)?;
+    let first = edges[0];
+    let last = edges[edges.len() - 1];
+
+    let mut bins: Vec<HistBin> = edges
+        .windows(2)
+        .map(|w| HistBin {
+            range: (w[0], w[1]),
+            value: 0.0,
+        })
+        .collect();
+
+    let mut count = 0;
+    for idx in samples.iter().filter_map(|&x| hist_bin_index(&edges, x)) {
+        bins[idx].value += 1.0;
+        count += 1;
+    }
+
+    let (cumulative, norm) = mode;
+    match cumulative {
+        des::series::Cumulative::No => (),
+        des::series::Cumulative::Forward => {
+            let mut sum = 0.0;
+            for bin in bins.iter_mut() {
+                sum += bin.value;
+                bin.value = sum;
+            }
+        }
+        des::series::Cumulative::Reverse => {
+            let mut sum = 0.0;
+            for bin in bins.iter_mut().rev() {
+                sum += bin.value;
+                bin.value = sum;
+            }
+        }
+    }
+
+    let is_cumulative = cumulative != des::series::Cumulative::No;
+    match norm {
+        _ if count == 0 => (),
+        None => (),
+        Some(des::series::HistNorm::Density) if !is_cumulative => {
+            for bin in bins.iter_mut() {
+                bin.value /= count as f64 * (bin.range.1 - bin.range.0);
+            }
+        }
+        Some(des::series::HistNorm::Sum | des::series::HistNorm::Density) => {
+            for bin in bins.iter_mut() {
+                bin.value /= count as f64;
+            }
+        }
+        Some(des::series::HistNorm::Max) => {
+            let max = bins.iter().map(|b| b.value).fold(0.0, f64::max);
+            for bin in bins.iter_mut() {
+                bin.value /= max;
+            }
+        }
+    }
+
+    let x_bounds = axis::NumBounds::from((first, last));
+    let mut y_bounds = axis::NumBounds::from(0.0);
+    for bin in bins.iter() {
+        y_bounds.add_sample(bin.value);
+    }
+
+    Ok((bins, (x_bounds, y_bounds)))
+}
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    index: usize,
+    data: des::DataCol,
+    bin_spec: des::series::BinSpec,
+    mode: HistMode,
+    ab: (axis::NumBounds, axis::NumBounds),
     axes: (des::axis::Ref, des::axis::Ref),
     bins: Vec<HistBin>,
     path: Option<geom::Path>,
@@ -660,45 +1795,15 @@ impl Histogram {
     where
         D: data::Source + ?Sized,
     {
-        let mut bins = Vec::with_capacity(hist.bins() as usize);
-
-        let col = get_column(hist.data(), data_source)?;
-        let col = col.f64().ok_or(Error::InconsistentData(
-            "Histogram data must be numeric".into(),
-        ))?;
-        let x_bounds = col.bounds().ok_or(Error::UnboundedAxis)?;
-
-        let width = x_bounds.span() / hist.bins() as f64;
-        let mut val = x_bounds.start();
-        while val <= x_bounds.end() {
-            bins.push(HistBin {
-                range: (val, val + width),
-                value: 0.0,
-            });
-            val += width;
-        }
-
-        let samp_add = if hist.density() {
-            1.0 / (col.len_some() as f64 * width)
-        } else {
-            1.0
-        };
-
-        for x in col.f64_iter() {
-            if let Some(x) = x {
-                let idx = (((x - x_bounds.start()) / width).floor() as usize).min(bins.len() - 1);
-                bins[idx].value += samp_add;
-            }
-        }
-
-        let mut y_bounds = axis::NumBounds::NAN;
-        for bin in bins.iter() {
-            y_bounds.add_sample(bin.value);
-        }
+        let mode = (hist.cumulative(), hist.normalize());
+        let (bins, ab) = calc_hist_bins(data_source, hist.data(), hist.bins(), mode)?;
 
         Ok(Histogram {
             index,
-            ab: (x_bounds, y_bounds),
+            data: hist.data().clone(),
+            bin_spec: hist.bins().clone(),
+            mode,
+            ab,
             axes: (hist.x_axis().clone(), hist.y_axis().clone()),
             bins,
             path: None,
@@ -727,7 +1832,7 @@ impl Histogram {
         self.path = Some(path);
     }
 
-    fn draw<S>(&self, surface: &mut S, style: &Style)
+    fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
@@ -739,7 +1844,161 @@ impl Histogram {
             stroke: self.line.as_ref().map(|l| l.as_stroke(&rc)),
             transform: None,
         };
-        surface.draw_path(&path);
+        surface.draw_path(&path)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Hist2dBin {
+    /// Start and end of this bin along x
+    x_range: (f64, f64),
+    /// Start and end of this bin along y
+    y_range: (f64, f64),
+    /// Number of samples in this bin
+    count: u32,
+}
+
+fn calc_hist2d_bins<D>(
+    data_source: &D,
+    cols: &(des::DataCol, des::DataCol),
+    specs: &(des::series::BinSpec, des::series::BinSpec),
+) -> Result<(Vec<Hist2dBin>, (axis::NumBounds, axis::NumBounds)), Error>
+where
+    D: data::Source + ?Sized,
+{
+    let x_col = get_column(&cols.0, data_source)?;
+    let y_col = get_column(&cols.1, data_source)?;
+    if x_col.len() != y_col.len() {
+        return Err(Error::InconsistentData(
+            "X and Y data must be the same length".to_string(),
+        ));
+    }
+    let x_col = x_col.f64().ok_or(Error::InconsistentData(
+        "2D histogram data must be numeric".into(),
+    ))?;
+    let y_col = y_col.f64().ok_or(Error::InconsistentData(
+        "2D histogram data must be numeric".into(),
+    ))?;
+
+    let points: Vec<(f64, f64)> = x_col
+        .f64_iter()
+        .zip(y_col.f64_iter())
+        .filter_map(|(x, y)| Some((x?, y?)))
+        .filter(|(x, y)| x.is_finite() && y.is_finite())
+        .collect();
+
+    // the edges along each axis are those of a 1D histogram of the coordinates
+    let axis_edges = |spec: &des::series::BinSpec, coord: fn(&(f64, f64)) -> f64| {
+        let mut sorted: Vec<f64> = points.iter().map(coord).collect();
+        sorted.sort_by(f64::total_cmp);
+        calc_hist_edges(spec, &sorted)
+    };
+    let x_edges = axis_edges(&specs.0, |p| p.0)?;
+    let y_edges = axis_edges(&specs.1, |p| p.1)?;
+
+    let nx = x_edges.len() - 1;
+    let ny = y_edges.len() - 1;
+    if nx * ny > des::series::MAX_HIST_BINS as usize {
+        return Err(Error::InconsistentDesign(format!(
+            "2D histogram can't have more than {} bins",
+            des::series::MAX_HIST_BINS
+        )));
+    }
+
+    let mut bins = Vec::with_capacity(nx * ny);
+    for y in y_edges.windows(2) {
+        for x in x_edges.windows(2) {
+            bins.push(Hist2dBin {
+                x_range: (x[0], x[1]),
+                y_range: (y[0], y[1]),
+                count: 0,
+            });
+        }
+    }
+
+    for &(x, y) in points.iter() {
+        if let (Some(i), Some(j)) = (hist_bin_index(&x_edges, x), hist_bin_index(&y_edges, y)) {
+            bins[j * nx + i].count += 1;
+        }
+    }
+
+    let x_bounds = axis::NumBounds::from((x_edges[0], x_edges[nx]));
+    let y_bounds = axis::NumBounds::from((y_edges[0], y_edges[ny]));
+    Ok((bins, (x_bounds, y_bounds)))
+}
+
+#[derive(Debug, Clone)]
+struct Hist2d {
+    cols: (des::DataCol, des::DataCol),
+    bin_specs: (des::series::BinSpec, des::series::BinSpec),
+    ab: (axis::NumBounds, axis::NumBounds),
+    axes: (des::axis::Ref, des::axis::Ref),
+    bins: Vec<Hist2dBin>,
+    cells: Vec<(geom::Rect, ColorU8)>,
+    colormap: style::series::ColorMap,
+    norm: des::series::Hist2dNorm,
+}
+
+impl Hist2d {
+    fn prepare<D>(hist: &des::series::Hist2d, data_source: &D) -> Result<Self, Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        let cols = (hist.x_data().clone(), hist.y_data().clone());
+        let (bins, ab) = calc_hist2d_bins(data_source, &cols, hist.bins())?;
+
+        Ok(Hist2d {
+            cols,
+            bin_specs: hist.bins().clone(),
+            ab,
+            axes: (hist.x_axis().clone(), hist.y_axis().clone()),
+            bins,
+            cells: Vec::new(),
+            colormap: hist.colormap().clone(),
+            norm: hist.norm(),
+        })
+    }
+
+    /// Value of a bin count according to the normalization mode
+    fn norm_value(&self, count: u32) -> f64 {
+        match self.norm {
+            des::series::Hist2dNorm::Linear => count as f64,
+            des::series::Hist2dNorm::Log => (count as f64).ln_1p(),
+        }
+    }
+
+    fn update_data(&mut self, rect: &geom::Rect, cm: &CoordMapXy) {
+        let max_count = self.bins.iter().map(|b| b.count).max().unwrap_or(0);
+        let max_val = self.norm_value(max_count);
+
+        self.cells = self
+            .bins
+            .iter()
+            .filter(|b| b.count > 0)
+            .map(|b| {
+                let left = rect.left() + cm.x.map_coord_num(b.x_range.0);
+                let right = rect.left() + cm.x.map_coord_num(b.x_range.1);
+                let top = rect.bottom() - cm.y.map_coord_num(b.y_range.1);
+                let bottom = rect.bottom() - cm.y.map_coord_num(b.y_range.0);
+                let color = self.colormap.sample(self.norm_value(b.count) / max_val);
+                (geom::Rect::from_trbl(top, right, bottom, left), color)
+            })
+            .collect();
+    }
+
+    fn draw<S>(&self, surface: &mut S) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        for (cell, color) in &self.cells {
+            surface.draw_rect(&render::Rect {
+                rect: *cell,
+                fill: Some((*color).into()),
+                stroke: None,
+                transform: None,
+            })?;
+        }
+        Ok(())
     }
 }
 
@@ -749,6 +2008,57 @@ enum BarsBounds {
     Horizontal(axis::NumBounds, Categories),
 }
 
+impl BarsBounds {
+    fn orientation(&self) -> des::series::BarsOrientation {
+        match self {
+            BarsBounds::Vertical(..) => des::series::BarsOrientation::Vertical,
+            BarsBounds::Horizontal(..) => des::series::BarsOrientation::Horizontal,
+        }
+    }
+}
+
+fn calc_bars_bounds<D>(
+    data_source: &D,
+    cols: &(des::DataCol, des::DataCol),
+    orientation: Option<des::series::BarsOrientation>,
+    baseline: f64,
+) -> Result<BarsBounds, Error>
+where
+    D: data::Source + ?Sized,
+{
+    let (x_bounds, y_bounds) = calc_xy_bounds(data_source, Some(&cols.0), &cols.1)?;
+
+    let bounds = match (x_bounds, y_bounds) {
+        (axis::Bounds::Num(mut x_bounds), axis::Bounds::Cat(y_bounds)) => {
+            x_bounds.add_sample(baseline);
+            BarsBounds::Horizontal(x_bounds, y_bounds)
+        }
+        (axis::Bounds::Cat(x_bounds), axis::Bounds::Num(mut y_bounds)) => {
+            y_bounds.add_sample(baseline);
+            BarsBounds::Vertical(x_bounds, y_bounds)
+        }
+        _ => {
+            return Err(Error::InconsistentData(
+                "One of X and Y data must be numeric and the other categorical".to_string(),
+            ));
+        }
+    };
+
+    match orientation {
+        Some(des::series::BarsOrientation::Vertical) if bounds.orientation().is_horizontal() => {
+            Err(Error::InconsistentData(
+                "Vertical bars need categorical X data and numeric Y data".to_string(),
+            ))
+        }
+        Some(des::series::BarsOrientation::Horizontal) if bounds.orientation().is_vertical() => {
+            Err(Error::InconsistentData(
+                "Horizontal bars need numeric X data and categorical Y data".to_string(),
+            ))
+        }
+        _ => Ok(bounds),
+    }
+}
+
 #[derive(Debug, Clone)]
 struct Bars {
     index: usize,
@@ -756,7 +2066,13 @@ struct Bars {
     bounds: BarsBounds,
     axes: (des::axis::Ref, des::axis::Ref),
     position: des::series::BarsPosition,
+    corner_radius: f32,
+    orientation: Option<des::series::BarsOrientation>,
+    baseline: f64,
+    sign_colors: Option<(style::series::Color, style::series::Color)>,
     path: Option<geom::Path>,
+    /// The bars below the baseline, if colored by sign
+    neg_path: Option<geom::Path>,
     fill: style::series::Fill,
     line: Option<style::series::Stroke>,
 }
@@ -767,23 +2083,7 @@ impl Bars {
         D: data::Source + ?Sized,
     {
         let cols = (des.x_data().clone(), des.y_data().clone());
-        let (x_bounds, y_bounds) = calc_xy_bounds(data_source, &cols.0, &cols.1)?;
-
-        let bounds = match (x_bounds, y_bounds) {
-            (axis::Bounds::Num(mut x_bounds), axis::Bounds::Cat(y_bounds)) => {
-                x_bounds.add_sample(0.0);
-                BarsBounds::Horizontal(x_bounds, y_bounds)
-            }
-            (axis::Bounds::Cat(x_bounds), axis::Bounds::Num(mut y_bounds)) => {
-                y_bounds.add_sample(0.0);
-                BarsBounds::Vertical(x_bounds, y_bounds)
-            }
-            _ => {
-                return Err(Error::InconsistentData(
-                    "One of X and Y data must be numeric and the other categorical".to_string(),
-                ));
-            }
-        };
+        let bounds = calc_bars_bounds(data_source, &cols, des.orientation(), des.baseline())?;
 
         Ok(Bars {
             index,
@@ -791,7 +2091,12 @@ impl Bars {
             bounds,
             axes: (des.x_axis().clone(), des.y_axis().clone()),
             position: des.position().clone(),
+            corner_radius: des.corner_radius(),
+            orientation: des.orientation(),
+            baseline: des.baseline(),
+            sign_colors: des.sign_colors(),
             path: None,
+            neg_path: None,
             fill: des.fill().clone(),
             line: des.line().cloned(),
         })
@@ -804,87 +2109,414 @@ impl Bars {
         }
     }
 
-    fn update_data<D>(&mut self, data_source: &D, rect: &geom::Rect, cm: &CoordMapXy)
-    where
-        D: data::Source + ?Sized,
-    {
-        // unwraping here as data is checked during setup phase
-        let x_col = get_column(&self.cols.0, data_source).unwrap();
-        let y_col = get_column(&self.cols.1, data_source).unwrap();
-        debug_assert!(x_col.len() == y_col.len());
+    fn update_data<D>(&mut self, data_source: &D, rect: &geom::Rect, cm: &CoordMapXy)
+    where
+        D: data::Source + ?Sized,
+    {
+        // unwraping here as data is checked during setup phase
+        let x_col = get_column(&self.cols.0, data_source).unwrap();
+        let y_col = get_column(&self.cols.1, data_source).unwrap();
+        debug_assert!(x_col.len() == y_col.len());
+
+        let mut pb = geom::PathBuilder::new();
+        let mut neg_pb = geom::PathBuilder::new();
+        // bars below the baseline go to their own path if colored by sign
+        let by_sign = self.sign_colors.is_some();
+        let below = |v: data::SampleRef| by_sign && v.as_num().is_some_and(|v| v < self.baseline);
+
+        match &self.bounds {
+            BarsBounds::Vertical(..) => {
+                let cat_bin_width = cm.x.cat_bin_size();
+                let y_start = rect.bottom() - cm.y.map_coord_num(self.baseline);
+
+                for (x, y) in x_col.sample_iter().zip(y_col.sample_iter()) {
+                    if x.is_null() || y.is_null() {
+                        continue;
+                    }
+
+                    let pb = if below(y) { &mut neg_pb } else { &mut pb };
+                    let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
+                    let x_start = rect.left() + x + cat_bin_width * (self.position.offset - 0.5);
+                    let x_end = x_start + cat_bin_width * self.position.width;
+                    let y_end = rect.bottom() - y;
+                    push_bar(
+                        pb,
+                        Orientation::X,
+                        (x_start, x_end),
+                        (y_start, y_end),
+                        self.corner_radius,
+                    );
+                }
+            }
+            BarsBounds::Horizontal(..) => {
+                let cat_bin_height = cm.y.cat_bin_size();
+                let x_start = rect.left() + cm.x.map_coord_num(self.baseline);
+
+                for (x, y) in x_col.sample_iter().zip(y_col.sample_iter()) {
+                    if x.is_null() || y.is_null() {
+                        continue;
+                    }
+
+                    let pb = if below(x) { &mut neg_pb } else { &mut pb };
+                    let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
+                    let y_start = rect.bottom() - y - cat_bin_height * (self.position.offset - 0.5);
+                    let y_end = y_start - cat_bin_height * self.position.width;
+                    let x_end = rect.left() + x;
+                    push_bar(
+                        pb,
+                        Orientation::Y,
+                        (y_start, y_end),
+                        (x_start, x_end),
+                        self.corner_radius,
+                    );
+                }
+            }
+        }
+
+        self.path = pb.finish();
+        self.neg_path = neg_pb.finish();
+    }
+
+    fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        let rc = (style, self.index);
+
+        let (fill, neg_fill) = match self.sign_colors {
+            Some((pos, neg)) => (
+                fill_with_color(self.fill, pos),
+                fill_with_color(self.fill, neg),
+            ),
+            None => (self.fill, self.fill),
+        };
+        for (path, fill) in [(&self.path, fill), (&self.neg_path, neg_fill)] {
+            let Some(path) = path else {
+                continue;
+            };
+            let path = render::Path {
+                path,
+                fill: Some(fill.as_paint(&rc)),
+                stroke: self.line.as_ref().map(|l| l.as_stroke(&rc)),
+                transform: None,
+            };
+            surface.draw_path(&path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Push the outline of a bar to `pb`, from the baseline `val.0` to the bar end `val.1`,
+/// and spanning `cat` across the category axis `cat_axis`.
+/// The two corners at the bar end are rounded by `radius`.
+fn push_bar(
+    pb: &mut geom::PathBuilder,
+    cat_axis: Orientation,
+    cat: (f32, f32),
+    val: (f32, f32),
+    radius: f32,
+) {
+    let pt = |c: f32, v: f32| match cat_axis {
+        Orientation::X => (c, v),
+        Orientation::Y => (v, c),
+    };
+    let (c0, c1) = cat;
+    let (v0, v1) = val;
+    let r = radius
+        .min((c1 - c0).abs() / 2.0)
+        .min((v1 - v0).abs())
+        .max(0.0);
+
+    let (x, y) = pt(c0, v0);
+    pb.move_to(x, y);
+    if r == 0.0 {
+        let (x, y) = pt(c0, v1);
+        pb.line_to(x, y);
+        let (x, y) = pt(c1, v1);
+        pb.line_to(x, y);
+    } else {
+        // signed radius along each axis, and cubic control distance of a quarter circle
+        let dc = r.copysign(c1 - c0);
+        let dv = r.copysign(v1 - v0);
+        let k = 0.552_284_8;
+
+        let (x, y) = pt(c0, v1 - dv);
+        pb.line_to(x, y);
+        let (x1, y1) = pt(c0, v1 - dv + k * dv);
+        let (x2, y2) = pt(c0 + dc - k * dc, v1);
+        let (x, y) = pt(c0 + dc, v1);
+        pb.cubic_to(x1, y1, x2, y2, x, y);
+        let (x, y) = pt(c1 - dc, v1);
+        pb.line_to(x, y);
+        let (x1, y1) = pt(c1 - dc + k * dc, v1);
+        let (x2, y2) = pt(c1, v1 - dv + k * dv);
+        let (x, y) = pt(c1, v1 - dv);
+        pb.cubic_to(x1, y1, x2, y2, x, y);
+    }
+    let (x, y) = pt(c1, v0);
+    pb.line_to(x, y);
+}
+
+fn calc_bars_group_bounds<D>(
+    data_source: &D,
+    categories: &des::DataCol,
+    series: &[des::series::BarSeries],
+    orientation: &des::series::BarsOrientation,
+    arrangement: &des::series::BarsArrangement,
+) -> Result<(axis::Bounds, axis::Bounds), Error>
+where
+    D: data::Source + ?Sized,
+{
+    let cat_col = get_column(categories, data_source)?;
+    let cats: Categories = cat_col
+        .str()
+        .ok_or_else(|| {
+            Error::InconsistentData("BarsGroup categories must be a string column".to_string())
+        })?
+        .into();
+
+    let mut bounds_per_cat: Vec<axis::NumBounds> = vec![axis::NumBounds::from(0.0); cats.len()];
+
+    for bs in series {
+        let data_col = get_column(bs.data(), data_source)?;
+        if data_col.len() != cats.len() {
+            return Err(Error::InconsistentData(
+                "BarsGroup data must be the same length as categories".to_string(),
+            ));
+        }
+        let data_col = data_col.f64().ok_or(Error::InconsistentData(
+            "BarsGroup data must be numeric".to_string(),
+        ))?;
+
+        for (v, bounds) in data_col.f64_iter().zip(bounds_per_cat.iter_mut()) {
+            if let Some(v) = v {
+                match arrangement {
+                    des::series::BarsArrangement::Aside(..) => {
+                        bounds.add_sample(v);
+                    }
+                    des::series::BarsArrangement::Stack(..) => {
+                        if bounds.end().is_finite() {
+                            bounds.add_sample(v + bounds.end());
+                        } else {
+                            bounds.add_sample(v);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut num_bounds = axis::NumBounds::NAN;
+    for bounds in &bounds_per_cat {
+        num_bounds.unite_with(bounds);
+    }
 
-        let mut pb = geom::PathBuilder::new();
+    let bounds = match orientation {
+        des::series::BarsOrientation::Vertical => {
+            (axis::Bounds::Cat(cats), axis::Bounds::Num(num_bounds))
+        }
+        des::series::BarsOrientation::Horizontal => {
+            (axis::Bounds::Num(num_bounds), axis::Bounds::Cat(cats))
+        }
+    };
 
-        match &self.bounds {
-            BarsBounds::Vertical(..) => {
-                let cat_bin_width = cm.x.cat_bin_size();
-                let y_start = rect.bottom() - cm.y.map_coord_num(0.0);
+    Ok(bounds)
+}
 
-                for (x, y) in x_col.sample_iter().zip(y_col.sample_iter()) {
-                    if x.is_null() || y.is_null() {
-                        continue;
-                    }
+/// Format the label of a bar with `value`, within the category sum `cat_sum` and the group sum `total`.
+/// Values are formatted with `value_prec` decimals.
+fn format_bar_label(
+    labels: &des::series::BarLabels,
+    value: f64,
+    value_prec: usize,
+    cat_sum: f64,
+    total: f64,
+) -> Option<String> {
+    let percent = |sum: f64| {
+        (sum != 0.0).then(|| {
+            format!(
+                "{:.*}%",
+                labels.precision().unwrap_or(0),
+                100.0 * value / sum
+            )
+        })
+    };
+    match labels.content() {
+        des::series::BarLabelContent::Value => Some(format!("{value:.value_prec$}")),
+        des::series::BarLabelContent::PercentOfTotal => percent(total),
+        des::series::BarLabelContent::PercentOfCategory => percent(cat_sum),
+    }
+}
 
-                    let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
-                    let x_start = rect.left() + x + cat_bin_width * (self.position.offset - 0.5);
-                    let x_end = x_start + cat_bin_width * self.position.width;
-                    let y_end = rect.bottom() - y;
-                    pb.move_to(x_start, y_start);
-                    pb.line_to(x_start, y_end);
-                    pb.line_to(x_end, y_end);
-                    pb.line_to(x_end, y_start);
-                }
-            }
-            BarsBounds::Horizontal(..) => {
-                let cat_bin_height = cm.y.cat_bin_size();
-                let x_start = rect.left() + cm.x.map_coord_num(0.0);
+/// Number of decimals of the bar labels of a group with the given values.
+/// All the values are shown with the same decimals, the fewest representing them all,
+/// but without going beyond 3 significant digits of the largest value.
+fn bar_label_auto_precision(values: &[f64]) -> usize {
+    let max = values.iter().fold(0.0f64, |max, v| max.max(v.abs()));
+    if max == 0.0 {
+        return 0;
+    }
+    let max_prec = (2 - max.log10().floor() as i32).max(0) as usize;
+    ticks::min_precision(values).min(max_prec)
+}
 
-                for (x, y) in x_col.sample_iter().zip(y_col.sample_iter()) {
-                    if x.is_null() || y.is_null() {
-                        continue;
-                    }
+/// Compute the text of the label of each bar, per series and per category
+fn calc_bar_labels<D>(
+    data_source: &D,
+    labels: &des::series::BarLabels,
+    series: &[des::series::BarSeries],
+    num_cats: usize,
+) -> Result<Vec<Vec<Option<String>>>, Error>
+where
+    D: data::Source + ?Sized,
+{
+    let mut values = Vec::with_capacity(series.len());
+    for bs in series {
+        let data_col = get_column(bs.data(), data_source)?;
+        let data_col = data_col.f64().ok_or(Error::InconsistentData(
+            "BarsGroup data must be numeric".to_string(),
+        ))?;
+        values.push(data_col.f64_iter().collect::<Vec<_>>());
+    }
 
-                    let (x, y) = cm.map_coord((x, y)).expect("Should be valid coordinates");
-                    let y_start = rect.bottom() - y - cat_bin_height * (self.position.offset - 0.5);
-                    let y_end = y_start - cat_bin_height * self.position.width;
-                    let x_end = rect.left() + x;
-                    pb.move_to(x_start, y_start);
-                    pb.line_to(x_end, y_start);
-                    pb.line_to(x_end, y_end);
-                    pb.line_to(x_start, y_end);
-                }
-            }
+    let mut cat_sums = vec![0.0; num_cats];
+    for vals in &values {
+        for (sum, v) in cat_sums.iter_mut().zip(vals.iter()) {
+            *sum += v.unwrap_or(0.0);
         }
+    }
+    let total: f64 = cat_sums.iter().sum();
+
+    let value_prec = labels.precision().unwrap_or_else(|| {
+        let finite: Vec<f64> = values
+            .iter()
+            .flatten()
+            .flatten()
+            .copied()
+            .filter(|v| v.is_finite())
+            .collect();
+        bar_label_auto_precision(&finite)
+    });
+
+    Ok(values
+        .iter()
+        .map(|vals| {
+            vals.iter()
+                .zip(cat_sums.iter())
+                .map(|(v, sum)| {
+                    v.and_then(|v| format_bar_label(labels, v, value_prec, *sum, total))
+                })
+                .collect()
+        })
+        .collect())
+}
 
-        let path = pb.finish().expect("Should be a valid path");
-        self.path = Some(path);
+#[derive(Debug, Clone)]
+struct BarLabels {
+    des: des::series::BarLabels,
+    /// The text of each label, per series and per category
+    strings: Vec<Vec<Option<String>>>,
+    /// The laid out text of each label, per series and per category
+    texts: Vec<Vec<Option<Text>>>,
+    /// The labels that fit in their bar, with their position
+    placed: Vec<((usize, usize), geom::Point)>,
+    /// Whether the data changed the text of some labels since they were laid out
+    outdated: bool,
+}
+
+impl BarLabels {
+    fn prepare(
+        des: &des::series::BarLabels,
+        strings: Vec<Vec<Option<String>>>,
+        fontdb: &fontdb::Database,
+    ) -> Result<Self, Error> {
+        let align = (text::line::Align::Center, text::line::VerAlign::Middle);
+        let texts = strings
+            .iter()
+            .map(|series| {
+                series
+                    .iter()
+                    .map(|s| {
+                        let Some(s) = s else { return Ok(None) };
+                        let line_text = text::LineText::new(
+                            s.clone(),
+                            align,
+                            des.font_size(),
+                            des.font().clone(),
+                            fontdb,
+                        )?;
+                        Ok(Some(Text::from_line_text(&line_text, fontdb, des.color())?))
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(BarLabels {
+            des: des.clone(),
+            strings,
+            texts,
+            placed: Vec::new(),
+            outdated: false,
+        })
+    }
+
+    /// Place the label of the bar of `series_idx` in `cat_idx` at the center of the bar,
+    /// if it fits in the bar
+    fn place(
+        &mut self,
+        (series_idx, cat_idx): (usize, usize),
+        bar: geom::Rect,
+        orientation: &des::series::BarsOrientation,
+    ) {
+        let Some(Some(text)) = self.texts.get(series_idx).and_then(|s| s.get(cat_idx)) else {
+            return;
+        };
+        let val_size = match orientation {
+            des::series::BarsOrientation::Vertical => bar.height(),
+            des::series::BarsOrientation::Horizontal => bar.width(),
+        };
+        if val_size < self.des.min_size()
+            || text.width() > bar.width()
+            || text.height() > bar.height()
+        {
+            return;
+        }
+        let center = geom::Point {
+            x: bar.center_x(),
+            y: bar.center_y(),
+        };
+        self.placed.push(((series_idx, cat_idx), center));
     }
 
-    fn draw<S>(&self, surface: &mut S, style: &Style)
+    fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
-        let rc = (style, self.index);
-
-        let path = render::Path {
-            path: self.path.as_ref().unwrap(),
-            fill: Some(self.fill.as_paint(&rc)),
-            stroke: self.line.as_ref().map(|l| l.as_stroke(&rc)),
-            transform: None,
-        };
-        surface.draw_path(&path);
+        for &((series_idx, cat_idx), center) in &self.placed {
+            let Some(text) = &self.texts[series_idx][cat_idx] else {
+                continue;
+            };
+            let transform = geom::Transform::from_translate(center.x, center.y);
+            text.draw(surface, style, Some(&transform))?;
+        }
+        Ok(())
     }
 }
 
+/// The rectangle of each bar, with its series and category indices
+type BarRects = Vec<((usize, usize), geom::Rect)>;
+
 #[derive(Debug, Clone)]
 pub struct BarsGroup {
     fst_index: usize,
+    categories: des::DataCol,
     bounds: (axis::Bounds, axis::Bounds),
     axes: (des::axis::Ref, des::axis::Ref),
     orientation: des::series::BarsOrientation,
     arrangement: des::series::BarsArrangement,
     series: Vec<des::series::BarSeries>,
     series_paths: Vec<geom::Path>,
+    labels: Option<BarLabels>,
 }
 
 impl BarsGroup {
@@ -892,85 +2524,72 @@ impl BarsGroup {
         index: usize,
         des: &des::series::BarsGroup,
         data_source: &D,
+        fontdb: &fontdb::Database,
     ) -> Result<Self, Error>
     where
         D: data::Source + ?Sized,
     {
-        let cat_col = get_column(des.categories(), data_source)?;
-        let categories: Categories = cat_col
-            .str()
-            .ok_or_else(|| {
-                Error::InconsistentData("BarsGroup categories must be a string column".to_string())
-            })?
-            .into();
-
-        let mut bounds_per_cat: Vec<axis::NumBounds> =
-            vec![axis::NumBounds::from(0.0); categories.len()];
-
-        for bs in des.series() {
-            let data_col = get_column(bs.data(), data_source)?;
-            if data_col.len() != categories.len() {
-                return Err(Error::InconsistentData(
-                    "BarsGroup data must be the same length as categories".to_string(),
-                ));
-            }
-            let data_col = data_col.f64().ok_or(Error::InconsistentData(
-                "BarsGroup data must be numeric".to_string(),
-            ))?;
-
-            for (v, bounds) in data_col.f64_iter().zip(bounds_per_cat.iter_mut()) {
-                if let Some(v) = v {
-                    match des.arrangement() {
-                        des::series::BarsArrangement::Aside(..) => {
-                            bounds.add_sample(v);
-                        }
-                        des::series::BarsArrangement::Stack(..) => {
-                            if bounds.end().is_finite() {
-                                bounds.add_sample(v + bounds.end());
-                            } else {
-                                bounds.add_sample(v);
-                            }
-                        }
-                    }
+        let bounds = calc_bars_group_bounds(
+            data_source,
+            des.categories(),
+            des.series(),
+            des.orientation(),
+            des.arrangement(),
+        )?;
+
+        let labels = match des.labels() {
+            Some(labels) => {
+                let num_cats = match des.orientation() {
+                    des::series::BarsOrientation::Vertical => bounds.0.as_cat(),
+                    des::series::BarsOrientation::Horizontal => bounds.1.as_cat(),
                 }
+                .map_or(0, |cats| cats.len());
+                let strings = calc_bar_labels(data_source, labels, des.series(), num_cats)?;
+                Some(BarLabels::prepare(labels, strings, fontdb)?)
             }
-        }
-
-        let mut num_bounds = axis::NumBounds::NAN;
-        for bounds in &bounds_per_cat {
-            num_bounds.unite_with(bounds);
-        }
-
-        let bounds = match des.orientation() {
-            des::series::BarsOrientation::Vertical => {
-                (axis::Bounds::Cat(categories), axis::Bounds::Num(num_bounds))
-            }
-            des::series::BarsOrientation::Horizontal => {
-                (axis::Bounds::Num(num_bounds), axis::Bounds::Cat(categories))
-            }
+            None => None,
         };
 
         Ok(BarsGroup {
             fst_index: index,
+            categories: des.categories().clone(),
             bounds,
             axes: (des.x_axis().clone(), des.y_axis().clone()),
             orientation: des.orientation().clone(),
             arrangement: des.arrangement().clone(),
             series: des.series().to_vec(),
             series_paths: Vec::new(),
+            labels,
         })
     }
 
+    fn categories(&self) -> &Categories {
+        match self.orientation {
+            des::series::BarsOrientation::Vertical => self.bounds.0.as_cat().unwrap(),
+            des::series::BarsOrientation::Horizontal => self.bounds.1.as_cat().unwrap(),
+        }
+    }
+
+    /// Check whether the data changed the text of the labels
+    fn update_labels<D>(&mut self, data_source: &D) -> Result<(), Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        let num_cats = self.categories().len();
+        if let Some(labels) = self.labels.as_mut() {
+            let strings = calc_bar_labels(data_source, &labels.des, &self.series, num_cats)?;
+            labels.outdated = strings != labels.strings;
+        }
+        Ok(())
+    }
+
     fn update_data<D>(&mut self, data_source: &D, rect: &geom::Rect, cm: &CoordMapXy)
     where
         D: data::Source + ?Sized,
     {
-        let categories = match self.orientation {
-            des::series::BarsOrientation::Vertical => self.bounds.0.as_cat().unwrap(),
-            des::series::BarsOrientation::Horizontal => self.bounds.1.as_cat().unwrap(),
-        };
+        let categories = self.categories();
 
-        let paths = match self.arrangement {
+        let (paths, bars) = match self.arrangement {
             des::series::BarsArrangement::Aside(aside) => {
                 self.build_paths_aside(data_source, &aside, categories, rect, cm)
             }
@@ -979,8 +2598,17 @@ impl BarsGroup {
             }
         };
         self.series_paths = paths;
+
+        if let Some(labels) = self.labels.as_mut() {
+            labels.placed.clear();
+            for (idx, bar) in bars {
+                labels.place(idx, bar, &self.orientation);
+            }
+        }
     }
 
+    /// Build the path of each series, and the rectangle of each bar,
+    /// indexed by series and category
     fn build_paths_aside<D>(
         &self,
         data_source: &D,
@@ -988,13 +2616,13 @@ impl BarsGroup {
         categories: &Categories,
         rect: &geom::Rect,
         cm: &CoordMapXy,
-    ) -> Vec<geom::Path>
+    ) -> (Vec<geom::Path>, BarRects)
     where
         D: data::Source + ?Sized,
     {
         let num_series = self.series.len();
         if num_series == 0 {
-            return Vec::new();
+            return (Vec::new(), Vec::new());
         }
         let num_gaps = num_series - 1;
 
@@ -1006,14 +2634,15 @@ impl BarsGroup {
         let width = (width - gap * num_gaps as f32) / num_series as f32;
 
         let mut paths = Vec::with_capacity(num_series);
+        let mut bars = Vec::new();
 
-        for series in &self.series {
+        for (series_idx, series) in self.series.iter().enumerate() {
             let data_col = get_column(series.data(), data_source).unwrap();
             let data_col = data_col.f64().unwrap();
 
             let mut pb = geom::PathBuilder::new();
 
-            for (cat, val) in categories.iter().zip(data_col.f64_iter()) {
+            for (cat_idx, (cat, val)) in categories.iter().zip(data_col.f64_iter()).enumerate() {
                 let Some(val) = val else { continue };
 
                 let val_start = 0.0;
@@ -1023,6 +2652,8 @@ impl BarsGroup {
                 let val_coords = self.orientation.val_coords(cm, val_start, val_end, rect);
                 self.orientation
                     .add_series_path(&mut pb, cat_coords, val_coords);
+                let bar = self.orientation.bar_rect(cat_coords, val_coords);
+                bars.push(((series_idx, cat_idx), bar));
             }
 
             let path = pb.finish().expect("Failed to build path");
@@ -1030,9 +2661,11 @@ impl BarsGroup {
 
             offset += width + gap;
         }
-        paths
+        (paths, bars)
     }
 
+    /// Build the path of each series, and the rectangle of each bar,
+    /// indexed by series and category
     fn build_paths_stack<D>(
         &self,
         data_source: &D,
@@ -1040,15 +2673,16 @@ impl BarsGroup {
         categories: &Categories,
         rect: &geom::Rect,
         cm: &CoordMapXy,
-    ) -> Vec<geom::Path>
+    ) -> (Vec<geom::Path>, BarRects)
     where
         D: data::Source + ?Sized,
     {
         let mut cat_values = vec![0.0; categories.len()];
 
         let mut paths = Vec::with_capacity(self.series.len());
+        let mut bars = Vec::new();
 
-        for series in &self.series {
+        for (series_idx, series) in self.series.iter().enumerate() {
             let data_col = get_column(series.data(), data_source).unwrap();
             let data_col = data_col.f64().unwrap();
 
@@ -1072,15 +2706,281 @@ impl BarsGroup {
                 let val_coords = self.orientation.val_coords(cm, val_start, val_end, rect);
                 self.orientation
                     .add_series_path(&mut pb, cat_coords, val_coords);
+                let bar = self.orientation.bar_rect(cat_coords, val_coords);
+                bars.push(((series_idx, idx), bar));
             }
 
             let path = pb.finish().expect("Failed to build path");
             paths.push(path);
         }
-        paths
+        (paths, bars)
+    }
+
+    fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
+    where
+        S: render::Surface,
+    {
+        let mut col_idx = self.fst_index;
+
+        for (series, path) in self.series.iter().zip(self.series_paths.iter()) {
+            let rc = (style, col_idx);
+            col_idx += 1;
+
+            let rpath = render::Path {
+                path,
+                fill: Some(series.fill().as_paint(&rc)),
+                stroke: series.line().map(|l| l.as_stroke(&rc)),
+                transform: None,
+            };
+            surface.draw_path(&rpath)?;
+        }
+
+        if let Some(labels) = &self.labels {
+            labels.draw(surface, style)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lower and upper levels of the areas of a group, over the shared x grid
+#[derive(Debug, Clone)]
+struct AreasStack {
+    x: Vec<f64>,
+    /// lower and upper levels of each area series
+    levels: Vec<(Vec<f64>, Vec<f64>)>,
+}
+
+/// Linearly interpolate the (x, y) samples at `x`, sorted by x.
+/// Returns zero outside of the x range of the samples.
+fn interpolate_at(samples: &[(f64, f64)], x: f64) -> f64 {
+    let i = samples.partition_point(|s| s.0 < x);
+    if i < samples.len() && samples[i].0 == x {
+        return samples[i].1;
+    }
+    if i == 0 || i == samples.len() {
+        return 0.0;
+    }
+    let (x0, y0) = samples[i - 1];
+    let (x1, y1) = samples[i];
+    y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+}
+
+/// Compute the baseline of the stack for the given area values.
+/// `values[i][j]` is the value of the series `i` at the x index `j`.
+fn calc_areas_baseline(
+    baseline: des::series::AreasBaseline,
+    x: &[f64],
+    values: &[Vec<f64>],
+) -> Vec<f64> {
+    let total = |j: usize| values.iter().map(|v| v[j]).sum::<f64>();
+    match baseline {
+        des::series::AreasBaseline::Zero => vec![0.0; x.len()],
+        des::series::AreasBaseline::Symmetric => (0..x.len()).map(|j| -total(j) / 2.0).collect(),
+        des::series::AreasBaseline::Wiggle => {
+            // each step of the baseline cancels the weighted average slope of the area mid-lines
+            let mut base = Vec::with_capacity(x.len());
+            let mut g = 0.0;
+            for j in 0..x.len() {
+                if j > 0 {
+                    let mut weighted = 0.0;
+                    let mut below = 0.0;
+                    for v in values {
+                        let dv = v[j] - v[j - 1];
+                        weighted += (below + dv / 2.0) * v[j];
+                        below += dv;
+                    }
+                    let total = total(j);
+                    if total != 0.0 {
+                        g -= weighted / total;
+                    }
+                }
+                base.push(g);
+            }
+            base
+        }
+    }
+}
+
+fn calc_areas_stack<D>(
+    data_source: &D,
+    series: &[des::series::AreaSeries],
+    baseline: des::series::AreasBaseline,
+    interpolate_x: bool,
+) -> Result<(AreasStack, (axis::Bounds, axis::Bounds)), Error>
+where
+    D: data::Source + ?Sized,
+{
+    let Some(first) = series.first() else {
+        return Err(Error::InconsistentDesign(
+            "AreasGroup must have at least one series".to_string(),
+        ));
+    };
+
+    let f64_col = |col: &des::DataCol| -> Result<Vec<Option<f64>>, Error> {
+        let col = get_column(col, data_source)?;
+        let col = col.f64().ok_or(Error::InconsistentData(
+            "AreasGroup data must be numeric".to_string(),
+        ))?;
+        Ok(col.f64_iter().collect())
+    };
+
+    // null x values are kept in the grid as NaN, and split the areas
+    let x: Vec<f64> = f64_col(first.x_data())?
+        .into_iter()
+        .map(|x| x.unwrap_or(f64::NAN))
+        .collect();
+
+    let mut values = Vec::with_capacity(series.len());
+    for s in series {
+        let sx = f64_col(s.x_data())?;
+        let sy = f64_col(s.y_data())?;
+        if sx.len() != sy.len() {
+            return Err(Error::InconsistentData(
+                "X and Y data must be the same length".to_string(),
+            ));
+        }
+        let same_grid = sx.len() == x.len()
+            && sx
+                .iter()
+                .zip(x.iter())
+                .all(|(a, b)| a.map_or(b.is_nan(), |a| a == *b));
+
+        let v = if same_grid {
+            sy.iter()
+                .map(|y| y.filter(|y| y.is_finite()).unwrap_or(0.0))
+                .collect()
+        } else if interpolate_x {
+            let mut samples: Vec<(f64, f64)> = sx
+                .iter()
+                .zip(sy.iter())
+                .filter_map(|(x, y)| Some((x.filter(|x| x.is_finite())?, y.unwrap_or(0.0))))
+                .filter(|(_, y)| y.is_finite())
+                .collect();
+            samples.sort_by(|a, b| a.0.total_cmp(&b.0));
+            x.iter().map(|&x| interpolate_at(&samples, x)).collect()
+        } else {
+            return Err(Error::InconsistentData(
+                "AreasGroup series must share the same X data".to_string(),
+            ));
+        };
+        values.push(v);
+    }
+
+    let mut lower = calc_areas_baseline(baseline, &x, &values);
+    let mut levels = Vec::with_capacity(values.len());
+    let mut x_bounds = axis::NumBounds::NAN;
+    let mut y_bounds = axis::NumBounds::NAN;
+    for v in values {
+        let upper: Vec<f64> = lower.iter().zip(v.iter()).map(|(l, v)| l + v).collect();
+        for (j, &x) in x.iter().enumerate() {
+            if x.is_finite() {
+                x_bounds.add_sample(x);
+                y_bounds.add_sample(lower[j]);
+                y_bounds.add_sample(upper[j]);
+            }
+        }
+        levels.push((lower, upper.clone()));
+        lower = upper;
+    }
+    if !x_bounds.start().is_finite() {
+        return Err(Error::UnboundedAxis);
+    }
+
+    let bounds = (axis::Bounds::Num(x_bounds), axis::Bounds::Num(y_bounds));
+    Ok((AreasStack { x, levels }, bounds))
+}
+
+#[derive(Debug, Clone)]
+pub struct AreasGroup {
+    fst_index: usize,
+    bounds: (axis::Bounds, axis::Bounds),
+    stack: AreasStack,
+    axes: (des::axis::Ref, des::axis::Ref),
+    baseline: des::series::AreasBaseline,
+    interpolate_x: bool,
+    series: Vec<des::series::AreaSeries>,
+    series_paths: Vec<Option<geom::Path>>,
+}
+
+impl AreasGroup {
+    fn prepare<D>(
+        index: usize,
+        des: &des::series::AreasGroup,
+        data_source: &D,
+    ) -> Result<Self, Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        let (stack, bounds) = calc_areas_stack(
+            data_source,
+            des.series(),
+            des.baseline(),
+            des.interpolate_x(),
+        )?;
+
+        Ok(AreasGroup {
+            fst_index: index,
+            bounds,
+            stack,
+            axes: (des.x_axis().clone(), des.y_axis().clone()),
+            baseline: des.baseline(),
+            interpolate_x: des.interpolate_x(),
+            series: des.series().to_vec(),
+            series_paths: Vec::new(),
+        })
     }
 
-    fn draw<S>(&self, surface: &mut S, style: &Style)
+    fn update_bounds<D>(&mut self, data_source: &D) -> Result<(), Error>
+    where
+        D: data::Source + ?Sized,
+    {
+        let (stack, bounds) =
+            calc_areas_stack(data_source, &self.series, self.baseline, self.interpolate_x)?;
+        self.stack = stack;
+        self.bounds = bounds;
+        Ok(())
+    }
+
+    fn update_data(&mut self, rect: &geom::Rect, cm: &CoordMapXy) {
+        let x = &self.stack.x;
+        let map = |x: f64, y: f64| plot_to_fig(rect, cm.x.map_coord_num(x), cm.y.map_coord_num(y));
+
+        self.series_paths = self
+            .stack
+            .levels
+            .iter()
+            .map(|(lower, upper)| {
+                let mut pb = geom::PathBuilder::new();
+                // each run of finite x values is a separate closed area
+                let mut j = 0;
+                while j < x.len() {
+                    if !x[j].is_finite() {
+                        j += 1;
+                        continue;
+                    }
+                    let start = j;
+                    while j < x.len() && x[j].is_finite() {
+                        j += 1;
+                    }
+                    let run = start..j;
+                    let uppers = x[run.clone()].iter().zip(&upper[run.clone()]);
+                    let lowers = x[run.clone()].iter().zip(&lower[run]).rev();
+                    for (i, (&x, &y)) in uppers.chain(lowers).enumerate() {
+                        let (px, py) = map(x, y);
+                        if i == 0 {
+                            pb.move_to(px, py);
+                        } else {
+                            pb.line_to(px, py);
+                        }
+                    }
+                    pb.close();
+                }
+                pb.finish()
+            })
+            .collect();
+    }
+
+    fn draw<S>(&self, surface: &mut S, style: &Style) -> Result<(), render::Error>
     where
         S: render::Surface,
     {
@@ -1090,14 +2990,16 @@ impl BarsGroup {
             let rc = (style, col_idx);
             col_idx += 1;
 
+            let Some(path) = path else { continue };
             let rpath = render::Path {
                 path,
                 fill: Some(series.fill().as_paint(&rc)),
                 stroke: series.line().map(|l| l.as_stroke(&rc)),
                 transform: None,
             };
-            surface.draw_path(&rpath);
+            surface.draw_path(&rpath)?;
         }
+        Ok(())
     }
 }
 
@@ -1128,6 +3030,8 @@ trait BarsOrientationExt {
         cat_coords: (f32, f32),
         val_coords: (f32, f32),
     );
+
+    fn bar_rect(&self, cat_coords: (f32, f32), val_coords: (f32, f32)) -> geom::Rect;
 }
 
 impl BarsOrientationExt for des::series::BarsOrientation {
@@ -1204,4 +3108,340 @@ impl BarsOrientationExt for des::series::BarsOrientation {
             }
         }
     }
+
+    fn bar_rect(&self, cat_coords: (f32, f32), val_coords: (f32, f32)) -> geom::Rect {
+        let (xs, ys) = match self {
+            Self::Vertical => (cat_coords, val_coords),
+            Self::Horizontal => (val_coords, cat_coords),
+        };
+        geom::Rect::from_trbl(
+            ys.0.min(ys.1),
+            xs.0.max(xs.1),
+            ys.0.max(ys.1),
+            xs.0.min(xs.1),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use des::series::{BinSpec, Cumulative, HistNorm};
+
+    #[test]
+    fn test_hist_edges() {
+        let samples: Vec<f64> = (0..=100).map(|i| i as f64 / 10.0).collect();
+
+        let edges = calc_hist_edges(&BinSpec::Count(4), &samples).unwrap();
+        assert_eq!(edges, vec![0.0, 2.5, 5.0, 7.5, 10.0]);
+
+        let edges = calc_hist_edges(&BinSpec::Width(3.0), &samples).unwrap();
+        assert_eq!(edges, vec![0.0, 3.0, 6.0, 9.0, 12.0]);
+
+        // 101 samples: ceil(log2(101)) + 1 = 8 bins
+        let edges = calc_hist_edges(&BinSpec::Sturges, &samples).unwrap();
+        assert_eq!(edges.len(), 9);
+
+        // IQR = 5, width = 10 / cbrt(101) ~= 2.15
+        let edges = calc_hist_edges(&BinSpec::FreedmanDiaconis, &samples).unwrap();
+        assert_eq!(edges.len(), 6);
+
+        let edges = calc_hist_edges(&BinSpec::Edges(vec![0.0, 1.0, 5.0]), &samples).unwrap();
+        assert_eq!(edges, vec![0.0, 1.0, 5.0]);
+
+        assert!(calc_hist_edges(&BinSpec::Edges(vec![0.0, 0.0]), &samples).is_err());
+        assert!(calc_hist_edges(&BinSpec::Width(0.0), &samples).is_err());
+    }
+
+    #[test]
+    fn test_hist_edges_max_bins() {
+        let samples = vec![0.0, 1000.0];
+
+        let err = calc_hist_edges(&BinSpec::Width(1e-9), &samples).unwrap_err();
+        assert!(matches!(err, Error::InconsistentDesign(_)));
+        let err = calc_hist_edges(&BinSpec::Count(u32::MAX), &samples).unwrap_err();
+        assert!(matches!(err, Error::InconsistentDesign(_)));
+
+        let max = des::series::MAX_HIST_BINS;
+        let edges = calc_hist_edges(&BinSpec::Count(max), &samples).unwrap();
+        assert_eq!(edges.len(), max as usize + 1);
+        let edges = calc_hist_edges(&BinSpec::Width(1000.0 / max as f64), &samples).unwrap();
+        assert_eq!(edges.len(), max as usize + 1);
+    }
+
+    #[test]
+    fn test_hist_edges_freedman_diaconis_fallback() {
+        // tiny IQR with a large outlier: the Freedman-Diaconis width is minute
+        let mut samples: Vec<f64> = (0..1000).map(|i| i as f64 * 1e-9).collect();
+        samples.push(1e6);
+
+        let edges = calc_hist_edges(&BinSpec::FreedmanDiaconis, &samples).unwrap();
+        let sturges = calc_hist_edges(&BinSpec::Sturges, &samples).unwrap();
+        assert_eq!(edges, sturges);
+    }
+
+    #[test]
+    fn test_hist_bins_density() {
+        let data = des::data_inline(vec![0.0, 0.5, 1.0, 2.0, 3.0, 4.0]);
+        let spec = BinSpec::Edges(vec![0.0, 1.0, 4.0]);
+
+        let mode = (Cumulative::No, None);
+        let (bins, (x_bounds, _)) = calc_hist_bins(&(), &data, &spec, mode).unwrap();
+        assert_eq!(bins.len(), 2);
+        assert_eq!(bins[0].value, 2.0);
+        assert_eq!(bins[1].value, 4.0);
+        assert_eq!((x_bounds.start(), x_bounds.end()), (0.0, 4.0));
+
+        let mode = (Cumulative::No, Some(HistNorm::Density));
+        let (bins, _) = calc_hist_bins(&(), &data, &spec, mode).unwrap();
+        let area: f64 = bins.iter().map(|b| b.value * (b.range.1 - b.range.0)).sum();
+        assert!((area - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hist_bins_cumulative() {
+        let data = des::data_inline(vec![0.0, 0.5, 1.0, 2.0, 3.0, 4.0]);
+        let spec = BinSpec::Edges(vec![0.0, 1.0, 2.0, 4.0]);
+        let values = |mode| {
+            let (bins, (_, y_bounds)) = calc_hist_bins(&(), &data, &spec, mode).unwrap();
+            let values: Vec<f64> = bins.iter().map(|b| b.value).collect();
+            (values, y_bounds.end())
+        };
+
+        assert_eq!(
+            values((Cumulative::Forward, None)),
+            (vec![2.0, 3.0, 6.0], 6.0)
+        );
+        assert_eq!(
+            values((Cumulative::Reverse, None)),
+            (vec![6.0, 4.0, 3.0], 6.0)
+        );
+        assert_eq!(
+            values((Cumulative::Forward, Some(HistNorm::Sum))),
+            (vec![2.0 / 6.0, 0.5, 1.0], 1.0)
+        );
+        assert_eq!(
+            values((Cumulative::Forward, Some(HistNorm::Density))),
+            (vec![2.0 / 6.0, 0.5, 1.0], 1.0)
+        );
+        assert_eq!(
+            values((Cumulative::No, Some(HistNorm::Max))),
+            (vec![2.0 / 3.0, 1.0 / 3.0, 1.0], 1.0)
+        );
+    }
+
+    #[test]
+    fn test_areas_stack() {
+        use des::series::{AreaSeries, AreasBaseline};
+
+        let x = || des::data_inline(vec![0.0, 1.0, 2.0]);
+        let series = vec![
+            AreaSeries::new(x(), des::data_inline(vec![1.0, 2.0, 3.0])),
+            AreaSeries::new(x(), des::data_inline(vec![3.0, 2.0, 1.0])),
+        ];
+
+        let (stack, (_, y_bounds)) =
+            calc_areas_stack(&(), &series, AreasBaseline::Zero, false).unwrap();
+        assert_eq!(stack.levels[0], (vec![0.0; 3], vec![1.0, 2.0, 3.0]));
+        assert_eq!(stack.levels[1], (vec![1.0, 2.0, 3.0], vec![4.0; 3]));
+        let y_bounds = y_bounds.as_bound_ref().as_num().unwrap();
+        assert_eq!((y_bounds.start(), y_bounds.end()), (0.0, 4.0));
+
+        let (stack, _) = calc_areas_stack(&(), &series, AreasBaseline::Symmetric, false).unwrap();
+        assert_eq!(stack.levels[0].0, vec![-2.0; 3]);
+        assert_eq!(stack.levels[1].1, vec![2.0; 3]);
+
+        // at each step, the mid-lines of both areas rise by 1/2,
+        // so the baseline goes down by 1/2 to cancel their weighted average slope
+        let (stack, _) = calc_areas_stack(&(), &series, AreasBaseline::Wiggle, false).unwrap();
+        assert_eq!(stack.levels[0].0, vec![0.0, -0.5, -1.0]);
+        assert_eq!(stack.levels[0].1, vec![1.0, 1.5, 2.0]);
+        assert_eq!(stack.levels[1].1, vec![4.0, 3.5, 3.0]);
+    }
+
+    #[test]
+    fn test_areas_stack_x_grid() {
+        use des::series::{AreaSeries, AreasBaseline};
+
+        let series = vec![
+            AreaSeries::new(
+                des::data_inline(vec![0.0, 1.0, 2.0]),
+                des::data_inline(vec![1.0, 1.0, 1.0]),
+            ),
+            AreaSeries::new(
+                des::data_inline(vec![0.0, 2.0]),
+                des::data_inline(vec![2.0, 4.0]),
+            ),
+        ];
+        assert!(matches!(
+            calc_areas_stack(&(), &series, AreasBaseline::Zero, false),
+            Err(Error::InconsistentData(..))
+        ));
+
+        let (stack, _) = calc_areas_stack(&(), &series, AreasBaseline::Zero, true).unwrap();
+        assert_eq!(stack.levels[1].1, vec![3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_hist2d_bins() {
+        let cols = (
+            des::data_inline(vec![0.0, 1.0, 2.0, 3.0, 4.0]),
+            des::data_inline(vec![0.0, 0.0, 1.0, 1.0, 4.0]),
+        );
+        let count_specs = |n| (BinSpec::Count(n), BinSpec::Count(n));
+        let (bins, (x_bounds, y_bounds)) = calc_hist2d_bins(&(), &cols, &count_specs(2)).unwrap();
+        assert_eq!((x_bounds.start(), x_bounds.end()), (0.0, 4.0));
+        assert_eq!((y_bounds.start(), y_bounds.end()), (0.0, 4.0));
+
+        // bins are stored row by row, from the bottom left one
+        let counts: Vec<u32> = bins.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 2, 0, 1]);
+        assert_eq!(bins[1].x_range, (2.0, 4.0));
+        assert_eq!(bins[1].y_range, (0.0, 2.0));
+
+        // the bounds of a single value along Y are widened around it, so it falls in the middle row
+        let cols = (
+            des::data_inline(vec![0.0, 1.0, 2.0]),
+            des::data_inline(vec![1.0, 1.0, 1.0]),
+        );
+        let specs = (BinSpec::Count(2), BinSpec::Count(3));
+        let (bins, (_, y_bounds)) = calc_hist2d_bins(&(), &cols, &specs).unwrap();
+        assert_eq!((y_bounds.start(), y_bounds.end()), (0.5, 1.5));
+        assert!(bins.iter().all(|b| b.y_range.1 > b.y_range.0));
+        let counts: Vec<u32> = bins.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![0, 0, 1, 2, 0, 0]);
+
+        let cols = (
+            des::data_inline(vec![0.0, 1.0]),
+            des::data_inline(vec![0.0]),
+        );
+        assert!(calc_hist2d_bins(&(), &cols, &count_specs(2)).is_err());
+    }
+
+    #[test]
+    fn test_hist2d_bin_specs() {
+        let cols = (
+            des::data_inline(vec![0.0, 1.0, 2.0, 3.0, 4.0, 9.0]),
+            des::data_inline(vec![0.0, 0.0, 1.0, 1.0, 4.0, 1.0]),
+        );
+
+        // explicit edges along x leave out the data outside of them, width along y
+        let specs = (BinSpec::Edges(vec![0.0, 1.0, 4.0]), BinSpec::Width(2.5));
+        let (bins, (x_bounds, y_bounds)) = calc_hist2d_bins(&(), &cols, &specs).unwrap();
+        assert_eq!((x_bounds.start(), x_bounds.end()), (0.0, 4.0));
+        assert_eq!((y_bounds.start(), y_bounds.end()), (0.0, 5.0));
+        let counts: Vec<u32> = bins.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![1, 3, 0, 1]);
+        assert_eq!(bins[1].x_range, (1.0, 4.0));
+
+        // the bins of each axis are checked like those of a 1D histogram
+        let specs = (BinSpec::Width(0.0), BinSpec::Count(2));
+        assert!(calc_hist2d_bins(&(), &cols, &specs).is_err());
+
+        // so is the total number of bins
+        let max = des::series::MAX_HIST_BINS;
+        let specs = (BinSpec::Count(max), BinSpec::Count(2));
+        let err = calc_hist2d_bins(&(), &cols, &specs).unwrap_err();
+        assert!(matches!(err, Error::InconsistentDesign(_)));
+    }
+
+    #[test]
+    fn test_bar_labels() {
+        use des::series::{BarLabelContent, BarLabels, BarSeries};
+
+        let series = vec![
+            BarSeries::new(des::data_inline(vec![1.0, 2.0])),
+            BarSeries::new(des::data_inline(vec![3.0, 4.0])),
+        ];
+        let labels = |content| {
+            let labels = BarLabels::new(content);
+            calc_bar_labels(&(), &labels, &series, 2).unwrap()
+        };
+
+        assert_eq!(
+            labels(BarLabelContent::Value)[0],
+            vec![Some("1".to_string()), Some("2".to_string())]
+        );
+        assert_eq!(
+            labels(BarLabelContent::PercentOfTotal)[1],
+            vec![Some("30%".to_string()), Some("40%".to_string())]
+        );
+        assert_eq!(
+            labels(BarLabelContent::PercentOfCategory)[1],
+            vec![Some("75%".to_string()), Some("67%".to_string())]
+        );
+
+        // values are shown with the decimals needed by the group, without float noise,
+        // and up to 3 significant digits of the largest value
+        let series = vec![
+            BarSeries::new(des::data_inline(vec![0.1 + 0.2, 1.0])),
+            BarSeries::new(des::data_inline(vec![2.25, 1.0 / 3.0])),
+        ];
+        let strings = calc_bar_labels(&(), &BarLabels::default(), &series, 2).unwrap();
+        assert_eq!(
+            strings[1],
+            vec![Some("2.25".to_string()), Some("0.33".to_string())]
+        );
+        let series = &series[..1];
+        let strings = calc_bar_labels(&(), &BarLabels::default(), series, 2).unwrap();
+        assert_eq!(
+            strings[0],
+            vec![Some("0.3".to_string()), Some("1.0".to_string())]
+        );
+        let labels = BarLabels::default().with_precision(2);
+        let strings = calc_bar_labels(&(), &labels, series, 2).unwrap();
+        assert_eq!(
+            strings[0],
+            vec![Some("0.30".to_string()), Some("1.00".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_jitter_unit() {
+        let units: Vec<f32> = (0..1000).map(|i| jitter_unit(42, i)).collect();
+        assert!(units.iter().all(|u| (0.0..1.0).contains(u)));
+        // reproducible, and depending on the seed
+        assert_eq!(units[7], jitter_unit(42, 7));
+        assert_ne!(units[7], jitter_unit(43, 7));
+        // roughly uniform
+        let mean = units.iter().sum::<f32>() / units.len() as f32;
+        assert!((mean - 0.5).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_swarm_offsets() {
+        // two categories, with points all at the same value in the first one
+        let centers = [10.0, 10.0, 10.0, 50.0, 50.0];
+        let values = [0.0, 0.0, 0.0, 0.0, 20.0];
+        let diameters = [4.0; 5];
+        let widths = [20.0; 5];
+        let offsets = swarm_offsets(&centers, &values, &diameters, &widths);
+
+        let mut first = offsets[..3].to_vec();
+        first.sort_by(f32::total_cmp);
+        assert_eq!(first, vec![-4.0, 0.0, 4.0]);
+        // no overlap in the second category
+        assert_eq!(&offsets[3..], &[0.0, 0.0]);
+
+        // clamped to the category width
+        let offsets = swarm_offsets(&centers, &values, &diameters, &[6.0; 5]);
+        assert!(offsets.iter().all(|o| o.abs() <= 3.0));
+    }
+
+    #[test]
+    fn test_swarm_offsets_distant_values() {
+        // points far apart along the values don't push each other,
+        // pairs of points at the same value do
+        let n = 1000;
+        let centers = vec![0.0; n];
+        let values: Vec<f32> = (0..n).map(|i| (i / 2) as f32 * 10.0).collect();
+        let diameters = vec![4.0; n];
+        let widths = vec![100.0; n];
+        let offsets = swarm_offsets(&centers, &values, &diameters, &widths);
+        for pair in offsets.chunks(2) {
+            let mut pair = pair.to_vec();
+            pair.sort_by(f32::total_cmp);
+            assert_eq!(pair, vec![-4.0, 0.0]);
+        }
+    }
 }
@@ -0,0 +1,97 @@
+//! Smoothing of series data
+
+use crate::des::series::Smoothing;
+use crate::drawing::fit;
+
+/// Smooth a run of consecutive, non-null values.
+/// The samples are assumed to be evenly spaced.
+pub fn smooth(smoothing: &Smoothing, values: &[f64]) -> Vec<f64> {
+    match *smoothing {
+        Smoothing::MovingAverage(window) => moving_average(values, window),
+        Smoothing::Ewma(alpha) => ewma(values, alpha),
+        Smoothing::SavitzkyGolay { window, order } => savitzky_golay(values, window, order),
+    }
+}
+
+/// Range of the centered window around `idx`, truncated to the values bounds
+fn window_range(idx: usize, window: usize, len: usize) -> std::ops::Range<usize> {
+    let half = window / 2;
+    let start = idx.saturating_sub(half);
+    let end = (idx + window - half).min(len);
+    start..end
+}
+
+fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    (0..values.len())
+        .map(|i| {
+            let win = &values[window_range(i, window, values.len())];
+            win.iter().sum::<f64>() / win.len() as f64
+        })
+        .collect()
+}
+
+fn ewma(values: &[f64], alpha: f64) -> Vec<f64> {
+    let alpha = alpha.clamp(0.0, 1.0);
+    let mut acc = None;
+    values
+        .iter()
+        .map(|&v| {
+            let s = match acc {
+                None => v,
+                Some(prev) => alpha * v + (1.0 - alpha) * prev,
+            };
+            acc = Some(s);
+            s
+        })
+        .collect()
+}
+
+fn savitzky_golay(values: &[f64], window: usize, order: u32) -> Vec<f64> {
+    let window = window.max(1);
+    (0..values.len())
+        .map(|i| {
+            let range = window_range(i, window, values.len());
+            let xs: Vec<f64> = range.clone().map(|j| j as f64 - i as f64).collect();
+            match fit::polyfit(&xs, &values[range], order as usize) {
+                // evaluated at the centered x = 0
+                Some(coefs) => coefs[0],
+                // window too small for the polynomial order (e.g. short runs)
+                None => values[i],
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_moving_average() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let smoothed = smooth(&Smoothing::MovingAverage(3), &values);
+        assert_eq!(smoothed, vec![1.5, 2.0, 3.0, 4.0, 4.5]);
+    }
+
+    #[test]
+    fn test_ewma() {
+        let values = [0.0, 1.0, 1.0];
+        let smoothed = smooth(&Smoothing::Ewma(0.5), &values);
+        assert_eq!(smoothed, vec![0.0, 0.5, 0.75]);
+    }
+
+    #[test]
+    fn test_savitzky_golay() {
+        // a quadratic is preserved by a quadratic filter, including at the edges
+        let values: Vec<f64> = (0..10).map(|i| (i * i) as f64).collect();
+        let smoothing = Smoothing::SavitzkyGolay {
+            window: 5,
+            order: 2,
+        };
+        let smoothed = smooth(&smoothing, &values);
+        for (s, v) in smoothed.iter().zip(values.iter()) {
+            assert!((s - v).abs() < 1e-9);
+        }
+    }
+}
@@ -6,34 +6,102 @@ use crate::data;
 use crate::des::axis::ticks::{
     DateTimeFormatter, DateTimeLocator, TimeDeltaFormatter, TimeDeltaLocator,
 };
-use crate::des::axis::ticks::{Formatter, Locator, Ticks};
+use crate::des::axis::ticks::{FixedLocator, Formatter, Locator, Subdivisions, Ticks};
 use crate::des::axis::{LogScale, Scale};
 use crate::drawing::{Categories, Error, axis};
 #[cfg(feature = "time")]
-use crate::time::{DateTime, DateTimeComps, TimeDelta};
+use crate::time::{DateTime, DateTimeComps, TimeDelta, Unit};
 
+/// Locate the major ticks.
+/// `count_hint` is the range of the number of ticks to aim for (see [`Ticks::with_count_range`]).
+/// With an axis break, the ticks are located on each side of the break independently.
 pub fn locate_num(
     locator: &Locator,
+    count_hint: Option<(u32, u32)>,
     nb: axis::NumBounds,
     scale: &Scale,
+    axis_break: Option<(f64, f64)>,
+) -> Result<Vec<f64>, Error> {
+    locate_around_break(nb, axis_break, |nb, share| {
+        locate_num_share(locator, count_hint, nb, scale, share)
+    })
+}
+
+/// Locate the minor ticks.
+/// `major_locator` and `major_count_hint` are used by [`Locator::Subdivide`] to locate the intervals to subdivide.
+/// With an axis break, the ticks are located on each side of the break independently.
+pub fn locate_minor(
+    locator: &Locator,
+    major_locator: &Locator,
+    major_count_hint: Option<(u32, u32)>,
+    nb: axis::NumBounds,
+    scale: &Scale,
+    axis_break: Option<(f64, f64)>,
+) -> Result<Vec<f64>, Error> {
+    locate_around_break(nb, axis_break, |nb, share| {
+        locate_minor_share(locator, major_locator, major_count_hint, nb, scale, share)
+    })
+}
+
+/// Call `locate` for each side of the break, with the share of the axis that the side spans.
+/// The ticks are only kept within their side, and none is kept at the seam.
+fn locate_around_break<F>(
+    nb: axis::NumBounds,
+    axis_break: Option<(f64, f64)>,
+    locate: F,
+) -> Result<Vec<f64>, Error>
+where
+    F: Fn(axis::NumBounds, f64) -> Result<Vec<f64>, Error>,
+{
+    let Some((low, high)) = axis_break else {
+        return locate(nb, 1.0);
+    };
+    let lower: axis::NumBounds = (nb.start(), low).into();
+    let upper: axis::NumBounds = (high, nb.end()).into();
+    let total = lower.span() + upper.span();
+
+    let mut locs = locate(lower, lower.span() / total)?;
+    locs.retain(|l| lower.contains(*l) && *l < low);
+    let mut upper_locs = locate(upper, upper.span() / total)?;
+    upper_locs.retain(|l| upper.contains(*l) && *l > high);
+    locs.extend(upper_locs);
+    Ok(locs)
+}
+
+/// Number of bins for a share of the axis
+fn share_bins(bins: u32, share: f64) -> u32 {
+    ((bins as f64 * share).round() as u32).max(1)
+}
+
+fn locate_num_share(
+    locator: &Locator,
+    count_hint: Option<(u32, u32)>,
+    nb: axis::NumBounds,
+    scale: &Scale,
+    share: f64,
 ) -> Result<Vec<f64>, Error> {
     match (locator, scale) {
-        (Locator::Auto, Scale::Auto | Scale::Linear { .. }) => Ok(MaxN::new_auto().ticks(nb)),
+        (Locator::Auto, Scale::Auto | Scale::Linear { .. }) => Ok(MaxN::new_auto(share)
+            .with_count_hint(count_hint, share)
+            .ticks(nb)),
         (Locator::Auto, Scale::Log(LogScale { base, .. })) => {
             Ok(LogLocator::new_major(*base).ticks(nb))
         }
         (Locator::MaxN(locator), Scale::Auto | Scale::Linear { .. }) => {
-            let ticker = MaxN::new(locator.bins, locator.steps.as_slice());
+            let ticker = MaxN::new(share_bins(locator.bins, share), locator.steps.as_slice())
+                .with_count_hint(count_hint, share);
             Ok(ticker.ticks(nb))
         }
         (Locator::PiMultiple(locator), Scale::Auto | Scale::Linear { .. }) => {
-            let ticker = MaxN::new_pi(locator.bins);
+            let ticker = MaxN::new_pi(share_bins(locator.bins, share));
             Ok(ticker.ticks(nb))
         }
         (Locator::Log(locator), Scale::Auto) => Ok(LogLocator::new_major(locator.base).ticks(nb)),
         (Locator::Log(locator), Scale::Log(LogScale { base, .. })) if locator.base == *base => {
             Ok(LogLocator::new_major(*base).ticks(nb))
         }
+        // the caller skips the positions out of the axis
+        (Locator::Fixed(locator), _) => Ok(locator.positions.clone()),
         #[cfg(feature = "time")]
         (Locator::TimeDelta(loc), Scale::Auto | Scale::Linear { .. }) => {
             locate_timedelta_num(loc, nb)
@@ -45,28 +113,42 @@ pub fn locate_num(
     }
 }
 
-pub fn locate_minor(
+fn locate_minor_share(
     locator: &Locator,
+    major_locator: &Locator,
+    major_count_hint: Option<(u32, u32)>,
     nb: axis::NumBounds,
     scale: &Scale,
+    share: f64,
 ) -> Result<Vec<f64>, Error> {
     match (locator, scale) {
-        (Locator::Auto, Scale::Auto | Scale::Linear { .. }) => Ok(MaxN::new_auto_minor().ticks(nb)),
+        (Locator::Subdivide(subdivisions), Scale::Auto | Scale::Linear { .. }) => {
+            // the major ticks extend to or beyond the axis ends, the caller clamps the result
+            let majors = locate_num_share(major_locator, major_count_hint, nb, scale, share)?;
+            Ok(subdivide(&majors, *subdivisions))
+        }
+        (Locator::Subdivide(_), Scale::Log(LogScale { base, .. })) => {
+            Ok(LogLocator::new_minor(*base).ticks(nb))
+        }
+        (Locator::Auto, Scale::Auto | Scale::Linear { .. }) => {
+            Ok(MaxN::new_auto_minor(share).ticks(nb))
+        }
         (Locator::Auto, Scale::Log(LogScale { base, .. })) => {
             Ok(LogLocator::new_minor(*base).ticks(nb))
         }
         (Locator::MaxN(locator), Scale::Auto | Scale::Linear { .. }) => {
-            let ticker = MaxN::new(locator.bins, locator.steps.as_slice());
+            let ticker = MaxN::new(share_bins(locator.bins, share), locator.steps.as_slice());
             Ok(ticker.ticks(nb))
         }
         (Locator::PiMultiple(locator), Scale::Auto | Scale::Linear { .. }) => {
-            let ticker = MaxN::new_pi(locator.bins);
+            let ticker = MaxN::new_pi(share_bins(locator.bins, share));
             Ok(ticker.ticks(nb))
         }
         (Locator::Log(locator), Scale::Auto) => Ok(LogLocator::new_minor(locator.base).ticks(nb)),
         (Locator::Log(locator), Scale::Log(LogScale { base, .. })) if locator.base == *base => {
             Ok(LogLocator::new_minor(*base).ticks(nb))
         }
+        (Locator::Fixed(locator), _) => Ok(locator.positions.clone()),
         _ => Err(Error::InconsistentDesign(format!(
             "Unsupported locator/scale combination: {:?}/{:?}",
             locator, scale
@@ -74,6 +156,26 @@ pub fn locate_minor(
     }
 }
 
+/// Place `n - 1` ticks between each pair of consecutive major ticks
+fn subdivide(majors: &[f64], subdivisions: Subdivisions) -> Vec<f64> {
+    let mut locs = Vec::new();
+    for pair in majors.windows(2) {
+        let step = pair[1] - pair[0];
+        let n = match subdivisions {
+            Subdivisions::Auto => auto_subdivisions(step),
+            Subdivisions::N(n) => n.max(1),
+        };
+        locs.extend((1..n).map(|i| pair[0] + step * i as f64 / n as f64));
+    }
+    locs
+}
+
+/// 4 subdivisions for steps multiple of 2, and 5 otherwise
+fn auto_subdivisions(step: f64) -> u32 {
+    let mantissa = step / 10f64.powf(step.log10().floor());
+    if is_close(mantissa, 2.0) { 4 } else { 5 }
+}
+
 #[cfg(feature = "time")]
 pub fn locate_datetime(locator: &Locator, tb: axis::TimeBounds) -> Result<Vec<DateTime>, Error> {
     match locator {
@@ -142,74 +244,36 @@ pub fn locate_datetime(locator: &Locator, tb: axis::TimeBounds) -> Result<Vec<Da
             Ok(res)
         }
         &Locator::DateTime(DateTimeLocator::Weeks(n)) => {
-            // TODO: scroll back to Monday
-            let start = tb.start();
+            // scroll back to the Monday of the start week
+            let start = tb.start().truncate_to(Unit::Day);
+            let monday = start - TimeDelta::from_days((start.to_date().weekday() - 1) as f64);
             let td = TimeDelta::from_seconds(7.0 * 24.0 * 3600.0) * n as f64;
-            Ok(locate_datetime_even(start, tb, td))
+            Ok(locate_datetime_even(monday, tb, td))
         }
         &Locator::DateTime(DateTimeLocator::Days(n)) => {
-            let start = tb.start().to_comps();
-            let start = DateTimeComps {
-                year: start.year,
-                month: start.month,
-                day: start.day,
-                ..DateTimeComps::epoch()
-            };
+            let start = tb.start().truncate_to(Unit::Day);
             let td = TimeDelta::from_seconds(24.0 * 3600.0) * n as f64;
-            Ok(locate_datetime_even(start.try_into().unwrap(), tb, td))
+            Ok(locate_datetime_even(start, tb, td))
         }
         &Locator::DateTime(DateTimeLocator::Hours(n)) => {
-            let start = tb.start().to_comps();
-            let start = DateTimeComps {
-                year: start.year,
-                month: start.month,
-                day: start.day,
-                hour: start.hour,
-                ..DateTimeComps::epoch()
-            };
+            let start = tb.start().truncate_to(Unit::Hour);
             let td = TimeDelta::from_seconds(3600.0) * n as f64;
-            Ok(locate_datetime_even(start.try_into().unwrap(), tb, td))
+            Ok(locate_datetime_even(start, tb, td))
         }
         &Locator::DateTime(DateTimeLocator::Minutes(n)) => {
-            let start = tb.start().to_comps();
-            let start = DateTimeComps {
-                year: start.year,
-                month: start.month,
-                day: start.day,
-                hour: start.hour,
-                minute: start.minute,
-                ..DateTimeComps::epoch()
-            };
+            let start = tb.start().truncate_to(Unit::Minute);
             let td = TimeDelta::from_seconds(60.0) * n as f64;
-            Ok(locate_datetime_even(start.try_into().unwrap(), tb, td))
+            Ok(locate_datetime_even(start, tb, td))
         }
         &Locator::DateTime(DateTimeLocator::Seconds(n)) => {
-            let start = tb.start().to_comps();
-            let start = DateTimeComps {
-                year: start.year,
-                month: start.month,
-                day: start.day,
-                hour: start.hour,
-                minute: start.minute,
-                second: start.second,
-                ..DateTimeComps::epoch()
-            };
+            let start = tb.start().truncate_to(Unit::Second);
             let td = TimeDelta::from_seconds(1.0) * n as f64;
-            Ok(locate_datetime_even(start.try_into().unwrap(), tb, td))
+            Ok(locate_datetime_even(start, tb, td))
         }
         &Locator::DateTime(DateTimeLocator::Micros(n)) => {
-            let start = tb.start().to_comps();
-            let start = DateTimeComps {
-                year: start.year,
-                month: start.month,
-                day: start.day,
-                hour: start.hour,
-                minute: start.minute,
-                second: start.second,
-                ..DateTimeComps::epoch()
-            };
+            let start = tb.start().truncate_to(Unit::Second);
             let td = TimeDelta::from_seconds(1E-6) * n as f64;
-            Ok(locate_datetime_even(start.try_into().unwrap(), tb, td))
+            Ok(locate_datetime_even(start, tb, td))
         }
         _ => Err(Error::InconsistentDesign(format!(
             "Inconsistent ticks locator for time axis: {locator:?}"
@@ -235,47 +299,64 @@ fn locate_datetime_even(start: DateTime, tb: axis::TimeBounds, td: TimeDelta) ->
     res
 }
 
+/// Human intervals, in seconds, for the ticks of a TimeDelta axis
 #[cfg(feature = "time")]
-fn time_steps(steps: &[u32], secs: f64, multiplier: f64) -> u32 {
-    for s in steps {
-        let bins = secs / ((*s as f64) * multiplier);
-        if bins < 10.0 {
-            return *s;
-        }
+const TIMEDELTA_STEPS: &[f64] = &[
+    1.0,
+    5.0,
+    10.0,
+    15.0,
+    30.0,
+    60.0,
+    5.0 * 60.0,
+    10.0 * 60.0,
+    15.0 * 60.0,
+    30.0 * 60.0,
+    3600.0,
+    2.0 * 3600.0,
+    3.0 * 3600.0,
+    6.0 * 3600.0,
+    12.0 * 3600.0,
+    86400.0,
+    2.0 * 86400.0,
+    7.0 * 86400.0,
+];
+
+/// Pick the smallest human interval giving at most [`AUTO_BINS`] bins over `span` seconds.
+/// Below one second, the steps are 1, 2 and 5 times a power of ten.
+/// Beyond one week, the steps are 1, 2 and 5 times a power of ten of days.
+#[cfg(feature = "time")]
+fn timedelta_auto_step(span: f64) -> f64 {
+    let min_step = span / AUTO_BINS as f64;
+    if min_step <= 1.0 {
+        let step = nice_step_125(min_step);
+        return if step.is_finite() && step > 0.0 {
+            step.min(1.0)
+        } else {
+            1.0
+        };
+    }
+    if let Some(step) = TIMEDELTA_STEPS.iter().copied().find(|s| *s >= min_step) {
+        return step;
     }
-    steps[steps.len() - 1]
+    nice_step_125(min_step / 86400.0) * 86400.0
+}
+
+/// Smallest of 1, 2 or 5 times a power of ten that is not below `min_step`
+#[cfg(feature = "time")]
+fn nice_step_125(min_step: f64) -> f64 {
+    let magnitude = 10f64.powf(min_step.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|s| *s >= min_step)
+        .unwrap_or(10.0 * magnitude)
 }
 
 #[cfg(feature = "time")]
 fn locate_timedelta_num(loc: &TimeDeltaLocator, nb: axis::NumBounds) -> Result<Vec<f64>, Error> {
     let step = match loc {
-        TimeDeltaLocator::Auto => {
-            let secs = nb.span();
-            let locator = if secs > 5.0 * 86400.0 {
-                let days = secs / (10.0 * 86400.0);
-                TimeDeltaLocator::Days((days as u32).max(1))
-            } else if secs > 5.0 * 3600.0 {
-                let hours = time_steps(&[1, 2, 3, 4, 6, 12, 24], secs, 3600.0);
-                TimeDeltaLocator::Hours(hours)
-            } else if secs > 5.0 * 60.0 {
-                let minutes = time_steps(&[1, 5, 10, 15, 30], secs, 60.0);
-                TimeDeltaLocator::Minutes(minutes)
-            } else if secs > 5.0 {
-                let seconds = time_steps(&[1, 5, 10, 15, 30], secs, 1.0);
-                TimeDeltaLocator::Seconds(seconds)
-            } else {
-                let micros = time_steps(
-                    &[
-                        1, 2, 5, 10, 20, 50, 100, 200, 500, 1000, 2000, 5000, 10000, 20000, 50000,
-                        100000, 200000, 500000,
-                    ],
-                    secs,
-                    1E-6,
-                );
-                TimeDeltaLocator::Seconds(micros)
-            };
-            return locate_timedelta_num(&locator, nb);
-        }
+        TimeDeltaLocator::Auto => timedelta_auto_step(nb.span()),
         TimeDeltaLocator::Days(n) if *n > 0 => *n as f64 * 86400.0,
         TimeDeltaLocator::Hours(n) if *n > 0 => *n as f64 * 3600.0,
         TimeDeltaLocator::Minutes(n) if *n > 0 => *n as f64 * 60.0,
@@ -287,21 +368,17 @@ fn locate_timedelta_num(loc: &TimeDeltaLocator, nb: axis::NumBounds) -> Result<V
             ));
         }
     };
-    let start = (nb.start() / step).floor() * step;
-    let end = nb.end();
-    Ok(locate_timedelta_even(start, end, step))
+    Ok(locate_timedelta_even(nb, step))
 }
 
+/// Locate the multiples of `step` from the one before the start to the end of the bounds.
+/// Ticks are computed from their index rather than accumulated,
+/// so that they fall exactly at zero and symmetrically around it for negative deltas.
 #[cfg(feature = "time")]
-fn locate_timedelta_even(start: f64, end: f64, step: f64) -> Vec<f64> {
-    // pushing from one tick before start to one tick after end
-    let mut res = Vec::with_capacity((1.0 + (end - start) / step) as usize);
-    let mut current = start;
-    while current <= end {
-        res.push(current);
-        current += step;
-    }
-    res
+fn locate_timedelta_even(nb: axis::NumBounds, step: f64) -> Vec<f64> {
+    let first = (nb.start() / step).floor() as i64;
+    let last = (nb.end() / step).floor() as i64;
+    (first..=last).map(|i| i as f64 * step).collect()
 }
 
 const AUTO_BINS: u32 = 10;
@@ -311,22 +388,47 @@ const AUTO_STEPS: &[f64] = &[1.0, 2.0, 2.5, 5.0];
 const PI: f64 = std::f64::consts::PI;
 const PI_STEPS: &[f64] = &[PI / 8.0, PI / 6.0, PI / 4.0, PI / 3.0, PI / 2.0, PI];
 
+/// Maximum number of steps tried to honor a count hint
+const MAX_COUNT_ADJUST: usize = 8;
+
 struct MaxN<'a> {
     bins: u32,
     steps: &'a [f64],
+    // range of the number of ticks within the bounds to aim for
+    count: Option<(u32, u32)>,
 }
 
 impl<'a> MaxN<'a> {
     fn new(bins: u32, steps: &'a [f64]) -> Self {
-        Self { bins, steps }
+        Self {
+            bins,
+            steps,
+            count: None,
+        }
     }
 
-    fn new_auto() -> Self {
-        Self::new(AUTO_BINS, AUTO_STEPS)
+    /// Aim for a number of ticks within `hint`, scaled to the share of the axis.
+    /// The number of bins starts in the middle of the range.
+    fn with_count_hint(self, hint: Option<(u32, u32)>, share: f64) -> Self {
+        let Some((min, max)) = hint else {
+            return self;
+        };
+        let min = ((min as f64 * share).round() as u32).max(1);
+        let max = ((max as f64 * share).round() as u32).max(min);
+        let target = (min + max) as f64 / 2.0;
+        Self {
+            bins: ((target - 1.0).round() as u32).max(1),
+            count: Some((min, max)),
+            ..self
+        }
+    }
+
+    fn new_auto(share: f64) -> Self {
+        Self::new(share_bins(AUTO_BINS, share), AUTO_STEPS)
     }
 
-    fn new_auto_minor() -> Self {
-        Self::new(AUTO_BINS_MINOR, AUTO_STEPS)
+    fn new_auto_minor(share: f64) -> Self {
+        Self::new(share_bins(AUTO_BINS_MINOR, share), AUTO_STEPS)
     }
 
     fn new_pi(bins: u32) -> Self {
@@ -340,17 +442,53 @@ impl<'a> MaxN<'a> {
         let scale = 10f64.powf(target_step.log10().div_euclid(1.0));
         assert!(scale > 0.0);
 
-        let step = {
-            let mut stepper = MaxNStepper::new(self.steps, scale);
-            while stepper.step() > target_step {
-                stepper.next_smaller();
-            }
-            while stepper.step() < target_step {
-                stepper.next_bigger();
+        let mut stepper = MaxNStepper::new(self.steps, scale);
+        while stepper.step() > target_step {
+            stepper.next_smaller();
+        }
+        while stepper.step() < target_step {
+            stepper.next_bigger();
+        }
+        let mut ticks = Self::ticks_with_step(nb, stepper.step());
+
+        // walk the nice steps towards the hinted count, without changing direction.
+        // if a step overshoots the range, the closest of the last two candidates is kept
+        if let Some((min, max)) = self.count {
+            let distance = |ticks: &[f64]| {
+                let n = ticks.iter().filter(|t| nb.contains(**t)).count() as u32;
+                if n < min {
+                    (min - n) as i64
+                } else if n > max {
+                    -((n - max) as i64)
+                } else {
+                    0
+                }
+            };
+            let dist = distance(&ticks);
+            for _ in 0..MAX_COUNT_ADJUST {
+                if dist == 0 {
+                    break;
+                }
+                if dist > 0 {
+                    stepper.next_smaller();
+                } else {
+                    stepper.next_bigger();
+                }
+                let next = Self::ticks_with_step(nb, stepper.step());
+                let next_dist = distance(&next);
+                if next_dist == 0 || next_dist.signum() != dist.signum() {
+                    if next_dist.abs() <= distance(&ticks).abs() {
+                        ticks = next;
+                    }
+                    break;
+                }
+                ticks = next;
             }
-            stepper.step()
-        };
+        }
+        ticks
+    }
 
+    fn ticks_with_step(nb: axis::NumBounds, step: f64) -> Vec<f64> {
         let vmin = (nb.start() / step).floor() * step;
 
         let edge = MaxNEdgeInteger { step };
@@ -484,17 +622,57 @@ impl LogLocator {
     }
 }
 
+/// Build the label formatter of numerical ticks located at `locs`.
+/// The explicit labels of a [`Locator::Fixed`] take precedence over the formatter, unless it hides the labels.
 pub fn num_label_formatter(
     ticks: &Ticks,
     ab: axis::NumBounds,
     scale: &Scale,
+    locs: &[f64],
+) -> Arc<dyn LabelFormatter> {
+    let formatter = value_label_formatter(ticks, ab, scale, locs);
+    match ticks.locator() {
+        Locator::Fixed(FixedLocator {
+            positions,
+            labels: Some(labels),
+        }) if shows_labels(ticks, scale) => Arc::new(FixedLabelFormat {
+            positions: positions.clone(),
+            labels: labels.clone(),
+            fallback: formatter,
+        }),
+        _ => formatter,
+    }
+}
+
+/// Whether the ticks formatter shows labels at all
+fn shows_labels(ticks: &Ticks, scale: &Scale) -> bool {
+    match ticks.formatter() {
+        None => false,
+        Some(Formatter::Auto) => !scale.is_shared(),
+        Some(_) => true,
+    }
+}
+
+fn value_label_formatter(
+    ticks: &Ticks,
+    ab: axis::NumBounds,
+    scale: &Scale,
+    locs: &[f64],
 ) -> Arc<dyn LabelFormatter> {
     match ticks.formatter() {
         None => Arc::new(NullFormat),
         Some(Formatter::Auto) if scale.is_shared() => Arc::new(NullFormat),
-        Some(Formatter::Auto | Formatter::SharedAuto) => {
-            auto_label_formatter(ticks.locator(), ab, scale)
+        #[cfg(feature = "time")]
+        Some(Formatter::Auto | Formatter::SharedAuto)
+            if matches!(ticks.locator(), Locator::TimeDelta(..)) =>
+        {
+            timedelta_label_formatter(ab, &TimeDeltaFormatter::Auto, locs)
         }
+        Some(Formatter::Auto | Formatter::SharedAuto) => ticks
+            .offset_notation()
+            .then(|| offset_label_formatter(ticks.locator(), ab, scale, locs))
+            .flatten()
+            .unwrap_or_else(|| auto_label_formatter(ticks.locator(), ab, scale, locs)),
         Some(Formatter::Prec(prec)) => Arc::new(PrecLabelFormat(*prec)),
         Some(Formatter::Percent(fmt)) => {
             let prec = fmt
@@ -503,7 +681,7 @@ pub fn num_label_formatter(
             Arc::new(PercentLabelFormat(prec))
         }
         #[cfg(feature = "time")]
-        Some(Formatter::TimeDelta(tdfmt)) => timedelta_label_formatter(ab, tdfmt),
+        Some(Formatter::TimeDelta(tdfmt)) => timedelta_label_formatter(ab, tdfmt, locs),
         #[cfg(feature = "time")]
         _ => todo!(),
     }
@@ -513,6 +691,7 @@ fn auto_label_formatter(
     locator: &Locator,
     ab: axis::NumBounds,
     scale: &Scale,
+    locs: &[f64],
 ) -> Arc<dyn LabelFormatter> {
     match (locator, scale) {
         (Locator::PiMultiple { .. }, _) => Arc::new(PiMultipleLabelFormat { prec: 2 }),
@@ -531,10 +710,84 @@ fn auto_label_formatter(
                 Arc::new(PrecLabelFormat(2))
             }
         }
+        // arbitrary positions: as many decimal places as needed
+        (Locator::Fixed(..), _) => {
+            let max = ab.start().abs().max(ab.end().abs());
+            if max >= 10000.0 || max < 0.01 {
+                Arc::new(SciLabelFormat)
+            } else {
+                Arc::new(PrecLabelFormat(min_precision(locs)))
+            }
+        }
         _ => todo!(),
     }
 }
 
+/// Minimum number of leading digits shared by the ticks to factor them out as an offset
+const OFFSET_MIN_DIGITS: i32 = 4;
+
+/// Formatter factoring out of the labels a common offset, or otherwise a common order of magnitude.
+/// Returns None if neither is warranted by the axis bounds.
+fn offset_label_formatter(
+    locator: &Locator,
+    ab: axis::NumBounds,
+    scale: &Scale,
+    locs: &[f64],
+) -> Option<Arc<dyn LabelFormatter>> {
+    if !matches!(scale, Scale::Auto | Scale::Linear(..))
+        || !matches!(locator, Locator::Auto | Locator::MaxN(..))
+    {
+        return None;
+    }
+    let span = ab.span();
+    if span <= 0.0 || !span.is_finite() {
+        return None;
+    }
+
+    // the offset keeps the digits of the bound closest to zero above the order of magnitude of the span
+    let span_exp = span.log10().ceil() as i32;
+    let offset = if ab.start() >= 0.0 {
+        round_to_exp(ab.start(), span_exp, f64::floor)
+    } else if ab.end() <= 0.0 {
+        round_to_exp(ab.end(), span_exp, f64::ceil)
+    } else {
+        0.0
+    };
+    if offset != 0.0 && offset.abs().log10().floor() as i32 - span_exp + 1 >= OFFSET_MIN_DIGITS {
+        return Some(Arc::new(OffsetLabelFormat::new(locs, offset, 0)));
+    }
+
+    // same thresholds as the automatic scientific notation
+    let max = ab.start().abs().max(ab.end().abs());
+    if max >= 10000.0 || (max > 0.0 && max < 0.01) {
+        let exp = max.log10().floor() as i32;
+        return Some(Arc::new(OffsetLabelFormat::new(locs, 0.0, exp)));
+    }
+    None
+}
+
+/// Round `val` to a multiple of `10^exp` using `round`.
+/// Negative exponents divide by an exact power of ten to avoid noise in the result.
+fn round_to_exp(val: f64, exp: i32, round: fn(f64) -> f64) -> f64 {
+    if exp >= 0 {
+        let p = 10f64.powi(exp);
+        round(val / p) * p
+    } else {
+        let p = 10f64.powi(-exp);
+        round(val * p) / p
+    }
+}
+
+/// Smallest number of decimal places representing all the values
+pub(super) fn min_precision(vals: &[f64]) -> usize {
+    (0..6)
+        .find(|prec| {
+            let p = 10f64.powi(*prec as i32);
+            vals.iter().all(|v| ((v * p).round() - v * p).abs() < 1e-3)
+        })
+        .unwrap_or(6)
+}
+
 fn percent_auto_precision(ab: axis::NumBounds) -> usize {
     let span = ab.span();
     if span >= 1.0 {
@@ -604,17 +857,34 @@ fn auto_datetime_label_formatter(tb: axis::TimeBounds) -> Result<Arc<dyn LabelFo
     }))
 }
 
+/// Build the label formatter of TimeDelta ticks located at `locs`, in seconds.
+/// The automatic format depends on the interval between the ticks: `%H:%M` for intervals
+/// of minutes or more, with seconds and fractions of second added for smaller intervals.
+/// The days are prepended if the axis bounds reach a day.
 #[cfg(feature = "time")]
 pub fn timedelta_label_formatter(
     nb: axis::NumBounds,
     tdfmt: &TimeDeltaFormatter,
+    locs: &[f64],
 ) -> Arc<dyn LabelFormatter> {
     match tdfmt {
         TimeDeltaFormatter::Auto => {
-            let fmt = if nb.span() >= 86400.0 {
-                "%D %H:%M:%S".to_string()
+            let step = match locs {
+                [a, b, ..] => b - a,
+                _ => nb.span(),
+            };
+            let time = if step >= 60.0 {
+                "%H:%M"
+            } else if step >= 1.0 {
+                "%H:%M:%S"
+            } else {
+                "%H:%M:%S%.f"
+            };
+            let max = nb.start().abs().max(nb.end().abs());
+            let fmt = if max >= 86400.0 {
+                format!("%D {time}")
             } else {
-                "%H:%M:%S".to_string()
+                time.to_string()
             };
             Arc::new(TimeDeltaLabelFormat { fmt })
         }
@@ -622,7 +892,7 @@ pub fn timedelta_label_formatter(
     }
 }
 
-pub trait LabelFormatter: std::fmt::Debug {
+pub trait LabelFormatter: std::fmt::Debug + Send + Sync {
     fn axis_annotation(&self) -> Option<&str> {
         None
     }
@@ -675,6 +945,67 @@ impl LabelFormatter for PiMultipleLabelFormat {
     }
 }
 
+/// Labels reduced by an offset and a power of ten, both shown in the axis annotation
+#[derive(Debug)]
+struct OffsetLabelFormat {
+    offset: f64,
+    exp: i32,
+    prec: usize,
+    annot: String,
+}
+
+impl OffsetLabelFormat {
+    fn new(locs: &[f64], offset: f64, exp: i32) -> Self {
+        let mut fmt = OffsetLabelFormat {
+            offset,
+            exp,
+            prec: 0,
+            annot: String::new(),
+        };
+        let reduced: Vec<f64> = locs.iter().map(|l| fmt.reduce(*l)).collect();
+        fmt.prec = min_precision(&reduced);
+
+        if exp != 0 {
+            fmt.annot = format!("\u{00d7}10{}", superscript(exp));
+        } else if offset.abs() >= 10000.0 || offset.abs() < 0.01 {
+            fmt.annot = format!("{offset:+e}");
+        } else {
+            fmt.annot = format!("{offset:+}");
+        }
+        fmt
+    }
+
+    fn reduce(&self, val: f64) -> f64 {
+        (val - self.offset) / 10f64.powi(self.exp)
+    }
+}
+
+impl LabelFormatter for OffsetLabelFormat {
+    fn axis_annotation(&self) -> Option<&str> {
+        Some(&self.annot)
+    }
+    fn format_label(&self, data: data::SampleRef) -> String {
+        let val = self.reduce(data.as_num().unwrap());
+        // rounding first and adding zero avoids "-0" labels
+        let p = 10f64.powi(self.prec as i32);
+        let val = (val * p).round() / p + 0.0;
+        format!("{val:.*}", self.prec)
+    }
+}
+
+fn superscript(num: i32) -> String {
+    num.to_string()
+        .chars()
+        .map(|c| match c {
+            '-' => '\u{207b}',
+            '1' => '\u{00b9}',
+            '2' => '\u{00b2}',
+            '3' => '\u{00b3}',
+            c => char::from_u32(0x2070 + c.to_digit(10).unwrap()).unwrap(),
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 struct PercentLabelFormat(usize);
 
@@ -719,6 +1050,30 @@ impl LabelFormatter for TimeDeltaLabelFormat {
     }
 }
 
+/// Explicit labels of a [`Locator::Fixed`], matched to the ticks by position.
+/// The ticks without a label are formatted by `fallback`.
+#[derive(Debug)]
+struct FixedLabelFormat {
+    positions: Vec<f64>,
+    labels: Vec<String>,
+    fallback: Arc<dyn LabelFormatter>,
+}
+
+impl LabelFormatter for FixedLabelFormat {
+    fn axis_annotation(&self) -> Option<&str> {
+        self.fallback.axis_annotation()
+    }
+    fn format_label(&self, data: data::SampleRef) -> String {
+        let val = data.as_num().unwrap();
+        self.positions
+            .iter()
+            .position(|p| *p == val)
+            .and_then(|idx| self.labels.get(idx))
+            .cloned()
+            .unwrap_or_else(|| self.fallback.format_label(data))
+    }
+}
+
 #[derive(Debug)]
 struct NullFormat;
 
@@ -731,6 +1086,7 @@ impl LabelFormatter for NullFormat {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::des::axis::ticks::MaxNLocator;
     use crate::drawing::axis;
     use crate::tests::Near;
 
@@ -776,9 +1132,151 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_ticks_loc_broken() {
+        let nb = axis::NumBounds::from((0.0, 1010.0));
+        let ticks =
+            locate_num(&Locator::Auto, None, nb, &Scale::Auto, Some((10.0, 1000.0))).unwrap();
+        // each side gets half of the bins, and no tick is in the break nor at the seam
+        let expected = vec![
+            0.0, 2.0, 4.0, 6.0, 8.0, 1002.0, 1004.0, 1006.0, 1008.0, 1010.0,
+        ];
+        assert_contains_near!(abs, ticks, expected);
+        assert!(ticks.iter().all(|t| *t < 10.0 || *t > 1000.0));
+    }
+
+    #[test]
+    fn test_ticks_loc_subdivide() {
+        let nb = axis::NumBounds::from((0.05, 1.0));
+        let subdivide = Locator::Subdivide(Subdivisions::N(10));
+        let ticks = locate_minor(&subdivide, &Locator::Auto, None, nb, &Scale::Auto, None).unwrap();
+        // major ticks every 0.1, the locations out of the axis are clamped by the caller
+        assert_contains_near!(abs, ticks, vec![0.07, 0.08, 0.09, 0.11, 0.12]);
+        assert_eq!(ticks.iter().filter(|t| nb.contains(**t)).count(), 86);
+
+        // major ticks every 0.2: 4 subdivisions
+        let subdivide = Locator::Subdivide(Subdivisions::Auto);
+        let nb = axis::NumBounds::from((0.0, 2.0));
+        let ticks = locate_minor(&subdivide, &Locator::Auto, None, nb, &Scale::Auto, None).unwrap();
+        assert_eq!(ticks.iter().filter(|t| nb.contains(**t)).count(), 30);
+        assert_contains_near!(abs, ticks, vec![0.05, 0.1, 0.15, 0.25]);
+
+        // major ticks every 0.5: 5 subdivisions
+        let major = MaxNLocator {
+            bins: 4,
+            steps: vec![5.0],
+        };
+        let ticks = locate_minor(&subdivide, &major.into(), None, nb, &Scale::Auto, None).unwrap();
+        assert_eq!(ticks.iter().filter(|t| nb.contains(**t)).count(), 16);
+        assert_contains_near!(abs, ticks, vec![0.1, 0.2, 0.3, 0.4, 0.6]);
+
+        // log scales use the log minor locator
+        let log = Scale::Log(LogScale::default());
+        let ticks = locate_minor(
+            &subdivide,
+            &Locator::Auto,
+            None,
+            (1.0, 100.0).into(),
+            &log,
+            None,
+        )
+        .unwrap();
+        assert_contains_near!(rel, ticks, vec![20.0, 30.0, 40.0]);
+    }
+
+    #[test]
+    fn test_ticks_loc_count_hint() {
+        let count = |locator: &Locator, hint: Option<(u32, u32)>, start: f64, end: f64| {
+            let nb = axis::NumBounds::from((start, end));
+            let ticks = locate_num(locator, hint, nb, &Scale::Auto, None).unwrap();
+            ticks.iter().filter(|t| nb.contains(**t)).count()
+        };
+
+        assert_eq!(count(&Locator::Auto, None, 0.0, 1.0), 11);
+        // 0.25 steps
+        assert_eq!(count(&Locator::Auto, Some((5, 5)), 0.0, 1.0), 5);
+        // 0.5 steps
+        assert_eq!(count(&Locator::Auto, Some((3, 4)), 0.0, 1.0), 3);
+        // the density is kept on a different range
+        assert_eq!(count(&Locator::Auto, Some((3, 4)), 0.0, 1000.0), 3);
+
+        // 0.5 steps gives too few ticks, 0.05 steps too many: the closest is kept
+        let locator: Locator = MaxNLocator {
+            bins: 9,
+            steps: vec![5.0],
+        }
+        .into();
+        assert_eq!(count(&locator, Some((4, 6)), 0.0, 1.0), 3);
+    }
+
+    #[test]
+    fn test_offset_notation() {
+        let ticks = Ticks::new().with_offset_notation(true);
+        let format = |start: f64, end: f64| {
+            let nb = axis::NumBounds::from((start, end));
+            let mut locs =
+                locate_num(ticks.locator(), ticks.count_hint(), nb, &Scale::Auto, None).unwrap();
+            locs.retain(|l| nb.contains(*l));
+            let fmt = num_label_formatter(&ticks, nb, &Scale::Auto, &locs);
+            let lbls: Vec<String> = locs.iter().map(|l| fmt.format_label((*l).into())).collect();
+            (fmt.axis_annotation().map(str::to_string), lbls)
+        };
+
+        let (annot, lbls) = format(1500.1, 1500.5);
+        assert_eq!(annot.as_deref(), Some("+1500"));
+        assert_eq!(lbls.first().unwrap(), "0.10");
+        assert_eq!(lbls.last().unwrap(), "0.50");
+
+        let (annot, lbls) = format(-1500.5, -1500.1);
+        assert_eq!(annot.as_deref(), Some("-1500"));
+        assert_eq!(lbls.first().unwrap(), "-0.50");
+        assert_eq!(lbls.last().unwrap(), "-0.10");
+
+        let (annot, lbls) = format(0.0, 800000.0);
+        assert_eq!(annot.as_deref(), Some("\u{00d7}10\u{2075}"));
+        assert_eq!(lbls, vec!["0", "1", "2", "3", "4", "5", "6", "7", "8"]);
+
+        let (annot, lbls) = format(0.0, 0.0025);
+        assert_eq!(annot.as_deref(), Some("\u{00d7}10\u{207b}\u{00b3}"));
+        assert_eq!(lbls.last().unwrap(), "2.50");
+
+        // not warranted
+        let (annot, lbls) = format(0.0, 10.0);
+        assert_eq!(annot, None);
+        assert_eq!(lbls.first().unwrap(), "0.0");
+    }
+
+    #[test]
+    fn test_fixed_positions() {
+        let format = |ticks: &Ticks| {
+            let nb = axis::NumBounds::from((0.0, 10.0));
+            let mut locs =
+                locate_num(ticks.locator(), ticks.count_hint(), nb, &Scale::Auto, None).unwrap();
+            locs.retain(|l| nb.contains(*l));
+            let fmt = num_label_formatter(ticks, nb, &Scale::Auto, &locs);
+            let lbls: Vec<String> = locs.iter().map(|l| fmt.format_label((*l).into())).collect();
+            (locs, lbls)
+        };
+
+        // out of range positions are skipped
+        let (locs, lbls) = format(&Ticks::new().with_positions(vec![-1.0, 1.5, 3.25, 12.0]));
+        assert_eq!(locs, vec![1.5, 3.25]);
+        assert_eq!(lbls, vec!["1.50", "3.25"]);
+
+        // positions beyond the labels are formatted
+        let ticks =
+            Ticks::new().with_labeled_positions(vec![2.0, 12.0, 5.0], vec!["a".into(), "b".into()]);
+        let (locs, lbls) = format(&ticks);
+        assert_eq!(locs, vec![2.0, 5.0]);
+        assert_eq!(lbls, vec!["a", "5"]);
+
+        let (_, lbls) = format(&ticks.with_formatter(None));
+        assert_eq!(lbls, vec!["", ""]);
+    }
+
     #[test]
     fn test_ticks_loc_auto() {
-        let locator = MaxN::new_auto();
+        let locator = MaxN::new_auto(1.0);
 
         let ticks = locator.ticks(axis::NumBounds::from((-1.0, 1.0)));
         let expected = vec![-1.0, -0.8, -0.6, -0.4, -0.2, 0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
@@ -821,4 +1319,76 @@ mod tests {
         let expected = vec![0.0, 0.5 * PI, 1.0 * PI, 1.5 * PI, 2.0 * PI];
         assert_contains_near!(abs, ticks, expected);
     }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_timedelta_ticks() {
+        let ticks = Ticks::new().with_locator(TimeDeltaLocator::Auto.into());
+        let format = |start: f64, end: f64| {
+            let nb = axis::NumBounds::from((start, end));
+            let mut locs =
+                locate_num(ticks.locator(), ticks.count_hint(), nb, &Scale::Auto, None).unwrap();
+            locs.retain(|l| nb.contains(*l));
+            let fmt = num_label_formatter(&ticks, nb, &Scale::Auto, &locs);
+            let lbls: Vec<String> = locs.iter().map(|l| fmt.format_label((*l).into())).collect();
+            (locs, lbls)
+        };
+
+        // 40 minutes: every 5 minutes
+        let (locs, lbls) = format(0.0, 2400.0);
+        assert_eq!(locs.len(), 9);
+        assert_eq!(lbls[1], "00:05");
+        assert_eq!(lbls.last().unwrap(), "00:40");
+
+        // 2 hours around zero: every 15 minutes, symmetrically
+        let (locs, lbls) = format(-3600.0, 3600.0);
+        assert_eq!(locs.len(), 9);
+        assert_eq!(locs[4], 0.0);
+        assert_eq!(lbls[0], "-01:00");
+        assert_eq!(lbls[3], "-00:15");
+        assert_eq!(lbls[4], "00:00");
+        assert_eq!(lbls[8], "01:00");
+
+        // 20 seconds: every 5 seconds
+        let (_, lbls) = format(0.0, 20.0);
+        assert_eq!(
+            lbls,
+            vec!["00:00:00", "00:00:05", "00:00:10", "00:00:15", "00:00:20"]
+        );
+
+        // 3 days: every 12 hours, with the days
+        let (locs, lbls) = format(0.0, 3.0 * 86400.0);
+        assert_eq!(locs.len(), 7);
+        assert_eq!(lbls[2], "1 day 00:00");
+        assert_eq!(lbls[5], "2 days 12:00");
+
+        // sub-second steps
+        let (locs, _) = format(0.0, 0.9);
+        assert_contains_near!(abs, locs, vec![0.0, 0.1, 0.2, 0.3]);
+
+        // long spans: 1, 2 or 5 times a power of ten of days
+        assert_eq!(timedelta_auto_step(300.0 * 86400.0), 50.0 * 86400.0);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_datetime_loc_weeks() {
+        // from Wednesday 2025-01-15 to Tuesday 2025-02-11
+        let tb = axis::TimeBounds::from((
+            DateTime::from_ymd(2025, 1, 15).unwrap(),
+            DateTime::from_ymd(2025, 2, 11).unwrap(),
+        ));
+        let locator = Locator::DateTime(DateTimeLocator::Weeks(1));
+        let ticks = locate_datetime(&locator, tb).unwrap();
+        assert!(
+            ticks
+                .iter()
+                .all(|t| t.iso_week().2 == 1 && t.to_time().hour == 0)
+        );
+        assert_eq!(ticks[0], DateTime::from_ymd(2025, 1, 13).unwrap());
+        assert_eq!(
+            ticks.last().unwrap(),
+            &DateTime::from_ymd(2025, 2, 17).unwrap()
+        );
+    }
 }
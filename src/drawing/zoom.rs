@@ -229,7 +229,7 @@ impl super::PreparedFigure {
             "Number of axes in view does not match number of axes in plot"
         );
 
-        super::with_ctx(data_source, fontdb, |ctx| {
+        super::with_ctx(data_source, fontdb, &self.layout, |ctx| {
             for (x_ax, new_x_cm) in axes.x_mut().iter_mut().zip(view.x_infos.iter()) {
                 ctx.axis_set_coord_map(x_ax, new_x_cm.clone())?;
             }
@@ -53,7 +53,7 @@ use plotive_dsl::{self, Span, ast};
 pub use plotive_dsl::{Diagnostic, Source};
 
 use crate::text::{self, ParseRichTextError, ParsedRichText};
-use crate::{des, style};
+use crate::{data, des, style};
 
 /// Errors that can occur during EPLT parsing
 #[derive(Debug, Clone)]
@@ -122,12 +122,102 @@ impl plotive_dsl::DiagTrait for Error {
             Error::Parse { help, .. } => help.clone(),
         }
     }
+
+    fn included(&self) -> Option<(Source, Box<dyn plotive_dsl::DiagTrait>)> {
+        match self {
+            Error::Dsl(err) => err.included(),
+            _ => None,
+        }
+    }
 }
 
-/// Parse EPLT DSL input into a list of design figures
+/// Parse EPLT DSL input into a list of design figures.
+/// Files included with `@include` are resolved relative to the current working directory
+/// of the process. Use [`parse_with_path`] to resolve them relative to the input file.
 pub fn parse<S: AsRef<str>>(input: S) -> Result<Vec<des::Figure>, Error> {
-    let props = plotive_dsl::parse(input.as_ref().chars())?;
+    parse_with_path(input, None)
+}
+
+/// Parse EPLT DSL input into a list of design figures.
+/// `path` is the path of the input file, if any.
+/// Files included with `@include` are resolved relative to its directory,
+/// or to the current working directory of the process if `path` is `None`.
+///
+/// Data declarations are not supported, see [`parse_document`].
+pub fn parse_with_path<S: AsRef<str>>(
+    input: S,
+    path: Option<&path::Path>,
+) -> Result<Vec<des::Figure>, Error> {
+    let props = plotive_dsl::parse_with_path(input.as_ref().chars(), path)?;
+    parse_figs(props)
+}
+
+/// Parse EPLT DSL input into a list of design figures, returning diagnostics on error.
+/// Files included with `@include` are resolved relative to the directory of `file_name`.
+pub fn parse_diag<'a>(
+    input: &'a str,
+    file_name: Option<&'a path::Path>,
+) -> plotive_dsl::DiagResult<Vec<des::Figure>> {
+    to_diag(parse_with_path(input, file_name), input, file_name)
+}
+
+/// A self-contained DSL document: figures and the data they refer to
+#[derive(Debug, Clone)]
+pub struct Document {
+    /// The figures of the document
+    pub figures: Vec<des::Figure>,
+    /// The data declared in the document, either inline or from CSV files.
+    /// The columns are named after the inline data names or the CSV headers,
+    /// and can be referred to by the figures series.
+    pub data: data::TableSource,
+}
+
+/// Parse EPLT DSL input into a document, including the data it declares.
+/// `path` is the path of the input file, if any.
+/// Included and CSV files are resolved relative to its directory,
+/// or to the current working directory of the process if `path` is `None`.
+///
+/// Data can be declared inline, or read from a CSV file (requires the `data-csv` feature):
+/// ```dsl
+/// data x = [0, 1, 2]
+/// data y = [0.0, 0.5, 2.0]
+/// data from "measurements.csv"
+/// ```
+pub fn parse_document<S: AsRef<str>>(
+    input: S,
+    path: Option<&path::Path>,
+) -> Result<Document, Error> {
+    let doc = plotive_dsl::parse_document(input.as_ref().chars(), path)?;
+    let data = parse_data(doc.data)?;
+    let figures = parse_figs(doc.props)?;
+    Ok(Document { figures, data })
+}
+
+/// Parse EPLT DSL input into a document, returning diagnostics on error.
+/// See [`parse_document`].
+pub fn parse_document_diag<'a>(
+    input: &'a str,
+    file_name: Option<&'a path::Path>,
+) -> plotive_dsl::DiagResult<Document> {
+    to_diag(parse_document(input, file_name), input, file_name)
+}
+
+fn to_diag<T>(
+    res: Result<T, Error>,
+    input: &str,
+    file_name: Option<&path::Path>,
+) -> plotive_dsl::DiagResult<T> {
+    res.map_err(|err| {
+        let src = Source {
+            name: file_name.map(|s| s.to_str().unwrap_or("(non-utf8 filename)").to_string()),
+            src: input.to_string(),
+        };
+        let diag = Diagnostic::new(Box::new(err), src);
+        plotive_dsl::DiagReport::new(diag)
+    })
+}
 
+fn parse_figs(props: Vec<ast::Prop>) -> Result<Vec<des::Figure>, Error> {
     let mut figs = vec![];
     for prop in props {
         if prop.name.name == "figure" {
@@ -140,27 +230,72 @@ pub fn parse<S: AsRef<str>>(input: S) -> Result<Vec<des::Figure>, Error> {
             });
         }
     }
-
     Ok(figs)
 }
 
-/// Parse EPLT DSL input into a list of design figures, returning diagnostics on error.
-pub fn parse_diag<'a>(
-    input: &'a str,
-    file_name: Option<&'a path::Path>,
-) -> plotive_dsl::DiagResult<Vec<des::Figure>> {
-    match parse(input) {
-        Ok(figs) => Ok(figs),
-        Err(err) => {
-            let src = Source {
-                name: file_name.map(|s| s.to_str().unwrap_or("(non-utf8 filename)").to_string()),
-                src: input.to_string(),
-            };
-            let diag = Diagnostic::new(Box::new(err), src);
-            let report = plotive_dsl::DiagReport::new(diag);
-            Err(report)
+fn parse_data(decls: Vec<ast::Data>) -> Result<data::TableSource, Error> {
+    let mut table = data::TableSource::new();
+    for decl in decls {
+        let span = decl.span();
+        match decl {
+            ast::Data::Column { name, values } => {
+                let col = match values.kind {
+                    ast::ArrayKind::Empty => data::VecColumn::F64(vec![]),
+                    ast::ArrayKind::Int(vals) => {
+                        data::VecColumn::I64(vals.into_iter().map(Some).collect())
+                    }
+                    ast::ArrayKind::Float(vals) => data::VecColumn::F64(vals),
+                    ast::ArrayKind::Str(vals) => {
+                        data::VecColumn::Str(vals.into_iter().map(Some).collect())
+                    }
+                };
+                check_new_column(&table, &name.name, name.span)?;
+                table.add_column(&name.name, col);
+            }
+            ast::Data::Csv { path, .. } => {
+                let csv = read_csv(&path, span)?;
+                for (name, col) in csv.into_columns() {
+                    check_new_column(&table, &name, span)?;
+                    table.add_column(&name, col);
+                }
+            }
         }
     }
+    Ok(table)
+}
+
+fn check_new_column(table: &data::TableSource, name: &str, span: Span) -> Result<(), Error> {
+    if table.heads().iter().any(|h| h == name) {
+        return Err(Error::Parse {
+            span,
+            reason: format!("data column {name} is declared more than once"),
+            help: None,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(feature = "data-csv")]
+fn read_csv(path: &path::Path, span: Span) -> Result<data::TableSource, Error> {
+    let content = std::fs::read_to_string(path).map_err(|err| Error::Parse {
+        span,
+        reason: format!("could not read {}: {err}", path.display()),
+        help: None,
+    })?;
+    data::csv::parse_str(&content, Default::default()).map_err(|err| Error::Parse {
+        span,
+        reason: format!("could not parse {}: {err}", path.display()),
+        help: None,
+    })
+}
+
+#[cfg(not(feature = "data-csv"))]
+fn read_csv(_path: &path::Path, span: Span) -> Result<data::TableSource, Error> {
+    Err(Error::Parse {
+        span,
+        reason: "CSV data is not supported".to_string(),
+        help: Some("enable the `data-csv` feature".to_string()),
+    })
 }
 
 fn expect_int_scalar(scalar: ast::Scalar) -> Result<i64, Error> {
@@ -532,6 +667,10 @@ fn expect_prop(val: &mut ast::Struct, name: &str) -> Result<ast::Prop, Error> {
 
 fn expect_data_prop(val: &mut ast::Struct, prop_name: &str) -> Result<des::DataCol, Error> {
     let prop = expect_prop(val, prop_name)?;
+    expect_data_val(prop, prop_name)
+}
+
+fn expect_data_val(prop: ast::Prop, prop_name: &str) -> Result<des::DataCol, Error> {
     match prop.value {
         Some(ast::Value::Scalar(ast::Scalar {
             kind: ast::ScalarKind::Str(val),
@@ -558,10 +697,16 @@ fn expect_data_prop(val: &mut ast::Struct, prop_name: &str) -> Result<des::DataC
 }
 
 fn parse_line(mut val: ast::Struct) -> Result<des::series::Line, Error> {
-    let x_data = expect_data_prop(&mut val, "x-data")?;
+    let x_data = val
+        .take_prop("x-data")
+        .map(|prop| expect_data_val(prop, "x-data"))
+        .transpose()?;
     let y_data = expect_data_prop(&mut val, "y-data")?;
 
-    let mut line = des::series::Line::new(x_data, y_data);
+    let mut line = match x_data {
+        Some(x_data) => des::series::Line::new(x_data, y_data),
+        None => des::series::Line::from_y(y_data),
+    };
 
     if let Some(prop) = val.take_prop("name") {
         line = line.with_name(expect_string_val(prop)?.1);
@@ -577,10 +722,16 @@ fn parse_line(mut val: ast::Struct) -> Result<des::series::Line, Error> {
 }
 
 fn parse_scatter(mut val: ast::Struct) -> Result<des::series::Scatter, Error> {
-    let x_data = expect_data_prop(&mut val, "x-data")?;
+    let x_data = val
+        .take_prop("x-data")
+        .map(|prop| expect_data_val(prop, "x-data"))
+        .transpose()?;
     let y_data = expect_data_prop(&mut val, "y-data")?;
 
-    let mut series = des::series::Scatter::new(x_data, y_data);
+    let mut series = match x_data {
+        Some(x_data) => des::series::Scatter::new(x_data, y_data),
+        None => des::series::Scatter::from_y(y_data),
+    };
 
     if let Some(prop) = val.take_prop("name") {
         series = series.with_name(expect_string_val(prop)?.1);
@@ -936,3 +1087,86 @@ fn parse_ticks_struct(val: ast::Struct) -> Result<des::axis::Ticks, Error> {
     }
     Ok(ticks)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Source;
+
+    fn parse_error(input: &str, path: Option<&path::Path>) -> (Span, String, Option<String>) {
+        match parse_document(input, path) {
+            Err(Error::Parse { span, reason, help }) => (span, reason, help),
+            res => panic!("expected a parse error, got {res:?}"),
+        }
+    }
+
+    #[test]
+    fn test_inline_data() {
+        let doc = parse_document(
+            "data x = [0, 1, 2]\ndata y = [0.0, 0.5, 2.0]\ndata names = [\"a\", \"b\", \"c\"]\n",
+            None,
+        )
+        .unwrap();
+        assert!(doc.figures.is_empty());
+        assert_eq!(doc.data.heads(), ["x", "y", "names"]);
+
+        let x = doc.data.column("x").unwrap();
+        assert_eq!(x.len(), 3);
+        assert!(x.i64().is_some());
+        assert!(doc.data.column("y").unwrap().f64().is_some());
+        assert!(doc.data.column("names").unwrap().str().is_some());
+    }
+
+    #[test]
+    fn test_duplicate_column() {
+        let input = "data x = [0, 1]\ndata x = [2.0, 3.0]\n";
+        let (span, reason, _) = parse_error(input, None);
+        // the error is reported at the second declaration of the name
+        assert_eq!(&input[span.0..span.1], "x");
+        assert_eq!(span.0, 21);
+        assert_eq!(reason, "data column x is declared more than once");
+    }
+
+    #[cfg(feature = "data-csv")]
+    #[test]
+    fn test_inline_and_csv_data() {
+        /// Temporary directory, removed when dropped
+        struct TempDir(path::PathBuf);
+
+        impl Drop for TempDir {
+            fn drop(&mut self) {
+                let _ = std::fs::remove_dir_all(&self.0);
+            }
+        }
+
+        let dir =
+            TempDir(std::env::temp_dir().join(format!("plotive-dsl-data-{}", std::process::id())));
+        std::fs::create_dir_all(&dir.0).unwrap();
+        std::fs::write(dir.0.join("data.csv"), "a,b\n1.0,2.0\n3.0,4.0\n").unwrap();
+        let main = dir.0.join("main.plotive");
+
+        // CSV files are resolved relative to the document, and the columns follow the declarations
+        let doc = parse_document("data x = [0, 1]\ndata from \"data.csv\"\n", Some(&main)).unwrap();
+        assert_eq!(doc.data.heads(), ["x", "a", "b"]);
+        assert_eq!(doc.data.column("b").unwrap().len(), 2);
+
+        // a CSV column clashing with an inline column is reported at the CSV declaration
+        let input = "data a = [0, 1]\ndata from \"data.csv\"\n";
+        let (span, reason, _) = parse_error(input, Some(&main));
+        assert_eq!(&input[span.0..span.1], "data from \"data.csv\"");
+        assert_eq!(reason, "data column a is declared more than once");
+
+        let (_, reason, _) = parse_error("data from \"missing.csv\"\n", Some(&main));
+        assert!(reason.starts_with("could not read"));
+    }
+
+    #[cfg(not(feature = "data-csv"))]
+    #[test]
+    fn test_csv_unsupported() {
+        let input = "data x = [0, 1]\ndata from \"data.csv\"\n";
+        let (span, reason, help) = parse_error(input, None);
+        assert_eq!(&input[span.0..span.1], "data from \"data.csv\"");
+        assert_eq!(reason, "CSV data is not supported");
+        assert_eq!(help.as_deref(), Some("enable the `data-csv` feature"));
+    }
+}
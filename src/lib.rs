@@ -11,6 +11,7 @@
  *  - XY line plots
  *  - Scatter plots
  *  - Histograms
+ *  - 2D histograms
  *  - Bar plots
  *
  *
@@ -117,8 +118,9 @@
  *  - `data-polars`: enables [Polars](https://pola.rs) data source support (See [`data::polars`])
  *    pulls in the `polars` dependency, which is quite a beast to compile.
  *  - `dsl`: enables the support for `.plotive` DSL. (See [`dsl`] and [`plotive-dsl` crate](https://crates.io/crates/plotive-dsl))
- *  - `noto-mono`, `noto-sans`, `noto-sans-italic`, `noto-serif`, `noto-serif-italic`: bundles the corresponding fonts from Google in the final executable, and enables `plotive::bundled_font_db()`.<br />
+ *  - `noto-mono`, `noto-sans`, `noto-sans-italic`, `noto-serif`, `noto-serif-italic`: bundles the corresponding fonts from Google in the final executable, and enables `plotive::bundled_font_db()` and `plotive::FontDbBuilder`.<br />
  *   `noto-sans` is enabled by default
+ *  - `serde`: implements `Serialize` and `Deserialize` for the design types (See [`des`])
  *  - `time`: enables support for time series, CSV date-time parsing etc. (See [`time`])
  *  - `utils`: enables various utilities such as `linspace`, `logspace` etc. (See [`utils`])
  *
@@ -140,7 +142,7 @@
  *  - preparation: [`drawing::Prepare::prepare()`] returns a [`drawing::PreparedFigure`], which caches all the layout information,
  *    the text preprocessed as paths, the series data converted to paths, etc.
  *  - drawing: [`drawing::PreparedFigure::draw()`] draws the prepared figure onto the surface, using the cached information. Themes
- *    colors are resolved at this stage.
+ *    colors are resolved at this stage, so a single prepared figure can be drawn with several styles.
  *
  * The [`drawing::PreparedFigure`] has API to update the series with new data, so that dynamic plots can be implemented easily and efficiently.
  * It also supports zooming and panning operations.
@@ -179,6 +181,7 @@ pub mod geom {
 pub mod text {
     pub use plotive_text::*;
 }
+pub use text::fontdb;
 #[cfg(any(
     feature = "noto-sans",
     feature = "noto-sans-italic",
@@ -186,10 +189,7 @@ pub mod text {
     feature = "noto-serif-italic",
     feature = "noto-mono"
 ))]
-/// Loads fonts that are bundled with plotive
-/// and returns the database.
-pub use text::bundled_font_db;
-pub use text::fontdb;
+pub use text::{BundledFont, FontDbBuilder, bundled_font_db};
 
 #[cfg(feature = "utils")]
 pub mod utils {
@@ -228,26 +228,6 @@ pub mod utils {
     }
 }
 
-/// Module containing missing configuration values
-/// Basically we put here all magic values that would require proper parameters
-mod missing_params {
-    use crate::geom;
-
-    pub const FIG_TITLE_MARGIN: f32 = 12.0;
-
-    pub const PLOT_PADDING: geom::Padding = geom::Padding::Even(0.0);
-
-    pub const AXIS_MARGIN: f32 = 10.0;
-    pub const AXIS_TITLE_MARGIN: f32 = 8.0;
-    pub const AXIS_ANNOT_MARGIN: f32 = 4.0;
-    pub const AXIS_SPINE_WIDTH: f32 = 1.0;
-
-    pub const TICK_SIZE: f32 = 4.0;
-    pub const TICK_LABEL_MARGIN: f32 = 4.0;
-    pub const MINOR_TICK_LINE_WIDTH: f32 = 0.5;
-    pub const MINOR_TICK_SIZE: f32 = 2.0;
-}
-
 #[cfg(test)]
 pub(crate) mod tests {
     pub trait Near {
@@ -3,20 +3,92 @@
 //! All rendering surfaces must implement the `Surface` trait.
 //! See the `plotive-pxl` and `plotive-svg` crates for examples.
 
+use std::fmt;
+
 use crate::{ColorU8, geom};
 
+/// Errors that can occur when rendering on a surface
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The operation is not supported by the surface
+    Unsupported(&'static str),
+    /// The image is invalid, e.g. its size doesn't match its data
+    InvalidImage(String),
+    /// A clip was popped without matching push, or the surface was saved with clips still pushed
+    UnbalancedClipStack,
+    /// The backend of the surface failed to perform the operation,
+    /// e.g. an allocation failed or the output couldn't be written
+    Backend(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Unsupported(op) => write!(f, "Unsupported surface operation: {}", op),
+            Error::InvalidImage(reason) => write!(f, "Invalid image: {}", reason),
+            Error::UnbalancedClipStack => write!(f, "Unbalanced clip stack"),
+            Error::Backend(reason) => write!(f, "Surface backend error: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
 /// Surface trait: defines the rendering surface API
+///
+/// All operations return an [`Error`] if the surface fails to perform them.
+/// Drawing of a figure stops at the first error.
 pub trait Surface {
     /// Prepare the surface for drawing, with the given size in plot units
-    fn prepare(&mut self, size: geom::Size);
+    fn prepare(&mut self, size: geom::Size) -> Result<(), Error>;
+
+    /// Prepare the surface for drawing, with the given size in plot units,
+    /// and an optional dirty region in plot units.
+    ///
+    /// When a region is given, subsequent operations, including [`fill`](Surface::fill),
+    /// only affect that region and the rest of the surface is left untouched.
+    /// Surfaces that don't support partial redraw (see [`supports_partial`](Surface::supports_partial))
+    /// ignore the region.
+    ///
+    /// Default implementation ignores the region and calls [`prepare`](Surface::prepare)
+    fn prepare_region(
+        &mut self,
+        size: geom::Size,
+        region: Option<&geom::Rect>,
+    ) -> Result<(), Error> {
+        let _ = region;
+        self.prepare(size)
+    }
+
+    /// Whether the surface supports partial redraw with [`prepare_region`](Surface::prepare_region)
+    fn supports_partial(&self) -> bool {
+        false
+    }
 
     /// Fill the entire surface with the given fill pattern
-    fn fill(&mut self, fill: Paint);
+    fn fill(&mut self, fill: Paint) -> Result<(), Error>;
+
+    /// Fill a region of the surface, in plot units, with the given fill pattern.
+    /// The entire surface is filled if no region is given.
+    ///
+    /// Default implementation calls [`fill`](Surface::fill) if no region is given,
+    /// and [`draw_rect`](Surface::draw_rect) with the region otherwise.
+    fn fill_region(&mut self, fill: Paint, region: Option<&geom::Rect>) -> Result<(), Error> {
+        match region {
+            None => self.fill(fill),
+            Some(region) => self.draw_rect(&Rect {
+                rect: *region,
+                fill: Some(fill),
+                stroke: None,
+                transform: None,
+            }),
+        }
+    }
 
     /// Draw a rectangle
     ///
     /// Default implementation converts the rectangle to a path and call [`draw_path`](Surface::draw_path)
-    fn draw_rect(&mut self, rect: &Rect) {
+    fn draw_rect(&mut self, rect: &Rect) -> Result<(), Error> {
         let path = rect.rect.to_path();
         let rpath = self::Path {
             path: &path,
@@ -24,34 +96,86 @@ pub trait Surface {
             stroke: rect.stroke,
             transform: rect.transform,
         };
-        self.draw_path(&rpath);
+        self.draw_path(&rpath)
     }
 
     /// Draw a path
-    fn draw_path(&mut self, path: &Path);
+    fn draw_path(&mut self, path: &Path) -> Result<(), Error>;
 
-    /// Push a clipping rect
-    /// Subsequent draw operations will be clipped to this rect,
+    /// Draw a drop shadow beneath a shape.
+    /// This should be called before drawing the shape itself.
+    ///
+    /// Default implementation fills the offset path without blur
+    fn draw_shadow(&mut self, shadow: &Shadow) -> Result<(), Error> {
+        let (dx, dy) = shadow.offset;
+        let transform = shadow
+            .transform
+            .copied()
+            .unwrap_or_default()
+            .post_translate(dx, dy);
+        self.draw_path(&Path {
+            path: shadow.path,
+            fill: Some(shadow.color.into()),
+            stroke: None,
+            transform: Some(&transform),
+        })
+    }
+
+    /// Draw a raster image
+    ///
+    /// Default implementation returns [`Error::Unsupported`]
+    fn draw_image(&mut self, image: &Image) -> Result<(), Error> {
+        let _ = image;
+        Err(Error::Unsupported("draw_image"))
+    }
+
+    /// Push a clipping path
+    /// Subsequent draw operations will be clipped to this path,
     /// until a matching [`pop_clip`](Surface::pop_clip) is called
-    fn push_clip(&mut self, clip: &Clip);
+    fn push_clip(&mut self, clip: &Clip) -> Result<(), Error>;
 
-    /// Pop a clipping rect that was pushed previously with [`push_clip`](Surface::push_clip)
-    fn pop_clip(&mut self);
+    /// Pop a clipping path that was pushed previously with [`push_clip`](Surface::push_clip).
+    /// Returns [`Error::UnbalancedClipStack`] if no clip was pushed.
+    fn pop_clip(&mut self) -> Result<(), Error>;
 }
 
 /// Paint pattern, used for fill operations
 #[derive(Debug, Clone, Copy)]
 pub enum Paint {
     /// Solid color fill
-    Solid(ColorU8),
+    Solid {
+        /// Fill color
+        color: ColorU8,
+        /// Fill opacity (0.0 to 1.0), multiplied with the alpha of the color
+        opacity: Option<f32>,
+    },
+}
+
+impl Paint {
+    /// The effective opacity of the paint (0.0 to 1.0),
+    /// combining the alpha of the color and the standalone opacity.
+    /// Surfaces should use this rather than the color alpha to render the paint.
+    pub fn opacity(&self) -> f32 {
+        match self {
+            Paint::Solid { color, opacity } => effective_opacity(*color, *opacity),
+        }
+    }
 }
 
 impl From<ColorU8> for Paint {
     fn from(value: ColorU8) -> Self {
-        Paint::Solid(value)
+        Paint::Solid {
+            color: value,
+            opacity: None,
+        }
     }
 }
 
+fn effective_opacity(color: ColorU8, opacity: Option<f32>) -> f32 {
+    let alpha = color.alpha() as f32 / 255.0;
+    alpha * opacity.unwrap_or(1.0).clamp(0.0, 1.0)
+}
+
 /// Line pattern defines how the line is drawn
 #[derive(Debug, Clone, Copy, Default)]
 pub enum LinePattern<'a> {
@@ -71,6 +195,17 @@ pub struct Stroke<'a> {
     pub width: f32,
     /// Line pattern
     pub pattern: LinePattern<'a>,
+    /// Line opacity (0.0 to 1.0), multiplied with the alpha of the color
+    pub opacity: Option<f32>,
+}
+
+impl Stroke<'_> {
+    /// The effective opacity of the stroke (0.0 to 1.0),
+    /// combining the alpha of the color and the standalone opacity.
+    /// Surfaces should use this rather than the color alpha to render the stroke.
+    pub fn opacity(&self) -> f32 {
+        effective_opacity(self.color, self.opacity)
+    }
 }
 
 /// Rectangle to draw
@@ -99,11 +234,213 @@ pub struct Path<'a> {
     pub transform: Option<&'a geom::Transform>,
 }
 
-/// Clipping rectangle
+/// Raster image to draw
+#[derive(Debug, Clone)]
+pub struct Image<'a> {
+    /// RGBA pixels, 4 bytes per pixel in row-major order, with non-premultiplied alpha
+    pub rgba: &'a [u8],
+    /// Width of the image in pixels
+    pub width: u32,
+    /// Height of the image in pixels
+    pub height: u32,
+    /// Destination rectangle in figure units. The image is stretched to fill it.
+    pub rect: geom::Rect,
+    /// Opacity of the image (0.0 to 1.0)
+    pub opacity: f32,
+    /// Optional transform to apply to the destination rectangle
+    pub transform: Option<&'a geom::Transform>,
+}
+
+impl Image<'_> {
+    /// Check that the image is not empty and that its data matches its size
+    pub fn check(&self) -> Result<(), Error> {
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::InvalidImage(format!(
+                "empty image of {}x{} pixels",
+                self.width, self.height
+            )));
+        }
+        let expected = self.width as usize * self.height as usize * 4;
+        if self.rgba.len() != expected {
+            return Err(Error::InvalidImage(format!(
+                "image of {}x{} pixels should have {} bytes of RGBA data, got {}",
+                self.width,
+                self.height,
+                expected,
+                self.rgba.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Drop shadow to draw
+#[derive(Debug, Clone)]
+pub struct Shadow<'a> {
+    /// Path of the shape casting the shadow
+    pub path: &'a geom::Path,
+    /// Shadow color
+    pub color: ColorU8,
+    /// Offset of the shadow in figure units, applied after the transform
+    pub offset: (f32, f32),
+    /// Blur radius in figure units. Zero for a sharp shadow.
+    pub blur: f32,
+    /// Optional transform to apply to the path
+    pub transform: Option<&'a geom::Transform>,
+}
+
+/// Clipping path
 #[derive(Debug, Clone)]
 pub struct Clip<'a> {
-    /// Clipping rectangle
-    pub rect: &'a geom::Rect,
-    /// Optional transform to apply to the clipping rectangle
+    /// Clipping path, filled with the non-zero winding rule
+    pub path: &'a geom::Path,
+    /// Optional transform to apply to the clipping path
     pub transform: Option<&'a geom::Transform>,
 }
+
+/// Physical size of a rendered figure, e.g. for printing
+///
+/// The figure is scaled uniformly to fit the physical size, and centered
+/// if its aspect ratio is different. Give the figure the aspect ratio of the
+/// physical size (see `des::Figure::with_size`) to fill it entirely.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicalSize {
+    /// Width in millimeters
+    pub width_mm: f32,
+    /// Height in millimeters
+    pub height_mm: f32,
+    /// Resolution in dots per inch, used to compute the pixel size of rasterized outputs
+    pub dpi: f32,
+}
+
+impl PhysicalSize {
+    /// Millimeters per inch
+    pub const MM_PER_INCH: f32 = 25.4;
+
+    /// Physical size in millimeters
+    pub fn new(width_mm: f32, height_mm: f32, dpi: f32) -> Self {
+        PhysicalSize {
+            width_mm,
+            height_mm,
+            dpi,
+        }
+    }
+
+    /// Physical size in inches
+    pub fn from_inches(width_in: f32, height_in: f32, dpi: f32) -> Self {
+        Self::new(
+            width_in * Self::MM_PER_INCH,
+            height_in * Self::MM_PER_INCH,
+            dpi,
+        )
+    }
+
+    /// A4 paper size (210 x 297 mm), in portrait orientation
+    pub fn a4(dpi: f32) -> Self {
+        Self::new(210.0, 297.0, dpi)
+    }
+
+    /// US letter paper size (8.5 x 11 in), in portrait orientation
+    pub fn letter(dpi: f32) -> Self {
+        Self::from_inches(8.5, 11.0, dpi)
+    }
+
+    /// The same size, in landscape orientation
+    pub fn landscape(self) -> Self {
+        if self.width_mm >= self.height_mm {
+            self
+        } else {
+            Self::new(self.height_mm, self.width_mm, self.dpi)
+        }
+    }
+
+    /// Width in pixels at the given resolution
+    pub fn pixel_width(&self) -> u32 {
+        (self.width_mm / Self::MM_PER_INCH * self.dpi).round() as u32
+    }
+
+    /// Height in pixels at the given resolution
+    pub fn pixel_height(&self) -> u32 {
+        (self.height_mm / Self::MM_PER_INCH * self.dpi).round() as u32
+    }
+
+    /// Number of pixels per plot unit to fit a figure of the given size
+    pub fn fit_scale(&self, size: geom::Size) -> f32 {
+        let sx = self.pixel_width() as f32 / size.width();
+        let sy = self.pixel_height() as f32 / size.height();
+        sx.min(sy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct PathOnly;
+
+    impl Surface for PathOnly {
+        fn prepare(&mut self, _size: geom::Size) -> Result<(), Error> {
+            Ok(())
+        }
+        fn fill(&mut self, _fill: Paint) -> Result<(), Error> {
+            Ok(())
+        }
+        fn draw_path(&mut self, _path: &Path) -> Result<(), Error> {
+            Ok(())
+        }
+        fn push_clip(&mut self, _clip: &Clip) -> Result<(), Error> {
+            Ok(())
+        }
+        fn pop_clip(&mut self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    fn image(rgba: &[u8], width: u32, height: u32) -> Image<'_> {
+        Image {
+            rgba,
+            width,
+            height,
+            rect: geom::Rect::from_xywh(0.0, 0.0, 10.0, 10.0),
+            opacity: 1.0,
+            transform: None,
+        }
+    }
+
+    #[test]
+    fn test_draw_image_unsupported() {
+        let rgba = [0u8; 16];
+        let res = PathOnly.draw_image(&image(&rgba, 2, 2));
+        assert_eq!(res, Err(Error::Unsupported("draw_image")));
+    }
+
+    #[test]
+    fn test_image_check() {
+        let rgba = [0u8; 16];
+        assert!(image(&rgba, 2, 2).check().is_ok());
+        assert!(matches!(
+            image(&rgba, 2, 3).check(),
+            Err(Error::InvalidImage(_))
+        ));
+        assert!(matches!(
+            image(&[], 0, 0).check(),
+            Err(Error::InvalidImage(_))
+        ));
+    }
+
+    #[test]
+    fn test_physical_size() {
+        let a4 = PhysicalSize::a4(300.0);
+        assert_eq!((a4.pixel_width(), a4.pixel_height()), (2480, 3508));
+
+        let a4 = a4.landscape();
+        assert_eq!((a4.pixel_width(), a4.pixel_height()), (3508, 2480));
+        assert_eq!(a4.landscape(), a4);
+
+        let letter = PhysicalSize::letter(100.0);
+        assert_eq!((letter.pixel_width(), letter.pixel_height()), (850, 1100));
+
+        let scale = letter.fit_scale(geom::Size::new(425.0, 425.0));
+        assert_eq!(scale, 2.0);
+    }
+}
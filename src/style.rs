@@ -4,9 +4,9 @@ pub(crate) mod defaults;
 pub mod series;
 pub mod theme;
 
-use crate::style::series::Palette;
+use crate::style::series::{Palette, PaletteCycle};
 use crate::style::theme::Theme;
-use crate::{Color, ColorU8, ResolveColor, render};
+use crate::{Color, ColorU8, ResolveColor, geom, render};
 
 /// Overall style definition for figures
 ///
@@ -14,11 +14,14 @@ use crate::{Color, ColorU8, ResolveColor, render};
 /// - The theme, which defines colors for the figure background, foreground, grid lines, and legend.
 /// - The palette, which defines colors for data series.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Style {
     /// Theme used for the figure
     theme: Theme,
     /// Palette used for series colors
     palette: Palette,
+    /// How the palette colors are reused
+    palette_cycle: PaletteCycle,
 }
 
 impl Default for Style {
@@ -30,7 +33,20 @@ impl Default for Style {
 impl Style {
     /// Create a new style with the given theme and palette
     pub const fn new(theme: Theme, palette: Palette) -> Self {
-        Style { theme, palette }
+        Style {
+            theme,
+            palette,
+            palette_cycle: PaletteCycle::Repeat,
+        }
+    }
+
+    /// Set how the palette colors are reused when there are more series than colors,
+    /// and return self for chaining. By default, colors are repeated as is.
+    pub fn with_palette_cycle(self, palette_cycle: PaletteCycle) -> Self {
+        Style {
+            palette_cycle,
+            ..self
+        }
     }
 
     /// Create a black and white monochrome style
@@ -39,6 +55,7 @@ impl Style {
         Style {
             theme: Theme::Light,
             palette: Palette::Black,
+            palette_cycle: PaletteCycle::Repeat,
         }
     }
 
@@ -47,6 +64,7 @@ impl Style {
         Style {
             theme: Theme::Light,
             palette: Palette::Standard,
+            palette_cycle: PaletteCycle::Repeat,
         }
     }
 
@@ -55,14 +73,17 @@ impl Style {
         Style {
             theme: Theme::Dark,
             palette: Palette::Pastel,
+            palette_cycle: PaletteCycle::Repeat,
         }
     }
 
     /// Create a light theme with Okabe & Ito colorblind-safe palette
+    /// See [`Palette::OkabeIto`] to check the result with a color vision deficiency simulation.
     pub const fn okabe_ito() -> Self {
         Style {
             theme: Theme::Light,
             palette: Palette::OkabeIto,
+            palette_cycle: PaletteCycle::Repeat,
         }
     }
 
@@ -71,6 +92,7 @@ impl Style {
         Style {
             theme: Theme::Light,
             palette: Palette::TolBright,
+            palette_cycle: PaletteCycle::Repeat,
         }
     }
 
@@ -79,6 +101,7 @@ impl Style {
         Style {
             theme: Theme::CatppuccinMocha,
             palette: Palette::CatppuccinMocha,
+            palette_cycle: PaletteCycle::Repeat,
         }
     }
 
@@ -87,6 +110,7 @@ impl Style {
         Style {
             theme: Theme::CatppuccinMacchiato,
             palette: Palette::CatppuccinMacchiato,
+            palette_cycle: PaletteCycle::Repeat,
         }
     }
 
@@ -95,6 +119,7 @@ impl Style {
         Style {
             theme: Theme::CatppuccinFrappe,
             palette: Palette::CatppuccinFrappe,
+            palette_cycle: PaletteCycle::Repeat,
         }
     }
 
@@ -103,6 +128,7 @@ impl Style {
         Style {
             theme: Theme::CatppuccinLatte,
             palette: Palette::CatppuccinLatte,
+            palette_cycle: PaletteCycle::Repeat,
         }
     }
 
@@ -115,31 +141,124 @@ impl Style {
     pub const fn palette(&self) -> &Palette {
         &self.palette
     }
+
+    /// How the palette colors are reused
+    pub const fn palette_cycle(&self) -> PaletteCycle {
+        self.palette_cycle
+    }
+}
+
+/// The built-in styles, identified by name
+///
+/// This is convenient to select a style from a command line argument or a configuration file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Builtin {
+    /// See [`Style::light`]
+    Light,
+    /// See [`Style::dark`]
+    Dark,
+    /// See [`Style::black_white`]
+    BlackWhite,
+    /// See [`Style::okabe_ito`]
+    OkabeIto,
+    /// See [`Style::tol_bright`]
+    TolBright,
+    /// See [`Style::catppuccin_latte`]
+    CatppuccinLatte,
+    /// See [`Style::catppuccin_frappe`]
+    CatppuccinFrappe,
+    /// See [`Style::catppuccin_macchiato`]
+    CatppuccinMacchiato,
+    /// See [`Style::catppuccin_mocha`]
+    CatppuccinMocha,
+}
+
+impl Builtin {
+    /// All the built-in styles
+    pub const ALL: &[Builtin] = &[
+        Builtin::Light,
+        Builtin::Dark,
+        Builtin::BlackWhite,
+        Builtin::OkabeIto,
+        Builtin::TolBright,
+        Builtin::CatppuccinLatte,
+        Builtin::CatppuccinFrappe,
+        Builtin::CatppuccinMacchiato,
+        Builtin::CatppuccinMocha,
+    ];
+
+    /// The kebab-case name of the style, as accepted by [`std::str::FromStr`]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Builtin::Light => "light",
+            Builtin::Dark => "dark",
+            Builtin::BlackWhite => "black-white",
+            Builtin::OkabeIto => "okabe-ito",
+            Builtin::TolBright => "tol-bright",
+            Builtin::CatppuccinLatte => "catppuccin-latte",
+            Builtin::CatppuccinFrappe => "catppuccin-frappe",
+            Builtin::CatppuccinMacchiato => "catppuccin-macchiato",
+            Builtin::CatppuccinMocha => "catppuccin-mocha",
+        }
+    }
+
+    /// Get the style
+    pub const fn to_style(&self) -> Style {
+        match self {
+            Builtin::Light => Style::light(),
+            Builtin::Dark => Style::dark(),
+            Builtin::BlackWhite => Style::black_white(),
+            Builtin::OkabeIto => Style::okabe_ito(),
+            Builtin::TolBright => Style::tol_bright(),
+            Builtin::CatppuccinLatte => Style::catppuccin_latte(),
+            Builtin::CatppuccinFrappe => Style::catppuccin_frappe(),
+            Builtin::CatppuccinMacchiato => Style::catppuccin_macchiato(),
+            Builtin::CatppuccinMocha => Style::catppuccin_mocha(),
+        }
+    }
+}
+
+impl std::str::FromStr for Builtin {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Builtin::ALL
+            .iter()
+            .find(|b| b.name() == s)
+            .copied()
+            .ok_or(())
+    }
+}
+
+impl From<Builtin> for Style {
+    fn from(builtin: Builtin) -> Self {
+        builtin.to_style()
+    }
 }
 
 impl ResolveColor<theme::Color> for Style {
     fn resolve_color(&self, col: &theme::Color) -> ColorU8 {
-        self.theme().resolve_color(col)
+        (&self.theme, &self.palette).resolve_color(col)
     }
 }
 
 impl ResolveColor<series::IndexColor> for Style {
     fn resolve_color(&self, col: &series::IndexColor) -> ColorU8 {
-        self.palette.get(*col)
+        self.palette.color(col.0, self.palette_cycle)
     }
 }
 
 impl ResolveColor<series::AutoColor> for (&Style, usize) {
     fn resolve_color(&self, _col: &series::AutoColor) -> ColorU8 {
-        self.0.palette.get(series::IndexColor(self.1))
+        self.0.palette.color(self.1, self.0.palette_cycle)
     }
 }
 
 impl ResolveColor<series::Color> for (&Style, usize) {
     fn resolve_color(&self, col: &series::Color) -> ColorU8 {
         match col {
-            series::Color::Auto => self.0.palette.get(series::IndexColor(self.1)),
-            series::Color::Index(idx) => self.0.palette.get(*idx),
+            series::Color::Auto => self.0.palette.color(self.1, self.0.palette_cycle),
+            series::Color::Index(idx) => self.0.palette.color(idx.0, self.0.palette_cycle),
             series::Color::Fixed(c) => *c,
         }
     }
@@ -152,6 +271,7 @@ impl ResolveColor<series::Color> for (&Style, usize) {
 /// The lengths are relative to the line width.
 /// So a pattern will scale with the line width and remain visually consistent.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Dash(pub Vec<f32>);
 
 impl Default for Dash {
@@ -162,6 +282,7 @@ impl Default for Dash {
 
 /// Line pattern defines how the line is drawn
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LinePattern {
     /// Solid line
     Solid,
@@ -188,6 +309,7 @@ impl From<Dash> for LinePattern {
 /// The color is a generic parameter to support different color resolution strategies,
 /// such as fixed colors, theme-based colors, or series-based colors.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stroke<C: Color> {
     /// Line color
     pub color: C,
@@ -195,7 +317,8 @@ pub struct Stroke<C: Color> {
     pub width: f32,
     /// Line pattern
     pub pattern: LinePattern,
-    /// Line opacity (0.0 to 1.0)
+    /// Line opacity (0.0 to 1.0).
+    /// It is multiplied with the alpha of the color, such that both can be combined.
     pub opacity: Option<f32>,
 }
 
@@ -220,17 +343,19 @@ impl<C: Color> Stroke<C> {
         Stroke { pattern, ..self }
     }
 
+    /// Multiply the line opacity by `alpha`
+    pub(crate) fn faded(self, alpha: f32) -> Self {
+        Stroke {
+            opacity: Some(self.opacity.unwrap_or(1.0) * alpha),
+            ..self
+        }
+    }
+
     /// Convert to a renderable stroke, resolving colors using the provided resolver
     pub fn as_stroke<'a, R>(&'a self, rc: &R) -> render::Stroke<'a>
     where
         R: ResolveColor<C>,
     {
-        let color = if let Some(opacity) = self.opacity {
-            self.color.resolve(rc).with_opacity(opacity)
-        } else {
-            self.color.resolve(rc)
-        };
-
         let pattern = match &self.pattern {
             LinePattern::Solid => render::LinePattern::Solid,
             LinePattern::Dash(Dash(a)) => render::LinePattern::Dash(a.as_slice()),
@@ -238,9 +363,10 @@ impl<C: Color> Stroke<C> {
         };
 
         render::Stroke {
-            color,
+            color: self.color.resolve(rc),
             width: self.width,
             pattern,
+            opacity: self.opacity,
         }
     }
 }
@@ -293,12 +419,14 @@ impl<C: Color> From<(C, f32, Dash)> for Stroke<C> {
 /// The color is a generic parameter to support different color resolution strategies,
 /// such as fixed colors, theme based colors, or series-based colors.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Fill<C: Color> {
     /// Solid fill
     Solid {
         /// Fill color
         color: C,
-        /// Fill opacity (0.0 to 1.0)
+        /// Fill opacity (0.0 to 1.0).
+        /// It is multiplied with the alpha of the color, such that both can be combined.
         opacity: Option<f32>,
     },
 }
@@ -326,20 +454,26 @@ impl<C: Color> Fill<C> {
         }
     }
 
+    /// Multiply the fill opacity by `alpha`
+    pub(crate) fn faded(self, alpha: f32) -> Self {
+        match self {
+            Fill::Solid { color, opacity } => Fill::Solid {
+                color,
+                opacity: Some(opacity.unwrap_or(1.0) * alpha),
+            },
+        }
+    }
+
     /// Convert to a renderable paint, resolving colors using the provided resolver
     pub fn as_paint<R>(&self, rc: &R) -> render::Paint
     where
         R: ResolveColor<C>,
     {
         match self {
-            Fill::Solid {
-                color,
-                opacity: None,
-            } => render::Paint::Solid(color.resolve(rc)),
-            Fill::Solid {
-                color,
-                opacity: Some(opacity),
-            } => render::Paint::Solid(color.resolve(rc).with_opacity(*opacity)),
+            Fill::Solid { color, opacity } => render::Paint::Solid {
+                color: color.resolve(rc),
+                opacity: *opacity,
+            },
         }
     }
 }
@@ -353,8 +487,84 @@ impl<C: Color> From<C> for Fill<C> {
     }
 }
 
+/// Drop shadow style definition, drawn beneath boxes such as legends and annotation labels.
+/// The color is a generic parameter to support different color resolution strategies,
+/// such as fixed colors, theme based colors, or series-based colors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shadow<C: Color> {
+    /// Shadow color
+    pub color: C,
+    /// Shadow opacity (0.0 to 1.0)
+    pub opacity: Option<f32>,
+    /// Offset of the shadow relative to the box in figure units.
+    /// Positive values shift the shadow to the right and to the bottom.
+    pub offset: (f32, f32),
+    /// Blur radius in figure units. Zero for a sharp shadow.
+    pub blur: f32,
+}
+
+impl<C: Color> Shadow<C> {
+    /// Set the shadow opacity (0.0 to 1.0), returning self for chaining
+    pub fn with_opacity(self, opacity: f32) -> Self {
+        Shadow {
+            opacity: Some(opacity),
+            ..self
+        }
+    }
+
+    /// Set the shadow offset in figure units, returning self for chaining
+    pub fn with_offset(self, dx: f32, dy: f32) -> Self {
+        Shadow {
+            offset: (dx, dy),
+            ..self
+        }
+    }
+
+    /// Set the blur radius in figure units, returning self for chaining
+    pub fn with_blur(self, blur: f32) -> Self {
+        Shadow { blur, ..self }
+    }
+
+    /// Convert to a renderable shadow of the given path, resolving colors using the provided resolver
+    pub fn as_shadow<'a, R>(
+        &self,
+        rc: &R,
+        path: &'a geom::Path,
+        transform: Option<&'a geom::Transform>,
+    ) -> render::Shadow<'a>
+    where
+        R: ResolveColor<C>,
+    {
+        let color = if let Some(opacity) = self.opacity {
+            self.color.resolve(rc).with_opacity(opacity)
+        } else {
+            self.color.resolve(rc)
+        };
+        render::Shadow {
+            path,
+            color,
+            offset: self.offset,
+            blur: self.blur,
+            transform,
+        }
+    }
+}
+
+impl<C: Color> From<C> for Shadow<C> {
+    fn from(color: C) -> Self {
+        Shadow {
+            color,
+            opacity: None,
+            offset: defaults::SHADOW_OFFSET,
+            blur: defaults::SHADOW_BLUR,
+        }
+    }
+}
+
 /// Shape of a marker, used in scatter plots
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MarkerShape {
     /// Circle marker (the default)
     #[default]
@@ -375,6 +585,7 @@ pub enum MarkerShape {
 
 /// Size of a marker, used in scatter plots
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MarkerSize(pub f32);
 
 impl Default for MarkerSize {
@@ -391,6 +602,7 @@ impl From<f32> for MarkerSize {
 
 /// Marker style definition, used in scatter plots
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Marker<C: Color> {
     /// Marker size
     pub size: MarkerSize,
@@ -398,10 +610,38 @@ pub struct Marker<C: Color> {
     pub shape: MarkerShape,
     /// Marker fill style
     pub fill: Option<Fill<C>>,
-    /// Marker stroke style
+    /// Marker stroke style, which is the edge (outline) of the marker, drawn over the fill
     pub stroke: Option<Stroke<C>>,
 }
 
+impl<C: Color> Marker<C> {
+    /// Set the marker fill, returning self for chaining
+    pub fn with_fill(self, fill: Fill<C>) -> Self {
+        Marker {
+            fill: Some(fill),
+            ..self
+        }
+    }
+
+    /// Set the marker edge, returning self for chaining.
+    /// The edge is an outline stroked over the fill, for example to separate overlapping markers.
+    pub fn with_edge(self, edge: Stroke<C>) -> Self {
+        Marker {
+            stroke: Some(edge),
+            ..self
+        }
+    }
+
+    /// Multiply the opacity of the fill and of the edge by `alpha`
+    pub(crate) fn faded(self, alpha: f32) -> Self {
+        Marker {
+            fill: self.fill.map(|fill| fill.faded(alpha)),
+            stroke: self.stroke.map(|stroke| stroke.faded(alpha)),
+            ..self
+        }
+    }
+}
+
 impl<C> Default for Marker<C>
 where
     C: Color + Default,
@@ -442,4 +682,82 @@ mod tests {
         let stroke = fixed_color.as_stroke(&());
         assert_eq!(stroke.color, ColorU8::from_html(b"#123456"));
     }
+
+    #[test]
+    fn test_derived_color_resolve() {
+        let light = Style::light();
+        let dark = Style::dark();
+
+        let pal = theme::Color::Palette(series::IndexColor(2));
+        assert_eq!(pal.resolve(&light), ColorU8::from_html(b"#2ca02c"));
+        assert_eq!(
+            pal.resolve(&dark),
+            dark.palette().get(series::IndexColor(2))
+        );
+
+        let lighter = theme::Color::lighten(theme::Col::Foreground, 0.5);
+        assert_eq!(lighter.resolve(&light).rgb(), [188, 188, 188]);
+        assert_eq!(lighter.resolve(&dark), ColorU8::from_html(b"#ffffff"));
+
+        let darker = theme::Color::darken(theme::Col::Background, 1.5);
+        assert_eq!(darker.resolve(&light), ColorU8::from_html(b"#000000"));
+
+        let blend = theme::Color::blend(theme::Col::Foreground, series::IndexColor(2), 0.0);
+        assert_eq!(blend.resolve(&light), ColorU8::from_html(b"#000000"));
+        let blend = theme::Color::blend(theme::Col::Foreground, series::IndexColor(2), 1.0);
+        assert_eq!(blend.resolve(&light), ColorU8::from_html(b"#2ca02c"));
+    }
+
+    #[test]
+    fn test_palette_cycle() {
+        let palette = series::Palette::from_colors(vec![
+            ColorU8::from_html(b"#ff0000"),
+            ColorU8::from_html(b"#0000ff"),
+        ]);
+        assert_eq!(palette.len(), 2);
+
+        let repeat = series::PaletteCycle::Repeat;
+        assert_eq!(palette.color(0, repeat), palette.color(2, repeat));
+        assert_eq!(palette.color(3, repeat), ColorU8::from_html(b"#0000ff"));
+
+        let lighten = series::PaletteCycle::Lighten;
+        assert_eq!(palette.color(1, lighten), ColorU8::from_html(b"#0000ff"));
+        let c0 = palette.color(0, lighten);
+        let c2 = palette.color(2, lighten);
+        let c4 = palette.color(4, lighten);
+        assert_eq!(c0, ColorU8::from_html(b"#ff0000"));
+        assert_eq!(c2.red(), 255);
+        assert!(c2.green() > 0 && c4.green() > c2.green());
+        assert_eq!(c4, palette.color(4, lighten));
+
+        let style = Style::new(Theme::Light, palette).with_palette_cycle(lighten);
+        let series_line: Stroke<series::AutoColor> = (series::AutoColor, 2.0).into();
+        assert_eq!(series_line.as_stroke(&(&style, 2)).color, c2);
+    }
+
+    #[test]
+    fn test_color_map_sample() {
+        let map = series::ColorMap::Grayscale;
+        assert_eq!(map.sample(0.0), ColorU8::from_html(b"#000000"));
+        assert_eq!(map.sample(1.0), ColorU8::from_html(b"#ffffff"));
+        assert_eq!(map.sample(2.0), ColorU8::from_html(b"#ffffff"));
+        assert_eq!(map.sample(0.5).red(), 127);
+
+        let map = series::ColorMap::Viridis;
+        assert_eq!(map.sample(0.0), ColorU8::from_html(b"#440154"));
+        assert_eq!(map.sample(1.0), ColorU8::from_html(b"#fde725"));
+
+        let empty = series::ColorMap::Custom(vec![]);
+        assert_eq!(empty.stops(), map.stops());
+        assert_eq!(empty.sample(0.5), map.sample(0.5));
+    }
+
+    #[test]
+    fn test_builtin_names() {
+        for builtin in Builtin::ALL {
+            assert_eq!(builtin.name().parse::<Builtin>(), Ok(*builtin));
+        }
+        assert_eq!("mocha".parse::<Builtin>(), Err(()));
+        assert_eq!(Style::from(Builtin::Dark), Style::dark());
+    }
 }
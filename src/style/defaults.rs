@@ -6,11 +6,20 @@ pub const FIG_SIZE: geom::Size = geom::Size::new(800.0, 600.0);
 pub const FIG_PADDING: geom::Padding = geom::Padding::Even(20.0);
 
 pub const TITLE_FONT_SIZE: f32 = 20.0;
+pub const SUBTITLE_FONT_SIZE: f32 = 14.0;
+pub const PLOT_TITLE_FONT_SIZE: f32 = 16.0;
+pub const PANEL_LABEL_FONT_SIZE: f32 = 16.0;
 pub const AXIS_LABEL_FONT_SIZE: f32 = 16.0;
 pub const TICKS_LABEL_FONT_SIZE: f32 = 12.0;
+pub const BAR_LABEL_FONT_SIZE: f32 = 11.0;
 
 pub const SERIES_LINE_WIDTH: f32 = 1.5;
 pub const MARKER_SIZE: f32 = 10.0;
+pub const MARKER_SIZE_RANGE: (f32, f32) = (4.0, 30.0);
+
+pub const GRID_LINE_WIDTH: f32 = 1.0;
+pub const MINOR_GRID_LINE_WIDTH: f32 = 0.5;
+pub const MINOR_TICK_LINE_WIDTH: f32 = 0.5;
 
 pub const LEGEND_LABEL_FONT_SIZE: f32 = 13.0;
 pub const LEGEND_SHAPE_SPACING: f32 = 10.0;
@@ -19,6 +28,14 @@ pub const LEGEND_PADDING: f32 = 8.0;
 pub const LEGEND_H_SPACING: f32 = 16.0;
 pub const LEGEND_V_SPACING: f32 = 10.0;
 pub const LEGEND_MARGIN: f32 = 12.0;
+pub const LEGEND_DIMMED_OPACITY: f32 = 0.3;
+
+pub const SHADOW_OFFSET: (f32, f32) = (3.0, 3.0);
+pub const SHADOW_BLUR: f32 = 4.0;
+pub const SHADOW_OPACITY: f32 = 0.4;
+
+pub const WATERMARK_FONT_SIZE: f32 = 48.0;
+pub const WATERMARK_OPACITY: f32 = 0.15;
 
 pub const PLOT_XY_AUTO_INSETS: geom::Padding = geom::Padding::Even(20.0);
 pub const PLOT_VER_BARS_AUTO_INSETS: geom::Padding = geom::Padding::Custom {
@@ -35,3 +52,5 @@ pub const PLOT_HOR_BARS_AUTO_INSETS: geom::Padding = geom::Padding::Custom {
 };
 pub const PLOT_AXIS_ARROW_SIZE: f32 = 10.0;
 pub const PLOT_AXIS_ARROW_OVERFLOW: f32 = 10.0;
+
+pub const PALETTE_CYCLE_LIGHTEN: f32 = 0.3;
@@ -7,8 +7,10 @@ use crate::{ColorU8, ResolveColor};
 /// A palette for data series.
 /// It provides ordered colors for series in a figure.
 /// If more series are present than colors in the palette,
-/// colors are reused in order.
+/// colors are reused in order, deterministically.
+/// See [`Palette::color`] and [`PaletteCycle`].
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Palette {
     /// Black monochrome palette
     Black,
@@ -19,7 +21,9 @@ pub enum Palette {
     Pastel,
     /// Paul Tol's bright colorblind-safe palette
     TolBright,
-    /// Okabe & Ito colorblind-safe palette
+    /// Okabe & Ito colorblind-safe palette.
+    /// The readability of a figure for colorblind readers can be checked with
+    /// `PxlSurface::simulate_cvd` of the `plotive-pxl` crate.
     OkabeIto,
     /// Catppuccin Mocha palette
     CatppuccinMocha,
@@ -29,7 +33,8 @@ pub enum Palette {
     CatppuccinFrappe,
     /// Catppuccin Latte palette
     CatppuccinLatte,
-    /// A custom palette
+    /// A custom palette.
+    /// It must have at least one color (see [`Palette::from_colors`]).
     Custom(Vec<ColorU8>),
 }
 
@@ -50,31 +55,132 @@ impl Palette {
         }
     }
 
+    /// Create a custom palette from the given colors.
+    ///
+    /// # Panics
+    /// Panics if `colors` is empty.
+    pub fn from_colors(colors: Vec<ColorU8>) -> Self {
+        assert!(!colors.is_empty(), "A palette must have at least one color");
+        Palette::Custom(colors)
+    }
+
     /// Get the number of colors in the palette
     pub const fn len(&self) -> usize {
         self.colors().len()
     }
 
-    /// Get a color from the palette by its index
+    /// Check whether the palette has no color.
+    /// Only an empty custom palette is empty.
+    pub const fn is_empty(&self) -> bool {
+        self.colors().is_empty()
+    }
+
+    /// Get a color from the palette by its index.
+    /// Colors are reused in order when `index` exceeds the palette length.
     pub const fn get(&self, col: IndexColor) -> ColorU8 {
         self.colors()[col.0 % self.len()]
     }
+
+    /// Get the color at `index`, cycling over the palette.
+    ///
+    /// Index `i` maps to the color `i % len()`. This color is used as is during the first cycle,
+    /// and on the following cycles, it is either reused as is or lightened according to `cycle`.
+    /// The same index always gives the same color.
+    pub fn color(&self, index: usize, cycle: PaletteCycle) -> ColorU8 {
+        let color = self.get(IndexColor(index));
+        match cycle {
+            PaletteCycle::Repeat => color,
+            PaletteCycle::Lighten => {
+                let n = (index / self.len()) as i32;
+                if n == 0 {
+                    color
+                } else {
+                    let frac = 1.0 - (1.0 - defaults::PALETTE_CYCLE_LIGHTEN).powi(n);
+                    color.blend(crate::color::WHITE, frac)
+                }
+            }
+        }
+    }
+}
+
+/// How palette colors are reused when there are more series than colors in the palette
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PaletteCycle {
+    /// Colors are reused as is
+    #[default]
+    Repeat,
+    /// Colors are lightened on each new cycle, so that wrapped colors differ from the first ones
+    Lighten,
+}
+
+/// A continuous color map, used to color data values such as the counts of a 2D histogram.
+/// Colors are linearly interpolated between the stops of the map.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColorMap {
+    /// Perceptually uniform map, from dark purple to yellow
+    #[default]
+    Viridis,
+    /// Perceptually uniform map, from black to light yellow through red
+    Magma,
+    /// From black to white
+    Grayscale,
+    /// A custom map, made of evenly spaced color stops.
+    /// An empty custom map falls back to [`ColorMap::Viridis`].
+    Custom(Vec<ColorU8>),
+}
+
+impl ColorMap {
+    /// Get the color stops of the map.
+    /// This is never empty, as an empty custom map falls back to [`ColorMap::Viridis`].
+    pub fn stops(&self) -> &[ColorU8] {
+        match self {
+            ColorMap::Viridis => colormaps::VIRIDIS,
+            ColorMap::Magma => colormaps::MAGMA,
+            ColorMap::Grayscale => colormaps::GRAYSCALE,
+            ColorMap::Custom(colors) if colors.is_empty() => colormaps::VIRIDIS,
+            ColorMap::Custom(colors) => colors.as_slice(),
+        }
+    }
+
+    /// Sample the color map at `t`, which is clamped between 0 and 1.
+    pub fn sample(&self, t: f64) -> ColorU8 {
+        let stops = self.stops();
+        if stops.len() == 1 || t.is_nan() {
+            return stops[0];
+        }
+        let pos = t.clamp(0.0, 1.0) * (stops.len() - 1) as f64;
+        let idx = (pos.floor() as usize).min(stops.len() - 2);
+        let frac = (pos - idx as f64) as f32;
+        let [r1, g1, b1, a1] = stops[idx].rgba_f32();
+        let [r2, g2, b2, a2] = stops[idx + 1].rgba_f32();
+        ColorU8::from_rgba_f32(
+            r1 + (r2 - r1) * frac,
+            g1 + (g2 - g1) * frac,
+            b1 + (b2 - b1) * frac,
+            a1 + (a2 - a1) * frac,
+        )
+    }
 }
 
 /// A series color identified by its index in a palette
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IndexColor(pub usize);
 
 impl style::Color for IndexColor {}
 
 /// A series color that is automatically chosen from a palette based on the series index
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AutoColor;
 
 impl style::Color for AutoColor {}
 
 /// A flexible color for data series
 #[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     /// Automatic color from the palette
     #[default]
@@ -107,21 +213,21 @@ impl style::Color for Color {}
 
 impl ResolveColor<IndexColor> for Palette {
     fn resolve_color(&self, col: &IndexColor) -> ColorU8 {
-        self.get(*col)
+        self.color(col.0, PaletteCycle::Repeat)
     }
 }
 
 impl ResolveColor<AutoColor> for (&Palette, usize) {
     fn resolve_color(&self, _col: &AutoColor) -> ColorU8 {
-        self.0.get(IndexColor(self.1))
+        self.0.color(self.1, PaletteCycle::Repeat)
     }
 }
 
 impl ResolveColor<Color> for (&Palette, usize) {
     fn resolve_color(&self, col: &Color) -> ColorU8 {
         match col {
-            Color::Auto => self.0.get(IndexColor(self.1)),
-            Color::Index(idx) => self.0.get(*idx),
+            Color::Auto => self.0.color(self.1, PaletteCycle::Repeat),
+            Color::Index(idx) => self.0.color(idx.0, PaletteCycle::Repeat),
             Color::Fixed(c) => *c,
         }
     }
@@ -181,6 +287,40 @@ impl From<ColorU8> for Marker {
     }
 }
 
+/// Color stops of the built-in color maps
+mod colormaps {
+    use crate::ColorU8;
+
+    pub const VIRIDIS: &[ColorU8] = &[
+        ColorU8::from_html(b"#440154"),
+        ColorU8::from_html(b"#482878"),
+        ColorU8::from_html(b"#3e4a89"),
+        ColorU8::from_html(b"#31688e"),
+        ColorU8::from_html(b"#26828e"),
+        ColorU8::from_html(b"#1f9e89"),
+        ColorU8::from_html(b"#35b779"),
+        ColorU8::from_html(b"#6ece58"),
+        ColorU8::from_html(b"#b5de2b"),
+        ColorU8::from_html(b"#fde725"),
+    ];
+    pub const MAGMA: &[ColorU8] = &[
+        ColorU8::from_html(b"#000004"),
+        ColorU8::from_html(b"#180f3d"),
+        ColorU8::from_html(b"#440f76"),
+        ColorU8::from_html(b"#721f81"),
+        ColorU8::from_html(b"#9e2f7f"),
+        ColorU8::from_html(b"#cd4071"),
+        ColorU8::from_html(b"#f1605d"),
+        ColorU8::from_html(b"#fd9668"),
+        ColorU8::from_html(b"#feca8d"),
+        ColorU8::from_html(b"#fcfdbf"),
+    ];
+    pub const GRAYSCALE: &[ColorU8] = &[
+        ColorU8::from_html(b"#000000"),
+        ColorU8::from_html(b"#ffffff"),
+    ];
+}
+
 /// Types for built-in and custom palettes
 mod palettes {
     use crate::ColorU8;
@@ -6,6 +6,7 @@ use crate::{style, text};
 
 /// A theme, for styling figures
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Theme {
     #[default]
     /// Light theme
@@ -72,6 +73,7 @@ impl Theme {
 
 /// The colors used in a theme
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ThemePalette {
     /// Background color
     pub background: ColorU8,
@@ -142,6 +144,7 @@ impl ThemePalette {
 
 /// Predefined colors for theme elements
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Col {
     /// Background color
     Background,
@@ -183,13 +186,74 @@ impl color::ResolveColor<Col> for Theme {
     }
 }
 
+/// A color that other theme colors can be derived from
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BaseColor {
+    /// A color from the theme
+    Theme(Col),
+    /// A color from the series palette, by index
+    Palette(style::series::IndexColor),
+    /// A fixed RGB color
+    Fixed(ColorU8),
+}
+
+impl From<Col> for BaseColor {
+    fn from(color: Col) -> Self {
+        BaseColor::Theme(color)
+    }
+}
+
+impl From<style::series::IndexColor> for BaseColor {
+    fn from(color: style::series::IndexColor) -> Self {
+        BaseColor::Palette(color)
+    }
+}
+
+impl From<ColorU8> for BaseColor {
+    fn from(color: ColorU8) -> Self {
+        BaseColor::Fixed(color)
+    }
+}
+
 /// A flexible color for theme elements
+///
+/// Besides the theme colors, colors can refer to the series palette,
+/// or be derived from other colors. They are all resolved at draw time,
+/// against the theme and palette of the style.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     /// A color from the theme
     Theme(Col),
     /// A fixed RGB color
     Fixed(ColorU8),
+    /// A color from the series palette, by index
+    Palette(style::series::IndexColor),
+    /// A color lightened towards white by the given fraction (0.0 to 1.0)
+    Lighten(BaseColor, f32),
+    /// A color darkened towards black by the given fraction (0.0 to 1.0)
+    Darken(BaseColor, f32),
+    /// A blend of two colors, the fraction (0.0 to 1.0) being the part of the second color.
+    /// The colors are blended in linear RGB space.
+    Blend(BaseColor, BaseColor, f32),
+}
+
+impl Color {
+    /// A color lightened towards white by the given fraction (0.0 to 1.0)
+    pub fn lighten(base: impl Into<BaseColor>, frac: f32) -> Self {
+        Color::Lighten(base.into(), frac)
+    }
+
+    /// A color darkened towards black by the given fraction (0.0 to 1.0)
+    pub fn darken(base: impl Into<BaseColor>, frac: f32) -> Self {
+        Color::Darken(base.into(), frac)
+    }
+
+    /// A blend of `a` and `b`, `t` being the part of `b` (0.0 to 1.0)
+    pub fn blend(a: impl Into<BaseColor>, b: impl Into<BaseColor>, t: f32) -> Self {
+        Color::Blend(a.into(), b.into(), t)
+    }
 }
 
 impl From<Col> for Color {
@@ -204,6 +268,22 @@ impl From<ColorU8> for Color {
     }
 }
 
+impl From<style::series::IndexColor> for Color {
+    fn from(color: style::series::IndexColor) -> Self {
+        Color::Palette(color)
+    }
+}
+
+impl From<BaseColor> for Color {
+    fn from(color: BaseColor) -> Self {
+        match color {
+            BaseColor::Theme(col) => Color::Theme(col),
+            BaseColor::Palette(idx) => Color::Palette(idx),
+            BaseColor::Fixed(c) => Color::Fixed(c),
+        }
+    }
+}
+
 impl super::Color for Color {}
 
 impl std::str::FromStr for Color {
@@ -225,15 +305,36 @@ impl text::rich::Foreground for Color {
     }
 }
 
-impl color::ResolveColor<Color> for Theme {
+impl color::ResolveColor<BaseColor> for (&Theme, &style::series::Palette) {
+    fn resolve_color(&self, col: &BaseColor) -> ColorU8 {
+        match col {
+            BaseColor::Theme(col) => self.0.resolve_color(col),
+            BaseColor::Palette(idx) => self.1.get(*idx),
+            BaseColor::Fixed(c) => *c,
+        }
+    }
+}
+
+impl color::ResolveColor<Color> for (&Theme, &style::series::Palette) {
     fn resolve_color(&self, col: &Color) -> ColorU8 {
         match col {
-            Color::Theme(col) => self.resolve_color(col),
+            Color::Theme(col) => self.0.resolve_color(col),
             Color::Fixed(c) => *c,
+            Color::Palette(idx) => self.1.get(*idx),
+            Color::Lighten(base, frac) => self.resolve_color(base).blend(color::WHITE, *frac),
+            Color::Darken(base, frac) => self.resolve_color(base).blend(color::BLACK, *frac),
+            Color::Blend(a, b, t) => self.resolve_color(a).blend(self.resolve_color(b), *t),
         }
     }
 }
 
+/// Colors referring to the series palette are resolved with the default palette.
+impl color::ResolveColor<Color> for Theme {
+    fn resolve_color(&self, col: &Color) -> ColorU8 {
+        (self, &style::series::Palette::default()).resolve_color(col)
+    }
+}
+
 /// Stroke style for theme elements
 pub type Stroke = style::Stroke<Color>;
 
@@ -264,6 +365,22 @@ impl From<Col> for Fill {
     }
 }
 
+/// Drop shadow style for theme elements
+pub type Shadow = style::Shadow<Color>;
+
+impl Default for Shadow {
+    /// Translucent black shadow, offset towards the bottom-right and slightly blurred
+    fn default() -> Self {
+        Shadow::from(Color::Fixed(color::BLACK)).with_opacity(style::defaults::SHADOW_OPACITY)
+    }
+}
+
+impl From<Col> for Shadow {
+    fn from(col: Col) -> Self {
+        Shadow::from(Color::from(col))
+    }
+}
+
 /// Marker style for theme elements
 pub type Marker = style::Marker<Color>;
 
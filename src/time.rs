@@ -78,10 +78,53 @@ const fn days_in_year(year: i32) -> i32 {
     if is_leap_year(year) { 366 } else { 365 }
 }
 
+/// Number of ISO 8601 weeks in the ISO year: 53 if the year starts on a Thursday,
+/// or on a Wednesday for a leap year, and 52 otherwise.
+const fn iso_weeks_in_year(year: i32) -> u32 {
+    const fn p(y: i32) -> i32 {
+        (y + y.div_euclid(4) - y.div_euclid(100) + y.div_euclid(400)).rem_euclid(7)
+    }
+    if p(year) == 4 || p(year - 1) == 3 {
+        53
+    } else {
+        52
+    }
+}
+
+/// Days elapsed from 1970-01-01 to the given date of the proleptic Gregorian calendar
+const fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year } as i64;
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// A calendar unit, to truncate or round a [`DateTime`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unit {
+    /// A second
+    Second,
+    /// A minute
+    Minute,
+    /// An hour
+    Hour,
+    /// A day
+    Day,
+    /// A month, of variable length
+    Month,
+    /// A year, of variable length
+    Year,
+}
+
 /// A type representing a date and time.
 /// It is represented by a `f64`, that is the seconds elapsed since Jan. 1, 2030, which is Plotive Epoch.
 /// Timezone is not supported.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DateTime(f64);
 
 impl DateTime {
@@ -135,12 +178,36 @@ impl DateTime {
     /// - `%.3f` for milliseconds (e.g. 340000 microseconds will format to ".340")
     /// - `%.6f` for microseconds (e.g. 340000 microseconds will format to ".340000")
     /// - `%.9f` for nanoseconsd (e.g. 340000 microseconds will format to ".340000000")
+    /// - `%G` for the ISO 8601 week-based year (YYYY), only for formatting
+    /// - `%V` for the ISO 8601 week number (WW, 01 to 53), only for formatting
+    /// - `%u` for the ISO 8601 weekday (1 for Monday to 7 for Sunday), only for formatting
+    ///
     /// As a result, parsing according ISO 8601 can be done e.g. with `%Y-%m-%dT%H:%M:%S`
+    /// (see also [DateTime::parse_iso8601] for the common variants, including timezone designators).
     pub fn fmt_parse(input: &str, fmt: &str) -> Result<DateTime, ParseError> {
         let comps = DateTimeComps::fmt_parse(input, fmt)?;
         Ok(comps.try_into()?)
     }
 
+    /// Parse a string in one of the common ISO 8601 (and RFC 3339) date time formats:
+    /// - `2025-01-13`
+    /// - `2025-01-13T15:46`
+    /// - `2025-01-13T15:46:32`
+    /// - `2025-01-13T15:46:32.25`, with any number of fractional digits (truncated to microseconds)
+    ///
+    /// The date and time can also be separated by a space.
+    /// The time can be followed by a timezone designator: `Z` for UTC, or a numeric offset
+    /// such as `+02:00`, `-0530` or `+02`.
+    /// As [`DateTime`] has no timezone, a time with an offset is converted to UTC.
+    /// A time without designator is taken as is.
+    pub fn parse_iso8601(input: &str) -> Result<DateTime, ParseError> {
+        let (main, offset) = split_iso8601_offset(input)?;
+        let fmt = iso8601_format(main)
+            .ok_or_else(|| ParseError::Parse(format!("'{input}' is not an ISO 8601 date time")))?;
+        let dt = DateTime::fmt_parse(main, &fmt)?;
+        Ok(dt - offset)
+    }
+
     /// Format this DateTime according to the given format string.
     /// See [DateTime::fmt_parse] for supported formats.
     pub fn fmt_write<W>(&self, fmt: &str, out: &mut W) -> fmt::Result
@@ -188,9 +255,82 @@ impl DateTime {
         }
     }
 
+    /// Truncate this DateTime to the start of the given unit,
+    /// by zeroing the lower components (e.g. the first day of the month at midnight for [`Unit::Month`])
+    pub fn truncate_to(&self, unit: Unit) -> DateTime {
+        let c = self.to_comps();
+        let trunc = match unit {
+            Unit::Year => DateTimeComps {
+                year: c.year,
+                ..DateTimeComps::epoch()
+            },
+            Unit::Month => DateTimeComps {
+                year: c.year,
+                month: c.month,
+                ..DateTimeComps::epoch()
+            },
+            Unit::Day => DateTimeComps {
+                hour: 0,
+                minute: 0,
+                second: 0,
+                micro: 0,
+                ..c
+            },
+            Unit::Hour => DateTimeComps {
+                minute: 0,
+                second: 0,
+                micro: 0,
+                ..c
+            },
+            Unit::Minute => DateTimeComps {
+                second: 0,
+                micro: 0,
+                ..c
+            },
+            Unit::Second => DateTimeComps { micro: 0, ..c },
+        };
+        trunc.try_into().expect("Should be a valid date time")
+    }
+
+    /// Round this DateTime to the nearest start of the given unit.
+    /// Halfway values round up, to the start of the next unit.
+    /// Months and years are rounded according to their actual length.
+    pub fn round_to(&self, unit: Unit) -> DateTime {
+        let start = self.truncate_to(unit);
+        let next = start.add_unit(unit);
+        if self.0 - start.0 < next.0 - self.0 {
+            start
+        } else {
+            next
+        }
+    }
+
+    /// Add one calendar unit to this DateTime, which must be truncated to that unit
+    fn add_unit(&self, unit: Unit) -> DateTime {
+        let secs = match unit {
+            Unit::Second => 1.0,
+            Unit::Minute => 60.0,
+            Unit::Hour => 3600.0,
+            Unit::Day => 86400.0,
+            Unit::Month => {
+                let date = self.to_date();
+                month_days(date.year)[date.month as usize - 1] as f64 * 86400.0
+            }
+            Unit::Year => days_in_year(self.to_date().year) as f64 * 86400.0,
+        };
+        DateTime(self.0 + secs)
+    }
+
+    /// Compute the ISO 8601 week date of this DateTime.
+    /// See [DateComps::iso_week].
+    pub fn iso_week(&self) -> (i32, u32, u32) {
+        self.to_date().iso_week()
+    }
+
     /// Compute the time components of this DateTime
     pub fn to_time(&self) -> TimeComps {
-        let seconds_in_day = self.0 % 86400.0;
+        // euclidean remainder, as date times before the epoch are negative
+        let seconds_in_day = self.0.rem_euclid(86400.0);
         let hour = (seconds_in_day / 3600.0).floor() as u32;
         let minute = ((seconds_in_day % 3600.0) / 60.0).floor() as u32;
         let second = (seconds_in_day % 60.0).floor() as u32;
@@ -266,6 +406,40 @@ pub struct DateComps {
     pub day: u32,
 }
 
+impl DateComps {
+    /// The day in the year (1 to 366)
+    pub fn ordinal(&self) -> u32 {
+        let month_days = month_days(self.year);
+        let prev_months = (self.month as usize).saturating_sub(1).min(12);
+        month_days[..prev_months].iter().sum::<u32>() + self.day
+    }
+
+    /// The ISO 8601 weekday, from 1 for Monday to 7 for Sunday
+    pub fn weekday(&self) -> u32 {
+        let days = days_from_civil(self.year, self.month, self.day);
+        // 1970-01-01 is a Thursday
+        (days + 3).rem_euclid(7) as u32 + 1
+    }
+
+    /// Compute the ISO 8601 week date: the ISO year, the week number (1 to 53)
+    /// and the weekday (1 for Monday to 7 for Sunday).
+    ///
+    /// Weeks start on Monday, and the first week of a year is the one containing its first Thursday.
+    /// As a result, the first days of January can belong to the last week of the previous ISO year,
+    /// and the last days of December to the first week of the next ISO year.
+    pub fn iso_week(&self) -> (i32, u32, u32) {
+        let weekday = self.weekday();
+        let week = (self.ordinal() as i32 - weekday as i32 + 10) / 7;
+        if week < 1 {
+            (self.year - 1, iso_weeks_in_year(self.year - 1), weekday)
+        } else if week as u32 > iso_weeks_in_year(self.year) {
+            (self.year + 1, 1, weekday)
+        } else {
+            (self.year, week as u32, weekday)
+        }
+    }
+}
+
 /// A type gathering the date components
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TimeComps {
@@ -361,7 +535,10 @@ impl DateTimeComps {
                         }
                     }
                 }
-                FmtToken::TimeDeltaDays => return Err(ParseError::FormatMismatch),
+                FmtToken::TimeDeltaDays
+                | FmtToken::IsoYear
+                | FmtToken::IsoWeek
+                | FmtToken::IsoWeekday => return Err(ParseError::FormatMismatch),
             }
         }
 
@@ -377,6 +554,11 @@ impl DateTimeComps {
     where
         W: fmt::Write,
     {
+        let date = DateComps {
+            year: self.year,
+            month: self.month,
+            day: self.day,
+        };
         let fmt = FmtStr(fmt);
         for tok in fmt.tokens() {
             let Ok(tok) = tok else { return Err(fmt::Error) };
@@ -392,6 +574,9 @@ impl DateTimeComps {
                 FmtToken::Nano => write!(out, ".{:09}", self.micro * 1000)?,
                 FmtToken::Frac => format_micro_opt(out, self.micro)?,
                 FmtToken::Lit(s) => out.write_str(s)?,
+                FmtToken::IsoYear => write!(out, "{:04}", date.iso_week().0)?,
+                FmtToken::IsoWeek => write!(out, "{:02}", date.iso_week().1)?,
+                FmtToken::IsoWeekday => write!(out, "{}", date.weekday())?,
                 FmtToken::TimeDeltaDays => return Err(fmt::Error),
             }
         }
@@ -477,6 +662,7 @@ impl fmt::Display for DateTimeComps {
 /// A type representing a time difference, or duration.
 /// The value can be negative.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TimeDelta(f64);
 
 impl TimeDelta {
@@ -857,6 +1043,61 @@ impl ops::SubAssign<TimeDelta> for TimeDelta {
     }
 }
 
+/// Split the timezone designator of an ISO 8601 date time, and return the offset from UTC
+fn split_iso8601_offset(input: &str) -> Result<(&str, TimeDelta), ParseError> {
+    // the designator follows the time, which starts after the date
+    let Some(time) = input.get(10..) else {
+        return Ok((input, TimeDelta::zero()));
+    };
+    if let Some(main) = input.strip_suffix(['Z', 'z']) {
+        return Ok((main, TimeDelta::zero()));
+    }
+    let Some(pos) = time.rfind(['+', '-']) else {
+        return Ok((input, TimeDelta::zero()));
+    };
+    let (main, designator) = input.split_at(10 + pos);
+
+    let (sign, hm) = designator.split_at(1);
+    let fmt = match hm.len() {
+        2 => "%H",
+        4 => "%H%M",
+        5 => "%H:%M",
+        _ => {
+            return Err(ParseError::Parse(format!(
+                "'{designator}' is not an ISO 8601 timezone offset"
+            )));
+        }
+    };
+    let offset: TimeDelta = TimeDeltaComps::fmt_parse(hm, fmt)?.try_into()?;
+    if sign == "-" {
+        Ok((main, offset * -1.0))
+    } else {
+        Ok((main, offset))
+    }
+}
+
+/// Build the format to parse an ISO 8601 date time without timezone designator
+fn iso8601_format(main: &str) -> Option<String> {
+    if main.len() == 10 {
+        return Some("%Y-%m-%d".to_string());
+    }
+    let sep = main.get(10..11).filter(|s| matches!(*s, "T" | "t" | " "))?;
+    let time = match main.len() {
+        16 => "%H:%M",
+        19 => "%H:%M:%S",
+        // the fraction parser stops at the first non digit
+        n if n > 20
+            && main
+                .get(20..)
+                .is_some_and(|f| f.bytes().all(|b| b.is_ascii_digit())) =>
+        {
+            "%H:%M:%S%.f"
+        }
+        _ => return None,
+    };
+    Some(format!("%Y-%m-%d{sep}{time}"))
+}
+
 #[derive(Debug, Clone, Copy)]
 struct FmtStr<'a>(&'a str);
 
@@ -872,6 +1113,9 @@ enum FmtToken<'a> {
     Micro,
     Nano,
     Frac,
+    IsoYear,
+    IsoWeek,
+    IsoWeekday,
     Lit(&'a str),
     TimeDeltaDays,
 }
@@ -957,6 +1201,18 @@ impl<'a> Iterator for FmtTokens<'a> {
                     self.remaining = &self.remaining[2..];
                     return Some(Ok(FmtToken::TimeDeltaDays));
                 }
+                "%G" => {
+                    self.remaining = &self.remaining[2..];
+                    return Some(Ok(FmtToken::IsoYear));
+                }
+                "%V" => {
+                    self.remaining = &self.remaining[2..];
+                    return Some(Ok(FmtToken::IsoWeek));
+                }
+                "%u" => {
+                    self.remaining = &self.remaining[2..];
+                    return Some(Ok(FmtToken::IsoWeekday));
+                }
                 _ => (),
             }
         }
@@ -966,20 +1222,11 @@ impl<'a> Iterator for FmtTokens<'a> {
             return Some(Err(ParseError::FormatMismatch));
         }
 
-        let mut end = 0;
-        for (i, c) in self.remaining.char_indices() {
-            end = i;
-            if c == '%' {
-                break;
-            }
-        }
-        if end == 0 {
-            None
-        } else {
-            let lit = &self.remaining[..end];
-            self.remaining = &self.remaining[end..];
-            Some(Ok(FmtToken::Lit(lit)))
-        }
+        // the literal extends up to the next specifier, or to the end of the format
+        let end = self.remaining.find('%').unwrap_or(self.remaining.len());
+        let lit = &self.remaining[..end];
+        self.remaining = &self.remaining[end..];
+        Some(Ok(FmtToken::Lit(lit)))
     }
 }
 
@@ -1101,6 +1348,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_iso8601() {
+        let expected = DateTime::fmt_parse("2025-01-13 15:46:32", "%Y-%m-%d %H:%M:%S").unwrap();
+        let parse = |s: &str| DateTime::parse_iso8601(s).unwrap();
+
+        assert_eq!(parse("2025-01-13T15:46:32"), expected);
+        assert_eq!(parse("2025-01-13 15:46:32"), expected);
+        assert_eq!(parse("2025-01-13T15:46:32Z"), expected);
+        assert_eq!(parse("2025-01-13T17:46:32+02:00"), expected);
+        assert_eq!(parse("2025-01-13T10:16:32-0530"), expected);
+        assert_eq!(parse("2025-01-13T16:46:32+01"), expected);
+        assert_eq!(parse("2025-01-13T15:46:32.25Z").to_comps().micro, 250_000);
+        assert_eq!(
+            parse("2025-01-13T15:46:32.123456789").to_comps().micro,
+            123_456
+        );
+        assert_eq!(
+            parse("2025-01-13T15:46"),
+            expected - TimeDelta::from_seconds(32.0)
+        );
+        assert_eq!(
+            parse("2025-01-13"),
+            DateTime::from_ymd(2025, 1, 13).unwrap()
+        );
+
+        // the offset can change the date
+        assert_eq!(parse("2025-01-01T01:00:00+02:00").to_comps().day, 31);
+
+        assert!(matches!(
+            DateTime::parse_iso8601("2025-01-13T15:46:32+2"),
+            Err(ParseError::Parse(_))
+        ));
+        assert!(matches!(
+            DateTime::parse_iso8601("2025-01-13T15:46:32.25abc"),
+            Err(ParseError::Parse(_))
+        ));
+        assert!(matches!(
+            DateTime::parse_iso8601("2025-01-13X15:46:32"),
+            Err(ParseError::Parse(_))
+        ));
+        assert!(matches!(
+            DateTime::parse_iso8601("2025-13-01T15:46:32Z"),
+            Err(ParseError::InvalidField("month", 13))
+        ));
+        assert!(matches!(
+            DateTime::parse_iso8601("2025-01-13T15:46:32+24:00"),
+            Err(ParseError::InvalidField("hour", 24))
+        ));
+    }
+
     #[test]
     fn test_parse_datetime_comps() {
         let input = "2025-01-13 15:46:32.25";
@@ -1175,4 +1472,148 @@ mod tests {
         let result = comps.fmt_to_string(fmt);
         assert_eq!(result, "2025-01-13 15:46:32.250000");
     }
+
+    #[test]
+    fn test_fmt_tokens_trailing_lit() {
+        let fmt = FmtStr("%H:%MZ");
+        let tokens: Vec<FmtToken> = fmt.tokens().map(|res| res.unwrap()).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                FmtToken::Hour,
+                FmtToken::Lit(":"),
+                FmtToken::Minute,
+                FmtToken::Lit("Z"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_comps_before_epoch() {
+        let dt = DateTime::fmt_parse("1999-12-31 23:59:58.5", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+        assert_eq!(dt.to_string(), "1999-12-31 23:59:58.5");
+    }
+
+    #[test]
+    fn test_iso_week() {
+        let iso_week = |y, m, d| DateTime::from_ymd(y, m, d).unwrap().iso_week();
+
+        // first days of January in the last week of the previous year
+        assert_eq!(iso_week(2005, 1, 1), (2004, 53, 6));
+        assert_eq!(iso_week(2005, 1, 2), (2004, 53, 7));
+        assert_eq!(iso_week(2010, 1, 3), (2009, 53, 7));
+        assert_eq!(iso_week(2021, 1, 3), (2020, 53, 7));
+        assert_eq!(iso_week(2021, 1, 4), (2021, 1, 1));
+
+        // last days of December in the first week of the next year
+        assert_eq!(iso_week(2007, 12, 30), (2007, 52, 7));
+        assert_eq!(iso_week(2007, 12, 31), (2008, 1, 1));
+        assert_eq!(iso_week(2008, 12, 29), (2009, 1, 1));
+
+        // years of 52 and 53 weeks
+        assert_eq!(iso_week(2005, 12, 31), (2005, 52, 6));
+        assert_eq!(iso_week(2020, 12, 31), (2020, 53, 4));
+        assert_eq!(iso_week(2026, 12, 31), (2026, 53, 4));
+        assert_eq!(iso_week(2007, 1, 1), (2007, 1, 1));
+
+        // around the epoch
+        assert_eq!(DateTime::epoch().iso_week(), (2030, 1, 2));
+        assert_eq!(iso_week(2029, 12, 30), (2029, 52, 7));
+    }
+
+    #[test]
+    fn test_format_iso_week() {
+        let dt = DateTime::from_ymd(2008, 12, 29).unwrap();
+        assert_eq!(dt.fmt_to_string("%G-W%V-%u"), "2009-W01-1");
+        assert_eq!(dt.fmt_to_string("%Y week %V"), "2008 week 01");
+
+        // formatting only
+        assert!(matches!(
+            DateTime::fmt_parse("2009-W01-1", "%G-W%V-%u"),
+            Err(ParseError::FormatMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_truncate_to() {
+        let dt = DateTime::fmt_parse("2024-02-29 13:45:30.75", "%Y-%m-%d %H:%M:%S%.f").unwrap();
+        let trunc = |unit| dt.truncate_to(unit).to_string();
+        assert_eq!(trunc(Unit::Second), "2024-02-29 13:45:30");
+        assert_eq!(trunc(Unit::Minute), "2024-02-29 13:45:00");
+        assert_eq!(trunc(Unit::Hour), "2024-02-29 13:00:00");
+        assert_eq!(trunc(Unit::Day), "2024-02-29 00:00:00");
+        assert_eq!(trunc(Unit::Month), "2024-02-01 00:00:00");
+        assert_eq!(trunc(Unit::Year), "2024-01-01 00:00:00");
+
+        // after the epoch
+        let dt = DateTime::fmt_parse("2032-12-31 23:59:59", "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            dt.truncate_to(Unit::Month).to_string(),
+            "2032-12-01 00:00:00"
+        );
+    }
+
+    #[test]
+    fn test_round_to() {
+        let round = |s: &str, unit| {
+            DateTime::fmt_parse(s, "%Y-%m-%d %H:%M:%S")
+                .unwrap()
+                .round_to(unit)
+                .to_string()
+        };
+        assert_eq!(
+            round("2024-02-29 11:59:59", Unit::Day),
+            "2024-02-29 00:00:00"
+        );
+        assert_eq!(
+            round("2024-02-29 12:00:00", Unit::Day),
+            "2024-03-01 00:00:00"
+        );
+        assert_eq!(
+            round("2023-12-31 23:30:00", Unit::Hour),
+            "2024-01-01 00:00:00"
+        );
+        assert_eq!(
+            round("2024-02-29 13:45:29", Unit::Minute),
+            "2024-02-29 13:45:00"
+        );
+
+        // February has 29 days in 2024: the middle is on the 15th at noon
+        assert_eq!(
+            round("2024-02-15 11:00:00", Unit::Month),
+            "2024-02-01 00:00:00"
+        );
+        assert_eq!(
+            round("2024-02-15 12:00:00", Unit::Month),
+            "2024-03-01 00:00:00"
+        );
+        // and 28 days in 2023: the middle is on the 15th at midnight
+        assert_eq!(
+            round("2023-02-14 23:00:00", Unit::Month),
+            "2023-02-01 00:00:00"
+        );
+        assert_eq!(
+            round("2023-02-15 00:00:00", Unit::Month),
+            "2023-03-01 00:00:00"
+        );
+
+        // 366 days in 2024: the middle is on July 2 at midnight
+        assert_eq!(
+            round("2024-07-01 23:00:00", Unit::Year),
+            "2024-01-01 00:00:00"
+        );
+        assert_eq!(
+            round("2024-07-02 00:00:00", Unit::Year),
+            "2025-01-01 00:00:00"
+        );
+        // 365 days in 2023: the middle is on July 2 at noon
+        assert_eq!(
+            round("2023-07-02 11:00:00", Unit::Year),
+            "2023-01-01 00:00:00"
+        );
+        assert_eq!(
+            round("2023-07-02 12:00:00", Unit::Year),
+            "2024-01-01 00:00:00"
+        );
+    }
 }
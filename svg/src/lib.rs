@@ -11,6 +11,7 @@ use svg::node::element;
 pub enum Error {
     Io(io::Error),
     Drawing(drawing::Error),
+    Render(render::Error),
 }
 
 impl From<io::Error> for Error {
@@ -25,11 +26,18 @@ impl From<drawing::Error> for Error {
     }
 }
 
+impl From<render::Error> for Error {
+    fn from(err: render::Error) -> Self {
+        Error::Render(err)
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::Io(err) => write!(f, "IO error: {}", err),
             Error::Drawing(err) => write!(f, "Drawing error: {}", err),
+            Error::Render(err) => write!(f, "Render error: {}", err),
         }
     }
 }
@@ -46,6 +54,10 @@ pub struct Params<'a> {
     /// as the fonts have already been resolved.
     /// In such case, this parameter can be left to `None` (which is the default).
     pub fontdb: Option<&'a plotive::fontdb::Database>,
+    /// Optional physical size of the document.
+    /// If set, the document width and height are given in millimeters,
+    /// and `scale` is ignored. The resolution of the physical size is not used.
+    pub physical_size: Option<render::PhysicalSize>,
 }
 
 impl Default for Params<'_> {
@@ -54,6 +66,7 @@ impl Default for Params<'_> {
             style: Style::default(),
             scale: 1.0,
             fontdb: None,
+            physical_size: None,
         }
     }
 }
@@ -112,8 +125,11 @@ impl SaveSvg for drawing::PreparedFigure {
         let height = (size.height() * params.scale) as u32;
 
         let mut surface = SvgSurface::new(witdth, height);
+        if let Some(physical_size) = params.physical_size {
+            surface = surface.with_physical_size(physical_size);
+        }
 
-        self.draw(&mut surface, &params.style);
+        self.draw(&mut surface, &params.style)?;
         surface.save_svg(path)?;
         Ok(())
     }
@@ -122,6 +138,7 @@ impl SaveSvg for drawing::PreparedFigure {
 pub struct SvgSurface {
     doc: svg::Document,
     clip_num: u32,
+    shadow_num: u32,
     _node_num: u32,
     group_stack: Vec<element::Group>,
 }
@@ -134,84 +151,158 @@ impl SvgSurface {
         SvgSurface {
             doc,
             clip_num: 0,
+            shadow_num: 0,
             _node_num: 0,
             group_stack: vec![],
         }
     }
 
-    pub fn save_svg<P: AsRef<std::path::Path>>(&self, path: P) -> io::Result<()> {
-        if !self.group_stack.is_empty() {
-            panic!("Unbalanced clip stack");
-        }
-        svg::save(path, &self.doc)
+    /// Set the document width and height in millimeters, for printing.
+    /// The drawing is scaled to fit and centered, as the view box stays in plot units.
+    pub fn with_physical_size(mut self, size: render::PhysicalSize) -> Self {
+        self.doc = self
+            .doc
+            .set("width", format!("{}mm", size.width_mm))
+            .set("height", format!("{}mm", size.height_mm));
+        self
     }
 
-    pub fn write<W>(&self, dest: &mut W) -> io::Result<()>
+    pub fn save_svg<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), Error> {
+        self.check_clip_stack()?;
+        svg::save(path, &self.doc)?;
+        Ok(())
+    }
+
+    pub fn write<W>(&self, dest: &mut W) -> Result<(), Error>
     where
         W: io::Write,
     {
-        if !self.group_stack.is_empty() {
-            panic!("Unbalanced clip stack");
+        self.check_clip_stack()?;
+        svg::write(dest, &self.doc)?;
+        Ok(())
+    }
+
+    fn check_clip_stack(&self) -> Result<(), render::Error> {
+        if self.group_stack.is_empty() {
+            Ok(())
+        } else {
+            Err(render::Error::UnbalancedClipStack)
         }
-        svg::write(dest, &self.doc)
     }
 }
 
 impl Surface for SvgSurface {
     /// Prepare the surface for drawing, with the given width and height in plot units
-    fn prepare(&mut self, size: geom::Size) {
+    fn prepare(&mut self, size: geom::Size) -> Result<(), render::Error> {
         self.doc
             .assign("viewBox", (0, 0, size.width(), size.height()));
+        Ok(())
     }
 
     /// Fill the entire surface with the given color
-    fn fill(&mut self, fill: render::Paint) {
+    fn fill(&mut self, fill: render::Paint) -> Result<(), render::Error> {
         let mut node = element::Rectangle::new()
             .set("width", "100%")
             .set("height", "100%");
-        match fill {
-            render::Paint::Solid(color) => node.assign("fill", color.html()),
-        }
+        assign_fill(&mut node, Some(&fill));
         self.append_node(node);
+        Ok(())
     }
 
     /// Draw a rectangle
-    fn draw_rect(&mut self, rect: &render::Rect) {
+    fn draw_rect(&mut self, rect: &render::Rect) -> Result<(), render::Error> {
         let mut node = rectangle_node(&rect.rect);
         assign_fill(&mut node, rect.fill.as_ref());
         assign_stroke(&mut node, rect.stroke.as_ref());
         assign_transform(&mut node, rect.transform);
         self.append_node(node);
+        Ok(())
     }
 
-    fn draw_path(&mut self, path: &render::Path) {
+    fn draw_path(&mut self, path: &render::Path) -> Result<(), render::Error> {
         let mut node = element::Path::new();
         assign_fill(&mut node, path.fill.as_ref());
         assign_stroke(&mut node, path.stroke.as_ref());
         assign_transform(&mut node, path.transform);
         node.assign("d", path_data(path.path));
         self.append_node(node);
+        Ok(())
     }
 
-    fn push_clip(&mut self, clip: &render::Clip) {
+    fn draw_shadow(&mut self, shadow: &render::Shadow) -> Result<(), render::Error> {
+        let shadow_id = self.bump_shadow_id();
+        let (dx, dy) = shadow.offset;
+        // the filter region is extended to leave room for the offset and the blur
+        let mut filter = element::Filter::new()
+            .set("id", shadow_id.clone())
+            .set("x", "-50%")
+            .set("y", "-50%")
+            .set("width", "200%")
+            .set("height", "200%")
+            .add(
+                element::FilterEffectOffset::new()
+                    .set("dx", dx)
+                    .set("dy", dy),
+            );
+        if shadow.blur > 0.0 {
+            // blur radius is taken as twice the gaussian standard deviation
+            filter = filter.add(
+                element::FilterEffectGaussianBlur::new().set("stdDeviation", shadow.blur / 2.0),
+            );
+        }
+        self.append_node(filter);
+
+        let mut node = element::Path::new();
+        assign_fill(&mut node, Some(&shadow.color.into()));
+        assign_transform(&mut node, shadow.transform);
+        node.assign("d", path_data(shadow.path));
+        // the filter is applied on a group, such that the offset and blur are in figure units
+        let group = element::Group::new()
+            .set("filter", format!("url(#{})", shadow_id))
+            .add(node);
+        self.append_node(group);
+        Ok(())
+    }
+
+    fn draw_image(&mut self, image: &render::Image) -> Result<(), render::Error> {
+        let png = encode_png(image)?;
+        let rect = &image.rect;
+        let mut node = element::Image::new()
+            .set("x", rect.x())
+            .set("y", rect.y())
+            .set("width", rect.width())
+            .set("height", rect.height())
+            .set("preserveAspectRatio", "none")
+            .set("href", format!("data:image/png;base64,{}", base64(&png)));
+        if image.opacity < 1.0 {
+            node.assign("opacity", image.opacity);
+        }
+        assign_transform(&mut node, image.transform);
+        self.append_node(node);
+        Ok(())
+    }
+
+    fn push_clip(&mut self, clip: &render::Clip) -> Result<(), render::Error> {
         let clip_id = self.bump_clip_id();
         let clip_id_url = format!("url(#{})", clip_id);
-        let mut rect_node = rectangle_node(&clip.rect);
-        assign_transform(&mut rect_node, clip.transform);
+        let mut path_node = element::Path::new().set("d", path_data(clip.path));
+        assign_transform(&mut path_node, clip.transform);
         let node = element::ClipPath::new()
             .set("id", clip_id.clone())
-            .add(rect_node);
+            .add(path_node);
         self.append_node(node);
         self.group_stack
             .push(element::Group::new().set("clip-path", clip_id_url));
+        Ok(())
     }
 
-    fn pop_clip(&mut self) {
-        let g = self.group_stack.pop();
-        if g.is_none() {
-            panic!("Unbalanced clip stack");
-        }
-        self.append_node(g.unwrap());
+    fn pop_clip(&mut self) -> Result<(), render::Error> {
+        let g = self
+            .group_stack
+            .pop()
+            .ok_or(render::Error::UnbalancedClipStack)?;
+        self.append_node(g);
+        Ok(())
     }
 }
 
@@ -232,6 +323,11 @@ impl SvgSurface {
         format!("plotive-clip{}", self.clip_num)
     }
 
+    fn bump_shadow_id(&mut self) -> String {
+        self.shadow_num += 1;
+        format!("plotive-shadow{}", self.shadow_num)
+    }
+
     fn _bump_node_id(&mut self) -> String {
         self._node_num += 1;
         format!("plotive-node{}", self._node_num)
@@ -348,9 +444,10 @@ fn assign_fill<N>(node: &mut N, fill: Option<&render::Paint>)
 where
     N: Node,
 {
-    if let Some(render::Paint::Solid(color)) = fill {
+    if let Some(paint @ render::Paint::Solid { color, .. }) = fill {
         node.assign("fill", color.html());
-        if let Some(opacity) = color.opacity() {
+        let opacity = paint.opacity();
+        if opacity < 1.0 {
             node.assign("fill-opacity", opacity);
         }
     } else {
@@ -366,7 +463,8 @@ where
         let w = stroke.width;
         node.assign("stroke", stroke.color.html());
         node.assign("stroke-width", w);
-        if let Some(opacity) = stroke.color.opacity() {
+        let opacity = stroke.opacity();
+        if opacity < 1.0 {
             node.assign("stroke-opacity", opacity);
         }
         match stroke.pattern {
@@ -405,6 +503,46 @@ fn path_data(path: &geom::Path) -> element::path::Data {
     data
 }
 
+/// Encode the non-premultiplied RGBA data of the image to PNG
+fn encode_png(image: &render::Image) -> Result<Vec<u8>, render::Error> {
+    image.check()?;
+    let size = tiny_skia::IntSize::from_wh(image.width, image.height)
+        .expect("Image size should be checked");
+    let data = image
+        .rgba
+        .chunks_exact(4)
+        .flat_map(|px| {
+            let c = tiny_skia::ColorU8::from_rgba(px[0], px[1], px[2], px[3]).premultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect();
+    tiny_skia::Pixmap::from_vec(data, size)
+        .expect("Image data should be checked")
+        .encode_png()
+        .map_err(|err| render::Error::InvalidImage(err.to_string()))
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
 fn rectangle_node(rect: &geom::Rect) -> element::Rectangle {
     element::Rectangle::new()
         .set("x", rect.x())
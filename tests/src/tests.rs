@@ -42,10 +42,33 @@ fn line2(x: &[f64], y: &[f64]) -> des::series::Line {
     des::series::Line::new(x.into(), y.into())
 }
 
+/// Extract the value of the given attribute in the first element starting with `tag`
+fn svg_attr<'a>(svg: &'a str, tag: &str, attr: &str) -> Option<&'a str> {
+    let elem = &svg[svg.find(tag)?..];
+    let elem = &elem[..elem.find('>')?];
+    let start = elem.find(&format!(" {attr}=\""))? + attr.len() + 3;
+    let len = elem[start..].find('"')?;
+    Some(&elem[start..start + len])
+}
+
+mod animate;
+mod annot;
+mod antialias;
+mod areas;
 mod axes;
+mod bars;
+mod clip;
+mod cvd;
+mod hist;
 mod interp;
+mod json;
 mod legend;
+mod opacity;
+mod physical;
+mod region;
+mod scatter;
 mod subplots;
+mod themes;
 
 #[test]
 fn empty() {
@@ -62,3 +85,59 @@ fn empty_title() {
 
     assert_fig_eq_ref!(&fig, "empty-title");
 }
+
+#[test]
+fn title_start_subtitle() {
+    let plot = des::Plot::new(vec![line().into()]);
+    let fig = fig_small(plot)
+        .with_title("Title".into())
+        .with_subtitle("A longer subtitle".into())
+        .with_title_align(plotive::text::line::Align::Start);
+
+    assert_fig_eq_ref!(&fig, "title-start-subtitle");
+}
+
+#[test]
+fn title_decorations() {
+    let title = plotive::text::parse_rich_text::<plotive::style::theme::Color>(
+        "[underline]Under[/underline], [strikeout]struck[/strikeout] and [underline;strikeout;color=red]both[/underline;strikeout;color]",
+    )
+    .unwrap();
+    let plot = des::Plot::new(vec![line().into()]);
+    let fig = fig_small(plot).with_title(title.into());
+
+    assert_fig_eq_ref!(&fig, "title-decorations");
+}
+
+#[test]
+fn watermark_text() {
+    let plot = des::Plot::new(vec![line().into()]);
+    let fig = fig_small(plot).with_watermark(des::Watermark::text("DRAFT"));
+
+    assert_fig_eq_ref!(&fig, "watermark-text");
+}
+
+#[test]
+fn watermark_image_front() {
+    // 8x8 red/blue gradient with a transparent corner
+    let mut rgba = Vec::new();
+    for y in 0..8u8 {
+        for x in 0..8u8 {
+            let alpha = if x + y < 4 { 0 } else { 255 };
+            rgba.extend_from_slice(&[x * 32, 0, y * 32, alpha]);
+        }
+    }
+    let image = des::figure::WatermarkImage {
+        rgba,
+        width: 8,
+        height: 8,
+        size: geom::Size::new(120.0, 120.0),
+    };
+    let watermark = des::Watermark::image(image)
+        .with_opacity(0.5)
+        .with_layer(des::figure::WatermarkLayer::Front);
+    let plot = des::Plot::new(vec![line().into()]);
+    let fig = fig_small(plot).with_watermark(watermark);
+
+    assert_fig_eq_ref!(&fig, "watermark-image-front");
+}
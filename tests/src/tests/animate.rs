@@ -0,0 +1,152 @@
+use std::path::PathBuf;
+
+use plotive::{data, des, geom};
+use plotive_pxl::{AnimationOutput, AnimationParams, Error, animate};
+
+const FRAMES: usize = 3;
+
+fn fig() -> des::Figure {
+    let series = des::series::Line::new(des::data_src_ref("x"), des::data_src_ref("y"));
+    des::Figure::new(des::Plot::new(vec![series.into()]).into())
+        .with_size(geom::Size::new(200.0, 150.0))
+}
+
+fn frame_source(i: usize) -> data::TableSource {
+    let x: Vec<f64> = (0..=20).map(|x| x as f64).collect();
+    let y = x.iter().map(|x| x * (i + 1) as f64).collect();
+    data::TableSource::new()
+        .with_f64_column("x", x)
+        .with_f64_column("y", y)
+}
+
+fn run(output: &AnimationOutput, fixed_bounds: bool) {
+    let mut src = frame_source(0);
+    let params = AnimationParams {
+        fixed_bounds,
+        ..Default::default()
+    };
+    animate(
+        &fig(),
+        &mut src,
+        FRAMES,
+        |i, src| *src = frame_source(i),
+        output,
+        params,
+    )
+    .unwrap();
+}
+
+fn out_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("plotive-animate-{}-{name}", std::process::id()))
+}
+
+#[test]
+fn animate_png_sequence() {
+    let dir = out_path("seq");
+    run(&AnimationOutput::PngSequence(dir.clone()), true);
+
+    let frames: Vec<_> = (0..FRAMES)
+        .map(|i| tiny_skia::Pixmap::load_png(dir.join(format!("frame-{i:04}.png"))).unwrap())
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    for frame in &frames {
+        assert_eq!((frame.width(), frame.height()), (200, 150));
+    }
+    // the line gets steeper within fixed axes bounds
+    assert_ne!(frames[0].data(), frames[1].data());
+    assert_ne!(frames[1].data(), frames[2].data());
+}
+
+#[test]
+fn animate_fixed_bounds() {
+    // without fixed bounds, the axes follow the data:
+    // the line stays in place and only the tick labels of the y axis change
+    let dir = out_path("follow");
+    run(&AnimationOutput::PngSequence(dir.clone()), false);
+
+    let frames: Vec<_> = (0..FRAMES)
+        .map(|i| tiny_skia::Pixmap::load_png(dir.join(format!("frame-{i:04}.png"))).unwrap())
+        .collect();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    let diff = |a: &tiny_skia::Pixmap, b: &tiny_skia::Pixmap| {
+        a.pixels()
+            .iter()
+            .zip(b.pixels())
+            .filter(|(a, b)| a != b)
+            .count()
+    };
+    let fixed_dir = out_path("fixed");
+    run(&AnimationOutput::PngSequence(fixed_dir.clone()), true);
+    let fixed_last =
+        tiny_skia::Pixmap::load_png(fixed_dir.join(format!("frame-{:04}.png", FRAMES - 1)))
+            .unwrap();
+    std::fs::remove_dir_all(&fixed_dir).unwrap();
+
+    assert!(diff(&frames[0], &frames[FRAMES - 1]) < diff(&frames[0], &fixed_last));
+}
+
+#[test]
+fn animate_gif() {
+    let path = out_path("anim.gif");
+    run(&AnimationOutput::Gif(path.clone()), true);
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(bytes.starts_with(b"GIF89a"));
+    assert_eq!(bytes.last(), Some(&0x3b));
+    // one graphic control extension per frame
+    let frames = bytes.windows(2).filter(|w| w == &[0x21, 0xf9]).count();
+    assert!(frames >= FRAMES);
+}
+
+#[test]
+fn animate_apng() {
+    let path = out_path("anim.png");
+    run(&AnimationOutput::Apng(path.clone()), true);
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let actl = bytes.windows(4).position(|w| w == b"acTL").unwrap();
+    let num_frames = u32::from_be_bytes(bytes[actl + 4..actl + 8].try_into().unwrap());
+    assert_eq!(num_frames, FRAMES as u32);
+    assert_eq!(bytes.windows(4).filter(|w| w == b"fcTL").count(), FRAMES);
+}
+
+#[test]
+fn animate_invalid_params() {
+    let run = |output: &AnimationOutput, frames: usize, frame_rate: f32| {
+        let mut src = frame_source(0);
+        let params = AnimationParams {
+            frame_rate,
+            ..Default::default()
+        };
+        animate(&fig(), &mut src, frames, |_, _| {}, output, params)
+    };
+    let path = out_path("invalid.gif");
+    let gif = AnimationOutput::Gif(path.clone());
+    let apng = AnimationOutput::Apng(path.clone());
+
+    assert!(matches!(run(&gif, 0, 10.0), Err(Error::NoFrames)));
+    assert!(matches!(run(&apng, 0, 10.0), Err(Error::NoFrames)));
+    for rate in [0.0, -5.0, f32::NAN, f32::INFINITY, 0.001] {
+        assert!(matches!(
+            run(&gif, FRAMES, rate),
+            Err(Error::InvalidFrameRate(_))
+        ));
+        assert!(matches!(
+            run(&apng, FRAMES, rate),
+            Err(Error::InvalidFrameRate(_))
+        ));
+    }
+    // nothing is written for invalid parameters
+    assert!(!path.exists());
+
+    // the frame rate is ignored for PNG sequences
+    let dir = out_path("invalid-seq");
+    let seq = AnimationOutput::PngSequence(dir.clone());
+    assert!(matches!(run(&seq, 0, 10.0), Err(Error::NoFrames)));
+    run(&seq, 1, 0.0).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,55 @@
+use plotive::{color, des, style};
+
+use super::{fig_small, line, line2};
+use crate::{TestHarness, assert_fig_eq_ref};
+
+#[test]
+fn annot_label_shadow() {
+    let series = line().into();
+    let label = des::annot::Label::new("label".into(), 2.0, 2.0)
+        .with_anchor(des::annot::Anchor::Center)
+        .with_frame(
+            Some(style::theme::Color::from(color::WHITE).into()),
+            Some(style::theme::Col::Foreground.into()),
+        )
+        .with_shadow(Some(Default::default()))
+        .with_angle(30.0);
+    let plot = des::Plot::new(vec![series]).with_annotation(label.into());
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "annot/label-shadow");
+}
+
+#[test]
+fn annot_spans() {
+    let series = line().into();
+    let x_span = des::annot::Span::vertical(Some(1.5), Some(2.0));
+    // open-ended span, extending to the top of the plot, drawn above the series
+    let y_span = des::annot::Span::horizontal(Some(2.5), None)
+        .with_fill(
+            style::theme::Fill::from(style::theme::Color::from(color::RED)).with_opacity(0.3),
+        )
+        .with_zpos(des::annot::ZPos::AboveSeries);
+    let plot = des::Plot::new(vec![series])
+        .with_span(x_span)
+        .with_span(y_span);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "annot/spans");
+}
+
+#[test]
+fn annot_span_log() {
+    let series = line2(&[1.0, 2.0, 3.0], &[1.0, 10.0, 1000.0]).into();
+    // the span is clipped to the plot area at the bottom
+    let span = des::annot::Span::horizontal(Some(0.01), Some(10.0));
+    let y_axis = des::Axis::new()
+        .with_scale(des::axis::LogScale::default().into())
+        .with_ticks(Default::default());
+    let plot = des::Plot::new(vec![series])
+        .with_y_axis(y_axis)
+        .with_span(span);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "annot/span-log");
+}
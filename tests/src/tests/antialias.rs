@@ -0,0 +1,64 @@
+use plotive::render::{self, Surface};
+use plotive::{ColorU8, geom};
+use plotive_pxl::{AntiAlias, PxlSurface};
+
+const SIZE: u32 = 40;
+
+/// Draw a grid line at a fractional position and a diagonal line
+fn draw_lines(anti_alias: AntiAlias) -> (bool, bool) {
+    let mut pxl = PxlSurface::new(SIZE, SIZE)
+        .unwrap()
+        .with_anti_alias(anti_alias);
+    pxl.prepare(geom::Size::new(SIZE as f32, SIZE as f32))
+        .unwrap();
+
+    let stroke = render::Stroke {
+        color: ColorU8::from_rgb(0, 0, 0),
+        width: 1.0,
+        pattern: render::LinePattern::Solid,
+        opacity: None,
+    };
+    let lines = [((0.0, 10.3), (20.0, 10.3)), ((25.0, 0.0), (40.0, 30.0))];
+    for ((x1, y1), (x2, y2)) in lines {
+        let mut pb = geom::PathBuilder::new();
+        pb.move_to(x1, y1);
+        pb.line_to(x2, y2);
+        let path = pb.finish().unwrap();
+        pxl.draw_path(&render::Path {
+            path: &path,
+            fill: None,
+            stroke: Some(stroke),
+            transform: None,
+        })
+        .unwrap();
+    }
+
+    let pixmap = pxl.into_pixmap();
+    // whether there are partially covered pixels in the left (grid) and right (diagonal) halves
+    let mut partial = (false, false);
+    for (i, px) in pixmap.pixels().iter().enumerate() {
+        if px.alpha() != 0 && px.alpha() != 255 {
+            if (i as u32 % SIZE) < SIZE / 2 {
+                partial.0 = true;
+            } else {
+                partial.1 = true;
+            }
+        }
+    }
+    partial
+}
+
+#[test]
+fn antialias_high_quality() {
+    assert_eq!(draw_lines(AntiAlias::HighQuality), (true, true));
+}
+
+#[test]
+fn antialias_none() {
+    assert_eq!(draw_lines(AntiAlias::None), (false, false));
+}
+
+#[test]
+fn antialias_sharp_lines() {
+    assert_eq!(draw_lines(AntiAlias::SharpLines), (false, true));
+}
@@ -0,0 +1,54 @@
+use plotive::{ColorU8, des};
+
+use crate::tests::fig_small;
+use crate::{TestHarness, assert_fig_eq_ref};
+
+fn areas_group() -> des::series::AreasGroup {
+    let x = || des::data_inline(vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    des::series::AreasGroup::new(vec![
+        des::series::AreaSeries::new(
+            x(),
+            des::data_inline(vec![1.0, 2.0, 4.0, 3.0, 2.0, 2.0, 1.0]),
+        )
+        .with_name("a")
+        .with_fill(ColorU8::from_html(b"#1f77b4").into()),
+        des::series::AreaSeries::new(
+            x(),
+            des::data_inline(vec![3.0, 3.0, 2.0, 1.0, 2.0, 4.0, 5.0]),
+        )
+        .with_name("b")
+        .with_fill(ColorU8::from_html(b"#ff7f0e").into()),
+        des::series::AreaSeries::new(
+            x(),
+            des::data_inline(vec![0.0, 1.0, 1.0, 3.0, 4.0, 2.0, 1.0]),
+        )
+        .with_name("c")
+        .with_fill(ColorU8::from_html(b"#2ca02c").into()),
+    ])
+}
+
+fn areas_fig(baseline: des::series::AreasBaseline) -> des::Figure {
+    let series = areas_group().with_baseline(baseline).into();
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+    fig_small(plot)
+}
+
+#[test]
+fn areas_baseline_zero() {
+    let fig = areas_fig(des::series::AreasBaseline::Zero);
+    assert_fig_eq_ref!(&fig, "areas/baseline-zero");
+}
+
+#[test]
+fn areas_baseline_symmetric() {
+    let fig = areas_fig(des::series::AreasBaseline::Symmetric);
+    assert_fig_eq_ref!(&fig, "areas/baseline-symmetric");
+}
+
+#[test]
+fn areas_baseline_wiggle() {
+    let fig = areas_fig(des::series::AreasBaseline::Wiggle);
+    assert_fig_eq_ref!(&fig, "areas/baseline-wiggle");
+}
@@ -1,4 +1,4 @@
-use plotive::{color, des};
+use plotive::{Prepare, color, des, geom, style, text};
 
 use super::{fig_small, line, line2};
 use crate::tests::fig_mid;
@@ -44,6 +44,95 @@ fn axes_titles() {
     assert_fig_eq_ref!(&fig, "axes/titles");
 }
 
+#[test]
+fn axes_multiline_titles() {
+    let series = line().into();
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(des::Axis::new().with_title("Frequency\n[Hz]".into()))
+        .with_y_axis(
+            des::Axis::new()
+                .with_title("Magnitude\nrelative to 1 V [dB]".into())
+                .with_title_align(text::line::Align::Start),
+        );
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/multiline-titles");
+}
+
+#[test]
+fn axes_math_titles() {
+    let series = line().into();
+    let x_title = text::parse_rich_text::<style::theme::Color>("$\\omega_0 t$ \\[rad]").unwrap();
+    let y_title =
+        text::parse_rich_text::<style::theme::Color>("$\\frac{1}{2} e^{-\\alpha t}$").unwrap();
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(des::Axis::new().with_title(x_title.into()))
+        .with_y_axis(des::Axis::new().with_title(y_title.into()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/math-titles");
+}
+
+#[test]
+fn axes_bidi_titles() {
+    // right to left paragraph with embedded numbers and latin text,
+    // and left to right paragraph with an embedded right to left word
+    let series = line().into();
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(
+            des::Axis::new()
+                .with_title("\u{5e9}\u{5dc}\u{5d5}\u{5dd} 2024 abc\n\u{5d0}\u{5d1}\u{5d2}".into())
+                .with_title_align(text::line::Align::Start),
+        )
+        .with_y_axis(des::Axis::new().with_title("abc \u{5d0}\u{5d1}\u{5d2} 123".into()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/bidi-titles");
+}
+
+#[test]
+fn axes_title_pos() {
+    let series = line().into();
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(
+            des::Axis::new()
+                .with_ticks(Default::default())
+                .with_title("x axis".into())
+                .with_title_pos(des::axis::TitlePos::End),
+        )
+        .with_y_axis(
+            des::Axis::new()
+                .with_ticks(Default::default())
+                .with_title("y axis".into())
+                .with_title_pos(des::axis::TitlePos::Start),
+        );
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/title-pos");
+}
+
+#[test]
+fn axes_title_side() {
+    let series = line().into();
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(
+            des::Axis::new()
+                .with_ticks(Default::default())
+                .with_title("x axis".into())
+                .with_title_side(des::axis::Side::Opposite),
+        )
+        .with_y_axis(
+            des::Axis::new()
+                .with_ticks(Default::default())
+                .with_title("y axis".into())
+                .with_title_side(des::axis::Side::Opposite)
+                .with_title_pos(des::axis::TitlePos::End),
+        );
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/title-side");
+}
+
 #[test]
 fn axes_x_major_ticks() {
     let series = line().into();
@@ -139,6 +228,52 @@ fn axes_minor_ticks() {
     assert_fig_eq_ref!(&fig, "axes/minor-ticks");
 }
 
+#[test]
+fn axes_ticks_in() {
+    let series = line().into();
+    let ticks = des::axis::Ticks::default().with_direction(des::axis::TickDirection::In);
+    let minor_ticks = des::axis::MinorTicks::default().with_direction(des::axis::TickDirection::In);
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(
+            des::Axis::new()
+                .with_ticks(ticks.clone())
+                .with_minor_ticks(minor_ticks.clone()),
+        )
+        .with_y_axis(
+            des::Axis::new()
+                .with_ticks(ticks)
+                .with_minor_ticks(minor_ticks),
+        );
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/ticks-in");
+}
+
+#[test]
+fn axes_ticks_out_length() {
+    let series = line().into();
+    let ticks = des::axis::Ticks::default()
+        .with_direction(des::axis::TickDirection::Out)
+        .with_length(8.0);
+    let minor_ticks = des::axis::MinorTicks::default()
+        .with_direction(des::axis::TickDirection::In)
+        .with_length(3.0);
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(
+            des::Axis::new()
+                .with_ticks(ticks.clone())
+                .with_minor_ticks(minor_ticks.clone()),
+        )
+        .with_y_axis(
+            des::Axis::new()
+                .with_ticks(ticks)
+                .with_minor_ticks(minor_ticks),
+        );
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/ticks-out-length");
+}
+
 #[test]
 fn axes_x_major_grid() {
     let series = line().into();
@@ -179,6 +314,24 @@ fn axes_major_grid() {
     assert_fig_eq_ref!(&fig, "axes/major-grid");
 }
 
+#[test]
+fn axes_grid_above_series() {
+    let x = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    let y = vec![1.0, 1.4, 3.0];
+    let series = des::series::Bars::new(x.into(), y.into());
+    let axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_grid(Default::default())
+        .with_grid_zorder(des::ZOrder::ABOVE_SERIES);
+    let line = des::annot::Line::horizontal(2.0).with_pattern(style::Dash::default().into());
+    let plot = des::Plot::new(vec![series.into()])
+        .with_y_axis(axis)
+        .with_annotation(des::Annotation::from(line).with_zorder(des::ZOrder(20)));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/grid-above-series");
+}
+
 #[test]
 fn axes_minor_grid() {
     let series = line().into();
@@ -196,6 +349,136 @@ fn axes_minor_grid() {
     assert_fig_eq_ref!(&fig, "axes/minor-grid");
 }
 
+#[test]
+fn axes_minor_grid_style() {
+    let series = line().into();
+    let minor_grid = style::theme::Stroke::from(style::theme::Col::Grid)
+        .with_width(1.0)
+        .with_pattern(style::LinePattern::Solid)
+        .with_opacity(0.5);
+    let axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_grid(Default::default())
+        .with_minor_ticks(des::axis::MinorTicks::new().with_width(1.5))
+        .with_minor_grid(des::axis::MinorGrid(minor_grid));
+
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(axis.clone())
+        .with_y_axis(axis);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/minor-grid-style");
+}
+
+#[test]
+fn axes_grid_range() {
+    let series = line2(&[-2.0, 0.0, 2.0], &[-2.0, 1.0, 3.0]).into();
+    let x_axis = des::Axis::new().with_ticks(Default::default());
+    let y_axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_grid(Default::default())
+        .with_minor_ticks(Default::default())
+        .with_minor_grid(Default::default())
+        .with_grid_range(0.0, f64::INFINITY);
+
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(x_axis)
+        .with_y_axis(y_axis);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/grid-range");
+}
+
+#[test]
+fn axes_emphasized_values() {
+    let series = line().into();
+    let emphasis = style::theme::Stroke::from(style::theme::Col::Foreground)
+        .with_width(1.5)
+        .with_pattern(style::LinePattern::Solid);
+    let x_axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_grid(Default::default());
+    // 2.0 replaces the major grid line, 0.0 is out of range and not drawn
+    let y_axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_grid(Default::default())
+        .with_emphasized_values(&[0.0, 2.0], emphasis);
+
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(x_axis)
+        .with_y_axis(y_axis);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/emphasized-values");
+}
+
+#[test]
+fn axes_emphasized_values_log() {
+    let series = line2(&[1.0, 2.0, 3.0], &[1.0, 10.0, 1000.0]).into();
+    let emphasis =
+        style::theme::Stroke::from(style::theme::Color::from(color::RED)).with_width(1.5);
+    // no grid nor ticks required, and negative values can't be mapped on a log scale
+    let y_axis = des::Axis::new()
+        .with_scale(des::axis::LogScale::default().into())
+        .with_ticks(Default::default())
+        .with_emphasized_values(&[-1.0, 50.0], emphasis);
+
+    let plot = des::Plot::new(vec![series]).with_y_axis(y_axis);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/emphasized-values-log");
+}
+
+#[test]
+fn axes_break() {
+    let series = line2(&[1.0, 2.0, 3.0, 4.0], &[1.0, 3.0, 2.0, 100.0]).into();
+    let y_axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_grid(Default::default())
+        .with_break(4.0, 98.0);
+
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(y_axis);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/break");
+}
+
+#[test]
+fn axes_minor_subdivisions() {
+    let series = line().into();
+    let x_axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_minor_ticks(
+            des::axis::MinorTicks::new().with_subdivisions(des::axis::ticks::Subdivisions::N(10)),
+        );
+    let y_axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_minor_ticks(
+            des::axis::MinorTicks::new().with_subdivisions(des::axis::ticks::Subdivisions::Auto),
+        );
+
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(x_axis)
+        .with_y_axis(y_axis);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/minor-subdivisions");
+}
+
+#[test]
+fn axes_offset_notation() {
+    let series = line2(&[0.0, 400000.0, 800000.0], &[1500.1, 1500.4, 1500.3]).into();
+    let ticks = des::axis::Ticks::new().with_offset_notation(true);
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(des::Axis::new().with_ticks(ticks.clone()))
+        .with_y_axis(des::Axis::new().with_ticks(ticks));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/offset-notation");
+}
+
 #[test]
 fn axes_categories() {
     let x = vec!["a".to_string(), "b".to_string(), "c".to_string()];
@@ -211,6 +494,25 @@ fn axes_categories() {
     assert_fig_eq_ref!(&fig, "axes/categories");
 }
 
+#[test]
+fn axes_categories_ellipsis() {
+    let x = vec![
+        "short".to_string(),
+        "a rather long category".to_string(),
+        "another long category".to_string(),
+    ];
+    let y = vec![1.0, 1.4, 3.0];
+    let series = des::series::Bars::new(x.into(), y.into())
+        .with_fill(color::TRANSPARENT.into())
+        .with_line(Default::default());
+
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/categories-ellipsis");
+}
+
 #[test]
 fn axes_pi_locator() {
     use std::f64::consts::PI;
@@ -354,3 +656,182 @@ fn axes_multiple_trbl_titles() {
 
     assert_fig_eq_ref!(&fig, "axes/multiple-trbl-titles");
 }
+
+fn circle() -> des::series::Line {
+    let theta = (0..=64).map(|i| i as f64 * std::f64::consts::TAU / 64.0);
+    let x: Vec<f64> = theta.clone().map(|t| 10.0 * t.cos()).collect();
+    let y: Vec<f64> = theta.map(|t| 10.0 * t.sin()).collect();
+    line2(&x, &y)
+}
+
+#[test]
+fn axes_aspect_equal() {
+    let axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_grid(Default::default());
+    let plot = des::Plot::new(vec![circle().into()])
+        .with_x_axis(axis.clone())
+        .with_y_axis(axis)
+        .with_aspect(des::plot::AspectMode::Equal);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/aspect-equal");
+}
+
+#[test]
+fn axes_aspect_ratio() {
+    let axis = des::Axis::new()
+        .with_ticks(Default::default())
+        .with_grid(Default::default());
+    let plot = des::Plot::new(vec![circle().into()])
+        .with_x_axis(axis.clone())
+        .with_y_axis(axis)
+        .with_aspect(des::plot::AspectMode::Ratio(0.5));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/aspect-ratio");
+}
+
+fn uniform_hist() -> des::series::Histogram {
+    let data: Vec<f64> = (0..100).map(|i| i as f64 + 0.5).collect();
+    des::series::Histogram::new(des::data_inline(data)).with_bins(5)
+}
+
+#[test]
+fn axes_clip_rounded() {
+    let series = uniform_hist().into();
+    let plot = des::Plot::new(vec![series])
+        .with_insets(Some(des::plot::Insets::Fixed(0.0, 0.0)))
+        .with_clip(des::plot::Clip::RoundedRect(20.0));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/clip-rounded");
+}
+
+#[test]
+fn axes_clip_custom() {
+    let mut pb = geom::PathBuilder::new();
+    pb.move_to(0.0, 200.0);
+    pb.line_to(150.0, 0.0);
+    pb.line_to(300.0, 200.0);
+    pb.close();
+    let triangle = pb.finish().unwrap();
+
+    let series = uniform_hist().into();
+    let plot = des::Plot::new(vec![series])
+        .with_insets(Some(des::plot::Insets::Fixed(0.0, 0.0)))
+        .with_clip(des::plot::Clip::Custom(triangle));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/clip-custom");
+}
+
+#[test]
+fn axes_border_rounded() {
+    let series = line().into();
+    let border = des::plot::BorderBox {
+        radius: 20.0,
+        ..Default::default()
+    };
+    let plot = des::Plot::new(vec![series])
+        .with_fill(plotive::style::theme::Color::from(color::LIGHTGRAY).into())
+        .with_border(border.into());
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/border-rounded");
+}
+
+#[test]
+fn axes_spines_open() {
+    let series = line().into();
+    let plot = des::Plot::new(vec![series]).with_spines(des::plot::SpineConfig::open());
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/spines-open");
+}
+
+#[test]
+fn axes_spines_styled() {
+    let series = line().into();
+    let spines = des::plot::SpineConfig::default()
+        .with_top(des::plot::Spine::HIDDEN)
+        .with_left(des::plot::Spine::VISIBLE.with_width(3.0))
+        .with_bottom(des::plot::Spine::VISIBLE.with_color(color::RED.into()));
+    let plot = des::Plot::new(vec![series])
+        .with_border(Some(des::plot::Border::Axis(
+            plotive::style::theme::Col::Foreground.into(),
+        )))
+        .with_spines(spines);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/spines-styled");
+}
+
+#[test]
+fn axes_spine_offset() {
+    let series = line().into();
+    let plot = des::Plot::new(vec![series])
+        .with_border(Some(des::plot::Border::Axis(
+            plotive::style::theme::Col::Foreground.into(),
+        )))
+        .with_x_axis(
+            des::Axis::new()
+                .with_ticks(Default::default())
+                .with_spine_offset(10.0),
+        )
+        .with_y_axis(
+            des::Axis::new()
+                .with_ticks(Default::default())
+                .with_spine_offset(10.0),
+        );
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/spine-offset");
+}
+
+#[test]
+fn axes_ticks_count_hint() {
+    let series = line().into();
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(des::Axis::new().with_ticks(des::axis::Ticks::new().with_target_count(3)))
+        .with_y_axis(des::Axis::new().with_ticks(des::axis::Ticks::new().with_count_range(8, 12)));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/ticks-count-hint");
+}
+
+#[test]
+fn axes_ticks_positions() {
+    let series = line().into();
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(
+            des::Axis::new().with_ticks(des::axis::Ticks::new().with_labeled_positions(
+                vec![1.2, 2.0, 2.7, 4.0],
+                vec!["start".into(), "event".into(), "end".into()],
+            )),
+        )
+        .with_y_axis(
+            des::Axis::new()
+                .with_ticks(des::axis::Ticks::new().with_positions(vec![1.0, 1.5, 2.25, 3.0])),
+        );
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "axes/ticks-positions");
+}
+
+#[test]
+fn axes_aspect_ratio_invalid() {
+    for ratio in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let plot = des::Plot::new(vec![circle().into()])
+            .with_x_axis(des::Axis::new())
+            .with_y_axis(des::Axis::new())
+            .with_aspect(des::plot::AspectMode::Ratio(ratio));
+        let fig = fig_small(plot);
+
+        let res = fig.prepare(&(), None);
+        assert!(
+            matches!(res, Err(plotive::drawing::Error::InconsistentDesign(_))),
+            "ratio {ratio} should be rejected"
+        );
+    }
+}
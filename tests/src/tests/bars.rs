@@ -0,0 +1,157 @@
+use plotive::{ColorU8, Prepare, des};
+
+use crate::tests::fig_small;
+use crate::{TestHarness, assert_fig_eq_ref};
+
+fn cats() -> Vec<String> {
+    ["a", "b", "c", "d"].iter().map(|s| s.to_string()).collect()
+}
+
+fn vals() -> Vec<f64> {
+    vec![2.0, -1.5, 3.0, 0.1]
+}
+
+#[test]
+fn bars_rounded() {
+    let series = des::series::Bars::new(cats().into(), vals().into())
+        .with_corner_radius(8.0)
+        .with_line(Default::default());
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "bars/rounded");
+}
+
+#[test]
+fn bars_rounded_horizontal() {
+    let series = des::series::Bars::new(vals().into(), cats().into())
+        .with_corner_radius(8.0)
+        .with_bar_width(0.8);
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "bars/rounded-horizontal");
+}
+
+#[test]
+fn bars_gap() {
+    let series = des::series::Bars::new(cats().into(), vals().into()).with_gap(0.1);
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "bars/gap");
+}
+
+#[test]
+fn bars_horizontal() {
+    let series = des::series::Bars::new(vals().into(), cats().into())
+        .with_orientation(des::series::BarsOrientation::Horizontal);
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "bars/horizontal");
+}
+
+#[test]
+fn bars_baseline() {
+    let series = des::series::Bars::new(cats().into(), vals().into())
+        .with_baseline(1.0)
+        .with_corner_radius(4.0);
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "bars/baseline");
+}
+
+#[test]
+fn bars_baseline_horizontal() {
+    let series =
+        des::series::Bars::new(vec![4.0, 6.5, 5.0, 7.5].into(), cats().into()).with_baseline(5.0);
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "bars/baseline-horizontal");
+}
+
+#[test]
+fn bars_sign_colors() {
+    let series = des::series::Bars::new(cats().into(), vals().into())
+        .with_name("Profit")
+        .with_sign_colors(
+            ColorU8::from_html(b"#2e7d32").into(),
+            ColorU8::from_html(b"#c62828").into(),
+        )
+        .with_line(Default::default());
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_legend(des::plot::LegendPos::InTopRight.into());
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "bars/sign-colors");
+}
+
+#[test]
+fn bars_orientation_mismatch() {
+    let series = des::series::Bars::new(cats().into(), vals().into())
+        .with_orientation(des::series::BarsOrientation::Horizontal);
+    let plot = des::Plot::new(vec![series.into()]);
+    let fig = fig_small(plot);
+
+    assert!(fig.prepare(&(), None).is_err());
+}
+
+fn labeled_group() -> des::series::BarsGroup {
+    let cats: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    des::series::BarsGroup::new(
+        cats.into(),
+        vec![
+            des::series::BarSeries::new(vec![1.5, 2.25, 0.75].into())
+                .with_fill(ColorU8::from_html(b"#8ecae6").into()),
+            des::series::BarSeries::new(vec![0.1, 1.25, 2.0].into())
+                .with_fill(ColorU8::from_html(b"#ffb703").into()),
+            des::series::BarSeries::new(vec![2.0, 0.5, 0.05].into())
+                .with_fill(ColorU8::from_html(b"#b5e48c").into()),
+        ],
+    )
+    .with_arrangement(des::series::BarsArrangement::Stack(Default::default()))
+}
+
+#[test]
+fn bars_labels_stack() {
+    // the labels of the thin segments are skipped
+    let series = labeled_group().with_labels(des::series::BarLabels::default().with_min_size(12.0));
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "bars/labels-stack");
+}
+
+#[test]
+fn bars_labels_percent_horizontal() {
+    let series = labeled_group()
+        .with_orientation(des::series::BarsOrientation::Horizontal)
+        .with_labels(des::series::BarLabels::new(
+            des::series::BarLabelContent::PercentOfCategory,
+        ));
+    let plot = des::Plot::new(vec![series.into()])
+        .with_x_axis(des::Axis::new().with_ticks(Default::default()))
+        .with_y_axis(des::Axis::new().with_ticks(Default::default()));
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "bars/labels-percent-horizontal");
+}
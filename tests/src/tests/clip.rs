@@ -0,0 +1,136 @@
+use plotive::render::{self, Surface};
+
+use super::svg_attr;
+use plotive::{ColorU8, geom};
+use plotive_pxl::PxlSurface;
+use plotive_svg::SvgSurface;
+use tiny_skia::{FillRule, Mask};
+
+const WIDTH: u32 = 100;
+const HEIGHT: u32 = 80;
+
+fn triangle() -> geom::Path {
+    let mut pb = geom::PathBuilder::new();
+    pb.move_to(0.0, 60.0);
+    pb.line_to(35.0, 0.0);
+    pb.line_to(70.0, 60.0);
+    pb.close();
+    pb.finish().unwrap()
+}
+
+/// Fill the whole surface in black through a triangular clip
+fn draw_clipped<S: Surface>(surface: &mut S, clip: &geom::Path, transform: &geom::Transform) {
+    surface
+        .prepare(geom::Size::new(WIDTH as f32, HEIGHT as f32))
+        .unwrap();
+    surface
+        .push_clip(&render::Clip {
+            path: clip,
+            transform: Some(transform),
+        })
+        .unwrap();
+    surface
+        .draw_rect(&render::Rect {
+            rect: geom::Rect::from_xywh(0.0, 0.0, WIDTH as f32, HEIGHT as f32),
+            fill: Some(ColorU8::from_html(b"#000000").into()),
+            stroke: None,
+            transform: None,
+        })
+        .unwrap();
+    surface.pop_clip().unwrap();
+}
+
+/// Parse SVG path data made of straight segments only
+fn parse_path_data(data: &str) -> geom::Path {
+    let mut pb = geom::PathBuilder::new();
+    for cmd in data.split_whitespace() {
+        let (verb, args) = cmd.split_at(1);
+        let coords: Vec<f32> = args
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse().unwrap())
+            .collect();
+        match verb {
+            "M" => pb.move_to(coords[0], coords[1]),
+            "L" => pb.line_to(coords[0], coords[1]),
+            "z" | "Z" => pb.close(),
+            _ => panic!("unexpected path command: {cmd}"),
+        }
+    }
+    pb.finish().unwrap()
+}
+
+fn parse_matrix(matrix: &str) -> geom::Transform {
+    let args: Vec<f32> = matrix
+        .trim_start_matches("matrix(")
+        .trim_end_matches(')')
+        .split_whitespace()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    geom::Transform::from_row(args[0], args[1], args[2], args[3], args[4], args[5])
+}
+
+#[test]
+fn clip_triangle_pxl_svg_identical() {
+    let clip = triangle();
+    let transform = geom::Transform::from_translate(15.0, 10.0);
+
+    let mut pxl = PxlSurface::new(WIDTH, HEIGHT).unwrap();
+    draw_clipped(&mut pxl, &clip, &transform);
+    let pixmap = pxl.into_pixmap();
+
+    let mut svg = SvgSurface::new(WIDTH, HEIGHT);
+    draw_clipped(&mut svg, &clip, &transform);
+    let mut svg_bytes = Vec::new();
+    svg.write(&mut svg_bytes).unwrap();
+    let svg_str = String::from_utf8(svg_bytes).unwrap();
+
+    // rasterize the clip emitted in the SVG document the way the pixel backend does
+    let clip_elem = &svg_str[svg_str.find("<clipPath").unwrap()..];
+    let svg_clip = parse_path_data(svg_attr(clip_elem, "<path", "d").unwrap());
+    let svg_transform = parse_matrix(svg_attr(clip_elem, "<path", "transform").unwrap());
+    let mut svg_mask = Mask::new(WIDTH, HEIGHT).unwrap();
+    svg_mask.fill_path(&svg_clip, FillRule::Winding, true, svg_transform);
+
+    let pxl_alpha = pixmap.pixels().iter().map(|p| p.alpha());
+    let mismatches = pxl_alpha
+        .zip(svg_mask.data().iter())
+        .filter(|(a, m)| a.abs_diff(**m) > 1)
+        .count();
+    assert_eq!(mismatches, 0);
+
+    // the clip actually masks: inside the triangle is painted, its bounding box corners are not
+    assert_eq!(pixmap.pixel(50, 45).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(17, 12).unwrap().alpha(), 0);
+    assert_eq!(pixmap.pixel(83, 12).unwrap().alpha(), 0);
+}
+
+#[test]
+fn clip_unbalanced_stack() {
+    let clip = triangle();
+    let clip = render::Clip {
+        path: &clip,
+        transform: None,
+    };
+
+    let mut pxl = PxlSurface::new(WIDTH, HEIGHT).unwrap();
+    assert_eq!(pxl.pop_clip(), Err(render::Error::UnbalancedClipStack));
+    pxl.push_clip(&clip).unwrap();
+    assert!(matches!(
+        pxl.push_clip(&clip),
+        Err(render::Error::Unsupported(_))
+    ));
+    assert_eq!(pxl.pop_clip(), Ok(()));
+
+    let mut svg = SvgSurface::new(WIDTH, HEIGHT);
+    assert_eq!(svg.pop_clip(), Err(render::Error::UnbalancedClipStack));
+    svg.push_clip(&clip).unwrap();
+    assert!(matches!(
+        svg.write(&mut Vec::new()),
+        Err(plotive_svg::Error::Render(
+            render::Error::UnbalancedClipStack
+        ))
+    ));
+    svg.pop_clip().unwrap();
+    assert!(svg.write(&mut Vec::new()).is_ok());
+}
@@ -0,0 +1,69 @@
+use plotive::render::{self, Surface};
+use plotive::{ColorU8, geom};
+use plotive_pxl::{Cvd, PxlSurface};
+
+const SIZE: u32 = 4;
+
+/// Draw the given colors as vertical strips, simulate `cvd` and return the resulting colors
+fn simulate(colors: &[ColorU8], cvd: Cvd) -> Vec<[u8; 4]> {
+    let width = SIZE * colors.len() as u32;
+    let mut pxl = PxlSurface::new(width, SIZE).unwrap();
+    pxl.prepare(geom::Size::new(width as f32, SIZE as f32))
+        .unwrap();
+    for (i, color) in colors.iter().enumerate() {
+        pxl.draw_rect(&render::Rect {
+            rect: geom::Rect::from_xywh((i as u32 * SIZE) as f32, 0.0, SIZE as f32, SIZE as f32),
+            fill: Some((*color).into()),
+            stroke: None,
+            transform: None,
+        })
+        .unwrap();
+    }
+    pxl.simulate_cvd(cvd);
+
+    let pixmap = pxl.into_pixmap();
+    (0..colors.len())
+        .map(|i| {
+            let px = pixmap
+                .pixel(i as u32 * SIZE + SIZE / 2, SIZE / 2)
+                .unwrap()
+                .demultiply();
+            [px.red(), px.green(), px.blue(), px.alpha()]
+        })
+        .collect()
+}
+
+fn distance(a: [u8; 4], b: [u8; 4]) -> i32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| (*a as i32 - *b as i32).abs())
+        .sum()
+}
+
+#[test]
+fn cvd_neutral_colors() {
+    let colors = [
+        ColorU8::from_rgb(0, 0, 0),
+        ColorU8::from_rgb(255, 255, 255),
+        ColorU8::from_rgba(0, 0, 0, 0),
+    ];
+    for cvd in [Cvd::Protanopia, Cvd::Deuteranopia, Cvd::Tritanopia] {
+        let sim = simulate(&colors, cvd);
+        assert_eq!(sim[0], [0, 0, 0, 255]);
+        assert!(distance(sim[1], [255, 255, 255, 255]) <= 3);
+        assert_eq!(sim[2][3], 0);
+    }
+}
+
+#[test]
+fn cvd_red_green_confusion() {
+    let red = ColorU8::from_html(b"#d62728");
+    let green = ColorU8::from_html(b"#2ca02c");
+    let deut = simulate(&[red, green], Cvd::Deuteranopia);
+    let prot = simulate(&[red, green], Cvd::Protanopia);
+
+    // red and green are much closer to each other for red-green deficiencies
+    let orig_dist = distance(red.rgba(), green.rgba());
+    assert!(distance(deut[0], deut[1]) < orig_dist / 2);
+    assert!(distance(prot[0], prot[1]) < orig_dist / 2);
+}
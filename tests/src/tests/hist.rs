@@ -0,0 +1,51 @@
+use plotive::{ColorU8, des, style};
+
+use crate::tests::fig_small;
+use crate::{TestHarness, assert_fig_eq_ref};
+
+/// Points along the curve y = x^2 with a deterministic spread,
+/// denser at the bottom left corner
+fn hist2d_data() -> (Vec<f64>, Vec<f64>) {
+    let mut x = Vec::new();
+    let mut y = Vec::new();
+    for i in 0..40 {
+        for j in 0..(40 - i) {
+            let t = i as f64 / 40.0;
+            let d = (j as f64 / 40.0 - 0.5) * 0.4;
+            x.push(t + d);
+            y.push(t * t - d);
+        }
+    }
+    (x, y)
+}
+
+#[test]
+fn hist2d_linear() {
+    let (x, y) = hist2d_data();
+    let series = des::series::Hist2d::new(des::data_inline(x), des::data_inline(y))
+        .with_bins(12, 10)
+        .into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "hist/hist2d-linear");
+}
+
+#[test]
+fn hist2d_log_custom_colormap() {
+    let (x, y) = hist2d_data();
+    let colormap = style::series::ColorMap::Custom(vec![
+        ColorU8::from_html(b"#ffffff"),
+        ColorU8::from_html(b"#1f77b4"),
+        ColorU8::from_html(b"#000000"),
+    ]);
+    let series = des::series::Hist2d::new(des::data_inline(x), des::data_inline(y))
+        .with_bins(12, 10)
+        .with_norm(des::series::Hist2dNorm::Log)
+        .with_colormap(colormap)
+        .into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "hist/hist2d-log-custom-colormap");
+}
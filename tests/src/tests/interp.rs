@@ -58,3 +58,70 @@ fn interp_spline() {
 
     assert_fig_eq_ref!(&fig, "interp/spline");
 }
+
+#[test]
+fn interp_smooth_moving_average() {
+    let plot = line()
+        .with_smoothing(des::series::Smoothing::MovingAverage(3))
+        .into_plot();
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "interp/smooth-moving-average");
+}
+
+#[test]
+fn interp_smooth_only_ewma() {
+    let plot = line()
+        .with_smoothing(des::series::Smoothing::Ewma(0.5))
+        .with_smoothing_only()
+        .into_plot();
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "interp/smooth-only-ewma");
+}
+
+fn line_with_nulls() -> des::series::Line {
+    let x = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+    let y = vec![Some(0), Some(2), None, None, Some(1), Some(4), Some(3)];
+    des::series::Line::new(des::data_inline(x), des::data_inline(y))
+}
+
+#[test]
+fn interp_nulls_gap() {
+    let plot = line_with_nulls().into_plot();
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "interp/nulls-gap");
+}
+
+#[test]
+fn interp_nulls_connected() {
+    let plot = line_with_nulls().with_connect_nulls(true).into_plot();
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "interp/nulls-connected");
+}
+
+#[test]
+fn interp_nulls_connected_step_middle() {
+    let plot = line_with_nulls()
+        .with_connect_nulls(true)
+        .with_interpolation(des::series::Interpolation::StepMiddle)
+        .into_plot();
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "interp/nulls-connected-step-middle");
+}
+
+#[test]
+fn interp_from_y() {
+    let y = vec![0.0, 2.0, 3.0, 1.0, 4.0, 4.0];
+    let line = des::series::Line::from_y(des::data_inline(y));
+    let x = vec![0.0, 2.5, 5.0];
+    let scatter =
+        des::series::Scatter::new(des::data_inline(x), des::data_inline(vec![1.0, 2.0, 3.0]));
+    let plot = des::Plot::new(vec![line.into(), scatter.into()]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "interp/from-y");
+}
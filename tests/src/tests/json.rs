@@ -0,0 +1,148 @@
+use plotive::{des, geom, style};
+
+use crate::tests::{fig_small, line};
+use crate::{TestHarness, assert_fig_eq_ref};
+
+/// Serialize the figure to JSON, deserialize it back,
+/// and check that serializing again gives the same JSON
+fn round_trip(fig: &des::Figure) -> des::Figure {
+    let json = serde_json::to_string(fig).unwrap();
+    let fig: des::Figure = serde_json::from_str(&json).unwrap();
+    assert_eq!(serde_json::to_string(&fig).unwrap(), json);
+    fig
+}
+
+fn round_trip_series(series: des::Series) {
+    let fig = fig_small(des::Plot::new(vec![series]));
+    round_trip(&fig);
+}
+
+#[test]
+fn json_line() {
+    let series = line()
+        .with_name("line")
+        .with_interpolation(des::series::Interpolation::StepMiddle)
+        .with_smoothing(des::series::Smoothing::MovingAverage(3))
+        .into();
+    round_trip_series(series);
+}
+
+#[test]
+fn json_scatter() {
+    let series = des::series::Scatter::new(
+        des::data_src_ref("x"),
+        des::data_inline(vec![1.0, f64::NAN, 3.0]),
+    )
+    .with_size_column(des::data_src_ref("size"))
+    .with_colormap(style::series::ColorMap::Viridis)
+    .with_trendline(des::series::Fit::Polynomial(2))
+    .into();
+    round_trip_series(series);
+}
+
+#[test]
+fn json_histogram() {
+    let series = des::series::Histogram::new(des::data_inline(vec![1.0, 2.0, 2.0, 3.0]))
+        .with_bins(3)
+        .with_cumulative(des::series::Cumulative::Forward)
+        .into();
+    round_trip_series(series);
+}
+
+#[test]
+fn json_hist2d() {
+    let series = des::series::Hist2d::new(des::data_src_ref("x"), des::data_src_ref("y"))
+        .with_bin_specs(
+            des::series::BinSpec::Width(0.5),
+            des::series::BinSpec::FreedmanDiaconis,
+        )
+        .with_norm(des::series::Hist2dNorm::Log)
+        .into();
+    round_trip_series(series);
+}
+
+#[test]
+fn json_areas_group() {
+    let x = || des::data_inline(vec![0.0, 1.0, 2.0]);
+    let series = des::series::AreasGroup::new(vec![
+        des::series::AreaSeries::new(x(), des::data_inline(vec![1.0, 2.0, 3.0])).with_name("a"),
+        des::series::AreaSeries::new(des::data_src_ref("x"), des::data_src_ref("b")).with_name("b"),
+    ])
+    .with_baseline(des::series::AreasBaseline::Wiggle)
+    .with_interpolate_x(true)
+    .into();
+    round_trip_series(series);
+}
+
+#[test]
+fn json_bars() {
+    let series = des::series::Bars::new(
+        des::data_inline(vec!["a".to_string(), "b".to_string()]),
+        des::data_inline(vec![1.0, 2.0]),
+    )
+    .with_name("bars")
+    .into();
+    round_trip_series(series);
+}
+
+#[test]
+fn json_bars_group() {
+    let series = des::series::BarsGroup::new(
+        des::data_src_ref("categories"),
+        vec![
+            des::series::BarSeries::new(des::data_src_ref("a")).with_name("a"),
+            des::series::BarSeries::new(des::data_src_ref("b")).with_name("b"),
+        ],
+    )
+    .with_orientation(des::series::BarsOrientation::Horizontal)
+    .into();
+    round_trip_series(series);
+}
+
+#[test]
+fn json_data_col() {
+    let src_ref = serde_json::to_value(des::data_src_ref("time")).unwrap();
+    assert_eq!(src_ref, serde_json::json!("time"));
+
+    let inline = serde_json::to_value(des::data_inline(vec![1.0, f64::NAN])).unwrap();
+    assert_eq!(inline, serde_json::json!({"F64": [1.0, null]}));
+
+    let col: des::DataCol = serde_json::from_value(inline).unwrap();
+    match col {
+        des::DataCol::Inline(plotive::data::VecColumn::F64(vals)) => {
+            assert_eq!(vals[0], 1.0);
+            assert!(vals[1].is_nan());
+        }
+        _ => panic!("expected inline f64 column"),
+    }
+}
+
+#[test]
+fn json_clip_path() {
+    let mut pb = geom::PathBuilder::new();
+    pb.move_to(0.0, 60.0);
+    pb.quad_to(20.0, 0.0, 35.0, 0.0);
+    pb.cubic_to(50.0, 0.0, 70.0, 30.0, 70.0, 60.0);
+    pb.close();
+    let path = pb.finish().unwrap();
+
+    let plot = des::Plot::new(vec![line().into()]).with_clip(des::plot::Clip::Custom(path));
+    let fig = round_trip(&fig_small(plot));
+    let des::plot::Clip::Custom(path) = fig.plots().iter().flatten().next().unwrap().clip() else {
+        panic!("expected custom clip");
+    };
+    assert_eq!(path.len(), 4);
+}
+
+#[test]
+fn json_render() {
+    let x = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    let y = vec![1.2, 1.9, 3.4, 3.8, 5.5, 7.1, 9.8, 13.0];
+    let series = des::series::Scatter::new(des::data_inline(x), des::data_inline(y))
+        .with_trendline(des::series::Fit::Linear)
+        .into();
+    let fig = fig_small(des::Plot::new(vec![series]));
+    let fig = round_trip(&fig);
+
+    assert_fig_eq_ref!(&fig, "scatter/trendline-linear");
+}
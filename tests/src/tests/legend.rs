@@ -1,6 +1,6 @@
-use plotive::des;
+use plotive::{ColorU8, des, style, text};
 
-use super::{fig_small, line};
+use super::{fig_small, line, line2};
 use crate::{TestHarness, assert_fig_eq_ref};
 
 #[test]
@@ -119,3 +119,142 @@ fn legend_pos_in_left() {
 
     assert_fig_eq_ref!(&fig, "legend-pos/in_left");
 }
+
+fn manual_entries_legend() -> des::PlotLegend {
+    let dash = style::series::Stroke::from(ColorU8::from_html(b"#808080"))
+        .with_pattern(style::Dash::default().into());
+    let region = style::series::Fill::from(ColorU8::from_html(b"#c0c0c0"));
+    des::PlotLegend::new(des::plot::LegendPos::InTopLeft)
+        .with_entry(des::legend::LegendEntry::new(
+            "reference",
+            des::legend::SampleStyle::Line(dash),
+        ))
+        .with_entry(des::legend::LegendEntry::new(
+            "region",
+            des::legend::SampleStyle::Rect(region, None),
+        ))
+}
+
+#[test]
+fn legend_entries_after() {
+    let series = line().with_name("line").into();
+    let plot = des::Plot::new(vec![series]).with_legend(manual_entries_legend());
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "legend-entries/after");
+}
+
+#[test]
+fn legend_entries_before() {
+    let series = line().with_name("line").into();
+    let legend = manual_entries_legend().with_entry_order(des::legend::EntryOrder::BeforeSeries);
+    let plot = des::Plot::new(vec![series]).with_legend(legend);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "legend-entries/before");
+}
+
+fn named_lines(names: &[&str]) -> Vec<des::Series> {
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let y = i as f64;
+            line2(&[1.0, 2.0, 3.0], &[y, y + 1.0, y + 2.0])
+                .with_name(*name)
+                .into()
+        })
+        .collect()
+}
+
+#[test]
+fn legend_columns() {
+    let series = named_lines(&["a", "long name", "b", "c", "another long name"]);
+    let legend = des::PlotLegend::new(des::plot::LegendPos::OutBottom).with_columns(3);
+    let plot = des::Plot::new(series).with_legend(legend);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "legend-layout/columns");
+}
+
+#[test]
+fn legend_max_rows() {
+    let series = named_lines(&["a", "long name", "b", "c", "another long name"]);
+    let legend = des::PlotLegend::new(des::plot::LegendPos::InTopLeft).with_max_rows(2);
+    let plot = des::Plot::new(series).with_legend(legend);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "legend-layout/max_rows");
+}
+
+#[test]
+fn legend_rounded() {
+    let series = line().with_name("line").into();
+    let legend = des::PlotLegend::new(des::plot::LegendPos::InTopLeft).with_radius(6.0);
+    let plot = des::Plot::new(vec![series]).with_legend(legend);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "legend-layout/rounded");
+}
+
+#[test]
+fn legend_shadow() {
+    let series = line().with_name("line").into();
+    let legend = des::PlotLegend::new(des::plot::LegendPos::InTopLeft)
+        .with_fill(Some(
+            style::theme::Color::from(ColorU8::from_html(b"#ffffff")).into(),
+        ))
+        .with_radius(6.0)
+        .with_shadow(Some(Default::default()));
+    let plot = des::Plot::new(vec![series]).with_legend(legend);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "legend-layout/shadow");
+}
+
+const LONG_NAME: &str = "a series with a name much too long to fit in the width of the figure";
+
+#[test]
+fn legend_wrap() {
+    let series = named_lines(&["a", LONG_NAME]);
+    let legend = des::PlotLegend::new(des::plot::LegendPos::OutBottom);
+    let plot = des::Plot::new(series).with_legend(legend);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "legend-layout/wrap");
+}
+
+#[test]
+fn legend_ellipsis() {
+    let series = named_lines(&["a", LONG_NAME]);
+    let legend = des::PlotLegend::new(des::plot::LegendPos::OutBottom)
+        .with_label_overflow(text::rich::Overflow::Ellipsis);
+    let plot = des::Plot::new(series).with_legend(legend);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "legend-layout/ellipsis");
+}
+
+fn hidden_series_plot() -> des::Plot {
+    let shown = line().with_name("shown").into();
+    let hidden = line2(&[1.0, 2.0, 3.0], &[3.0, 5.0, 6.0])
+        .with_name("hidden")
+        .with_visible(false)
+        .into();
+    des::Plot::new(vec![shown, hidden]).with_legend(des::plot::LegendPos::InTopLeft.into())
+}
+
+#[test]
+fn legend_hidden_series() {
+    let fig = fig_small(hidden_series_plot());
+
+    assert_fig_eq_ref!(&fig, "legend-hidden/bounds");
+}
+
+#[test]
+fn legend_hidden_series_no_bounds() {
+    let plot = hidden_series_plot().with_hidden_series_bounds(false);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "legend-hidden/no-bounds");
+}
@@ -0,0 +1,81 @@
+use plotive::render::{self, Surface};
+
+use super::svg_attr;
+use plotive::{ColorU8, geom};
+use plotive_pxl::PxlSurface;
+use plotive_svg::SvgSurface;
+
+const WIDTH: u32 = 40;
+const HEIGHT: u32 = 40;
+
+/// Draw a filled square, framed by a thick stroke, both faded by `opacity`
+fn draw_faded<S: Surface>(surface: &mut S, color: ColorU8, opacity: f32) {
+    surface
+        .prepare(geom::Size::new(WIDTH as f32, HEIGHT as f32))
+        .unwrap();
+    surface
+        .draw_rect(&render::Rect {
+            rect: geom::Rect::from_xywh(10.0, 10.0, 20.0, 20.0),
+            fill: Some(render::Paint::Solid {
+                color,
+                opacity: Some(opacity),
+            }),
+            stroke: Some(render::Stroke {
+                color,
+                width: 8.0,
+                pattern: render::LinePattern::Solid,
+                opacity: Some(opacity),
+            }),
+            transform: None,
+        })
+        .unwrap();
+}
+
+#[test]
+fn opacity_multiplies_color_alpha() {
+    let color = ColorU8::from_rgba(0, 0, 255, 128);
+    let paint = render::Paint::Solid {
+        color,
+        opacity: Some(0.5),
+    };
+    assert!((paint.opacity() - 128.0 / 255.0 * 0.5).abs() < 1e-6);
+
+    let paint: render::Paint = color.into();
+    assert!((paint.opacity() - 128.0 / 255.0).abs() < 1e-6);
+}
+
+#[test]
+fn opacity_pxl_svg_identical() {
+    let color = ColorU8::from_rgba(0, 0, 255, 192);
+    let opacity = 0.5;
+
+    let mut pxl = PxlSurface::new(WIDTH, HEIGHT).unwrap();
+    draw_faded(&mut pxl, color, opacity);
+    let pixmap = pxl.into_pixmap();
+
+    let mut svg = SvgSurface::new(WIDTH, HEIGHT);
+    draw_faded(&mut svg, color, opacity);
+    let mut svg_bytes = Vec::new();
+    svg.write(&mut svg_bytes).unwrap();
+    let svg_str = String::from_utf8(svg_bytes).unwrap();
+
+    let svg_fill: f32 = svg_attr(&svg_str, "<rect", "fill-opacity")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let svg_stroke: f32 = svg_attr(&svg_str, "<rect", "stroke-opacity")
+        .unwrap()
+        .parse()
+        .unwrap();
+    let expected = 192.0 / 255.0 * opacity;
+    assert!((svg_fill - expected).abs() < 1e-6);
+    assert!((svg_stroke - expected).abs() < 1e-6);
+
+    // the fill alone, inside the stroke
+    let pxl_fill = pixmap.pixel(20, 20).unwrap().alpha() as f32 / 255.0;
+    assert!((pxl_fill - svg_fill).abs() <= 1.0 / 255.0);
+
+    // the stroke alone, outside the fill
+    let pxl_stroke = pixmap.pixel(7, 20).unwrap().alpha() as f32 / 255.0;
+    assert!((pxl_stroke - svg_stroke).abs() <= 1.0 / 255.0);
+}
@@ -0,0 +1,54 @@
+use plotive::{Prepare, geom, render};
+use plotive_pxl::ToPixmap;
+use plotive_svg::SaveSvg;
+
+use super::{fig_small, line, svg_attr};
+
+#[test]
+fn physical_size_pxl() {
+    // 400x300 figure, at the 4:3 aspect ratio of 80x60 mm
+    let fig = fig_small(plotive::des::Plot::new(vec![line().into()]));
+    let params = plotive_pxl::Params {
+        physical_size: Some(render::PhysicalSize::new(80.0, 60.0, 254.0)),
+        ..Default::default()
+    };
+    let pixmap = fig.to_pixmap(&(), params).unwrap();
+    assert_eq!((pixmap.width(), pixmap.height()), (800, 600));
+}
+
+#[test]
+fn physical_size_pxl_centered() {
+    let fig = fig_small(plotive::des::Plot::new(vec![line().into()]));
+    let params = plotive_pxl::Params {
+        physical_size: Some(render::PhysicalSize::new(80.0, 80.0, 254.0)),
+        ..Default::default()
+    };
+    let pixmap = fig.to_pixmap(&(), params).unwrap();
+    assert_eq!((pixmap.width(), pixmap.height()), (800, 800));
+
+    // the figure is 800x600 pixels, with transparent margins of 100 pixels above and below
+    let alpha = |x: u32, y: u32| pixmap.pixel(x, y).unwrap().alpha();
+    assert_eq!(alpha(400, 50), 0);
+    assert_eq!(alpha(400, 750), 0);
+    assert_eq!(alpha(5, 105), 255);
+    assert_eq!(alpha(5, 695), 255);
+}
+
+#[test]
+fn physical_size_svg() {
+    let fig = fig_small(plotive::des::Plot::new(vec![line().into()]));
+    let prepared = fig.prepare(&(), None).unwrap();
+    let params = plotive_svg::Params {
+        physical_size: Some(render::PhysicalSize::a4(300.0).landscape()),
+        ..Default::default()
+    };
+    let path = std::env::temp_dir().join("plotive-physical-size.svg");
+    prepared.save_svg(&path, &(), params).unwrap();
+    let svg = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(svg_attr(&svg, "<svg", "width"), Some("297mm"));
+    assert_eq!(svg_attr(&svg, "<svg", "height"), Some("210mm"));
+    assert_eq!(svg_attr(&svg, "<svg", "viewBox"), Some("0 0 400 300"));
+    assert_eq!(prepared.size(), geom::Size::new(400.0, 300.0));
+}
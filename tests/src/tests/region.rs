@@ -0,0 +1,58 @@
+use plotive::render::{self, Surface};
+use plotive::{ColorU8, Prepare, geom};
+use plotive_pxl::PxlSurface;
+use plotive_svg::SvgSurface;
+
+use super::{fig_small, line, svg_attr};
+
+const RED: ColorU8 = ColorU8::from_rgb(255, 0, 0);
+
+/// Index of the pixel at (x, y) in the pixmap data of a figure of 400 pixels width
+fn px_idx(x: u32, y: u32) -> usize {
+    4 * (y * 400 + x) as usize
+}
+
+#[test]
+fn region_fill_pxl() {
+    let fig = fig_small(plotive::des::Plot::new(vec![line().into()]));
+    let prepared = fig.prepare(&(), None).unwrap();
+    let style = crate::bw_theme();
+    let region = geom::Rect::from_xywh(100.0, 100.0, 50.0, 50.0);
+
+    let mut full = PxlSurface::new(400, 300).unwrap();
+    prepared.draw(&mut full, &style).unwrap();
+    let full = full.into_pixmap();
+
+    let mut partial = PxlSurface::new(400, 300).unwrap();
+    partial.prepare(prepared.size()).unwrap();
+    partial.fill(RED.into()).unwrap();
+    // twice, to also draw with the reused region mask
+    prepared.draw_region(&mut partial, &style, &region).unwrap();
+    prepared.draw_region(&mut partial, &style, &region).unwrap();
+    let partial = partial.into_pixmap();
+
+    let inside = px_idx(125, 125);
+    assert_eq!(
+        &partial.data()[inside..inside + 4],
+        &full.data()[inside..inside + 4]
+    );
+    for (x, y) in [(50, 50), (99, 125), (150, 125), (125, 150)] {
+        let outside = px_idx(x, y);
+        assert_eq!(&partial.data()[outside..outside + 4], &[255, 0, 0, 255]);
+    }
+}
+
+#[test]
+fn region_fill_default() {
+    let region = geom::Rect::from_xywh(10.0, 20.0, 30.0, 40.0);
+    let mut svg = SvgSurface::new(100, 100);
+    svg.prepare(geom::Size::new(100.0, 100.0)).unwrap();
+    svg.fill_region(render::Paint::from(RED), Some(&region))
+        .unwrap();
+    let mut svg_bytes = Vec::new();
+    svg.write(&mut svg_bytes).unwrap();
+    let svg_str = String::from_utf8(svg_bytes).unwrap();
+
+    assert_eq!(svg_attr(&svg_str, "<rect", "width"), Some("30"));
+    assert_eq!(svg_attr(&svg_str, "<rect", "height"), Some("40"));
+}
@@ -0,0 +1,231 @@
+use plotive::{ColorU8, Prepare, des, style};
+
+use crate::tests::fig_small;
+use crate::{TestHarness, assert_fig_eq_ref};
+
+fn scatter() -> des::series::Scatter {
+    let x = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+    let y = vec![1.2, 1.9, 3.4, 3.8, 5.5, 7.1, 9.8, 13.0];
+    des::series::Scatter::new(des::data_inline(x), des::data_inline(y))
+}
+
+#[test]
+fn scatter_trendline_linear() {
+    let series = scatter().with_trendline(des::series::Fit::Linear).into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/trendline-linear");
+}
+
+#[test]
+fn scatter_trendline_polynomial() {
+    let series = scatter()
+        .with_trendline(des::series::Fit::Polynomial(2))
+        .into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/trendline-polynomial");
+}
+
+#[test]
+fn scatter_trendline_log_linear() {
+    let series = scatter().with_trendline(des::series::Fit::LogLinear).into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/trendline-log-linear");
+}
+
+#[test]
+fn scatter_trendline_degenerate() {
+    let x = vec![1.0, 1.0, 1.0];
+    let y = vec![1.0, 2.0, 3.0];
+    let series = des::series::Scatter::new(des::data_inline(x), des::data_inline(y))
+        .with_trendline(des::series::Fit::Linear)
+        .into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/trendline-degenerate");
+}
+
+#[test]
+fn scatter_trendline_log_y() {
+    // the linear fit crosses zero at x ~= 1.6, only its positive part is drawn
+    let x = vec![1.0, 2.0, 3.0, 4.0];
+    let y = vec![1.0, 10.0, 100.0, 1000.0];
+    let series = des::series::Scatter::new(des::data_inline(x), des::data_inline(y))
+        .with_trendline(des::series::Fit::Linear)
+        .into();
+    let plot = des::Plot::new(vec![series])
+        .with_y_axis(des::Axis::new().with_scale(des::axis::LogScale::default().into()));
+    let fig = fig_small(plot);
+
+    let prepared = fig.prepare(&(), None).unwrap();
+    let geometry = prepared.series_geometry((0, 0), 0).unwrap();
+    let first = geometry.points.unwrap()[0];
+    let paths = geometry.paths;
+    assert_eq!(paths.len(), 1);
+    assert!(paths[0].bounds().left() > first.x);
+
+    assert_fig_eq_ref!(&fig, "scatter/trendline-log-y");
+}
+
+#[test]
+fn scatter_bubble() {
+    let size = vec![1.0, 4.0, 2.0, 8.0, 3.0, 6.0, 5.0, 7.0];
+    let color = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+    let series = scatter()
+        .with_size_column(des::data_inline(size))
+        .with_color_column(des::data_inline(color))
+        .into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/bubble");
+}
+
+#[test]
+fn scatter_bubble_nulls() {
+    // NaN values are null values
+    let size = vec![1.0, f64::NAN, 2.0, 8.0];
+    let color = vec![0.0, 1.0, f64::NAN, 3.0];
+    let series = des::series::Scatter::new(
+        des::data_inline(vec![1.0, 2.0, 3.0, 4.0]),
+        des::data_inline(vec![1.0, 2.0, 3.0, 4.0]),
+    )
+    .with_size_column(des::data_inline(size))
+    .with_color_column(des::data_inline(color))
+    .into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/bubble-nulls");
+}
+
+#[test]
+fn scatter_color_hollow() {
+    // the colors of hollow markers apply to their edge
+    let marker = style::series::Marker {
+        size: 14.0.into(),
+        fill: None,
+        stroke: Some(style::series::Stroke::from(ColorU8::from_html(b"#000000")).with_width(2.0)),
+        ..Default::default()
+    };
+    let color = vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, f64::NAN];
+    let series = scatter()
+        .with_marker(marker)
+        .with_color_column(des::data_inline(color))
+        .into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/color-hollow");
+}
+
+#[test]
+fn scatter_marker_edge() {
+    let marker = style::series::Marker {
+        size: 30.0.into(),
+        ..Default::default()
+    }
+    .with_edge(style::series::Stroke::from(ColorU8::from_html(b"#ffffff")).with_width(2.0));
+    let series = des::series::Scatter::new(
+        des::data_inline(vec![1.0, 1.1, 1.2, 3.0]),
+        des::data_inline(vec![1.0, 1.1, 1.2, 3.0]),
+    )
+    .with_marker(marker)
+    .into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/marker-edge");
+}
+
+#[test]
+fn scatter_alpha() {
+    let marker = style::series::Marker {
+        size: 30.0.into(),
+        ..Default::default()
+    }
+    .with_edge(style::series::Stroke::from(ColorU8::from_html(b"#000000")).with_width(2.0));
+    let series = des::series::Scatter::new(
+        des::data_inline(vec![1.0, 1.1, 1.2, 3.0]),
+        des::data_inline(vec![1.0, 1.1, 1.2, 3.0]),
+    )
+    .with_name("Faded")
+    .with_marker(marker)
+    .with_alpha(0.3)
+    .into();
+    let plot = des::Plot::new(vec![series]).with_legend(Default::default());
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/alpha");
+}
+
+fn strip() -> des::series::Scatter {
+    let cats = ["a", "b", "c"];
+    let x: Vec<String> = (0..60).map(|i| cats[i % 3].to_string()).collect();
+    let y: Vec<f64> = (0..60)
+        .map(|i| (i % 3) as f64 + (i as f64 * 1.7).sin() * 1.5)
+        .collect();
+    des::series::Scatter::new(des::data_inline(x), des::data_inline(y))
+}
+
+#[test]
+fn scatter_jitter() {
+    let series = strip().with_jitter(0.6, 42).into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/jitter");
+}
+
+#[test]
+fn scatter_swarm() {
+    let series = strip().with_swarm().into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/swarm");
+}
+
+#[test]
+fn scatter_spread_log_axis() {
+    // the category of x = 0.4 extends to x <= 0 on the log axis: no spread for these points
+    let x = vec![0.4, 0.4, 0.4, 5.0, 5.0, 5.0];
+    let y = vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0];
+    let log_x = || {
+        let range = des::axis::Range(Some(0.1), Some(20.0));
+        des::Axis::new().with_scale(des::axis::LogScale::new(10.0, range).into())
+    };
+    for series in [
+        des::series::Scatter::new(des::data_inline(x.clone()), des::data_inline(y.clone()))
+            .with_swarm(),
+        des::series::Scatter::new(des::data_inline(x.clone()), des::data_inline(y.clone()))
+            .with_jitter(0.8, 7),
+    ] {
+        let plot = des::Plot::new(vec![series.into()]).with_x_axis(log_x());
+        let prepared = fig_small(plot).prepare(&(), None).unwrap();
+        let points = prepared.series_geometry((0, 0), 0).unwrap().points.unwrap();
+        assert!(points.iter().all(|p| p.x.is_finite() && p.y.is_finite()));
+        assert!(points[..3].iter().all(|p| p.x == points[0].x));
+        assert_ne!(points[3].x, points[4].x);
+    }
+}
+
+#[test]
+fn scatter_jitter_num_axis() {
+    // categories encoded as integers: the x bounds expand to contain the spread
+    let x: Vec<f64> = (0..30).map(|i| (i % 3) as f64).collect();
+    let y: Vec<f64> = (0..30).map(|i| ((i * 7) % 11) as f64).collect();
+    let series = des::series::Scatter::new(des::data_inline(x), des::data_inline(y))
+        .with_jitter(0.8, 7)
+        .into();
+    let plot = des::Plot::new(vec![series]);
+    let fig = fig_small(plot);
+
+    assert_fig_eq_ref!(&fig, "scatter/jitter-num-axis");
+}
@@ -1,6 +1,6 @@
-use plotive::des;
+use plotive::{des, geom};
 
-use super::{fig_high, fig_wide, line};
+use super::{fig_high, fig_mid, fig_wide, line, line2};
 use crate::{TestHarness, assert_fig_eq_ref};
 
 #[test]
@@ -28,6 +28,18 @@ fn subplots_space10() {
     assert_fig_eq_ref!(&fig, "subplots/space10");
 }
 
+#[test]
+fn subplots_padding() {
+    let plot1 = des::Plot::new(vec![line().into()]).with_padding((10.0, 40.0).into());
+    let plot2 = des::Plot::new(vec![line().into()]);
+    let subplots = des::Subplots::new(2, 1)
+        .with_plot((0, 0), plot1)
+        .with_plot((1, 0), plot2);
+
+    let fig = fig_high(subplots).with_padding(40.0.into());
+    assert_fig_eq_ref!(&fig, "subplots/padding");
+}
+
 #[test]
 fn subplots_sharedx() {
     let plot1 = des::Plot::new(vec![line().into()]).with_x_axis(
@@ -68,3 +80,164 @@ fn subplots_sharedy() {
     let fig = fig_wide(subplots);
     assert_fig_eq_ref!(&fig, "subplots/sharedy");
 }
+
+#[test]
+fn subplots_fig_legend() {
+    let plot = || {
+        des::Plot::new(vec![
+            line().with_name("shared").into(),
+            line2(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0])
+                .with_name("other")
+                .into(),
+        ])
+        .with_legend(des::plot::LegendPos::InTopLeft.into())
+    };
+    let subplots = des::Subplots::new(2, 1)
+        .with_plot((0, 0), plot())
+        .with_plot((1, 0), plot());
+
+    let fig = fig_high(subplots)
+        .with_legend(des::figure::LegendPos::Bottom.into())
+        .with_plot_legends(false);
+    assert_fig_eq_ref!(&fig, "subplots/fig_legend");
+}
+
+#[test]
+fn subplots_ratios() {
+    let subplots = des::Subplots::new(2, 2)
+        .with_plot((0, 0), des::Plot::new(vec![line().into()]))
+        .with_plot((0, 1), des::Plot::new(vec![line().into()]))
+        .with_plot((1, 0), des::Plot::new(vec![line().into()]))
+        .with_plot((1, 1), des::Plot::new(vec![line().into()]))
+        .with_col_ratios(&[2.0, 1.0])
+        .with_row_ratios(&[1.0, 2.0])
+        .with_space(10.0);
+
+    let fig = fig_wide(subplots);
+    assert_fig_eq_ref!(&fig, "subplots/ratios");
+}
+
+#[test]
+fn subplots_shared_xy() {
+    let axis = || {
+        des::Axis::new()
+            .with_ticks(Default::default())
+            .with_grid(Default::default())
+    };
+    let plot = |x: &[f64], y: &[f64]| {
+        des::Plot::new(vec![line2(x, y).into()])
+            .with_x_axis(axis().with_title("x".into()))
+            .with_y_axis(axis().with_title("y".into()))
+    };
+    let subplots = des::Subplots::new(2, 2)
+        .with_plot((0, 0), plot(&[0.0, 1.0], &[0.0, 1.0]))
+        .with_plot((0, 1), plot(&[0.0, 2.0], &[0.0, 0.5]))
+        .with_plot((1, 0), plot(&[0.5, 1.5], &[0.0, 3.0]))
+        .with_plot((1, 1), plot(&[1.0, 4.0], &[1.0, 2.0]))
+        .with_shared_x(true)
+        .with_shared_y(true)
+        .with_space(10.0);
+
+    let fig = fig_mid(subplots);
+    assert_fig_eq_ref!(&fig, "subplots/shared_xy");
+}
+
+#[test]
+fn subplots_freeform_inset() {
+    let x_axis = des::Axis::new().with_ticks(Default::default());
+    let y_axis = des::Axis::new().with_ticks(Default::default());
+    let main = des::Plot::new(vec![line2(&[0.0, 5.0, 10.0], &[0.0, 10.0, 5.0]).into()])
+        .with_x_axis(x_axis.clone())
+        .with_y_axis(y_axis.clone());
+    let inset = des::Plot::new(vec![line().into()])
+        .with_x_axis(x_axis)
+        .with_y_axis(y_axis)
+        .with_fill(plotive::style::theme::Col::Background.into());
+    let layout = des::FreeformLayout::new()
+        .with_plot(geom::Rect::from_xywh(0.0, 0.0, 1.0, 1.0), main)
+        .with_plot(geom::Rect::from_xywh(0.55, 0.5, 0.4, 0.4), inset);
+
+    let fig = fig_mid(layout);
+    assert_fig_eq_ref!(&fig, "subplots/freeform-inset");
+}
+
+#[test]
+fn subplots_zoom_inset() {
+    let x: Vec<f64> = (0..=40).map(|i| i as f64 * 0.25).collect();
+    let y: Vec<f64> = x.iter().map(|x| (x * 3.0).sin() / (1.0 + x)).collect();
+    let x_axis = des::Axis::new().with_ticks(Default::default());
+    let y_axis = des::Axis::new().with_ticks(Default::default());
+    let inset = des::ZoomInset::new(
+        (4.0, 7.0),
+        (-0.2, 0.2),
+        geom::Rect::from_xywh(0.45, 0.05, 0.5, 0.4),
+    );
+    let plot = des::Plot::new(vec![line2(&x, &y).into()])
+        .with_x_axis(x_axis)
+        .with_y_axis(y_axis)
+        .with_zoom_inset(inset);
+
+    let fig = fig_mid(plot);
+    assert_fig_eq_ref!(&fig, "subplots/zoom-inset");
+}
+
+#[test]
+fn subplots_plot_titles() {
+    let title =
+        plotive::text::parse_rich_text::<plotive::style::theme::Color>("(b) $e^{x}$").unwrap();
+    let plot1 = des::Plot::new(vec![line().into()]).with_title("(a) Start".into());
+    let plot2 = des::Plot::new(vec![line().into()])
+        .with_title(title.into())
+        .with_title_align(plotive::text::line::Align::End)
+        .with_x_axis(
+            des::Axis::new()
+                .with_opposite_side()
+                .with_ticks(Default::default()),
+        );
+    let subplots = des::Subplots::new(1, 2)
+        .with_plot(
+            (0, 0),
+            plot1.with_title_align(plotive::text::line::Align::Start),
+        )
+        .with_plot((0, 1), plot2);
+
+    let fig = fig_wide(subplots).with_title("Figure".into());
+    assert_fig_eq_ref!(&fig, "subplots/plot-titles");
+}
+
+#[test]
+fn subplots_auto_labels() {
+    let subplots = des::Subplots::new(2, 2)
+        .with_plot((0, 0), des::Plot::new(vec![line().into()]))
+        .with_plot(
+            (0, 1),
+            des::Plot::new(vec![line2(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]).into()])
+                .with_title("Start title".into())
+                .with_title_align(plotive::text::line::Align::Start),
+        )
+        .with_plot((1, 1), des::Plot::new(vec![line().into()]));
+    let plots = des::figure::Plots::from(subplots).with_auto_labels(
+        des::plot::PanelLabelStyle::Lowercase,
+        des::plot::PanelLabelPos::OutTopLeft,
+    );
+
+    let fig = fig_mid(plots);
+    assert_fig_eq_ref!(&fig, "subplots/auto-labels");
+}
+
+#[test]
+fn subplots_auto_labels_inside() {
+    let subplots = des::Subplots::new(1, 2)
+        .with_plot((0, 0), des::Plot::new(vec![line().into()]))
+        .with_plot(
+            (0, 1),
+            des::Plot::new(vec![line2(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]).into()]),
+        );
+    let plots = des::figure::Plots::from(subplots).with_auto_labels(
+        des::plot::PanelLabelStyle::Uppercase,
+        des::plot::PanelLabelPos::InTopRight,
+    );
+
+    let fig = fig_wide(plots);
+    assert_fig_eq_ref!(&fig, "subplots/auto-labels-inside");
+}
@@ -0,0 +1,45 @@
+use plotive::{Prepare, Style, des};
+use plotive_pxl::PxlSurface;
+
+use super::{fig_small, line};
+
+fn themed_fig() -> des::Figure {
+    let series = line().with_name("line").into();
+    let plot = des::Plot::new(vec![series])
+        .with_x_axis(
+            des::Axis::new()
+                .with_title("x axis".into())
+                .with_ticks(Default::default())
+                .with_grid(Default::default()),
+        )
+        .with_y_axis(
+            des::Axis::new()
+                .with_title("y axis".into())
+                .with_ticks(Default::default()),
+        )
+        .with_legend(des::plot::LegendPos::InTopRight.into());
+    fig_small(plot).with_title("Title".into())
+}
+
+fn draw_pixels(fig: &plotive::drawing::PreparedFigure, style: &Style) -> Vec<u8> {
+    let mut surface = PxlSurface::new(400, 300).unwrap();
+    fig.draw(&mut surface, style).unwrap();
+    surface.into_pixmap().data().to_vec()
+}
+
+#[test]
+fn themes_prepared_once() {
+    let fig = themed_fig();
+    let prepared = fig.prepare(&(), None).unwrap();
+
+    let light = draw_pixels(&prepared, &Style::light());
+    let dark = draw_pixels(&prepared, &Style::dark());
+    assert_ne!(light, dark);
+
+    // the same prepared figure renders as if it was prepared for each style
+    let light_again = draw_pixels(&prepared, &Style::light());
+    assert_eq!(light, light_again);
+
+    let fresh = fig.prepare(&(), None).unwrap();
+    assert_eq!(dark, draw_pixels(&fresh, &Style::dark()));
+}
@@ -0,0 +1,57 @@
+use std::time::Instant;
+
+use plotive_text::{bundled_font_db, font, fontdb, line};
+
+/// Measures the rendering of a few hundreds of numeric tick labels,
+/// with and without the glyph outline cache.
+fn main() {
+    const RUNS: u32 = 20;
+
+    let labels: Vec<String> = (0..500)
+        .map(|i| format!("{:.2}", i as f64 * 0.25))
+        .collect();
+
+    let cached_db = bundled_font_db();
+    let mut uncached_db = bundled_font_db();
+    uncached_db.set_glyph_cache_capacity(0);
+
+    let render = |db: &fontdb::Database| {
+        let lines: Vec<line::LineText> = labels
+            .iter()
+            .map(|label| {
+                line::LineText::new(
+                    label.clone(),
+                    Default::default(),
+                    12.0,
+                    font::Font::default(),
+                    db,
+                )
+                .unwrap()
+            })
+            .collect();
+
+        let start = Instant::now();
+        for _ in 0..RUNS {
+            for line in &lines {
+                line::render_line_text_with(line, db, |path| {
+                    std::hint::black_box(path);
+                });
+            }
+        }
+        start.elapsed() / RUNS
+    };
+
+    let uncached = render(&uncached_db);
+    let cached = render(&cached_db);
+
+    println!("{} labels", labels.len());
+    println!("without cache: {uncached:?}");
+    println!(
+        "with cache:    {cached:?} ({} outlines cached)",
+        cached_db.glyph_cache_len()
+    );
+    println!(
+        "speedup:       {:.2}x",
+        uncached.as_secs_f64() / cached.as_secs_f64()
+    );
+}
@@ -39,6 +39,17 @@ impl BidiAlgo {
                 dir: *dir,
             }],
             BidiAlgo::Yep { default_lev } => {
+                if default_lev.is_none() {
+                    // the paragraph level is given by the first strong character,
+                    // and is kept for the following lines
+                    *default_lev = crate::script_is_rtl(text).map(|rtl| {
+                        if rtl {
+                            unicode_bidi::RTL_LEVEL
+                        } else {
+                            unicode_bidi::LTR_LEVEL
+                        }
+                    });
+                }
                 let bidi = unicode_bidi::BidiInfo::new(text, *default_lev);
                 let mut res_runs = Vec::new();
 
@@ -52,10 +63,6 @@ impl BidiAlgo {
                         } else {
                             rustybuzz::Direction::LeftToRight
                         };
-                        if default_lev.is_none() {
-                            // assign for following lines
-                            *default_lev = Some(lev);
-                        }
                         res_runs.push(BidiRun {
                             start: start + run.start,
                             end: start + run.end,
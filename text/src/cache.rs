@@ -0,0 +1,302 @@
+//! Bounded cache of glyph outlines
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+
+use plotive_base::geom;
+use ttf_parser as ttf;
+
+use crate::font;
+use crate::fontdb::ID;
+
+/// Default number of glyph outlines kept by the cache
+pub(crate) const DEFAULT_GLYPH_CACHE_CAPACITY: usize = 2048;
+
+/// Key of a glyph outline.
+///
+/// Outlines are cached in font units, so the font size is not part of the key:
+/// the same entry serves all the sizes, the scale being applied by the glyph transform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct GlyphKey {
+    face_id: ID,
+    glyph_id: ttf::GlyphId,
+    weight: u16,
+    width: u16,
+}
+
+impl GlyphKey {
+    pub(crate) fn new(face_id: ID, glyph_id: ttf::GlyphId, font: &font::Font) -> Self {
+        GlyphKey {
+            face_id,
+            glyph_id,
+            weight: font.weight().to_number(),
+            width: font.width().to_number(),
+        }
+    }
+}
+
+const NIL: usize = usize::MAX;
+
+#[derive(Debug)]
+struct Entry {
+    key: GlyphKey,
+    // None for glyphs without outline (e.g. spaces)
+    outline: Option<geom::Path>,
+    prev: usize,
+    next: usize,
+}
+
+/// A least recently used cache of glyph outlines.
+/// Entries are linked from the most recently used (head) to the least recently used (tail).
+#[derive(Debug)]
+struct Lru {
+    capacity: usize,
+    map: HashMap<GlyphKey, usize>,
+    entries: Vec<Entry>,
+    head: usize,
+    tail: usize,
+}
+
+impl Lru {
+    fn new(capacity: usize) -> Self {
+        Lru {
+            capacity,
+            map: HashMap::new(),
+            entries: Vec::new(),
+            head: NIL,
+            tail: NIL,
+        }
+    }
+
+    fn unlink(&mut self, idx: usize) {
+        let (prev, next) = (self.entries[idx].prev, self.entries[idx].next);
+        match prev {
+            NIL => self.head = next,
+            prev => self.entries[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.entries[next].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, idx: usize) {
+        self.entries[idx].prev = NIL;
+        self.entries[idx].next = self.head;
+        match self.head {
+            NIL => self.tail = idx,
+            head => self.entries[head].prev = idx,
+        }
+        self.head = idx;
+    }
+
+    fn get(&mut self, key: &GlyphKey) -> Option<&Option<geom::Path>> {
+        let idx = *self.map.get(key)?;
+        if idx != self.head {
+            self.unlink(idx);
+            self.push_front(idx);
+        }
+        Some(&self.entries[idx].outline)
+    }
+
+    fn insert(&mut self, key: GlyphKey, outline: Option<geom::Path>) -> &Option<geom::Path> {
+        debug_assert!(self.capacity > 0 && !self.map.contains_key(&key));
+        let idx = if self.entries.len() < self.capacity {
+            self.entries.push(Entry {
+                key,
+                outline,
+                prev: NIL,
+                next: NIL,
+            });
+            self.entries.len() - 1
+        } else {
+            // recycle the least recently used entry
+            let idx = self.tail;
+            self.unlink(idx);
+            self.map.remove(&self.entries[idx].key);
+            self.entries[idx].key = key;
+            self.entries[idx].outline = outline;
+            idx
+        };
+        self.map.insert(key, idx);
+        self.push_front(idx);
+        &self.entries[idx].outline
+    }
+
+    fn clear(&mut self) {
+        self.map.clear();
+        self.entries.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+}
+
+/// Thread-safe glyph outline cache, owned by the font database.
+pub(crate) struct GlyphCache {
+    lru: Mutex<Lru>,
+}
+
+impl GlyphCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        GlyphCache {
+            lru: Mutex::new(Lru::new(capacity)),
+        }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.lru.lock().unwrap().capacity
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.lru.lock().unwrap().map.len()
+    }
+
+    pub(crate) fn clear(&self) {
+        self.lru.lock().unwrap().clear();
+    }
+
+    /// Append the outline of a glyph to `builder`, transformed by `ts`.
+    /// `outline_fn` is called to build the outline in font units if it is not cached.
+    pub(crate) fn push_glyph<F>(
+        &self,
+        key: GlyphKey,
+        ts: geom::Transform,
+        builder: &mut geom::PathBuilder,
+        outline_fn: F,
+    ) where
+        F: FnOnce(&mut geom::PathBuilder),
+    {
+        let mut lru = self.lru.lock().unwrap();
+        if let Some(outline) = lru.get(&key) {
+            if let Some(outline) = outline {
+                push_transformed(builder, outline, ts);
+            }
+            return;
+        }
+
+        let mut gl_builder = geom::PathBuilder::new();
+        outline_fn(&mut gl_builder);
+        let outline = gl_builder.finish();
+        if lru.capacity == 0 {
+            if let Some(outline) = outline {
+                push_transformed(builder, &outline, ts);
+            }
+        } else if let Some(outline) = lru.insert(key, outline) {
+            push_transformed(builder, outline, ts);
+        }
+    }
+}
+
+impl fmt::Debug for GlyphCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lru = self.lru.lock().unwrap();
+        f.debug_struct("GlyphCache")
+            .field("capacity", &lru.capacity)
+            .field("len", &lru.map.len())
+            .finish()
+    }
+}
+
+/// Cloning a cache gives an empty cache of the same capacity.
+/// Face IDs of cloned databases diverge as faces are loaded in each of them,
+/// so the entries can't be shared.
+impl Clone for GlyphCache {
+    fn clone(&self) -> Self {
+        GlyphCache::new(self.capacity())
+    }
+}
+
+fn push_transformed(builder: &mut geom::PathBuilder, path: &geom::Path, ts: geom::Transform) {
+    use geom::PathSegment;
+
+    let map = |mut pt: geom::Point| {
+        ts.map_point(&mut pt);
+        pt
+    };
+    for seg in path.segments() {
+        match seg {
+            PathSegment::MoveTo(p) => {
+                let p = map(p);
+                builder.move_to(p.x, p.y);
+            }
+            PathSegment::LineTo(p) => {
+                let p = map(p);
+                builder.line_to(p.x, p.y);
+            }
+            PathSegment::QuadTo(p1, p) => {
+                let (p1, p) = (map(p1), map(p));
+                builder.quad_to(p1.x, p1.y, p.x, p.y);
+            }
+            PathSegment::CubicTo(p1, p2, p) => {
+                let (p1, p2, p) = (map(p1), map(p2), map(p));
+                builder.cubic_to(p1.x, p1.y, p2.x, p2.y, p.x, p.y);
+            }
+            PathSegment::Close => builder.close(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(glyph: u16) -> GlyphKey {
+        GlyphKey::new(ID::dummy(), ttf::GlyphId(glyph), &font::Font::default())
+    }
+
+    fn square(size: f32) -> geom::PathBuilder {
+        let mut pb = geom::PathBuilder::new();
+        pb.push_rect(tiny_skia_path::Rect::from_xywh(0.0, 0.0, size, size).unwrap());
+        pb
+    }
+
+    #[test]
+    fn test_lru_eviction() {
+        let cache = GlyphCache::new(2);
+        let calls = std::cell::Cell::new(0);
+        let push = |glyph: u16| {
+            let mut pb = geom::PathBuilder::new();
+            cache.push_glyph(key(glyph), geom::Transform::identity(), &mut pb, |gl| {
+                calls.set(calls.get() + 1);
+                *gl = square(glyph as f32);
+            });
+        };
+
+        push(1);
+        push(2);
+        push(1);
+        // 2 is the least recently used
+        push(3);
+        push(1);
+        assert_eq!(calls.get(), 3);
+        push(2);
+        assert_eq!(calls.get(), 4);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_transformed_outline() {
+        let cache = GlyphCache::new(4);
+        let ts = geom::Transform::from_row(2.0, 0.0, 0.0, -2.0, 10.0, 20.0);
+        for _ in 0..2 {
+            let mut pb = geom::PathBuilder::new();
+            cache.push_glyph(key(1), ts, &mut pb, |gl| *gl = square(1.0));
+            let bounds = pb.finish().unwrap().bounds();
+            assert_eq!(
+                bounds,
+                tiny_skia_path::Rect::from_ltrb(10.0, 18.0, 12.0, 20.0).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_zero_capacity() {
+        let cache = GlyphCache::new(0);
+        let mut pb = geom::PathBuilder::new();
+        cache.push_glyph(key(1), geom::Transform::identity(), &mut pb, |gl| {
+            *gl = square(1.0)
+        });
+        assert!(pb.finish().is_some());
+        assert_eq!(cache.len(), 0);
+    }
+}
@@ -1,3 +1,4 @@
+use std::ops::Range;
 use std::{fmt, str};
 
 pub use fontdb::{Database, ID};
@@ -6,6 +7,7 @@ use ttf_parser as ttf;
 use crate::fontdb;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Family {
     SansSerif,
     Serif,
@@ -139,6 +141,7 @@ pub fn font_families_to_string(families: &[Family]) -> String {
 
 /// Specifies the weight of glyphs in the font, their degree of blackness or stroke thickness.
 #[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Weight(pub u16);
 
 impl Default for Weight {
@@ -220,6 +223,7 @@ impl str::FromStr for Weight {
 
 /// Allows italic or oblique faces to be selected.
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Style {
     /// A face that is neither italic not obliqued.
     Normal,
@@ -273,6 +277,7 @@ impl str::FromStr for Style {
 /// A face [width](https://docs.microsoft.com/en-us/typography/opentype/spec/os2#uswidthclass).
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Width {
     UltraCondensed,
     ExtraCondensed,
@@ -372,6 +377,7 @@ impl str::FromStr for Width {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Font {
     families: Vec<Family>,
     weight: Weight,
@@ -451,6 +457,13 @@ pub trait DatabaseExt {
     fn select_face_for_str(&self, font: &Font, s: &str) -> Option<ID>;
 
     fn select_face_fallback(&self, s: &str, already_tried: &[ID]) -> Option<ID>;
+
+    /// Split the string into byte ranges that can each be rendered by a single face.
+    /// Each character is given the first face of the fallback chain that has a glyph for it:
+    /// the faces matching the families of `font` in order, then any face of the same style.
+    /// Characters that no face can render are given the primary face.
+    /// Returns `None` if no face matches `font`.
+    fn select_faces_for_str(&self, font: &Font, s: &str) -> Option<Vec<(Range<usize>, ID)>>;
 }
 
 impl DatabaseExt for Database {
@@ -546,6 +559,58 @@ impl DatabaseExt for Database {
         }
         None
     }
+
+    fn select_faces_for_str(&self, font: &Font, s: &str) -> Option<Vec<(Range<usize>, ID)>> {
+        if let Some(id) = self.select_face_for_str(font, s) {
+            return Some(vec![(0..s.len(), id)]);
+        }
+
+        let primary = self.select_face(font)?;
+        let mut chain = vec![primary];
+        for family in &font.families {
+            let query = fontdb::Query {
+                families: &[to_fontdb_family(family)],
+                weight: font.weight().to_fontdb(),
+                stretch: font.width().to_fontdb(),
+                style: font.style().to_fontdb(),
+            };
+            if let Some(id) = self.query(&query)
+                && !chain.contains(&id)
+            {
+                chain.push(id);
+            }
+        }
+
+        let mut spans: Vec<(Range<usize>, ID)> = Vec::new();
+        for (idx, c) in s.char_indices() {
+            let end = idx + c.len_utf8();
+            // whitespace stays with the current face to avoid splitting runs of other scripts
+            if let Some((range, id)) = spans.last_mut()
+                && c.is_whitespace()
+                && self.has_char(*id, c)
+            {
+                range.end = end;
+                continue;
+            }
+
+            let id = match chain.iter().find(|id| self.has_char(**id, c)) {
+                Some(id) => *id,
+                None => match select_face_fallback(self, c, &chain) {
+                    Some(id) => {
+                        chain.push(id);
+                        id
+                    }
+                    None => primary,
+                },
+            };
+
+            match spans.last_mut() {
+                Some((range, last)) if *last == id => range.end = end,
+                _ => spans.push((idx..end, id)),
+            }
+        }
+        Some(spans)
+    }
 }
 
 fn to_fontdb_family(family: &Family) -> fontdb::Family<'_> {
@@ -1147,4 +1212,28 @@ mod tests {
         ]);
         assert_eq!(parse_font_families(input), expected);
     }
+
+    #[cfg(feature = "noto-sans")]
+    #[test]
+    fn test_select_faces_for_str() {
+        let mut db = Database::new();
+        db.load_font_data(include_bytes!("noto/NotoSans-VariableFont_wdth,wght.ttf").to_vec());
+        db.load_font_data(include_bytes!("noto/NotoSansMath-Regular.ttf").to_vec());
+        db.set_sans_serif_family("Noto Sans");
+        let font = Font::default();
+
+        let spans = db.select_faces_for_str(&font, "Ab").unwrap();
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].0, 0..2);
+        let sans = spans[0].1;
+
+        // U+1D538 (double-struck A) is only in the math font
+        let s = "A \u{1D538} B";
+        let spans = db.select_faces_for_str(&font, s).unwrap();
+        assert_eq!(spans.len(), 3);
+        assert_eq!(spans[0], (0..2, sans));
+        assert_eq!(spans[1].0, 2..7);
+        assert_ne!(spans[1].1, sans);
+        assert_eq!(spans[2], (7..8, sans));
+    }
 }
@@ -99,8 +99,11 @@ use alloc::{
 
 use slotmap::SlotMap;
 use tinyvec::TinyVec;
+
 pub use ttf_parser::{Language, UnicodeRanges, Width as Stretch};
 
+use crate::cache::{DEFAULT_GLYPH_CACHE_CAPACITY, GlyphCache};
+
 /// A unique per database face ID.
 ///
 /// Since `Database` is not global/unique, we cannot guarantee that a specific ID
@@ -182,6 +185,7 @@ pub struct Database {
     family_cursive: String,
     family_fantasy: String,
     family_monospace: String,
+    glyph_cache: GlyphCache,
 }
 
 impl Default for Database {
@@ -212,6 +216,7 @@ impl Database {
             #[cfg(target_os = "macos")]
             family_fantasy: "Papyrus".to_string(),
             family_monospace: "Courier New".to_string(),
+            glyph_cache: GlyphCache::new(DEFAULT_GLYPH_CACHE_CAPACITY),
         }
     }
 
@@ -643,6 +648,33 @@ impl Database {
         self.faces.len()
     }
 
+    /// Sets the maximum number of glyph outlines kept in cache for rendering.
+    ///
+    /// The least recently used outlines are evicted when the cache is full.
+    /// A capacity of zero disables the cache. The default is 2048 outlines.
+    pub fn set_glyph_cache_capacity(&mut self, capacity: usize) {
+        self.glyph_cache = GlyphCache::new(capacity);
+    }
+
+    /// Returns the maximum number of glyph outlines kept in cache for rendering.
+    pub fn glyph_cache_capacity(&self) -> usize {
+        self.glyph_cache.capacity()
+    }
+
+    /// Returns the number of glyph outlines currently in cache.
+    pub fn glyph_cache_len(&self) -> usize {
+        self.glyph_cache.len()
+    }
+
+    /// Removes all the glyph outlines from the cache.
+    pub fn clear_glyph_cache(&self) {
+        self.glyph_cache.clear();
+    }
+
+    pub(crate) fn glyph_cache(&self) -> &GlyphCache {
+        &self.glyph_cache
+    }
+
     /// Sets the family that will be used by `Family::Serif`.
     pub fn set_serif_family<S: Into<String>>(&mut self, family: S) {
         self.family_serif = family.into();
@@ -7,6 +7,7 @@
 //!  - `font`:  Font description and matching utilities.
 //!  - `line`: Text layout and shaping for single lines of text.
 //!  - `rich`: Rich text parsing and rendering.
+//!  - `metrics`: Measurement of text without rendering, for custom layouts.
 //!
 //! For both single line text and rich text, the text is first analyzed for bidirectional runs using
 //! the [unicode-bidi](https://crates.io/crates/unicode-bidi) crate, then each run is shaped separately
@@ -20,13 +21,16 @@ use plotive_base::geom;
 use ttf_parser as ttf;
 
 mod bidi;
+mod cache;
 pub mod font;
 pub mod fontdb;
 pub mod line;
+pub mod metrics;
 pub mod rich;
 
 pub use font::{Font, ScaledMetrics, parse_font_families};
 pub use line::{LineText, render_line_text};
+pub use metrics::{TextMetrics, measure, measure_rich};
 pub use rich::{
     ParseRichTextError, ParsedRichText, RichPrimitive, RichText, RichTextBuilder, parse_rich_text,
     parse_rich_text_with_classes, render_rich_text, render_rich_text_with,
@@ -41,29 +45,151 @@ pub use rich::{
 ))]
 /// Loads fonts that are bundled with plotive
 /// and returns the database.
+///
+/// This is a shortcut for `FontDbBuilder::new().with_all_bundled().build()`.
 pub fn bundled_font_db() -> fontdb::Database {
-    let mut db = fontdb::Database::new();
+    FontDbBuilder::new().with_all_bundled().build()
+}
 
+#[cfg(any(
+    feature = "noto-sans",
+    feature = "noto-sans-italic",
+    feature = "noto-serif",
+    feature = "noto-serif-italic",
+    feature = "noto-mono"
+))]
+/// A font family bundled with plotive.
+/// Each variant is available with the cargo feature of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BundledFont {
+    /// Noto Sans, upright
     #[cfg(feature = "noto-sans")]
-    db.load_font_data(include_bytes!("noto/NotoSans-VariableFont_wdth,wght.ttf").to_vec());
+    NotoSans,
+    /// Noto Sans, italic
     #[cfg(feature = "noto-sans-italic")]
-    db.load_font_data(include_bytes!("noto/NotoSans-Italic-VariableFont_wdth,wght.ttf").to_vec());
-    #[cfg(any(feature = "noto-sans", feature = "noto-sans-italic"))]
-    db.set_sans_serif_family("Noto Sans");
-
+    NotoSansItalic,
+    /// Noto Serif, upright
     #[cfg(feature = "noto-serif")]
-    db.load_font_data(include_bytes!("noto/NotoSerif-VariableFont_wdth,wght.ttf").to_vec());
+    NotoSerif,
+    /// Noto Serif, italic
     #[cfg(feature = "noto-serif-italic")]
-    db.load_font_data(include_bytes!("noto/NotoSerif-Italic-VariableFont_wdth,wght.ttf").to_vec());
-    #[cfg(any(feature = "noto-serif", feature = "noto-serif-italic"))]
-    db.set_serif_family("Noto Serif");
-
-    #[cfg(feature = "noto-mono")]
-    db.load_font_data(include_bytes!("noto/NotoSansMono-VariableFont_wdth,wght.ttf").to_vec());
+    NotoSerifItalic,
+    /// Noto Sans Mono
     #[cfg(feature = "noto-mono")]
-    db.set_monospace_family("Noto Sans Mono");
+    NotoMono,
+}
+
+#[cfg(any(
+    feature = "noto-sans",
+    feature = "noto-sans-italic",
+    feature = "noto-serif",
+    feature = "noto-serif-italic",
+    feature = "noto-mono"
+))]
+impl BundledFont {
+    /// All the fonts bundled with the enabled features
+    pub const ALL: &'static [BundledFont] = &[
+        #[cfg(feature = "noto-sans")]
+        BundledFont::NotoSans,
+        #[cfg(feature = "noto-sans-italic")]
+        BundledFont::NotoSansItalic,
+        #[cfg(feature = "noto-serif")]
+        BundledFont::NotoSerif,
+        #[cfg(feature = "noto-serif-italic")]
+        BundledFont::NotoSerifItalic,
+        #[cfg(feature = "noto-mono")]
+        BundledFont::NotoMono,
+    ];
+
+    fn data(self) -> &'static [u8] {
+        match self {
+            #[cfg(feature = "noto-sans")]
+            BundledFont::NotoSans => include_bytes!("noto/NotoSans-VariableFont_wdth,wght.ttf"),
+            #[cfg(feature = "noto-sans-italic")]
+            BundledFont::NotoSansItalic => {
+                include_bytes!("noto/NotoSans-Italic-VariableFont_wdth,wght.ttf")
+            }
+            #[cfg(feature = "noto-serif")]
+            BundledFont::NotoSerif => include_bytes!("noto/NotoSerif-VariableFont_wdth,wght.ttf"),
+            #[cfg(feature = "noto-serif-italic")]
+            BundledFont::NotoSerifItalic => {
+                include_bytes!("noto/NotoSerif-Italic-VariableFont_wdth,wght.ttf")
+            }
+            #[cfg(feature = "noto-mono")]
+            BundledFont::NotoMono => {
+                include_bytes!("noto/NotoSansMono-VariableFont_wdth,wght.ttf")
+            }
+        }
+    }
+
+    fn set_generic_family(self, db: &mut fontdb::Database) {
+        match self {
+            #[cfg(feature = "noto-sans")]
+            BundledFont::NotoSans => db.set_sans_serif_family("Noto Sans"),
+            #[cfg(feature = "noto-sans-italic")]
+            BundledFont::NotoSansItalic => db.set_sans_serif_family("Noto Sans"),
+            #[cfg(feature = "noto-serif")]
+            BundledFont::NotoSerif => db.set_serif_family("Noto Serif"),
+            #[cfg(feature = "noto-serif-italic")]
+            BundledFont::NotoSerifItalic => db.set_serif_family("Noto Serif"),
+            #[cfg(feature = "noto-mono")]
+            BundledFont::NotoMono => db.set_monospace_family("Noto Sans Mono"),
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "noto-sans",
+    feature = "noto-sans-italic",
+    feature = "noto-serif",
+    feature = "noto-serif-italic",
+    feature = "noto-mono"
+))]
+/// Builds a font database out of a selection of bundled fonts and of user provided font data.
+///
+/// Bundled fonts also become the generic family they belong to (sans-serif, serif or monospace).
+#[derive(Debug, Default)]
+pub struct FontDbBuilder {
+    db: fontdb::Database,
+}
+
+#[cfg(any(
+    feature = "noto-sans",
+    feature = "noto-sans-italic",
+    feature = "noto-serif",
+    feature = "noto-serif-italic",
+    feature = "noto-mono"
+))]
+impl FontDbBuilder {
+    /// Create a builder with an empty database
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Include the given bundled font
+    pub fn with_bundled(mut self, font: BundledFont) -> Self {
+        self.db.load_font_data(font.data().to_vec());
+        font.set_generic_family(&mut self.db);
+        self
+    }
+
+    /// Include all the fonts bundled with the enabled features
+    pub fn with_all_bundled(self) -> Self {
+        BundledFont::ALL
+            .iter()
+            .fold(self, |builder, font| builder.with_bundled(*font))
+    }
 
-    db
+    /// Include font data (e.g. the content of a TTF or OTF file)
+    pub fn with_font_data(mut self, data: Vec<u8>) -> Self {
+        self.db.load_font_data(data);
+        self
+    }
+
+    /// Finalize and return the database
+    pub fn build(self) -> fontdb::Database {
+        self.db
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -154,3 +280,72 @@ impl ttf::OutlineBuilder for Outliner<'_> {
         self.0.close();
     }
 }
+
+#[cfg(all(test, feature = "noto-sans"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_db_builder() {
+        let db = FontDbBuilder::new().build();
+        assert!(db.is_empty());
+
+        let db = FontDbBuilder::new()
+            .with_bundled(BundledFont::NotoSans)
+            .build();
+        assert_eq!(db.len(), 1);
+        assert_eq!(db.family_name(&fontdb::Family::SansSerif), "Noto Sans");
+
+        let db = FontDbBuilder::new().with_all_bundled().build();
+        assert_eq!(db.len(), BundledFont::ALL.len());
+    }
+
+    #[test]
+    fn test_glyph_cache_render() {
+        let db = bundled_font_db();
+        let mut uncached_db = bundled_font_db();
+        uncached_db.set_glyph_cache_capacity(0);
+
+        let render = |db: &fontdb::Database, text: &str, size: f32| {
+            let line = LineText::new(
+                text.to_string(),
+                Default::default(),
+                size,
+                Font::default(),
+                db,
+            )
+            .unwrap();
+            let mut paths = Vec::new();
+            line::render_line_text_with(&line, db, |path| paths.push(format!("{path:?}")));
+            paths
+        };
+
+        for (text, size) in [("0.25", 12.0), ("0.50", 12.0), ("0.25", 20.0)] {
+            assert_eq!(render(&db, text, size), render(&uncached_db, text, size));
+        }
+        // the outlines are cached independently of the font size
+        assert_eq!(db.glyph_cache_len(), 4);
+        assert_eq!(uncached_db.glyph_cache_len(), 0);
+    }
+
+    #[test]
+    fn test_line_letter_spacing() {
+        let db = bundled_font_db();
+        let line =
+            |align| LineText::new("abcd".to_string(), align, 12.0, Font::default(), &db).unwrap();
+
+        let left = line((line::Align::Left, line::VerAlign::Baseline));
+        let spaced = line((line::Align::Left, line::VerAlign::Baseline)).with_letter_spacing(3.0);
+        assert!((spaced.width() - left.width() - 9.0).abs() < 1e-3);
+        assert_eq!(spaced.bbox().unwrap().left(), 0.0);
+
+        // the text stays centered, and resetting the spacing restores the layout
+        let centered =
+            line((line::Align::Center, line::VerAlign::Baseline)).with_letter_spacing(3.0);
+        let bbox = centered.bbox().unwrap();
+        assert!((bbox.left() + bbox.right()).abs() < 1e-3);
+        let reset = centered.with_letter_spacing(0.0);
+        let centered = line((line::Align::Center, line::VerAlign::Baseline));
+        assert!((reset.width() - centered.width()).abs() < 1e-3);
+    }
+}
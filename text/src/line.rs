@@ -4,11 +4,13 @@ use plotive_base::geom;
 use ttf_parser as ttf;
 
 use crate::bidi::{self, BidiAlgo};
+use crate::cache::GlyphKey;
 use crate::font::{self, DatabaseExt};
 use crate::{Error, Font, ScriptDir, fontdb};
 
 /// Horizontal alignment
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Align {
     /// Align the start of the text (left or right depending on the direction)
     #[default]
@@ -25,6 +27,7 @@ pub enum Align {
 
 /// Vertical alignment for a single line of text
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VerAlign {
     /// Align the bottom of the descender
     Bottom,
@@ -46,6 +49,7 @@ pub struct LineText {
     align: (Align, VerAlign),
     font_size: f32,
     font: Font,
+    letter_spacing: f32,
     bbox: Option<geom::Rect>,
     main_dir: ScriptDir,
     metrics: font::ScaledMetrics,
@@ -69,6 +73,11 @@ impl LineText {
         &self.font
     }
 
+    /// Extra advance between two consecutive clusters
+    pub fn letter_spacing(&self) -> f32 {
+        self.letter_spacing
+    }
+
     pub fn bbox(&self) -> Option<&geom::Rect> {
         self.bbox.as_ref()
     }
@@ -97,6 +106,7 @@ impl LineText {
             align: (Default::default(), Default::default()),
             font_size: 1.0,
             font,
+            letter_spacing: 0.0,
             bbox: None,
             main_dir: ScriptDir::LeftToRight,
             metrics: font::ScaledMetrics::null(),
@@ -137,16 +147,82 @@ impl LineText {
             },
         };
 
+        let face_spans = db
+            .select_faces_for_str(&font, &text)
+            .ok_or_else(|| Error::NoSuchFont(font.clone()))?;
+
         let mut shapes = Vec::with_capacity(bidi_runs.len());
         let mut ctx = Ctx { buffer: None };
         for run in &bidi_runs {
-            let shape = Shape::shape_run(&text, run, font_size, &font, db, &mut ctx)?;
-            shapes.push(shape);
+            // a run covered by several faces is shaped in one piece per face, in visual order
+            let first = shapes.len();
+            for (range, face_id) in face_spans.iter() {
+                let start = range.start.max(run.start);
+                let end = range.end.min(run.end);
+                if start < end {
+                    let run = bidi::BidiRun {
+                        start,
+                        end,
+                        dir: run.dir,
+                    };
+                    let shape =
+                        Shape::shape_run(&text, &run, *face_id, font_size, &font, db, &mut ctx)?;
+                    shapes.push(shape);
+                }
+            }
+            if run.dir == rustybuzz::Direction::RightToLeft {
+                shapes[first..].reverse();
+            }
+        }
+
+        if shapes.is_empty() {
+            return Ok(LineText::new_empty(font));
         }
 
-        let (align, ver_align) = align;
+        let mut line = LineText {
+            text,
+            align,
+            font_size,
+            font,
+            letter_spacing: 0.0,
+            bbox: None,
+            main_dir,
+            metrics: shapes.metrics(),
+            shapes,
+        };
+        line.layout_glyphs();
+        Ok(line)
+    }
+
+    /// Add `spacing` to the advance between each cluster of the text and the following one.
+    /// The text is laid out again with the same alignment, and the bounding box is updated.
+    pub fn with_letter_spacing(mut self, spacing: f32) -> Self {
+        let delta = spacing - self.letter_spacing;
+        self.letter_spacing = spacing;
+        if delta == 0.0 || self.shapes.is_empty() {
+            return self;
+        }
+        let mut glyphs = self
+            .shapes
+            .iter_mut()
+            .flat_map(|s| s.glyphs.iter_mut())
+            .peekable();
+        while let Some(glyph) = glyphs.next() {
+            // the spacing goes after the last glyph of each cluster, except the last one
+            match glyphs.peek() {
+                Some(next) if next.cluster != glyph.cluster => glyph.x_advance += delta,
+                _ => (),
+            }
+        }
+        self.layout_glyphs();
+        self
+    }
 
-        let metrics = shapes.metrics();
+    /// Position the glyphs relatively to the alignment point and compute the bounding box
+    fn layout_glyphs(&mut self) {
+        let (align, ver_align) = self.align;
+        let metrics = self.metrics;
+        let main_dir = self.main_dir;
 
         let mut y_cursor = match ver_align {
             VerAlign::Bottom => metrics.descent,
@@ -156,7 +232,7 @@ impl LineText {
             VerAlign::Top => metrics.ascent,
         };
 
-        let width = shapes.width();
+        let width = self.shapes.width();
 
         let x_start = match (align, main_dir) {
             (Align::Start, ScriptDir::LeftToRight)
@@ -175,7 +251,7 @@ impl LineText {
 
         let y_flip = geom::Transform::from_scale(1.0, -1.0);
 
-        for shape in shapes.iter_mut() {
+        for shape in self.shapes.iter_mut() {
             let scale_ts = geom::Transform::from_scale(shape.metrics.scale, shape.metrics.scale);
             for glyph in shape.glyphs.iter_mut() {
                 let x = x_cursor + glyph.x_offset;
@@ -187,16 +263,46 @@ impl LineText {
             }
         }
 
-        Ok(LineText {
-            text,
-            align: (align, ver_align),
-            font_size,
-            font: font.clone(),
-            bbox: Some(geom::Rect::from_trbl(top, x_cursor, bottom, x_start)),
-            main_dir,
-            metrics,
-            shapes,
-        })
+        self.bbox = Some(geom::Rect::from_trbl(top, x_cursor, bottom, x_start));
+    }
+
+    /// Truncate the text with an ellipsis ("…") if it is wider than `max_width`.
+    /// Returns the text unchanged if it fits.
+    pub fn ellipsize(self, max_width: f32, db: &fontdb::Database) -> Result<Self, Error> {
+        if self.width() <= max_width {
+            return Ok(self);
+        }
+
+        let ellipsis = LineText::new(
+            "\u{2026}".to_string(),
+            self.align,
+            self.font_size,
+            self.font.clone(),
+            db,
+        )?;
+        let spacing = self.letter_spacing;
+
+        let mut advances = vec![0.0f32; self.text.len()];
+        for g in self.shapes.iter().flat_map(|s| s.glyphs.iter()) {
+            advances[g.cluster] += g.x_advance;
+        }
+
+        let mut cut = 0;
+        let mut width = ellipsis.width() + spacing;
+        for (i, c) in self.text.char_indices() {
+            width += advances[i];
+            if width > max_width {
+                break;
+            }
+            cut = i + c.len_utf8();
+        }
+
+        let mut text = self.text[..cut].trim_end().to_string();
+        text.push('\u{2026}');
+        Ok(
+            LineText::new(text, self.align, self.font_size, self.font, db)?
+                .with_letter_spacing(spacing),
+        )
     }
 }
 
@@ -209,7 +315,7 @@ pub(crate) struct Shape {
 }
 
 impl Shape {
-    fn width(&self) -> f32 {
+    pub(crate) fn width(&self) -> f32 {
         self.glyphs.iter().map(|g| g.x_advance).sum()
     }
 }
@@ -245,6 +351,7 @@ impl ShapesExt for [Shape] {
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct Glyph {
     pub(crate) id: ttf::GlyphId,
+    cluster: usize,
     x_offset: f32,
     y_offset: f32,
     x_advance: f32,
@@ -261,16 +368,12 @@ impl Shape {
     fn shape_run(
         text: &str,
         run: &bidi::BidiRun,
+        face_id: fontdb::ID,
         font_size: f32,
         font: &font::Font,
         db: &fontdb::Database,
         ctx: &mut Ctx,
     ) -> Result<Self, Error> {
-        let face_id = db
-            .select_face_for_str(font, text)
-            .or_else(|| db.select_face(&font))
-            .ok_or_else(|| Error::NoSuchFont(font.clone()))?;
-
         let mut buffer = ctx
             .buffer
             .take()
@@ -301,6 +404,7 @@ impl Shape {
         for (i, p) in shape.glyph_infos().iter().zip(shape.glyph_positions()) {
             glyphs.push(Glyph {
                 id: ttf::GlyphId(i.glyph_id as u16),
+                cluster: i.cluster as usize + run.start,
                 x_advance: p.x_advance as f32 * metrics.scale,
                 y_advance: p.y_advance as f32 * metrics.scale,
                 x_offset: p.x_offset as f32 * metrics.scale,
@@ -323,30 +427,25 @@ pub fn render_line_text_with<R>(line: &LineText, db: &font::Database, mut render
 where
     R: FnMut(&geom::Path),
 {
+    let cache = db.glyph_cache();
     for shape in line.shapes.iter() {
         db.with_face_data(shape.face_id, |data, index| {
-            let mut face = ttf::Face::parse(data, index).unwrap();
-            font::apply_ttf_variations(&mut face, line.font());
+            // the face is only parsed if a glyph is missing from the cache
+            let mut face = None;
 
             // the path builder for the entire string
             let mut str_pb = geom::PathBuilder::new();
-            // the path builder for each glyph
-            let mut gl_pb = geom::PathBuilder::new();
 
             for gl in &shape.glyphs {
-                {
-                    let mut builder = crate::Outliner(&mut gl_pb);
-                    face.outline_glyph(gl.id, &mut builder);
-                }
-
-                if let Some(path) = gl_pb.finish() {
-                    let path = path.transform(gl.ts).unwrap();
-                    str_pb.push_path(&path);
-
-                    gl_pb = path.clear();
-                } else {
-                    gl_pb = geom::PathBuilder::new();
-                }
+                let key = GlyphKey::new(shape.face_id, gl.id, line.font());
+                cache.push_glyph(key, gl.ts, &mut str_pb, |gl_pb| {
+                    let face = face.get_or_insert_with(|| {
+                        let mut face = ttf::Face::parse(data, index).unwrap();
+                        font::apply_ttf_variations(&mut face, line.font());
+                        face
+                    });
+                    face.outline_glyph(gl.id, &mut crate::Outliner(gl_pb));
+                });
             }
 
             if let Some(path) = str_pb.finish() {
@@ -0,0 +1,171 @@
+//! Module to measure text without rendering it
+//!
+//! The measurements are obtained with the same shaping and layout as the rendering,
+//! so that boxes sized with them fit exactly the rendered text.
+
+use plotive_base::geom;
+
+use crate::rich::{self, LineSpan, RichText, RichTextBuilder};
+use crate::{Error, Font, LineText, fontdb, line};
+
+/// Metrics of a laid out text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextMetrics {
+    /// Bounding box of the text, relative to the alignment reference point.
+    /// The Y axis points downwards. `None` if the text is empty.
+    pub bbox: Option<geom::Rect>,
+    /// Advance of the longest line, that is the sum of the glyph advances along the line.
+    /// For vertical text, this is the advance of the longest column.
+    pub advance: f32,
+    /// Distance between the baselines of two successive lines,
+    /// that is the height of the tallest line including the line gap,
+    /// multiplied by the line height factor of rich text.
+    /// For vertical text, this is the width of the widest column.
+    pub line_height: f32,
+    /// Number of lines (or columns for vertical text)
+    pub lines: usize,
+}
+
+impl TextMetrics {
+    const EMPTY: TextMetrics = TextMetrics {
+        bbox: None,
+        advance: 0.0,
+        line_height: 0.0,
+        lines: 0,
+    };
+
+    /// Width of the bounding box
+    #[inline]
+    pub fn width(&self) -> f32 {
+        self.bbox.map_or(0.0, |bbox| bbox.width())
+    }
+
+    /// Height of the bounding box
+    #[inline]
+    pub fn height(&self) -> f32 {
+        self.bbox.map_or(0.0, |bbox| bbox.height())
+    }
+
+    /// Metrics of an already laid out line of text
+    pub fn from_line_text(line: &LineText) -> Self {
+        if line.shapes.is_empty() {
+            return Self::EMPTY;
+        }
+        let metrics = line.metrics();
+        TextMetrics {
+            bbox: line.bbox().copied(),
+            advance: line.shapes.iter().map(|s| s.width()).sum(),
+            line_height: metrics.height() + metrics.line_gap,
+            lines: 1,
+        }
+    }
+
+    /// Metrics of an already laid out rich text
+    pub fn from_rich_text<C>(text: &RichText<C>) -> Self
+    where
+        C: Clone,
+    {
+        if text.lines().is_empty() {
+            return Self::EMPTY;
+        }
+        let vertical = matches!(text.layout(), rich::Layout::Vertical(..));
+        let max =
+            |f: &dyn Fn(&LineSpan<C>) -> f32| text.lines().iter().map(f).fold(0.0f32, f32::max);
+        let (advance, line_height) = if vertical {
+            (
+                max(&|l| l.bbox().map_or(0.0, |b| b.height())),
+                max(&|l| l.bbox().map_or(0.0, |b| b.width())),
+            )
+        } else {
+            (
+                max(&|l| l.bbox().map_or(0.0, |b| b.width())),
+                max(&|l| l.total_height()) * text.line_height(),
+            )
+        };
+        TextMetrics {
+            bbox: text.bbox().copied(),
+            advance,
+            line_height,
+            lines: text.lines().len(),
+        }
+    }
+}
+
+/// Measure a single line of text, laid out with `align` around the reference point.
+///
+/// This is equivalent to measuring a [`LineText`], without keeping the shaped glyphs.
+pub fn measure(
+    text: &str,
+    font: &Font,
+    font_size: f32,
+    align: (line::Align, line::VerAlign),
+    db: &fontdb::Database,
+) -> Result<TextMetrics, Error> {
+    let line = LineText::new(text.to_string(), align, font_size, font.clone(), db)?;
+    Ok(TextMetrics::from_line_text(&line))
+}
+
+/// Measure a rich text, with the layout and maximum width set on the builder.
+///
+/// This is equivalent to measuring the [`RichText`] built by `builder`.
+pub fn measure_rich<C>(
+    builder: RichTextBuilder<C>,
+    db: &fontdb::Database,
+) -> Result<TextMetrics, Error>
+where
+    C: Clone + PartialEq,
+{
+    let text = builder.done(db)?;
+    Ok(TextMetrics::from_rich_text(&text))
+}
+
+#[cfg(all(test, feature = "noto-sans"))]
+mod tests {
+    use plotive_base::ColorU8;
+
+    use super::*;
+    use crate::bundled_font_db;
+    use crate::rich::{Overflow, TextProps};
+
+    #[test]
+    fn test_measure_line() {
+        let db = bundled_font_db();
+        let font = Font::default();
+        let align = (line::Align::Center, line::VerAlign::Baseline);
+
+        let m = measure("Hello", &font, 20.0, align, &db).unwrap();
+        let line = LineText::new("Hello".to_string(), align, 20.0, font.clone(), &db).unwrap();
+        assert_eq!(m.bbox, line.bbox().copied());
+        assert_eq!(m.lines, 1);
+        assert!((m.advance - line.width()).abs() < 1e-3);
+        assert!(m.line_height >= m.height());
+
+        // metrics scale with the font size
+        let m2 = measure("Hello", &font, 40.0, align, &db).unwrap();
+        assert!((m2.advance - 2.0 * m.advance).abs() < 1e-2);
+
+        let empty = measure("", &font, 20.0, align, &db).unwrap();
+        assert_eq!(empty, TextMetrics::EMPTY);
+    }
+
+    #[test]
+    fn test_measure_rich() {
+        let db = bundled_font_db();
+        let props = TextProps::<ColorU8>::new(20.0);
+
+        let one = RichTextBuilder::new("Hello world".to_string(), props.clone());
+        let one = measure_rich(one, &db).unwrap();
+        assert_eq!(one.lines, 1);
+
+        let two = RichTextBuilder::new("Hello\nworld".to_string(), props.clone());
+        let two = measure_rich(two, &db).unwrap();
+        assert_eq!(two.lines, 2);
+        assert!(two.advance < one.advance);
+        assert!((two.height() - (one.height() + two.line_height)).abs() < 1e-3);
+
+        let wrapped = RichTextBuilder::new("Hello world".to_string(), props)
+            .with_max_width(one.advance / 2.0 + 10.0, Overflow::Wrap);
+        let wrapped = measure_rich(wrapped, &db).unwrap();
+        assert_eq!(wrapped.lines, 2);
+    }
+}
@@ -5,10 +5,12 @@ use crate::{Error, font, fontdb, line};
 
 mod boundaries;
 mod builder;
+mod math;
 mod parse;
 mod render;
 
 use boundaries::Boundaries;
+
 pub use parse::{
     ParseRichTextError, ParsedRichText, parse_rich_text, parse_rich_text_with_classes,
 };
@@ -151,6 +153,18 @@ impl Default for Layout {
     }
 }
 
+/// How horizontal text that exceeds its maximum width is handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Overflow {
+    /// Lines are broken at word boundaries.
+    /// A single word wider than the maximum width still overflows.
+    #[default]
+    Wrap,
+    /// Lines are truncated and terminated with an ellipsis ("…")
+    Ellipsis,
+}
+
 /// A builder struct for rich text
 #[derive(Debug, Clone)]
 pub struct RichTextBuilder<C>
@@ -160,7 +174,11 @@ where
     text: String,
     root_props: TextProps<C>,
     layout: Layout,
+    max_width: Option<(f32, Overflow)>,
+    letter_spacing: f32,
+    line_height: f32,
     spans: Vec<TextSpan<C>>,
+    math: Vec<MathElem>,
 }
 
 impl<C> RichTextBuilder<C>
@@ -173,7 +191,11 @@ where
             text,
             root_props,
             layout: Layout::default(),
+            max_width: None,
+            letter_spacing: 0.0,
+            line_height: 1.0,
             spans: vec![],
+            math: vec![],
         }
     }
 
@@ -182,6 +204,28 @@ where
         self
     }
 
+    /// Constrain the width of the lines, handling the longer lines with `overflow`.
+    /// Only applies to horizontal layout.
+    pub fn with_max_width(mut self, max_width: f32, overflow: Overflow) -> Self {
+        self.max_width = Some((max_width, overflow));
+        self
+    }
+
+    /// Add `spacing` to the advance between each cluster of a line and the following one.
+    /// Only applies to horizontal layout.
+    pub fn with_letter_spacing(mut self, spacing: f32) -> Self {
+        self.letter_spacing = spacing;
+        self
+    }
+
+    /// Multiply the distance between the baselines of successive lines by `factor`.
+    /// The default factor is 1, that is the height of the lines plus the line gap of the font.
+    /// Only applies to horizontal layout.
+    pub fn with_line_height(mut self, factor: f32) -> Self {
+        self.line_height = factor;
+        self
+    }
+
     /// Add a new text span
     pub fn add_span(&mut self, start: usize, end: usize, props: TextOptProps<C>) {
         assert!(start <= end);
@@ -192,6 +236,22 @@ where
         self.spans.push(TextSpan { start, end, props });
     }
 
+    /// Add a math construct.
+    /// Math constructs are laid out only for horizontal text.
+    pub fn add_math(&mut self, elem: MathElem) {
+        for (start, end) in elem.ranges() {
+            assert!(start <= end);
+            assert!(
+                self.text.is_char_boundary(start) && self.text.is_char_boundary(end),
+                "start and end must be on char boundaries"
+            );
+        }
+        if let MathElem::Frac { num, den } = elem {
+            assert_eq!(num.1, den.0, "denominator must follow the numerator");
+        }
+        self.math.push(elem);
+    }
+
     /// Create a RichText from this builder
     pub fn done(self, fontdb: &fontdb::Database) -> Result<RichText<C>, Error> {
         self.done_impl(fontdb)
@@ -205,6 +265,7 @@ where
 {
     text: String,
     layout: Layout,
+    line_height: f32,
     lines: Vec<LineSpan<C>>,
     bbox: Option<geom::Rect>,
 }
@@ -221,6 +282,11 @@ where
         self.layout
     }
 
+    /// The factor applied to the distance between successive lines
+    pub fn line_height(&self) -> f32 {
+        self.line_height
+    }
+
     pub fn lines(&self) -> &[LineSpan<C>] {
         &self.lines
     }
@@ -259,6 +325,7 @@ where
         RichText {
             text: self.text.clone(),
             layout: self.layout,
+            line_height: self.line_height,
             lines: self
                 .lines
                 .iter()
@@ -272,6 +339,7 @@ where
         Self {
             text: String::new(),
             layout: Layout::default(),
+            line_height: 1.0,
             lines: Vec::new(),
             bbox: None,
         }
@@ -281,32 +349,25 @@ where
     pub fn assert_flat_coverage(&self) {
         let len = self.text.len();
         let mut cursor = 0;
-        for l in self.lines.iter() {
-            assert_eq!(l.start, cursor);
-            cursor = l.end;
-            if cursor == len {
-                // last line might not end with a newline
-                break;
-            }
-            if self.text.as_bytes()[cursor] == b'\r' {
-                cursor += 1;
-            }
-            assert_eq!(
-                self.text.as_bytes()[cursor],
-                b'\n',
-                "expected end of line, found {}",
-                self.text[cursor..].chars().next().unwrap()
+        for (i, l) in self.lines.iter().enumerate() {
+            // lines are separated by a line break, or by whitespace if wrapped
+            let gap = &self.text[cursor..l.start];
+            assert!(
+                gap.chars().all(char::is_whitespace),
+                "expected end of line or whitespace, found {gap:?}"
             );
-            cursor += 1;
+            assert!(i > 0 || gap.is_empty());
+            cursor = l.end;
             l.assert_flat_coverage();
         }
-        assert_eq!(cursor, len);
+        assert!(self.text[cursor..len].chars().all(char::is_whitespace));
     }
 }
 
 /// A set of properties to be applied to a text span.
 /// If a property is `None`, value is inherited from the parent span.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextOptProps<C> {
     pub font_family: Option<Vec<font::Family>>,
     pub font_weight: Option<font::Weight>,
@@ -317,6 +378,11 @@ pub struct TextOptProps<C> {
     pub stroke: Option<(C, f32)>,
     pub underline: Option<bool>,
     pub strikeout: Option<bool>,
+    /// Factor applied to the font size of the parent span
+    pub font_scale: Option<f32>,
+    /// Vertical shift of the baseline, as a factor of the font size of the parent span.
+    /// Positive values raise the text.
+    pub baseline_shift: Option<f32>,
 }
 
 impl<C> Default for TextOptProps<C> {
@@ -331,6 +397,8 @@ impl<C> Default for TextOptProps<C> {
             stroke: None,
             underline: None,
             strikeout: None,
+            font_scale: None,
+            baseline_shift: None,
         }
     }
 }
@@ -342,11 +410,14 @@ impl<C> TextOptProps<C> {
             || self.font_width.is_some()
             || self.font_style.is_some()
             || self.font_size.is_some()
+            || self.font_scale.is_some()
+            || self.baseline_shift.is_some()
     }
 }
 
 /// A set of resolved properties for a text span
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextProps<C>
 where
     C: Clone,
@@ -357,6 +428,7 @@ where
     outline: Option<(C, f32)>,
     underline: bool,
     strikeout: bool,
+    baseline_shift: f32,
 }
 
 impl<C> TextProps<C>
@@ -376,6 +448,7 @@ where
             outline: self.outline.as_ref().map(|(c, w)| (color_map(c), *w)),
             underline: self.underline,
             strikeout: self.strikeout,
+            baseline_shift: self.baseline_shift,
         }
     }
 }
@@ -404,6 +477,7 @@ where
             outline: None,
             underline: false,
             strikeout: false,
+            baseline_shift: 0.0,
         }
     }
 }
@@ -461,6 +535,12 @@ where
         self.strikeout
     }
 
+    /// The vertical shift of the baseline, in the same unit as the font size.
+    /// Positive values raise the text.
+    pub fn baseline_shift(&self) -> f32 {
+        self.baseline_shift
+    }
+
     fn apply_opts(&mut self, opts: &TextOptProps<C>) {
         if let Some(font_family) = &opts.font_family {
             self.font = self.font.clone().with_families(font_family.clone());
@@ -474,9 +554,16 @@ where
         if let Some(font_style) = opts.font_style {
             self.font = self.font.clone().with_style(font_style);
         }
+        let parent_size = self.font_size;
         if let Some(font_size) = opts.font_size {
             self.font_size = font_size;
         }
+        if let Some(font_scale) = opts.font_scale {
+            self.font_size *= font_scale;
+        }
+        if let Some(baseline_shift) = opts.baseline_shift {
+            self.baseline_shift += baseline_shift * parent_size;
+        }
         if let Some(fill) = opts.fill.as_ref() {
             self.fill = Some(fill.clone());
         }
@@ -500,6 +587,40 @@ struct TextSpan<C> {
     props: TextOptProps<C>,
 }
 
+/// A math construct, laid out over byte ranges of the text.
+/// (See [`parse_rich_text`] for the `$...$` math syntax)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MathElem {
+    /// A fraction: the numerator is stacked over the denominator with a rule in between.
+    /// Both are reduced in size. The denominator must directly follow the numerator.
+    Frac {
+        num: (usize, usize),
+        den: (usize, usize),
+    },
+    /// A square root: `sign` is the radical sign, and a rule is drawn over the radicand
+    Sqrt {
+        sign: (usize, usize),
+        radicand: (usize, usize),
+    },
+}
+
+impl MathElem {
+    /// The byte ranges of the text covered by this element
+    fn ranges(&self) -> [(usize, usize); 2] {
+        match *self {
+            MathElem::Frac { num, den } => [num, den],
+            MathElem::Sqrt { sign, radicand } => [sign, radicand],
+        }
+    }
+
+    /// The whole byte range covered by this element
+    fn extent(&self) -> (usize, usize) {
+        let [a, b] = self.ranges();
+        (a.0, b.1)
+    }
+}
+
 /// A line of rich text
 #[derive(Debug, Clone)]
 pub struct LineSpan<C>
@@ -577,11 +698,7 @@ where
     }
 
     pub fn height(&self) -> f32 {
-        self.shapes
-            .iter()
-            .map(|s| s.metrics.height())
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(0.0)
+        self.ascent() - self.descent()
     }
 
     pub fn ascent(&self) -> f32 {
@@ -596,7 +713,7 @@ where
         self.shapes
             .iter()
             .map(|s| s.metrics.descent)
-            .max_by(|a, b| a.partial_cmp(b).unwrap())
+            .min_by(|a, b| a.partial_cmp(b).unwrap())
             .unwrap_or(0.0)
     }
 
@@ -634,8 +751,11 @@ where
 
     #[cfg(debug_assertions)]
     fn assert_flat_coverage(&self) {
+        // shapes are in visual order
+        let mut shapes: Vec<_> = self.shapes.iter().collect();
+        shapes.sort_by_key(|s| s.start);
         let mut cursor = self.start;
-        for s in self.shapes.iter() {
+        for s in shapes {
             assert_eq!(s.start, cursor);
             cursor = s.end;
             s.assert_flat_coverage();
@@ -766,6 +886,7 @@ where
     end: usize,
     props: TextProps<C>,
     bbox: Option<geom::Rect>,
+    rules: Vec<geom::Rect>,
 }
 
 impl<C> PropsSpan<C>
@@ -783,6 +904,7 @@ where
             end: self.end,
             props: self.props.to_other_color(color_map),
             bbox: self.bbox,
+            rules: self.rules.clone(),
         }
     }
 
@@ -806,6 +928,11 @@ where
         // no empty spans are built
         self.bbox.unwrap()
     }
+
+    /// Rule lines drawn with the span, such as fraction bars
+    pub fn rules(&self) -> &[geom::Rect] {
+        &self.rules
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -2,8 +2,9 @@ use plotive_base::geom;
 use ttf_parser as ttf;
 
 use super::{
-    Align, Boundaries, Direction, Error, Glyph, HorAlign, Layout, LineSpan, PropsSpan, RichText,
-    RichTextBuilder, ShapeSpan, TextOptProps, TextProps, VerAlign, VerDirection, VerProgression,
+    Align, Boundaries, Direction, Error, Glyph, HorAlign, Layout, LineSpan, MathElem, Overflow,
+    PropsSpan, RichText, RichTextBuilder, ShapeSpan, TextOptProps, TextProps, TextSpan, VerAlign,
+    VerDirection, VerProgression, math,
 };
 use crate::bidi::BidiAlgo;
 use crate::font::{self, DatabaseExt};
@@ -19,6 +20,108 @@ where
     buffer: Option<rustybuzz::UnicodeBuffer>,
 }
 
+impl<C> BuilderCtx<C>
+where
+    C: Clone,
+{
+    fn take_buffer(&mut self) -> rustybuzz::UnicodeBuffer {
+        self.buffer.take().unwrap_or_default()
+    }
+}
+
+/// Shape the content of `buffer` with the given face.
+/// Clusters are offset by `start`.
+fn shape_glyphs<C>(
+    buffer: rustybuzz::UnicodeBuffer,
+    start: usize,
+    face_id: fontdb::ID,
+    props: &TextProps<C>,
+    fontdb: &fontdb::Database,
+) -> Result<(Vec<Glyph>, font::ScaledMetrics, rustybuzz::GlyphBuffer), Error>
+where
+    C: Clone,
+{
+    let (glyphs, mut metrics, buffer) = fontdb
+        .with_face_data(face_id, |data, index| -> Result<_, Error> {
+            let face = ttf::Face::parse(data, index)?;
+            let metrics = font::face_metrics(&face).scaled(props.font_size);
+            let mut hbface = rustybuzz::Face::from_face(face);
+            font::apply_hb_variations(&mut hbface, &props.font);
+
+            let buffer = rustybuzz::shape(&hbface, &[], buffer);
+
+            let mut glyphs = Vec::with_capacity(buffer.len());
+            for (i, p) in buffer.glyph_infos().iter().zip(buffer.glyph_positions()) {
+                let id = ttf::GlyphId(i.glyph_id as u16);
+                let rect = hbface.glyph_bounding_box(id).unwrap_or(ttf::Rect {
+                    x_min: 0,
+                    y_min: 0,
+                    x_max: 0,
+                    y_max: 0,
+                });
+                glyphs.push(Glyph {
+                    id,
+                    cluster: i.cluster as usize + start,
+                    x_advance: p.x_advance as f32 * metrics.scale,
+                    y_advance: p.y_advance as f32 * metrics.scale,
+                    x_offset: p.x_offset as f32 * metrics.scale,
+                    y_offset: p.y_offset as f32 * metrics.scale,
+                    ts: tiny_skia::Transform::identity(),
+                    rect,
+                })
+            }
+
+            Ok((glyphs, metrics, buffer))
+        })
+        .expect("should be a valid face id")?;
+
+    // a shifted baseline moves the whole shape
+    metrics.ascent += props.baseline_shift;
+    metrics.descent += props.baseline_shift;
+
+    Ok((glyphs, metrics, buffer))
+}
+
+/// Break a line at word boundaries so that each part fits in `max_width` if possible.
+/// The whitespace at the breaks is excluded from the parts.
+fn wrap_breaks(
+    text: &str,
+    advances: &[(usize, f32)],
+    end: usize,
+    max_width: f32,
+) -> Vec<(usize, usize)> {
+    let mut parts = Vec::new();
+    let Some(&(mut part_start, _)) = advances.first() else {
+        return parts;
+    };
+    let mut width = 0.0;
+    // the last whitespace run seen in the current part, and the width up to its end
+    let mut brk: Option<(usize, usize, f32)> = None;
+    for (idx, &(i, adv)) in advances.iter().enumerate() {
+        let c = text[i..].chars().next().unwrap();
+        let next = advances.get(idx + 1).map_or(end, |a| a.0);
+        if c.is_whitespace() {
+            width += adv;
+            brk = match brk {
+                Some((ws_start, ws_end, _)) if ws_end == i => Some((ws_start, next, width)),
+                _ => Some((i, next, width)),
+            };
+            continue;
+        }
+        width += adv;
+        if width > max_width
+            && let Some((ws_start, ws_end, ws_width)) = brk.take()
+            && ws_start > part_start
+        {
+            parts.push((part_start, ws_start));
+            part_start = ws_end;
+            width -= ws_width;
+        }
+    }
+    parts.push((part_start, end));
+    parts
+}
+
 #[derive(Debug)]
 struct PropsResolver<C>
 where
@@ -51,6 +154,7 @@ where
             outline: props.outline.clone(),
             underline: props.underline,
             strikeout: props.strikeout,
+            baseline_shift: props.baseline_shift,
         }
     }
 
@@ -66,7 +170,103 @@ where
             }
         }
     }
+
+    /// Reset the stack to the state it has when shaping reaches `pos`
+    /// (spans starting at `pos` are not pushed yet)
+    fn reset_at(&mut self, spans: &[TextSpan<C>], pos: usize) {
+        let mut open: Vec<_> = spans
+            .iter()
+            .filter(|s| s.start < pos && pos < s.end)
+            .collect();
+        open.sort_by_key(|s| s.start);
+        self.stack = open.into_iter().map(|s| s.props.clone()).collect();
+    }
 }
+
+/// Progression of the horizontal layout through a fraction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FracState {
+    Pending,
+    Num,
+    Den,
+    Done,
+}
+
+/// Horizontal layout of a fraction in a line
+#[derive(Debug)]
+struct FracLayout {
+    num: (usize, usize),
+    den: (usize, usize),
+    num_width: f32,
+    den_width: f32,
+    /// Position of the fraction, set when the numerator is reached
+    x: f32,
+    state: FracState,
+}
+
+impl FracLayout {
+    fn width(&self) -> f32 {
+        self.num_width.max(self.den_width)
+    }
+
+    /// Width saved by stacking the numerator and denominator
+    fn saving(&self) -> f32 {
+        self.num_width.min(self.den_width)
+    }
+
+    /// Move the cursor to center the numerator and denominator over each other
+    /// when a shape starting at `pos` is reached.
+    /// `fracs` must be sorted from the outermost fraction.
+    fn reach(fracs: &mut [FracLayout], pos: usize, x_cursor: &mut f32) {
+        loop {
+            let mut changed = false;
+            // innermost fractions are closed first
+            for f in fracs.iter_mut().rev() {
+                if f.state == FracState::Den && f.den.1 <= pos {
+                    *x_cursor = f.x + f.width();
+                    f.state = FracState::Done;
+                    changed = true;
+                }
+            }
+            for f in fracs.iter_mut() {
+                if f.state == FracState::Num && f.den.0 <= pos {
+                    *x_cursor = f.x + (f.width() - f.den_width) / 2.0;
+                    f.state = FracState::Den;
+                    changed = true;
+                }
+            }
+            for f in fracs.iter_mut() {
+                if f.state == FracState::Pending && f.num.0 <= pos {
+                    f.x = *x_cursor;
+                    *x_cursor += (f.width() - f.num_width) / 2.0;
+                    f.state = FracState::Num;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+}
+
+impl<C> LineSpan<C>
+where
+    C: Clone,
+{
+    /// Add a rule to the span that contains `pos`
+    fn push_rule(&mut self, pos: usize, rule: geom::Rect) {
+        let span = self
+            .shapes
+            .iter_mut()
+            .flat_map(|s| s.spans.iter_mut())
+            .find(|s| s.start <= pos && pos < s.end);
+        if let Some(span) = span {
+            span.rules.push(rule);
+        }
+    }
+}
+
 #[derive(Debug)]
 enum Justify {
     Nope,
@@ -102,7 +302,7 @@ where
         for s in &self.shapes {
             metrics.scale = metrics.scale.max(s.metrics.scale);
             metrics.ascent = metrics.ascent.max(s.metrics.ascent);
-            metrics.descent = metrics.descent.max(s.metrics.descent);
+            metrics.descent = metrics.descent.min(s.metrics.descent);
             metrics.x_height = metrics.x_height.max(s.metrics.x_height);
             metrics.cap_height = metrics.cap_height.max(s.metrics.cap_height);
             metrics.line_gap = metrics.line_gap.max(s.metrics.line_gap);
@@ -121,6 +321,24 @@ where
     fn x_advance(&self) -> f32 {
         self.shapes.iter().map(|s| s.x_advance()).sum()
     }
+
+    /// Add `spacing` to the advance of the last glyph of each cluster, except the last one
+    fn add_letter_spacing(&mut self, spacing: f32) {
+        if spacing == 0.0 {
+            return;
+        }
+        let mut glyphs = self
+            .shapes
+            .iter_mut()
+            .flat_map(|s| s.glyphs.iter_mut())
+            .peekable();
+        while let Some(glyph) = glyphs.next() {
+            match glyphs.peek() {
+                Some(next) if next.cluster != glyph.cluster => glyph.x_advance += spacing,
+                _ => (),
+            }
+        }
+    }
 }
 
 // This implementation gathers method specific to vertical text
@@ -143,18 +361,18 @@ where
 }
 
 trait Lines {
-    fn baseline(&self, idx: usize) -> f32;
+    fn baseline(&self, idx: usize, line_height: f32) -> f32;
 }
 
 impl<C> Lines for [LineSpan<C>]
 where
     C: Clone,
 {
-    fn baseline(&self, idx: usize) -> f32 {
+    fn baseline(&self, idx: usize, line_height: f32) -> f32 {
         let mut h = 0.0;
         let mut l = 0;
         while l < idx {
-            h += self[l].total_height();
+            h += self[l].total_height() * line_height;
             l += 1;
         }
         h
@@ -176,11 +394,13 @@ where
     C: Clone + PartialEq,
 {
     /// Create a RichText from this builder
-    pub(super) fn done_impl(self, fontdb: &fontdb::Database) -> Result<RichText<C>, Error> {
+    pub(super) fn done_impl(mut self, fontdb: &fontdb::Database) -> Result<RichText<C>, Error> {
         if self.text.is_empty() {
             return Ok(RichText::empty());
         }
 
+        self.add_math_spans();
+
         let bidi_algo = match &self.layout {
             Layout::Horizontal(_, _, Direction::Mixed) => BidiAlgo::Yep { default_lev: None },
             Layout::Horizontal(_, _, Direction::MixedLTR) => BidiAlgo::Yep {
@@ -222,23 +442,24 @@ where
                     was_cr = true;
                 }
                 '\n' => {
-                    lines.push(self.shape_line(
+                    self.push_line(
+                        &mut lines,
                         line_start,
                         if was_cr { i - 1 } else { i },
                         if was_cr { 2 } else { 1 },
                         fontdb,
                         &mut ctx,
-                    )?);
+                    )?;
                     line_start = i + 1;
                     was_cr = false;
                 }
                 '\u{85}' => {
-                    lines.push(self.shape_line(line_start, i, 2, fontdb, &mut ctx)?);
+                    self.push_line(&mut lines, line_start, i, 2, fontdb, &mut ctx)?;
                     line_start = i + 2;
                     was_cr = false;
                 }
                 '\u{2028}' | '\u{2029}' => {
-                    lines.push(self.shape_line(line_start, i, 3, fontdb, &mut ctx)?);
+                    self.push_line(&mut lines, line_start, i, 3, fontdb, &mut ctx)?;
                     line_start = i + 3;
                     was_cr = false;
                 }
@@ -248,11 +469,212 @@ where
             }
         }
         if line_start < self.text.len() {
-            lines.push(self.shape_line(line_start, self.text.len(), 0, fontdb, &mut ctx)?);
+            let end = self.text.len();
+            self.push_line(&mut lines, line_start, end, 0, fontdb, &mut ctx)?;
         }
         self.build_layout(lines)
     }
 
+    /// Add the spans that reduce and shift numerators and denominators.
+    /// Fractions are sorted from the outermost, so that nested spans are resolved in order.
+    fn add_math_spans(&mut self) {
+        self.math.sort_by_key(|m| {
+            let (start, end) = m.extent();
+            (start, std::cmp::Reverse(end))
+        });
+        for elem in self.math.iter() {
+            if let MathElem::Frac { num, den } = *elem {
+                for ((start, end), shift) in [(num, math::NUM_SHIFT), (den, math::DEN_SHIFT)] {
+                    self.spans.push(TextSpan {
+                        start,
+                        end,
+                        props: TextOptProps {
+                            font_scale: Some(math::SCRIPT_SCALE),
+                            baseline_shift: Some(shift),
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+        }
+    }
+
+    /// Resolve the properties of the spans that entirely cover the given range
+    fn props_over(&self, start: usize, end: usize) -> TextProps<C> {
+        let mut spans: Vec<_> = self
+            .spans
+            .iter()
+            .filter(|s| s.start <= start && end <= s.end)
+            .collect();
+        spans.sort_by_key(|s| (s.start, std::cmp::Reverse(s.end)));
+        let mut props = self.root_props.clone();
+        for s in spans {
+            props.apply_opts(&s.props);
+        }
+        props
+    }
+
+    /// Shape the line and push it to `lines`,
+    /// possibly broken or truncated if it exceeds the maximum width
+    fn push_line(
+        &self,
+        lines: &mut Vec<LineSpan<C>>,
+        start: usize,
+        end: usize,
+        eol: usize,
+        fontdb: &fontdb::Database,
+        ctx: &mut BuilderCtx<C>,
+    ) -> Result<(), Error> {
+        let line = self.shape_line(start, end, eol, fontdb, ctx)?;
+        let max_width = match (self.layout, self.max_width) {
+            (Layout::Horizontal(..), Some((max_width, overflow))) => Some((max_width, overflow)),
+            _ => None,
+        };
+        let Some((max_width, overflow)) = max_width.filter(|(w, _)| line.x_advance() > *w) else {
+            lines.push(line);
+            return Ok(());
+        };
+
+        let advances = self.char_advances(&line);
+        match overflow {
+            Overflow::Wrap => {
+                for (sub_start, sub_end) in wrap_breaks(&self.text, &advances, end, max_width) {
+                    ctx.resolver.reset_at(&self.spans, sub_start);
+                    lines.push(self.shape_line(sub_start, sub_end, 0, fontdb, ctx)?);
+                }
+            }
+            Overflow::Ellipsis => {
+                lines.push(self.shape_ellipsis(start, end, &advances, max_width, fontdb, ctx)?);
+            }
+        }
+        ctx.resolver.reset_at(&self.spans, end);
+        Ok(())
+    }
+
+    /// The advance of each char of the line, in logical order.
+    /// The advance of a cluster of several chars is given to its first char.
+    fn char_advances(&self, line: &LineSpan<C>) -> Vec<(usize, f32)> {
+        let mut by_byte = vec![0.0f32; line.end - line.start];
+        for g in line.shapes.iter().flat_map(|s| s.glyphs.iter()) {
+            by_byte[g.cluster - line.start] += g.x_advance;
+        }
+        self.text[line.start..line.end]
+            .char_indices()
+            .map(|(i, _)| (line.start + i, by_byte[i]))
+            .collect()
+    }
+
+    /// Shape the line truncated to fit `max_width` with an ellipsis
+    fn shape_ellipsis(
+        &self,
+        start: usize,
+        end: usize,
+        advances: &[(usize, f32)],
+        max_width: f32,
+        fontdb: &fontdb::Database,
+        ctx: &mut BuilderCtx<C>,
+    ) -> Result<LineSpan<C>, Error> {
+        const ELLIPSIS: &str = "\u{2026}";
+
+        let first_char = advances.get(1).map_or(end, |a| a.0);
+        let (_, ell_width) = self.shape_text(
+            ELLIPSIS,
+            start,
+            &self.props_over(start, first_char),
+            fontdb,
+            ctx,
+        )?;
+
+        let mut cut = start;
+        let mut width = 0.0;
+        for (i, adv) in advances.iter().copied() {
+            width += adv;
+            if width + self.letter_spacing + ell_width > max_width {
+                break;
+            }
+            cut = i + self.text[i..].chars().next().map_or(0, char::len_utf8);
+        }
+        cut = start + self.text[start..cut].trim_end().len();
+
+        ctx.resolver.reset_at(&self.spans, start);
+        let mut line = self.shape_line(start, cut, 0, fontdb, ctx)?;
+        if let Some(last) = line.shapes.last_mut().and_then(|s| s.glyphs.last_mut()) {
+            last.x_advance += self.letter_spacing;
+        }
+
+        let last_char = self.text[..cut]
+            .char_indices()
+            .next_back()
+            .map_or(start, |(i, _)| i)
+            .max(start);
+        let props = if last_char < cut {
+            self.props_over(last_char, cut)
+        } else {
+            self.props_over(start, first_char)
+        };
+        let (shape, _) = self.shape_text(ELLIPSIS, cut, &props, fontdb, ctx)?;
+        // the ellipsis is at the end of the text, visually on the left of right to left lines
+        let idx = match line.main_dir {
+            rustybuzz::Direction::RightToLeft => 0,
+            _ => line.shapes.len(),
+        };
+        line.shapes.insert(
+            idx,
+            ShapeSpan {
+                end,
+                spans: vec![PropsSpan {
+                    start: cut,
+                    end,
+                    props,
+                    bbox: None,
+                    rules: Vec::new(),
+                }],
+                ..shape
+            },
+        );
+        line.end = end;
+        Ok(line)
+    }
+
+    /// Shape a text that is not part of the builder text, such as the ellipsis.
+    /// All glyphs are assigned to the cluster `pos`.
+    /// Returns the shape and its advance.
+    fn shape_text(
+        &self,
+        txt: &str,
+        pos: usize,
+        props: &TextProps<C>,
+        fontdb: &fontdb::Database,
+        ctx: &mut BuilderCtx<C>,
+    ) -> Result<(ShapeSpan<C>, f32), Error> {
+        let face_id = fontdb
+            .select_faces_for_str(&props.font, txt)
+            .and_then(|faces| faces.first().map(|(_, id)| *id))
+            .ok_or_else(|| Error::NoSuchFont(props.font.clone()))?;
+        let mut buffer = ctx.take_buffer();
+        buffer.push_str(txt);
+        buffer.set_direction(ctx.bidi_algo.start_dir());
+        buffer.guess_segment_properties();
+
+        let (mut glyphs, metrics, buffer) = shape_glyphs(buffer, pos, face_id, props, fontdb)?;
+        ctx.buffer = Some(buffer.clear());
+        for g in glyphs.iter_mut() {
+            g.cluster = pos;
+        }
+        let advance = glyphs.iter().map(|g| g.x_advance).sum();
+        let shape = ShapeSpan {
+            start: pos,
+            end: pos,
+            spans: Vec::new(),
+            face_id,
+            glyphs,
+            metrics,
+            y_baseline: f32::NAN,
+            bbox: None,
+        };
+        Ok((shape, advance))
+    }
+
     fn shape_line(
         &self,
         start: usize,
@@ -269,9 +691,10 @@ where
         //  - a change of text direction (LTR or RTL)
         //  - a paragraph separator (unlikely to happen as lines are already split)
 
+        // the runs must be computed first, as they can set the direction of the paragraph
+        let bidi_runs = ctx.bidi_algo.visual_runs(line_txt, start);
         let main_dir = ctx.bidi_algo.start_dir();
         let mut cur_dir = main_dir;
-        let bidi_runs = ctx.bidi_algo.visual_runs(line_txt, start);
 
         let mut boundaries = Boundaries::new(start, end);
         for run in bidi_runs.iter() {
@@ -292,16 +715,38 @@ where
                     cur_dir = run.dir;
                 }
             }
-            shapes.push(self.shape_span(span_start, span_end, cur_dir, fontdb, ctx)?);
+            shapes.extend(self.shape_span(span_start, span_end, cur_dir, fontdb, ctx)?);
         }
 
-        Ok(LineSpan {
+        // the shapes are built in logical order to resolve the span properties,
+        // and are reordered visually for the layout
+        shapes.sort_by_key(|s| {
+            let run = bidi_runs
+                .iter()
+                .position(|r| r.start <= s.start && s.start < r.end)
+                .unwrap_or(0);
+            let rtl = bidi_runs[run].dir == rustybuzz::Direction::RightToLeft;
+            (
+                run,
+                if rtl {
+                    -(s.start as isize)
+                } else {
+                    s.start as isize
+                },
+            )
+        });
+
+        let mut line = LineSpan {
             start,
             end,
             shapes,
             main_dir,
             bbox: None,
-        })
+        };
+        if let Layout::Horizontal(..) = self.layout {
+            line.add_letter_spacing(self.letter_spacing);
+        }
+        Ok(line)
     }
 
     fn shape_span(
@@ -311,7 +756,7 @@ where
         dir: rustybuzz::Direction,
         fontdb: &fontdb::Database,
         ctx: &mut BuilderCtx<C>,
-    ) -> Result<ShapeSpan<C>, Error> {
+    ) -> Result<Vec<ShapeSpan<C>>, Error> {
         debug_assert!(self.text.is_char_boundary(start) && self.text.is_char_boundary(end));
 
         let txt = &self.text[start..end];
@@ -335,6 +780,7 @@ where
                 end: span_end,
                 props: ctx.resolver.resolved(),
                 bbox: None,
+                rules: Vec::new(),
             });
             for span in self.spans.iter() {
                 if span.end == span_end {
@@ -346,15 +792,50 @@ where
         // shape_props is only interested in the font and font_size,
         // which are all the same for the subspans within the shape
         let shape_props = &props_spans.first().unwrap().props;
-        let face_id = fontdb
-            .select_face_for_str(&shape_props.font, txt)
-            .or_else(|| fontdb.select_face(&shape_props.font))
+        let face_spans = fontdb
+            .select_faces_for_str(&shape_props.font, txt)
             .ok_or_else(|| Error::NoSuchFont(shape_props.font.clone()))?;
 
-        let mut buffer = ctx
-            .buffer
-            .take()
-            .unwrap_or_else(|| rustybuzz::UnicodeBuffer::new());
+        if let [(_, face_id)] = face_spans.as_slice() {
+            let shape =
+                self.shape_face_span((start, end), dir, *face_id, props_spans, fontdb, ctx)?;
+            return Ok(vec![shape]);
+        }
+
+        // one shape per face, each with the props spans it overlaps
+        let mut shapes = Vec::with_capacity(face_spans.len());
+        for (range, face_id) in face_spans {
+            let start = start + range.start;
+            let end = start + range.len();
+            let spans = props_spans
+                .iter()
+                .filter(|p| p.start < end && start < p.end)
+                .map(|p| PropsSpan {
+                    start: p.start.max(start),
+                    end: p.end.min(end),
+                    props: p.props.clone(),
+                    bbox: None,
+                    rules: Vec::new(),
+                })
+                .collect();
+            shapes.push(self.shape_face_span((start, end), dir, face_id, spans, fontdb, ctx)?);
+        }
+        Ok(shapes)
+    }
+
+    fn shape_face_span(
+        &self,
+        (start, end): (usize, usize),
+        dir: rustybuzz::Direction,
+        face_id: fontdb::ID,
+        props_spans: Vec<PropsSpan<C>>,
+        fontdb: &fontdb::Database,
+        ctx: &mut BuilderCtx<C>,
+    ) -> Result<ShapeSpan<C>, Error> {
+        let txt = &self.text[start..end];
+        let shape_props = &props_spans.first().unwrap().props;
+
+        let mut buffer = ctx.take_buffer();
         buffer.push_str(txt);
         if start != 0 {
             buffer.set_pre_context(&self.text[..start]);
@@ -365,40 +846,7 @@ where
         buffer.set_direction(dir);
         buffer.guess_segment_properties();
 
-        let (glyphs, metrics, buffer) = fontdb
-            .with_face_data(face_id, |data, index| -> Result<_, Error> {
-                let face = ttf::Face::parse(data, index)?;
-                let metrics = font::face_metrics(&face).scaled(shape_props.font_size);
-                let mut hbface = rustybuzz::Face::from_face(face);
-                font::apply_hb_variations(&mut hbface, &shape_props.font);
-
-                let buffer = rustybuzz::shape(&hbface, &[], buffer);
-
-                let mut glyphs = Vec::with_capacity(buffer.len());
-                for (i, p) in buffer.glyph_infos().iter().zip(buffer.glyph_positions()) {
-                    let id = ttf::GlyphId(i.glyph_id as u16);
-                    let rect = hbface.glyph_bounding_box(id).unwrap_or(ttf::Rect {
-                        x_min: 0,
-                        y_min: 0,
-                        x_max: 0,
-                        y_max: 0,
-                    });
-                    glyphs.push(Glyph {
-                        id,
-                        cluster: i.cluster as usize + start,
-                        x_advance: p.x_advance as f32 * metrics.scale,
-                        y_advance: p.y_advance as f32 * metrics.scale,
-                        x_offset: p.x_offset as f32 * metrics.scale,
-                        y_offset: p.y_offset as f32 * metrics.scale,
-                        ts: tiny_skia::Transform::identity(),
-                        rect,
-                    })
-                }
-
-                Ok((glyphs, metrics, buffer))
-            })
-            .expect("should be a valid face id")?;
-
+        let (glyphs, metrics, buffer) = shape_glyphs(buffer, start, face_id, shape_props, fontdb)?;
         ctx.buffer = Some(buffer.clear());
 
         let shape = ShapeSpan {
@@ -440,6 +888,7 @@ where
         Ok(RichText {
             text: self.text,
             layout,
+            line_height: self.line_height,
             lines,
             bbox,
         })
@@ -455,14 +904,17 @@ where
         // y-cursor must be placed at the baseline of the first line
         let mut y_cursor = match ver_align {
             VerAlign::Top => lines[0].ascent(),
-            VerAlign::Bottom => lines[lines_len - 1].descent() - lines.baseline(lines_len - 1),
+            VerAlign::Bottom => {
+                lines[lines_len - 1].descent() - lines.baseline(lines_len - 1, self.line_height)
+            }
             VerAlign::Center => {
                 let top = lines[0].ascent();
-                let bottom = lines[lines_len - 1].descent() - lines.baseline(lines_len - 1);
+                let bottom = lines[lines_len - 1].descent()
+                    - lines.baseline(lines_len - 1, self.line_height);
                 (top + bottom) / 2.0
             }
             VerAlign::Line(line, align) => {
-                let baseline = lines.baseline(line);
+                let baseline = lines.baseline(line, self.line_height);
                 let lst_metrics = lines[lines_len - 1].metrics();
                 match align {
                     line::VerAlign::Bottom => lst_metrics.descent - baseline,
@@ -476,23 +928,26 @@ where
 
         for lidx in 0..lines_len {
             if lidx != 0 {
-                y_cursor += lines[lidx].height();
+                y_cursor += lines[lidx].height() * self.line_height;
             }
 
             self.layout_horizontal_line(&mut lines[lidx], y_cursor, align);
 
-            y_cursor += lines[lidx].gap();
+            y_cursor += lines[lidx].gap() * self.line_height;
         }
 
         Ok(())
     }
 
     fn layout_horizontal_line(&self, line: &mut LineSpan<C>, y_baseline: f32, align: Align) {
+        let mut fracs = self.line_fracs(line);
+
         let ws = self.text[line.start..line.end]
             .chars()
             .filter(|c| c.is_whitespace())
             .count();
-        let width = line.x_advance();
+        // stacked fractions take less room than their glyphs laid out in a row
+        let width = line.x_advance() - fracs.iter().map(FracLayout::saving).sum::<f32>();
         let (width, justify) = match align {
             Align::Justify(sz) => {
                 let sz = sz.max(width);
@@ -527,11 +982,13 @@ where
 
         let y_flip = geom::Transform::from_scale(1.0, -1.0);
         for shape in line.shapes.iter_mut() {
+            FracLayout::reach(&mut fracs, shape.start, &mut x_cursor);
             let shape_start = x_cursor;
+            let shift = shape.spans[0].props.baseline_shift;
             let scale_ts = geom::Transform::from_scale(shape.metrics.scale, shape.metrics.scale);
             for glyph in shape.glyphs.iter_mut() {
                 let x = x_cursor + glyph.x_offset;
-                let y = y_cursor - glyph.y_offset;
+                let y = y_cursor - glyph.y_offset - shift;
                 let pos_ts = geom::Transform::from_translate(x, y);
                 glyph.ts = y_flip.post_concat(scale_ts).post_concat(pos_ts);
                 let glyph_start = x_cursor;
@@ -561,15 +1018,138 @@ where
                     }
                 }
             }
-            shape.y_baseline = y_baseline;
+            shape.y_baseline = y_baseline - shift;
             shape.bbox = Some(geom::Rect::from_trbl(top, x_cursor, bottom, shape_start));
         }
+        FracLayout::reach(&mut fracs, line.end, &mut x_cursor);
         line.bbox = Some(geom::Rect::from_trbl(
             y_baseline - line.ascent(),
             x_cursor,
             y_baseline - line.descent(),
             x_start,
         ));
+
+        self.add_math_rules(line, &fracs, y_baseline);
+    }
+
+    /// The fractions of the line, sorted from the outermost
+    fn line_fracs(&self, line: &LineSpan<C>) -> Vec<FracLayout> {
+        let advance = |(start, end): (usize, usize)| -> f32 {
+            line.shapes
+                .iter()
+                .flat_map(|s| s.glyphs.iter())
+                .filter(|g| start <= g.cluster && g.cluster < end)
+                .map(|g| g.x_advance)
+                .sum()
+        };
+
+        let mut elems: Vec<_> = self
+            .math
+            .iter()
+            .filter_map(|m| match *m {
+                MathElem::Frac { num, den } if line.start <= num.0 && den.1 <= line.end => {
+                    Some((num, den))
+                }
+                _ => None,
+            })
+            .collect();
+
+        // innermost first, so that nested fractions are accounted in the width of their parent
+        elems.sort_by_key(|(num, den)| den.1 - num.0);
+        let mut fracs: Vec<FracLayout> = Vec::with_capacity(elems.len());
+        for (num, den) in elems {
+            let width = |(start, end): (usize, usize)| {
+                let saving: f32 = fracs
+                    .iter()
+                    .filter(|f| start <= f.num.0 && f.den.1 <= end)
+                    .map(FracLayout::saving)
+                    .sum();
+                advance((start, end)) - saving
+            };
+            let num_width = width(num);
+            let den_width = width(den);
+            fracs.push(FracLayout {
+                num,
+                den,
+                num_width,
+                den_width,
+                x: 0.0,
+                state: FracState::Pending,
+            });
+        }
+        fracs.sort_by_key(|f| (f.num.0, std::cmp::Reverse(f.den.1)));
+        fracs
+    }
+
+    /// Add the fraction bars and the radical rules of the line,
+    /// and stretch radical signs that are shorter than their radicand
+    fn add_math_rules(&self, line: &mut LineSpan<C>, fracs: &[FracLayout], y_baseline: f32) {
+        for f in fracs {
+            let base = self.props_over(f.num.0, f.den.1);
+            let y = y_baseline - base.baseline_shift - math::FRAC_AXIS * base.font_size;
+            let thickness = math::RULE_THICKNESS * base.font_size;
+            let rule = geom::Rect::from_trbl(
+                y - thickness / 2.0,
+                f.x + f.width(),
+                y + thickness / 2.0,
+                f.x,
+            );
+            line.push_rule(f.num.0, rule);
+        }
+
+        for elem in self.math.iter() {
+            let MathElem::Sqrt { sign, radicand } = *elem else {
+                continue;
+            };
+            if sign.0 < line.start || line.end < radicand.1 {
+                continue;
+            }
+            let Some(sign_glyph) = line
+                .shapes
+                .iter_mut()
+                .flat_map(|s| s.glyphs.iter_mut())
+                .find(|g| g.cluster == sign.0)
+            else {
+                continue;
+            };
+
+            let base = self.props_over(sign.0, radicand.1);
+            let gap = math::RADICAL_GAP * base.font_size;
+            let thickness = math::RULE_THICKNESS * base.font_size;
+
+            let sign_bbox = sign_glyph.visual_bbox();
+            let sign_baseline = sign_glyph.ts.ty;
+
+            let (rad_top, rad_right) = line
+                .shapes
+                .iter()
+                .flat_map(|s| s.glyphs.iter())
+                .filter(|g| radicand.0 <= g.cluster && g.cluster < radicand.1)
+                .fold((sign_bbox.top(), sign_bbox.right()), |(top, right), g| {
+                    let g_right = g.ts.tx - g.x_offset + g.x_advance;
+                    (top.min(g.visual_bbox().top()), right.max(g_right))
+                });
+
+            let top = sign_bbox.top().min(rad_top - gap - thickness);
+            if top < sign_bbox.top() {
+                // stretch the sign vertically from its baseline up to the rule
+                let sy = (sign_baseline - top) / (sign_baseline - sign_bbox.top());
+                let stretch =
+                    geom::Transform::from_row(1.0, 0.0, 0.0, sy, 0.0, sign_baseline * (1.0 - sy));
+                if let Some(g) = line
+                    .shapes
+                    .iter_mut()
+                    .flat_map(|s| s.glyphs.iter_mut())
+                    .find(|g| g.cluster == sign.0)
+                {
+                    g.ts = g.ts.post_concat(stretch);
+                }
+            }
+
+            let left = sign_bbox.right() - thickness;
+            let rule = geom::Rect::from_trbl(top, rad_right, top + thickness, left);
+            line.push_rule(sign.0, rule);
+        }
     }
 
     fn build_vertical_layout(&self, cols: &mut Vec<LineSpan<C>>) -> Result<VerProgression, Error> {
@@ -722,4 +1302,161 @@ mod tests {
         assert_eq!(text.lines[0].shapes[0].spans[1].props.underline, true);
         assert_eq!(text.lines[1].shapes[0].spans[0].props.underline, false);
     }
+
+    #[test]
+    fn wrap_breaks_at_whitespace() {
+        // every char is 1.0 wide
+        let text = "aa bb  cc dddd";
+        let advances: Vec<_> = text.char_indices().map(|(i, _)| (i, 1.0)).collect();
+        let parts = wrap_breaks(text, &advances, text.len(), 5.0);
+        assert_eq!(parts, vec![(0, 5), (7, 9), (10, 14)]);
+
+        // a single word wider than the max width is not broken
+        let parts = wrap_breaks(text, &advances, text.len(), 2.0);
+        assert_eq!(parts, vec![(0, 2), (3, 5), (7, 9), (10, 14)]);
+    }
+
+    #[test]
+    fn wrap_long_line() {
+        let db = bundled_font_db();
+        let text = "Some rather long text\nshort";
+        let builder: RichTextBuilder<ColorU8> =
+            RichTextBuilder::new(text.to_string(), TextProps::new(12.0));
+        let unwrapped = builder.clone().done(&db).unwrap();
+        let max_width = unwrapped.width() * 0.6;
+        let wrapped = builder
+            .with_max_width(max_width, Overflow::Wrap)
+            .done(&db)
+            .unwrap();
+        wrapped.assert_flat_coverage();
+
+        let lines: Vec<_> = wrapped
+            .lines
+            .iter()
+            .map(|l| &text[l.start..l.end])
+            .collect();
+        assert_eq!(lines, vec!["Some rather", "long text", "short"]);
+        assert!(wrapped.width() <= max_width);
+        assert!(wrapped.height() > unwrapped.height());
+    }
+
+    #[test]
+    fn ellipsis_long_line() {
+        let db = bundled_font_db();
+        let text = "Some rather long text";
+        let builder: RichTextBuilder<ColorU8> =
+            RichTextBuilder::new(text.to_string(), TextProps::new(12.0));
+        let full = builder.clone().done(&db).unwrap();
+        let max_width = full.width() / 2.0;
+        let truncated = builder
+            .with_max_width(max_width, Overflow::Ellipsis)
+            .done(&db)
+            .unwrap();
+        truncated.assert_flat_coverage();
+
+        assert_eq!(truncated.lines.len(), 1);
+        let line = &truncated.lines[0];
+        assert_eq!(line.end, text.len());
+        let ellipsis = line.shapes.last().unwrap();
+        assert_eq!(ellipsis.glyphs.len(), 1);
+        assert_eq!(&text[line.start..ellipsis.start], "Some rat");
+        assert!(truncated.width() <= max_width);
+        assert!(truncated.width() > max_width * 0.8);
+    }
+
+    #[test]
+    fn letter_spacing_widens_lines() {
+        let db = bundled_font_db();
+        let text = "Some text\nabc";
+        let builder: RichTextBuilder<ColorU8> =
+            RichTextBuilder::new(text.to_string(), TextProps::new(12.0));
+        let normal = builder.clone().done(&db).unwrap();
+        let spaced = builder.with_letter_spacing(2.0).done(&db).unwrap();
+
+        // the spacing is between the clusters, not after the last one
+        let widths = |t: &RichText<ColorU8>| -> Vec<f32> {
+            t.lines.iter().map(|l| l.bbox.unwrap().width()).collect()
+        };
+        let (normal, spaced) = (widths(&normal), widths(&spaced));
+        assert!((spaced[0] - normal[0] - 8.0 * 2.0).abs() < 1e-3);
+        assert!((spaced[1] - normal[1] - 2.0 * 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn line_height_factor() {
+        let db = bundled_font_db();
+        let builder: RichTextBuilder<ColorU8> =
+            RichTextBuilder::new("Line 1\nLine 2\nLine 3".to_string(), TextProps::new(12.0))
+                .with_layout(Layout::Horizontal(
+                    Align::Start,
+                    VerAlign::Top,
+                    Direction::default(),
+                ));
+        let normal = builder.clone().done(&db).unwrap();
+        let loose = builder.with_line_height(1.5).done(&db).unwrap();
+
+        let baseline_gap = |t: &RichText<ColorU8>| {
+            t.lines[1].shapes[0].y_baseline - t.lines[0].shapes[0].y_baseline
+        };
+        assert!((baseline_gap(&loose) - 1.5 * baseline_gap(&normal)).abs() < 1e-3);
+        // the first line stays at the top, and the bounding box covers the added space
+        assert_eq!(loose.bbox.unwrap().top(), normal.bbox.unwrap().top());
+        let added = 2.0 * 0.5 * baseline_gap(&normal);
+        assert!((loose.height() - normal.height() - added).abs() < 1e-3);
+    }
+
+    /// X position of the glyph of the char at byte `idx`
+    fn glyph_x(text: &RichText<ColorU8>, idx: usize) -> f32 {
+        text.lines
+            .iter()
+            .flat_map(|l| l.shapes.iter())
+            .flat_map(|s| s.glyphs.iter())
+            .find(|g| g.cluster == idx)
+            .unwrap()
+            .ts
+            .tx
+    }
+
+    #[test]
+    fn bidi_embedded_rtl() {
+        let db = bundled_font_db();
+        // Hebrew word embedded in a left to right paragraph
+        let txt = "abc \u{5d0}\u{5d1}\u{5d2} def";
+        let text: RichText<ColorU8> = RichTextBuilder::new(txt.to_string(), TextProps::new(12.0))
+            .done(&db)
+            .unwrap();
+        text.assert_flat_coverage();
+        assert_eq!(text.lines[0].main_dir, rustybuzz::Direction::LeftToRight);
+
+        let alef = txt.find('\u{5d0}').unwrap();
+        let gimel = txt.find('\u{5d2}').unwrap();
+        assert!(glyph_x(&text, 0) < glyph_x(&text, gimel));
+        assert!(glyph_x(&text, gimel) < glyph_x(&text, alef));
+        assert!(glyph_x(&text, alef) < glyph_x(&text, txt.find('d').unwrap()));
+    }
+
+    #[test]
+    fn bidi_rtl_paragraph() {
+        let db = bundled_font_db();
+        // right to left paragraph, with embedded numbers and latin word
+        let txt = "\u{5d0}\u{5d1} 123 abc";
+        let text: RichText<ColorU8> = RichTextBuilder::new(txt.to_string(), TextProps::new(12.0))
+            .done(&db)
+            .unwrap();
+        text.assert_flat_coverage();
+        assert_eq!(text.lines[0].main_dir, rustybuzz::Direction::RightToLeft);
+
+        // the paragraph starts on the right, and the embedded runs keep their order
+        let alef = glyph_x(&text, 0);
+        let one = glyph_x(&text, txt.find('1').unwrap());
+        let three = glyph_x(&text, txt.find('3').unwrap());
+        let a = glyph_x(&text, txt.find('a').unwrap());
+        let c = glyph_x(&text, txt.find('c').unwrap());
+        assert!(a < c && c < one && one < three && three < alef);
+
+        // start alignment is on the right for a right to left paragraph
+        let bbox = text.bbox.unwrap();
+        assert!(bbox.right().abs() < 1e-3);
+        assert!(bbox.left() < 0.0);
+    }
 }
@@ -0,0 +1,434 @@
+//! Minimal math mode for rich text.
+//!
+//! Supported are symbols (e.g. `\alpha`, `\sum`, `\infty`), superscripts (`^`),
+//! subscripts (`_`), fractions (`\frac{num}{den}`) and square roots (`\sqrt{x}`).
+//! Latin letters are set in italic, and `-` is replaced by the minus sign.
+//! Spaces are kept as written.
+
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use super::parse::{ParseRichTextError, Pos};
+use super::{MathElem, TextOptProps};
+use crate::font;
+
+/// Font scale of superscripts, subscripts, numerators and denominators
+pub(super) const SCRIPT_SCALE: f32 = 0.7;
+/// Baseline shift of superscripts, in em of the parent font size
+const SUP_SHIFT: f32 = 0.4;
+/// Baseline shift of subscripts, in em of the parent font size
+const SUB_SHIFT: f32 = -0.2;
+/// Baseline shift of numerators, in em of the parent font size
+pub(super) const NUM_SHIFT: f32 = 0.55;
+/// Baseline shift of denominators, in em of the parent font size
+pub(super) const DEN_SHIFT: f32 = -0.4;
+/// Height of the fraction bar above the baseline, in em of the parent font size
+pub(super) const FRAC_AXIS: f32 = 0.27;
+/// Thickness of fraction bars and radical rules, in em of the parent font size
+pub(super) const RULE_THICKNESS: f32 = 0.05;
+/// Minimum gap between a radicand and the rule above it, in em of the parent font size
+pub(super) const RADICAL_GAP: f32 = 0.1;
+
+/// Output of the math parser, appended to the text being parsed
+pub(super) struct MathOutput<'a, C> {
+    pub text: &'a mut String,
+    pub prop_spans: &'a mut Vec<(Pos, Pos, TextOptProps<C>)>,
+    pub math: &'a mut Vec<MathElem>,
+}
+
+/// Parse the math source `src`, found at `offset` in the rich text input
+pub(super) fn parse_math<C>(
+    src: &str,
+    offset: Pos,
+    out: MathOutput<'_, C>,
+) -> Result<(), ParseRichTextError> {
+    let mut parser = MathParser {
+        src,
+        offset,
+        chars: src.char_indices().peekable(),
+        out,
+        last_italic: None,
+    };
+    parser.parse_seq(None)
+}
+
+struct MathParser<'a, 'o, C> {
+    src: &'a str,
+    offset: Pos,
+    chars: Peekable<CharIndices<'a>>,
+    out: MathOutput<'o, C>,
+    /// Index of the last italic span in `out.prop_spans`
+    last_italic: Option<usize>,
+}
+
+impl<C> MathParser<'_, '_, C> {
+    fn pos(&mut self) -> Pos {
+        self.offset + self.chars.peek().map_or(self.src.len(), |(i, _)| *i)
+    }
+
+    fn error(&self, start: Pos, end: Pos, reason: &str) -> ParseRichTextError {
+        ParseRichTextError::InvalidMath((start, end), reason.to_string())
+    }
+
+    /// Parse atoms until the end of the source, or until the closing brace of a group
+    /// opened at `group_start`
+    fn parse_seq(&mut self, group_start: Option<Pos>) -> Result<(), ParseRichTextError> {
+        loop {
+            match (self.chars.peek(), group_start) {
+                (None, None) => return Ok(()),
+                (None, Some(start)) => {
+                    let end = self.pos();
+                    return Err(self.error(start, end, "unclosed group"));
+                }
+                (Some((_, '}')), Some(_)) => {
+                    self.chars.next();
+                    return Ok(());
+                }
+                (Some((_, '}')), None) => {
+                    let start = self.pos();
+                    return Err(self.error(start, start + 1, "unmatched closing brace"));
+                }
+                _ => self.parse_atom()?,
+            }
+        }
+    }
+
+    /// Parse the argument of a script or command: a group, a command or a single character
+    fn parse_arg(&mut self) -> Result<(Pos, Pos), ParseRichTextError> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        let pos = self.pos();
+        if self.chars.peek().is_none() {
+            return Err(self.error(pos, pos, "missing argument"));
+        }
+        let start = self.out.text.len();
+        self.parse_atom()?;
+        Ok((start, self.out.text.len()))
+    }
+
+    fn parse_atom(&mut self) -> Result<(), ParseRichTextError> {
+        let start = self.pos();
+        let Some((_, c)) = self.chars.next() else {
+            return Ok(());
+        };
+        match c {
+            '{' => self.parse_seq(Some(start)),
+            '^' | '_' => {
+                let (s, e) = self.parse_arg()?;
+                let shift = if c == '^' { SUP_SHIFT } else { SUB_SHIFT };
+                self.out.prop_spans.push((
+                    s,
+                    e,
+                    TextOptProps {
+                        font_scale: Some(SCRIPT_SCALE),
+                        baseline_shift: Some(shift),
+                        ..Default::default()
+                    },
+                ));
+                Ok(())
+            }
+            '\\' => self.parse_command(start),
+            '-' => {
+                self.out.text.push('\u{2212}');
+                Ok(())
+            }
+            c if c.is_ascii_alphabetic() => {
+                let s = self.out.text.len();
+                self.out.text.push(c);
+                self.push_italic(s, self.out.text.len());
+                Ok(())
+            }
+            c => {
+                self.out.text.push(c);
+                Ok(())
+            }
+        }
+    }
+
+    fn parse_command(&mut self, start: Pos) -> Result<(), ParseRichTextError> {
+        let mut name = String::new();
+        while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_alphabetic()) {
+            name.push(c);
+        }
+        if name.is_empty() {
+            let Some((_, c)) = self.chars.next() else {
+                return Err(self.error(start, start + 1, "unterminated command"));
+            };
+            let c = match c {
+                ',' => '\u{2009}',
+                ';' => '\u{2005}',
+                c if "{}$\\_^[ ".contains(c) => c,
+                _ => {
+                    let end = self.pos();
+                    return Err(self.error(start, end, "unknown command"));
+                }
+            };
+            self.out.text.push(c);
+            return Ok(());
+        }
+
+        match name.as_str() {
+            "frac" => {
+                let num = self.parse_arg()?;
+                let den = self.parse_arg()?;
+                self.out.math.push(MathElem::Frac { num, den });
+                Ok(())
+            }
+            "sqrt" => {
+                let s = self.out.text.len();
+                self.out.text.push('\u{221A}');
+                let sign = (s, self.out.text.len());
+                let radicand = self.parse_arg()?;
+                self.out.math.push(MathElem::Sqrt { sign, radicand });
+                Ok(())
+            }
+            _ => match symbol(&name) {
+                Some(c) => {
+                    self.out.text.push(c);
+                    Ok(())
+                }
+                None => {
+                    let end = self.pos();
+                    Err(self.error(start, end, "unknown command"))
+                }
+            },
+        }
+    }
+
+    /// Set the given range in italic, merging with the previous italic span if contiguous
+    fn push_italic(&mut self, start: Pos, end: Pos) {
+        let last = self.out.prop_spans.len().checked_sub(1);
+        if let Some(idx) = last
+            && self.last_italic == Some(idx)
+            && self.out.prop_spans[idx].1 == start
+        {
+            self.out.prop_spans[idx].1 = end;
+            return;
+        }
+        self.last_italic = Some(self.out.prop_spans.len());
+        self.out.prop_spans.push((
+            start,
+            end,
+            TextOptProps {
+                font_style: Some(font::Style::Italic),
+                ..Default::default()
+            },
+        ));
+    }
+}
+
+/// Look-up the symbol of a math command
+fn symbol(name: &str) -> Option<char> {
+    SYMBOLS
+        .binary_search_by(|(n, _)| n.cmp(&name))
+        .ok()
+        .map(|idx| SYMBOLS[idx].1)
+}
+
+/// Math symbols, sorted by command name
+const SYMBOLS: &[(&str, char)] = &[
+    ("Delta", 'Δ'),
+    ("Gamma", 'Γ'),
+    ("Im", 'ℑ'),
+    ("Lambda", 'Λ'),
+    ("Leftarrow", '⇐'),
+    ("Leftrightarrow", '⇔'),
+    ("Omega", 'Ω'),
+    ("Phi", 'Φ'),
+    ("Pi", 'Π'),
+    ("Psi", 'Ψ'),
+    ("Re", 'ℜ'),
+    ("Rightarrow", '⇒'),
+    ("Sigma", 'Σ'),
+    ("Theta", 'Θ'),
+    ("Upsilon", 'Υ'),
+    ("Xi", 'Ξ'),
+    ("aleph", 'ℵ'),
+    ("alpha", 'α'),
+    ("angle", '∠'),
+    ("approx", '≈'),
+    ("ast", '∗'),
+    ("beta", 'β'),
+    ("bullet", '•'),
+    ("cap", '∩'),
+    ("cdot", '·'),
+    ("cdots", '⋯'),
+    ("chi", 'χ'),
+    ("circ", '∘'),
+    ("cup", '∪'),
+    ("dagger", '†'),
+    ("degree", '°'),
+    ("delta", 'δ'),
+    ("div", '÷'),
+    ("downarrow", '↓'),
+    ("ell", 'ℓ'),
+    ("emptyset", '∅'),
+    ("epsilon", 'ϵ'),
+    ("equiv", '≡'),
+    ("eta", 'η'),
+    ("exists", '∃'),
+    ("forall", '∀'),
+    ("gamma", 'γ'),
+    ("ge", '≥'),
+    ("geq", '≥'),
+    ("gg", '≫'),
+    ("hbar", 'ℏ'),
+    ("in", '∈'),
+    ("infty", '∞'),
+    ("int", '∫'),
+    ("iota", 'ι'),
+    ("kappa", 'κ'),
+    ("lambda", 'λ'),
+    ("langle", '⟨'),
+    ("lceil", '⌈'),
+    ("ldots", '…'),
+    ("le", '≤'),
+    ("leftarrow", '←'),
+    ("leftrightarrow", '↔'),
+    ("leq", '≤'),
+    ("lfloor", '⌊'),
+    ("ll", '≪'),
+    ("mapsto", '↦'),
+    ("mp", '∓'),
+    ("mu", 'μ'),
+    ("nabla", '∇'),
+    ("ne", '≠'),
+    ("neg", '¬'),
+    ("neq", '≠'),
+    ("notin", '∉'),
+    ("nu", 'ν'),
+    ("oint", '∮'),
+    ("omega", 'ω'),
+    ("parallel", '∥'),
+    ("partial", '∂'),
+    ("perp", '⊥'),
+    ("phi", 'ϕ'),
+    ("pi", 'π'),
+    ("pm", '±'),
+    ("prime", '′'),
+    ("prod", '∏'),
+    ("propto", '∝'),
+    ("psi", 'ψ'),
+    ("quad", '\u{2003}'),
+    ("rangle", '⟩'),
+    ("rceil", '⌉'),
+    ("rfloor", '⌋'),
+    ("rho", 'ρ'),
+    ("rightarrow", '→'),
+    ("sigma", 'σ'),
+    ("sim", '∼'),
+    ("simeq", '≃'),
+    ("star", '⋆'),
+    ("subset", '⊂'),
+    ("sum", '∑'),
+    ("supset", '⊃'),
+    ("tau", 'τ'),
+    ("theta", 'θ'),
+    ("times", '×'),
+    ("to", '→'),
+    ("uparrow", '↑'),
+    ("upsilon", 'υ'),
+    ("varepsilon", 'ε'),
+    ("varphi", 'φ'),
+    ("vartheta", 'ϑ'),
+    ("vee", '∨'),
+    ("wedge", '∧'),
+    ("xi", 'ξ'),
+    ("zeta", 'ζ'),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use plotive_base::ColorU8;
+
+    fn parse(
+        src: &str,
+    ) -> (
+        String,
+        Vec<(Pos, Pos, TextOptProps<ColorU8>)>,
+        Vec<MathElem>,
+    ) {
+        let mut text = String::new();
+        let mut prop_spans = Vec::new();
+        let mut math = Vec::new();
+        parse_math(
+            src,
+            0,
+            MathOutput {
+                text: &mut text,
+                prop_spans: &mut prop_spans,
+                math: &mut math,
+            },
+        )
+        .unwrap();
+        (text, prop_spans, math)
+    }
+
+    #[test]
+    fn symbols_sorted() {
+        assert!(SYMBOLS.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn math_symbols() {
+        let (text, spans, math) = parse(r"\alpha + \sum 2-1 \leq \infty");
+        assert_eq!(text, "α + ∑ 2\u{2212}1 ≤ ∞");
+        assert!(spans.is_empty());
+        assert!(math.is_empty());
+    }
+
+    #[test]
+    fn math_italic_and_scripts() {
+        let (text, spans, _) = parse("x_i^{2n}");
+        assert_eq!(text, "xi2n");
+        assert_eq!(spans.len(), 4);
+        // contiguous letters are merged in a single italic span
+        assert_eq!((spans[0].0, spans[0].1), (0, 2));
+        assert_eq!(spans[0].2.font_style, Some(font::Style::Italic));
+        // subscript
+        assert_eq!((spans[1].0, spans[1].1), (1, 2));
+        assert_eq!(spans[1].2.baseline_shift, Some(SUB_SHIFT));
+        // superscript
+        assert_eq!((spans[3].0, spans[3].1), (2, 4));
+        assert_eq!(spans[3].2.baseline_shift, Some(SUP_SHIFT));
+        assert_eq!(spans[3].2.font_scale, Some(SCRIPT_SCALE));
+    }
+
+    #[test]
+    fn math_frac_sqrt() {
+        let (text, _, math) = parse(r"\frac{1}{\sqrt 2}");
+        assert_eq!(text, "1√2");
+        assert_eq!(
+            math,
+            vec![
+                MathElem::Sqrt {
+                    sign: (1, 4),
+                    radicand: (4, 5)
+                },
+                MathElem::Frac {
+                    num: (0, 1),
+                    den: (1, 5)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn math_errors() {
+        let mut text = String::new();
+        let mut prop_spans: Vec<(Pos, Pos, TextOptProps<ColorU8>)> = Vec::new();
+        let mut math = Vec::new();
+        for src in [r"\unknown", r"\frac{1}", "{x", "x}", "x^"] {
+            let res = parse_math(
+                src,
+                0,
+                MathOutput {
+                    text: &mut text,
+                    prop_spans: &mut prop_spans,
+                    math: &mut math,
+                },
+            );
+            assert!(res.is_err(), "{src} should not parse");
+        }
+    }
+}
@@ -3,7 +3,8 @@ use std::str::FromStr;
 
 use plotive_base::Color;
 
-use crate::rich::{TextOptProps, TextProps};
+use crate::rich::math::{self, MathOutput};
+use crate::rich::{MathElem, TextOptProps, TextProps};
 use crate::{RichTextBuilder, font};
 
 /// Position into an input stream
@@ -21,6 +22,8 @@ pub enum ParseRichTextError {
     UnexpectedEndOfStr(Pos),
     UnknownClass(Span, String),
     BadPropValue(Span, String, String),
+    UnterminatedMath(Span),
+    InvalidMath(Span, String),
 }
 
 impl ParseRichTextError {
@@ -32,6 +35,8 @@ impl ParseRichTextError {
             ParseRichTextError::UnexpectedEndOfStr(pos) => (*pos, *pos),
             ParseRichTextError::UnknownClass(span, _) => *span,
             ParseRichTextError::BadPropValue(span, _, _) => *span,
+            ParseRichTextError::UnterminatedMath(span) => *span,
+            ParseRichTextError::InvalidMath(span, _) => *span,
         }
     }
 }
@@ -57,6 +62,12 @@ impl fmt::Display for ParseRichTextError {
             ParseRichTextError::BadPropValue(_, prop, value) => {
                 write!(f, "bad value '{}' for property '{}'", value, prop)
             }
+            ParseRichTextError::UnterminatedMath(..) => {
+                write!(f, "unterminated math")
+            }
+            ParseRichTextError::InvalidMath(_, reason) => {
+                write!(f, "invalid math: {}", reason)
+            }
         }
     }
 }
@@ -67,6 +78,7 @@ impl std::error::Error for ParseRichTextError {}
 pub struct ParsedRichText<C> {
     pub text: String,
     pub prop_spans: Vec<(Pos, Pos, TextOptProps<C>)>,
+    pub math: Vec<MathElem>,
 }
 
 impl<C> ParsedRichText<C>
@@ -78,10 +90,24 @@ where
         for (start, end, props) in self.prop_spans {
             builder.add_span(start, end, props);
         }
+        for elem in self.math {
+            builder.add_math(elem);
+        }
         builder
     }
 }
 
+/// Parse rich text markup.
+///
+/// Properties are applied to text spans with tags such as `[bold]text[/bold]`
+/// or `[color=red;size=14]text[/color;size]`.
+/// `[underline]` and `[strikeout]` draw a line under or through the text, with the color of the text.
+///
+/// Text between `$` signs is set in math mode, which supports Greek letters and common symbols
+/// (e.g. `$\alpha \leq \infty$`), superscripts and subscripts (`$x_i^2$`),
+/// fractions (`$\frac{a}{b}$`) and square roots (`$\sqrt{2}$`).
+///
+/// `\[`, `\$` and `\\` escape the `[`, `$` and `\` characters.
 pub fn parse_rich_text<C>(fmt: &str) -> Result<ParsedRichText<C>, ParseRichTextError>
 where
     C: Color + FromStr,
@@ -128,6 +154,7 @@ where
 
         let mut text = String::new();
         let mut prop_spans = Vec::new();
+        let mut math = Vec::new();
         for token in tokens {
             let token = token?;
             match token.1 {
@@ -147,6 +174,15 @@ where
                         .collect::<Vec<_>>();
                     prop_stack.push((text.len(), token.0, close_tag, props));
                 }
+                lex::TokenKind::Math(src) => {
+                    let out = MathOutput {
+                        text: &mut text,
+                        prop_spans: &mut prop_spans,
+                        math: &mut math,
+                    };
+                    // skip the opening '$'
+                    math::parse_math(&src, token.0.0 + 1, out)?;
+                }
                 lex::TokenKind::CloseTag(closing_tag) => {
                     for idx in (0..prop_stack.len()).rev() {
                         if closing_tag.0.iter().all(|c| prop_stack[idx].2.contains(c)) {
@@ -165,7 +201,11 @@ where
             return Err(ParseRichTextError::UnmatchedTag(prop_stack[0].1));
         }
 
-        Ok(ParsedRichText { text, prop_spans })
+        Ok(ParsedRichText {
+            text,
+            prop_spans,
+            math,
+        })
     }
 
     fn merge_props(base: TextOptProps<C>, overlay: &TextOptProps<C>) -> TextOptProps<C> {
@@ -179,6 +219,8 @@ where
             stroke: overlay.stroke.or(base.stroke),
             underline: overlay.underline.or(base.underline),
             strikeout: overlay.strikeout.or(base.strikeout),
+            font_scale: overlay.font_scale.or(base.font_scale),
+            baseline_shift: overlay.baseline_shift.or(base.baseline_shift),
         }
     }
 
@@ -425,6 +467,8 @@ mod lex {
         OpenTag(OpeningTag),
         CloseTag(ClosingTag),
         StrLit(String),
+        /// Math source, between `$` delimiters
+        Math(String),
     }
 
     #[derive(Debug, Clone, PartialEq)]
@@ -485,6 +529,11 @@ mod lex {
                     let tag = self.parse_tag(start_pos)?;
                     Ok(Some(tag))
                 }
+                '$' => {
+                    self.cursor.next();
+                    let math = self.parse_math(start_pos)?;
+                    Ok(Some(TokenKind::Math(math)))
+                }
                 _ => {
                     let lit = self.parse_str_lit(start_pos)?;
                     Ok(Some(TokenKind::StrLit(lit)))
@@ -498,6 +547,7 @@ mod lex {
             };
             match c {
                 '[' => Ok('['),
+                '$' => Ok('$'),
                 '\\' => Ok('\\'),
                 _ => Err(ParseRichTextError::InvalidEscSequence(
                     (start_pos, self.cursor.pos()),
@@ -512,7 +562,7 @@ mod lex {
                 let pos = self.cursor.pos();
                 match self.cursor.first() {
                     None => break,
-                    Some('[') => break,
+                    Some('[') | Some('$') => break,
                     Some('\\') => {
                         self.cursor.next();
                         buf.push(self.parse_esc_sequence(pos)?);
@@ -526,6 +576,31 @@ mod lex {
             Ok(buf)
         }
 
+        /// Read the math source up to the closing `$`.
+        /// Escape sequences are kept, to be processed by the math parser.
+        fn parse_math(&mut self, start_pos: Pos) -> Result<String, ParseRichTextError> {
+            let mut buf = String::new();
+            loop {
+                match self.cursor.next() {
+                    None => {
+                        return Err(ParseRichTextError::UnterminatedMath((
+                            start_pos,
+                            self.cursor.pos(),
+                        )));
+                    }
+                    Some('$') => break,
+                    Some('\\') => {
+                        buf.push('\\');
+                        if let Some(c) = self.cursor.next() {
+                            buf.push(c);
+                        }
+                    }
+                    Some(c) => buf.push(c),
+                }
+            }
+            Ok(buf)
+        }
+
         fn parse_tag(&mut self, start_pos: Pos) -> Result<TokenKind, ParseRichTextError> {
             let Some(c) = self.cursor.first() else {
                 return Err(ParseRichTextError::UnexpectedEndOfStr(start_pos));
@@ -749,4 +824,41 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn lex_math() {
+        let input = r#"Cost: \$5, $\frac{a}{b} \$$"#;
+        let tokens: Vec<_> = lex::tokenize(input.chars())
+            .map(|res| res.unwrap().1)
+            .collect();
+        assert_eq!(
+            &tokens,
+            &[
+                lex::TokenKind::StrLit("Cost: $5, ".to_string()),
+                lex::TokenKind::Math(r#"\frac{a}{b} \$"#.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_unterminated_math() {
+        let input = "Some $x^2";
+        let res: Result<Vec<_>, _> = lex::tokenize(input.chars()).collect();
+        assert!(matches!(res, Err(ParseRichTextError::UnterminatedMath(_))));
+    }
+
+    #[test]
+    fn parse_math() {
+        use plotive_base::color::ColorU8;
+
+        let parsed = parse_rich_text::<ColorU8>("Ratio $\\frac{1}{x}$").unwrap();
+        assert_eq!(parsed.text, "Ratio 1x");
+        assert_eq!(
+            &parsed.math,
+            &[MathElem::Frac {
+                num: (6, 7),
+                den: (7, 8),
+            }]
+        );
+    }
 }
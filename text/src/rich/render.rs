@@ -1,7 +1,8 @@
 use plotive_base::{ColorU8, geom};
 use ttf_parser as ttf;
 
-use super::RichText;
+use super::{Layout, RichText};
+use crate::cache::GlyphKey;
 use crate::{font, fontdb};
 
 #[derive(Debug)]
@@ -22,17 +23,20 @@ where
     C: Clone,
     RenderFn: FnMut(RichPrimitive<'_, C>),
 {
+    let cache = fontdb.glyph_cache();
     let mut span_builder = geom::PathBuilder::new();
     let mut glyph_builder = geom::PathBuilder::new();
 
+    // vertical text has no baseline: underline is not supported,
+    // and strikeout is drawn through the middle of the column
+    let vertical = matches!(text.layout, Layout::Vertical(..));
+
     for line in &text.lines {
         for shape in &line.shapes {
             (glyph_builder, span_builder) = fontdb
                 .with_face_data(shape.face_id, |data, index| {
-                    let mut face = ttf::Face::parse(data, index).unwrap();
-                    font::apply_ttf_variations(&mut face, shape.font());
-
-                    // TODO: get span bbox and render underline and strikeout lines
+                    // the face is only parsed if a glyph is missing from the cache
+                    let mut face = None;
 
                     for span in &shape.spans {
                         for glyph in shape
@@ -40,22 +44,18 @@ where
                             .iter()
                             .filter(|g| g.cluster >= span.start && g.cluster < span.end)
                         {
-                            {
-                                let mut builder = crate::Outliner(&mut glyph_builder);
-                                face.outline_glyph(glyph.id, &mut builder);
-                            }
-
-                            if let Some(path) = glyph_builder.finish() {
-                                let path = path.transform(glyph.ts).unwrap();
-                                span_builder.push_path(&path);
-
-                                glyph_builder = path.clear();
-                            } else {
-                                glyph_builder = geom::PathBuilder::new();
-                            }
+                            let key = GlyphKey::new(shape.face_id, glyph.id, shape.font());
+                            cache.push_glyph(key, glyph.ts, &mut span_builder, |gl_pb| {
+                                let face = face.get_or_insert_with(|| {
+                                    let mut face = ttf::Face::parse(data, index).unwrap();
+                                    font::apply_ttf_variations(&mut face, shape.font());
+                                    face
+                                });
+                                face.outline_glyph(glyph.id, &mut crate::Outliner(gl_pb));
+                            });
                         }
 
-                        if span.props.underline {
+                        if span.props.underline && !vertical {
                             let line = shape.metrics.uline;
                             let path =
                                 line_path(span.bbox(), shape.y_baseline, line, glyph_builder);
@@ -64,11 +64,17 @@ where
                         }
                         if span.props.strikeout {
                             let line = shape.metrics.strikeout;
-                            let path =
-                                line_path(span.bbox(), shape.y_baseline, line, glyph_builder);
+                            let path = if vertical {
+                                ver_line_path(span.bbox(), line, glyph_builder)
+                            } else {
+                                line_path(span.bbox(), shape.y_baseline, line, glyph_builder)
+                            };
                             span_builder.push_path(&path);
                             glyph_builder = path.clear();
                         }
+                        for rule in span.rules() {
+                            span_builder.push_path(&rule.to_path());
+                        }
 
                         if let Some(path) = span_builder.finish() {
                             if let Some(c) = span.props.fill.as_ref() {
@@ -132,3 +138,68 @@ fn line_path(
     builder.close();
     builder.finish().unwrap()
 }
+
+fn ver_line_path(
+    rect: geom::Rect,
+    line: font::ScaledLineMetrics,
+    mut builder: geom::PathBuilder,
+) -> geom::Path {
+    let x = rect.center().x - line.thickness / 2.0;
+    builder.move_to(x, rect.top());
+    builder.line_to(x, rect.bottom());
+    builder.line_to(x + line.thickness, rect.bottom());
+    builder.line_to(x + line.thickness, rect.top());
+    builder.close();
+    builder.finish().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundled_font_db;
+    use crate::rich::{RichTextBuilder, TextOptProps, TextProps};
+
+    fn render_decorated(layout: Layout) -> Vec<tiny_skia::Rect> {
+        let db = bundled_font_db();
+        let mut builder =
+            RichTextBuilder::new("Some text".to_string(), TextProps::new(12.0)).with_layout(layout);
+        builder.add_span(
+            5,
+            9,
+            TextOptProps {
+                underline: Some(true),
+                strikeout: Some(true),
+                ..Default::default()
+            },
+        );
+        let text: RichText = builder.done(&db).unwrap();
+        let mut bounds = Vec::new();
+        render_rich_text_with(&text, &db, |prim| {
+            if let RichPrimitive::Fill(path, _) = prim {
+                bounds.push(path.bounds());
+            }
+        })
+        .unwrap();
+        bounds
+    }
+
+    #[test]
+    fn decorations_follow_layout() {
+        let hor = render_decorated(Layout::default());
+        let ver = render_decorated(Layout::Vertical(
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+        ));
+        // one path for "Some " and one for the decorated "text"
+        assert_eq!(hor.len(), 2);
+        assert_eq!(ver.len(), 2);
+
+        // the underline is below the baseline, hence below the undecorated span
+        assert!(hor[1].bottom() > hor[0].bottom());
+        // the vertical strikeout stays within the column
+        assert!(ver[1].left() >= ver[0].left() - 1.0 && ver[1].right() <= ver[0].right() + 1.0);
+    }
+}